@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Parser and renderer benchmarks on synthetic stacks
+//!
+//! Run with `cargo bench`; stacks come from `generate_random_stack` so results do not
+//! depend on confidential foundry files.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use egui::{Context, Pos2, Rect, Vec2};
+use itf_viewer::parser::{parse_2d_number_matrix, parse_itf_file};
+use itf_viewer::renderer::{fitted_transform, render_frame_headless, StackRenderer};
+use itf_viewer::utils::{generate_random_stack, write_itf};
+use std::hint::black_box;
+
+const LAYER_COUNTS: [usize; 3] = [50, 200, 1000];
+const VIEWPORT: Vec2 = Vec2::new(1280.0, 800.0);
+/// Rows and columns of the square lookup tables parsed by `bench_table`
+const TABLE_SIZES: [usize; 3] = [16, 128, 512];
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for layer_count in LAYER_COUNTS {
+        let itf = write_itf(&generate_random_stack(1, layer_count));
+        group.throughput(Throughput::Bytes(itf.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(layer_count), &itf, |b, itf| {
+            b.iter(|| parse_itf_file(black_box(itf)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// VALUES body of a dense RHO_VS_WIDTH_AND_SPACING table
+fn table_body(size: usize) -> String {
+    let rows: Vec<String> = (0..size)
+        .map(|row| {
+            let values: Vec<String> = (0..size)
+                .map(|column| format!("{:.6e}", 1.0e-2 + (row * size + column) as f64 * 1.0e-7))
+                .collect();
+            values.join(" ")
+        })
+        .collect();
+    format!("{{\n{}\n}}", rows.join("\n"))
+}
+
+/// Compare with `cargo bench --features fast-tables -- table`
+fn bench_table(c: &mut Criterion) {
+    let mut group = c.benchmark_group("table");
+    for size in TABLE_SIZES {
+        let body = table_body(size);
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &body, |b, body| {
+            b.iter(|| parse_2d_number_matrix(black_box(body)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_geometry(c: &mut Criterion) {
+    let renderer = StackRenderer::new();
+    let viewport_rect = Rect::from_min_size(Pos2::ZERO, VIEWPORT);
+
+    let mut group = c.benchmark_group("geometry");
+    for layer_count in LAYER_COUNTS {
+        let stack = generate_random_stack(1, layer_count);
+        let transform = fitted_transform(&renderer, &stack, VIEWPORT);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(layer_count),
+            &stack,
+            |b, stack| {
+                b.iter(|| {
+                    let scaler = renderer.get_current_scaler(stack);
+                    let layers = renderer.create_layer_geometries_ordered(
+                        black_box(stack),
+                        &scaler,
+                        &transform,
+                        viewport_rect,
+                    );
+                    let vias = renderer.create_via_geometries_with_scaler(
+                        black_box(stack),
+                        &scaler,
+                        &transform,
+                        viewport_rect,
+                    );
+                    (layers, vias)
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_frame(c: &mut Criterion) {
+    let ctx = Context::default();
+    let renderer = StackRenderer::new();
+
+    let mut group = c.benchmark_group("frame");
+    for layer_count in LAYER_COUNTS {
+        let stack = generate_random_stack(1, layer_count);
+        let transform = fitted_transform(&renderer, &stack, VIEWPORT);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(layer_count),
+            &stack,
+            |b, stack| {
+                b.iter(|| render_frame_headless(&ctx, &renderer, black_box(stack), &transform))
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_parse,
+    bench_table,
+    bench_geometry,
+    bench_frame
+);
+criterion_main!(benches);
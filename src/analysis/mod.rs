@@ -0,0 +1,8 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+pub mod resistance;
+pub mod via_count;
+
+pub use resistance::*;
+pub use via_count::*;
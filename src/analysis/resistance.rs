@@ -0,0 +1,409 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{Celsius, ConductorLayer, Micrometers, OhmsPerSquare, TableRangeWarning};
+use thiserror::Error;
+
+/// Table or value the resistivity of a conductor is taken from, in priority order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResistivitySource {
+    /// Volume resistivity in ohm·um by silicon width and thickness
+    RhoVsSiWidthAndThickness,
+    /// Sheet resistance in ohm/sq by width, looked up at zero spacing
+    RhoVsWidthSpacing,
+    /// Fixed sheet resistance in ohm/sq
+    Rpsq,
+}
+
+impl ResistivitySource {
+    /// Techfile keyword of the source
+    pub fn keyword(self) -> &'static str {
+        match self {
+            ResistivitySource::RhoVsSiWidthAndThickness => "RHO_VS_SI_WIDTH_AND_THICKNESS",
+            ResistivitySource::RhoVsWidthSpacing => "RHO_VS_WIDTH_SPACING",
+            ResistivitySource::Rpsq => "RPSQ",
+        }
+    }
+
+    /// Source `conductor` would use, if it has any resistivity data
+    pub fn of(conductor: &ConductorLayer) -> Option<Self> {
+        if conductor.rho_vs_si_width_thickness.is_some() {
+            Some(ResistivitySource::RhoVsSiWidthAndThickness)
+        } else if conductor.rho_vs_width_spacing.is_some() {
+            Some(ResistivitySource::RhoVsWidthSpacing)
+        } else if conductor.electrical_props.rpsq.is_some() {
+            Some(ResistivitySource::Rpsq)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the temperature coefficients came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtSource {
+    /// Interpolated from CRT_VS_SI_WIDTH
+    CrtVsSiWidth,
+    /// CRT1 and CRT2, 0 when unset
+    Fixed,
+}
+
+/// Straight wire segment on one conductor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WireGeometry {
+    /// Silicon width in um
+    pub width: f64,
+    /// Length in um
+    pub length: f64,
+    /// Thickness in um instead of the layer's THICKNESS, for what-if sweeps
+    pub thickness: Option<f64>,
+}
+
+impl WireGeometry {
+    pub fn new(width: f64, length: f64) -> Self {
+        Self {
+            width,
+            length,
+            thickness: None,
+        }
+    }
+
+    pub fn with_thickness(mut self, thickness: f64) -> Self {
+        self.thickness = Some(thickness);
+        self
+    }
+}
+
+/// Temperatures in °C the resistance is evaluated at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperatingConditions {
+    pub temperature: f64,
+    /// Temperature the resistivity data was characterized at
+    pub reference_temperature: f64,
+}
+
+impl OperatingConditions {
+    pub fn new(temperature: f64, reference_temperature: f64) -> Self {
+        Self {
+            temperature,
+            reference_temperature,
+        }
+    }
+
+    /// Evaluate at the reference temperature, where the CRT terms vanish
+    pub fn at_reference(reference_temperature: f64) -> Self {
+        Self::new(reference_temperature, reference_temperature)
+    }
+}
+
+/// Resistance of a wire with the intermediate values it was derived from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResistanceResult {
+    /// Resistance in ohm
+    pub resistance: f64,
+    /// Resistance per square of the wire at the operating temperature, in ohm/sq
+    pub sheet_resistance: f64,
+    pub resistivity_source: ResistivitySource,
+    /// Resistivity at the reference temperature: ohm·um for RHO_VS_SI_WIDTH_AND_THICKNESS,
+    /// ohm/sq otherwise
+    pub base_resistivity: f64,
+    pub crt1: f64,
+    pub crt2: f64,
+    pub crt_source: CrtSource,
+    /// ρ(T) / ρ₀ = 1 + CRT1 ΔT + CRT2 ΔT²
+    pub temperature_factor: f64,
+    /// Table lookups clamped to the edge of their axes
+    pub range_warnings: Vec<TableRangeWarning>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ResistanceError {
+    #[error("{layer} has no RPSQ or resistivity table")]
+    NoResistivityData { layer: String },
+
+    #[error("{table} lookup of {layer} failed")]
+    LookupFailed { layer: String, table: &'static str },
+
+    #[error(
+        "Width and thickness must be positive, got width {width} um and thickness {thickness} um"
+    )]
+    InvalidGeometry { width: f64, thickness: f64 },
+
+    #[error("Length must not be negative, got {length} um")]
+    NegativeLength { length: f64 },
+}
+
+/// Resistance of `wire` on `conductor` at `conditions`
+///
+/// The resistivity comes from RHO_VS_SI_WIDTH_AND_THICKNESS, RHO_VS_WIDTH_SPACING or RPSQ,
+/// the first one defined, and is scaled by ρ(T) = ρ₀ (1 + CRT1 ΔT + CRT2 ΔT²) with the
+/// coefficients of CRT_VS_SI_WIDTH at the wire width, else CRT1 and CRT2. Volume
+/// resistivities give R = ρ L / (W T), sheet resistances R = Rsq L / W.
+pub fn calculate_resistance(
+    conductor: &ConductorLayer,
+    wire: &WireGeometry,
+    conditions: &OperatingConditions,
+) -> Result<ResistanceResult, ResistanceError> {
+    let thickness = wire.thickness.unwrap_or(conductor.thickness);
+    let source =
+        ResistivitySource::of(conductor).ok_or_else(|| ResistanceError::NoResistivityData {
+            layer: conductor.name.clone(),
+        })?;
+    let lookup_failed = || ResistanceError::LookupFailed {
+        layer: conductor.name.clone(),
+        table: source.keyword(),
+    };
+    // Volume resistivities also divide by the thickness
+    let thin = source == ResistivitySource::RhoVsSiWidthAndThickness && thickness <= 0.0;
+    if wire.width <= 0.0 || thin {
+        return Err(ResistanceError::InvalidGeometry {
+            width: wire.width,
+            thickness,
+        });
+    }
+    if wire.length < 0.0 {
+        return Err(ResistanceError::NegativeLength {
+            length: wire.length,
+        });
+    }
+    let base_resistivity = match source {
+        ResistivitySource::RhoVsSiWidthAndThickness => conductor
+            .rho_vs_si_width_thickness
+            .as_ref()
+            .and_then(|table| table.lookup(wire.width, thickness))
+            .ok_or_else(lookup_failed)?,
+        ResistivitySource::RhoVsWidthSpacing => conductor
+            .rho_vs_width_spacing
+            .as_ref()
+            .and_then(|table| table.lookup(wire.width, 0.0))
+            .ok_or_else(lookup_failed)?,
+        ResistivitySource::Rpsq => conductor.electrical_props.rpsq.unwrap_or_default(),
+    };
+
+    let (crt1, crt2, crt_source) = crt_coefficients(conductor, wire.width);
+    let delta =
+        Celsius(conditions.temperature).difference(Celsius(conditions.reference_temperature));
+    let temperature_factor = 1.0 + crt1 * delta + crt2 * delta.powi(2);
+    let resistivity = base_resistivity * temperature_factor;
+
+    let sheet_resistance = match source {
+        ResistivitySource::RhoVsSiWidthAndThickness => {
+            OhmsPerSquare::from_resistivity(resistivity, Micrometers(thickness))
+        }
+        ResistivitySource::RhoVsWidthSpacing | ResistivitySource::Rpsq => {
+            OhmsPerSquare(resistivity)
+        }
+    };
+    let resistance = sheet_resistance
+        .resistance(Micrometers(wire.length), Micrometers(wire.width))
+        .ohms();
+    log::debug!(
+        "{}: R = {resistance:.6e} ohm for W = {} um, L = {} um, T = {thickness} um at {} °C \
+         ({} = {base_resistivity:.6e}, factor {temperature_factor:.6})",
+        conductor.name,
+        wire.width,
+        wire.length,
+        conditions.temperature,
+        source.keyword()
+    );
+
+    Ok(ResistanceResult {
+        resistance,
+        sheet_resistance: sheet_resistance.ohms_per_square(),
+        resistivity_source: source,
+        base_resistivity,
+        crt1,
+        crt2,
+        crt_source,
+        temperature_factor,
+        range_warnings: table_range_warnings(conductor, wire.width, thickness),
+    })
+}
+
+/// CRT1 and CRT2 of `conductor` at `width`, from CRT_VS_SI_WIDTH when it covers the lookup
+pub fn crt_coefficients(conductor: &ConductorLayer, width: f64) -> (f64, f64, CrtSource) {
+    conductor
+        .crt_vs_si_width
+        .as_ref()
+        .and_then(|table| table.lookup_crt_values(width))
+        .map(|(crt1, crt2)| (crt1, crt2, CrtSource::CrtVsSiWidth))
+        .unwrap_or_else(|| {
+            let props = &conductor.electrical_props;
+            (
+                props.crt1.unwrap_or(0.0),
+                props.crt2.unwrap_or(0.0),
+                CrtSource::Fixed,
+            )
+        })
+}
+
+/// Table lookups [`calculate_resistance`] would clamp for a wire of `width` and `thickness`
+pub fn table_range_warnings(
+    conductor: &ConductorLayer,
+    width: f64,
+    thickness: f64,
+) -> Vec<TableRangeWarning> {
+    let mut warnings = Vec::new();
+
+    if let Some(table) = &conductor.rho_vs_si_width_thickness {
+        let name = ResistivitySource::RhoVsSiWidthAndThickness.keyword();
+        warnings.extend(TableRangeWarning::check(
+            name,
+            "Width",
+            table.width_range(),
+            width,
+        ));
+        warnings.extend(TableRangeWarning::check(
+            name,
+            "Thickness",
+            table.spacing_range(),
+            thickness,
+        ));
+    } else if let Some(table) = &conductor.rho_vs_width_spacing {
+        warnings.extend(TableRangeWarning::check(
+            ResistivitySource::RhoVsWidthSpacing.keyword(),
+            "Width",
+            table.width_range(),
+            width,
+        ));
+    }
+
+    if let Some(table) = &conductor.crt_vs_si_width {
+        warnings.extend(TableRangeWarning::check(
+            "CRT_VS_SI_WIDTH",
+            "Width",
+            table.width_range(),
+            width,
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{CrtVsSiWidthTable, LookupTable2D};
+
+    fn conductor_with_rpsq(rpsq: f64) -> ConductorLayer {
+        let mut conductor = ConductorLayer::new("metal1".to_string(), 0.2);
+        conductor.electrical_props.rpsq = Some(rpsq);
+        conductor
+    }
+
+    #[test]
+    fn test_sheet_resistance() {
+        let mut conductor = conductor_with_rpsq(0.1);
+        conductor.electrical_props.crt1 = Some(4.0e-3);
+
+        let wire = WireGeometry::new(0.5, 10.0);
+        let result =
+            calculate_resistance(&conductor, &wire, &OperatingConditions::at_reference(25.0))
+                .unwrap();
+        assert_eq!(result.resistivity_source, ResistivitySource::Rpsq);
+        assert_eq!(result.crt_source, CrtSource::Fixed);
+        assert!((result.resistance - 2.0).abs() < 1e-12);
+        assert!((result.sheet_resistance - 0.1).abs() < 1e-12);
+
+        // 1 + 4e-3 × 100
+        let hot = OperatingConditions::new(125.0, 25.0);
+        let result = calculate_resistance(&conductor, &wire, &hot).unwrap();
+        assert!((result.temperature_factor - 1.4).abs() < 1e-12);
+        assert!((result.resistance - 2.8).abs() < 1e-12);
+        assert!(result.range_warnings.is_empty());
+    }
+
+    #[test]
+    fn test_volume_resistivity() {
+        let mut conductor = conductor_with_rpsq(0.1);
+        conductor.rho_vs_si_width_thickness = Some(LookupTable2D::new(
+            vec![0.1, 1.0],
+            vec![0.1, 0.3],
+            vec![vec![0.02, 0.02], vec![0.02, 0.02]],
+        ));
+        conductor.crt_vs_si_width = Some(CrtVsSiWidthTable::new(
+            vec![0.1, 1.0],
+            vec![3.0e-3, 3.0e-3],
+            vec![0.0, 0.0],
+        ));
+
+        let wire = WireGeometry::new(0.5, 10.0);
+        let result =
+            calculate_resistance(&conductor, &wire, &OperatingConditions::at_reference(25.0))
+                .unwrap();
+        assert_eq!(
+            result.resistivity_source,
+            ResistivitySource::RhoVsSiWidthAndThickness
+        );
+        assert_eq!(result.crt_source, CrtSource::CrtVsSiWidth);
+        // 0.02 × 10 / (0.5 × 0.2)
+        assert!((result.resistance - 2.0).abs() < 1e-9);
+
+        // A thinner wire than the layer, outside the table's thickness axis
+        let thin = wire.with_thickness(0.05);
+        let result =
+            calculate_resistance(&conductor, &thin, &OperatingConditions::at_reference(25.0))
+                .unwrap();
+        assert!((result.resistance - 8.0).abs() < 1e-9);
+        assert_eq!(result.range_warnings.len(), 1);
+        assert_eq!(result.range_warnings[0].axis, "Thickness");
+    }
+
+    #[test]
+    fn test_resistance_errors() {
+        let conductor = ConductorLayer::new("metal1".to_string(), 0.2);
+        let wire = WireGeometry::new(0.5, 10.0);
+        let conditions = OperatingConditions::at_reference(25.0);
+        assert_eq!(
+            calculate_resistance(&conductor, &wire, &conditions),
+            Err(ResistanceError::NoResistivityData {
+                layer: "metal1".to_string()
+            })
+        );
+
+        let mut conductor = conductor_with_rpsq(0.1);
+        conductor.rho_vs_si_width_thickness = Some(LookupTable2D::new(
+            vec![0.1, 1.0],
+            vec![0.1, 0.3],
+            vec![vec![0.02, 0.02], vec![0.02, 0.02]],
+        ));
+        assert!(matches!(
+            calculate_resistance(&conductor, &wire.with_thickness(0.0), &conditions),
+            Err(ResistanceError::InvalidGeometry { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rpsq_geometry_errors() {
+        let conductor = conductor_with_rpsq(0.1);
+        let conditions = OperatingConditions::at_reference(25.0);
+        for width in [0.0, -0.5] {
+            assert_eq!(
+                calculate_resistance(&conductor, &WireGeometry::new(width, 10.0), &conditions),
+                Err(ResistanceError::InvalidGeometry {
+                    width,
+                    thickness: conductor.thickness
+                })
+            );
+        }
+        assert_eq!(
+            calculate_resistance(&conductor, &WireGeometry::new(0.5, -1.0), &conditions),
+            Err(ResistanceError::NegativeLength { length: -1.0 })
+        );
+
+        // A sheet resistance does not depend on the thickness
+        let result = calculate_resistance(
+            &conductor,
+            &WireGeometry::new(0.5, 10.0).with_thickness(0.0),
+            &conditions,
+        )
+        .unwrap();
+        assert!((result.resistance - 2.0).abs() < 1e-9);
+        assert_eq!(
+            calculate_resistance(&conductor, &WireGeometry::new(0.5, 0.0), &conditions)
+                .unwrap()
+                .resistance,
+            0.0
+        );
+    }
+}
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ViaConnection;
+
+/// Vias needed in parallel to reach a target resistance, from AREA and RPV
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViaCountEstimate {
+    /// RPV × AREA in ohm·um², the resistance one um² of cut would have
+    pub specific_resistance: f64,
+    pub via_count: u32,
+    /// Resistance of `via_count` vias in parallel, in ohm
+    pub resistance: f64,
+    /// Cut area of all vias in um²
+    pub cut_area: f64,
+}
+
+impl ViaCountEstimate {
+    /// Resistance per via of a cut of `area` um², assuming it scales with the inverse area
+    pub fn rpv_for_area(&self, area: f64) -> f64 {
+        self.specific_resistance / area
+    }
+}
+
+/// Fewest vias of `via` in parallel with at most `target_resistance` ohm
+///
+/// `None` unless AREA, RPV and the target are positive.
+pub fn estimate_via_count(via: &ViaConnection, target_resistance: f64) -> Option<ViaCountEstimate> {
+    let rpv = via.resistance_per_via;
+    if via.area <= 0.0 || rpv <= 0.0 || target_resistance <= 0.0 {
+        return None;
+    }
+    // Allow for rounding, so a target of exactly RPV / n asks for n vias
+    let needed = (rpv / target_resistance * (1.0 - 1e-12)).ceil().max(1.0);
+    let via_count = needed.min(f64::from(u32::MAX)) as u32;
+    Some(ViaCountEstimate {
+        specific_resistance: rpv * via.area,
+        via_count,
+        resistance: via.calculate_resistance(via_count),
+        cut_area: f64::from(via_count) * via.area,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_via_count() {
+        let via = ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            5.0,
+        );
+        let estimate = estimate_via_count(&via, 1.0).unwrap();
+        assert_eq!(estimate.via_count, 5);
+        assert!((estimate.resistance - 1.0).abs() < 1e-12);
+        assert!((estimate.cut_area - 0.2).abs() < 1e-12);
+        assert!((estimate.specific_resistance - 0.2).abs() < 1e-12);
+        assert!((estimate.rpv_for_area(0.08) - 2.5).abs() < 1e-12);
+
+        assert_eq!(estimate_via_count(&via, 0.9).unwrap().via_count, 6);
+        assert_eq!(estimate_via_count(&via, 10.0).unwrap().via_count, 1);
+        assert!(estimate_via_count(&via, 0.0).is_none());
+
+        let no_rpv = ViaConnection::new(
+            "via2".to_string(),
+            "a".to_string(),
+            "b".to_string(),
+            0.04,
+            0.0,
+        );
+        assert!(estimate_via_count(&no_rpv, 1.0).is_none());
+    }
+}
@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    layer::{DielectricLayer, Layer},
+    stack::ProcessStack,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Name of the dielectric filling the gap between two bonded stacks
+pub const BOND_GAP_LAYER: &str = "bond_gap";
+
+/// How two stacks are bonded face-to-face
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BondOptions {
+    /// Thickness of the bonding dielectric between the two faces in um, none when zero
+    pub gap: f64,
+    pub gap_dielectric_constant: f64,
+    /// Prepended to the layer and via names of the flipped stack
+    pub top_prefix: String,
+}
+
+impl Default for BondOptions {
+    fn default() -> Self {
+        Self {
+            gap: 0.0,
+            gap_dielectric_constant: 3.9,
+            top_prefix: "top_".to_string(),
+        }
+    }
+}
+
+impl BondOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_gap(mut self, gap: f64) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn with_gap_dielectric_constant(mut self, dielectric_constant: f64) -> Self {
+        self.gap_dielectric_constant = dielectric_constant;
+        self
+    }
+
+    pub fn with_top_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.top_prefix = prefix.into();
+        self
+    }
+}
+
+/// Heights of a bonded stack in um
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BondStats {
+    /// Height of the stack left upright, which is also the bottom of the bond gap
+    pub bottom_height: f64,
+    pub gap: f64,
+    /// Height of the flipped stack
+    pub top_height: f64,
+}
+
+impl BondStats {
+    /// Top of the bond gap, where the face of the flipped stack sits
+    pub fn bond_top_z(&self) -> f64 {
+        self.bottom_height + self.gap
+    }
+
+    pub fn total_height(&self) -> f64 {
+        self.bottom_height + self.gap + self.top_height
+    }
+}
+
+impl fmt::Display for BondStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bottom {:.4} um + gap {:.4} um + top {:.4} um = {:.4} um",
+            self.bottom_height,
+            self.gap,
+            self.top_height,
+            self.total_height()
+        )
+    }
+}
+
+/// Outcome of [`ProcessStack::bond_face_to_face`]
+#[derive(Debug, Clone)]
+pub struct BondedStack {
+    pub stack: ProcessStack,
+    pub stats: BondStats,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum BondError {
+    #[error("Bond gap must not be negative, got {0}")]
+    NegativeGap(f64),
+
+    #[error("Name '{0}' is used by both stacks, set a different prefix for the top stack")]
+    DuplicateName(String),
+}
+
+impl ProcessStack {
+    /// Bond `top` onto this stack face-to-face, as in a hybrid-bonded chiplet
+    ///
+    /// `top` is flipped upside down so the top layers of both stacks face each other across
+    /// the bond gap. The layers and vias of `top` are renamed with the prefix of `options`,
+    /// and a dielectric embedding a conductor is split into an `_upper` part as thick as
+    /// the conductor, which holds it, and the rest. The substrate and field regions of both
+    /// stacks are kept, those of `top` renamed with the prefix too.
+    pub fn bond_face_to_face(
+        &self,
+        top: &ProcessStack,
+        options: &BondOptions,
+    ) -> Result<BondedStack, BondError> {
+        if options.gap < 0.0 {
+            return Err(BondError::NegativeGap(options.gap));
+        }
+        let prefixed = |name: &str| format!("{}{name}", options.top_prefix);
+        let flipped: Vec<Layer> = flip_layers(&top.layers)
+            .into_iter()
+            .map(|mut layer| {
+                layer.set_name(prefixed(layer.name()));
+                if let Layer::Dielectric(dielectric) = &mut layer {
+                    if let Some(measured_from) = &mut dielectric.measured_from {
+                        *measured_from = prefixed(measured_from);
+                    }
+                }
+                layer
+            })
+            .collect();
+
+        let mut names = HashSet::new();
+        let top_names = flipped.iter().map(|layer| layer.name().to_string());
+        let gap_name = (options.gap > 0.0).then(|| BOND_GAP_LAYER.to_string());
+        let bottom_names = self.layers.iter().map(|layer| layer.name().to_string());
+        for name in top_names.chain(gap_name).chain(bottom_names) {
+            if !names.insert(name.clone()) {
+                return Err(BondError::DuplicateName(name));
+            }
+        }
+        let top_vias = top.via_stack.iter().map(|via| prefixed(&via.name));
+        let bottom_vias = self.via_stack.iter().map(|via| via.name.clone());
+        let mut via_names = HashSet::new();
+        if let Some(name) = top_vias
+            .chain(bottom_vias)
+            .find(|name| !via_names.insert(name.clone()))
+        {
+            return Err(BondError::DuplicateName(name));
+        }
+
+        let mut technology_info = self.technology_info.clone();
+        technology_info.name =
+            format!("{}+{}", self.technology_info.name, top.technology_info.name);
+        let mut stack = ProcessStack::new(technology_info);
+
+        for layer in flipped {
+            stack.add_layer(layer);
+        }
+        if options.gap > 0.0 {
+            stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+                BOND_GAP_LAYER.to_string(),
+                options.gap,
+                options.gap_dielectric_constant,
+            )));
+        }
+        for layer in &self.layers {
+            stack.add_layer(layer.clone());
+        }
+
+        for via in top.via_stack.iter() {
+            let mut via = via.clone();
+            via.name = prefixed(&via.name);
+            via.from_layer = prefixed(&via.from_layer);
+            via.to_layer = prefixed(&via.to_layer);
+            stack.add_via(via);
+        }
+        for via in self.via_stack.iter() {
+            stack.add_via(via.clone());
+        }
+
+        for region in &self.er_regions {
+            stack.add_er_region(region.clone());
+        }
+        for region in &top.er_regions {
+            let mut region = region.clone();
+            region.name = prefixed(&region.name);
+            stack.add_er_region(region);
+        }
+
+        let stats = BondStats {
+            bottom_height: self.get_stack_top_z(),
+            gap: options.gap,
+            top_height: top.get_stack_top_z(),
+        };
+        Ok(BondedStack { stack, stats })
+    }
+}
+
+/// `layers` upside down, still listed from top to bottom
+///
+/// ITF places a conductor at the bottom of the dielectric listed before it. Flipped, the
+/// conductor belongs at the top of that dielectric, so the dielectric is split and the
+/// conductor placed at the bottom of the upper part.
+fn flip_layers(layers: &[Layer]) -> Vec<Layer> {
+    let mut flipped = Vec::with_capacity(layers.len());
+    let mut index = layers.len();
+    while index > 0 {
+        index -= 1;
+        let layer = &layers[index];
+        let host = index.checked_sub(1).map(|above| &layers[above]);
+        match (layer, host) {
+            (Layer::Conductor(conductor), Some(Layer::Dielectric(dielectric)))
+                if conductor.thickness < dielectric.thickness =>
+            {
+                let mut upper = dielectric.clone();
+                upper.name = format!("{}_upper", dielectric.name);
+                upper.thickness = conductor.thickness;
+                let mut lower = dielectric.clone();
+                lower.thickness -= conductor.thickness;
+                flipped.extend([
+                    Layer::Dielectric(upper),
+                    layer.clone(),
+                    Layer::Dielectric(lower),
+                ]);
+                // The host dielectric is placed
+                index -= 1;
+            }
+            _ => flipped.push(layer.clone()),
+        }
+    }
+    flipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, TechnologyInfo, ViaConnection};
+
+    fn create_die(name: &str) -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new(name.to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd2".to_string(),
+            0.5,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.3,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd1".to_string(),
+            0.4,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.2,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide".to_string(),
+            1.0,
+            3.9,
+        )));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            2.0,
+        ));
+        stack
+    }
+
+    #[test]
+    fn test_bond_face_to_face() {
+        let bottom = create_die("logic");
+        let top = create_die("memory");
+        let bonded = bottom
+            .bond_face_to_face(&top, &BondOptions::new().with_gap(0.1))
+            .unwrap();
+        let stack = &bonded.stack;
+        assert_eq!(stack.technology_info.name, "logic+memory");
+
+        let names: Vec<&str> = stack.layers.iter().map(Layer::name).collect();
+        assert_eq!(
+            names,
+            [
+                "top_oxide",
+                "top_imd1_upper",
+                "top_metal1",
+                "top_imd1",
+                "top_imd2_upper",
+                "top_metal2",
+                "top_imd2",
+                "bond_gap",
+                "imd2",
+                "metal2",
+                "imd1",
+                "metal1",
+                "oxide"
+            ]
+        );
+
+        let stats = bonded.stats;
+        assert!((stats.bottom_height - 1.9).abs() < 1e-9);
+        assert!((stats.bond_top_z() - 2.0).abs() < 1e-9);
+        assert!((stats.total_height() - 3.9).abs() < 1e-9);
+        assert!((stack.get_stack_top_z() - stats.total_height()).abs() < 1e-9);
+        assert_eq!(
+            stats.to_string(),
+            "bottom 1.9000 um + gap 0.1000 um + top 1.9000 um = 3.9000 um"
+        );
+
+        // Flipped, metal2 keeps its 0.2 um distance to the bond face
+        let (bottom_z, top_z) = stack.get_layer_z_range("top_metal2").unwrap();
+        assert!((bottom_z - stats.bond_top_z() - 0.2).abs() < 1e-9);
+        assert!((top_z - bottom_z - 0.3).abs() < 1e-9);
+        let (_, imd2_top) = stack.get_layer_z_range("top_imd2_upper").unwrap();
+        assert!((imd2_top - top_z).abs() < 1e-9);
+
+        let via = stack
+            .via_stack
+            .iter()
+            .find(|via| via.name == "top_via1")
+            .unwrap();
+        assert_eq!(
+            (via.from_layer.as_str(), via.to_layer.as_str()),
+            ("top_metal1", "top_metal2")
+        );
+        assert_eq!(stack.get_via_count(), 2);
+    }
+
+    #[test]
+    fn test_bond_keeps_er_regions() {
+        use crate::data::{ErRegion, ErRegionKind};
+
+        let mut bottom = create_die("logic");
+        bottom.add_er_region(ErRegion::new(
+            ErRegionKind::Substrate,
+            "psub".to_string(),
+            11.9,
+        ));
+        let mut top = create_die("memory");
+        top.add_er_region(ErRegion::new(
+            ErRegionKind::Substrate,
+            "psub".to_string(),
+            11.7,
+        ));
+        top.add_er_region(ErRegion::new(ErRegionKind::Field, "sti".to_string(), 3.9));
+
+        let stack = bottom
+            .bond_face_to_face(&top, &BondOptions::new())
+            .unwrap()
+            .stack;
+        let regions: Vec<String> = stack.er_regions.iter().map(ErRegion::label).collect();
+        assert_eq!(
+            regions,
+            ["SUBSTRATE psub", "SUBSTRATE top_psub", "FIELD top_sti"]
+        );
+        assert_eq!(stack.er_regions[1].dielectric_constant, 11.7);
+    }
+
+    #[test]
+    fn test_bond_errors() {
+        let die = create_die("die");
+        assert_eq!(
+            die.bond_face_to_face(&die, &BondOptions::new().with_gap(-0.1))
+                .unwrap_err(),
+            BondError::NegativeGap(-0.1)
+        );
+        assert_eq!(
+            die.bond_face_to_face(&die, &BondOptions::new().with_top_prefix(""))
+                .unwrap_err(),
+            BondError::DuplicateName("imd2".to_string())
+        );
+
+        // Without a gap the faces touch
+        let bonded = die.bond_face_to_face(&die, &BondOptions::new()).unwrap();
+        assert!(bonded.stack.get_layer(BOND_GAP_LAYER).is_none());
+        assert_eq!(bonded.stack.get_layer_count(), 12);
+    }
+
+    #[test]
+    fn test_bonded_itf_keeps_die_properties() {
+        use crate::parser::parse_itf_file;
+        use crate::utils::write_itf;
+
+        let load = |path: &str| parse_itf_file(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let bottom = load("tests/data/complex_1p7m.itf");
+        let top = load("tests/data/complex_test.itf");
+        let bonded = bottom
+            .bond_face_to_face(&top, &BondOptions::new().with_gap(0.5))
+            .unwrap()
+            .stack;
+        assert!(bonded.layers.iter().any(Layer::is_auto_created));
+
+        let written = write_itf(&bonded);
+        assert!(!written.contains("DIELECTRIC SUBSTRATE"));
+        let parsed = parse_itf_file(&written).unwrap();
+        assert_eq!(parsed.fingerprint(), bonded.fingerprint());
+
+        let conductor = |name: &str| match parsed.get_layer(name) {
+            Some(Layer::Conductor(conductor)) => conductor.clone(),
+            _ => panic!("{name} is not a conductor"),
+        };
+        let metal = conductor("top_M9");
+        assert!(metal.rho_vs_si_width_thickness.is_some());
+        assert!(metal.crt_vs_si_width.is_some());
+        assert_eq!(conductor("metal7").capacitive_only_etch, Some(-6.8203e-2));
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    layer::{ConductorLayer, DielectricLayer, Layer},
+    stack::ProcessStack,
+};
+
+/// Dielectric deposited conformally over a conductor, from its SW_T and TW_T
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformalLiner {
+    pub dielectric: String,
+    pub conductor: String,
+    /// Thickness on the conductor sidewalls in um, SW_T
+    pub sidewall_thickness: f64,
+    /// Thickness on top of the conductor in um, TW_T
+    pub top_thickness: f64,
+}
+
+impl ProcessStack {
+    /// Conductor a conformal dielectric covers
+    ///
+    /// This is the conductor named by MEASURED_FROM, or else the conductor listed right
+    /// after the dielectric, which ITF places directly below it.
+    pub fn get_liner_conductor(&self, dielectric: &DielectricLayer) -> Option<&ConductorLayer> {
+        if let Some(Layer::Conductor(conductor)) = dielectric
+            .measured_from
+            .as_deref()
+            .and_then(|name| self.get_layer(name))
+        {
+            return Some(conductor);
+        }
+
+        let index = self
+            .layers
+            .iter()
+            .position(|layer| layer.name() == dielectric.name)?;
+        match self.layers.get(index + 1) {
+            Some(Layer::Conductor(conductor)) => Some(conductor),
+            _ => None,
+        }
+    }
+
+    /// Every dielectric with a positive SW_T or TW_T and a conductor to cover, in stack order
+    pub fn get_conformal_liners(&self) -> Vec<ConformalLiner> {
+        self.layers
+            .iter()
+            .filter_map(|layer| match layer {
+                Layer::Dielectric(dielectric) => Some(dielectric),
+                Layer::Conductor(_) => None,
+            })
+            .filter_map(|dielectric| {
+                let sidewall_thickness = dielectric.sw_t.unwrap_or(0.0).max(0.0);
+                let top_thickness = dielectric.tw_t.unwrap_or(0.0).max(0.0);
+                if sidewall_thickness == 0.0 && top_thickness == 0.0 {
+                    return None;
+                }
+                let conductor = self.get_liner_conductor(dielectric)?;
+                Some(ConformalLiner {
+                    dielectric: dielectric.name.clone(),
+                    conductor: conductor.name.clone(),
+                    sidewall_thickness,
+                    top_thickness,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::TechnologyInfo;
+
+    #[test]
+    fn test_conformal_liners() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("liners".to_string()));
+        let mut cap = DielectricLayer::new("cap".to_string(), 0.1, 7.0);
+        cap.sw_t = Some(0.02);
+        cap.tw_t = Some(0.05);
+        let mut remote = DielectricLayer::new("remote".to_string(), 0.1, 4.0);
+        remote.sw_t = Some(0.01);
+        remote.measured_from = Some("metal1".to_string());
+        let mut orphan = DielectricLayer::new("orphan".to_string(), 0.1, 4.0);
+        orphan.tw_t = Some(0.03);
+
+        // ITF order, top first
+        stack.add_layer(Layer::Dielectric(remote));
+        stack.add_layer(Layer::Dielectric(cap));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.3,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd".to_string(),
+            0.5,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.2,
+        ))));
+        stack.add_layer(Layer::Dielectric(orphan));
+
+        let liners = stack.get_conformal_liners();
+        assert_eq!(liners.len(), 2);
+        assert_eq!(liners[0].dielectric, "remote");
+        assert_eq!(liners[0].conductor, "metal1");
+        assert_eq!(liners[0].top_thickness, 0.0);
+        assert_eq!(liners[1].conductor, "metal2");
+        assert_eq!(liners[1].sidewall_thickness, 0.02);
+        assert_eq!(liners[1].top_thickness, 0.05);
+    }
+}
@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Something noteworthy found while loading a techfile, such as a skipped line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line in the source file, if the message refers to one
+    pub line: Option<usize>,
+    /// Layer or via the message is about
+    #[serde(default)]
+    pub layer: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            line: None,
+            layer: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn with_layer(mut self, layer: impl Into<String>) -> Self {
+        self.layer = Some(layer.into());
+        self
+    }
+
+    /// Where "Go to" leads, with the line defining the layer if the message has none
+    pub fn target(&self, stack: &ProcessStack) -> ProblemTarget {
+        ProblemTarget {
+            layer: self.layer.clone(),
+            line: self
+                .line
+                .or_else(|| stack.get_source_line(self.layer.as_deref()?)),
+        }
+    }
+}
+
+/// Object and source line a diagnostic or lint finding points to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProblemTarget {
+    /// Layer or via to select in the viewer
+    pub layer: Option<String>,
+    /// 1-based line to show in the source
+    pub line: Option<usize>,
+}
+
+impl ProblemTarget {
+    pub fn is_empty(&self) -> bool {
+        self.layer.is_none() && self.line.is_none()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(
+                f,
+                "{}: line {line}: {}",
+                self.severity.label(),
+                self.message
+            ),
+            None => write!(f, "{}: {}", self.severity.label(), self.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic_display() {
+        let diagnostic = Diagnostic::new(Severity::Warning, "Skipped line").with_line(12);
+        assert_eq!(diagnostic.to_string(), "warning: line 12: Skipped line");
+        assert_eq!(
+            Diagnostic::new(Severity::Info, "Auto-created layer").to_string(),
+            "info: Auto-created layer"
+        );
+        assert!(Severity::Error > Severity::Warning);
+    }
+
+    #[test]
+    fn test_diagnostic_target() {
+        let mut stack = ProcessStack::new(crate::data::TechnologyInfo::new("test".to_string()));
+        stack.set_source_line("metal1", 7);
+
+        let diagnostic = Diagnostic::new(Severity::Info, "Table").with_layer("metal1");
+        assert_eq!(
+            diagnostic.target(&stack),
+            ProblemTarget {
+                layer: Some("metal1".to_string()),
+                line: Some(7),
+            }
+        );
+        assert_eq!(diagnostic.with_line(3).target(&stack).line, Some(3));
+        assert!(Diagnostic::new(Severity::Warning, "Missing layer")
+            .target(&stack)
+            .is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{layer::Layer, stack::ProcessStack};
+
+/// Dielectric between two vertically adjacent conductors
+#[derive(Debug, Clone, PartialEq)]
+pub struct DielectricGap {
+    pub lower_conductor: String,
+    pub upper_conductor: String,
+    /// Dielectrics listed between the two conductors, from bottom to top
+    pub dielectric_layers: Vec<String>,
+    /// Sum of the dielectric thicknesses between the conductors in um
+    pub dielectric_thickness: f64,
+    /// Top of the lower conductor to the bottom of the upper one in um
+    ///
+    /// The lower conductor is embedded in the first dielectric above it, so this is the
+    /// dielectric sum minus its thickness; zero for conductors at the same level.
+    pub vertical_gap: f64,
+}
+
+impl ProcessStack {
+    /// Dielectric gap between each pair of adjacent conductors, from the bottom up
+    pub fn get_dielectric_gaps(&self) -> Vec<DielectricGap> {
+        let mut gaps = Vec::new();
+        let mut lower: Option<&Layer> = None;
+        let mut dielectrics: Vec<&Layer> = Vec::new();
+
+        // ITF lists layers from top to bottom
+        for layer in self.layers.iter().rev() {
+            match layer {
+                Layer::Dielectric(_) => dielectrics.push(layer),
+                Layer::Conductor(_) => {
+                    if let Some(lower) = lower {
+                        let dielectric_thickness = dielectrics
+                            .iter()
+                            .fold(0.0, |total, layer| total + layer.thickness());
+                        gaps.push(DielectricGap {
+                            lower_conductor: lower.name().to_string(),
+                            upper_conductor: layer.name().to_string(),
+                            dielectric_layers: dielectrics
+                                .iter()
+                                .map(|layer| layer.name().to_string())
+                                .collect(),
+                            dielectric_thickness,
+                            vertical_gap: (dielectric_thickness - lower.thickness()).max(0.0),
+                        });
+                    }
+                    lower = Some(layer);
+                    dielectrics.clear();
+                }
+            }
+        }
+
+        gaps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+
+    #[test]
+    fn test_dielectric_gaps() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("gaps".to_string()));
+        let dielectric = |name: &str, thickness| {
+            Layer::Dielectric(DielectricLayer::new(name.into(), thickness, 4.0))
+        };
+        let conductor = |name: &str, thickness| {
+            Layer::Conductor(Box::new(ConductorLayer::new(name.into(), thickness)))
+        };
+
+        // ITF order, top first
+        stack.add_layer(dielectric("imd2", 0.4));
+        stack.add_layer(conductor("metal2", 0.3));
+        stack.add_layer(dielectric("imd1b", 0.5));
+        stack.add_layer(dielectric("imd1a", 0.25));
+        stack.add_layer(conductor("metal1", 0.2));
+        stack.add_layer(conductor("metal1_alt", 0.2));
+        stack.add_layer(dielectric("ild", 0.6));
+
+        let gaps = stack.get_dielectric_gaps();
+        assert_eq!(gaps.len(), 2);
+
+        // Side-by-side conductors have no dielectric between them
+        assert_eq!(gaps[0].lower_conductor, "metal1_alt");
+        assert_eq!(gaps[0].dielectric_thickness, 0.0);
+        assert_eq!(gaps[0].vertical_gap, 0.0);
+
+        assert_eq!(gaps[1].lower_conductor, "metal1");
+        assert_eq!(gaps[1].upper_conductor, "metal2");
+        assert_eq!(gaps[1].dielectric_layers, vec!["imd1a", "imd1b"]);
+        assert!((gaps[1].dielectric_thickness - 0.75).abs() < 1e-12);
+        assert!((gaps[1].vertical_gap - 0.55).abs() < 1e-12);
+    }
+}
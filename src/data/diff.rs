@@ -0,0 +1,335 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{er_region::ErRegion, layer::Layer, stack::ProcessStack};
+use std::fmt;
+
+/// Value of a compared property on one side of a diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffValue {
+    Number(f64),
+    Text(String),
+    Unset,
+}
+
+impl DiffValue {
+    fn from_option(value: Option<f64>) -> Self {
+        value.map_or(DiffValue::Unset, DiffValue::Number)
+    }
+}
+
+impl fmt::Display for DiffValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffValue::Number(value) => write!(f, "{value}"),
+            DiffValue::Text(text) => write!(f, "{text}"),
+            DiffValue::Unset => write!(f, "unset"),
+        }
+    }
+}
+
+/// A property of a layer or via that differs between the old and the new stack
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyChange {
+    /// Layer or via name, or the keyword and name of a region such as `SUBSTRATE psub`
+    pub item: String,
+    pub property: String,
+    pub old_value: DiffValue,
+    pub new_value: DiffValue,
+}
+
+impl PropertyChange {
+    /// Relative change in percent, for numbers with a nonzero old value
+    pub fn percent_delta(&self) -> Option<f64> {
+        match (&self.old_value, &self.new_value) {
+            (DiffValue::Number(old), DiffValue::Number(new)) if *old != 0.0 => {
+                Some((new - old) / old.abs() * 100.0)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PropertyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} -> {}",
+            self.item, self.property, self.old_value, self.new_value
+        )?;
+        if let Some(percent) = self.percent_delta() {
+            write!(f, " ({percent:+.1}%)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of [`ProcessStack::diff`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StackDiff {
+    pub added_layers: Vec<String>,
+    pub removed_layers: Vec<String>,
+    pub layer_changes: Vec<PropertyChange>,
+    pub added_vias: Vec<String>,
+    pub removed_vias: Vec<String>,
+    pub via_changes: Vec<PropertyChange>,
+    /// Substrate and field regions, named by keyword and name
+    pub added_regions: Vec<String>,
+    pub removed_regions: Vec<String>,
+    pub region_changes: Vec<PropertyChange>,
+}
+
+impl StackDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_layers.is_empty()
+            && self.removed_layers.is_empty()
+            && self.layer_changes.is_empty()
+            && self.added_vias.is_empty()
+            && self.removed_vias.is_empty()
+            && self.via_changes.is_empty()
+            && self.added_regions.is_empty()
+            && self.removed_regions.is_empty()
+            && self.region_changes.is_empty()
+    }
+}
+
+/// Collects the differing properties of one layer or via
+struct Comparer<'a> {
+    item: &'a str,
+    changes: &'a mut Vec<PropertyChange>,
+}
+
+impl Comparer<'_> {
+    fn compare(&mut self, property: &str, old_value: DiffValue, new_value: DiffValue) {
+        if old_value != new_value {
+            self.changes.push(PropertyChange {
+                item: self.item.to_string(),
+                property: property.to_string(),
+                old_value,
+                new_value,
+            });
+        }
+    }
+
+    fn number(&mut self, property: &str, old_value: Option<f64>, new_value: Option<f64>) {
+        self.compare(
+            property,
+            DiffValue::from_option(old_value),
+            DiffValue::from_option(new_value),
+        );
+    }
+
+    fn text(&mut self, property: &str, old_value: &str, new_value: &str) {
+        self.compare(
+            property,
+            DiffValue::Text(old_value.to_string()),
+            DiffValue::Text(new_value.to_string()),
+        );
+    }
+}
+
+impl ProcessStack {
+    /// Layers, vias and regions that were added, removed or changed from this stack to
+    /// `other`
+    ///
+    /// Items are matched by name, and regions by kind and name. For layers the type,
+    /// THICKNESS, ER and RPSQ are compared, for vias FROM, TO, AREA and RPV and for regions
+    /// ER and THICKNESS. Lists follow the order the items appear in the stack. Layers
+    /// auto-created for dangling vias are not part of either file and are skipped.
+    pub fn diff(&self, other: &ProcessStack) -> StackDiff {
+        let mut diff = StackDiff::default();
+
+        for layer in self.layers.iter().filter(|l| !l.is_auto_created()) {
+            let Some(other_layer) = other.get_layer(layer.name()) else {
+                diff.removed_layers.push(layer.name().to_string());
+                continue;
+            };
+            let mut comparer = Comparer {
+                item: layer.name(),
+                changes: &mut diff.layer_changes,
+            };
+            comparer.text("TYPE", layer_type_name(layer), layer_type_name(other_layer));
+            comparer.number(
+                "THICKNESS",
+                Some(layer.thickness()),
+                Some(other_layer.thickness()),
+            );
+            comparer.number("ER", layer_er(layer), layer_er(other_layer));
+            comparer.number("RPSQ", layer_rpsq(layer), layer_rpsq(other_layer));
+        }
+        diff.added_layers = other
+            .layers
+            .iter()
+            .filter(|layer| !layer.is_auto_created() && self.get_layer(layer.name()).is_none())
+            .map(|layer| layer.name().to_string())
+            .collect();
+
+        for via in self.via_stack.iter() {
+            let Some(other_via) = other.via_stack.get_via(&via.name) else {
+                diff.removed_vias.push(via.name.clone());
+                continue;
+            };
+            let mut comparer = Comparer {
+                item: &via.name,
+                changes: &mut diff.via_changes,
+            };
+            comparer.text("FROM", &via.from_layer, &other_via.from_layer);
+            comparer.text("TO", &via.to_layer, &other_via.to_layer);
+            comparer.number("AREA", Some(via.area), Some(other_via.area));
+            comparer.number(
+                "RPV",
+                Some(via.resistance_per_via),
+                Some(other_via.resistance_per_via),
+            );
+        }
+        diff.added_vias = other
+            .via_stack
+            .iter()
+            .filter(|via| self.via_stack.get_via(&via.name).is_none())
+            .map(|via| via.name.clone())
+            .collect();
+
+        for region in &self.er_regions {
+            let item = region.label();
+            let Some(other_region) = other.get_er_region(region.kind, &region.name) else {
+                diff.removed_regions.push(item);
+                continue;
+            };
+            let mut comparer = Comparer {
+                item: &item,
+                changes: &mut diff.region_changes,
+            };
+            comparer.number(
+                "ER",
+                Some(region.dielectric_constant),
+                Some(other_region.dielectric_constant),
+            );
+            comparer.number("THICKNESS", region.thickness, other_region.thickness);
+        }
+        diff.added_regions = other
+            .er_regions
+            .iter()
+            .filter(|region| self.get_er_region(region.kind, &region.name).is_none())
+            .map(ErRegion::label)
+            .collect();
+
+        diff
+    }
+}
+
+fn layer_type_name(layer: &Layer) -> &'static str {
+    match layer {
+        Layer::Dielectric(_) => "DIELECTRIC",
+        Layer::Conductor(_) => "CONDUCTOR",
+    }
+}
+
+fn layer_er(layer: &Layer) -> Option<f64> {
+    match layer {
+        Layer::Dielectric(dielectric) => Some(dielectric.dielectric_constant),
+        Layer::Conductor(conductor) => conductor.physical_props.dielectric_constant,
+    }
+}
+
+fn layer_rpsq(layer: &Layer) -> Option<f64> {
+    match layer {
+        Layer::Dielectric(_) => None,
+        Layer::Conductor(conductor) => conductor.electrical_props.rpsq,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo, ViaConnection};
+
+    fn stack(metal_thickness: f64, rpsq: f64, with_via: bool) -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("diff".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "ild".to_string(),
+            1.0,
+            4.2,
+        )));
+        let mut metal = ConductorLayer::new("metal1".to_string(), metal_thickness);
+        metal.electrical_props.rpsq = Some(rpsq);
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+        if with_via {
+            stack.add_via(ViaConnection::new(
+                "via1".to_string(),
+                "ild".to_string(),
+                "metal1".to_string(),
+                0.04,
+                5.0,
+            ));
+        }
+        stack
+    }
+
+    #[test]
+    fn test_stack_diff() {
+        let old = stack(0.2, 0.1, true);
+        let mut new = stack(0.25, 0.1, false);
+        new.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "substrate".to_string(),
+            2.0,
+            11.9,
+        )));
+
+        assert!(old.diff(&old).is_empty());
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added_layers, vec!["substrate"]);
+        assert!(diff.removed_layers.is_empty());
+        assert_eq!(diff.removed_vias, vec!["via1"]);
+        assert_eq!(diff.layer_changes.len(), 1);
+        let change = &diff.layer_changes[0];
+        assert_eq!(change.property, "THICKNESS");
+        assert!((change.percent_delta().unwrap() - 25.0).abs() < 1e-9);
+        assert_eq!(change.to_string(), "metal1 THICKNESS: 0.2 -> 0.25 (+25.0%)");
+
+        // The reverse diff swaps additions and removals
+        let diff = new.diff(&old);
+        assert_eq!(diff.removed_layers, vec!["substrate"]);
+        assert_eq!(diff.added_vias, vec!["via1"]);
+
+        let change = PropertyChange {
+            item: "m".to_string(),
+            property: "RPSQ".to_string(),
+            old_value: DiffValue::Unset,
+            new_value: DiffValue::Number(0.1),
+        };
+        assert_eq!(change.percent_delta(), None);
+        assert_eq!(change.to_string(), "m RPSQ: unset -> 0.1");
+    }
+
+    #[test]
+    fn test_stack_diff_er_regions() {
+        use crate::data::ErRegionKind;
+
+        let mut old = stack(0.2, 0.1, false);
+        old.add_er_region(ErRegion::new(
+            ErRegionKind::Substrate,
+            "psub".to_string(),
+            11.9,
+        ));
+        old.add_er_region(ErRegion::new(ErRegionKind::Field, "sti".to_string(), 3.9));
+        let mut new = stack(0.2, 0.1, false);
+        new.add_er_region(ErRegion::new(
+            ErRegionKind::Substrate,
+            "psub".to_string(),
+            11.7,
+        ));
+        new.add_er_region(ErRegion::new(ErRegionKind::Field, "locos".to_string(), 3.9));
+
+        assert!(old.diff(&old).is_empty());
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.removed_regions, vec!["FIELD sti"]);
+        assert_eq!(diff.added_regions, vec!["FIELD locos"]);
+        assert_eq!(diff.region_changes.len(), 1);
+        assert_eq!(
+            diff.region_changes[0].to_string(),
+            "SUBSTRATE psub ER: 11.9 -> 11.7 (-1.7%)"
+        );
+    }
+}
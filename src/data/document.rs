@@ -0,0 +1,535 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{layer::Layer, stack::ProcessStack, stack::TechnologyInfo};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+
+/// Notification sent to subscribers after the document's stack changed
+#[derive(Debug, Clone, PartialEq)]
+pub enum StackChange {
+    /// The whole stack was replaced, e.g. by loading a file
+    Loaded,
+    LayerModified {
+        layer_name: String,
+    },
+    LayerRenamed {
+        old_name: String,
+        new_name: String,
+    },
+    LayersReordered,
+    TechnologyModified,
+    /// Lookup tables were dropped to save memory
+    TablesUnloaded,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum EditError {
+    #[error("Layer '{0}' not found")]
+    UnknownLayer(String),
+
+    #[error("Invalid thickness {thickness} for layer '{layer_name}'")]
+    InvalidThickness { layer_name: String, thickness: f64 },
+
+    #[error("Layer name '{0}' is already in use")]
+    DuplicateLayerName(String),
+
+    #[error("Layer name must not be empty")]
+    EmptyLayerName,
+}
+
+/// A reversible edit of a process stack
+///
+/// Applying an edit returns its inverse, which is what the history stores to undo it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StackEdit {
+    SetLayerThickness {
+        layer_name: String,
+        thickness: f64,
+    },
+    RenameLayer {
+        layer_name: String,
+        new_name: String,
+    },
+    /// Move a layer to a new index in the layer list
+    MoveLayer {
+        layer_name: String,
+        index: usize,
+    },
+    /// Replace the layer with the same name
+    ReplaceLayer(Box<Layer>),
+    SetTechnologyInfo(TechnologyInfo),
+}
+
+impl StackEdit {
+    pub fn description(&self) -> String {
+        match self {
+            StackEdit::SetLayerThickness { layer_name, .. } => {
+                format!("Set thickness of {layer_name}")
+            }
+            StackEdit::RenameLayer {
+                layer_name,
+                new_name,
+            } => format!("Rename {layer_name} to {new_name}"),
+            StackEdit::MoveLayer { layer_name, .. } => format!("Move {layer_name}"),
+            StackEdit::ReplaceLayer(layer) => format!("Edit {}", layer.name()),
+            StackEdit::SetTechnologyInfo(_) => "Edit technology".to_string(),
+        }
+    }
+
+    /// Apply the edit, returning its inverse and the resulting change
+    pub fn apply(self, stack: &mut ProcessStack) -> Result<(StackEdit, StackChange), EditError> {
+        match self {
+            StackEdit::SetLayerThickness {
+                layer_name,
+                thickness,
+            } => {
+                if !thickness.is_finite() || thickness < 0.0 {
+                    return Err(EditError::InvalidThickness {
+                        layer_name,
+                        thickness,
+                    });
+                }
+                let previous = stack
+                    .set_layer_thickness(&layer_name, thickness)
+                    .ok_or_else(|| EditError::UnknownLayer(layer_name.clone()))?;
+                Ok((
+                    StackEdit::SetLayerThickness {
+                        layer_name: layer_name.clone(),
+                        thickness: previous,
+                    },
+                    StackChange::LayerModified { layer_name },
+                ))
+            }
+            StackEdit::RenameLayer {
+                layer_name,
+                new_name,
+            } => {
+                if new_name.trim().is_empty() {
+                    return Err(EditError::EmptyLayerName);
+                }
+                if stack.get_layer(&layer_name).is_none() {
+                    return Err(EditError::UnknownLayer(layer_name));
+                }
+                if !stack.rename_layer(&layer_name, &new_name) {
+                    return Err(EditError::DuplicateLayerName(new_name));
+                }
+                Ok((
+                    StackEdit::RenameLayer {
+                        layer_name: new_name.clone(),
+                        new_name: layer_name.clone(),
+                    },
+                    StackChange::LayerRenamed {
+                        old_name: layer_name,
+                        new_name,
+                    },
+                ))
+            }
+            StackEdit::MoveLayer { layer_name, index } => {
+                let previous = stack
+                    .move_layer(&layer_name, index)
+                    .ok_or_else(|| EditError::UnknownLayer(layer_name.clone()))?;
+                Ok((
+                    StackEdit::MoveLayer {
+                        layer_name,
+                        index: previous,
+                    },
+                    StackChange::LayersReordered,
+                ))
+            }
+            StackEdit::ReplaceLayer(layer) => {
+                let layer_name = layer.name().to_string();
+                let previous = stack
+                    .replace_layer(*layer)
+                    .ok_or_else(|| EditError::UnknownLayer(layer_name.clone()))?;
+                Ok((
+                    StackEdit::ReplaceLayer(Box::new(previous)),
+                    StackChange::LayerModified { layer_name },
+                ))
+            }
+            StackEdit::SetTechnologyInfo(info) => {
+                let previous = std::mem::replace(&mut stack.technology_info, info);
+                Ok((
+                    StackEdit::SetTechnologyInfo(previous),
+                    StackChange::TechnologyModified,
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    description: String,
+    edit: StackEdit,
+}
+
+/// A process stack together with its edit history
+///
+/// All mutations go through `apply`, `undo` and `redo`, so subscribers are told about
+/// every change and the revision number can be used to invalidate derived caches.
+pub struct StackDocument {
+    /// Shared with background analyses; an edit copies it only while one still holds it
+    stack: Arc<ProcessStack>,
+    undo_stack: Vec<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+    subscribers: Vec<Sender<StackChange>>,
+    revision: u64,
+}
+
+impl StackDocument {
+    pub fn new(stack: ProcessStack) -> Self {
+        Self {
+            stack: Arc::new(stack),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            subscribers: Vec::new(),
+            revision: 0,
+        }
+    }
+
+    pub fn stack(&self) -> &ProcessStack {
+        &self.stack
+    }
+
+    /// The current stack, for reading on other threads
+    pub fn shared_stack(&self) -> Arc<ProcessStack> {
+        Arc::clone(&self.stack)
+    }
+
+    pub fn into_stack(self) -> ProcessStack {
+        Arc::unwrap_or_clone(self.stack)
+    }
+
+    /// Incremented on every change, starting at 0 for a freshly loaded stack
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Receive a `StackChange` for every subsequent change
+    pub fn subscribe(&mut self) -> Receiver<StackChange> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Replace the whole stack, dropping the edit history
+    pub fn load_stack(&mut self, stack: ProcessStack) {
+        self.stack = Arc::new(stack);
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.notify(StackChange::Loaded);
+    }
+
+    /// Drop the lookup tables of the stack, returning the approximate bytes freed
+    ///
+    /// The edit history is dropped too, as its entries may still hold the tables.
+    pub fn unload_tables(&mut self) -> usize {
+        let bytes = Arc::make_mut(&mut self.stack).unload_tables();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.notify(StackChange::TablesUnloaded);
+        bytes
+    }
+
+    pub fn apply(&mut self, edit: StackEdit) -> Result<(), EditError> {
+        let description = edit.description();
+        let (inverse, change) = edit.apply(Arc::make_mut(&mut self.stack))?;
+
+        self.undo_stack.push(HistoryEntry {
+            description,
+            edit: inverse,
+        });
+        self.redo_stack.clear();
+        self.notify(change);
+        Ok(())
+    }
+
+    /// Undo the most recent edit, returning its description
+    pub fn undo(&mut self) -> Result<Option<String>, EditError> {
+        Self::step(
+            Arc::make_mut(&mut self.stack),
+            &mut self.undo_stack,
+            &mut self.redo_stack,
+        )
+        .map(|result| {
+            result.map(|(description, change)| {
+                self.notify(change);
+                description
+            })
+        })
+    }
+
+    /// Redo the most recently undone edit, returning its description
+    pub fn redo(&mut self) -> Result<Option<String>, EditError> {
+        Self::step(
+            Arc::make_mut(&mut self.stack),
+            &mut self.redo_stack,
+            &mut self.undo_stack,
+        )
+        .map(|result| {
+            result.map(|(description, change)| {
+                self.notify(change);
+                description
+            })
+        })
+    }
+
+    fn step(
+        stack: &mut ProcessStack,
+        from: &mut Vec<HistoryEntry>,
+        to: &mut Vec<HistoryEntry>,
+    ) -> Result<Option<(String, StackChange)>, EditError> {
+        let Some(entry) = from.pop() else {
+            return Ok(None);
+        };
+
+        match entry.edit.clone().apply(stack) {
+            Ok((inverse, change)) => {
+                to.push(HistoryEntry {
+                    description: entry.description.clone(),
+                    edit: inverse,
+                });
+                Ok(Some((entry.description, change)))
+            }
+            Err(e) => {
+                from.push(entry);
+                Err(e)
+            }
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo_description(&self) -> Option<&str> {
+        self.undo_stack
+            .last()
+            .map(|entry| entry.description.as_str())
+    }
+
+    pub fn redo_description(&self) -> Option<&str> {
+        self.redo_stack
+            .last()
+            .map(|entry| entry.description.as_str())
+    }
+
+    pub fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    fn notify(&mut self, change: StackChange) {
+        self.revision += 1;
+        // Drop subscribers whose receiver is gone
+        self.subscribers
+            .retain(|subscriber| subscriber.send(change.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer};
+    use approx::assert_relative_eq;
+
+    fn create_test_document() -> StackDocument {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("doc_tech".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide1".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.5,
+        ))));
+        StackDocument::new(stack)
+    }
+
+    #[test]
+    fn test_undo_redo_thickness() {
+        let mut document = create_test_document();
+        assert!(!document.can_undo());
+
+        document
+            .apply(StackEdit::SetLayerThickness {
+                layer_name: "oxide1".to_string(),
+                thickness: 2.0,
+            })
+            .unwrap();
+        assert_relative_eq!(document.stack().get_total_height(), 2.5);
+        assert_eq!(document.undo_description(), Some("Set thickness of oxide1"));
+
+        assert_eq!(
+            document.undo().unwrap().as_deref(),
+            Some("Set thickness of oxide1")
+        );
+        assert_relative_eq!(document.stack().get_total_height(), 1.5);
+        assert!(document.can_redo());
+
+        document.redo().unwrap();
+        assert_relative_eq!(document.stack().get_total_height(), 2.5);
+        assert!(!document.can_redo());
+        assert_eq!(document.redo().unwrap(), None);
+    }
+
+    #[test]
+    fn test_shared_stack_snapshot() {
+        let mut document = create_test_document();
+        let snapshot = document.shared_stack();
+        assert!(Arc::ptr_eq(&snapshot, &document.shared_stack()));
+
+        // Edits leave a snapshot held elsewhere untouched
+        document
+            .apply(StackEdit::SetLayerThickness {
+                layer_name: "oxide1".to_string(),
+                thickness: 2.0,
+            })
+            .unwrap();
+        assert_relative_eq!(snapshot.get_total_height(), 1.5);
+        assert_relative_eq!(document.stack().get_total_height(), 2.5);
+        assert!(!Arc::ptr_eq(&snapshot, &document.shared_stack()));
+
+        drop(snapshot);
+        assert_relative_eq!(document.into_stack().get_total_height(), 2.5);
+    }
+
+    #[test]
+    fn test_rename_and_replace() {
+        let mut document = create_test_document();
+
+        document
+            .apply(StackEdit::RenameLayer {
+                layer_name: "metal1".to_string(),
+                new_name: "M1".to_string(),
+            })
+            .unwrap();
+        assert!(document.stack().get_layer("M1").is_some());
+
+        let mut oxide = DielectricLayer::new("oxide1".to_string(), 1.0, 2.9);
+        oxide.sw_t = Some(0.1);
+        document
+            .apply(StackEdit::ReplaceLayer(Box::new(Layer::Dielectric(oxide))))
+            .unwrap();
+
+        document.undo().unwrap();
+        document.undo().unwrap();
+        assert!(document.stack().get_layer("metal1").is_some());
+        match document.stack().get_layer("oxide1").unwrap() {
+            Layer::Dielectric(layer) => assert_relative_eq!(layer.dielectric_constant, 4.2),
+            Layer::Conductor(_) => panic!("Expected dielectric"),
+        }
+    }
+
+    #[test]
+    fn test_move_layer() {
+        let mut document = create_test_document();
+
+        document
+            .apply(StackEdit::MoveLayer {
+                layer_name: "metal1".to_string(),
+                index: 0,
+            })
+            .unwrap();
+        assert_eq!(document.stack().layers[0].name(), "metal1");
+        assert_relative_eq!(
+            document.stack().get_layer("oxide1").unwrap().z_position(),
+            0.5
+        );
+
+        document.undo().unwrap();
+        assert_eq!(document.stack().layers[0].name(), "oxide1");
+        assert_relative_eq!(
+            document.stack().get_layer("metal1").unwrap().z_position(),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_invalid_edits() {
+        let mut document = create_test_document();
+
+        assert_eq!(
+            document.apply(StackEdit::SetLayerThickness {
+                layer_name: "missing".to_string(),
+                thickness: 1.0,
+            }),
+            Err(EditError::UnknownLayer("missing".to_string()))
+        );
+        assert!(matches!(
+            document.apply(StackEdit::SetLayerThickness {
+                layer_name: "oxide1".to_string(),
+                thickness: -1.0,
+            }),
+            Err(EditError::InvalidThickness { .. })
+        ));
+        assert_eq!(
+            document.apply(StackEdit::RenameLayer {
+                layer_name: "metal1".to_string(),
+                new_name: "oxide1".to_string(),
+            }),
+            Err(EditError::DuplicateLayerName("oxide1".to_string()))
+        );
+        assert!(!document.can_undo());
+        assert_eq!(document.revision(), 0);
+    }
+
+    #[test]
+    fn test_change_notifications() {
+        let mut document = create_test_document();
+        let receiver = document.subscribe();
+        let dropped = document.subscribe();
+        drop(dropped);
+
+        document
+            .apply(StackEdit::SetLayerThickness {
+                layer_name: "metal1".to_string(),
+                thickness: 0.4,
+            })
+            .unwrap();
+        document.undo().unwrap();
+        document.load_stack(ProcessStack::new(TechnologyInfo::new("new".to_string())));
+
+        let changes: Vec<StackChange> = receiver.try_iter().collect();
+        assert_eq!(
+            changes,
+            vec![
+                StackChange::LayerModified {
+                    layer_name: "metal1".to_string()
+                },
+                StackChange::LayerModified {
+                    layer_name: "metal1".to_string()
+                },
+                StackChange::Loaded,
+            ]
+        );
+        assert_eq!(document.revision(), 3);
+        assert!(!document.can_undo());
+        assert_eq!(document.subscribers.len(), 1);
+    }
+
+    #[test]
+    fn test_unload_tables_drops_history() {
+        let mut document = create_test_document();
+        let receiver = document.subscribe();
+        document
+            .apply(StackEdit::SetLayerThickness {
+                layer_name: "metal1".to_string(),
+                thickness: 0.4,
+            })
+            .unwrap();
+
+        assert_eq!(document.unload_tables(), 0);
+        assert!(!document.can_undo());
+        assert_eq!(
+            receiver.try_iter().last(),
+            Some(StackChange::TablesUnloaded)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{properties::PropValue, stack::ProcessStack};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Kind of region below the interconnect stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErRegionKind {
+    /// Silicon substrate, from a `SUBSTRATE` block
+    Substrate,
+    /// Field oxide or isolation between the devices, from a `FIELD` block
+    Field,
+}
+
+impl ErRegionKind {
+    pub const ALL: [ErRegionKind; 2] = [ErRegionKind::Field, ErRegionKind::Substrate];
+
+    /// ITF keyword opening a block of this kind
+    pub fn keyword(self) -> &'static str {
+        match self {
+            ErRegionKind::Substrate => "SUBSTRATE",
+            ErRegionKind::Field => "FIELD",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ErRegionKind::Substrate => "Substrate",
+            ErRegionKind::Field => "Field",
+        }
+    }
+}
+
+/// Substrate or field region defined by its ER, kept apart from the dielectric layers
+///
+/// Written `SUBSTRATE [name] { ER = 11.9 THICKNESS = 2 }` or `FIELD [name] { ER = 3.9 }`;
+/// the name defaults to the keyword in lower case.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErRegion {
+    pub kind: ErRegionKind,
+    pub name: String,
+    pub dielectric_constant: f64,
+    pub thickness: Option<f64>,
+    /// Properties from the techfile without a dedicated field, keyed in upper case
+    pub extra_properties: HashMap<String, PropValue>,
+}
+
+impl ErRegion {
+    pub fn new(kind: ErRegionKind, name: String, dielectric_constant: f64) -> Self {
+        Self {
+            kind,
+            name,
+            dielectric_constant,
+            thickness: None,
+            extra_properties: HashMap::new(),
+        }
+    }
+
+    pub fn with_thickness(mut self, thickness: f64) -> Self {
+        self.thickness = Some(thickness);
+        self
+    }
+
+    /// Keyword and name, such as `SUBSTRATE psub`
+    pub fn label(&self) -> String {
+        format!("{} {}", self.kind.keyword(), self.name)
+    }
+}
+
+impl ProcessStack {
+    /// Add a region, replacing the one of the same kind and name
+    pub fn add_er_region(&mut self, region: ErRegion) {
+        match self
+            .er_regions
+            .iter_mut()
+            .find(|existing| existing.kind == region.kind && existing.name == region.name)
+        {
+            Some(existing) => *existing = region,
+            None => self.er_regions.push(region),
+        }
+    }
+
+    pub fn get_er_region(&self, kind: ErRegionKind, name: &str) -> Option<&ErRegion> {
+        self.er_regions
+            .iter()
+            .find(|region| region.kind == kind && region.name == name)
+    }
+
+    /// Regions from just below the stack downward: field regions, then the substrate
+    pub fn get_er_regions_from_top(&self) -> Vec<&ErRegion> {
+        ErRegionKind::ALL
+            .iter()
+            .flat_map(|&kind| self.er_regions.iter().filter(move |r| r.kind == kind))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::TechnologyInfo;
+
+    #[test]
+    fn test_er_regions_from_top() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("regions".to_string()));
+        stack.add_er_region(ErRegion::new(
+            ErRegionKind::Substrate,
+            "psub".to_string(),
+            11.9,
+        ));
+        stack.add_er_region(ErRegion::new(ErRegionKind::Field, "sti".to_string(), 3.9));
+        stack.add_er_region(
+            ErRegion::new(ErRegionKind::Field, "sti".to_string(), 4.1).with_thickness(0.3),
+        );
+
+        let regions = stack.get_er_regions_from_top();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].name, "sti");
+        assert_eq!(regions[0].dielectric_constant, 4.1);
+        assert_eq!(regions[0].thickness, Some(0.3));
+        assert_eq!(regions[1].kind, ErRegionKind::Substrate);
+    }
+}
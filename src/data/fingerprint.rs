@@ -0,0 +1,313 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    er_region::ErRegion,
+    layer::Layer,
+    properties::{LookupTable2D, PropValue},
+    stack::ProcessStack,
+    via::ViaConnection,
+};
+use std::collections::HashMap;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a over `KEY=value` records, independent of the Rust version and platform
+struct Fingerprinter {
+    hash: u64,
+}
+
+impl Fingerprinter {
+    fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.hash ^= u64::from(byte);
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn text(&mut self, key: &str, value: &str) {
+        self.write(key.as_bytes());
+        self.write(b"=");
+        self.write(value.as_bytes());
+        self.write(b"\n");
+    }
+
+    fn number(&mut self, key: &str, value: f64) {
+        // -0.0 and 0.0 describe the same process
+        let value = if value == 0.0 { 0.0 } else { value };
+        self.text(key, &format!("{value:?}"));
+    }
+
+    fn optional(&mut self, key: &str, value: Option<f64>) {
+        if let Some(value) = value {
+            self.number(key, value);
+        }
+    }
+
+    fn flag(&mut self, key: &str, value: bool) {
+        if value {
+            self.text(key, "YES");
+        }
+    }
+
+    fn numbers(&mut self, key: &str, values: &[f64]) {
+        for value in values {
+            self.number(key, *value);
+        }
+    }
+
+    fn table(&mut self, key: &str, table: &Option<LookupTable2D>) {
+        if let Some(table) = table {
+            self.text(key, "{");
+            self.numbers("WIDTHS", &table.widths);
+            self.numbers("SPACINGS", &table.spacings);
+            for row in &table.values {
+                self.numbers("VALUES", row);
+                self.write(b";");
+            }
+            self.text(key, "}");
+        }
+    }
+
+    fn extra_properties(&mut self, properties: &HashMap<String, PropValue>) {
+        let mut keys: Vec<_> = properties.keys().collect();
+        keys.sort();
+        for key in keys {
+            match &properties[key] {
+                PropValue::Number(value) => self.number(key, *value),
+                PropValue::Text(text) => self.text(key, text),
+            }
+        }
+    }
+
+    fn layer(&mut self, layer: &Layer) {
+        match layer {
+            Layer::Dielectric(dielectric) => {
+                self.text("DIELECTRIC", &dielectric.name);
+                self.number("THICKNESS", dielectric.thickness);
+                self.number("ER", dielectric.dielectric_constant);
+                if let Some(measured_from) = &dielectric.measured_from {
+                    self.text("MEASURED_FROM", measured_from);
+                }
+                self.optional("SW_T", dielectric.sw_t);
+                self.optional("TW_T", dielectric.tw_t);
+                self.extra_properties(&dielectric.extra_properties);
+            }
+            Layer::Conductor(conductor) => {
+                self.text("CONDUCTOR", &conductor.name);
+                self.number("THICKNESS", conductor.thickness);
+                let electrical = &conductor.electrical_props;
+                self.optional("CRT1", electrical.crt1);
+                self.optional("CRT2", electrical.crt2);
+                self.optional("RPSQ", electrical.rpsq);
+                self.optional("RPV", electrical.rpv);
+                let physical = &conductor.physical_props;
+                self.optional("WMIN", physical.width_min);
+                self.optional("SMIN", physical.spacing_min);
+                self.optional("SIDE_TANGENT", physical.side_tangent);
+                self.optional("ER", physical.dielectric_constant);
+                let gate = &conductor.gate_props;
+                if let Some(layer_type) = &gate.layer_type {
+                    self.text("LAYER_TYPE", layer_type);
+                }
+                self.flag("GATE_FORMING_LAYER", gate.gate_forming_layer);
+                self.optional("GATE_TO_CONTACT_SMIN", gate.gate_to_contact_smin);
+                self.flag("GATE_TO_DIFFUSION_CAP", gate.has_gate_to_diffusion_cap);
+                self.optional("RESISTIVE_ONLY_ETCH", conductor.resistive_only_etch);
+                self.optional("CAPACITIVE_ONLY_ETCH", conductor.capacitive_only_etch);
+                self.table("RHO_VS_WIDTH_AND_SPACING", &conductor.rho_vs_width_spacing);
+                self.table(
+                    "RHO_VS_SI_WIDTH_AND_THICKNESS",
+                    &conductor.rho_vs_si_width_thickness,
+                );
+                self.table(
+                    "ETCH_VS_WIDTH_AND_SPACING",
+                    &conductor.etch_vs_width_spacing,
+                );
+                self.table("ETCH_FROM_TOP", &conductor.etch_from_top);
+                self.table(
+                    "THICKNESS_VS_WIDTH_AND_SPACING",
+                    &conductor.thickness_vs_width_spacing,
+                );
+                if let Some(crt) = &conductor.crt_vs_si_width {
+                    self.numbers("CRT_VS_SI_WIDTH", &crt.widths);
+                    self.numbers("CRT1", &crt.crt1_values);
+                    self.numbers("CRT2", &crt.crt2_values);
+                }
+                if let Some(variation) = &conductor.process_variation {
+                    for order in &variation.density_polynomial_orders {
+                        self.text("DENSITY_POLYNOMIAL_ORDERS", &order.to_string());
+                    }
+                    for order in &variation.width_polynomial_orders {
+                        self.text("WIDTH_POLYNOMIAL_ORDERS", &order.to_string());
+                    }
+                    self.numbers("WIDTH_RANGES", &variation.width_ranges);
+                    for coefficients in &variation.polynomial_coefficients {
+                        self.numbers("POLYNOMIAL_COEFFICIENTS", coefficients);
+                        self.write(b";");
+                    }
+                }
+                self.extra_properties(&conductor.extra_properties);
+            }
+        }
+    }
+
+    fn via(&mut self, via: &ViaConnection) {
+        self.text("VIA", &via.name);
+        self.text("FROM", &via.from_layer);
+        self.text("TO", &via.to_layer);
+        self.number("AREA", via.area);
+        self.number("RPV", via.resistance_per_via);
+        // Arrays approximated from AREA carry no information of their own
+        if let Some(array) = via.array.as_ref().filter(|array| !array.approximated) {
+            self.text("COLUMNS", &array.columns.to_string());
+            self.text("ROWS", &array.rows.to_string());
+            self.number("CUT_WIDTH", array.cut_width);
+            self.number("PITCH_X", array.pitch_x);
+            self.number("PITCH_Y", array.pitch_y);
+        }
+        self.extra_properties(&via.extra_properties);
+    }
+
+    fn er_region(&mut self, region: &ErRegion) {
+        self.text(region.kind.keyword(), &region.name);
+        self.number("ER", region.dielectric_constant);
+        self.optional("THICKNESS", region.thickness);
+        self.extra_properties(&region.extra_properties);
+    }
+}
+
+impl ProcessStack {
+    /// Stable content hash of the stack as 16 hex digits
+    ///
+    /// Covers the technology header, the substrate and field regions and every layer and
+    /// via property, so two techfiles with the same fingerprint describe the same process.
+    /// Comments, formatting, property order within a block and the order of the vias and
+    /// regions do not matter; the layer order does. Derived data such as positions,
+    /// auto-created layers and diagnostics are left out.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Fingerprinter::new();
+
+        let technology = &self.technology_info;
+        hasher.text("TECHNOLOGY", &technology.name);
+        hasher.optional("GLOBAL_TEMPERATURE", technology.global_temperature);
+        if let Some(direction) = &technology.reference_direction {
+            hasher.text("REFERENCE_DIRECTION", direction);
+        }
+        hasher.optional("BACKGROUND_ER", technology.background_er);
+        hasher.optional("HALF_NODE_SCALE_FACTOR", technology.half_node_scale_factor);
+        if let Some(use_si_density) = technology.use_si_density {
+            hasher.text("USE_SI_DENSITY", if use_si_density { "YES" } else { "NO" });
+        }
+        hasher.optional(
+            "DROP_FACTOR_LATERAL_SPACING",
+            technology.drop_factor_lateral_spacing,
+        );
+
+        let mut regions: Vec<_> = self.er_regions.iter().collect();
+        regions.sort_by(|a, b| (a.kind.keyword(), &a.name).cmp(&(b.kind.keyword(), &b.name)));
+        for region in regions {
+            hasher.er_region(region);
+        }
+
+        for layer in self.layers.iter().filter(|layer| !layer.is_auto_created()) {
+            hasher.layer(layer);
+        }
+
+        let mut vias: Vec<_> = self.via_stack.iter().collect();
+        vias.sort_by(|a, b| {
+            (&a.name, &a.from_layer, &a.to_layer).cmp(&(&b.name, &b.from_layer, &b.to_layer))
+        });
+        for via in vias {
+            hasher.via(via);
+        }
+
+        format!("{:016x}", hasher.hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+
+    fn stack(via_names: &[&str]) -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("fingerprint".to_string()));
+        let mut ild = DielectricLayer::new("ild".to_string(), 1.0, 4.2);
+        ild.extra_properties
+            .insert("RHO".to_string(), PropValue::Number(0.0));
+        ild.extra_properties
+            .insert("NOTE".to_string(), PropValue::Text("low-k".to_string()));
+        stack.add_layer(Layer::Dielectric(ild));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.2,
+        ))));
+        for name in via_names {
+            stack.add_via(ViaConnection::new(
+                name.to_string(),
+                "ild".to_string(),
+                "metal1".to_string(),
+                0.04,
+                5.0,
+            ));
+        }
+        stack
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let fingerprint = stack(&["via1", "via2"]).fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+        assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // Via order is not significant
+        assert_eq!(stack(&["via2", "via1"]).fingerprint(), fingerprint);
+        assert_ne!(stack(&["via1"]).fingerprint(), fingerprint);
+
+        let mut changed = stack(&["via1", "via2"]);
+        if let Layer::Conductor(metal) = &mut changed.layers[1] {
+            metal.electrical_props.rpsq = Some(0.1);
+        }
+        assert_ne!(changed.fingerprint(), fingerprint);
+
+        let mut negative_zero = stack(&["via1", "via2"]);
+        if let Layer::Dielectric(ild) = &mut negative_zero.layers[0] {
+            ild.extra_properties
+                .insert("RHO".to_string(), PropValue::Number(-0.0));
+        }
+        assert_eq!(negative_zero.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_fingerprint_er_regions() {
+        use crate::data::ErRegionKind;
+
+        let with_regions = |substrate_er: f64, field_first: bool| {
+            let mut stack = stack(&["via1"]);
+            let substrate =
+                ErRegion::new(ErRegionKind::Substrate, "psub".to_string(), substrate_er);
+            let field = ErRegion::new(ErRegionKind::Field, "sti".to_string(), 3.9);
+            if field_first {
+                stack.add_er_region(field);
+                stack.add_er_region(substrate);
+            } else {
+                stack.add_er_region(substrate);
+                stack.add_er_region(field);
+            }
+            stack
+        };
+        let fingerprint = with_regions(11.9, false).fingerprint();
+        assert_ne!(stack(&["via1"]).fingerprint(), fingerprint);
+        assert_ne!(with_regions(11.7, false).fingerprint(), fingerprint);
+        // Region order is not significant
+        assert_eq!(with_regions(11.9, true).fingerprint(), fingerprint);
+    }
+}
@@ -1,13 +1,17 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+use crate::analysis;
 use crate::data::properties::*;
+use crate::data::units::{Micrometers, OhmMicrometers, OhmsPerSquare};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LayerType {
     Dielectric,
     Conductor,
+    Gate,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -20,6 +24,8 @@ pub struct DielectricLayer {
     pub tw_t: Option<f64>,
     pub z_position: f64,
     pub auto_created: bool,
+    /// Properties from the techfile without a dedicated field, keyed in upper case
+    pub extra_properties: HashMap<String, PropValue>,
 }
 
 impl DielectricLayer {
@@ -33,6 +39,7 @@ impl DielectricLayer {
             tw_t: None,
             z_position: 0.0,
             auto_created: false,
+            extra_properties: HashMap::new(),
         }
     }
 
@@ -46,6 +53,7 @@ impl DielectricLayer {
             tw_t: None,
             z_position: 0.0,
             auto_created: true,
+            extra_properties: HashMap::new(),
         }
     }
 
@@ -63,8 +71,13 @@ impl DielectricLayer {
         LayerType::Dielectric
     }
 
+    pub fn thickness_um(&self) -> Micrometers {
+        Micrometers(self.thickness)
+    }
+
+    /// Top of the layer, at its bottom for markers
     pub fn get_top_z(&self) -> f64 {
-        self.z_position + self.thickness
+        self.z_position + self.thickness.max(0.0)
     }
 
     pub fn get_bottom_z(&self) -> f64 {
@@ -78,6 +91,7 @@ pub struct ConductorLayer {
     pub thickness: f64,
     pub electrical_props: ElectricalProperties,
     pub physical_props: PhysicalProperties,
+    pub gate_props: GateProperties,
     pub rho_vs_width_spacing: Option<LookupTable2D>,
     pub rho_vs_si_width_thickness: Option<LookupTable2D>,
     pub etch_vs_width_spacing: Option<LookupTable2D>,
@@ -88,6 +102,8 @@ pub struct ConductorLayer {
     pub resistive_only_etch: Option<f64>,
     pub capacitive_only_etch: Option<f64>,
     pub z_position: f64,
+    /// Properties from the techfile without a dedicated field, keyed in upper case
+    pub extra_properties: HashMap<String, PropValue>,
 }
 
 impl ConductorLayer {
@@ -108,6 +124,7 @@ impl ConductorLayer {
                 side_tangent: None,
                 dielectric_constant: None,
             },
+            gate_props: GateProperties::default(),
             rho_vs_width_spacing: None,
             rho_vs_si_width_thickness: None,
             etch_vs_width_spacing: None,
@@ -118,6 +135,7 @@ impl ConductorLayer {
             resistive_only_etch: None,
             capacitive_only_etch: None,
             z_position: 0.0,
+            extra_properties: HashMap::new(),
         }
     }
 
@@ -142,12 +160,30 @@ impl ConductorLayer {
         self
     }
 
+    pub fn with_gate_layer_type(mut self, layer_type: String) -> Self {
+        self.gate_props.layer_type = Some(layer_type);
+        self
+    }
+
     pub fn get_layer_type(&self) -> LayerType {
-        LayerType::Conductor
+        if self.is_gate() {
+            LayerType::Gate
+        } else {
+            LayerType::Conductor
+        }
+    }
+
+    pub fn is_gate(&self) -> bool {
+        self.gate_props.is_gate()
+    }
+
+    pub fn thickness_um(&self) -> Micrometers {
+        Micrometers(self.thickness)
     }
 
+    /// Top of the layer, at its bottom for markers
     pub fn get_top_z(&self) -> f64 {
-        self.z_position + self.thickness
+        self.z_position + self.thickness.max(0.0)
     }
 
     pub fn get_bottom_z(&self) -> f64 {
@@ -158,6 +194,20 @@ impl ConductorLayer {
         self.physical_props.side_tangent.is_some()
     }
 
+    /// RPSQ as a typed sheet resistance
+    pub fn get_sheet_resistance(&self) -> Option<OhmsPerSquare> {
+        self.electrical_props.rpsq.map(OhmsPerSquare)
+    }
+
+    /// Bulk resistivity implied by RPSQ and the drawn thickness, ρ = RPSQ × THICKNESS
+    ///
+    /// This is a derived value; RPSQ stays the quantity given by the techfile.
+    pub fn get_effective_resistivity(&self) -> Option<OhmMicrometers> {
+        self.get_sheet_resistance()
+            .filter(|_| self.thickness > 0.0)
+            .map(|rpsq| rpsq.resistivity(self.thickness_um()))
+    }
+
     pub fn get_trapezoid_angle(&self) -> f64 {
         self.physical_props
             .side_tangent
@@ -165,6 +215,10 @@ impl ConductorLayer {
             .unwrap_or(0.0)
     }
 
+    /// Resistance in ohm of a wire of `width` and `length` in um at `temperature` in °C
+    ///
+    /// None without resistivity data; [`crate::analysis::calculate_resistance`] reports why
+    /// and the values the resistance was derived from.
     pub fn calculate_resistance(
         &self,
         width: f64,
@@ -172,105 +226,42 @@ impl ConductorLayer {
         temperature: f64,
         reference_temp: f64,
     ) -> Option<f64> {
-        println!("=== Resistance Calculation Debug ===");
-        println!("Layer: {}", self.name);
-        println!(
-            "Width: {:.6} um, Length: {:.6} um, Thickness: {:.6} um",
-            width, length, self.thickness
-        );
-        println!("Temperature: {temperature:.2}°C, Reference: {reference_temp:.2}°C");
-
-        // Try to get RHO from different tables in priority order
-        let (base_rho, rho_source) = if let Some(table) = &self.rho_vs_si_width_thickness {
-            if let Some(rho) = table.lookup(width, self.thickness) {
-                println!("Using RHO_VS_SI_WIDTH_AND_THICKNESS table lookup");
-                println!("  Found rho = {rho:.6e} ohm*um (volume resistivity)");
-                (rho, "RHO_VS_SI_WIDTH_AND_THICKNESS")
-            } else {
-                println!("RHO_VS_SI_WIDTH_AND_THICKNESS table lookup failed");
-                return None;
-            }
-        } else if let Some(table) = &self.rho_vs_width_spacing {
-            if let Some(rho) = table.lookup(width, 0.0) {
-                println!("Using RHO_VS_WIDTH_SPACING table lookup");
-                println!("  Found rho = {rho:.6e} ohm/sq (sheet resistance)");
-                (rho, "RHO_VS_WIDTH_SPACING")
-            } else {
-                println!("RHO_VS_WIDTH_SPACING table lookup failed");
-                return None;
-            }
-        } else if let Some(rpsq) = self.electrical_props.rpsq {
-            println!("Using fixed RPSQ value");
-            println!("  RPSQ = {rpsq:.6e} ohm/sq");
-            (rpsq, "RPSQ")
-        } else {
-            println!("No resistivity data available");
-            return None;
-        };
-
-        // Get CRT values from CRT_VS_SI_WIDTH table if available, otherwise use fixed values
-        let (crt1, crt2) = if let Some(crt_table) = &self.crt_vs_si_width {
-            if let Some((c1, c2)) = crt_table.lookup_crt_values(width) {
-                println!("Using CRT_VS_SI_WIDTH table lookup");
-                println!("  Interpolated CRT1 = {c1:.6e} /°C, CRT2 = {c2:.6e} /°C²");
-                (c1, c2)
-            } else {
-                let c1 = self.electrical_props.crt1.unwrap_or(0.0);
-                let c2 = self.electrical_props.crt2.unwrap_or(0.0);
-                println!("CRT_VS_SI_WIDTH lookup failed, using fixed values");
-                println!("  Fixed CRT1 = {c1:.6e} /°C, CRT2 = {c2:.6e} /°C²");
-                (c1, c2)
-            }
-        } else {
-            let c1 = self.electrical_props.crt1.unwrap_or(0.0);
-            let c2 = self.electrical_props.crt2.unwrap_or(0.0);
-            println!("Using fixed CRT values");
-            println!("  Fixed CRT1 = {c1:.6e} /°C, CRT2 = {c2:.6e} /°C²");
-            (c1, c2)
-        };
-
-        let temp_diff = temperature - reference_temp;
-        let temp_coefficient = crt1 * temp_diff + crt2 * temp_diff.powi(2);
-        println!("Temperature coefficient calculation:");
-        println!("  ΔT = {temp_diff:.2}°C");
-        println!("  Temp coefficient = CRT1*ΔT + CRT2*ΔT² = {temp_coefficient:.6e}");
-
-        let temp_adjusted_rho = base_rho * (1.0 + temp_coefficient);
-        println!("Temperature adjusted resistivity:");
-        println!("  ρ(T) = ρ₀ * (1 + temp_coeff) = {temp_adjusted_rho:.6e}");
-
-        // Calculate resistance based on resistivity type
-        let resistance = if rho_source == "RHO_VS_SI_WIDTH_AND_THICKNESS" {
-            // Volume resistivity formula: R = ρ * L / (W * T)
-            let r = temp_adjusted_rho * length / (width * self.thickness);
-            println!("Using volume resistivity formula:");
-            println!(
-                "  R = ρ * L / (W * T) = {:.6e} * {:.6} / ({:.6} * {:.6}) = {:.6e} Ω",
-                temp_adjusted_rho, length, width, self.thickness, r
-            );
-            r
-        } else {
-            // Sheet resistance formula: R = Rsq * L / W
-            let r = temp_adjusted_rho * length / width;
-            println!("Using sheet resistance formula:");
-            println!("  R = Rsq * L / W = {temp_adjusted_rho:.6e} * {length:.6} / {width:.6} = {r:.6e} Ω");
-            r
-        };
-
-        println!("Final resistance: {resistance:.6e} Ω");
-        println!("==============================");
+        analysis::calculate_resistance(
+            self,
+            &analysis::WireGeometry::new(width, length),
+            &analysis::OperatingConditions::new(temperature, reference_temp),
+        )
+        .ok()
+        .map(|result| result.resistance)
+    }
 
-        Some(resistance)
+    /// List the table lookups `calculate_resistance` would clamp for the given width
+    pub fn check_resistance_table_ranges(&self, width: f64) -> Vec<TableRangeWarning> {
+        analysis::table_range_warnings(self, width, self.thickness)
     }
 
     pub fn get_effective_width(&self, nominal_width: f64, spacing: f64) -> f64 {
-        let etch_bias = self
-            .etch_vs_width_spacing
+        self.silicon_width(nominal_width, spacing)
+    }
+
+    /// Etch per line edge from ETCH_VS_WIDTH_AND_SPACING, 0 without a table
+    ///
+    /// Positive values shrink the line and widen the gap to its neighbours.
+    pub fn etch_bias(&self, drawn_width: f64, spacing: f64) -> f64 {
+        self.etch_vs_width_spacing
             .as_ref()
-            .and_then(|table| table.lookup(nominal_width, spacing))
-            .unwrap_or(0.0);
+            .and_then(|table| table.lookup(drawn_width, spacing))
+            .unwrap_or(0.0)
+    }
+
+    /// Width on silicon of a line drawn `drawn_width` wide at `spacing` to its neighbours
+    pub fn silicon_width(&self, drawn_width: f64, spacing: f64) -> f64 {
+        (drawn_width - 2.0 * self.etch_bias(drawn_width, spacing)).max(0.0)
+    }
 
-        (nominal_width - 2.0 * etch_bias).max(0.0)
+    /// Gap on silicon next to a line drawn `drawn_width` wide at `spacing`
+    pub fn silicon_spacing(&self, drawn_width: f64, spacing: f64) -> f64 {
+        (spacing + 2.0 * self.etch_bias(drawn_width, spacing)).max(0.0)
     }
 }
 
@@ -288,6 +279,13 @@ impl Layer {
         }
     }
 
+    pub fn extra_properties(&self) -> &HashMap<String, PropValue> {
+        match self {
+            Layer::Dielectric(layer) => &layer.extra_properties,
+            Layer::Conductor(layer) => &layer.extra_properties,
+        }
+    }
+
     pub fn thickness(&self) -> f64 {
         match self {
             Layer::Dielectric(layer) => layer.thickness,
@@ -295,6 +293,21 @@ impl Layer {
         }
     }
 
+    pub fn thickness_um(&self) -> Micrometers {
+        Micrometers(self.thickness())
+    }
+
+    /// Whether the layer is a marker: a zero or negative thickness sentinel that marks a
+    /// boundary instead of adding material
+    pub fn is_marker(&self) -> bool {
+        self.thickness() <= 0.0
+    }
+
+    /// Height the layer adds to the stack, zero for markers
+    pub fn stacked_thickness(&self) -> f64 {
+        self.thickness().max(0.0)
+    }
+
     pub fn z_position(&self) -> f64 {
         match self {
             Layer::Dielectric(layer) => layer.z_position,
@@ -309,6 +322,23 @@ impl Layer {
         }
     }
 
+    pub fn set_thickness(&mut self, thickness: f64) {
+        match self {
+            Layer::Dielectric(layer) => layer.thickness = thickness,
+            Layer::Conductor(layer) => {
+                layer.thickness = thickness;
+                layer.physical_props.thickness = thickness;
+            }
+        }
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        match self {
+            Layer::Dielectric(layer) => layer.name = name,
+            Layer::Conductor(layer) => layer.name = name,
+        }
+    }
+
     pub fn get_top_z(&self) -> f64 {
         match self {
             Layer::Dielectric(layer) => layer.get_top_z(),
@@ -326,7 +356,7 @@ impl Layer {
     pub fn layer_type(&self) -> LayerType {
         match self {
             Layer::Dielectric(_) => LayerType::Dielectric,
-            Layer::Conductor(_) => LayerType::Conductor,
+            Layer::Conductor(layer) => layer.get_layer_type(),
         }
     }
 
@@ -334,6 +364,10 @@ impl Layer {
         matches!(self, Layer::Conductor(_))
     }
 
+    pub fn is_gate(&self) -> bool {
+        matches!(self, Layer::Conductor(layer) if layer.is_gate())
+    }
+
     pub fn is_dielectric(&self) -> bool {
         matches!(self, Layer::Dielectric(_))
     }
@@ -378,6 +412,28 @@ mod tests {
         assert_relative_eq!(layer.get_trapezoid_angle(), 0.1_f64.atan(), epsilon = 1e-10);
     }
 
+    #[test]
+    fn test_gate_layer_classification() {
+        let metal = ConductorLayer::new("metal1".to_string(), 0.2);
+        assert_eq!(metal.get_layer_type(), LayerType::Conductor);
+
+        let gate = ConductorLayer::new("n_gpoly".to_string(), 0.04)
+            .with_gate_layer_type("GATE".to_string());
+        assert!(gate.is_gate());
+        assert_eq!(gate.get_layer_type(), LayerType::Gate);
+
+        let diffusion = ConductorLayer::new("NOD".to_string(), 0.06)
+            .with_gate_layer_type("DIFFUSION".to_string());
+        assert!(!diffusion.is_gate());
+
+        let mut poly = ConductorLayer::new("npoly".to_string(), 0.08);
+        poly.gate_props.gate_to_contact_smin = Some(0.04);
+        let layer = Layer::Conductor(Box::new(poly));
+        assert!(layer.is_gate());
+        assert!(layer.is_conductor());
+        assert_eq!(layer.layer_type(), LayerType::Gate);
+    }
+
     #[test]
     fn test_layer_enum() {
         let dielectric = Layer::Dielectric(DielectricLayer::new("test".to_string(), 1.0, 4.2));
@@ -406,6 +462,25 @@ mod tests {
         assert!(r > 0.0);
     }
 
+    #[test]
+    fn test_effective_resistivity() {
+        let mut layer = ConductorLayer::new("metal1".to_string(), 0.2);
+        assert_eq!(layer.get_effective_resistivity(), None);
+
+        layer.electrical_props.rpsq = Some(0.1);
+        let rho = layer.get_effective_resistivity().unwrap();
+        assert_relative_eq!(rho.ohm_micrometers(), 0.02);
+        // The derived resistivity gives back the given RPSQ
+        assert_relative_eq!(
+            OhmsPerSquare::from_resistivity(rho.ohm_micrometers(), layer.thickness_um())
+                .ohms_per_square(),
+            0.1
+        );
+
+        layer.thickness = 0.0;
+        assert_eq!(layer.get_effective_resistivity(), None);
+    }
+
     #[test]
     fn test_effective_width_calculation() {
         let mut layer = ConductorLayer::new("metal1".to_string(), 0.2);
@@ -420,4 +495,57 @@ mod tests {
         let effective_width = layer.get_effective_width(0.2, 0.1);
         assert_relative_eq!(effective_width, 0.2 - 2.0 * 0.015, epsilon = 1e-10);
     }
+
+    #[test]
+    fn test_silicon_width() {
+        let mut layer = ConductorLayer::new("metal1".to_string(), 0.2);
+        assert_relative_eq!(layer.silicon_width(0.1, 0.2), 0.1);
+        assert_relative_eq!(layer.silicon_spacing(0.1, 0.2), 0.2);
+
+        layer.etch_vs_width_spacing = Some(LookupTable2D::new(
+            vec![0.1, 0.2],
+            vec![0.1, 0.2],
+            vec![vec![0.01, 0.015], vec![0.005, 0.01]],
+        ));
+        assert_relative_eq!(layer.etch_bias(0.1, 0.2), 0.005, epsilon = 1e-10);
+        assert_relative_eq!(layer.silicon_width(0.1, 0.2), 0.09, epsilon = 1e-10);
+        assert_relative_eq!(layer.silicon_spacing(0.1, 0.2), 0.21, epsilon = 1e-10);
+
+        // Drawn plus spaced pitch is preserved on silicon
+        let (w, s) = (0.15, 0.12);
+        assert_relative_eq!(
+            layer.silicon_width(w, s) + layer.silicon_spacing(w, s),
+            w + s,
+            epsilon = 1e-10
+        );
+
+        // An etch wider than half the line leaves nothing
+        assert_eq!(layer.silicon_width(0.01, 0.1), 0.0);
+    }
+
+    #[test]
+    fn test_resistance_table_range_warnings() {
+        let mut layer = ConductorLayer::new("metal1".to_string(), 0.2);
+        assert!(layer.check_resistance_table_ranges(0.01).is_empty());
+
+        layer.rho_vs_si_width_thickness = Some(LookupTable2D::new(
+            vec![0.05, 0.1, 1.0],
+            vec![0.1, 0.3],
+            vec![vec![0.02, 0.019, 0.018], vec![0.021, 0.02, 0.019]],
+        ));
+        assert!(layer.check_resistance_table_ranges(0.5).is_empty());
+
+        let warnings = layer.check_resistance_table_ranges(2.0);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].axis, "Width");
+        assert_eq!(warnings[0].clamped, 1.0);
+
+        layer.thickness = 0.05;
+        let warnings = layer.check_resistance_table_ranges(0.01);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].clamped, 0.05);
+        assert_eq!(warnings[1].axis, "Thickness");
+        assert_eq!(warnings[1].clamped, 0.1);
+        assert!(warnings[1].to_string().contains("clamped to 0.1000"));
+    }
 }
@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{layer::Layer, stack::ProcessStack};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Conductors thinner than this in um are taken for barriers or liners
+pub const BARRIER_MAX_THICKNESS: f64 = 0.02;
+/// Dielectrics up to this thick in um with a high ER are taken for metal caps
+pub const CAP_MAX_THICKNESS: f64 = 0.1;
+/// Lowest ER of a capping dielectric such as SiN or SiCN
+pub const CAP_MIN_ER: f64 = 5.0;
+
+/// Role of a layer in the process, finer grained than dielectric or conductor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LayerClass {
+    SignalMetal,
+    Gate,
+    BarrierLiner,
+    Seed,
+    Dielectric,
+    Capping,
+    EtchStop,
+    Passivation,
+    Substrate,
+}
+
+impl LayerClass {
+    pub const ALL: [LayerClass; 9] = [
+        LayerClass::SignalMetal,
+        LayerClass::Gate,
+        LayerClass::BarrierLiner,
+        LayerClass::Seed,
+        LayerClass::Dielectric,
+        LayerClass::Capping,
+        LayerClass::EtchStop,
+        LayerClass::Passivation,
+        LayerClass::Substrate,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LayerClass::SignalMetal => "Signal metal",
+            LayerClass::Gate => "Gate",
+            LayerClass::BarrierLiner => "Barrier/liner",
+            LayerClass::Seed => "Seed",
+            LayerClass::Dielectric => "Dielectric",
+            LayerClass::Capping => "Capping dielectric",
+            LayerClass::EtchStop => "Etch stop",
+            LayerClass::Passivation => "Passivation",
+            LayerClass::Substrate => "Substrate",
+        }
+    }
+
+    /// Identifier of the class in theme files
+    pub fn id(self) -> &'static str {
+        match self {
+            LayerClass::SignalMetal => "signal_metal",
+            LayerClass::Gate => "gate",
+            LayerClass::BarrierLiner => "barrier_liner",
+            LayerClass::Seed => "seed",
+            LayerClass::Dielectric => "dielectric",
+            LayerClass::Capping => "capping",
+            LayerClass::EtchStop => "etch_stop",
+            LayerClass::Passivation => "passivation",
+            LayerClass::Substrate => "substrate",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|class| class.id() == id)
+    }
+
+    pub fn is_conductor_class(self) -> bool {
+        matches!(
+            self,
+            LayerClass::SignalMetal
+                | LayerClass::Gate
+                | LayerClass::BarrierLiner
+                | LayerClass::Seed
+        )
+    }
+
+    /// Guess the class of a layer from its name, thickness and ER
+    ///
+    /// Name hints such as `barrier`, `TaN`, `seed`, `cap`, `esl` or `pass` win over the
+    /// thickness rules: conductors thinner than [`BARRIER_MAX_THICKNESS`] are barriers,
+    /// and thin dielectrics with an ER of at least [`CAP_MIN_ER`] are metal caps.
+    pub fn infer(layer: &Layer) -> Self {
+        let name = layer.name().to_lowercase();
+        let tokens: Vec<&str> = name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .collect();
+        let contains = |words: &[&str]| words.iter().any(|word| name.contains(word));
+        let token = |words: &[&str]| tokens.iter().any(|token| words.contains(token));
+
+        match layer {
+            Layer::Conductor(conductor) => {
+                let thin_film = conductor.gate_props.layer_type.is_none()
+                    && conductor.thickness > 0.0
+                    && conductor.thickness < BARRIER_MAX_THICKNESS;
+                if conductor.is_gate() {
+                    LayerClass::Gate
+                } else if contains(&["seed"]) {
+                    LayerClass::Seed
+                } else if contains(&["barrier", "liner"])
+                    || token(&["ta", "tan", "ti", "tin"])
+                    || thin_film
+                {
+                    LayerClass::BarrierLiner
+                } else {
+                    LayerClass::SignalMetal
+                }
+            }
+            Layer::Dielectric(dielectric) => {
+                let high_k_film = dielectric.thickness <= CAP_MAX_THICKNESS
+                    && dielectric.dielectric_constant >= CAP_MIN_ER;
+                if contains(&["substrate"]) {
+                    LayerClass::Substrate
+                } else if contains(&["pass"]) {
+                    LayerClass::Passivation
+                } else if contains(&["etch", "stop"]) || token(&["esl"]) {
+                    LayerClass::EtchStop
+                } else if contains(&["cap"]) || high_k_film {
+                    LayerClass::Capping
+                } else {
+                    LayerClass::Dielectric
+                }
+            }
+        }
+    }
+
+    /// Class of a layer, preferring the user's choice in `overrides` over the guess
+    pub fn of(layer: &Layer, overrides: &HashMap<String, LayerClass>) -> Self {
+        overrides
+            .get(layer.name())
+            .copied()
+            .unwrap_or_else(|| LayerClass::infer(layer))
+    }
+}
+
+impl ProcessStack {
+    /// Layer names by class in stack order, leaving out classes without layers
+    pub fn get_layers_by_class(
+        &self,
+        overrides: &HashMap<String, LayerClass>,
+    ) -> Vec<(LayerClass, Vec<&str>)> {
+        LayerClass::ALL
+            .iter()
+            .map(|&class| {
+                let names = self
+                    .layers
+                    .iter()
+                    .filter(|layer| LayerClass::of(layer, overrides) == class)
+                    .map(|layer| layer.name())
+                    .collect::<Vec<_>>();
+                (class, names)
+            })
+            .filter(|(_, names)| !names.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+
+    fn dielectric(name: &str, thickness: f64, er: f64) -> Layer {
+        Layer::Dielectric(DielectricLayer::new(name.to_string(), thickness, er))
+    }
+
+    fn conductor(name: &str, thickness: f64) -> Layer {
+        Layer::Conductor(Box::new(ConductorLayer::new(name.to_string(), thickness)))
+    }
+
+    #[test]
+    fn test_infer_layer_class() {
+        let cases = [
+            (conductor("metal1", 0.1), LayerClass::SignalMetal),
+            (conductor("m1_TaN", 0.1), LayerClass::BarrierLiner),
+            (conductor("cu_liner", 0.1), LayerClass::BarrierLiner),
+            (conductor("thin_film", 0.005), LayerClass::BarrierLiner),
+            (conductor("cu_seed", 0.01), LayerClass::Seed),
+            (conductor("titanium_top", 0.5), LayerClass::SignalMetal),
+            (dielectric("IMD2a", 0.0275, 5.69), LayerClass::Capping),
+            (dielectric("IMD2c", 0.155, 2.9), LayerClass::Dielectric),
+            (dielectric("tox", 0.0023, 4.5), LayerClass::Dielectric),
+            (dielectric("m1_cap", 0.2, 4.0), LayerClass::Capping),
+            (dielectric("esl_1", 0.05, 4.0), LayerClass::EtchStop),
+            (dielectric("PASS3", 0.064, 7.4), LayerClass::Passivation),
+            (dielectric("substrate", 1.0, 11.9), LayerClass::Substrate),
+        ];
+        for (layer, class) in &cases {
+            assert_eq!(LayerClass::infer(layer), *class, "{}", layer.name());
+        }
+
+        let gate = Layer::Conductor(Box::new(
+            ConductorLayer::new("poly".to_string(), 0.005).with_gate_layer_type("GATE".to_string()),
+        ));
+        assert_eq!(LayerClass::infer(&gate), LayerClass::Gate);
+    }
+
+    #[test]
+    fn test_layer_class_overrides() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("classes".to_string()));
+        stack.add_layer(dielectric("ild", 1.0, 4.2));
+        stack.add_layer(conductor("metal1", 0.2));
+        stack.add_layer(dielectric("sin", 0.05, 7.0));
+
+        let mut overrides = HashMap::new();
+        assert_eq!(
+            stack.get_layers_by_class(&overrides),
+            vec![
+                (LayerClass::SignalMetal, vec!["metal1"]),
+                (LayerClass::Dielectric, vec!["ild"]),
+                (LayerClass::Capping, vec!["sin"]),
+            ]
+        );
+
+        overrides.insert("sin".to_string(), LayerClass::EtchStop);
+        assert_eq!(
+            LayerClass::of(stack.get_layer("sin").unwrap(), &overrides),
+            LayerClass::EtchStop
+        );
+        assert_eq!(
+            stack.get_layers_by_class(&overrides)[2].0,
+            LayerClass::EtchStop
+        );
+    }
+}
@@ -0,0 +1,447 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    diagnostic::{ProblemTarget, Severity},
+    layer::{DielectricLayer, Layer},
+    stack::ProcessStack,
+    units::Micrometers,
+    via::ViaConnection,
+    via_span::VIA_SPAN_TOLERANCE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Relative permittivity range of real dielectrics, from vacuum to high-k films
+const DIELECTRIC_CONSTANT_RANGE: (f64, f64) = (1.0, 25.0);
+/// Largest SIDE_TANGENT magnitude of a plausible trapezoid
+const SIDE_TANGENT_LIMIT: f64 = 1.0;
+/// Thicker layers are usually a unit mistake (nm or Å entered as um)
+const THICKNESS_LIMIT: f64 = 50.0;
+
+/// Plausibility check on parameter values that parse fine but are likely mistakes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LintRule {
+    DielectricConstantRange,
+    SideTangentRange,
+    ExcessiveThickness,
+    ZeroSheetResistance,
+    ConformalThickness,
+    ViaDielectricSpan,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 6] = [
+        LintRule::DielectricConstantRange,
+        LintRule::SideTangentRange,
+        LintRule::ExcessiveThickness,
+        LintRule::ZeroSheetResistance,
+        LintRule::ConformalThickness,
+        LintRule::ViaDielectricSpan,
+    ];
+
+    /// Short name used on the command line and in reports
+    pub fn id(&self) -> &'static str {
+        match self {
+            LintRule::DielectricConstantRange => "er-range",
+            LintRule::SideTangentRange => "side-tangent-range",
+            LintRule::ExcessiveThickness => "thickness-limit",
+            LintRule::ZeroSheetResistance => "zero-rpsq",
+            LintRule::ConformalThickness => "conformal-thickness",
+            LintRule::ViaDielectricSpan => "via-span",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<LintRule> {
+        Self::ALL.into_iter().find(|rule| rule.id() == id)
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            LintRule::DielectricConstantRange => Severity::Warning,
+            LintRule::SideTangentRange => Severity::Warning,
+            LintRule::ExcessiveThickness => Severity::Info,
+            LintRule::ZeroSheetResistance => Severity::Warning,
+            LintRule::ConformalThickness => Severity::Warning,
+            LintRule::ViaDielectricSpan => Severity::Warning,
+        }
+    }
+
+    /// Why a value flagged by the rule is suspicious
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            LintRule::DielectricConstantRange => {
+                "ER below 1 is not physical and above 25 is beyond common high-k films; \
+                 check for a typo or a misplaced value."
+            }
+            LintRule::SideTangentRange => {
+                "SIDE_TANGENT is the tangent of the sidewall angle; beyond ±1 the sidewall \
+                 leans more than 45°, which usually means an angle was entered instead."
+            }
+            LintRule::ExcessiveThickness => {
+                "Layers thicker than 50 um are rare outside packaging stacks and often come \
+                 from a thickness given in nm or Å."
+            }
+            LintRule::ZeroSheetResistance => {
+                "An RPSQ of zero makes the conductor ideal, so every resistance derived \
+                 from it is zero; omit RPSQ or give the real value."
+            }
+            LintRule::ConformalThickness => {
+                "SW_T and TW_T are the sidewall and top thicknesses of a dielectric deposited \
+                 over a conductor; they cannot be negative, need a conductor below the layer \
+                 or named by MEASURED_FROM, and two sidewalls thicker than the conductor's \
+                 SMIN would fill the narrowest gaps."
+            }
+            LintRule::ViaDielectricSpan => {
+                "A via runs from the top of the lower layer it connects to the bottom of the \
+                 upper one, through the dielectrics in between; when the layers overlap or the \
+                 dielectrics do not fill that height, a thickness is likely wrong."
+            }
+        }
+    }
+}
+
+/// Which lint rules are enabled, every rule by default
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintConfig {
+    disabled: BTreeSet<LintRule>,
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only `rule` enabled
+    pub fn only(rule: LintRule) -> Self {
+        Self {
+            disabled: LintRule::ALL
+                .into_iter()
+                .filter(|&other| other != rule)
+                .collect(),
+        }
+    }
+
+    pub fn is_enabled(&self, rule: LintRule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+
+    pub fn set_enabled(&mut self, rule: LintRule, enabled: bool) {
+        if enabled {
+            self.disabled.remove(&rule);
+        } else {
+            self.disabled.insert(rule);
+        }
+    }
+
+    pub fn enabled_rules(&self) -> impl Iterator<Item = LintRule> + '_ {
+        LintRule::ALL
+            .into_iter()
+            .filter(|&rule| self.is_enabled(rule))
+    }
+}
+
+/// A suspicious value found by a lint rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    /// Layer or via the value belongs to
+    pub layer: String,
+    pub message: String,
+}
+
+impl LintFinding {
+    pub fn severity(&self) -> Severity {
+        self.rule.severity()
+    }
+
+    /// The layer or via of the finding and the line defining it
+    pub fn target(&self, stack: &ProcessStack) -> ProblemTarget {
+        ProblemTarget {
+            layer: Some(self.layer.clone()),
+            line: stack.get_source_line(&self.layer),
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}: {}",
+            self.severity().label(),
+            self.rule.id(),
+            self.layer,
+            self.message
+        )
+    }
+}
+
+impl ProcessStack {
+    /// Run the enabled lint rules over every layer in stack order, then every via
+    pub fn lint(&self, config: &LintConfig) -> Vec<LintFinding> {
+        let layers = self
+            .layers
+            .iter()
+            .flat_map(|layer| self.lint_layer(layer, config));
+        let vias = self
+            .via_stack
+            .iter()
+            .flat_map(|via| self.lint_via(via, config));
+        layers.chain(vias).collect()
+    }
+
+    /// Run the enabled via rules over one via of this stack
+    pub fn lint_via(&self, via: &ViaConnection, config: &LintConfig) -> Vec<LintFinding> {
+        let rule = LintRule::ViaDielectricSpan;
+        if !config.is_enabled(rule) {
+            return Vec::new();
+        }
+        check_via_span(self, via)
+            .map(|message| LintFinding {
+                rule,
+                layer: via.name.clone(),
+                message,
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Run the enabled lint rules over one layer of this stack
+    pub fn lint_layer(&self, layer: &Layer, config: &LintConfig) -> Vec<LintFinding> {
+        config
+            .enabled_rules()
+            .filter_map(|rule| {
+                check_layer(self, rule, layer).map(|message| LintFinding {
+                    rule,
+                    layer: layer.name().to_string(),
+                    message,
+                })
+            })
+            .collect()
+    }
+}
+
+fn check_layer(stack: &ProcessStack, rule: LintRule, layer: &Layer) -> Option<String> {
+    match (rule, layer) {
+        (LintRule::DielectricConstantRange, Layer::Dielectric(dielectric)) => {
+            let (min, max) = DIELECTRIC_CONSTANT_RANGE;
+            let er = dielectric.dielectric_constant;
+            (!(min..=max).contains(&er)).then(|| format!("ER {er} is outside [{min}, {max}]"))
+        }
+        (LintRule::SideTangentRange, Layer::Conductor(conductor)) => conductor
+            .physical_props
+            .side_tangent
+            .filter(|tangent| tangent.abs() > SIDE_TANGENT_LIMIT)
+            .map(|tangent| format!("SIDE_TANGENT {tangent} is beyond ±{SIDE_TANGENT_LIMIT}")),
+        (LintRule::ExcessiveThickness, layer) => (layer.thickness() > THICKNESS_LIMIT).then(|| {
+            // Read as nm, the value is what the author likely meant
+            format!(
+                "Thickness {} um exceeds {THICKNESS_LIMIT} um, as nm it would be {:.4}",
+                layer.thickness(),
+                Micrometers::from_nm(layer.thickness())
+            )
+        }),
+        (LintRule::ZeroSheetResistance, Layer::Conductor(conductor)) => {
+            (conductor.electrical_props.rpsq == Some(0.0)).then(|| "RPSQ is zero".to_string())
+        }
+        (LintRule::ConformalThickness, Layer::Dielectric(dielectric)) => {
+            check_conformal_thickness(stack, dielectric)
+        }
+        _ => None,
+    }
+}
+
+fn check_conformal_thickness(stack: &ProcessStack, dielectric: &DielectricLayer) -> Option<String> {
+    for (key, value) in [("SW_T", dielectric.sw_t), ("TW_T", dielectric.tw_t)] {
+        if let Some(value) = value.filter(|&value| value < 0.0) {
+            return Some(format!("{key} {value} is negative"));
+        }
+    }
+    if dielectric.sw_t.is_none() && dielectric.tw_t.is_none() {
+        return None;
+    }
+
+    let Some(conductor) = stack.get_liner_conductor(dielectric) else {
+        return Some("SW_T/TW_T set without a conductor below or in MEASURED_FROM".to_string());
+    };
+    let sidewall = dielectric.sw_t.unwrap_or(0.0);
+    conductor
+        .physical_props
+        .spacing_min
+        .filter(|&smin| 2.0 * sidewall > smin)
+        .map(|smin| {
+            format!(
+                "Sidewalls of SW_T {sidewall} fill the {smin} um SMIN of {}",
+                conductor.name
+            )
+        })
+}
+
+fn check_via_span(stack: &ProcessStack, via: &ViaConnection) -> Option<String> {
+    // Auto-created layers only stand in for missing ones, their thickness is made up
+    let is_auto_created = |name: &str| stack.get_layer(name).is_some_and(Layer::is_auto_created);
+    if is_auto_created(&via.from_layer) || is_auto_created(&via.to_layer) {
+        return None;
+    }
+
+    let span = stack.get_via_dielectric_span(via)?;
+    if span.is_consistent() {
+        None
+    } else if span.via_height().abs() <= VIA_SPAN_TOLERANCE {
+        Some(format!(
+            "{} and {} touch, leaving no height for the via",
+            via.from_layer, via.to_layer
+        ))
+    } else if span.via_height() < 0.0 {
+        Some(format!(
+            "{} and {} overlap by {:.4} um, leaving no height for the via",
+            via.from_layer,
+            via.to_layer,
+            -span.via_height()
+        ))
+    } else {
+        Some(format!(
+            "Dielectrics span {:.4} um but the via is {:.4} um high",
+            span.total_span(),
+            span.via_height()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+
+    #[test]
+    fn test_lint_rules() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("lint".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "passivation".to_string(),
+            60.0,
+            0.5,
+        )));
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.2).with_side_tangent(-1.5);
+        metal.electrical_props.rpsq = Some(0.0);
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide".to_string(),
+            1.0,
+            3.9,
+        )));
+
+        let mut config = LintConfig::new();
+        let findings = stack.lint(&config);
+        let rules: Vec<(&str, LintRule)> = findings
+            .iter()
+            .map(|finding| (finding.layer.as_str(), finding.rule))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("passivation", LintRule::DielectricConstantRange),
+                ("passivation", LintRule::ExcessiveThickness),
+                ("metal1", LintRule::SideTangentRange),
+                ("metal1", LintRule::ZeroSheetResistance),
+            ]
+        );
+        assert_eq!(
+            findings[0].to_string(),
+            "warning[er-range]: passivation: ER 0.5 is outside [1, 25]"
+        );
+        assert_eq!(
+            findings[1].message,
+            "Thickness 60 um exceeds 50 um, as nm it would be 0.0600 μm"
+        );
+
+        config.set_enabled(LintRule::ExcessiveThickness, false);
+        config.set_enabled(LintRule::ZeroSheetResistance, false);
+        assert_eq!(stack.lint(&config).len(), 2);
+        assert!(!config.is_enabled(LintRule::ExcessiveThickness));
+
+        assert_eq!(
+            LintRule::from_id("zero-rpsq"),
+            Some(LintRule::ZeroSheetResistance)
+        );
+        assert_eq!(LintRule::from_id("unknown"), None);
+    }
+
+    #[test]
+    fn test_conformal_thickness() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("lint".to_string()));
+        let mut negative = DielectricLayer::new("negative".to_string(), 0.1, 4.0);
+        negative.tw_t = Some(-0.01);
+        let mut thick = DielectricLayer::new("thick".to_string(), 0.1, 4.0);
+        thick.sw_t = Some(0.06);
+        let mut orphan = DielectricLayer::new("orphan".to_string(), 0.1, 4.0);
+        orphan.sw_t = Some(0.01);
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.2);
+        metal.physical_props.spacing_min = Some(0.1);
+
+        stack.add_layer(Layer::Dielectric(negative));
+        stack.add_layer(Layer::Dielectric(thick));
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+        stack.add_layer(Layer::Dielectric(orphan));
+
+        let messages: Vec<String> = stack
+            .lint(&LintConfig::only(LintRule::ConformalThickness))
+            .iter()
+            .map(|finding| format!("{}: {}", finding.layer, finding.message))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "negative: TW_T -0.01 is negative",
+                "thick: Sidewalls of SW_T 0.06 fill the 0.1 um SMIN of metal1",
+                "orphan: SW_T/TW_T set without a conductor below or in MEASURED_FROM",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_via_dielectric_span() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("lint".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd2".to_string(),
+            0.4,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.3,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd1".to_string(),
+            0.5,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.2,
+        ))));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            2.0,
+        ));
+        let config = LintConfig::only(LintRule::ViaDielectricSpan);
+        assert!(stack.lint(&config).is_empty());
+
+        // A metal1 thicker than imd1 runs into metal2
+        stack.set_layer_thickness("metal1", 0.6);
+        let findings = stack.lint(&config);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].to_string(),
+            "warning[via-span]: via1: metal1 and metal2 overlap by 0.1000 um, \
+             leaving no height for the via"
+        );
+        assert!(stack
+            .lint(&LintConfig::only(LintRule::ZeroSheetResistance))
+            .is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{layer::Layer, stack::ProcessStack, units::OhmMicrometers};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Whether a material is matched against conductors or dielectrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MaterialKind {
+    Conductor,
+    Dielectric,
+}
+
+/// Material recognized by its resistivity (conductors) or ER (dielectrics)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    pub name: String,
+    pub kind: MaterialKind,
+    /// Lowest matching resistivity in ohm·um, or ER, inclusive
+    pub min: f64,
+    /// Highest matching resistivity in ohm·um, or ER, exclusive
+    pub max: f64,
+    /// sRGB color of the material in legends and reports
+    pub color: [u8; 3],
+    /// Lowercase layer name tokens that select the material whatever the value
+    pub hints: Vec<String>,
+}
+
+impl Material {
+    pub fn new(name: &str, kind: MaterialKind, min: f64, max: f64, color: [u8; 3]) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            min,
+            max,
+            color,
+            hints: Vec::new(),
+        }
+    }
+
+    pub fn with_hints(mut self, hints: &[&str]) -> Self {
+        self.hints = hints.iter().map(|hint| hint.to_lowercase()).collect();
+        self
+    }
+
+    pub fn matches_value(&self, value: f64) -> bool {
+        value >= self.min && value < self.max
+    }
+}
+
+/// Resistivity of a conductor in ohm·um from its RPSQ and thickness
+pub fn conductor_resistivity(layer: &Layer) -> Option<f64> {
+    match layer {
+        Layer::Conductor(conductor) => conductor
+            .get_effective_resistivity()
+            .map(OhmMicrometers::ohm_micrometers)
+            .filter(|resistivity| *resistivity > 0.0),
+        Layer::Dielectric(_) => None,
+    }
+}
+
+/// Value a layer is matched by: resistivity in ohm·um for conductors, ER for dielectrics
+pub fn material_value(layer: &Layer) -> Option<f64> {
+    match layer {
+        Layer::Conductor(_) => conductor_resistivity(layer),
+        Layer::Dielectric(dielectric) => Some(dielectric.dielectric_constant),
+    }
+}
+
+/// Materials layers are tagged with, searched in order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaterialDatabase {
+    materials: Vec<Material>,
+}
+
+impl MaterialDatabase {
+    pub fn new() -> Self {
+        Self {
+            materials: Vec::new(),
+        }
+    }
+
+    /// Common BEOL metals and dielectrics
+    ///
+    /// Resistivity ranges allow for the size effects of narrow lines over the bulk values.
+    pub fn builtin() -> Self {
+        use MaterialKind::{Conductor, Dielectric};
+        Self::new()
+            .with_material(
+                Material::new("Cu", Conductor, 0.017, 0.027, [184, 115, 51])
+                    .with_hints(&["cu", "copper"]),
+            )
+            .with_material(
+                Material::new("Al", Conductor, 0.027, 0.045, [176, 180, 188])
+                    .with_hints(&["al", "alu", "alucap", "aluminum"]),
+            )
+            .with_material(
+                Material::new("W", Conductor, 0.045, 0.2, [110, 114, 130])
+                    .with_hints(&["w", "tungsten"]),
+            )
+            .with_material(
+                Material::new("SiN", Dielectric, 6.0, 8.0, [120, 170, 120])
+                    .with_hints(&["sin", "nitride", "sicn"]),
+            )
+            .with_material(
+                Material::new("SiO2", Dielectric, 3.9, 4.4, [200, 220, 240])
+                    .with_hints(&["sio2", "oxide", "teos", "usg"]),
+            )
+            .with_material(
+                Material::new("FSG", Dielectric, 3.4, 3.9, [180, 210, 235]).with_hints(&["fsg"]),
+            )
+            .with_material(
+                Material::new("SiCOH", Dielectric, 2.7, 3.4, [160, 195, 225])
+                    .with_hints(&["sicoh", "lowk"]),
+            )
+            .with_material(
+                Material::new("ULK", Dielectric, 2.0, 2.7, [140, 180, 215])
+                    .with_hints(&["ulk", "elk"]),
+            )
+    }
+
+    /// Add a material, replacing one of the same name
+    pub fn with_material(mut self, material: Material) -> Self {
+        match self
+            .materials
+            .iter_mut()
+            .find(|existing| existing.name == material.name)
+        {
+            Some(existing) => *existing = material,
+            None => self.materials.push(material),
+        }
+        self
+    }
+
+    pub fn get_materials(&self) -> &[Material] {
+        &self.materials
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Material> {
+        self.materials.iter().find(|material| material.name == name)
+    }
+
+    /// Materials that can be assigned to a layer of this kind
+    pub fn materials_for(&self, layer: &Layer) -> impl Iterator<Item = &Material> {
+        let kind = kind_of(layer);
+        self.materials
+            .iter()
+            .filter(move |material| material.kind == kind)
+    }
+
+    /// Guess the material of a layer from its name, else its resistivity or ER
+    pub fn infer(&self, layer: &Layer) -> Option<&Material> {
+        let name = layer.name().to_lowercase();
+        let tokens: Vec<&str> = name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .collect();
+        let by_name = self.materials_for(layer).find(|material| {
+            material
+                .hints
+                .iter()
+                .any(|hint| tokens.contains(&hint.as_str()))
+        });
+        by_name.or_else(|| {
+            let value = material_value(layer)?;
+            self.materials_for(layer)
+                .find(|material| material.matches_value(value))
+        })
+    }
+
+    /// Material of a layer, preferring the user's choice in `overrides` over the guess
+    pub fn material_of(
+        &self,
+        layer: &Layer,
+        overrides: &HashMap<String, String>,
+    ) -> Option<&Material> {
+        overrides
+            .get(layer.name())
+            .and_then(|name| self.get(name))
+            .or_else(|| self.infer(layer))
+    }
+}
+
+impl Default for MaterialDatabase {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+fn kind_of(layer: &Layer) -> MaterialKind {
+    match layer {
+        Layer::Conductor(_) => MaterialKind::Conductor,
+        Layer::Dielectric(_) => MaterialKind::Dielectric,
+    }
+}
+
+/// Material tagged on one layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerMaterial {
+    pub layer_name: String,
+    /// None when nothing in the database matches
+    pub material: Option<Material>,
+    /// Whether the user picked the material rather than it being inferred
+    pub user_set: bool,
+    /// Resistivity in ohm·um or ER the layer was matched by
+    pub value: Option<f64>,
+}
+
+impl ProcessStack {
+    /// Material of every layer in stack order
+    pub fn get_layer_materials(
+        &self,
+        database: &MaterialDatabase,
+        overrides: &HashMap<String, String>,
+    ) -> Vec<LayerMaterial> {
+        self.layers
+            .iter()
+            .map(|layer| LayerMaterial {
+                layer_name: layer.name().to_string(),
+                material: database.material_of(layer, overrides).cloned(),
+                user_set: overrides
+                    .get(layer.name())
+                    .is_some_and(|name| database.get(name).is_some()),
+                value: material_value(layer),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+
+    fn conductor(name: &str, thickness: f64, rpsq: f64) -> Layer {
+        let mut conductor = ConductorLayer::new(name.to_string(), thickness);
+        conductor.electrical_props.rpsq = Some(rpsq);
+        Layer::Conductor(Box::new(conductor))
+    }
+
+    fn dielectric(name: &str, er: f64) -> Layer {
+        Layer::Dielectric(DielectricLayer::new(name.to_string(), 0.2, er))
+    }
+
+    #[test]
+    fn test_infer_material() {
+        let database = MaterialDatabase::builtin();
+        let cases = [
+            (conductor("metal1", 0.2, 0.1), Some("Cu")),
+            (conductor("alucap", 1.0, 0.1), Some("Al")),
+            (conductor("rdl", 3.0, 0.01), Some("Al")),
+            (conductor("contact", 0.1, 1.0), Some("W")),
+            (conductor("poly", 0.1, 8.0), None),
+            (dielectric("imd", 4.1), Some("SiO2")),
+            (dielectric("imd2", 2.9), Some("SiCOH")),
+            (dielectric("imd3", 2.4), Some("ULK")),
+            (dielectric("pass2", 7.0), Some("SiN")),
+            (dielectric("cap_sin", 5.0), Some("SiN")),
+            (dielectric("gox", 25.0), None),
+        ];
+        for (layer, material) in &cases {
+            assert_eq!(
+                database.infer(layer).map(|material| material.name.as_str()),
+                *material,
+                "{}",
+                layer.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_material_overrides() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("materials".to_string()));
+        stack.add_layer(dielectric("imd", 4.1));
+        stack.add_layer(conductor("metal1", 0.2, 0.1));
+
+        // Plugging in a material makes it available for overrides
+        let database = MaterialDatabase::builtin().with_material(Material::new(
+            "Co",
+            MaterialKind::Conductor,
+            0.06,
+            0.1,
+            [80, 90, 160],
+        ));
+        let mut overrides = HashMap::new();
+        overrides.insert("metal1".to_string(), "Co".to_string());
+        overrides.insert("imd".to_string(), "Unobtainium".to_string());
+
+        let materials = stack.get_layer_materials(&database, &overrides);
+        assert_eq!(materials[0].material.as_ref().unwrap().name, "SiO2");
+        assert!(!materials[0].user_set);
+        assert_eq!(materials[1].material.as_ref().unwrap().name, "Co");
+        assert!(materials[1].user_set);
+        assert!((materials[1].value.unwrap() - 0.02).abs() < 1e-12);
+    }
+}
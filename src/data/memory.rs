@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    layer::{ConductorLayer, Layer},
+    properties::{CrtVsSiWidthTable, LookupTable2D, ProcessVariation, PropValue},
+    stack::ProcessStack,
+};
+use std::collections::HashMap;
+
+/// Approximate memory held by one layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerMemoryUsage {
+    pub layer_name: String,
+    /// Bytes of the layer without its lookup tables
+    pub geometry_bytes: usize,
+    /// Bytes of the lookup tables and process variation polynomials
+    pub table_bytes: usize,
+    pub table_count: usize,
+}
+
+/// Approximate memory held by a parsed stack, counting the values but not allocator overhead
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackMemoryUsage {
+    pub layers: Vec<LayerMemoryUsage>,
+    /// Bytes of the vias and the diagnostics
+    pub other_bytes: usize,
+}
+
+impl StackMemoryUsage {
+    pub fn table_bytes(&self) -> usize {
+        self.layers.iter().map(|layer| layer.table_bytes).sum()
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.layers
+            .iter()
+            .map(|layer| layer.geometry_bytes + layer.table_bytes)
+            .sum::<usize>()
+            + self.other_bytes
+    }
+}
+
+/// Bytes of the elements, which `size_of_val` on the `Vec` itself would miss
+fn vec_bytes<T>(values: &[T]) -> usize {
+    size_of_val(values)
+}
+
+fn lookup_table_bytes(table: &LookupTable2D) -> usize {
+    vec_bytes(&table.widths)
+        + vec_bytes(&table.spacings)
+        + table
+            .values
+            .iter()
+            .map(|row| size_of::<Vec<f64>>() + vec_bytes(row))
+            .sum::<usize>()
+}
+
+fn crt_table_bytes(table: &CrtVsSiWidthTable) -> usize {
+    vec_bytes(&table.widths) + vec_bytes(&table.crt1_values) + vec_bytes(&table.crt2_values)
+}
+
+fn process_variation_bytes(variation: &ProcessVariation) -> usize {
+    vec_bytes(&variation.density_polynomial_orders)
+        + vec_bytes(&variation.width_polynomial_orders)
+        + vec_bytes(&variation.width_ranges)
+        + variation
+            .polynomial_coefficients
+            .iter()
+            .map(|row| size_of::<Vec<f64>>() + vec_bytes(row))
+            .sum::<usize>()
+}
+
+fn properties_bytes(properties: &HashMap<String, PropValue>) -> usize {
+    properties
+        .iter()
+        .map(|(key, value)| {
+            let text = match value {
+                PropValue::Text(text) => text.len(),
+                PropValue::Number(_) => 0,
+            };
+            size_of::<(String, PropValue)>() + key.len() + text
+        })
+        .sum()
+}
+
+impl ConductorLayer {
+    /// Lookup tables as (count, bytes)
+    fn table_usage(&self) -> (usize, usize) {
+        let tables = [
+            &self.rho_vs_width_spacing,
+            &self.rho_vs_si_width_thickness,
+            &self.etch_vs_width_spacing,
+            &self.etch_from_top,
+            &self.thickness_vs_width_spacing,
+        ];
+        let (mut count, mut bytes) = tables
+            .iter()
+            .filter_map(|table| table.as_ref())
+            .fold((0, 0), |(count, bytes), table| {
+                (count + 1, bytes + lookup_table_bytes(table))
+            });
+        if let Some(table) = &self.crt_vs_si_width {
+            count += 1;
+            bytes += crt_table_bytes(table);
+        }
+        if let Some(variation) = &self.process_variation {
+            count += 1;
+            bytes += process_variation_bytes(variation);
+        }
+        (count, bytes)
+    }
+
+    /// Drop the lookup tables, returning the approximate bytes freed
+    ///
+    /// Thickness, positions and the scalar properties stay, so the layer still draws as before.
+    pub fn unload_tables(&mut self) -> usize {
+        let (_, bytes) = self.table_usage();
+        self.rho_vs_width_spacing = None;
+        self.rho_vs_si_width_thickness = None;
+        self.etch_vs_width_spacing = None;
+        self.etch_from_top = None;
+        self.thickness_vs_width_spacing = None;
+        self.crt_vs_si_width = None;
+        self.process_variation = None;
+        bytes
+    }
+}
+
+impl ProcessStack {
+    /// Approximate memory of every layer in stack order
+    pub fn memory_usage(&self) -> StackMemoryUsage {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let (table_count, table_bytes, extra_properties) = match layer {
+                    Layer::Conductor(conductor) => {
+                        let (count, bytes) = conductor.table_usage();
+                        (
+                            count,
+                            bytes,
+                            size_of::<ConductorLayer>()
+                                + properties_bytes(&conductor.extra_properties),
+                        )
+                    }
+                    Layer::Dielectric(dielectric) => (
+                        0,
+                        0,
+                        dielectric.measured_from.as_ref().map_or(0, String::len)
+                            + properties_bytes(&dielectric.extra_properties),
+                    ),
+                };
+                LayerMemoryUsage {
+                    layer_name: layer.name().to_string(),
+                    geometry_bytes: size_of::<Layer>() + layer.name().len() + extra_properties,
+                    table_bytes,
+                    table_count,
+                }
+            })
+            .collect();
+        let vias: usize = self
+            .via_stack
+            .iter()
+            .map(|via| {
+                size_of_val(via)
+                    + via.name.len()
+                    + via.from_layer.len()
+                    + via.to_layer.len()
+                    + properties_bytes(&via.extra_properties)
+            })
+            .sum();
+        let diagnostics: usize = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| size_of_val(diagnostic) + diagnostic.message.len())
+            .sum();
+        StackMemoryUsage {
+            layers,
+            other_bytes: vias + diagnostics,
+        }
+    }
+
+    /// Drop the lookup tables of every conductor, returning the approximate bytes freed
+    pub fn unload_tables(&mut self) -> usize {
+        self.layers
+            .iter_mut()
+            .map(|layer| match layer {
+                Layer::Conductor(conductor) => conductor.unload_tables(),
+                Layer::Dielectric(_) => 0,
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, TechnologyInfo};
+
+    #[test]
+    fn test_memory_usage_and_unload() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("memory".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd".to_string(),
+            0.5,
+            4.0,
+        )));
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.2);
+        metal.electrical_props.rpsq = Some(0.1);
+        metal.rho_vs_width_spacing = Some(LookupTable2D::new(
+            vec![0.1, 0.2],
+            vec![0.1, 0.2, 0.3],
+            vec![vec![0.1; 3], vec![0.1; 3]],
+        ));
+        metal.crt_vs_si_width = Some(CrtVsSiWidthTable::new(vec![0.1], vec![3.0e-3], vec![0.0]));
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+
+        let usage = stack.memory_usage();
+        assert_eq!(usage.layers[0].table_bytes, 0);
+        assert_eq!(usage.layers[1].table_count, 2);
+        // 11 values of the 2D table, 2 row headers and 3 CRT values
+        let expected = 11 * 8 + 2 * size_of::<Vec<f64>>() + 3 * 8;
+        assert_eq!(usage.table_bytes(), expected);
+        assert!(usage.total_bytes() > usage.table_bytes());
+
+        assert_eq!(stack.unload_tables(), expected);
+        assert_eq!(stack.memory_usage().table_bytes(), 0);
+        let Some(Layer::Conductor(metal)) = stack.get_layer("metal1") else {
+            panic!("metal1 is a conductor");
+        };
+        assert_eq!(metal.thickness, 0.2);
+        assert_eq!(metal.electrical_props.rpsq, Some(0.1));
+    }
+}
@@ -0,0 +1,560 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    er_region::ErRegion,
+    layer::{ConductorLayer, DielectricLayer, Layer},
+    properties::PropValue,
+    stack::ProcessStack,
+    via::{ViaConnection, ViaStack},
+};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A property the overlay set to a different value than the base
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// Layer or via name, region label, or `TECHNOLOGY` for header values
+    pub item: String,
+    pub property: String,
+    pub base_value: String,
+    pub overlay_value: String,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} -> {}",
+            self.item, self.property, self.base_value, self.overlay_value
+        )
+    }
+}
+
+/// Outcome of [`ProcessStack::merge`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    pub conflicts: Vec<MergeConflict>,
+    pub added_layers: Vec<String>,
+    pub added_vias: Vec<String>,
+    /// Labels of the substrate and field regions only the overlay has
+    pub added_regions: Vec<String>,
+}
+
+impl MergeReport {
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// Collects conflicts while overriding the values of one item
+struct Merger<'a> {
+    item: &'a str,
+    conflicts: &'a mut Vec<MergeConflict>,
+}
+
+impl Merger<'_> {
+    fn set<T: PartialEq + Clone>(
+        &mut self,
+        property: &str,
+        base: &mut T,
+        overlay: &T,
+        describe: impl Fn(&T) -> String,
+    ) {
+        if base != overlay {
+            self.conflicts.push(MergeConflict {
+                item: self.item.to_string(),
+                property: property.to_string(),
+                base_value: describe(base),
+                overlay_value: describe(overlay),
+            });
+            *base = overlay.clone();
+        }
+    }
+
+    /// Override when the overlay has a value; a value missing from the base is not a conflict
+    fn option<T: PartialEq + Clone>(
+        &mut self,
+        property: &str,
+        base: &mut Option<T>,
+        overlay: &Option<T>,
+        describe: impl Fn(&T) -> String,
+    ) {
+        match (base.as_mut(), overlay) {
+            (Some(base), Some(overlay)) => self.set(property, base, overlay, describe),
+            (None, Some(overlay)) => *base = Some(overlay.clone()),
+            (_, None) => {}
+        }
+    }
+
+    fn number(&mut self, property: &str, base: &mut Option<f64>, overlay: Option<f64>) {
+        self.option(property, base, &overlay, f64::to_string);
+    }
+
+    fn table<T: PartialEq + Clone>(
+        &mut self,
+        property: &str,
+        base: &mut Option<T>,
+        overlay: &Option<T>,
+    ) {
+        self.option(property, base, overlay, |_| "table".to_string());
+    }
+
+    fn extra_properties(
+        &mut self,
+        base: &mut HashMap<String, PropValue>,
+        overlay: &HashMap<String, PropValue>,
+    ) {
+        let mut keys: Vec<&String> = overlay.keys().collect();
+        keys.sort();
+        for key in keys {
+            let mut value = base.get(key).cloned();
+            self.option(key, &mut value, &Some(overlay[key].clone()), |v| {
+                v.to_string()
+            });
+            base.insert(key.clone(), overlay[key].clone());
+        }
+    }
+}
+
+impl ProcessStack {
+    /// Apply an overlay stack, e.g. a customer-specific option file, on top of this one
+    ///
+    /// Layers and vias are matched by name, and regions by kind and name. Values present
+    /// in the overlay replace the base values and every changed value is reported as a
+    /// conflict; a layer whose type differs is replaced as a whole. New layers are inserted
+    /// after the preceding overlay layer, or at the front of the list, and new vias and
+    /// regions are appended.
+    ///
+    /// The ITF parser cannot tell an omitted required value from its default, so a zero
+    /// THICKNESS, AREA or RPV, an empty FROM/TO and an ER of exactly 1.0 in the overlay
+    /// leave the base value untouched. Layers the overlay auto-created for its vias are
+    /// ignored.
+    pub fn merge(&mut self, overlay: &ProcessStack) -> MergeReport {
+        let mut report = MergeReport::default();
+
+        self.merge_technology(overlay, &mut report.conflicts);
+
+        let mut insert_index = 0;
+        for overlay_layer in overlay.layers.iter().filter(|l| !l.is_auto_created()) {
+            let name = overlay_layer.name();
+            let Some(base_layer) = self.get_layer(name) else {
+                self.add_layer(overlay_layer.clone());
+                self.move_layer(name, insert_index);
+                report.added_layers.push(name.to_string());
+                insert_index += 1;
+                continue;
+            };
+
+            let mut merger = Merger {
+                item: name,
+                conflicts: &mut report.conflicts,
+            };
+            let merged = match (base_layer, overlay_layer) {
+                (Layer::Dielectric(base), Layer::Dielectric(overlay)) => {
+                    let mut layer = base.clone();
+                    merger.dielectric(&mut layer, overlay);
+                    Layer::Dielectric(layer)
+                }
+                (Layer::Conductor(base), Layer::Conductor(overlay)) => {
+                    let mut layer = base.clone();
+                    merger.conductor(&mut layer, overlay);
+                    Layer::Conductor(layer)
+                }
+                (base, overlay) => {
+                    merger.set("type", &mut base.layer_type(), &overlay.layer_type(), |t| {
+                        format!("{t:?}")
+                    });
+                    overlay.clone()
+                }
+            };
+            self.replace_layer(merged);
+            insert_index = self.get_layer_index(name).map_or(0, |index| index + 1);
+        }
+
+        self.merge_vias(overlay, &mut report);
+        self.merge_er_regions(overlay, &mut report);
+        report
+    }
+
+    fn merge_er_regions(&mut self, overlay: &ProcessStack, report: &mut MergeReport) {
+        for overlay_region in &overlay.er_regions {
+            let Some(base) = self.er_regions.iter_mut().find(|region| {
+                region.kind == overlay_region.kind && region.name == overlay_region.name
+            }) else {
+                self.er_regions.push(overlay_region.clone());
+                report.added_regions.push(overlay_region.label());
+                continue;
+            };
+            let item = overlay_region.label();
+            let mut merger = Merger {
+                item: &item,
+                conflicts: &mut report.conflicts,
+            };
+            merger.er_region(base, overlay_region);
+        }
+    }
+
+    fn merge_technology(&mut self, overlay: &ProcessStack, conflicts: &mut Vec<MergeConflict>) {
+        let base = &mut self.technology_info;
+        let overlay = &overlay.technology_info;
+        let mut merger = Merger {
+            item: "TECHNOLOGY",
+            conflicts,
+        };
+
+        merger.number(
+            "GLOBAL_TEMPERATURE",
+            &mut base.global_temperature,
+            overlay.global_temperature,
+        );
+        merger.option(
+            "REFERENCE_DIRECTION",
+            &mut base.reference_direction,
+            &overlay.reference_direction,
+            String::clone,
+        );
+        merger.number(
+            "BACKGROUND_ER",
+            &mut base.background_er,
+            overlay.background_er,
+        );
+        merger.number(
+            "HALF_NODE_SCALE_FACTOR",
+            &mut base.half_node_scale_factor,
+            overlay.half_node_scale_factor,
+        );
+        merger.option(
+            "USE_SI_DENSITY",
+            &mut base.use_si_density,
+            &overlay.use_si_density,
+            bool::to_string,
+        );
+        merger.number(
+            "DROP_FACTOR_LATERAL_SPACING",
+            &mut base.drop_factor_lateral_spacing,
+            overlay.drop_factor_lateral_spacing,
+        );
+    }
+
+    fn merge_vias(&mut self, overlay: &ProcessStack, report: &mut MergeReport) {
+        let mut vias = std::mem::take(&mut self.via_stack.vias);
+
+        for overlay_via in &overlay.via_stack.vias {
+            let Some(base) = vias.iter_mut().find(|via| via.name == overlay_via.name) else {
+                vias.push(overlay_via.clone());
+                report.added_vias.push(overlay_via.name.clone());
+                continue;
+            };
+            let mut merger = Merger {
+                item: &overlay_via.name,
+                conflicts: &mut report.conflicts,
+            };
+            merger.via(base, overlay_via);
+        }
+
+        // Rebuild so the layer lookup and via positions follow the merged layers
+        self.via_stack = ViaStack::new();
+        for via in vias {
+            self.add_via(via);
+        }
+    }
+
+    fn get_layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name() == name)
+    }
+}
+
+impl Merger<'_> {
+    fn required_number(&mut self, property: &str, base: &mut f64, overlay: f64, unset: f64) {
+        if overlay != unset {
+            self.set(property, base, &overlay, f64::to_string);
+        }
+    }
+
+    fn er_region(&mut self, base: &mut ErRegion, overlay: &ErRegion) {
+        self.required_number(
+            "ER",
+            &mut base.dielectric_constant,
+            overlay.dielectric_constant,
+            1.0,
+        );
+        self.number("THICKNESS", &mut base.thickness, overlay.thickness);
+        self.extra_properties(&mut base.extra_properties, &overlay.extra_properties);
+    }
+
+    fn dielectric(&mut self, base: &mut DielectricLayer, overlay: &DielectricLayer) {
+        self.required_number("THICKNESS", &mut base.thickness, overlay.thickness, 0.0);
+        self.required_number(
+            "ER",
+            &mut base.dielectric_constant,
+            overlay.dielectric_constant,
+            1.0,
+        );
+        self.option(
+            "MEASURED_FROM",
+            &mut base.measured_from,
+            &overlay.measured_from,
+            String::clone,
+        );
+        self.number("SW_T", &mut base.sw_t, overlay.sw_t);
+        self.number("TW_T", &mut base.tw_t, overlay.tw_t);
+        self.extra_properties(&mut base.extra_properties, &overlay.extra_properties);
+    }
+
+    fn conductor(&mut self, base: &mut ConductorLayer, overlay: &ConductorLayer) {
+        self.required_number("THICKNESS", &mut base.thickness, overlay.thickness, 0.0);
+        base.physical_props.thickness = base.thickness;
+
+        let (electrical, overlay_electrical) =
+            (&mut base.electrical_props, &overlay.electrical_props);
+        self.number("CRT1", &mut electrical.crt1, overlay_electrical.crt1);
+        self.number("CRT2", &mut electrical.crt2, overlay_electrical.crt2);
+        self.number("RPSQ", &mut electrical.rpsq, overlay_electrical.rpsq);
+        self.number("RPV", &mut electrical.rpv, overlay_electrical.rpv);
+
+        let (physical, overlay_physical) = (&mut base.physical_props, &overlay.physical_props);
+        self.number("WMIN", &mut physical.width_min, overlay_physical.width_min);
+        self.number(
+            "SMIN",
+            &mut physical.spacing_min,
+            overlay_physical.spacing_min,
+        );
+        self.number(
+            "SIDE_TANGENT",
+            &mut physical.side_tangent,
+            overlay_physical.side_tangent,
+        );
+        self.number(
+            "ER",
+            &mut physical.dielectric_constant,
+            overlay_physical.dielectric_constant,
+        );
+
+        let (gate, overlay_gate) = (&mut base.gate_props, &overlay.gate_props);
+        self.option(
+            "LAYER_TYPE",
+            &mut gate.layer_type,
+            &overlay_gate.layer_type,
+            String::clone,
+        );
+        self.number(
+            "GATE_TO_CONTACT_SMIN",
+            &mut gate.gate_to_contact_smin,
+            overlay_gate.gate_to_contact_smin,
+        );
+        gate.gate_forming_layer |= overlay_gate.gate_forming_layer;
+        gate.has_gate_to_diffusion_cap |= overlay_gate.has_gate_to_diffusion_cap;
+
+        self.table(
+            "RHO_VS_WIDTH_AND_SPACING",
+            &mut base.rho_vs_width_spacing,
+            &overlay.rho_vs_width_spacing,
+        );
+        self.table(
+            "RHO_VS_SI_WIDTH_AND_THICKNESS",
+            &mut base.rho_vs_si_width_thickness,
+            &overlay.rho_vs_si_width_thickness,
+        );
+        self.table(
+            "ETCH_VS_WIDTH_AND_SPACING",
+            &mut base.etch_vs_width_spacing,
+            &overlay.etch_vs_width_spacing,
+        );
+        self.table(
+            "ETCH_FROM_TOP",
+            &mut base.etch_from_top,
+            &overlay.etch_from_top,
+        );
+        self.table(
+            "THICKNESS_VS_WIDTH_AND_SPACING",
+            &mut base.thickness_vs_width_spacing,
+            &overlay.thickness_vs_width_spacing,
+        );
+        self.table(
+            "CRT_VS_SI_WIDTH",
+            &mut base.crt_vs_si_width,
+            &overlay.crt_vs_si_width,
+        );
+        self.table(
+            "POLYNOMIAL_BASED_THICKNESS_VARIATION",
+            &mut base.process_variation,
+            &overlay.process_variation,
+        );
+        self.number(
+            "RESISTIVE_ONLY_ETCH",
+            &mut base.resistive_only_etch,
+            overlay.resistive_only_etch,
+        );
+        self.number(
+            "CAPACITIVE_ONLY_ETCH",
+            &mut base.capacitive_only_etch,
+            overlay.capacitive_only_etch,
+        );
+        self.extra_properties(&mut base.extra_properties, &overlay.extra_properties);
+    }
+
+    fn via(&mut self, base: &mut ViaConnection, overlay: &ViaConnection) {
+        if !overlay.from_layer.is_empty() {
+            self.set(
+                "FROM",
+                &mut base.from_layer,
+                &overlay.from_layer,
+                String::clone,
+            );
+        }
+        if !overlay.to_layer.is_empty() {
+            self.set("TO", &mut base.to_layer, &overlay.to_layer, String::clone);
+        }
+        self.required_number("AREA", &mut base.area, overlay.area, 0.0);
+        self.required_number(
+            "RPV",
+            &mut base.resistance_per_via,
+            overlay.resistance_per_via,
+            0.0,
+        );
+        self.table("ARRAY", &mut base.array, &overlay.array);
+        self.extra_properties(&mut base.extra_properties, &overlay.extra_properties);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::TechnologyInfo;
+
+    fn create_base_stack() -> ProcessStack {
+        let mut stack =
+            ProcessStack::new(TechnologyInfo::new("base".to_string()).with_temperature(25.0));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide2".to_string(),
+            0.5,
+            4.2,
+        )));
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.2);
+        metal.electrical_props.rpsq = Some(0.05);
+        metal.physical_props.width_min = Some(0.1);
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide1".to_string(),
+            1.0,
+            3.9,
+        )));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "oxide1".to_string(),
+            0.01,
+            2.0,
+        ));
+        stack
+    }
+
+    #[test]
+    fn test_merge_overrides_and_conflicts() {
+        let mut base = create_base_stack();
+
+        let mut overlay = ProcessStack::new(TechnologyInfo::new("option".to_string()));
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.0);
+        metal.electrical_props.rpsq = Some(0.06);
+        metal.electrical_props.crt1 = Some(3.0e-3);
+        overlay.add_layer(Layer::Conductor(Box::new(metal)));
+        overlay.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "cap_oxide".to_string(),
+            0.3,
+            7.0,
+        )));
+        overlay.add_via(ViaConnection::new(
+            "via1".to_string(),
+            String::new(),
+            String::new(),
+            0.0,
+            2.5,
+        ));
+
+        let report = base.merge(&overlay);
+
+        let metal1 = base.get_layer("metal1").unwrap();
+        assert_eq!(metal1.thickness(), 0.2);
+        if let Layer::Conductor(metal1) = metal1 {
+            assert_eq!(metal1.electrical_props.rpsq, Some(0.06));
+            assert_eq!(metal1.electrical_props.crt1, Some(3.0e-3));
+            assert_eq!(metal1.physical_props.width_min, Some(0.1));
+        } else {
+            panic!("metal1 should stay a conductor");
+        }
+
+        // New layer lands right after the overlay layer before it
+        let names: Vec<&str> = base.layers.iter().map(|l| l.name()).collect();
+        assert_eq!(names, ["oxide2", "metal1", "cap_oxide", "oxide1"]);
+        assert_eq!(report.added_layers, ["cap_oxide"]);
+
+        let via = &base.via_stack.vias[0];
+        assert_eq!(via.from_layer, "metal1");
+        assert_eq!(via.area, 0.01);
+        assert_eq!(via.resistance_per_via, 2.5);
+        assert_eq!(base.technology_info.name, "base");
+        assert_eq!(base.technology_info.global_temperature, Some(25.0));
+
+        // Only values that existed and changed are conflicts
+        let conflicts: Vec<String> = report.conflicts.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            conflicts,
+            ["metal1 RPSQ: 0.05 -> 0.06", "via1 RPV: 2 -> 2.5"]
+        );
+        assert!(report.has_conflicts());
+    }
+
+    #[test]
+    fn test_merge_replaces_layer_type() {
+        let mut base = create_base_stack();
+        let mut overlay = ProcessStack::new(TechnologyInfo::new("option".to_string()));
+        overlay.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "oxide1".to_string(),
+            0.4,
+        ))));
+        overlay.add_via(ViaConnection::new(
+            "via2".to_string(),
+            "oxide1".to_string(),
+            "oxide2".to_string(),
+            0.02,
+            1.0,
+        ));
+
+        let report = base.merge(&overlay);
+
+        assert!(base.get_layer("oxide1").unwrap().is_conductor());
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].property, "type");
+        assert_eq!(report.added_vias, ["via2"]);
+        assert_eq!(base.via_stack.get_vias_for_layer("oxide2").len(), 1);
+        assert_eq!(base.get_total_height(), 0.5 + 0.2 + 0.4);
+    }
+
+    #[test]
+    fn test_merge_er_regions() {
+        use crate::data::ErRegionKind;
+
+        let mut base = create_base_stack();
+        base.add_er_region(ErRegion::new(
+            ErRegionKind::Substrate,
+            "psub".to_string(),
+            11.9,
+        ));
+        let mut overlay = ProcessStack::new(TechnologyInfo::new("option".to_string()));
+        overlay.add_er_region(
+            ErRegion::new(ErRegionKind::Substrate, "psub".to_string(), 11.7).with_thickness(5.0),
+        );
+        overlay.add_er_region(ErRegion::new(ErRegionKind::Field, "sti".to_string(), 3.9));
+
+        let report = base.merge(&overlay);
+
+        assert_eq!(base.er_regions.len(), 2);
+        assert_eq!(base.er_regions[0].dielectric_constant, 11.7);
+        assert_eq!(base.er_regions[0].thickness, Some(5.0));
+        assert_eq!(base.er_regions[1].name, "sti");
+        assert_eq!(report.added_regions, ["FIELD sti"]);
+        let conflicts: Vec<String> = report.conflicts.iter().map(|c| c.to_string()).collect();
+        assert_eq!(conflicts, ["SUBSTRATE psub ER: 11.9 -> 11.7"]);
+    }
+}
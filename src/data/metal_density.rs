@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    layer::{ConductorLayer, Layer},
+    layer_class::LayerClass,
+    stack::ProcessStack,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Pattern density assumed for metals without a user value, mid CMP window
+pub const DEFAULT_METAL_DENSITY: f64 = 0.5;
+
+/// Assumed metal fill density per conductor, as a fraction from 0 to 1
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetalDensities {
+    /// User values by layer name; other metals use [`DEFAULT_METAL_DENSITY`]
+    pub densities: HashMap<String, f64>,
+}
+
+impl MetalDensities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, layer_name: &str) -> f64 {
+        self.densities
+            .get(layer_name)
+            .copied()
+            .unwrap_or(DEFAULT_METAL_DENSITY)
+    }
+
+    pub fn is_set(&self, layer_name: &str) -> bool {
+        self.densities.contains_key(layer_name)
+    }
+
+    /// Set the density of a layer, clamped to 0..=1, or go back to the default (`None`)
+    pub fn set(&mut self, layer_name: &str, density: Option<f64>) {
+        match density {
+            Some(density) => {
+                self.densities
+                    .insert(layer_name.to_string(), density.clamp(0.0, 1.0));
+            }
+            None => {
+                self.densities.remove(layer_name);
+            }
+        }
+    }
+
+    /// Keep a layer's density when the layer is renamed
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        if let Some(density) = self.densities.remove(old_name) {
+            self.densities.insert(new_name.to_string(), density);
+        }
+    }
+}
+
+/// Assumed density of one metal and the thickness it leads to
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityContext {
+    pub layer_name: String,
+    pub density: f64,
+    /// Whether the density was entered by the user rather than defaulted
+    pub user_set: bool,
+    pub nominal_thickness: f64,
+    /// Thickness after the density dependent variation of the techfile, if it has one
+    pub thickness: f64,
+}
+
+impl DensityContext {
+    pub fn thickness_change(&self) -> f64 {
+        self.thickness - self.nominal_thickness
+    }
+}
+
+impl ConductorLayer {
+    /// Thickness at a metal density, including the THICKNESS_VS_DENSITY variation at WMIN
+    ///
+    /// Without variation data the nominal thickness is returned.
+    pub fn thickness_at_density(&self, density: f64) -> f64 {
+        let width = self.physical_props.width_min.unwrap_or(0.0);
+        self.thickness
+            + self.process_variation.as_ref().map_or(0.0, |variation| {
+                variation.calculate_thickness_variation(density, width)
+            })
+    }
+}
+
+impl ProcessStack {
+    /// Density and resulting thickness of every signal metal, bottom metal first
+    pub fn get_density_context(&self, densities: &MetalDensities) -> Vec<DensityContext> {
+        self.layers
+            .iter()
+            .rev()
+            .filter_map(|layer| match layer {
+                Layer::Conductor(conductor)
+                    if LayerClass::infer(layer) == LayerClass::SignalMetal =>
+                {
+                    let density = densities.get(&conductor.name);
+                    Some(DensityContext {
+                        layer_name: conductor.name.clone(),
+                        density,
+                        user_set: densities.is_set(&conductor.name),
+                        nominal_thickness: conductor.thickness,
+                        thickness: conductor.thickness_at_density(density),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, ProcessVariation, TechnologyInfo};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_density_context() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("density".to_string()));
+        let mut metal2 = ConductorLayer::new("metal2".to_string(), 0.3);
+        // Thickness drops by 0.1 um per unit of density
+        metal2.process_variation = Some(ProcessVariation {
+            density_polynomial_orders: vec![1],
+            width_polynomial_orders: vec![0],
+            width_ranges: vec![],
+            polynomial_coefficients: vec![vec![-0.1]],
+        });
+        stack.add_layer(Layer::Conductor(Box::new(metal2)));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "ild".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.2,
+        ))));
+
+        let mut densities = MetalDensities::new();
+        densities.set("metal2", Some(1.5));
+        let context = stack.get_density_context(&densities);
+
+        assert_eq!(context.len(), 2);
+        assert_eq!(context[0].layer_name, "metal1");
+        assert_eq!(context[0].density, DEFAULT_METAL_DENSITY);
+        assert!(!context[0].user_set);
+        assert_eq!(context[0].thickness_change(), 0.0);
+
+        assert_eq!(context[1].density, 1.0);
+        assert!(context[1].user_set);
+        assert_relative_eq!(context[1].thickness, 0.2);
+
+        densities.rename("metal2", "M2");
+        assert!(densities.is_set("M2"));
+        densities.set("M2", None);
+        assert!(densities.densities.is_empty());
+    }
+}
@@ -1,12 +1,51 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+pub mod bond;
+pub mod conformal;
+pub mod diagnostic;
+pub mod dielectric_gap;
+pub mod diff;
+pub mod document;
+pub mod er_region;
+pub mod fingerprint;
 pub mod layer;
+pub mod layer_class;
+pub mod lint;
+pub mod materials;
+pub mod memory;
+pub mod merge;
+pub mod metal_density;
+pub mod naming;
+pub mod passivation;
 pub mod properties;
 pub mod stack;
+pub mod temperature_drift;
+pub mod units;
 pub mod via;
+pub mod via_chain;
+pub mod via_span;
 
+pub use bond::*;
+pub use conformal::*;
+pub use diagnostic::*;
+pub use dielectric_gap::*;
+pub use diff::*;
+pub use document::*;
+pub use er_region::*;
 pub use layer::*;
+pub use layer_class::*;
+pub use lint::*;
+pub use materials::*;
+pub use memory::*;
+pub use merge::*;
+pub use metal_density::*;
+pub use naming::*;
+pub use passivation::*;
 pub use properties::*;
 pub use stack::*;
+pub use temperature_drift::*;
+pub use units::*;
 pub use via::*;
+pub use via_chain::*;
+pub use via_span::*;
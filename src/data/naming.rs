@@ -0,0 +1,375 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{layer::Layer, stack::ProcessStack};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Name prefixes of numbered metals, such as M1, MET2 or METAL3
+const METAL_PREFIXES: [&str; 4] = ["m", "me", "met", "metal"];
+/// Name prefixes of numbered vias, such as V1 or VIA2
+const VIA_PREFIXES: [&str; 3] = ["v", "vi", "via"];
+/// Name prefixes of thick top metals, optionally numbered, such as MT, TM2 or AP
+const TOP_METAL_PREFIXES: [&str; 7] = ["mt", "tm", "mtop", "topmetal", "ap", "alpa", "rdl"];
+const POLY_NAMES: [&str; 3] = ["po", "poly", "polysilicon"];
+const CONTACT_NAMES: [&str; 5] = ["co", "ct", "cont", "contact", "mcon"];
+
+/// Lower metal of via digits naming two adjacent metals, 1 for "12" or 10 for "1011"
+fn joined_metals(digits: &str) -> Option<u32> {
+    (1..digits.len()).find_map(|split| {
+        let (lower, upper) = digits.split_at(split);
+        let (lower, upper) = (lower.parse::<u32>().ok()?, upper.parse::<u32>().ok()?);
+        (!digits[split..].starts_with('0') && upper == lower + 1).then_some(lower)
+    })
+}
+
+/// Place of a conductor or via in the interconnect, as told by its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NameRole {
+    Poly,
+    /// Contact from the front end to the first metal
+    Contact,
+    Metal(u32),
+    /// Via above `Metal(n)`
+    Via(u32),
+    TopMetal(u32),
+}
+
+/// Part of the stack a role belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NameGroup {
+    FrontEnd,
+    Interconnect,
+    TopMetal,
+}
+
+impl NameGroup {
+    pub const ALL: [NameGroup; 3] = [
+        NameGroup::FrontEnd,
+        NameGroup::Interconnect,
+        NameGroup::TopMetal,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NameGroup::FrontEnd => "Front end",
+            NameGroup::Interconnect => "Interconnect",
+            NameGroup::TopMetal => "Top metal",
+        }
+    }
+}
+
+impl NameRole {
+    /// Recognize a common naming convention, ignoring case and separators
+    ///
+    /// Numbered metals and vias (M1, METAL_2, VIA3), top metals (MT, TM1, AP), poly
+    /// (PO, POLY) and contacts (CO, CONT) are recognized; other names give `None`. Vias
+    /// may also be named after the metals they join, as VIA12 or VIA_M1_M2.
+    pub fn recognize(name: &str) -> Option<Self> {
+        let lower = name.to_ascii_lowercase();
+        let tokens: Vec<&str> = lower
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .collect();
+        if let [via, metal, ..] = tokens[..] {
+            if VIA_PREFIXES.contains(&via) {
+                if let Some(NameRole::Metal(n)) = Self::recognize(metal) {
+                    return Some(NameRole::Via(n));
+                }
+            }
+        }
+
+        let name: String = name
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        let split = name
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(name.len());
+        let (prefix, digits) = name.split_at(split);
+        let number = if digits.is_empty() {
+            None
+        } else {
+            Some(digits.parse::<u32>().ok()?)
+        };
+
+        match (prefix, number) {
+            (prefix, Some(n)) if METAL_PREFIXES.contains(&prefix) => Some(NameRole::Metal(n)),
+            (prefix, Some(n)) if VIA_PREFIXES.contains(&prefix) => {
+                Some(NameRole::Via(joined_metals(digits).unwrap_or(n)))
+            }
+            (prefix, n) if TOP_METAL_PREFIXES.contains(&prefix) => {
+                Some(NameRole::TopMetal(n.unwrap_or(1)))
+            }
+            (prefix, None) if POLY_NAMES.contains(&prefix) => Some(NameRole::Poly),
+            (prefix, None) if CONTACT_NAMES.contains(&prefix) => Some(NameRole::Contact),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(self) -> String {
+        match self {
+            NameRole::Poly => "Poly".to_string(),
+            NameRole::Contact => "Contact".to_string(),
+            NameRole::Metal(n) => format!("Metal {n}"),
+            NameRole::Via(n) => format!("Via {n}"),
+            NameRole::TopMetal(n) => format!("Top Metal {n}"),
+        }
+    }
+
+    pub fn group(self) -> NameGroup {
+        match self {
+            NameRole::Poly | NameRole::Contact => NameGroup::FrontEnd,
+            NameRole::Metal(_) | NameRole::Via(_) => NameGroup::Interconnect,
+            NameRole::TopMetal(_) => NameGroup::TopMetal,
+        }
+    }
+
+    /// Whether the role belongs to a via rather than a conductor layer
+    pub fn is_via(self) -> bool {
+        matches!(self, NameRole::Contact | NameRole::Via(_))
+    }
+
+    /// Position on the connectivity ladder, bottom first
+    fn rank(self) -> (u8, u32) {
+        match self {
+            NameRole::Poly => (0, 0),
+            NameRole::Contact => (1, 0),
+            NameRole::Metal(n) => (2, 2 * n),
+            NameRole::Via(n) => (2, 2 * n + 1),
+            NameRole::TopMetal(n) => (3, n),
+        }
+    }
+}
+
+/// A conductor or via with the role it plays on the ladder
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamingEntry {
+    pub name: String,
+    pub role: NameRole,
+    /// Whether the role was set by the user instead of recognized from the name
+    pub overridden: bool,
+}
+
+/// Naming convention of a stack: the connectivity ladder built from the names of its
+/// conductors and vias, and the names that fit no convention
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StackNaming {
+    /// Conductors and vias from the bottom of the interconnect up
+    pub ladder: Vec<NamingEntry>,
+    /// Conductors and vias without a role, in ITF order
+    pub unrecognized: Vec<String>,
+    /// Places where the names and the stack disagree
+    pub warnings: Vec<String>,
+}
+
+impl StackNaming {
+    pub fn get_role(&self, name: &str) -> Option<NameRole> {
+        self.ladder
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.role)
+    }
+
+    /// Ladder entries of one group, bottom first
+    pub fn get_group(&self, group: NameGroup) -> impl DoubleEndedIterator<Item = &NamingEntry> {
+        self.ladder
+            .iter()
+            .filter(move |entry| entry.role.group() == group)
+    }
+
+    /// Display names of the conductors and vias on the ladder, keyed by ITF name
+    pub fn get_display_names(&self) -> HashMap<String, String> {
+        self.ladder
+            .iter()
+            .map(|entry| (entry.name.clone(), entry.role.display_name()))
+            .collect()
+    }
+
+    pub fn is_recognized(&self) -> bool {
+        !self.ladder.is_empty()
+    }
+}
+
+impl ProcessStack {
+    /// Recognize the naming convention of the conductors and vias
+    ///
+    /// Roles in `overrides` win over recognized ones. Recognized roles are only used when
+    /// they fit, a via role for a via and a layer role for a conductor.
+    pub fn get_naming(&self, overrides: &HashMap<String, NameRole>) -> StackNaming {
+        let conductors = self
+            .layers
+            .iter()
+            .filter(|layer| matches!(layer, Layer::Conductor(_)))
+            .map(|layer| (layer.name(), false));
+        let vias = self.via_stack.iter().map(|via| (via.name.as_str(), true));
+
+        let mut naming = StackNaming::default();
+        for (name, is_via) in conductors.chain(vias) {
+            let role = match overrides.get(name) {
+                Some(&role) => Some((role, true)),
+                None => NameRole::recognize(name)
+                    .filter(|role| role.is_via() == is_via)
+                    .map(|role| (role, false)),
+            };
+            match role {
+                Some((role, overridden)) => naming.ladder.push(NamingEntry {
+                    name: name.to_string(),
+                    role,
+                    overridden,
+                }),
+                None => naming.unrecognized.push(name.to_string()),
+            }
+        }
+        naming.ladder.sort_by_key(|entry| entry.role.rank());
+        naming.warnings = self.naming_warnings(&naming.ladder);
+        naming
+    }
+
+    fn naming_warnings(&self, ladder: &[NamingEntry]) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        for pair in ladder.windows(2) {
+            if pair[0].role == pair[1].role {
+                warnings.push(format!(
+                    "{} and {} are both {}",
+                    pair[0].name,
+                    pair[1].name,
+                    pair[0].role.display_name()
+                ));
+            }
+        }
+
+        let mut metals: Vec<u32> = ladder
+            .iter()
+            .filter_map(|entry| match entry.role {
+                NameRole::Metal(n) => Some(n),
+                _ => None,
+            })
+            .collect();
+        metals.dedup();
+        for pair in metals.windows(2) {
+            if pair[1] > pair[0] + 1 {
+                warnings.push(format!(
+                    "No metal between Metal {} and Metal {}",
+                    pair[0], pair[1]
+                ));
+            }
+        }
+
+        // A via is expected to connect the conductors next to it on the ladder
+        for (index, entry) in ladder.iter().enumerate() {
+            if !entry.role.is_via() {
+                continue;
+            }
+            let Some(via) = self.via_stack.get_via(&entry.name) else {
+                continue;
+            };
+            let below = ladder[..index].iter().rev().find(|e| !e.role.is_via());
+            let above = ladder[index + 1..].iter().find(|e| !e.role.is_via());
+            if let (Some(below), Some(above)) = (below, above) {
+                if !via.connects_layers(&below.name, &above.name) {
+                    warnings.push(format!(
+                        "{} connects {} and {}, but its name puts it between {} and {}",
+                        via.name, via.from_layer, via.to_layer, below.name, above.name
+                    ));
+                }
+            }
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, TechnologyInfo, ViaConnection};
+
+    #[test]
+    fn test_recognize_names() {
+        assert_eq!(NameRole::recognize("M1"), Some(NameRole::Metal(1)));
+        assert_eq!(NameRole::recognize("metal_12"), Some(NameRole::Metal(12)));
+        assert_eq!(NameRole::recognize("MET3"), Some(NameRole::Metal(3)));
+        assert_eq!(NameRole::recognize("VIA2"), Some(NameRole::Via(2)));
+        assert_eq!(NameRole::recognize("v0"), Some(NameRole::Via(0)));
+        assert_eq!(NameRole::recognize("via23"), Some(NameRole::Via(2)));
+        assert_eq!(NameRole::recognize("VIA1011"), Some(NameRole::Via(10)));
+        assert_eq!(NameRole::recognize("via10"), Some(NameRole::Via(10)));
+        assert_eq!(NameRole::recognize("via_m1_m2"), Some(NameRole::Via(1)));
+        assert_eq!(
+            NameRole::recognize("top_metal"),
+            Some(NameRole::TopMetal(1))
+        );
+        assert_eq!(NameRole::recognize("MT"), Some(NameRole::TopMetal(1)));
+        assert_eq!(NameRole::recognize("TM2"), Some(NameRole::TopMetal(2)));
+        assert_eq!(NameRole::recognize("AP"), Some(NameRole::TopMetal(1)));
+        assert_eq!(NameRole::recognize("PO"), Some(NameRole::Poly));
+        assert_eq!(NameRole::recognize("Poly"), Some(NameRole::Poly));
+        assert_eq!(NameRole::recognize("CO"), Some(NameRole::Contact));
+        assert_eq!(NameRole::recognize("oxide1"), None);
+        assert_eq!(NameRole::recognize("metal"), None);
+        assert_eq!(NameRole::recognize("m1a"), None);
+
+        assert_eq!(NameRole::Metal(2).display_name(), "Metal 2");
+        assert_eq!(NameRole::Contact.group(), NameGroup::FrontEnd);
+        assert_eq!(NameRole::TopMetal(1).group(), NameGroup::TopMetal);
+    }
+
+    #[test]
+    fn test_stack_naming() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("naming".to_string()));
+        for name in ["AP", "M3", "M1", "PO", "rdl_x"] {
+            stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+                name.to_string(),
+                0.2,
+            ))));
+        }
+        for (name, from, to) in [
+            ("CO", "PO", "M1"),
+            ("VIA1", "M1", "M3"),
+            ("VIA3", "M1", "AP"),
+        ] {
+            stack.add_via(ViaConnection::new(
+                name.to_string(),
+                from.to_string(),
+                to.to_string(),
+                0.01,
+                1.0,
+            ));
+        }
+
+        let naming = stack.get_naming(&HashMap::new());
+        let ladder: Vec<&str> = naming.ladder.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(ladder, ["PO", "CO", "M1", "VIA1", "M3", "VIA3", "AP"]);
+        assert_eq!(naming.unrecognized, ["rdl_x"]);
+        assert_eq!(naming.get_role("VIA3"), Some(NameRole::Via(3)));
+        assert_eq!(
+            naming.get_display_names().get("AP").map(String::as_str),
+            Some("Top Metal 1")
+        );
+        assert_eq!(
+            naming
+                .get_group(NameGroup::FrontEnd)
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>(),
+            ["PO", "CO"]
+        );
+        assert_eq!(
+            naming.warnings,
+            [
+                "No metal between Metal 1 and Metal 3",
+                "VIA3 connects M1 and AP, but its name puts it between M3 and AP",
+            ]
+        );
+
+        // A mapping places names no convention covers
+        let overrides = HashMap::from([("rdl_x".to_string(), NameRole::TopMetal(2))]);
+        let naming = stack.get_naming(&overrides);
+        assert!(naming.unrecognized.is_empty());
+        let last = naming.ladder.last().unwrap();
+        assert_eq!(last.name, "rdl_x");
+        assert!(last.overridden);
+    }
+}
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    layer::{ConductorLayer, DielectricLayer, Layer},
+    properties::PropValue,
+    stack::ProcessStack,
+};
+
+/// Opening in the passivation above the last metal, where pads or bumps land
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassivationOpening {
+    /// Top conductor left open by the opening
+    pub conductor: String,
+    /// Dielectrics above the conductor cut by the opening, top first
+    pub dielectrics: Vec<String>,
+}
+
+/// YES, TRUE or a non-zero number
+fn is_set(value: Option<&PropValue>) -> bool {
+    match value {
+        Some(PropValue::Number(value)) => *value != 0.0,
+        Some(PropValue::Text(text)) => {
+            text.eq_ignore_ascii_case("YES") || text.eq_ignore_ascii_case("TRUE")
+        }
+        None => false,
+    }
+}
+
+impl ConductorLayer {
+    /// Whether `EXPOSED = YES` marks the top of this metal as open through the passivation
+    pub fn is_exposed(&self) -> bool {
+        is_set(self.extra_properties.get("EXPOSED"))
+    }
+}
+
+impl DielectricLayer {
+    /// Whether `PASSIVATION_OPENING = YES` marks this dielectric as opened above the last metal
+    pub fn has_passivation_opening(&self) -> bool {
+        is_set(self.extra_properties.get("PASSIVATION_OPENING"))
+    }
+}
+
+impl ProcessStack {
+    /// Opening above the last metal, if the techfile defines one
+    ///
+    /// An EXPOSED top metal opens every dielectric above it. Otherwise only the dielectrics
+    /// above it with PASSIVATION_OPENING are opened.
+    pub fn get_passivation_opening(&self) -> Option<PassivationOpening> {
+        // ITF lists layers top first, so the first conductor is the last metal
+        let (index, conductor) =
+            self.layers
+                .iter()
+                .enumerate()
+                .find_map(|(index, layer)| match layer {
+                    Layer::Conductor(conductor) => Some((index, conductor)),
+                    Layer::Dielectric(_) => None,
+                })?;
+        let exposed = conductor.is_exposed();
+        let dielectrics: Vec<String> = self.layers[..index]
+            .iter()
+            .filter_map(|layer| match layer {
+                Layer::Dielectric(dielectric)
+                    if exposed || dielectric.has_passivation_opening() =>
+                {
+                    Some(dielectric.name.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        if dielectrics.is_empty() {
+            return None;
+        }
+        Some(PassivationOpening {
+            conductor: conductor.name.clone(),
+            dielectrics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::TechnologyInfo;
+
+    fn create_stack(top_metal: ConductorLayer, passivation: DielectricLayer) -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("pad".to_string()));
+        stack.add_layer(Layer::Dielectric(passivation));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "pass1".to_string(),
+            0.5,
+            4.1,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(top_metal)));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd".to_string(),
+            1.0,
+            4.0,
+        )));
+        stack
+    }
+
+    #[test]
+    fn test_passivation_opening() {
+        let nitride = DielectricLayer::new("pass2".to_string(), 0.6, 7.0);
+        let pad = ConductorLayer::new("ap".to_string(), 1.5);
+        assert_eq!(
+            create_stack(pad.clone(), nitride.clone()).get_passivation_opening(),
+            None
+        );
+
+        let mut exposed_pad = pad.clone();
+        exposed_pad
+            .extra_properties
+            .insert("EXPOSED".to_string(), PropValue::Text("yes".to_string()));
+        assert!(exposed_pad.is_exposed());
+        let opening = create_stack(exposed_pad, nitride.clone())
+            .get_passivation_opening()
+            .unwrap();
+        assert_eq!(opening.conductor, "ap");
+        assert_eq!(opening.dielectrics, ["pass2", "pass1"]);
+
+        // Only the flagged dielectric is opened
+        let mut opened_nitride = nitride;
+        opened_nitride
+            .extra_properties
+            .insert("PASSIVATION_OPENING".to_string(), PropValue::Number(1.0));
+        let opening = create_stack(pad, opened_nitride)
+            .get_passivation_opening()
+            .unwrap();
+        assert_eq!(opening.dielectrics, ["pass2"]);
+    }
+}
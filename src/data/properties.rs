@@ -2,6 +2,7 @@
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ElectricalProperties {
@@ -20,6 +21,58 @@ pub struct PhysicalProperties {
     pub dielectric_constant: Option<f64>,
 }
 
+/// Gate and poly specific conductor attributes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GateProperties {
+    /// Raw `LAYER_TYPE` value, e.g. `GATE`, `FIELD_POLY` or `DIFFUSION`
+    pub layer_type: Option<String>,
+    pub gate_forming_layer: bool,
+    pub gate_to_contact_smin: Option<f64>,
+    pub has_gate_to_diffusion_cap: bool,
+}
+
+impl GateProperties {
+    pub fn is_gate(&self) -> bool {
+        let gate_layer_type = self.layer_type.as_deref().is_some_and(|layer_type| {
+            layer_type.eq_ignore_ascii_case("GATE") || layer_type.eq_ignore_ascii_case("FIELD_POLY")
+        });
+
+        self.gate_forming_layer || gate_layer_type || self.gate_to_contact_smin.is_some()
+    }
+}
+
+/// Value of a `KEY = value` property kept as written, without a dedicated field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PropValue {
+    Number(f64),
+    Text(String),
+}
+
+impl PropValue {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            PropValue::Number(value) => Some(*value),
+            PropValue::Text(_) => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            PropValue::Number(_) => None,
+            PropValue::Text(text) => Some(text),
+        }
+    }
+}
+
+impl fmt::Display for PropValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropValue::Number(value) => write!(f, "{value}"),
+            PropValue::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LookupTable2D {
     pub widths: Vec<f64>,
@@ -73,6 +126,14 @@ impl LookupTable2D {
         Some(result)
     }
 
+    pub fn width_range(&self) -> Option<(f64, f64)> {
+        axis_range(&self.widths)
+    }
+
+    pub fn spacing_range(&self) -> Option<(f64, f64)> {
+        axis_range(&self.spacings)
+    }
+
     fn find_interpolation_indices(&self, array: &[f64], value: f64) -> Option<(usize, usize, f64)> {
         if array.is_empty() {
             return None;
@@ -157,6 +218,10 @@ impl CrtVsSiWidthTable {
         }
     }
 
+    pub fn width_range(&self) -> Option<(f64, f64)> {
+        axis_range(&self.widths)
+    }
+
     pub fn lookup_crt_values(&self, width: f64) -> Option<(f64, f64)> {
         if self.widths.is_empty() {
             return None;
@@ -189,6 +254,55 @@ impl CrtVsSiWidthTable {
     }
 }
 
+/// A lookup that fell outside a table axis and was clamped to its nearest edge
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRangeWarning {
+    pub table: &'static str,
+    pub axis: &'static str,
+    pub requested: f64,
+    pub clamped: f64,
+    pub range: (f64, f64),
+}
+
+impl TableRangeWarning {
+    /// Returns a warning when `requested` lies outside `range`
+    pub fn check(
+        table: &'static str,
+        axis: &'static str,
+        range: Option<(f64, f64)>,
+        requested: f64,
+    ) -> Option<Self> {
+        let (min, max) = range?;
+        if requested >= min && requested <= max {
+            return None;
+        }
+
+        Some(Self {
+            table,
+            axis,
+            requested,
+            clamped: requested.clamp(min, max),
+            range: (min, max),
+        })
+    }
+}
+
+impl fmt::Display for TableRangeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:.4} μm outside {} range [{:.4}, {:.4}], clamped to {:.4} μm",
+            self.axis, self.requested, self.table, self.range.0, self.range.1, self.clamped
+        )
+    }
+}
+
+fn axis_range(values: &[f64]) -> Option<(f64, f64)> {
+    let min = values.iter().copied().reduce(f64::min)?;
+    let max = values.iter().copied().reduce(f64::max)?;
+    Some((min, max))
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProcessVariation {
     pub density_polynomial_orders: Vec<u32>,
@@ -1,7 +1,13 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use crate::data::{layer::Layer, via::ViaStack};
+use crate::data::{
+    diagnostic::{Diagnostic, Severity},
+    er_region::ErRegion,
+    layer::Layer,
+    units::Celsius,
+    via::ViaStack,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -34,10 +40,20 @@ impl TechnologyInfo {
         self
     }
 
+    pub fn get_temperature(&self) -> Option<Celsius> {
+        self.global_temperature.map(Celsius)
+    }
+
     pub fn with_reference_direction(mut self, direction: String) -> Self {
         self.reference_direction = Some(direction);
         self
     }
+
+    /// Layout width or spacing after HALF_NODE_SCALE_FACTOR, unchanged when it is unset
+    pub fn scale_half_node(&self, length: f64) -> f64 {
+        self.half_node_scale_factor
+            .map_or(length, |factor| length * factor)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +61,14 @@ pub struct ProcessStack {
     pub technology_info: TechnologyInfo,
     pub layers: Vec<Layer>,
     pub via_stack: ViaStack,
+    /// Messages collected while loading the stack, in the order they were raised
+    pub diagnostics: Vec<Diagnostic>,
+    /// Substrate and field regions, drawn below the layers
+    #[serde(default)]
+    pub er_regions: Vec<ErRegion>,
+    /// 1-based line defining each layer and via, for stacks parsed from a file
+    #[serde(default)]
+    source_lines: HashMap<String, usize>,
     layer_name_to_index: HashMap<String, usize>,
     total_height: f64,
 }
@@ -55,11 +79,24 @@ impl ProcessStack {
             technology_info,
             layers: Vec::new(),
             via_stack: ViaStack::new(),
+            diagnostics: Vec::new(),
+            er_regions: Vec::new(),
+            source_lines: HashMap::new(),
             layer_name_to_index: HashMap::new(),
             total_height: 0.0,
         }
     }
 
+    /// Record the source line defining the layer or via `name`
+    pub fn set_source_line(&mut self, name: &str, line: usize) {
+        self.source_lines.insert(name.to_string(), line);
+    }
+
+    /// Source line defining the layer or via `name`, if it was parsed from a file
+    pub fn get_source_line(&self, name: &str) -> Option<usize> {
+        self.source_lines.get(name).copied()
+    }
+
     pub fn add_layer(&mut self, layer: Layer) {
         let layer_name = layer.name().to_string();
         let index = self.layers.len();
@@ -79,8 +116,15 @@ impl ProcessStack {
             return;
         }
 
-        let average_thickness = if !self.layers.is_empty() {
-            self.layers.iter().map(|l| l.thickness()).sum::<f64>() / self.layers.len() as f64
+        // Markers have no height and do not count toward the average
+        let thicknesses: Vec<f64> = self
+            .layers
+            .iter()
+            .filter(|l| !l.is_marker())
+            .map(|l| l.thickness())
+            .collect();
+        let average_thickness = if !thicknesses.is_empty() {
+            thicknesses.iter().sum::<f64>() / thicknesses.len() as f64
         } else {
             1.0
         };
@@ -96,13 +140,102 @@ impl ProcessStack {
         // Add substrate layer to the end (bottom) so it appears at bottom in panel and rendering
         self.layers.push(auto_layer);
 
+        self.rebuild_layer_index();
+        self.update_layer_positions();
+    }
+
+    /// Replace the layer with the same name, returning the previous layer
+    pub fn replace_layer(&mut self, layer: Layer) -> Option<Layer> {
+        let index = *self.layer_name_to_index.get(layer.name())?;
+        let previous = std::mem::replace(&mut self.layers[index], layer);
+        self.update_layer_positions();
+        Some(previous)
+    }
+
+    /// Set a layer's thickness and restack the layers above it, returning the old thickness
+    pub fn set_layer_thickness(&mut self, name: &str, thickness: f64) -> Option<f64> {
+        let layer = self.get_layer_mut(name)?;
+        let previous = layer.thickness();
+        layer.set_thickness(thickness);
+        self.update_layer_positions();
+        Some(previous)
+    }
+
+    /// Rename a layer and every via reference to it
+    ///
+    /// Returns false when the layer does not exist or the new name is taken.
+    pub fn rename_layer(&mut self, old_name: &str, new_name: &str) -> bool {
+        if self.get_layer(new_name).is_some() {
+            return false;
+        }
+        let Some(layer) = self.get_layer_mut(old_name) else {
+            return false;
+        };
+        layer.set_name(new_name.to_string());
+        if let Some(line) = self.source_lines.remove(old_name) {
+            self.source_lines.insert(new_name.to_string(), line);
+        }
+
+        for via in &mut self.via_stack.vias {
+            if via.from_layer == old_name {
+                via.from_layer = new_name.to_string();
+            }
+            if via.to_layer == old_name {
+                via.to_layer = new_name.to_string();
+            }
+        }
+
+        self.rebuild_layer_index();
+        true
+    }
+
+    /// Move a layer to a new index in the layer list, returning its previous index
+    pub fn move_layer(&mut self, name: &str, index: usize) -> Option<usize> {
+        let previous = *self.layer_name_to_index.get(name)?;
+        let layer = self.layers.remove(previous);
+        let index = index.min(self.layers.len());
+        self.layers.insert(index, layer);
+
+        self.rebuild_layer_index();
+        self.update_layer_positions();
+        Some(previous)
+    }
+
+    /// Vias whose span crosses a conductor layer other than the two they connect
+    pub fn get_via_span_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        for via in &self.via_stack.vias {
+            let (Some(&from_index), Some(&to_index)) = (
+                self.layer_name_to_index.get(&via.from_layer),
+                self.layer_name_to_index.get(&via.to_layer),
+            ) else {
+                continue;
+            };
+
+            let (low, high) = (from_index.min(to_index), from_index.max(to_index));
+            for layer in &self.layers[low + 1..high] {
+                if layer.is_conductor() {
+                    warnings.push(format!(
+                        "VIA '{}' ({} -> {}) crosses conductor '{}'",
+                        via.name,
+                        via.from_layer,
+                        via.to_layer,
+                        layer.name()
+                    ));
+                }
+            }
+        }
+
+        warnings
+    }
+
+    fn rebuild_layer_index(&mut self) {
         self.layer_name_to_index.clear();
         for (index, layer) in self.layers.iter().enumerate() {
             self.layer_name_to_index
                 .insert(layer.name().to_string(), index);
         }
-
-        self.update_layer_positions();
     }
 
     pub fn ensure_via_layers_exist(&mut self) {
@@ -117,15 +250,22 @@ impl ProcessStack {
             }
         }
 
+        // Sorted so the created layers do not depend on hash order
+        let mut missing_layers: Vec<String> = missing_layers.into_iter().collect();
+        missing_layers.sort();
         for layer_name in missing_layers {
-            eprintln!("Info: Auto-creating missing layer '{layer_name}' (200% thickness)");
+            log::info!("Auto-creating missing layer '{layer_name}' (200% thickness)");
             self.create_missing_layer(&layer_name);
+            self.diagnostics.push(Diagnostic::new(
+                Severity::Info,
+                format!("Auto-created layer '{layer_name}' referenced by a via but not defined"),
+            ));
         }
     }
 
     fn update_layer_positions(&mut self) {
         // Calculate total height first
-        let total_height: f64 = self.layers.iter().map(|l| l.thickness()).sum();
+        let total_height: f64 = self.layers.iter().map(|l| l.stacked_thickness()).sum();
         self.total_height = total_height;
 
         // ITF layers are ordered from bottom to top (substrate first, passivation last)
@@ -134,7 +274,7 @@ impl ProcessStack {
 
         for layer in &mut self.layers {
             layer.set_z_position(current_z);
-            current_z += layer.thickness();
+            current_z += layer.stacked_thickness();
         }
 
         self.update_via_positions();
@@ -246,6 +386,54 @@ impl ProcessStack {
         self.total_height
     }
 
+    /// Bottom and top of every layer in um above the substrate, as drawn at true thickness
+    ///
+    /// Dielectrics stack upwards from the last listed layer and each conductor sits at the
+    /// bottom of the dielectric listed right before it. Unlike `z_position`, which stacks
+    /// every layer in list order, this matches the cross-section the viewer draws. Markers
+    /// get an empty range at their boundary.
+    pub fn get_layer_z_ranges(&self) -> HashMap<String, (f64, f64)> {
+        let mut ranges = HashMap::new();
+        let mut current_z = 0.0;
+        for layer in self.layers.iter().rev() {
+            if let Layer::Dielectric(dielectric) = layer {
+                let height = layer.stacked_thickness();
+                ranges.insert(dielectric.name.clone(), (current_z, current_z + height));
+                current_z += height;
+            }
+        }
+
+        for (index, layer) in self.layers.iter().enumerate() {
+            if let Layer::Conductor(conductor) = layer {
+                let bottom = index
+                    .checked_sub(1)
+                    .and_then(|above| match &self.layers[above] {
+                        Layer::Dielectric(dielectric) => ranges.get(&dielectric.name),
+                        Layer::Conductor(_) => None,
+                    })
+                    .map_or(0.0, |&(bottom, _)| bottom);
+                ranges.insert(
+                    conductor.name.clone(),
+                    (bottom, bottom + layer.stacked_thickness()),
+                );
+            }
+        }
+        ranges
+    }
+
+    /// Bottom and top of a layer in um above the substrate, see `get_layer_z_ranges`
+    pub fn get_layer_z_range(&self, name: &str) -> Option<(f64, f64)> {
+        self.get_layer_z_ranges().remove(name)
+    }
+
+    /// Height of the top of the chip in um, the highest layer top
+    pub fn get_stack_top_z(&self) -> f64 {
+        self.get_layer_z_ranges()
+            .values()
+            .map(|&(_, top)| top)
+            .fold(0.0, f64::max)
+    }
+
     pub fn get_layer_count(&self) -> usize {
         self.layers.len()
     }
@@ -292,9 +480,8 @@ impl ProcessStack {
         }
 
         for (i, layer) in self.layers.iter().enumerate() {
-            // Only check for negative thickness - allow zero thickness layers
-            // Real ITF files commonly have zero thickness layers for various reasons
-            if layer.thickness() < 0.0 {
+            // Zero and negative thicknesses are marker layers, common in real ITF files
+            if !layer.thickness().is_finite() {
                 return Err(StackValidationError::InvalidThickness {
                     layer_name: layer.name().to_string(),
                     thickness: layer.thickness(),
@@ -345,8 +532,8 @@ impl ProcessStack {
         let mut warnings = Vec::new();
 
         for (i, layer) in self.layers.iter().enumerate() {
-            // Only check for negative thickness - allow zero thickness layers
-            if layer.thickness() < 0.0 {
+            // Zero and negative thicknesses are marker layers
+            if !layer.thickness().is_finite() {
                 return Err(StackValidationError::InvalidThickness {
                     layer_name: layer.name().to_string(),
                     thickness: layer.thickness(),
@@ -394,7 +581,7 @@ impl ProcessStack {
         let poly_layers: Vec<_> = self
             .layers
             .iter()
-            .filter(|layer| layer.name().contains("poly"))
+            .filter(|layer| layer.is_gate() || layer.name().contains("poly"))
             .collect();
 
         ProcessSummary {
@@ -407,6 +594,7 @@ impl ProcessStack {
             via_connections: self.via_stack.len(),
             total_height: self.total_height,
             global_temperature: self.technology_info.global_temperature,
+            fingerprint: self.fingerprint(),
         }
     }
 }
@@ -422,6 +610,8 @@ pub struct ProcessSummary {
     pub via_connections: usize,
     pub total_height: f64,
     pub global_temperature: Option<f64>,
+    /// See [`ProcessStack::fingerprint`]
+    pub fingerprint: String,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -463,6 +653,15 @@ mod tests {
         assert_eq!(tech.reference_direction, Some("VERTICAL".to_string()));
     }
 
+    #[test]
+    fn test_scale_half_node() {
+        let mut tech = TechnologyInfo::new("half_node".to_string());
+        assert_eq!(tech.scale_half_node(0.1), 0.1);
+
+        tech.half_node_scale_factor = Some(0.9);
+        assert_relative_eq!(tech.scale_half_node(0.1), 0.09);
+    }
+
     #[test]
     fn test_process_stack_creation() {
         let tech = TechnologyInfo::new("test_process".to_string());
@@ -505,6 +704,47 @@ mod tests {
         assert_eq!(layer3.get_top_z(), 3.5);
     }
 
+    #[test]
+    fn test_marker_layers() {
+        let tech = TechnologyInfo::new("test_process".to_string());
+        let mut stack = ProcessStack::new(tech);
+
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide1".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "etch_stop".to_string(),
+            0.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "cap_marker".to_string(),
+            -1.0,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide2".to_string(),
+            2.0,
+            4.2,
+        )));
+
+        assert!(stack.get_layer("etch_stop").unwrap().is_marker());
+        assert!(stack.get_layer("cap_marker").unwrap().is_marker());
+        assert!(!stack.get_layer("oxide1").unwrap().is_marker());
+
+        // Markers add no height, a negative sentinel does not lower the layers after it
+        assert_relative_eq!(stack.get_total_height(), 3.0, epsilon = 1e-10);
+        let marker = stack.get_layer("cap_marker").unwrap();
+        assert_eq!(marker.get_bottom_z(), marker.get_top_z());
+        assert_eq!(stack.get_layer("oxide2").unwrap().get_bottom_z(), 1.0);
+        assert!(stack.validate_stack().is_ok());
+        assert_eq!(stack.get_layer_z_range("etch_stop"), Some((2.0, 2.0)));
+
+        stack.create_missing_layer("substrate");
+        assert_relative_eq!(stack.get_layer("substrate").unwrap().thickness(), 3.0);
+    }
+
     #[test]
     fn test_via_addition_and_positioning() {
         let tech = TechnologyInfo::new("test_process".to_string());
@@ -612,6 +852,85 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_layer_mutations() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide1".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.5,
+        ))));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "oxide1".to_string(),
+            "metal1".to_string(),
+            0.04,
+            5.0,
+        ));
+
+        assert_eq!(stack.set_layer_thickness("oxide1", 2.0), Some(1.0));
+        assert_relative_eq!(stack.get_total_height(), 2.5);
+        assert_relative_eq!(stack.get_layer("metal1").unwrap().z_position(), 2.0);
+        assert_eq!(stack.set_layer_thickness("missing", 1.0), None);
+
+        stack.set_source_line("metal1", 12);
+        assert!(stack.rename_layer("metal1", "M1"));
+        assert_eq!(stack.get_source_line("M1"), Some(12));
+        assert_eq!(stack.get_source_line("metal1"), None);
+        assert!(stack.get_layer("metal1").is_none());
+        assert!(stack.get_layer("M1").is_some());
+        assert_eq!(stack.via_stack.vias[0].to_layer, "M1");
+        assert!(!stack.rename_layer("M1", "oxide1"));
+
+        assert_eq!(stack.move_layer("M1", 0), Some(1));
+        assert_eq!(stack.layers[0].name(), "M1");
+        assert_relative_eq!(stack.get_layer("oxide1").unwrap().z_position(), 0.5);
+        assert_eq!(stack.move_layer("M1", 10), Some(0));
+        assert_eq!(stack.layers[1].name(), "M1");
+
+        let replacement = Layer::Dielectric(DielectricLayer::new("oxide1".to_string(), 0.5, 3.0));
+        let previous = stack.replace_layer(replacement).unwrap();
+        assert_relative_eq!(previous.thickness(), 2.0);
+        assert_relative_eq!(stack.get_total_height(), 1.0);
+    }
+
+    #[test]
+    fn test_via_span_warnings() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        for name in ["metal2", "oxide2", "metal1"] {
+            let layer = if name.starts_with("metal") {
+                Layer::Conductor(Box::new(ConductorLayer::new(name.to_string(), 0.5)))
+            } else {
+                Layer::Dielectric(DielectricLayer::new(name.to_string(), 1.0, 4.2))
+            };
+            stack.add_layer(layer);
+        }
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            5.0,
+        ));
+        assert!(stack.get_via_span_warnings().is_empty());
+
+        stack.move_layer("metal1", 1);
+        assert!(stack.get_via_span_warnings().is_empty());
+
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal3".to_string(),
+            0.5,
+        ))));
+        stack.move_layer("metal3", 1);
+        let warnings = stack.get_via_span_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("crosses conductor 'metal3'"));
+    }
+
     #[test]
     fn test_process_summary() {
         let tech = TechnologyInfo::new("test_1p3m".to_string()).with_temperature(85.0);
@@ -651,4 +970,36 @@ mod tests {
         assert_eq!(summary.global_temperature, Some(85.0));
         assert_relative_eq!(summary.total_height, 3.2, epsilon = 1e-10);
     }
+
+    #[test]
+    fn test_layer_z_ranges() {
+        // ITF order, top first; metal1 sits at the bottom of ild, inside it
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "passivation".to_string(),
+            0.5,
+            7.0,
+        )));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "ild".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.4,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "substrate".to_string(),
+            2.0,
+            11.9,
+        )));
+
+        assert_eq!(stack.get_layer_z_range("substrate"), Some((0.0, 2.0)));
+        assert_eq!(stack.get_layer_z_range("ild"), Some((2.0, 3.0)));
+        assert_eq!(stack.get_layer_z_range("metal1"), Some((2.0, 2.4)));
+        assert_eq!(stack.get_layer_z_range("passivation"), Some((3.0, 3.5)));
+        assert_eq!(stack.get_layer_z_range("missing"), None);
+        assert_relative_eq!(stack.get_stack_top_z(), 3.5);
+    }
 }
@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    layer::{ConductorLayer, Layer},
+    stack::ProcessStack,
+    units::Celsius,
+};
+
+/// Temperatures the drift is evaluated over, the automotive grade range
+pub const DRIFT_TEMPERATURE_RANGE: (Celsius, Celsius) = (Celsius(-40.0), Celsius(125.0));
+/// Spacing of the evaluated temperatures in °C
+pub const DRIFT_TEMPERATURE_STEP: f64 = 5.0;
+/// CRT reference temperature of stacks without GLOBAL_TEMPERATURE
+pub const DEFAULT_REFERENCE_TEMPERATURE: Celsius = Celsius(25.0);
+
+/// Relative resistance change in percent for a temperature difference in kelvins
+///
+/// The model of `ConductorLayer::calculate_resistance`: ρ(T) = ρ₀ (1 + CRT1 ΔT + CRT2 ΔT²).
+pub fn resistance_drift_percent(crt1: f64, crt2: f64, delta: f64) -> f64 {
+    100.0 * (crt1 * delta + crt2 * delta * delta)
+}
+
+/// Resistance drift of one conductor over the temperature range
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConductorDrift {
+    pub layer_name: String,
+    /// Drift in percent at each temperature of the summary, for the coefficients below
+    pub drift_percent: Vec<f64>,
+    /// Drift of the largest magnitude, keeping its sign
+    pub worst_drift_percent: f64,
+    pub worst_temperature: Celsius,
+    pub crt1: f64,
+    pub crt2: f64,
+    /// Width of the CRT_VS_SI_WIDTH entry giving the worst drift, `None` for fixed CRT1/CRT2
+    pub crt_width: Option<f64>,
+}
+
+/// Resistance drift of every conductor with temperature coefficients
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperatureDriftSummary {
+    /// Temperature the CRT values are relative to, where the drift is zero
+    pub reference: Celsius,
+    /// Evaluated temperatures, from cold to hot
+    pub temperatures: Vec<Celsius>,
+    /// Conductors in ITF order, top first
+    pub conductors: Vec<ConductorDrift>,
+}
+
+impl TemperatureDriftSummary {
+    /// Conductor drifting the most over the range
+    pub fn worst(&self) -> Option<&ConductorDrift> {
+        self.conductors.iter().max_by(|a, b| {
+            a.worst_drift_percent
+                .abs()
+                .total_cmp(&b.worst_drift_percent.abs())
+        })
+    }
+}
+
+impl ProcessStack {
+    /// Resistance drift of each conductor from -40 °C to 125 °C relative to
+    /// GLOBAL_TEMPERATURE, or 25 °C when the stack has none
+    ///
+    /// Conductors with a CRT_VS_SI_WIDTH table are evaluated at every width of the table
+    /// and report the one drifting the most; conductors without coefficients are left out.
+    pub fn get_temperature_drift(&self) -> TemperatureDriftSummary {
+        let reference = self
+            .technology_info
+            .get_temperature()
+            .unwrap_or(DEFAULT_REFERENCE_TEMPERATURE);
+        let (cold, hot) = DRIFT_TEMPERATURE_RANGE;
+        let steps = ((hot.difference(cold)) / DRIFT_TEMPERATURE_STEP).round() as usize;
+        let temperatures: Vec<Celsius> = (0..=steps)
+            .map(|step| Celsius(cold.celsius() + step as f64 * DRIFT_TEMPERATURE_STEP))
+            .collect();
+
+        let conductors = self
+            .layers
+            .iter()
+            .filter_map(|layer| match layer {
+                Layer::Conductor(conductor) => conductor_drift(conductor, reference, &temperatures),
+                Layer::Dielectric(_) => None,
+            })
+            .collect();
+
+        TemperatureDriftSummary {
+            reference,
+            temperatures,
+            conductors,
+        }
+    }
+}
+
+fn conductor_drift(
+    conductor: &ConductorLayer,
+    reference: Celsius,
+    temperatures: &[Celsius],
+) -> Option<ConductorDrift> {
+    // The coefficients `calculate_resistance` may use, the table taking priority
+    let candidates: Vec<(f64, f64, Option<f64>)> = match &conductor.crt_vs_si_width {
+        Some(table) if !table.widths.is_empty() => table
+            .widths
+            .iter()
+            .zip(&table.crt1_values)
+            .zip(&table.crt2_values)
+            .map(|((&width, &crt1), &crt2)| (crt1, crt2, Some(width)))
+            .collect(),
+        _ => {
+            let props = &conductor.electrical_props;
+            if props.crt1.is_none() && props.crt2.is_none() {
+                return None;
+            }
+            vec![(props.crt1.unwrap_or(0.0), props.crt2.unwrap_or(0.0), None)]
+        }
+    };
+
+    candidates
+        .into_iter()
+        .map(|(crt1, crt2, crt_width)| {
+            let drift_percent: Vec<f64> = temperatures
+                .iter()
+                .map(|temperature| {
+                    resistance_drift_percent(crt1, crt2, temperature.difference(reference))
+                })
+                .collect();
+            let (worst_index, &worst_drift_percent) = drift_percent
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+                .unwrap_or((0, &0.0));
+            ConductorDrift {
+                layer_name: conductor.name.clone(),
+                worst_drift_percent,
+                worst_temperature: temperatures.get(worst_index).copied().unwrap_or(reference),
+                drift_percent,
+                crt1,
+                crt2,
+                crt_width,
+            }
+        })
+        .max_by(|a, b| {
+            a.worst_drift_percent
+                .abs()
+                .total_cmp(&b.worst_drift_percent.abs())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{CrtVsSiWidthTable, DielectricLayer, TechnologyInfo};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_temperature_drift() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("drift".to_string()));
+        let mut metal2 = ConductorLayer::new("metal2".to_string(), 0.3);
+        metal2.electrical_props.crt1 = Some(3.0e-3);
+        metal2.electrical_props.crt2 = Some(-1.0e-6);
+        let mut metal1 = ConductorLayer::new("metal1".to_string(), 0.2);
+        metal1.crt_vs_si_width = Some(CrtVsSiWidthTable::new(
+            vec![0.1, 0.5],
+            vec![2.0e-3, 4.0e-3],
+            vec![0.0, 0.0],
+        ));
+        stack.add_layer(Layer::Conductor(Box::new(metal2)));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd1".to_string(),
+            0.5,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(metal1)));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "poly".to_string(),
+            0.1,
+        ))));
+
+        let summary = stack.get_temperature_drift();
+        assert_eq!(summary.reference, DEFAULT_REFERENCE_TEMPERATURE);
+        assert_eq!(summary.temperatures.len(), 34);
+        assert_eq!(summary.temperatures.last(), Some(&Celsius(125.0)));
+        let names: Vec<&str> = summary
+            .conductors
+            .iter()
+            .map(|drift| drift.layer_name.as_str())
+            .collect();
+        assert_eq!(names, ["metal2", "metal1"], "poly has no coefficients");
+
+        // 100 K above the reference: 0.3 - 0.01 = 0.29, i.e. 29 %
+        let metal2 = &summary.conductors[0];
+        assert_eq!(metal2.worst_temperature, Celsius(125.0));
+        assert_relative_eq!(metal2.worst_drift_percent, 29.0, epsilon = 1e-9);
+        assert_eq!(metal2.crt_width, None);
+        // -40 °C is 65 K below the reference
+        assert_relative_eq!(
+            metal2.drift_percent[0],
+            100.0 * (-3.0e-3 * 65.0 - 1.0e-6 * 65.0 * 65.0),
+            epsilon = 1e-9
+        );
+
+        // The widest table entry has the larger coefficient
+        let metal1 = &summary.conductors[1];
+        assert_eq!(metal1.crt_width, Some(0.5));
+        assert_relative_eq!(metal1.worst_drift_percent, 40.0, epsilon = 1e-9);
+        assert_eq!(summary.worst().unwrap().layer_name, "metal1");
+
+        // The drift is relative to GLOBAL_TEMPERATURE
+        stack.technology_info.global_temperature = Some(125.0);
+        let summary = stack.get_temperature_drift();
+        assert_eq!(summary.conductors[0].worst_temperature, Celsius(-40.0));
+        assert!(summary.conductors[0].worst_drift_percent < 0.0);
+    }
+}
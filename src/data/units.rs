@@ -0,0 +1,279 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// Layers and vias keep plain f64 fields in ITF units for parsing and serialization;
+// calculations wrap them in these types so a length in nm or a temperature difference
+// cannot be passed where a length in um or a temperature is expected.
+
+pub const NM_PER_UM: f64 = 1000.0;
+pub const ANGSTROM_PER_UM: f64 = 10_000.0;
+/// Offset between the Celsius and Kelvin scales
+pub const ZERO_CELSIUS_IN_KELVIN: f64 = 273.15;
+
+/// Write `value` with the precision of the formatter, then `unit`
+fn write_quantity(f: &mut fmt::Formatter<'_>, value: f64, unit: &str) -> fmt::Result {
+    match f.precision() {
+        Some(precision) => write!(f, "{value:.precision$} {unit}"),
+        None => write!(f, "{value} {unit}"),
+    }
+}
+
+/// Adds the arithmetic that keeps the unit: sums and differences of two quantities,
+/// scaling by a plain number and the ratio of two quantities
+macro_rules! quantity_ops {
+    ($quantity:ident) => {
+        impl Add for $quantity {
+            type Output = Self;
+            fn add(self, other: Self) -> Self {
+                Self(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $quantity {
+            type Output = Self;
+            fn sub(self, other: Self) -> Self {
+                Self(self.0 - other.0)
+            }
+        }
+
+        impl Neg for $quantity {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $quantity {
+            type Output = Self;
+            fn mul(self, factor: f64) -> Self {
+                Self(self.0 * factor)
+            }
+        }
+
+        impl Div<f64> for $quantity {
+            type Output = Self;
+            fn div(self, divisor: f64) -> Self {
+                Self(self.0 / divisor)
+            }
+        }
+
+        impl Div for $quantity {
+            type Output = f64;
+            fn div(self, other: Self) -> f64 {
+                self.0 / other.0
+            }
+        }
+
+        impl Sum for $quantity {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self(iter.map(|quantity| quantity.0).sum())
+            }
+        }
+    };
+}
+
+/// Length in micrometers, the length unit of ITF
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Micrometers(pub f64);
+
+impl Micrometers {
+    pub fn from_nm(nm: f64) -> Self {
+        Self(nm / NM_PER_UM)
+    }
+
+    pub fn from_angstrom(angstrom: f64) -> Self {
+        Self(angstrom / ANGSTROM_PER_UM)
+    }
+
+    pub fn um(self) -> f64 {
+        self.0
+    }
+
+    pub fn nm(self) -> f64 {
+        self.0 * NM_PER_UM
+    }
+
+    pub fn angstrom(self) -> f64 {
+        self.0 * ANGSTROM_PER_UM
+    }
+
+    /// Rounded to whole nanometers, the resolution of process dimensions
+    pub fn round_to_nm(self) -> Self {
+        Self::from_nm(self.nm().round())
+    }
+
+    pub fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+}
+
+quantity_ops!(Micrometers);
+
+impl fmt::Display for Micrometers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_quantity(f, self.0, "μm")
+    }
+}
+
+/// Resistance in ohms
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ohms(pub f64);
+
+impl Ohms {
+    pub fn ohms(self) -> f64 {
+        self.0
+    }
+
+    /// Resistance of `count` equal resistors in parallel, infinite for none
+    pub fn parallel(self, count: u32) -> Self {
+        match count {
+            0 => Self(f64::INFINITY),
+            count => Self(self.0 / f64::from(count)),
+        }
+    }
+}
+
+quantity_ops!(Ohms);
+
+impl fmt::Display for Ohms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_quantity(f, self.0, "Ω")
+    }
+}
+
+/// Sheet resistance in ohms per square, as RPSQ
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OhmsPerSquare(pub f64);
+
+impl OhmsPerSquare {
+    /// Sheet resistance of a film `thickness` thick with a bulk resistivity in ohm*um
+    pub fn from_resistivity(resistivity: f64, thickness: Micrometers) -> Self {
+        Self(resistivity / thickness.um())
+    }
+
+    pub fn ohms_per_square(self) -> f64 {
+        self.0
+    }
+
+    /// Bulk resistivity implied by this sheet resistance for a film `thickness` thick
+    pub fn resistivity(self, thickness: Micrometers) -> OhmMicrometers {
+        OhmMicrometers(self.0 * thickness.um())
+    }
+
+    /// Resistance of a line `length` long and `width` wide, one square per width of length
+    pub fn resistance(self, length: Micrometers, width: Micrometers) -> Ohms {
+        Ohms(self.0 * (length / width))
+    }
+}
+
+quantity_ops!(OhmsPerSquare);
+
+impl fmt::Display for OhmsPerSquare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_quantity(f, self.0, "Ω/□")
+    }
+}
+
+/// Bulk resistivity in ohm*um, as in RHO_VS_SI_WIDTH_AND_THICKNESS
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OhmMicrometers(pub f64);
+
+impl OhmMicrometers {
+    pub fn ohm_micrometers(self) -> f64 {
+        self.0
+    }
+}
+
+quantity_ops!(OhmMicrometers);
+
+impl fmt::Display for OhmMicrometers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_quantity(f, self.0, "Ω·μm")
+    }
+}
+
+/// Temperature in degrees Celsius, as GLOBAL_TEMPERATURE and the CRT reference
+///
+/// Differences of two temperatures are plain `f64` kelvins, as used with CRT1 and CRT2.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Celsius(pub f64);
+
+impl Celsius {
+    pub fn from_kelvin(kelvin: f64) -> Self {
+        Self(kelvin - ZERO_CELSIUS_IN_KELVIN)
+    }
+
+    pub fn celsius(self) -> f64 {
+        self.0
+    }
+
+    pub fn kelvin(self) -> f64 {
+        self.0 + ZERO_CELSIUS_IN_KELVIN
+    }
+
+    /// Temperature difference `self - reference` in kelvins
+    pub fn difference(self, reference: Celsius) -> f64 {
+        self.0 - reference.0
+    }
+}
+
+impl fmt::Display for Celsius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_quantity(f, self.0, "°C")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_length_conversions() {
+        let thickness = Micrometers::from_nm(250.0);
+        assert_relative_eq!(thickness.um(), 0.25);
+        assert_relative_eq!(thickness.angstrom(), 2500.0);
+        assert_relative_eq!(Micrometers::from_angstrom(15.0).nm(), 1.5);
+        assert_eq!(Micrometers(0.12345).round_to_nm(), Micrometers(0.123));
+
+        let total: Micrometers = [Micrometers(0.1), Micrometers(0.2)].into_iter().sum();
+        assert_relative_eq!(total.um(), 0.3);
+        assert_relative_eq!((total - Micrometers(0.4)).abs().um(), 0.1);
+        assert_relative_eq!(Micrometers(0.6) / Micrometers(0.2), 3.0);
+        assert_eq!(format!("{:.2}", Micrometers(0.125)), "0.12 μm");
+        assert_eq!(Micrometers(1.5).to_string(), "1.5 μm");
+    }
+
+    #[test]
+    fn test_resistance_units() {
+        let rpsq = OhmsPerSquare::from_resistivity(0.02, Micrometers(0.2));
+        assert_relative_eq!(rpsq.ohms_per_square(), 0.1);
+        let resistance = rpsq.resistance(Micrometers(10.0), Micrometers(0.5));
+        assert_relative_eq!(resistance.ohms(), 2.0);
+        assert_eq!(Ohms(10.0).parallel(4), Ohms(2.5));
+        assert_eq!(Ohms(10.0).parallel(0), Ohms(f64::INFINITY));
+        assert_eq!(format!("{:.3}", resistance), "2.000 Ω");
+        assert_eq!(format!("{rpsq:.1}"), "0.1 Ω/□");
+        let rho = rpsq.resistivity(Micrometers(0.2));
+        assert_relative_eq!(rho.ohm_micrometers(), 0.02);
+        assert_eq!(format!("{rho:.2}"), "0.02 Ω·μm");
+    }
+
+    #[test]
+    fn test_temperature() {
+        let hot = Celsius(125.0);
+        assert_relative_eq!(hot.difference(Celsius(25.0)), 100.0);
+        assert_relative_eq!(Celsius::from_kelvin(hot.kelvin()).celsius(), 125.0);
+        assert_eq!(format!("{:.1}", Celsius(85.0)), "85.0 °C");
+    }
+}
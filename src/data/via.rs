@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+use crate::data::{units::Ohms, PropValue};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ViaConnection {
@@ -12,6 +14,81 @@ pub struct ViaConnection {
     pub resistance_per_via: f64,
     pub z_position: f64,
     pub height: f64,
+    pub array: Option<ViaArray>,
+    /// Properties from the techfile without a dedicated field, keyed in upper case
+    pub extra_properties: HashMap<String, PropValue>,
+}
+
+/// Maximum number of columns/rows used when approximating an array from AREA
+pub const MAX_APPROXIMATED_VIA_CUTS: u32 = 8;
+
+/// A via array or slot bar made of repeated cuts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViaArray {
+    pub columns: u32,
+    pub rows: u32,
+    pub cut_width: f64,
+    pub pitch_x: f64,
+    pub pitch_y: f64,
+    pub approximated: bool,
+}
+
+impl ViaArray {
+    pub fn new(columns: u32, rows: u32, cut_width: f64, pitch_x: f64, pitch_y: f64) -> Self {
+        Self {
+            columns: columns.max(1),
+            rows: rows.max(1),
+            cut_width,
+            pitch_x,
+            pitch_y,
+            approximated: false,
+        }
+    }
+
+    /// Approximate a square array from the via AREA and the area of a single cut
+    ///
+    /// Cuts are assumed to be spaced by their own width, so the pitch is twice the cut width.
+    pub fn from_area(area: f64, unit_cut_area: f64) -> Self {
+        let unit_cut_area = if unit_cut_area > 0.0 {
+            unit_cut_area.min(area)
+        } else {
+            area
+        };
+        let cut_width = unit_cut_area.max(0.0).sqrt();
+
+        let cuts = if unit_cut_area > 0.0 {
+            (area / unit_cut_area).max(1.0)
+        } else {
+            1.0
+        };
+        let per_side = (cuts.sqrt().round() as u32).clamp(1, MAX_APPROXIMATED_VIA_CUTS);
+
+        Self {
+            columns: per_side,
+            rows: per_side,
+            cut_width,
+            pitch_x: cut_width * 2.0,
+            pitch_y: cut_width * 2.0,
+            approximated: true,
+        }
+    }
+
+    pub fn cut_count(&self) -> u32 {
+        self.columns * self.rows
+    }
+
+    /// Total width of the array in the cross-section direction
+    pub fn span_x(&self) -> f64 {
+        (self.columns - 1) as f64 * self.pitch_x + self.cut_width
+    }
+
+    /// Horizontal offsets of each cut center, relative to the array center
+    pub fn cut_offsets_x(&self) -> Vec<f64> {
+        let first = -((self.columns - 1) as f64) * self.pitch_x * 0.5;
+        (0..self.columns)
+            .map(|i| first + i as f64 * self.pitch_x)
+            .collect()
+    }
 }
 
 impl ViaConnection {
@@ -24,9 +101,29 @@ impl ViaConnection {
             resistance_per_via: rpv,
             z_position: 0.0,
             height: 0.0,
+            array: None,
+            extra_properties: HashMap::new(),
         }
     }
 
+    pub fn with_array(mut self, array: ViaArray) -> Self {
+        self.array = Some(array);
+        self
+    }
+
+    /// Get the explicit via array, or approximate one from AREA
+    pub fn get_array(&self, unit_cut_area: f64) -> ViaArray {
+        self.array
+            .clone()
+            .unwrap_or_else(|| ViaArray::from_area(self.area, unit_cut_area))
+    }
+
+    /// Resistance of all cuts of the explicit array in parallel, or of a single via otherwise
+    pub fn get_array_resistance(&self) -> f64 {
+        let cuts = self.array.as_ref().map(|a| a.cut_count()).unwrap_or(1);
+        self.calculate_resistance(cuts)
+    }
+
     pub fn with_geometry(mut self, z_position: f64, height: f64) -> Self {
         self.z_position = z_position;
         self.height = height;
@@ -49,12 +146,13 @@ impl ViaConnection {
         self.height
     }
 
+    pub fn get_resistance_per_via(&self) -> Ohms {
+        Ohms(self.resistance_per_via)
+    }
+
+    /// Resistance of `num_vias` cuts in parallel in ohms, infinite for none
     pub fn calculate_resistance(&self, num_vias: u32) -> f64 {
-        if num_vias == 0 {
-            f64::INFINITY
-        } else {
-            self.resistance_per_via / num_vias as f64
-        }
+        self.get_resistance_per_via().parallel(num_vias).ohms()
     }
 
     pub fn connects_layers(&self, layer1: &str, layer2: &str) -> bool {
@@ -131,6 +229,10 @@ impl ViaStack {
             .unwrap_or_default()
     }
 
+    pub fn get_via(&self, name: &str) -> Option<&ViaConnection> {
+        self.vias.iter().find(|via| via.name == name)
+    }
+
     pub fn get_via_between_layers(&self, layer1: &str, layer2: &str) -> Option<&ViaConnection> {
         self.vias
             .iter()
@@ -186,6 +288,15 @@ impl ViaStack {
         None
     }
 
+    /// Smallest positive via AREA, used as the single cut area when approximating arrays
+    pub fn get_min_via_area(&self) -> Option<f64> {
+        self.vias
+            .iter()
+            .map(|via| via.area)
+            .filter(|&area| area > 0.0)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
     pub fn len(&self) -> usize {
         self.vias.len()
     }
@@ -261,6 +372,58 @@ mod tests {
         assert_eq!(via.calculate_resistance(0), f64::INFINITY);
     }
 
+    #[test]
+    fn test_via_array() {
+        let array = ViaArray::new(4, 2, 0.1, 0.2, 0.3);
+        assert_eq!(array.cut_count(), 8);
+        assert_relative_eq!(array.span_x(), 0.7, epsilon = 1e-10);
+
+        let offsets = array.cut_offsets_x();
+        assert_eq!(offsets.len(), 4);
+        assert_relative_eq!(offsets[0], -0.3, epsilon = 1e-10);
+        assert_relative_eq!(offsets[3], 0.3, epsilon = 1e-10);
+
+        let via = ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.01,
+            8.0,
+        )
+        .with_array(array.clone());
+        assert_eq!(via.get_array(0.01), array);
+        assert_relative_eq!(via.get_array_resistance(), 1.0, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_via_array_from_area() {
+        // A single cut when the via area equals the unit cut area
+        let single = ViaArray::from_area(0.01, 0.01);
+        assert_eq!(single.cut_count(), 1);
+        assert!(single.approximated);
+        assert_relative_eq!(single.cut_width, 0.1, epsilon = 1e-10);
+        assert_relative_eq!(single.pitch_x, 0.2, epsilon = 1e-10);
+
+        // Sixteen unit cuts fit into a 4x4 array
+        let array = ViaArray::from_area(0.16, 0.01);
+        assert_eq!(array.columns, 4);
+        assert_eq!(array.rows, 4);
+
+        // Huge pad vias are clamped
+        let pad = ViaArray::from_area(100.0, 0.01);
+        assert_eq!(pad.columns, MAX_APPROXIMATED_VIA_CUTS);
+
+        let via = ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            5.0,
+        );
+        assert_eq!(via.get_array(0.01).columns, 2);
+        assert_eq!(via.get_array_resistance(), 5.0);
+    }
+
     #[test]
     fn test_via_type_detection() {
         let contact_via = ViaConnection::new(
@@ -320,6 +483,10 @@ mod tests {
         let connection = stack.get_via_between_layers("metal1", "metal2");
         assert!(connection.is_some());
         assert_eq!(connection.unwrap().name, "via1");
+        assert_eq!(stack.get_via("via2").unwrap().from_layer, "metal2");
+        assert!(stack.get_via("via3").is_none());
+
+        assert_eq!(stack.get_min_via_area(), Some(0.04));
     }
 
     #[test]
@@ -0,0 +1,234 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{layer::Layer, naming::NameRole, stack::ProcessStack, via::ViaConnection};
+use thiserror::Error;
+
+/// One level of a via chain: a single cut between two adjacent conductors
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViaChainHop {
+    pub lower_layer: String,
+    pub upper_layer: String,
+    /// Smallest-area via connecting the two conductors, if there is one
+    pub via_name: Option<String>,
+    pub area: f64,
+    /// RPV of the via, or `None` when there is no via or it has no positive RPV
+    pub resistance: Option<f64>,
+}
+
+impl ViaChainHop {
+    pub fn is_connected(&self) -> bool {
+        self.resistance.is_some()
+    }
+}
+
+/// Outcome of [`ProcessStack::via_chain`], hops listed from bottom to top
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ViaChainReport {
+    pub hops: Vec<ViaChainHop>,
+}
+
+impl ViaChainReport {
+    /// Sum of the known hop resistances in ohms
+    pub fn total_resistance(&self) -> f64 {
+        self.hops
+            .iter()
+            .filter_map(|hop| hop.resistance)
+            .fold(0.0, |total, resistance| total + resistance)
+    }
+
+    /// Whether every hop has a via with a usable RPV
+    pub fn is_continuous(&self) -> bool {
+        self.hops.iter().all(ViaChainHop::is_connected)
+    }
+
+    /// Running total after each hop, skipping hops without a resistance
+    pub fn cumulative_resistances(&self) -> Vec<f64> {
+        self.hops
+            .iter()
+            .scan(0.0, |total, hop| {
+                *total += hop.resistance.unwrap_or(0.0);
+                Some(*total)
+            })
+            .collect()
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ViaChainError {
+    #[error("Unknown conductor layer '{0}'")]
+    UnknownConductor(String),
+
+    #[error("Stack has no conductor layers")]
+    NoConductors,
+}
+
+impl ProcessStack {
+    /// Conductors ordered from the bottom of the stack to the top
+    fn conductors_bottom_up(&self) -> Vec<&str> {
+        self.layers
+            .iter()
+            .rev()
+            .filter(|layer| layer.is_conductor())
+            .map(Layer::name)
+            .collect()
+    }
+
+    /// First routing metal and top conductor, the default ends of a via chain
+    ///
+    /// The first metal is a conductor named like METAL1, MET1 or M1, otherwise the
+    /// conductor a contact lands on, otherwise the lowest conductor.
+    pub fn default_via_chain_endpoints(&self) -> Option<(String, String)> {
+        let conductors = self.conductors_bottom_up();
+        let top = conductors.last()?;
+
+        let named_metal1 = conductors
+            .iter()
+            .find(|name| NameRole::recognize(name) == Some(NameRole::Metal(1)));
+        let contact_landing = || {
+            let position = |name: &str| conductors.iter().position(|&c| c == name);
+            self.via_stack
+                .iter()
+                .filter(|via| via.is_contact_via())
+                .filter_map(|via| position(&via.from_layer).max(position(&via.to_layer)))
+                .min()
+                .map(|index| &conductors[index])
+        };
+        let bottom = named_metal1
+            .or_else(contact_landing)
+            .unwrap_or(&conductors[0]);
+
+        Some((bottom.to_string(), top.to_string()))
+    }
+
+    /// Resistance of a chain of single vias between two conductors
+    ///
+    /// Every pair of adjacent conductors between `from` and `to` is one hop, using the
+    /// smallest-area via between them as the 1x1 via of that level. Hops without a via
+    /// or RPV are kept in the report so gaps in the stack are visible.
+    pub fn via_chain(&self, from: &str, to: &str) -> Result<ViaChainReport, ViaChainError> {
+        let conductors = self.conductors_bottom_up();
+        if conductors.is_empty() {
+            return Err(ViaChainError::NoConductors);
+        }
+        let position = |name: &str| {
+            conductors
+                .iter()
+                .position(|&c| c == name)
+                .ok_or_else(|| ViaChainError::UnknownConductor(name.to_string()))
+        };
+        let (from_index, to_index) = (position(from)?, position(to)?);
+        let (bottom, top) = (from_index.min(to_index), from_index.max(to_index));
+
+        let hops = conductors[bottom..=top]
+            .windows(2)
+            .map(|pair| {
+                let via = self.smallest_via_between(pair[0], pair[1]);
+                ViaChainHop {
+                    lower_layer: pair[0].to_string(),
+                    upper_layer: pair[1].to_string(),
+                    via_name: via.map(|via| via.name.clone()),
+                    area: via.map_or(0.0, |via| via.area),
+                    resistance: via
+                        .map(|via| via.resistance_per_via)
+                        .filter(|&rpv| rpv > 0.0),
+                }
+            })
+            .collect();
+
+        Ok(ViaChainReport { hops })
+    }
+
+    /// Via with the smallest positive AREA between two layers, or any connecting via
+    fn smallest_via_between(&self, lower: &str, upper: &str) -> Option<&ViaConnection> {
+        let vias: Vec<&ViaConnection> = self
+            .via_stack
+            .iter()
+            .filter(|via| via.connects_layers(lower, upper))
+            .collect();
+        vias.iter()
+            .filter(|via| via.area > 0.0)
+            .min_by(|a, b| a.area.total_cmp(&b.area))
+            .or_else(|| vias.first())
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+
+    fn create_stack() -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("chain".to_string()));
+        // ITF order, top first
+        for name in ["metal3", "metal2", "metal1", "poly"] {
+            stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+                format!("ild_{name}"),
+                0.5,
+                4.0,
+            )));
+            stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+                name.to_string(),
+                0.2,
+            ))));
+        }
+        stack.add_via(ViaConnection::new(
+            "poly_contact".to_string(),
+            "poly".to_string(),
+            "metal1".to_string(),
+            0.01,
+            20.0,
+        ));
+        stack.add_via(ViaConnection::new(
+            "via1_bar".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            1.0,
+        ));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.01,
+            4.0,
+        ));
+        stack
+    }
+
+    #[test]
+    fn test_via_chain() {
+        let mut stack = create_stack();
+        assert_eq!(
+            stack.default_via_chain_endpoints(),
+            Some(("metal1".to_string(), "metal3".to_string()))
+        );
+
+        // The minimum-area via is the single cut; metal2 to metal3 has no via yet
+        let report = stack.via_chain("metal1", "metal3").unwrap();
+        assert_eq!(report.hops.len(), 2);
+        assert_eq!(report.hops[0].via_name.as_deref(), Some("via1"));
+        assert_eq!(report.hops[0].resistance, Some(4.0));
+        assert!(!report.hops[1].is_connected());
+        assert!(!report.is_continuous());
+
+        stack.add_via(ViaConnection::new(
+            "via2".to_string(),
+            "metal2".to_string(),
+            "metal3".to_string(),
+            0.01,
+            3.0,
+        ));
+        // Endpoints may be given in either order
+        let report = stack.via_chain("metal3", "metal1").unwrap();
+        assert!(report.is_continuous());
+        assert_eq!(report.total_resistance(), 7.0);
+        assert_eq!(report.cumulative_resistances(), vec![4.0, 7.0]);
+
+        assert_eq!(
+            stack.via_chain("ild_metal1", "metal3"),
+            Err(ViaChainError::UnknownConductor("ild_metal1".to_string()))
+        );
+    }
+}
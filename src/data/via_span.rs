@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{layer::Layer, stack::ProcessStack, via::ViaConnection};
+
+/// Largest difference in um between the dielectric span and the via height taken as equal
+pub const VIA_SPAN_TOLERANCE: f64 = 1e-6;
+
+/// A dielectric a via passes through
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedDielectric {
+    pub name: String,
+    pub thickness: f64,
+    /// Part of the thickness inside the via in um, less than `thickness` where the via
+    /// starts or ends within the layer
+    pub crossed: f64,
+}
+
+/// Dielectrics between the two layers a via connects, as drawn in the cross-section
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViaDielectricSpan {
+    /// Dielectrics the via passes through, from the bottom up
+    pub dielectrics: Vec<SpannedDielectric>,
+    /// Top of the lower layer in um above the substrate
+    pub bottom_z: f64,
+    /// Bottom of the upper layer in um above the substrate
+    pub top_z: f64,
+}
+
+impl ViaDielectricSpan {
+    /// Height of the via in um, negative when the two layers overlap
+    pub fn via_height(&self) -> f64 {
+        self.top_z - self.bottom_z
+    }
+
+    /// Sum of the crossed dielectric thicknesses in um
+    pub fn total_span(&self) -> f64 {
+        self.dielectrics
+            .iter()
+            .fold(0.0, |total, dielectric| total + dielectric.crossed)
+    }
+
+    /// Whether the via has a height and the dielectrics fill it
+    pub fn is_consistent(&self) -> bool {
+        self.via_height() > VIA_SPAN_TOLERANCE
+            && (self.total_span() - self.via_height()).abs() <= VIA_SPAN_TOLERANCE
+    }
+}
+
+impl ProcessStack {
+    /// Dielectrics `via` passes through, `None` if one of its layers is missing
+    pub fn get_via_dielectric_span(&self, via: &ViaConnection) -> Option<ViaDielectricSpan> {
+        let ranges = self.get_layer_z_ranges();
+        let from = *ranges.get(&via.from_layer)?;
+        let to = *ranges.get(&via.to_layer)?;
+        let (lower, upper) = if from.0 <= to.0 {
+            (from, to)
+        } else {
+            (to, from)
+        };
+        let (bottom_z, top_z) = (lower.1, upper.0);
+
+        // ITF lists layers from top to bottom
+        let dielectrics = self
+            .layers
+            .iter()
+            .rev()
+            .filter(|layer| matches!(layer, Layer::Dielectric(_)))
+            .filter(|layer| layer.name() != via.from_layer && layer.name() != via.to_layer)
+            .filter_map(|layer| {
+                let (bottom, top) = ranges[layer.name()];
+                let crossed = top.min(top_z) - bottom.max(bottom_z);
+                (crossed > VIA_SPAN_TOLERANCE).then(|| SpannedDielectric {
+                    name: layer.name().to_string(),
+                    thickness: layer.thickness(),
+                    crossed,
+                })
+            })
+            .collect();
+
+        Some(ViaDielectricSpan {
+            dielectrics,
+            bottom_z,
+            top_z,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+
+    fn create_test_stack() -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("span".to_string()));
+        let dielectric = |name: &str, thickness| {
+            Layer::Dielectric(DielectricLayer::new(name.into(), thickness, 4.0))
+        };
+        let conductor = |name: &str, thickness| {
+            Layer::Conductor(Box::new(ConductorLayer::new(name.into(), thickness)))
+        };
+
+        // ITF order, top first
+        stack.add_layer(dielectric("imd2", 0.4));
+        stack.add_layer(conductor("metal2", 0.3));
+        stack.add_layer(dielectric("ild", 0.5));
+        stack.add_layer(dielectric("imd1", 0.25));
+        stack.add_layer(conductor("metal1", 0.2));
+        stack.add_layer(dielectric("oxide", 1.0));
+        stack
+    }
+
+    #[test]
+    fn test_via_dielectric_span() {
+        let stack = create_test_stack();
+        let via = ViaConnection::new("via1".into(), "metal1".into(), "metal2".into(), 0.04, 2.0);
+        let span = stack.get_via_dielectric_span(&via).unwrap();
+        let names: Vec<&str> = span.dielectrics.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["imd1", "ild"]);
+
+        // metal1 fills the bottom 0.2 of imd1, leaving 0.05 for the via
+        assert!((span.dielectrics[0].thickness - 0.25).abs() < 1e-9);
+        assert!((span.dielectrics[0].crossed - 0.05).abs() < 1e-9);
+        assert!((span.bottom_z - 1.2).abs() < 1e-9);
+        assert!((span.top_z - 1.75).abs() < 1e-9);
+        assert!((span.total_span() - 0.55).abs() < 1e-9);
+        assert!(span.is_consistent());
+
+        // The same span when FROM and TO are swapped
+        let reversed =
+            ViaConnection::new("via1r".into(), "metal2".into(), "metal1".into(), 0.04, 2.0);
+        assert_eq!(stack.get_via_dielectric_span(&reversed), Some(span));
+    }
+
+    #[test]
+    fn test_via_dielectric_span_without_height() {
+        let mut stack = create_test_stack();
+
+        // metal1 reaches above the bottom of metal2
+        stack.set_layer_thickness("metal1", 0.9);
+        let via = ViaConnection::new("via1".into(), "metal1".into(), "metal2".into(), 0.04, 2.0);
+        let span = stack.get_via_dielectric_span(&via).unwrap();
+        assert!(span.dielectrics.is_empty());
+        assert!(span.via_height() < 0.0);
+        assert!(!span.is_consistent());
+
+        let missing = ViaConnection::new(
+            "missing".into(),
+            "metal1".into(),
+            "metal9".into(),
+            0.04,
+            2.0,
+        );
+        assert!(stack.get_via_dielectric_span(&missing).is_none());
+    }
+}
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::renderer::{RenderPrimitive, StackLayout};
+use egui::Color32;
+use std::fmt::Write;
+
+/// AutoCAD color index and RGB of the standard colors 1 to 9
+const ACI_COLORS: [(u8, [u8; 3]); 9] = [
+    (1, [255, 0, 0]),
+    (2, [255, 255, 0]),
+    (3, [0, 255, 0]),
+    (4, [0, 255, 255]),
+    (5, [0, 0, 255]),
+    (6, [255, 0, 255]),
+    (7, [255, 255, 255]),
+    (8, [128, 128, 128]),
+    (9, [192, 192, 192]),
+];
+
+/// Write the cross-section as an ASCII DXF (R12) drawing
+///
+/// Every polygon of the layout becomes a closed polyline on a DXF layer named after its
+/// ITF layer or via, colored with the closest standard AutoCAD color. Coordinates are the
+/// layout's world coordinates in um, with y pointing up.
+pub fn export_dxf(layout: &StackLayout) -> String {
+    let primitives = layout.primitives();
+
+    // DXF layers in order of first appearance, with the color of their first polygon
+    let mut layers: Vec<(String, Color32)> = Vec::new();
+    for primitive in &primitives {
+        let name = dxf_layer_name(primitive.base_name());
+        if !layers.iter().any(|(existing, _)| *existing == name) {
+            layers.push((name, primitive.fill_color));
+        }
+    }
+
+    let mut dxf = String::new();
+    group(&mut dxf, 999, "itf-viewer cross-section, units: um");
+
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "HEADER");
+    group(&mut dxf, 9, "$ACADVER");
+    group(&mut dxf, 1, "AC1009");
+    group(&mut dxf, 0, "ENDSEC");
+
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "TABLES");
+    group(&mut dxf, 0, "TABLE");
+    group(&mut dxf, 2, "LAYER");
+    group(&mut dxf, 70, layers.len());
+    for (name, color) in &layers {
+        group(&mut dxf, 0, "LAYER");
+        group(&mut dxf, 2, name);
+        group(&mut dxf, 70, 0);
+        group(&mut dxf, 62, closest_aci_color(*color));
+        group(&mut dxf, 6, "CONTINUOUS");
+    }
+    group(&mut dxf, 0, "ENDTAB");
+    group(&mut dxf, 0, "ENDSEC");
+
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "ENTITIES");
+    for primitive in &primitives {
+        write_polyline(&mut dxf, primitive);
+    }
+    group(&mut dxf, 0, "ENDSEC");
+    group(&mut dxf, 0, "EOF");
+
+    dxf
+}
+
+fn write_polyline(dxf: &mut String, primitive: &RenderPrimitive) {
+    let layer = dxf_layer_name(primitive.base_name());
+
+    group(dxf, 0, "POLYLINE");
+    group(dxf, 8, &layer);
+    group(dxf, 66, 1); // Vertices follow
+    group(dxf, 70, 1); // Closed
+    for point in &primitive.points {
+        group(dxf, 0, "VERTEX");
+        group(dxf, 8, &layer);
+        group(dxf, 10, format!("{:.6}", point.x));
+        group(dxf, 20, format!("{:.6}", point.y));
+        group(dxf, 30, "0.0");
+    }
+    group(dxf, 0, "SEQEND");
+    group(dxf, 8, &layer);
+}
+
+/// One DXF group: the code line followed by the value line
+fn group(dxf: &mut String, code: u16, value: impl std::fmt::Display) {
+    let _ = writeln!(dxf, "{code:>3}\n{value}");
+}
+
+/// R12 layer names allow letters, digits, `$`, `-` and `_` only
+fn dxf_layer_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '$' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        "0".to_string()
+    } else {
+        name
+    }
+}
+
+fn closest_aci_color(color: Color32) -> u8 {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    ACI_COLORS
+        .iter()
+        .min_by_key(|(_, [ar, ag, ab])| {
+            let dr = r as i32 - *ar as i32;
+            let dg = g as i32 - *ag as i32;
+            let db = b as i32 - *ab as i32;
+            dr * dr + dg * dg + db * db
+        })
+        .map(|(index, _)| *index)
+        .unwrap_or(7)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{
+        ConductorLayer, DielectricLayer, Layer, ProcessStack, TechnologyInfo, ViaConnection,
+    };
+    use crate::renderer::StackRenderer;
+
+    #[test]
+    fn test_export_dxf() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("dxf".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd 2".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.4,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd1".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.3,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "substrate".to_string(),
+            2.0,
+            11.9,
+        )));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            5.0,
+        ));
+
+        let layout = StackRenderer::new().layout_stack(&stack, 800.0);
+        let dxf = export_dxf(&layout);
+        let lines: Vec<&str> = dxf.lines().map(str::trim).collect();
+
+        // One closed polyline per polygon, each with its four corners
+        let polylines = lines.iter().filter(|l| **l == "POLYLINE").count();
+        assert_eq!(polylines, layout.primitives().len());
+        assert_eq!(
+            lines.iter().filter(|l| **l == "VERTEX").count(),
+            polylines * 4
+        );
+        assert_eq!(
+            lines.iter().filter(|l| **l == "SECTION").count(),
+            lines.iter().filter(|l| **l == "ENDSEC").count()
+        );
+        assert_eq!(lines.last(), Some(&"EOF"));
+
+        // One DXF layer per ITF layer and via, with invalid characters replaced
+        let table_entries = lines.windows(2).filter(|w| w == &["0", "LAYER"]).count();
+        assert_eq!(table_entries, 6);
+        assert!(lines.contains(&"imd_2"));
+        assert!(lines.contains(&"via1"));
+        assert!(!lines.contains(&"via1_0"));
+    }
+
+    #[test]
+    fn test_closest_aci_color() {
+        assert_eq!(closest_aci_color(Color32::from_rgb(250, 10, 10)), 1);
+        assert_eq!(closest_aci_color(Color32::from_rgb(100, 149, 237)), 9);
+        assert_eq!(closest_aci_color(Color32::from_rgb(255, 140, 0)), 2);
+    }
+}
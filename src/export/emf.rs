@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::export::slide::{
+    layout_slide, slide_font, text_advances, SlideError, SlideItem, SlideOptions, SLIDE_HEIGHT,
+    SLIDE_WIDTH,
+};
+use crate::renderer::StackLayout;
+use egui::{Color32, Pos2};
+
+// Record types, see the [MS-EMF] specification
+const EMR_HEADER: u32 = 1;
+const EMR_EOF: u32 = 14;
+const EMR_SETBKMODE: u32 = 18;
+const EMR_SETTEXTALIGN: u32 = 22;
+const EMR_SETTEXTCOLOR: u32 = 24;
+const EMR_SELECTOBJECT: u32 = 37;
+const EMR_CREATEPEN: u32 = 38;
+const EMR_CREATEBRUSHINDIRECT: u32 = 39;
+const EMR_EXTCREATEFONTINDIRECTW: u32 = 82;
+const EMR_EXTTEXTOUTW: u32 = 84;
+const EMR_POLYGON16: u32 = 86;
+
+const EMF_SIGNATURE: u32 = 0x464D_4520;
+const EMF_VERSION: u32 = 0x0001_0000;
+const HEADER_SIZE: usize = 108;
+
+/// Logical units per point, so coordinates are twips
+const UNITS_PER_POINT: f32 = 20.0;
+/// Hundredths of a millimeter per twip, the text scale of the compatible graphics mode
+const HMM_PER_UNIT: f32 = 2540.0 / 1440.0;
+
+const TRANSPARENT: u32 = 1;
+const TA_BASELINE: u32 = 24;
+const GM_COMPATIBLE: u32 = 1;
+const PS_SOLID: u32 = 0;
+const PS_NULL: u32 = 5;
+const BS_SOLID: u32 = 0;
+
+/// Write the cross-section as a slide in an Enhanced Metafile
+///
+/// The picture is 13.33 x 7.5 inches, a full 16:9 slide, with the layout of
+/// [`layout_slide`]. Shapes stay vectors and text stays editable after PowerPoint or Word
+/// converts the picture to drawing objects.
+pub fn export_emf(layout: &StackLayout, options: &SlideOptions) -> Result<Vec<u8>, SlideError> {
+    let font = slide_font(&options.theme)?;
+    let mut emf = EmfWriter {
+        outline_width: options.theme.outline_width,
+        font_face: options.theme.font_face.clone(),
+        ..EmfWriter::default()
+    };
+    emf.record(EMR_SETBKMODE, &TRANSPARENT.to_le_bytes());
+    emf.record(EMR_SETTEXTALIGN, &TA_BASELINE.to_le_bytes());
+
+    for item in layout_slide(layout, options)? {
+        match item {
+            SlideItem::Polygon {
+                points,
+                fill,
+                outline,
+            } => {
+                let brush = emf.brush(fill);
+                emf.select(brush);
+                let pen = emf.pen(outline);
+                emf.select(pen);
+                emf.polygon(&points);
+            }
+            SlideItem::Text {
+                position,
+                size,
+                text,
+                color,
+            } => {
+                let font_handle = emf.font(size);
+                emf.select(font_handle);
+                if emf.text_color != Some(color) {
+                    emf.record(EMR_SETTEXTCOLOR, &colorref(color).to_le_bytes());
+                    emf.text_color = Some(color);
+                }
+                emf.text(position, &text, &text_advances(&font, &text, size));
+            }
+        }
+    }
+    Ok(emf.finish(&options.title))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EmfObject {
+    Brush(Color32),
+    Pen(Option<Color32>),
+    Font(i32),
+}
+
+/// Records written so far, with the graphics objects they created
+#[derive(Default)]
+struct EmfWriter {
+    records: Vec<u8>,
+    record_count: u32,
+    /// Objects by handle, starting at 1 as handle 0 is the metafile itself
+    objects: Vec<EmfObject>,
+    selected: Vec<u32>,
+    text_color: Option<Color32>,
+    /// Pen width in points
+    outline_width: f32,
+    font_face: String,
+}
+
+impl EmfWriter {
+    fn record(&mut self, record_type: u32, data: &[u8]) {
+        self.records.extend(record_type.to_le_bytes());
+        self.records.extend((8 + data.len() as u32).to_le_bytes());
+        self.records.extend(data);
+        self.record_count += 1;
+    }
+
+    /// Handle of `object`, creating it on first use
+    fn object(&mut self, object: EmfObject) -> u32 {
+        if let Some(index) = self.objects.iter().position(|existing| *existing == object) {
+            return index as u32 + 1;
+        }
+        self.objects.push(object);
+        let handle = self.objects.len() as u32;
+        let mut data = handle.to_le_bytes().to_vec();
+        match object {
+            EmfObject::Brush(color) => {
+                data.extend(BS_SOLID.to_le_bytes());
+                data.extend(colorref(color).to_le_bytes());
+                data.extend(0u32.to_le_bytes());
+                self.record(EMR_CREATEBRUSHINDIRECT, &data);
+            }
+            EmfObject::Pen(color) => {
+                let style = if color.is_some() { PS_SOLID } else { PS_NULL };
+                let width = (self.outline_width * UNITS_PER_POINT).round() as i32;
+                data.extend(style.to_le_bytes());
+                data.extend(width.to_le_bytes());
+                data.extend(0i32.to_le_bytes());
+                data.extend(colorref(color.unwrap_or_default()).to_le_bytes());
+                self.record(EMR_CREATEPEN, &data);
+            }
+            EmfObject::Font(height) => {
+                data.extend(log_font_panose(height, &self.font_face));
+                self.record(EMR_EXTCREATEFONTINDIRECTW, &data);
+            }
+        }
+        handle
+    }
+
+    fn brush(&mut self, color: Color32) -> u32 {
+        self.object(EmfObject::Brush(color))
+    }
+
+    fn pen(&mut self, color: Option<Color32>) -> u32 {
+        self.object(EmfObject::Pen(color))
+    }
+
+    fn font(&mut self, size: f32) -> u32 {
+        // A negative height is the character height without internal leading
+        self.object(EmfObject::Font(-(size * UNITS_PER_POINT).round() as i32))
+    }
+
+    /// Select `handle`, replacing the selected object of the same kind
+    fn select(&mut self, handle: u32) {
+        if self.selected.contains(&handle) {
+            return;
+        }
+        let kind = std::mem::discriminant(&self.objects[handle as usize - 1]);
+        self.selected
+            .retain(|&other| std::mem::discriminant(&self.objects[other as usize - 1]) != kind);
+        self.selected.push(handle);
+        self.record(EMR_SELECTOBJECT, &handle.to_le_bytes());
+    }
+
+    fn polygon(&mut self, points: &[Pos2]) {
+        let points: Vec<(i16, i16)> = points.iter().map(|p| logical(*p)).collect();
+        let mut data = bounds(&points);
+        data.extend((points.len() as u32).to_le_bytes());
+        for (x, y) in points {
+            data.extend(x.to_le_bytes());
+            data.extend(y.to_le_bytes());
+        }
+        self.record(EMR_POLYGON16, &data);
+    }
+
+    fn text(&mut self, position: Pos2, text: &str, advances: &[f32]) {
+        let units: Vec<u16> = text.encode_utf16().collect();
+        // One spacing per UTF-16 unit, none for the second half of a surrogate pair
+        let mut spacing = Vec::with_capacity(units.len());
+        for (c, advance) in text.chars().zip(advances) {
+            spacing.push((advance * UNITS_PER_POINT).round() as i32);
+            spacing.resize(spacing.len() + c.len_utf16() - 1, 0);
+        }
+        let (x, y) = logical(position);
+
+        // The fixed part of the record is 76 bytes, offsets count from its start
+        let string_offset = 76u32;
+        let string_size = (units.len() * 2).next_multiple_of(4) as u32;
+        let mut data = vec![0; 16];
+        data.extend(GM_COMPATIBLE.to_le_bytes());
+        data.extend(HMM_PER_UNIT.to_le_bytes());
+        data.extend(HMM_PER_UNIT.to_le_bytes());
+        data.extend(i32::from(x).to_le_bytes());
+        data.extend(i32::from(y).to_le_bytes());
+        data.extend((units.len() as u32).to_le_bytes());
+        data.extend(string_offset.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend([0; 16]);
+        data.extend((string_offset + string_size).to_le_bytes());
+        for unit in &units {
+            data.extend(unit.to_le_bytes());
+        }
+        data.resize(string_offset as usize - 8 + string_size as usize, 0);
+        for dx in spacing {
+            data.extend(dx.to_le_bytes());
+        }
+        self.record(EMR_EXTTEXTOUTW, &data);
+    }
+
+    /// The metafile with its header and end record, described as `title`
+    fn finish(mut self, title: &str) -> Vec<u8> {
+        // EMR_EOF with no palette entries
+        self.record(EMR_EOF, &[0, 0, 0, 0, 16, 0, 0, 0, 20, 0, 0, 0]);
+
+        let mut description: Vec<u16> = format!("itf-viewer\0{title}\0\0").encode_utf16().collect();
+        if description.len() % 2 == 1 {
+            description.push(0);
+        }
+        let header_size = HEADER_SIZE + description.len() * 2;
+        let total_size = header_size + self.records.len();
+        let width = (SLIDE_WIDTH * UNITS_PER_POINT) as i32;
+        let height = (SLIDE_HEIGHT * UNITS_PER_POINT) as i32;
+        // Millimeters and micrometers of the reference device, one twip per pixel
+        let width_um = (SLIDE_WIDTH / 72.0 * 25_400.0).round() as i32;
+        let height_um = (SLIDE_HEIGHT / 72.0 * 25_400.0).round() as i32;
+
+        let mut emf = Vec::with_capacity(total_size);
+        let mut put = |value: u32| emf.extend(value.to_le_bytes());
+        put(EMR_HEADER);
+        put(header_size as u32);
+        // Bounds in device units, inclusive
+        for value in [0, 0, width - 1, height - 1] {
+            put(value as u32);
+        }
+        // Frame in hundredths of a millimeter
+        let frame_width = (width_um as f32 / 10.0).round() as i32;
+        let frame_height = (height_um as f32 / 10.0).round() as i32;
+        for value in [0, 0, frame_width, frame_height] {
+            put(value as u32);
+        }
+        put(EMF_SIGNATURE);
+        put(EMF_VERSION);
+        put(total_size as u32);
+        put(self.record_count + 1);
+        // nHandles and sReserved
+        put(self.objects.len() as u32 + 1);
+        put(description.len() as u32);
+        put(HEADER_SIZE as u32);
+        put(0);
+        for value in [width, height, width_um / 1000, height_um / 1000] {
+            put(value as u32);
+        }
+        // No pixel format and no OpenGL records
+        for value in [0, 0, 0] {
+            put(value);
+        }
+        for value in [width_um, height_um] {
+            put(value as u32);
+        }
+        for unit in description {
+            emf.extend(unit.to_le_bytes());
+        }
+        emf.extend(self.records);
+        emf
+    }
+}
+
+fn logical(p: Pos2) -> (i16, i16) {
+    (
+        (p.x * UNITS_PER_POINT).round() as i16,
+        (p.y * UNITS_PER_POINT).round() as i16,
+    )
+}
+
+/// Inclusive bounding rectangle of `points`
+fn bounds(points: &[(i16, i16)]) -> Vec<u8> {
+    let (mut left, mut top, mut right, mut bottom) = (i16::MAX, i16::MAX, i16::MIN, i16::MIN);
+    for &(x, y) in points {
+        (left, top) = (left.min(x), top.min(y));
+        (right, bottom) = (right.max(x), bottom.max(y));
+    }
+    [left, top, right, bottom]
+        .into_iter()
+        .flat_map(|value| i32::from(value).to_le_bytes())
+        .collect()
+}
+
+/// `color` as a Windows COLORREF, 0x00BBGGRR
+fn colorref(color: Color32) -> u32 {
+    u32::from_le_bytes([color.r(), color.g(), color.b(), 0])
+}
+
+/// LogFontPanose object of the font named `face` at `height` logical units
+fn log_font_panose(height: i32, face: &str) -> Vec<u8> {
+    let mut data = Vec::with_capacity(320);
+    // LogFont: height, width, escapement, orientation, weight
+    for value in [height, 0, 0, 0, 400] {
+        data.extend(value.to_le_bytes());
+    }
+    // Italic, underline, strike out, default charset, precisions, quality, pitch and family
+    data.extend([0, 0, 0, 1, 0, 0, 0, 0]);
+    data.extend(utf16_field(face, 32));
+    // Full name and style
+    data.extend(utf16_field("", 64));
+    data.extend(utf16_field("", 32));
+    // Version, style size, match, reserved, vendor id, culture and panose
+    data.resize(320, 0);
+    data
+}
+
+/// `text` as a null-padded UTF-16 field of `length` units
+fn utf16_field(text: &str, length: usize) -> Vec<u8> {
+    let mut units: Vec<u16> = text.encode_utf16().take(length - 1).collect();
+    units.resize(length, 0);
+    units.into_iter().flat_map(u16::to_le_bytes).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, Layer, ProcessStack, TechnologyInfo};
+    use crate::renderer::StackRenderer;
+
+    fn read_u32(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_export_emf() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("emf".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide".to_string(),
+            1.0,
+            3.9,
+        )));
+        let layout = StackRenderer::new().layout_stack(&stack, 1200.0);
+        let emf = export_emf(&layout, &SlideOptions::new().with_title("Stack")).unwrap();
+
+        assert_eq!(read_u32(&emf, 0), EMR_HEADER);
+        assert_eq!(read_u32(&emf, 40), EMF_SIGNATURE);
+        assert_eq!(read_u32(&emf, 48) as usize, emf.len());
+        // Frame of a 338.67 x 190.5 mm slide
+        assert_eq!(read_u32(&emf, 32), 33867);
+        assert_eq!(read_u32(&emf, 36), 19050);
+
+        // Walk the records up to EMR_EOF
+        let mut offset = 0;
+        let mut types = Vec::new();
+        while offset < emf.len() {
+            let size = read_u32(&emf, offset + 4) as usize;
+            assert_eq!(size % 4, 0);
+            types.push(read_u32(&emf, offset));
+            offset += size;
+        }
+        assert_eq!(offset, emf.len());
+        assert_eq!(types.len() as u32, read_u32(&emf, 52));
+        assert_eq!(types.last(), Some(&EMR_EOF));
+        assert!(types.contains(&EMR_POLYGON16));
+        // The title and one legend entry
+        assert_eq!(types.iter().filter(|&&t| t == EMR_EXTTEXTOUTW).count(), 2);
+        // The layer and its swatch share a brush, plus a pen and two fonts
+        assert_eq!(read_u32(&emf, 56) & 0xFFFF, 5);
+    }
+
+    #[test]
+    fn test_colorref() {
+        assert_eq!(colorref(Color32::from_rgb(0x12, 0x34, 0x56)), 0x0056_3412);
+        assert_eq!(utf16_field("Arial", 32).len(), 64);
+        assert_eq!(log_font_panose(-220, "Arial").len(), 320);
+    }
+}
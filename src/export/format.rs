@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use std::path::Path;
+
+/// Formats the cross-section can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Dxf,
+    Gds,
+    /// Standalone page with an interactive viewer
+    Html,
+    /// 16:9 slide as an Enhanced Metafile
+    Emf,
+    /// 16:9 slide as a high-resolution PNG image
+    SlidePng,
+}
+
+impl ExportFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Dxf => "DXF",
+            ExportFormat::Gds => "GDSII",
+            ExportFormat::Html => "HTML",
+            ExportFormat::Emf => "EMF",
+            ExportFormat::SlidePng => "PNG",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Dxf => "dxf",
+            ExportFormat::Gds => "gds",
+            ExportFormat::Html => "html",
+            ExportFormat::Emf => "emf",
+            ExportFormat::SlidePng => "png",
+        }
+    }
+
+    /// Slide format offered first on this platform, EMF where Office renders it natively
+    pub fn default_slide() -> Self {
+        if cfg!(windows) {
+            ExportFormat::Emf
+        } else {
+            ExportFormat::SlidePng
+        }
+    }
+
+    pub fn is_slide(&self) -> bool {
+        matches!(self, ExportFormat::Emf | ExportFormat::SlidePng)
+    }
+
+    /// Slide format matching the extension of `path`
+    pub fn slide_from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        [ExportFormat::Emf, ExportFormat::SlidePng]
+            .into_iter()
+            .find(|format| extension.eq_ignore_ascii_case(format.extension()))
+    }
+}
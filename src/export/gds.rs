@@ -0,0 +1,506 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::renderer::StackLayout;
+
+// Record types and their data types, see the GDSII stream format reference
+const HEADER: [u8; 2] = [0x00, 0x02];
+const BGNLIB: [u8; 2] = [0x01, 0x02];
+const LIBNAME: [u8; 2] = [0x02, 0x06];
+const UNITS: [u8; 2] = [0x03, 0x05];
+const ENDLIB: [u8; 2] = [0x04, 0x00];
+const BGNSTR: [u8; 2] = [0x05, 0x02];
+const STRNAME: [u8; 2] = [0x06, 0x06];
+const ENDSTR: [u8; 2] = [0x07, 0x00];
+const BOUNDARY: [u8; 2] = [0x08, 0x00];
+const PATH: [u8; 2] = [0x09, 0x00];
+const LAYER: [u8; 2] = [0x0D, 0x02];
+const DATATYPE: [u8; 2] = [0x0E, 0x02];
+const WIDTH: [u8; 2] = [0x0F, 0x03];
+const XY: [u8; 2] = [0x10, 0x03];
+const ENDEL: [u8; 2] = [0x11, 0x00];
+
+/// Stream format version written to the HEADER record
+const GDS_VERSION: i16 = 600;
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum GdsError {
+    #[error("Layer map line {line}: {message}")]
+    InvalidLayerMap { line: usize, message: String },
+
+    #[error("Coordinate {0} um is outside the GDSII coordinate range")]
+    CoordinateOverflow(f64),
+
+    #[error("Invalid export option: {0}")]
+    InvalidOption(String),
+}
+
+/// Assignment of ITF layer and via names to GDSII layer/datatype pairs
+///
+/// Names are matched case-insensitively. Layers missing from the map get the next free
+/// layer numbers with datatype 0 when exporting.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GdsLayerMap {
+    entries: Vec<(String, u16, u16)>,
+}
+
+impl GdsLayerMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_layer(mut self, name: impl Into<String>, layer: u16, datatype: u16) -> Self {
+        self.set_layer(name, layer, datatype);
+        self
+    }
+
+    pub fn set_layer(&mut self, name: impl Into<String>, layer: u16, datatype: u16) {
+        let name = name.into();
+        self.entries
+            .retain(|(existing, _, _)| !existing.eq_ignore_ascii_case(&name));
+        self.entries.push((name, layer, datatype));
+    }
+
+    pub fn get(&self, name: &str) -> Option<(u16, u16)> {
+        self.entries
+            .iter()
+            .find(|(existing, _, _)| existing.eq_ignore_ascii_case(name))
+            .map(|&(_, layer, datatype)| (layer, datatype))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Parse a layer map with one `NAME LAYER [DATATYPE]` entry per line
+    ///
+    /// `LAYER/DATATYPE` is accepted as well. Blank lines and text after `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self, GdsError> {
+        let mut map = Self::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let error = |message: String| GdsError::InvalidLayerMap {
+                line: index + 1,
+                message,
+            };
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .filter(|field| !field.is_empty())
+                .collect();
+            let (name, numbers) = match fields.split_first() {
+                Some((name, numbers)) if (1..=2).contains(&numbers.len()) => (*name, numbers),
+                _ => {
+                    return Err(error(format!(
+                        "expected NAME LAYER [DATATYPE], got '{line}'"
+                    )))
+                }
+            };
+
+            let parse_number = |text: &str| {
+                text.parse::<u16>()
+                    .map_err(|_| error(format!("invalid number '{text}'")))
+            };
+            let layer = parse_number(numbers[0])?;
+            let datatype = numbers.get(1).map(|n| parse_number(n)).transpose()?;
+            map.set_layer(name, layer, datatype.unwrap_or(0));
+        }
+
+        Ok(map)
+    }
+}
+
+/// Options of the GDSII export
+#[derive(Debug, Clone, PartialEq)]
+pub struct GdsOptions {
+    /// Name of the single cell holding the cross-section
+    pub cell_name: String,
+    pub layer_map: GdsLayerMap,
+    /// Factor applied to the layout coordinates, e.g. to enlarge a schematic drawing
+    pub scale: f64,
+    /// Database unit in um
+    pub database_unit: f64,
+    /// Write every polygon as a zero-width closed PATH instead of a filled BOUNDARY
+    pub outline_only: bool,
+}
+
+impl GdsOptions {
+    pub fn new() -> Self {
+        Self {
+            cell_name: "CROSS_SECTION".to_string(),
+            layer_map: GdsLayerMap::new(),
+            scale: 1.0,
+            database_unit: 0.001,
+            outline_only: false,
+        }
+    }
+
+    pub fn with_cell_name(mut self, cell_name: impl Into<String>) -> Self {
+        self.cell_name = cell_name.into();
+        self
+    }
+
+    pub fn with_layer_map(mut self, layer_map: GdsLayerMap) -> Self {
+        self.layer_map = layer_map;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_database_unit(mut self, database_unit: f64) -> Self {
+        self.database_unit = database_unit;
+        self
+    }
+
+    pub fn with_outline_only(mut self, outline_only: bool) -> Self {
+        self.outline_only = outline_only;
+        self
+    }
+}
+
+impl Default for GdsOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// GDSII layer/datatype for every layer and via of the layout, in drawing order
+///
+/// Names found in the layer map use its entry; the others are numbered upwards from the
+/// highest mapped layer.
+pub fn resolve_gds_layers(
+    layout: &StackLayout,
+    layer_map: &GdsLayerMap,
+) -> Vec<(String, u16, u16)> {
+    let mut next_layer = layer_map
+        .entries
+        .iter()
+        .map(|&(_, layer, _)| layer + 1)
+        .max()
+        .unwrap_or(1);
+    let mut resolved: Vec<(String, u16, u16)> = Vec::new();
+
+    for primitive in layout.primitives() {
+        let name = primitive.base_name();
+        if resolved.iter().any(|(existing, _, _)| existing == name) {
+            continue;
+        }
+        let (layer, datatype) = layer_map.get(name).unwrap_or_else(|| {
+            next_layer += 1;
+            (next_layer - 1, 0)
+        });
+        resolved.push((name.to_string(), layer, datatype));
+    }
+
+    resolved
+}
+
+/// Write the cross-section as a GDSII stream with a single cell
+///
+/// Every polygon of the layout becomes a BOUNDARY on the layer chosen by
+/// `resolve_gds_layers`, or a closed zero-width PATH with `options.outline_only`. Coordinates are the layout's world coordinates in um times
+/// `options.scale`, with y pointing up.
+pub fn export_gds(layout: &StackLayout, options: &GdsOptions) -> Result<Vec<u8>, GdsError> {
+    let positive = |value: f64| value.is_finite() && value > 0.0;
+    if !positive(options.database_unit) || !positive(options.scale) {
+        return Err(GdsError::InvalidOption(
+            "scale and database unit must be positive".to_string(),
+        ));
+    }
+
+    let layers = resolve_gds_layers(layout, &options.layer_map);
+    // Fixed timestamps keep the output reproducible
+    let timestamps: Vec<i16> = [1970, 1, 1, 0, 0, 0].repeat(2);
+
+    let mut gds = Vec::new();
+    write_record(&mut gds, HEADER, &GDS_VERSION.to_be_bytes());
+    write_record(&mut gds, BGNLIB, &int2_data(&timestamps));
+    write_record(&mut gds, LIBNAME, &string_data("ITFVIEWER.DB"));
+    let mut units = gds_real8(options.database_unit).to_vec();
+    units.extend(gds_real8(options.database_unit * 1e-6));
+    write_record(&mut gds, UNITS, &units);
+
+    write_record(&mut gds, BGNSTR, &int2_data(&timestamps));
+    write_record(
+        &mut gds,
+        STRNAME,
+        &string_data(&cell_name(&options.cell_name)),
+    );
+
+    for primitive in layout.primitives() {
+        let (layer, datatype) = layers
+            .iter()
+            .find(|(name, _, _)| name == primitive.base_name())
+            .map(|&(_, layer, datatype)| (layer, datatype))
+            .unwrap_or((0, 0));
+
+        // Boundaries and outlines are closed by repeating the first point
+        let mut xy = Vec::with_capacity((primitive.points.len() + 1) * 8);
+        for point in primitive.points.iter().chain(primitive.points.first()) {
+            for value in [point.x, point.y] {
+                xy.extend(to_database_units(value as f64, options)?.to_be_bytes());
+            }
+        }
+
+        write_record(
+            &mut gds,
+            if options.outline_only { PATH } else { BOUNDARY },
+            &[],
+        );
+        write_record(&mut gds, LAYER, &(layer as i16).to_be_bytes());
+        write_record(&mut gds, DATATYPE, &(datatype as i16).to_be_bytes());
+        if options.outline_only {
+            write_record(&mut gds, WIDTH, &0i32.to_be_bytes());
+        }
+        write_record(&mut gds, XY, &xy);
+        write_record(&mut gds, ENDEL, &[]);
+    }
+
+    write_record(&mut gds, ENDSTR, &[]);
+    write_record(&mut gds, ENDLIB, &[]);
+    Ok(gds)
+}
+
+fn to_database_units(value: f64, options: &GdsOptions) -> Result<i32, GdsError> {
+    let scaled = (value * options.scale / options.database_unit).round();
+    if scaled.abs() > i32::MAX as f64 {
+        return Err(GdsError::CoordinateOverflow(value));
+    }
+    Ok(scaled as i32)
+}
+
+fn write_record(gds: &mut Vec<u8>, record: [u8; 2], data: &[u8]) {
+    let length = (4 + data.len()) as u16;
+    gds.extend(length.to_be_bytes());
+    gds.extend(record);
+    gds.extend(data);
+}
+
+fn int2_data(values: &[i16]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect()
+}
+
+/// ASCII string padded with a NUL to an even length
+fn string_data(text: &str) -> Vec<u8> {
+    let mut data = text.as_bytes().to_vec();
+    if data.len() % 2 == 1 {
+        data.push(0);
+    }
+    data
+}
+
+/// Cell names are limited to letters, digits, `_`, `?` and `$`
+fn cell_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '?' | '$') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        "CROSS_SECTION".to_string()
+    } else {
+        name
+    }
+}
+
+/// GDSII 8-byte real: sign bit, excess-64 base-16 exponent and a 56-bit mantissa
+fn gds_real8(value: f64) -> [u8; 8] {
+    if value == 0.0 {
+        return [0; 8];
+    }
+
+    let sign = if value < 0.0 { 0x80 } else { 0x00 };
+    let mut mantissa = value.abs();
+    let mut exponent: i32 = 64;
+    while mantissa >= 1.0 {
+        mantissa /= 16.0;
+        exponent += 1;
+    }
+    while mantissa < 1.0 / 16.0 {
+        mantissa *= 16.0;
+        exponent -= 1;
+    }
+
+    let mut bits = (mantissa * 2f64.powi(56)).round() as u64;
+    if bits >= 1 << 56 {
+        // Rounding carried into the next hex digit
+        bits >>= 4;
+        exponent += 1;
+    }
+
+    let mut bytes = bits.to_be_bytes();
+    bytes[0] = sign | exponent.clamp(0, 127) as u8;
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{
+        ConductorLayer, DielectricLayer, Layer, ProcessStack, TechnologyInfo, ViaConnection,
+    };
+    use crate::renderer::StackRenderer;
+
+    fn decode_real8(bytes: &[u8]) -> f64 {
+        let sign = if bytes[0] & 0x80 != 0 { -1.0 } else { 1.0 };
+        let exponent = (bytes[0] & 0x7F) as i32 - 64;
+        let mut mantissa_bytes = [0u8; 8];
+        mantissa_bytes[1..].copy_from_slice(&bytes[1..8]);
+        let mantissa = u64::from_be_bytes(mantissa_bytes) as f64 / 2f64.powi(56);
+        sign * mantissa * 16f64.powi(exponent)
+    }
+
+    /// Record types in file order
+    fn record_types(gds: &[u8]) -> Vec<[u8; 2]> {
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while offset < gds.len() {
+            let length = u16::from_be_bytes([gds[offset], gds[offset + 1]]) as usize;
+            records.push([gds[offset + 2], gds[offset + 3]]);
+            offset += length;
+        }
+        assert_eq!(offset, gds.len());
+        records
+    }
+
+    fn create_test_layout() -> StackLayout {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("gds".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd1".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.4,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "ild0".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.3,
+        ))));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            5.0,
+        ));
+        StackRenderer::new().layout_stack(&stack, 800.0)
+    }
+
+    #[test]
+    fn test_gds_real8() {
+        for value in [1.0, 0.001, 1e-9, 0.5, -2.75, 1234.5678] {
+            let decoded = decode_real8(&gds_real8(value));
+            approx::assert_relative_eq!(decoded, value, max_relative = 1e-14);
+        }
+        assert_eq!(gds_real8(0.0), [0; 8]);
+        assert_eq!(gds_real8(1.0), [0x41, 0x10, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_layer_map_parse() {
+        let map = GdsLayerMap::parse(
+            "# name layer datatype\nmetal1 31 0\nMETAL2 32/1  # trailing comment\n\nvia1 51\n",
+        )
+        .unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("metal1"), Some((31, 0)));
+        assert_eq!(map.get("metal2"), Some((32, 1)));
+        assert_eq!(map.get("VIA1"), Some((51, 0)));
+
+        assert!(matches!(
+            GdsLayerMap::parse("metal1\n"),
+            Err(GdsError::InvalidLayerMap { line: 1, .. })
+        ));
+        assert!(matches!(
+            GdsLayerMap::parse("metal1 1\nmetal2 x\n"),
+            Err(GdsError::InvalidLayerMap { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_gds_layers() {
+        let layout = create_test_layout();
+        let map = GdsLayerMap::new().with_layer("metal1", 31, 0);
+        let layers = resolve_gds_layers(&layout, &map);
+
+        assert_eq!(layers.len(), 5);
+        assert!(layers.contains(&("metal1".to_string(), 31, 0)));
+        // Unmapped names are numbered above the highest mapped layer
+        let mut auto: Vec<u16> = layers
+            .iter()
+            .filter(|(name, _, _)| name != "metal1")
+            .map(|&(_, layer, _)| layer)
+            .collect();
+        auto.sort();
+        assert_eq!(auto, vec![32, 33, 34, 35]);
+    }
+
+    #[test]
+    fn test_export_gds() {
+        let layout = create_test_layout();
+        let options = GdsOptions::new()
+            .with_cell_name("my cell")
+            .with_scale(2.0)
+            .with_layer_map(GdsLayerMap::new().with_layer("metal1", 31, 0));
+        let gds = export_gds(&layout, &options).unwrap();
+
+        let records = record_types(&gds);
+        assert_eq!(records.first(), Some(&HEADER));
+        assert_eq!(records.last(), Some(&ENDLIB));
+        let boundaries = records.iter().filter(|r| **r == BOUNDARY).count();
+        assert_eq!(boundaries, layout.primitives().len());
+        assert_eq!(boundaries, records.iter().filter(|r| **r == ENDEL).count());
+
+        // The first polygon is the bottom dielectric, scaled by 2 into nm
+        let first_xy = gds
+            .windows(2)
+            .position(|w| w == XY)
+            .map(|index| index + 2)
+            .unwrap();
+        let y0 = i32::from_be_bytes(gds[first_xy + 4..first_xy + 8].try_into().unwrap());
+        let expected = layout.primitives()[0].points[0].y as f64 * 2.0 / 0.001;
+        assert_eq!(y0, expected.round() as i32);
+
+        assert!(gds.windows(7).any(|w| w == b"my_cell"));
+        assert!(export_gds(&layout, &options.clone().with_scale(0.0)).is_err());
+
+        // Outlines replace every boundary with a zero-width path
+        let outlines = export_gds(&layout, &options.with_outline_only(true)).unwrap();
+        let records = record_types(&outlines);
+        assert!(!records.contains(&BOUNDARY));
+        assert_eq!(
+            records.iter().filter(|r| **r == PATH).count(),
+            layout.primitives().len()
+        );
+        assert_eq!(
+            records.iter().filter(|r| **r == WIDTH).count(),
+            layout.primitives().len()
+        );
+    }
+}
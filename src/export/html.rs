@@ -0,0 +1,258 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{Layer, ProcessStack};
+use crate::renderer::StackLayout;
+use crate::utils::json::Json;
+use std::fmt::Write;
+
+/// Canvas viewer of the embedded `STACK` data: drag to pan, scroll to zoom, hover for details
+const VIEWER_SCRIPT: &str = r##"
+const canvas = document.getElementById("view");
+const tooltip = document.getElementById("tooltip");
+const context = canvas.getContext("2d");
+let scale = 1, offsetX = 0, offsetY = 0, drag = null;
+
+function bounds() {
+  let minX = Infinity, minY = Infinity, maxX = -Infinity, maxY = -Infinity;
+  for (const shape of STACK.shapes) {
+    for (let i = 0; i < shape.points.length; i += 2) {
+      minX = Math.min(minX, shape.points[i]); maxX = Math.max(maxX, shape.points[i]);
+      minY = Math.min(minY, shape.points[i + 1]); maxY = Math.max(maxY, shape.points[i + 1]);
+    }
+  }
+  return { minX, minY, maxX, maxY };
+}
+
+function fit() {
+  const b = bounds();
+  if (!isFinite(b.minX)) return;
+  scale = 0.9 * Math.min(canvas.width / (b.maxX - b.minX || 1), canvas.height / (b.maxY - b.minY || 1));
+  offsetX = canvas.width / 2 - scale * (b.minX + b.maxX) / 2;
+  offsetY = canvas.height / 2 + scale * (b.minY + b.maxY) / 2;
+}
+
+// World y points up, screen y down
+const toScreen = (x, y) => [offsetX + x * scale, offsetY - y * scale];
+const toWorld = (x, y) => [(x - offsetX) / scale, (offsetY - y) / scale];
+
+function draw() {
+  context.clearRect(0, 0, canvas.width, canvas.height);
+  context.lineWidth = 0.5;
+  context.strokeStyle = "#404040";
+  for (const shape of STACK.shapes) {
+    context.beginPath();
+    for (let i = 0; i < shape.points.length; i += 2) {
+      const [x, y] = toScreen(shape.points[i], shape.points[i + 1]);
+      if (i === 0) context.moveTo(x, y); else context.lineTo(x, y);
+    }
+    context.closePath();
+    context.fillStyle = shape.color;
+    context.fill();
+    context.stroke();
+  }
+}
+
+function contains(points, x, y) {
+  let inside = false;
+  for (let i = 0, j = points.length - 2; i < points.length; j = i, i += 2) {
+    const [xi, yi, xj, yj] = [points[i], points[i + 1], points[j], points[j + 1]];
+    if ((yi > y) !== (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi) inside = !inside;
+  }
+  return inside;
+}
+
+function resize() {
+  canvas.width = window.innerWidth;
+  canvas.height = window.innerHeight - canvas.offsetTop;
+  fit();
+  draw();
+}
+
+canvas.addEventListener("mousedown", (event) => { drag = [event.clientX, event.clientY]; });
+window.addEventListener("mouseup", () => { drag = null; });
+canvas.addEventListener("dblclick", () => { fit(); draw(); });
+canvas.addEventListener("wheel", (event) => {
+  event.preventDefault();
+  const factor = Math.exp(-event.deltaY * 0.002);
+  offsetX = event.offsetX - (event.offsetX - offsetX) * factor;
+  offsetY = event.offsetY - (event.offsetY - offsetY) * factor;
+  scale *= factor;
+  draw();
+}, { passive: false });
+canvas.addEventListener("mousemove", (event) => {
+  if (drag) {
+    offsetX += event.clientX - drag[0];
+    offsetY += event.clientY - drag[1];
+    drag = [event.clientX, event.clientY];
+    draw();
+  }
+  // Vias are drawn last, so the last hit is the shape on top
+  const [x, y] = toWorld(event.offsetX, event.offsetY);
+  const hit = STACK.shapes.filter((shape) => contains(shape.points, x, y)).pop();
+  if (!hit) {
+    tooltip.style.display = "none";
+    return;
+  }
+  tooltip.textContent = [hit.name, ...(STACK.details[hit.name] || [])].join("\n");
+  tooltip.style.left = event.pageX + 12 + "px";
+  tooltip.style.top = event.pageY + 12 + "px";
+  tooltip.style.display = "block";
+});
+canvas.addEventListener("mouseleave", () => { tooltip.style.display = "none"; });
+window.addEventListener("resize", resize);
+resize();
+"##;
+
+/// Write the cross-section as a single HTML page that explores it without the application
+///
+/// The page embeds the polygons of the layout as JSON and a small canvas script with pan,
+/// zoom and hover tooltips listing the key values of each layer and via of `stack`.
+/// Coordinates are the layout's world coordinates, so the page shows the view as exported.
+pub fn export_html(layout: &StackLayout, stack: &ProcessStack, title: &str) -> String {
+    let shapes = layout
+        .primitives()
+        .into_iter()
+        .map(|primitive| {
+            let [r, g, b, a] = primitive.fill_color.to_srgba_unmultiplied();
+            let points = primitive
+                .points
+                .iter()
+                .flat_map(|point| [point.x, point.y])
+                // Float noise of f32 would only bloat the page
+                .map(|value| (value as f64 * 1e6).round() / 1e6)
+                .collect::<Vec<_>>();
+            Json::Object(vec![
+                ("name".to_string(), Json::string(primitive.base_name())),
+                ("kind".to_string(), Json::string(primitive.kind.label())),
+                (
+                    "color".to_string(),
+                    Json::string(format!("rgba({r}, {g}, {b}, {:.3})", a as f32 / 255.0)),
+                ),
+                ("points".to_string(), Json::numbers(&points)),
+            ])
+        })
+        .collect();
+    let data = Json::Object(vec![
+        ("title".to_string(), Json::string(title)),
+        ("shapes".to_string(), Json::Array(shapes)),
+        ("details".to_string(), details(stack)),
+    ]);
+    // A name containing `</script>` must not end the script early
+    let data = data.to_json().replace("</", "<\\/");
+
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>{}</title>", escape_html(title));
+    html.push_str(
+        "<style>\n\
+         body { margin: 0; font-family: sans-serif; overflow: hidden; }\n\
+         header { padding: 6px 10px; border-bottom: 1px solid #ccc; }\n\
+         header span { color: #666; font-size: small; margin-left: 1em; }\n\
+         canvas { display: block; cursor: grab; }\n\
+         #tooltip { position: absolute; display: none; white-space: pre; pointer-events: none; \
+         background: rgba(0, 0, 0, 0.8); color: #fff; padding: 4px 8px; border-radius: 4px; \
+         font-size: small; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    let _ = writeln!(
+        html,
+        "<header><strong>{}</strong><span>Drag to pan, scroll to zoom, \
+         double-click to fit</span></header>",
+        escape_html(title)
+    );
+    html.push_str("<canvas id=\"view\"></canvas>\n<div id=\"tooltip\"></div>\n<script>\n");
+    let _ = write!(html, "const STACK = {};", data.trim_end());
+    html.push_str(VIEWER_SCRIPT);
+    html.push_str("</script>\n</body>\n</html>\n");
+    html
+}
+
+/// Tooltip lines of each layer and via, keyed by name
+fn details(stack: &ProcessStack) -> Json {
+    let layers = stack.layers.iter().map(|layer| {
+        let mut lines = vec![format!("Thickness: {} um", layer.thickness())];
+        match layer {
+            Layer::Dielectric(dielectric) => {
+                lines.insert(0, "Dielectric".to_string());
+                lines.push(format!("ER: {}", dielectric.dielectric_constant));
+            }
+            Layer::Conductor(conductor) => {
+                lines.insert(0, "Conductor".to_string());
+                if let Some(rpsq) = conductor.electrical_props.rpsq {
+                    lines.push(format!("RPSQ: {rpsq} ohm/sq"));
+                }
+                if let Some(width_min) = conductor.physical_props.width_min {
+                    lines.push(format!("WMIN: {width_min} um"));
+                }
+            }
+        }
+        (layer.name().to_string(), lines)
+    });
+    let vias = stack.via_stack.iter().map(|via| {
+        let lines = vec![
+            format!("Via from {} to {}", via.from_layer, via.to_layer),
+            format!("AREA: {} um²", via.area),
+            format!("RPV: {} ohm", via.resistance_per_via),
+        ];
+        (via.name.clone(), lines)
+    });
+    Json::Object(
+        layers
+            .chain(vias)
+            .map(|(name, lines)| {
+                let lines = lines.into_iter().map(Json::String).collect();
+                (name, Json::Array(lines))
+            })
+            .collect(),
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo, ViaConnection};
+    use crate::renderer::StackRenderer;
+
+    #[test]
+    fn test_export_html() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("html".to_string()));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.4,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd</script>".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.3,
+        ))));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            5.0,
+        ));
+        let layout = StackRenderer::new().layout_stack(&stack, 800.0);
+
+        let html = export_html(&layout, &stack, "A & B");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>A &amp; B</title>"));
+        assert!(html.contains("\"name\": \"via1\""));
+        assert!(html.contains("\"RPV: 5 ohm\""));
+        assert!(html.contains("\"ER: 4.2\""));
+        // Only the viewer's own script tag closes
+        assert_eq!(html.matches("</script>").count(), 1);
+        assert!(html.contains("imd<\\/script>"));
+    }
+}
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+pub mod dxf;
+pub mod emf;
+pub mod format;
+pub mod gds;
+pub mod html;
+pub mod png;
+pub mod slide;
+pub mod theme;
+
+pub use dxf::*;
+pub use emf::*;
+pub use format::*;
+pub use gds::*;
+pub use html::*;
+pub use png::*;
+pub use slide::*;
+pub use theme::*;
@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::export::slide::{
+    layout_slide, slide_font, text_advances, SlideError, SlideItem, SlideOptions, MAX_SLIDE_SCALE,
+};
+use crate::renderer::StackLayout;
+use ab_glyph::{Font, FontArc, OutlineCurve, PxScale, ScaleFont};
+use egui::{Color32, ColorImage, Pos2};
+use tiny_skia::{FillRule, Paint, Path, PathBuilder, Pixmap, Stroke, Transform};
+
+/// Render the cross-section as a slide image, `options.scale` pixels per point
+pub fn render_slide(
+    layout: &StackLayout,
+    options: &SlideOptions,
+) -> Result<ColorImage, SlideError> {
+    let pixmap = render_pixmap(layout, options)?;
+    // The background is opaque, so premultiplied and straight alpha are the same
+    Ok(ColorImage::from_rgba_unmultiplied(
+        [pixmap.width() as usize, pixmap.height() as usize],
+        pixmap.data(),
+    ))
+}
+
+/// Render the cross-section as a slide and encode it as PNG
+pub fn export_slide_png(
+    layout: &StackLayout,
+    options: &SlideOptions,
+) -> Result<Vec<u8>, SlideError> {
+    render_pixmap(layout, options)?
+        .encode_png()
+        .map_err(|e| SlideError::Png(e.to_string()))
+}
+
+fn render_pixmap(layout: &StackLayout, options: &SlideOptions) -> Result<Pixmap, SlideError> {
+    if !(options.scale.is_finite() && options.scale > 0.0 && options.scale <= MAX_SLIDE_SCALE) {
+        return Err(SlideError::InvalidOption(format!(
+            "scale must be above 0 and at most {MAX_SLIDE_SCALE}"
+        )));
+    }
+    let [width, height] = options.pixel_size();
+    let mut pixmap = Pixmap::new(width as u32, height as u32)
+        .ok_or_else(|| SlideError::InvalidOption("scale is too small".to_string()))?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+
+    let font = slide_font(&options.theme)?;
+    let transform = Transform::from_scale(options.scale, options.scale);
+    let stroke = Stroke {
+        width: options.theme.outline_width,
+        ..Stroke::default()
+    };
+    for item in layout_slide(layout, options)? {
+        match item {
+            SlideItem::Polygon {
+                points,
+                fill,
+                outline,
+            } => {
+                let Some(path) = polygon_path(&points) else {
+                    continue;
+                };
+                pixmap.fill_path(&path, &paint(fill), FillRule::Winding, transform, None);
+                if let Some(outline) = outline {
+                    pixmap.stroke_path(&path, &paint(outline), &stroke, transform, None);
+                }
+            }
+            SlideItem::Text {
+                position,
+                size,
+                text,
+                color,
+            } => {
+                if let Some(path) = text_path(&font, position, size, &text) {
+                    pixmap.fill_path(&path, &paint(color), FillRule::Winding, transform, None);
+                }
+            }
+        }
+    }
+    Ok(pixmap)
+}
+
+fn paint(color: Color32) -> Paint<'static> {
+    let mut paint = Paint::default();
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    paint.set_color_rgba8(r, g, b, a);
+    paint.anti_alias = true;
+    paint
+}
+
+fn polygon_path(points: &[Pos2]) -> Option<Path> {
+    let (first, rest) = points.split_first()?;
+    let mut builder = PathBuilder::new();
+    builder.move_to(first.x, first.y);
+    for p in rest {
+        builder.line_to(p.x, p.y);
+    }
+    builder.close();
+    builder.finish()
+}
+
+/// Glyph outlines of `text` as one path, so text scales with the slide like the polygons
+fn text_path(font: &FontArc, position: Pos2, size: f32, text: &str) -> Option<Path> {
+    let factor = font.as_scaled(PxScale::from(size)).scale_factor();
+    let mut builder = PathBuilder::new();
+    let mut x = position.x;
+    for (c, advance) in text.chars().zip(text_advances(font, text, size)) {
+        if let Some(outline) = font.outline(font.glyph_id(c)) {
+            // Font units have y pointing up from the baseline
+            let map = |p: ab_glyph::Point| {
+                (
+                    x + p.x * factor.horizontal,
+                    position.y - p.y * factor.vertical,
+                )
+            };
+            let mut contour_end = None;
+            for curve in &outline.curves {
+                let (start, end) = match *curve {
+                    OutlineCurve::Line(start, end)
+                    | OutlineCurve::Quad(start, _, end)
+                    | OutlineCurve::Cubic(start, _, _, end) => (start, end),
+                };
+                if contour_end != Some(start) {
+                    if contour_end.is_some() {
+                        builder.close();
+                    }
+                    let (sx, sy) = map(start);
+                    builder.move_to(sx, sy);
+                }
+                match *curve {
+                    OutlineCurve::Line(_, end) => {
+                        let (ex, ey) = map(end);
+                        builder.line_to(ex, ey);
+                    }
+                    OutlineCurve::Quad(_, control, end) => {
+                        let ((cx, cy), (ex, ey)) = (map(control), map(end));
+                        builder.quad_to(cx, cy, ex, ey);
+                    }
+                    OutlineCurve::Cubic(_, control1, control2, end) => {
+                        let ((c1x, c1y), (c2x, c2y)) = (map(control1), map(control2));
+                        let (ex, ey) = map(end);
+                        builder.cubic_to(c1x, c1y, c2x, c2y, ex, ey);
+                    }
+                }
+                contour_end = Some(end);
+            }
+            if contour_end.is_some() {
+                builder.close();
+            }
+        }
+        x += advance;
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, Layer, ProcessStack, TechnologyInfo};
+    use crate::export::RenderTheme;
+    use crate::renderer::StackRenderer;
+
+    fn create_test_layout() -> StackLayout {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("png".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide".to_string(),
+            1.0,
+            3.9,
+        )));
+        StackRenderer::new().layout_stack(&stack, 1200.0)
+    }
+
+    #[test]
+    fn test_export_slide_png() {
+        let layout = create_test_layout();
+        let options = SlideOptions::new().with_title("Slide").with_scale(0.5);
+        let png = export_slide_png(&layout, &options).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        // IHDR holds the size
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 480);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 270);
+
+        let image = render_slide(&layout, &options).unwrap();
+        assert_eq!(image.size, [480, 270]);
+        assert_eq!(image.pixels[0], Color32::WHITE);
+        // The title and the cross-section are drawn
+        assert!(image.pixels.iter().any(|pixel| pixel.r() < 128));
+        assert!(image
+            .pixels
+            .iter()
+            .any(|pixel| *pixel != Color32::WHITE && pixel.r() != pixel.g()));
+
+        assert!(matches!(
+            export_slide_png(&layout, &options.clone().with_scale(0.0)),
+            Err(SlideError::InvalidOption(_))
+        ));
+
+        let dark = render_slide(&layout, &options.with_theme(RenderTheme::dark())).unwrap();
+        assert_eq!(dark.pixels[0], Color32::from_gray(30));
+    }
+}
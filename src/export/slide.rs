@@ -0,0 +1,455 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::export::theme::{LabelDensity, RenderTheme};
+use crate::renderer::{exaggeration_label, PrimitiveKind, StackLayout};
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use egui::{Color32, Pos2, Rect};
+use std::sync::OnceLock;
+
+/// Size of a 16:9 widescreen slide in points, 13.33 x 7.5 inches
+pub const SLIDE_WIDTH: f32 = 960.0;
+pub const SLIDE_HEIGHT: f32 = 540.0;
+
+/// Largest pixels per point of raster slides, 8 gives 7680 x 4320
+pub const MAX_SLIDE_SCALE: f32 = 8.0;
+
+const MARGIN: f32 = 24.0;
+const TITLE_GAP: f32 = 16.0;
+/// Legend row height and swatch size relative to the text size
+const LEGEND_ROW_HEIGHT: f32 = 16.0 / 11.0;
+const SWATCH_SIZE: f32 = 10.0 / 11.0;
+const LEGEND_GAP: f32 = 16.0;
+/// Space between a layer label and the left edge of its layer
+const LABEL_PADDING: f32 = 4.0;
+
+/// What goes on a slide besides the cross-section
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlideOptions {
+    /// Drawn at the top left, none when empty
+    pub title: String,
+    /// Whether the layers and vias are listed with their colors right of the cross-section
+    pub legend: bool,
+    /// Pixels per point of raster output, 4 gives 3840 x 2160
+    pub scale: f32,
+    pub theme: RenderTheme,
+    /// Stated below the cross-section when its thicknesses are not to scale
+    pub vertical_exaggeration: Option<f32>,
+}
+
+impl Default for SlideOptions {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            legend: true,
+            scale: 4.0,
+            theme: RenderTheme::default(),
+            vertical_exaggeration: None,
+        }
+    }
+}
+
+impl SlideOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: RenderTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_vertical_exaggeration(mut self, exaggeration: Option<f32>) -> Self {
+        self.vertical_exaggeration = exaggeration;
+        self
+    }
+
+    /// Pixel size of raster output
+    pub fn pixel_size(&self) -> [usize; 2] {
+        [
+            (SLIDE_WIDTH * self.scale).round() as usize,
+            (SLIDE_HEIGHT * self.scale).round() as usize,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SlideError {
+    #[error("Invalid export option: {0}")]
+    InvalidOption(String),
+
+    #[error("The slide font could not be loaded")]
+    Font,
+
+    #[error("PNG encoding failed: {0}")]
+    Png(String),
+}
+
+/// One element of a slide, in points from the top left corner
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlideItem {
+    Polygon {
+        points: Vec<Pos2>,
+        fill: Color32,
+        outline: Option<Color32>,
+    },
+    /// Single line of text starting at `position` on its baseline
+    Text {
+        position: Pos2,
+        size: f32,
+        text: String,
+        color: Color32,
+    },
+}
+
+/// Lay out the cross-section, title and legend on a slide, in drawing order
+///
+/// The cross-section is scaled to fit the space left by the title and legend, keeping its
+/// aspect ratio. Colors are made opaque over the theme background, as presentation tools
+/// handle transparency poorly. A background other than white is drawn as the first item.
+pub fn layout_slide(
+    layout: &StackLayout,
+    options: &SlideOptions,
+) -> Result<Vec<SlideItem>, SlideError> {
+    let theme = &options.theme;
+    let font = slide_font(theme)?;
+    let primitives = layout.primitives();
+    let mut items = Vec::new();
+    let outline = Some(theme.outline_color).filter(|_| theme.outline_width > 0.0);
+    let row_height = theme.text_size * LEGEND_ROW_HEIGHT;
+    let swatch_size = theme.text_size * SWATCH_SIZE;
+
+    let mut top = MARGIN;
+    if !options.title.is_empty() {
+        items.push(SlideItem::Text {
+            position: Pos2::new(MARGIN, top + theme.title_size * 0.8),
+            size: theme.title_size,
+            text: options.title.clone(),
+            color: theme.text_color,
+        });
+        top += theme.title_size + TITLE_GAP;
+    }
+    let mut bottom = SLIDE_HEIGHT - MARGIN;
+    if let Some(exaggeration) = options.vertical_exaggeration {
+        items.push(SlideItem::Text {
+            position: Pos2::new(MARGIN, bottom),
+            size: theme.text_size,
+            text: exaggeration_label(exaggeration),
+            color: theme.text_color,
+        });
+        bottom -= theme.text_size * LEGEND_ROW_HEIGHT;
+    }
+
+    // Legend entries with the color of their first polygon, top of the stack first
+    let mut entries: Vec<(&str, Color32, f32)> = Vec::new();
+    for primitive in &primitives {
+        let top_z = primitive
+            .points
+            .iter()
+            .fold(f32::NEG_INFINITY, |top_z, p| top_z.max(p.y));
+        match entries
+            .iter_mut()
+            .find(|(name, _, _)| *name == primitive.base_name())
+        {
+            Some(entry) => entry.2 = entry.2.max(top_z),
+            None => entries.push((primitive.base_name(), primitive.fill_color, top_z)),
+        }
+    }
+    entries.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let mut content_right = SLIDE_WIDTH - MARGIN;
+    if options.legend && !entries.is_empty() {
+        let rows = (((bottom - top) / row_height) as usize).max(1);
+        let column_width = entries
+            .iter()
+            .map(|(name, _, _)| text_width(&font, name, theme.text_size))
+            .fold(0.0, f32::max)
+            + swatch_size * 1.6
+            + LEGEND_GAP;
+        let columns = entries.len().div_ceil(rows);
+        let legend_left = SLIDE_WIDTH - MARGIN - columns as f32 * column_width + LEGEND_GAP;
+        content_right = legend_left - LEGEND_GAP;
+
+        for (index, (name, color, _)) in entries.iter().enumerate() {
+            let x = legend_left + (index / rows) as f32 * column_width;
+            let y = top + (index % rows) as f32 * row_height;
+            let swatch = Rect::from_min_size(
+                Pos2::new(x, y + (row_height - swatch_size) * 0.5),
+                egui::vec2(swatch_size, swatch_size),
+            );
+            items.push(SlideItem::Polygon {
+                points: vec![
+                    swatch.left_top(),
+                    swatch.right_top(),
+                    swatch.right_bottom(),
+                    swatch.left_bottom(),
+                ],
+                fill: opaque(*color, theme.background),
+                outline,
+            });
+            items.push(SlideItem::Text {
+                position: Pos2::new(x + swatch_size * 1.6, y + row_height * 0.72),
+                size: theme.text_size,
+                text: name.to_string(),
+                color: theme.text_color,
+            });
+        }
+    }
+
+    // World coordinates have z pointing up, slide coordinates y pointing down
+    let bounds = primitives
+        .iter()
+        .flat_map(|primitive| &primitive.points)
+        .fold(Rect::NOTHING, |bounds, p| bounds.union(Rect::from_pos(*p)));
+    let area = Rect::from_min_max(Pos2::new(MARGIN, top), Pos2::new(content_right, bottom));
+    if bounds.is_positive() && area.is_positive() {
+        let scale = (area.width() / bounds.width()).min(area.height() / bounds.height());
+        let to_slide = |p: Pos2| {
+            Pos2::new(
+                area.center().x + (p.x - bounds.center().x) * scale,
+                area.center().y - (p.y - bounds.center().y) * scale,
+            )
+        };
+        let polygons: Vec<_> = primitives
+            .iter()
+            .map(|primitive| SlideItem::Polygon {
+                points: primitive.points.iter().copied().map(to_slide).collect(),
+                fill: opaque(primitive.fill_color, theme.background),
+                outline,
+            })
+            .collect();
+
+        // Labels on the first polygon of each layer, where the name fits inside it
+        let mut labels = Vec::new();
+        let mut labeled: Vec<&str> = Vec::new();
+        for primitive in &primitives {
+            let wanted = match theme.label_density {
+                LabelDensity::None => false,
+                LabelDensity::Conductors => primitive.kind == PrimitiveKind::Conductor,
+                LabelDensity::All => primitive.kind != PrimitiveKind::Via,
+            };
+            if !wanted || labeled.contains(&primitive.base_name()) {
+                continue;
+            }
+            labeled.push(primitive.base_name());
+            let rect = primitive.points.iter().fold(Rect::NOTHING, |rect, p| {
+                rect.union(Rect::from_pos(to_slide(*p)))
+            });
+            let width = text_width(&font, primitive.base_name(), theme.text_size);
+            if rect.height() >= theme.text_size && rect.width() >= width + 2.0 * LABEL_PADDING {
+                labels.push(SlideItem::Text {
+                    position: Pos2::new(
+                        rect.left() + LABEL_PADDING,
+                        rect.center().y + theme.text_size * 0.35,
+                    ),
+                    size: theme.text_size,
+                    text: primitive.base_name().to_string(),
+                    color: theme.text_color,
+                });
+            }
+        }
+        // The cross-section goes below the title and legend
+        items.splice(0..0, polygons.into_iter().chain(labels));
+    }
+
+    if theme.background != Color32::WHITE {
+        let slide = Rect::from_min_size(Pos2::ZERO, egui::vec2(SLIDE_WIDTH, SLIDE_HEIGHT));
+        items.insert(
+            0,
+            SlideItem::Polygon {
+                points: vec![
+                    slide.left_top(),
+                    slide.right_top(),
+                    slide.right_bottom(),
+                    slide.left_bottom(),
+                ],
+                fill: theme.background,
+                outline: None,
+            },
+        );
+    }
+    Ok(items)
+}
+
+/// Font used to measure and draw slide text: the theme font, else the egui proportional font
+pub(crate) fn slide_font(theme: &RenderTheme) -> Result<FontArc, SlideError> {
+    static FONT: OnceLock<Option<FontArc>> = OnceLock::new();
+    if let Some(data) = &theme.font_data {
+        return FontArc::try_from_vec(data.to_vec()).map_err(|_| SlideError::Font);
+    }
+    FONT.get_or_init(|| {
+        let definitions = egui::FontDefinitions::default();
+        let data = definitions.font_data.get("Ubuntu-Light")?;
+        FontArc::try_from_vec(data.font.to_vec()).ok()
+    })
+    .clone()
+    .ok_or(SlideError::Font)
+}
+
+/// Advance in points of every character of `text`, including kerning with the next one
+pub(crate) fn text_advances(font: &FontArc, text: &str, size: f32) -> Vec<f32> {
+    let font = font.as_scaled(PxScale::from(size));
+    let mut advances: Vec<f32> = Vec::new();
+    let mut previous = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let (Some(previous), Some(last)) = (previous, advances.last_mut()) {
+            *last += font.kern(previous, id);
+        }
+        advances.push(font.h_advance(id));
+        previous = Some(id);
+    }
+    advances
+}
+
+fn text_width(font: &FontArc, text: &str, size: f32) -> f32 {
+    text_advances(font, text, size).iter().sum()
+}
+
+/// `color` blended over `background`
+fn opaque(color: Color32, background: Color32) -> Color32 {
+    // Color32 is premultiplied, so the background shows through by the missing alpha
+    let [r, g, b, a] = color.to_array();
+    let behind = |channel: u8| (channel as u32 * (255 - a as u32) / 255) as u8;
+    Color32::from_rgb(
+        r.saturating_add(behind(background.r())),
+        g.saturating_add(behind(background.g())),
+        b.saturating_add(behind(background.b())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, Layer, ProcessStack, TechnologyInfo};
+    use crate::renderer::StackRenderer;
+
+    fn create_test_layout() -> StackLayout {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("slide".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd1".to_string(),
+            0.5,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.3,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide".to_string(),
+            1.0,
+            3.9,
+        )));
+        StackRenderer::new().layout_stack(&stack, 1200.0)
+    }
+
+    #[test]
+    fn test_layout_slide() {
+        let layout = create_test_layout();
+        let options = SlideOptions::new().with_title("Test stack");
+        assert_eq!(options.pixel_size(), [3840, 2160]);
+
+        let items = layout_slide(&layout, &options).unwrap();
+        let texts: Vec<&str> = items
+            .iter()
+            .filter_map(|item| match item {
+                SlideItem::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, ["Test stack", "imd1", "metal1", "oxide"]);
+
+        // Everything fits on the slide and is opaque
+        let slide = Rect::from_min_size(Pos2::ZERO, egui::vec2(SLIDE_WIDTH, SLIDE_HEIGHT));
+        for item in &items {
+            if let SlideItem::Polygon { points, fill, .. } = item {
+                assert!(points.iter().all(|p| slide.expand(1e-3).contains(*p)));
+                assert!(fill.is_opaque());
+            }
+        }
+
+        let bare = layout_slide(&layout, &SlideOptions::new().with_legend(false)).unwrap();
+        assert!(bare
+            .iter()
+            .all(|item| matches!(item, SlideItem::Polygon { .. })));
+        assert_eq!(items.len() - bare.len(), 1 + 2 * 3);
+
+        // Stretched thicknesses are stated below the cross-section
+        let stretched = layout_slide(
+            &layout,
+            &SlideOptions::new()
+                .with_legend(false)
+                .with_vertical_exaggeration(Some(14.2)),
+        )
+        .unwrap();
+        assert!(stretched.iter().any(|item| matches!(
+            item,
+            SlideItem::Text { text, position, .. }
+                if text == "vertical ×14 not to scale" && position.y == SLIDE_HEIGHT - MARGIN
+        )));
+    }
+
+    #[test]
+    fn test_layout_slide_theme() {
+        let layout = create_test_layout();
+        let texts = |items: &[SlideItem]| -> Vec<String> {
+            items
+                .iter()
+                .filter_map(|item| match item {
+                    SlideItem::Text { text, .. } => Some(text.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let theme = RenderTheme::dark().with_label_density(LabelDensity::All);
+        let options = SlideOptions::new().with_legend(false).with_theme(theme);
+        let items = layout_slide(&layout, &options).unwrap();
+        // The background comes first, then every layer is named inside the cross-section
+        assert!(matches!(
+            &items[0],
+            SlideItem::Polygon { fill, outline: None, .. } if *fill == Color32::from_gray(30)
+        ));
+        let mut labels = texts(&items);
+        labels.sort();
+        assert_eq!(labels, ["imd1", "metal1", "oxide"]);
+
+        let conductors = options
+            .clone()
+            .with_theme(RenderTheme::new().with_label_density(LabelDensity::Conductors));
+        assert_eq!(
+            texts(&layout_slide(&layout, &conductors).unwrap()),
+            ["metal1"]
+        );
+
+        let broken = options.with_theme(RenderTheme::new().with_font(vec![0u8; 4], "Broken"));
+        assert_eq!(layout_slide(&layout, &broken), Err(SlideError::Font));
+    }
+
+    #[test]
+    fn test_opaque() {
+        assert_eq!(opaque(Color32::RED, Color32::WHITE), Color32::RED);
+        assert_eq!(opaque(Color32::TRANSPARENT, Color32::WHITE), Color32::WHITE);
+        let half = Color32::from_rgba_unmultiplied(0, 0, 255, 128);
+        assert_eq!(
+            opaque(half, Color32::WHITE),
+            Color32::from_rgb(127, 127, 255)
+        );
+        assert_eq!(opaque(half, Color32::BLACK), Color32::from_rgb(0, 0, 128));
+    }
+}
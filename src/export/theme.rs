@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use egui::Color32;
+use std::sync::Arc;
+
+/// Which layers are named inside the cross-section of exported slides
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LabelDensity {
+    /// Layers are only named in the legend
+    #[default]
+    None,
+    Conductors,
+    /// Conductors and dielectrics, vias stay unlabeled
+    All,
+}
+
+impl LabelDensity {
+    pub const ALL: [LabelDensity; 3] = [
+        LabelDensity::None,
+        LabelDensity::Conductors,
+        LabelDensity::All,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LabelDensity::None => "none",
+            LabelDensity::Conductors => "conductors",
+            LabelDensity::All => "all",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|density| density.label().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Colors, outlines and text of exported slides, independent of the GUI settings
+///
+/// Scripts pass the same theme to every export of a batch to get consistent images.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderTheme {
+    pub background: Color32,
+    pub text_color: Color32,
+    pub outline_color: Color32,
+    /// Width of polygon outlines in points
+    pub outline_width: f32,
+    /// TrueType or OpenType font used to lay out and draw text, the egui font if none
+    pub font_data: Option<Arc<[u8]>>,
+    /// Font name EMF viewers substitute, as EMF text refers to fonts by name
+    pub font_face: String,
+    pub title_size: f32,
+    /// Size of legend entries and layer labels
+    pub text_size: f32,
+    pub label_density: LabelDensity,
+}
+
+impl Default for RenderTheme {
+    fn default() -> Self {
+        Self {
+            background: Color32::WHITE,
+            text_color: Color32::from_gray(32),
+            outline_color: Color32::from_gray(64),
+            outline_width: 0.5,
+            font_data: None,
+            font_face: "Arial".to_string(),
+            title_size: 28.0,
+            text_size: 11.0,
+            label_density: LabelDensity::None,
+        }
+    }
+}
+
+impl RenderTheme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Light text and outlines on a dark background
+    pub fn dark() -> Self {
+        Self {
+            background: Color32::from_gray(30),
+            text_color: Color32::from_gray(230),
+            outline_color: Color32::from_gray(150),
+            ..Self::default()
+        }
+    }
+
+    /// Built-in theme by name, `light` or `dark`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "light" => Some(Self::default()),
+            "dark" => Some(Self::dark()),
+            _ => None,
+        }
+    }
+
+    pub fn with_background(mut self, background: Color32) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn with_text_color(mut self, text_color: Color32) -> Self {
+        self.text_color = text_color;
+        self
+    }
+
+    pub fn with_outline(mut self, color: Color32, width: f32) -> Self {
+        self.outline_color = color;
+        self.outline_width = width;
+        self
+    }
+
+    pub fn with_font(mut self, data: impl Into<Arc<[u8]>>, face: impl Into<String>) -> Self {
+        self.font_data = Some(data.into());
+        self.font_face = face.into();
+        self
+    }
+
+    pub fn with_text_sizes(mut self, title_size: f32, text_size: f32) -> Self {
+        self.title_size = title_size;
+        self.text_size = text_size;
+        self
+    }
+
+    pub fn with_label_density(mut self, label_density: LabelDensity) -> Self {
+        self.label_density = label_density;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_theme() {
+        assert_eq!(RenderTheme::from_name("Light"), Some(RenderTheme::new()));
+        assert_eq!(RenderTheme::from_name("dark"), Some(RenderTheme::dark()));
+        assert_eq!(RenderTheme::from_name("sepia"), None);
+
+        let theme = RenderTheme::dark()
+            .with_outline(Color32::BLACK, 1.5)
+            .with_label_density(LabelDensity::Conductors);
+        assert_eq!(theme.outline_width, 1.5);
+        assert_eq!(theme.background, Color32::from_gray(30));
+
+        assert_eq!(LabelDensity::from_name("ALL"), Some(LabelDensity::All));
+        assert_eq!(LabelDensity::from_name("some"), None);
+    }
+}
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{MetalDensities, Micrometers, ProcessStack, DEFAULT_METAL_DENSITY};
+use egui::{Color32, Context, DragValue, RichText, ScrollArea, TopBottomPanel};
+
+/// Strip under the cross-section with the assumed fill density of each metal
+///
+/// Densities are entered in percent and feed the thickness-vs-density preview next to
+/// each value, so CMP-related thickness changes can be discussed on the stack itself.
+pub struct DensityStrip {
+    open: bool,
+    densities: MetalDensities,
+    densities_changed: bool,
+}
+
+impl DensityStrip {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            densities: MetalDensities::new(),
+            densities_changed: false,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_densities(&self) -> &MetalDensities {
+        &self.densities
+    }
+
+    pub fn set_densities(&mut self, densities: MetalDensities) {
+        self.densities = densities;
+        self.densities_changed = true;
+    }
+
+    pub fn set_density(&mut self, layer_name: &str, density: Option<f64>) {
+        self.densities.set(layer_name, density);
+        self.densities_changed = true;
+    }
+
+    /// Keep a layer's density when the layer is renamed
+    pub fn rename_layer(&mut self, old_name: &str, new_name: &str) {
+        if self.densities.is_set(old_name) {
+            self.densities.rename(old_name, new_name);
+            self.densities_changed = true;
+        }
+    }
+
+    /// Return the densities if the user changed them since the last call
+    pub fn take_densities_change(&mut self) -> Option<MetalDensities> {
+        std::mem::take(&mut self.densities_changed).then(|| self.densities.clone())
+    }
+
+    pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) {
+        if !self.open {
+            return;
+        }
+        let Some(stack) = stack else {
+            return;
+        };
+
+        TopBottomPanel::bottom("density_strip").show(ctx, |ui| {
+            let context = stack.get_density_context(&self.densities);
+            if context.is_empty() {
+                ui.weak("No signal metals in this stack");
+                return;
+            }
+
+            ScrollArea::horizontal()
+                .id_salt("density_strip_scroll")
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Metal density:").on_hover_text(format!(
+                            "Assumed fill density per metal, bottom first; unset metals use {:.0}%",
+                            DEFAULT_METAL_DENSITY * 100.0
+                        ));
+                        for metal in &context {
+                            ui.separator();
+                            ui.vertical(|ui| {
+                                let name = RichText::new(&metal.layer_name).strong();
+                                ui.label(if metal.user_set { name } else { name.weak() });
+
+                                let mut percent = metal.density * 100.0;
+                                let response = ui.add(
+                                    DragValue::new(&mut percent)
+                                        .speed(0.5)
+                                        .range(0.0..=100.0)
+                                        .suffix("%"),
+                                );
+                                if response.changed() {
+                                    self.set_density(&metal.layer_name, Some(percent / 100.0));
+                                }
+                                response.context_menu(|ui| {
+                                    if ui
+                                        .add_enabled(
+                                            metal.user_set,
+                                            egui::Button::new("Reset density"),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.set_density(&metal.layer_name, None);
+                                        ui.close();
+                                    }
+                                });
+
+                                let change = metal.thickness_change();
+                                let text = format!("{:.4} μm", metal.thickness);
+                                if change == 0.0 {
+                                    ui.label(text).on_hover_text(
+                                        "No THICKNESS_VS_DENSITY data, nominal thickness",
+                                    );
+                                } else {
+                                    let color = if change < 0.0 {
+                                        Color32::from_rgb(255, 165, 0)
+                                    } else {
+                                        Color32::from_rgb(100, 200, 100)
+                                    };
+                                    ui.label(RichText::new(text).color(color)).on_hover_text(
+                                        format!(
+                                            "Nominal {:.4} μm, {:+.1} nm at this density",
+                                            metal.nominal_thickness,
+                                            Micrometers(change).nm()
+                                        ),
+                                    );
+                                }
+                            });
+                        }
+                    });
+                });
+        });
+    }
+}
+
+impl Default for DensityStrip {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_density_strip() {
+        let mut strip = DensityStrip::new();
+        assert!(!strip.is_open());
+        assert!(strip.take_densities_change().is_none());
+
+        strip.set_density("metal1", Some(0.3));
+        let densities = strip.take_densities_change().unwrap();
+        assert_eq!(densities.get("metal1"), 0.3);
+        assert!(strip.take_densities_change().is_none());
+
+        strip.rename_layer("metal1", "M1");
+        assert_eq!(strip.get_densities().get("M1"), 0.3);
+        assert!(strip.take_densities_change().is_some());
+
+        // Renaming a layer without a density is not a change
+        strip.rename_layer("metal2", "M2");
+        assert!(strip.take_densities_change().is_none());
+    }
+}
@@ -2,7 +2,7 @@
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
 use crate::data::ProcessStack;
-use crate::parser::parse_itf_file;
+use crate::parser::{parse_itf_file, read_itf_source};
 use egui::{Context, RichText, Window};
 use poll_promise::Promise;
 use rfd::AsyncFileDialog;
@@ -103,6 +103,7 @@ impl FileMenu {
                                 ui.label(format!("Temperature: {temp:.1}°C"));
                             }
                             ui.label(format!("Total height: {:.3} um", summary.total_height));
+                            ui.label(format!("Fingerprint: {}", summary.fingerprint));
                         });
                     }
                 });
@@ -128,9 +129,10 @@ impl FileMenu {
     }
 
     fn load_file(&mut self, path: PathBuf) {
-        match std::fs::read_to_string(&path) {
-            Ok(content) => match parse_itf_file(&content) {
-                Ok(stack) => {
+        match read_itf_source(&path) {
+            Ok(source) => match parse_itf_file(&source.text) {
+                Ok(mut stack) => {
+                    source.add_diagnostics(&mut stack);
                     self.load_result = Some(Ok(stack));
                     self.error_message = None;
                 }
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::renderer::Guide;
+use egui::{Context, DragValue, Grid, TextEdit, Window};
+
+/// Typed entry of reference guide lines
+pub struct GuidesWindow {
+    open: bool,
+    new_z: f32,
+    new_label: String,
+}
+
+impl GuidesWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            new_z: 0.0,
+            new_label: String::new(),
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Show the window, editing `guides` in place; returns whether they changed
+    pub fn show(&mut self, ctx: &Context, guides: &mut Vec<Guide>) -> bool {
+        if !self.open {
+            return false;
+        }
+
+        let mut changed = false;
+        let mut open = self.open;
+        Window::new("Guides")
+            .open(&mut open)
+            .default_size([360.0, 240.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label("Heights above the bottom of the stack. Guides can also be dragged out of the ruler, and dropped back on it to remove them.");
+                ui.separator();
+
+                let mut remove = None;
+                Grid::new("guides_list")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, guide) in guides.iter_mut().enumerate() {
+                            changed |= ui
+                                .add(DragValue::new(&mut guide.z).speed(0.01).suffix(" μm"))
+                                .changed();
+                            changed |= ui
+                                .add(TextEdit::singleline(&mut guide.label).hint_text("Label"))
+                                .changed();
+                            if ui.small_button("Remove").clicked() {
+                                remove = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(index) = remove {
+                    guides.remove(index);
+                    changed = true;
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add(DragValue::new(&mut self.new_z).speed(0.01).suffix(" μm"));
+                    ui.add(
+                        TextEdit::singleline(&mut self.new_label)
+                            .hint_text("Label")
+                            .desired_width(120.0),
+                    );
+                    if ui.button("Add").clicked() {
+                        guides.push(self.take_new_guide());
+                        changed = true;
+                    }
+                });
+            });
+        self.open = open;
+        changed
+    }
+
+    /// Guide from the entry row, clearing its label for the next one
+    fn take_new_guide(&mut self) -> Guide {
+        Guide::new(self.new_z).with_label(std::mem::take(&mut self.new_label).trim())
+    }
+}
+
+impl Default for GuidesWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guides_window() {
+        let mut window = GuidesWindow::new();
+        assert!(!window.is_open());
+        window.set_open(true);
+        assert!(window.is_open());
+
+        window.new_z = 12.5;
+        window.new_label = " bump ".to_string();
+        assert_eq!(window.take_new_guide(), Guide::new(12.5).with_label("bump"));
+        assert!(window.new_label.is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{Layer, ProcessStack};
+use egui::{ComboBox, Context, Slider, Window};
+use egui_plot::{Bar, BarChart, Plot};
+
+/// Layer property plotted by the histogram window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HistogramProperty {
+    #[default]
+    Thickness,
+    DielectricConstant,
+    SheetResistance,
+}
+
+impl HistogramProperty {
+    pub const ALL: [HistogramProperty; 3] = [
+        HistogramProperty::Thickness,
+        HistogramProperty::DielectricConstant,
+        HistogramProperty::SheetResistance,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HistogramProperty::Thickness => "Thickness (um)",
+            HistogramProperty::DielectricConstant => "ER",
+            HistogramProperty::SheetResistance => "RPSQ (ohm/sq)",
+        }
+    }
+
+    /// Value of the property for `layer`, if the layer has one
+    pub fn value(self, layer: &Layer) -> Option<f64> {
+        match (self, layer) {
+            (HistogramProperty::Thickness, layer) => Some(layer.thickness()),
+            (HistogramProperty::DielectricConstant, Layer::Dielectric(dielectric)) => {
+                Some(dielectric.dielectric_constant)
+            }
+            (HistogramProperty::SheetResistance, Layer::Conductor(conductor)) => {
+                conductor.electrical_props.rpsq
+            }
+            _ => None,
+        }
+    }
+}
+
+/// One histogram bin with the layers whose value falls in `[start, end)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistogramBin {
+    pub start: f64,
+    pub end: f64,
+    pub layers: Vec<String>,
+}
+
+/// Split the values of `property` across the stack into `bin_count` equal-width bins
+///
+/// The last bin includes the maximum. When every layer has the same value, a single
+/// bin of width one holds all of them. Returns no bins when no layer has the property.
+pub fn property_histogram(
+    stack: &ProcessStack,
+    property: HistogramProperty,
+    bin_count: usize,
+) -> Vec<HistogramBin> {
+    let values: Vec<(&str, f64)> = stack
+        .layers
+        .iter()
+        .filter_map(|layer| Some((layer.name(), property.value(layer)?)))
+        .filter(|(_, value)| value.is_finite())
+        .collect();
+    let Some(min) = values.iter().map(|(_, value)| *value).reduce(f64::min) else {
+        return Vec::new();
+    };
+    let max = values.iter().map(|(_, value)| *value).fold(min, f64::max);
+
+    let bin_count = if max > min { bin_count.max(1) } else { 1 };
+    let width = if max > min {
+        (max - min) / bin_count as f64
+    } else {
+        1.0
+    };
+    let mut bins: Vec<HistogramBin> = (0..bin_count)
+        .map(|index| HistogramBin {
+            start: min + width * index as f64,
+            end: min + width * (index + 1) as f64,
+            layers: Vec::new(),
+        })
+        .collect();
+    for (name, value) in values {
+        let index = (((value - min) / width) as usize).min(bin_count - 1);
+        bins[index].layers.push(name.to_string());
+    }
+    bins
+}
+
+/// Histogram of a layer property across the stack, to spot outliers
+pub struct HistogramWindow {
+    open: bool,
+    property: HistogramProperty,
+    bin_count: usize,
+}
+
+impl HistogramWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            property: HistogramProperty::default(),
+            bin_count: 20,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_property(&self) -> HistogramProperty {
+        self.property
+    }
+
+    pub fn set_property(&mut self, property: HistogramProperty) {
+        self.property = property;
+    }
+
+    pub fn set_bin_count(&mut self, bin_count: usize) {
+        self.bin_count = bin_count.max(1);
+    }
+
+    pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) {
+        if !self.open {
+            return;
+        }
+
+        let mut open = self.open;
+        Window::new("Property Histogram")
+            .open(&mut open)
+            .default_size([520.0, 340.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ComboBox::from_label("Property")
+                        .selected_text(self.property.label())
+                        .show_ui(ui, |ui| {
+                            for property in HistogramProperty::ALL {
+                                ui.selectable_value(&mut self.property, property, property.label());
+                            }
+                        });
+                    ui.add(Slider::new(&mut self.bin_count, 1..=100).text("Bins"));
+                });
+                ui.separator();
+
+                let Some(stack) = stack else {
+                    ui.label("Load a stack to plot its layer properties.");
+                    return;
+                };
+
+                let bins = property_histogram(stack, self.property, self.bin_count);
+                if bins.is_empty() {
+                    ui.label(format!("No layer defines {}.", self.property.label()));
+                    return;
+                }
+
+                // Each bar is named after its layers so hovering it shows which ones they are
+                let bars = bins
+                    .iter()
+                    .filter(|bin| !bin.layers.is_empty())
+                    .map(|bin| {
+                        Bar::new((bin.start + bin.end) / 2.0, bin.layers.len() as f64)
+                            .width(bin.end - bin.start)
+                            .name(bin.layers.join(", "))
+                    })
+                    .collect();
+
+                Plot::new(("property_histogram", self.property as u8))
+                    .view_aspect(2.0)
+                    .x_axis_label(self.property.label())
+                    .y_axis_label("Layers")
+                    .show(ui, |plot_ui| {
+                        plot_ui.bar_chart(BarChart::new(self.property.label(), bars));
+                    });
+            });
+        self.open = open;
+    }
+}
+
+impl Default for HistogramWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+
+    #[test]
+    fn test_property_histogram() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("histogram".to_string()));
+        for (name, thickness) in [("ild1", 1.0), ("ild2", 1.1), ("ild3", 3.0)] {
+            stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+                name.to_string(),
+                thickness,
+                4.2,
+            )));
+        }
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            2.0,
+        ))));
+
+        let bins = property_histogram(&stack, HistogramProperty::Thickness, 4);
+        assert_eq!(bins.len(), 4);
+        assert_eq!(bins[0].layers, vec!["ild1", "ild2"]);
+        assert_eq!(bins[2].layers, vec!["metal1"]);
+        assert_eq!(bins[3].layers, vec!["ild3"]);
+        assert_eq!(bins[3].end, 3.0);
+
+        // Equal values share one bin; properties no layer has give none
+        let bins = property_histogram(&stack, HistogramProperty::DielectricConstant, 4);
+        assert_eq!(bins.len(), 1);
+        assert_eq!(bins[0].layers.len(), 3);
+        assert!(property_histogram(&stack, HistogramProperty::SheetResistance, 4).is_empty());
+
+        let mut window = HistogramWindow::new();
+        assert!(!window.is_open());
+        window.set_property(HistogramProperty::SheetResistance);
+        assert_eq!(window.get_property(), HistogramProperty::SheetResistance);
+    }
+}
@@ -0,0 +1,342 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{ProcessStack, PropertyChange, StackDiff, StackDocument};
+use crate::parser::ItfParser;
+use crate::utils::{file_history, read_revision, FileRevision};
+use egui::{Color32, Context, Grid, ScrollArea, Window};
+use std::path::{Path, PathBuf};
+
+/// Earlier git revisions of the open file, one of them outlined over the stack and compared
+/// with it
+pub struct HistoryWindow {
+    open: bool,
+    /// File the revisions are listed for
+    file: Option<PathBuf>,
+    /// Revisions newest first, or why git could not list them; listed when first shown
+    revisions: Option<Result<Vec<FileRevision>, String>>,
+    selected: Option<usize>,
+    /// The selected revision parsed, or why it could not be
+    old_stack: Option<Result<ProcessStack, String>>,
+    /// Differences from the selected revision to the open stack, with the document revision
+    /// they were taken at
+    diff: Option<(u64, StackDiff)>,
+    show_ghost: bool,
+    ghost_changed: bool,
+}
+
+impl HistoryWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            file: None,
+            revisions: None,
+            selected: None,
+            old_stack: None,
+            diff: None,
+            show_ghost: true,
+            ghost_changed: false,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        if open != self.open {
+            self.open = open;
+            self.ghost_changed = true;
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Follow the open file, dropping the revisions of the previous one
+    pub fn set_file(&mut self, file: Option<&Path>) {
+        if self.file.as_deref() == file {
+            return;
+        }
+        self.file = file.map(Path::to_path_buf);
+        self.revisions = None;
+        self.clear_selection();
+    }
+
+    fn clear_selection(&mut self) {
+        self.selected = None;
+        self.diff = None;
+        if self.old_stack.take().is_some() {
+            self.ghost_changed = true;
+        }
+    }
+
+    /// List the revisions of the file again
+    pub fn refresh(&mut self) {
+        self.clear_selection();
+        self.revisions = self
+            .file
+            .as_deref()
+            .map(|file| file_history(file).map_err(|e| e.to_string()));
+    }
+
+    fn select(&mut self, index: usize) {
+        let (Some(file), Some(Ok(revisions))) = (&self.file, &self.revisions) else {
+            return;
+        };
+        let Some(revision) = revisions.get(index) else {
+            return;
+        };
+        let old_stack = read_revision(file, revision)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                ItfParser::new()
+                    .parse_itf_file(&text)
+                    .map_err(|e| format!("Failed to parse {}: {e}", revision.short_commit))
+            });
+        self.selected = Some(index);
+        self.old_stack = Some(old_stack);
+        self.diff = None;
+        self.ghost_changed = true;
+    }
+
+    /// The selected revision, if it parsed
+    pub fn get_old_stack(&self) -> Option<&ProcessStack> {
+        self.old_stack.as_ref()?.as_ref().ok()
+    }
+
+    /// The stack to outline over the view if that changed since the last call
+    pub fn take_ghost_change(&mut self) -> Option<Option<ProcessStack>> {
+        if !std::mem::take(&mut self.ghost_changed) {
+            return None;
+        }
+        let visible = self.open && self.show_ghost;
+        Some(self.get_old_stack().filter(|_| visible).cloned())
+    }
+
+    /// Compare the selected revision with `document` unless that was done at its revision
+    fn update_diff(&mut self, document: &StackDocument) {
+        let Some(Ok(old_stack)) = &self.old_stack else {
+            return;
+        };
+        let revision = document.revision();
+        if self
+            .diff
+            .as_ref()
+            .is_none_or(|(taken_at, _)| *taken_at != revision)
+        {
+            self.diff = Some((revision, old_stack.diff(document.stack())));
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, document: Option<&StackDocument>, file: Option<&Path>) {
+        self.set_file(file);
+        if !self.open {
+            return;
+        }
+        if self.revisions.is_none() && self.file.is_some() {
+            self.refresh();
+        }
+        if let Some(document) = document {
+            self.update_diff(document);
+        }
+
+        let mut open = self.open;
+        let mut refresh = false;
+        let mut clicked = None;
+        Window::new("File History")
+            .open(&mut open)
+            .default_size([520.0, 440.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let Some(file) = &self.file else {
+                    ui.label("Open a file to see its git history");
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    let name = file.file_name().unwrap_or(file.as_os_str());
+                    ui.strong(name.to_string_lossy());
+                    refresh = ui.button("Refresh").clicked();
+                });
+                ui.separator();
+
+                let revisions = match &self.revisions {
+                    Some(Ok(revisions)) if !revisions.is_empty() => revisions,
+                    Some(Ok(_)) => {
+                        ui.label("No commits of this file yet");
+                        return;
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(Color32::from_rgb(255, 165, 0), message);
+                        return;
+                    }
+                    None => return,
+                };
+                ScrollArea::vertical()
+                    .id_salt("history_revisions")
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for (index, revision) in revisions.iter().enumerate() {
+                            let text = format!(
+                                "{}  {}  {}",
+                                revision.short_commit, revision.date, revision.summary
+                            );
+                            if ui
+                                .selectable_label(self.selected == Some(index), text)
+                                .on_hover_text(format!("{}\n{}", revision.author, revision.path))
+                                .clicked()
+                            {
+                                clicked = Some(index);
+                            }
+                        }
+                    });
+                ui.separator();
+
+                match &self.old_stack {
+                    None => {
+                        ui.label("Select a revision to compare it with the open stack");
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(Color32::from_rgb(255, 165, 0), message);
+                    }
+                    Some(Ok(_)) => {
+                        if ui
+                            .checkbox(&mut self.show_ghost, "Outline in the view")
+                            .changed()
+                        {
+                            self.ghost_changed = true;
+                        }
+                        if let (Some(_), Some((_, diff))) = (document, &self.diff) {
+                            show_diff(ui, diff);
+                        }
+                    }
+                }
+            });
+
+        self.set_open(open);
+        if refresh {
+            self.refresh();
+        } else if let Some(index) = clicked {
+            self.select(index);
+        }
+    }
+}
+
+impl Default for HistoryWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Differences from the selected revision to the open stack
+fn show_diff(ui: &mut egui::Ui, diff: &StackDiff) {
+    if diff.is_empty() {
+        ui.label("No differences to the open stack");
+        return;
+    }
+    for (label, names) in [
+        ("Layers added since", &diff.added_layers),
+        ("Layers removed since", &diff.removed_layers),
+        ("Vias added since", &diff.added_vias),
+        ("Vias removed since", &diff.removed_vias),
+        ("Regions added since", &diff.added_regions),
+        ("Regions removed since", &diff.removed_regions),
+    ] {
+        if !names.is_empty() {
+            ui.label(format!("{label}: {}", names.join(", ")));
+        }
+    }
+
+    let changes: Vec<&PropertyChange> = diff
+        .layer_changes
+        .iter()
+        .chain(&diff.via_changes)
+        .chain(&diff.region_changes)
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
+    ScrollArea::vertical()
+        .id_salt("history_diff")
+        .show(ui, |ui| {
+            Grid::new("history_diff_grid")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Item");
+                    ui.strong("Property");
+                    ui.strong("Revision");
+                    ui.strong("Open");
+                    ui.strong("Change");
+                    ui.end_row();
+                    for change in changes {
+                        ui.label(&change.item);
+                        ui.label(&change.property);
+                        ui.label(change.old_value.to_string());
+                        ui.label(change.new_value.to_string());
+                        ui.label(
+                            change
+                                .percent_delta()
+                                .map(|percent| format!("{percent:+.1}%"))
+                                .unwrap_or_default(),
+                        );
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, Layer, TechnologyInfo};
+
+    #[test]
+    fn test_ghost_follows_window_and_file() {
+        let mut window = HistoryWindow::new();
+        window.set_file(Some(Path::new("a.itf")));
+        window.revisions = Some(Ok(Vec::new()));
+        window.old_stack = Some(Ok(ProcessStack::new(TechnologyInfo::new(
+            "old".to_string(),
+        ))));
+        window.set_open(true);
+        let ghost = window.take_ghost_change().unwrap().unwrap();
+        assert_eq!(ghost.technology_info.name, "old");
+        assert!(window.take_ghost_change().is_none());
+
+        window.set_open(false);
+        assert!(matches!(window.take_ghost_change(), Some(None)));
+
+        // Another file drops the selected revision
+        window.set_open(true);
+        window.set_file(Some(Path::new("b.itf")));
+        assert!(window.get_old_stack().is_none());
+        assert!(window.revisions.is_none());
+        assert!(matches!(window.take_ghost_change(), Some(None)));
+    }
+
+    #[test]
+    fn test_diff_follows_document_revision() {
+        let mut window = HistoryWindow::new();
+        window.old_stack = Some(Ok(ProcessStack::new(TechnologyInfo::new(
+            "old".to_string(),
+        ))));
+        let mut document =
+            StackDocument::new(ProcessStack::new(TechnologyInfo::new("old".to_string())));
+        window.update_diff(&document);
+        assert!(window.diff.as_ref().unwrap().1.is_empty());
+
+        let mut stack = ProcessStack::new(TechnologyInfo::new("old".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd".to_string(),
+            0.5,
+            4.2,
+        )));
+        document.load_stack(stack);
+        window.update_diff(&document);
+        let (revision, diff) = window.diff.as_ref().unwrap();
+        assert_eq!(*revision, document.revision());
+        assert!(!diff.is_empty());
+
+        window.clear_selection();
+        assert!(window.diff.is_none());
+    }
+}
@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use egui::{InputState, Key, KeyboardShortcut, Modifiers, MouseWheelUnit};
+use serde::{Deserialize, Serialize};
+
+/// What scrolling over the stack viewer does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScrollBehavior {
+    /// Mouse wheels zoom, touchpad two-finger scrolling pans
+    #[default]
+    Auto,
+    /// Every scroll zooms
+    Zoom,
+    /// Every scroll pans
+    Pan,
+}
+
+impl ScrollBehavior {
+    pub const ALL: [ScrollBehavior; 3] = [Self::Auto, Self::Zoom, Self::Pan];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Auto => "Wheel zooms, touchpad pans",
+            Self::Zoom => "Always zoom",
+            Self::Pan => "Always pan",
+        }
+    }
+
+    /// Whether a scroll reported in `unit` pans instead of zooming
+    ///
+    /// Touchpads report smooth scrolling in points, mouse wheels in lines.
+    pub fn pans(&self, unit: MouseWheelUnit) -> bool {
+        match self {
+            Self::Auto => unit == MouseWheelUnit::Point,
+            Self::Zoom => false,
+            Self::Pan => true,
+        }
+    }
+}
+
+/// View action that can be bound to a keyboard shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ViewCommand {
+    ZoomIn,
+    ZoomOut,
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+    ResetView,
+    FitWidth,
+    FitHeight,
+    /// Show only the selected layer and its neighbor dielectrics while the key is held
+    Solo,
+}
+
+impl ViewCommand {
+    pub const ALL: [ViewCommand; 10] = [
+        Self::ZoomIn,
+        Self::ZoomOut,
+        Self::PanLeft,
+        Self::PanRight,
+        Self::PanUp,
+        Self::PanDown,
+        Self::ResetView,
+        Self::FitWidth,
+        Self::FitHeight,
+        Self::Solo,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ZoomIn => "Zoom in",
+            Self::ZoomOut => "Zoom out",
+            Self::PanLeft => "Pan left",
+            Self::PanRight => "Pan right",
+            Self::PanUp => "Pan up",
+            Self::PanDown => "Pan down",
+            Self::ResetView => "Reset view",
+            Self::FitWidth => "Fit width",
+            Self::FitHeight => "Fit height",
+            Self::Solo => "Solo selected layer (hold)",
+        }
+    }
+}
+
+/// Scroll behavior and keyboard shortcuts of the stack viewer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputBindings {
+    pub scroll_behavior: ScrollBehavior,
+    /// A command may have several shortcuts, a shortcut triggers one command
+    keys: Vec<(ViewCommand, KeyboardShortcut)>,
+}
+
+impl InputBindings {
+    pub fn new() -> Self {
+        let key = |command, key| (command, KeyboardShortcut::new(Modifiers::NONE, key));
+        Self {
+            scroll_behavior: ScrollBehavior::default(),
+            keys: vec![
+                key(ViewCommand::ZoomIn, Key::Plus),
+                key(ViewCommand::ZoomIn, Key::Equals),
+                key(ViewCommand::ZoomOut, Key::Minus),
+                key(ViewCommand::PanLeft, Key::ArrowLeft),
+                key(ViewCommand::PanRight, Key::ArrowRight),
+                key(ViewCommand::PanUp, Key::ArrowUp),
+                key(ViewCommand::PanDown, Key::ArrowDown),
+                key(ViewCommand::FitWidth, Key::W),
+                key(ViewCommand::FitHeight, Key::H),
+                key(ViewCommand::Solo, Key::S),
+                (
+                    ViewCommand::ResetView,
+                    KeyboardShortcut::new(Modifiers::COMMAND, Key::R),
+                ),
+            ],
+        }
+    }
+
+    /// Shortcuts bound to `command`
+    pub fn shortcuts(&self, command: ViewCommand) -> Vec<KeyboardShortcut> {
+        self.keys
+            .iter()
+            .filter(|(bound, _)| *bound == command)
+            .map(|(_, shortcut)| *shortcut)
+            .collect()
+    }
+
+    /// Bind `shortcut` as the only shortcut of `command`, unbinding it from any other command
+    pub fn set_binding(&mut self, command: ViewCommand, shortcut: KeyboardShortcut) {
+        self.keys
+            .retain(|(bound, existing)| *bound != command && *existing != shortcut);
+        self.keys.push((command, shortcut));
+    }
+
+    pub fn clear_binding(&mut self, command: ViewCommand) {
+        self.keys.retain(|(bound, _)| *bound != command);
+    }
+
+    /// Command bound to `shortcut`, if any
+    pub fn command_for(&self, shortcut: KeyboardShortcut) -> Option<ViewCommand> {
+        self.keys
+            .iter()
+            .find(|(_, existing)| *existing == shortcut)
+            .map(|(command, _)| *command)
+    }
+
+    /// Commands whose shortcut was pressed this frame
+    pub fn triggered(&self, input: &InputState) -> Vec<ViewCommand> {
+        let mut commands = Vec::new();
+        for (command, shortcut) in &self.keys {
+            if input.key_pressed(shortcut.logical_key)
+                && input.modifiers.matches_logically(shortcut.modifiers)
+                && !commands.contains(command)
+            {
+                commands.push(*command);
+            }
+        }
+        commands
+    }
+
+    /// Whether a shortcut of `command` is held down
+    pub fn held(&self, command: ViewCommand, input: &InputState) -> bool {
+        self.keys.iter().any(|(bound, shortcut)| {
+            *bound == command
+                && input.key_down(shortcut.logical_key)
+                && input.modifiers.matches_logically(shortcut.modifiers)
+        })
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_bindings() {
+        let bindings = InputBindings::new();
+        assert_eq!(bindings.shortcuts(ViewCommand::ZoomIn).len(), 2);
+        assert_eq!(
+            bindings.command_for(KeyboardShortcut::new(Modifiers::NONE, Key::ArrowLeft)),
+            Some(ViewCommand::PanLeft)
+        );
+        assert_eq!(
+            bindings.command_for(KeyboardShortcut::new(Modifiers::COMMAND, Key::R)),
+            Some(ViewCommand::ResetView)
+        );
+        assert_eq!(
+            bindings.command_for(KeyboardShortcut::new(Modifiers::NONE, Key::H)),
+            Some(ViewCommand::FitHeight)
+        );
+        assert_eq!(
+            bindings.command_for(KeyboardShortcut::new(Modifiers::NONE, Key::S)),
+            Some(ViewCommand::Solo)
+        );
+
+        let mut input = InputState::default();
+        assert!(!bindings.held(ViewCommand::Solo, &input));
+        input.keys_down.insert(Key::S);
+        assert!(bindings.held(ViewCommand::Solo, &input));
+        assert!(!bindings.held(ViewCommand::FitHeight, &input));
+
+        assert!(ScrollBehavior::Auto.pans(MouseWheelUnit::Point));
+        assert!(!ScrollBehavior::Auto.pans(MouseWheelUnit::Line));
+        assert!(!ScrollBehavior::Zoom.pans(MouseWheelUnit::Point));
+        assert!(ScrollBehavior::Pan.pans(MouseWheelUnit::Line));
+    }
+
+    #[test]
+    fn test_set_binding() {
+        let mut bindings = InputBindings::new();
+        let w = KeyboardShortcut::new(Modifiers::NONE, Key::W);
+        bindings.set_binding(ViewCommand::PanUp, w);
+        assert_eq!(bindings.shortcuts(ViewCommand::PanUp), vec![w]);
+        assert!(bindings.shortcuts(ViewCommand::FitWidth).is_empty());
+
+        // Taking a shortcut from another command leaves that command unbound
+        let minus = KeyboardShortcut::new(Modifiers::NONE, Key::Minus);
+        bindings.set_binding(ViewCommand::PanDown, minus);
+        assert_eq!(bindings.command_for(minus), Some(ViewCommand::PanDown));
+        assert!(bindings.shortcuts(ViewCommand::ZoomOut).is_empty());
+
+        bindings.clear_binding(ViewCommand::ZoomIn);
+        assert!(bindings.shortcuts(ViewCommand::ZoomIn).is_empty());
+        assert_ne!(bindings, InputBindings::default());
+    }
+}
@@ -1,8 +1,11 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use crate::data::{Layer, ProcessStack};
-use egui::{CollapsingHeader, Context, RichText, ScrollArea, SidePanel};
+use crate::analysis::estimate_via_count;
+use crate::data::{ConductorLayer, Layer, LintConfig, LintRule, ProcessStack, PropValue};
+use crate::renderer::drawn_side_tangent;
+use egui::{CollapsingHeader, Color32, Context, DragValue, Grid, RichText, ScrollArea, SidePanel};
+use std::collections::HashMap;
 
 pub struct LayerDetailsPanel {
     pub is_open: bool,
@@ -10,6 +13,16 @@ pub struct LayerDetailsPanel {
     pub show_electrical_props: bool,
     pub show_physical_props: bool,
     pub show_lookup_tables: bool,
+    /// Drawn width and spacing at which etched widths are shown, WMIN/SMIN when unset
+    pub etch_operating_point: Option<(f64, f64)>,
+    /// Also show widths and spacings scaled by HALF_NODE_SCALE_FACTOR
+    pub apply_half_node_scale: bool,
+    /// Factor the cross-section applies to SIDE_TANGENT, `None` while it is stylized
+    pub sidewall_exaggeration: Option<f32>,
+    /// Resistance in ohm the via count estimate aims for
+    pub via_target_resistance: f64,
+    /// Via name and the cut area in um² its RPV is suggested for, AREA until edited
+    pub via_cut_area: Option<(String, f64)>,
 }
 
 impl LayerDetailsPanel {
@@ -20,6 +33,11 @@ impl LayerDetailsPanel {
             show_electrical_props: true,
             show_physical_props: true,
             show_lookup_tables: false,
+            etch_operating_point: None,
+            apply_half_node_scale: false,
+            sidewall_exaggeration: None,
+            via_target_resistance: 1.0,
+            via_cut_area: None,
         }
     }
 
@@ -51,16 +69,16 @@ impl LayerDetailsPanel {
                 ui.separator();
 
                 if let Some(stack) = stack {
-                    if let Some(ref selected_name) = self.selected_layer {
+                    if let Some(selected_name) = self.selected_layer.clone() {
                         ScrollArea::vertical()
                             .id_salt("layer_details_scroll")
                             .show(ui, |ui| {
-                                if let Some(layer) = stack.get_layer(selected_name) {
-                                    self.show_layer_details(ui, layer);
+                                if let Some(layer) = stack.get_layer(&selected_name) {
+                                    self.show_layer_details(ui, stack, layer);
                                 } else if let Some(via) =
-                                    stack.via_stack.iter().find(|v| &v.name == selected_name)
+                                    stack.via_stack.iter().find(|v| v.name == selected_name)
                                 {
-                                    self.show_via_details(ui, via);
+                                    self.show_via_details(ui, stack, via);
                                 } else {
                                     ui.centered_and_justified(|ui| {
                                         ui.label("Selected layer not found");
@@ -80,7 +98,7 @@ impl LayerDetailsPanel {
             });
     }
 
-    fn show_layer_details(&self, ui: &mut egui::Ui, layer: &Layer) {
+    fn show_layer_details(&mut self, ui: &mut egui::Ui, stack: &ProcessStack, layer: &Layer) {
         // Basic properties
         CollapsingHeader::new("Basic Properties")
             .default_open(true)
@@ -88,22 +106,61 @@ impl LayerDetailsPanel {
                 ui.label(format!("Name: {}", layer.name()));
                 ui.label(format!("Type: {:?}", layer.layer_type()));
                 ui.label(format!("Thickness: {:.6} μm", layer.thickness()));
-                ui.label(format!("Z Position: {:.6} μm", layer.z_position()));
-                ui.label(format!("Bottom Z: {:.6} μm", layer.get_bottom_z()));
-                ui.label(format!("Top Z: {:.6} μm", layer.get_top_z()));
+                if layer.is_marker() {
+                    ui.label("Marker layer: drawn as a boundary line, adds no height");
+                }
+                if let Some((bottom, top)) = stack.get_layer_z_range(layer.name()) {
+                    let chip_top = stack.get_stack_top_z();
+                    ui.label(format!("Bottom Z: {bottom:.6} μm"))
+                        .on_hover_text("Above the substrate, at true thickness in every view mode");
+                    ui.label(format!("Top Z: {top:.6} μm"));
+                    ui.label(format!(
+                        "Depth: {:.6} - {:.6} μm below the top of the chip",
+                        chip_top - top,
+                        chip_top - bottom
+                    ));
+                }
             });
 
         match layer {
             Layer::Dielectric(d) => {
-                self.show_dielectric_details(ui, d);
+                self.show_dielectric_details(ui, stack, d);
             }
             Layer::Conductor(c) => {
-                self.show_conductor_details(ui, c);
+                let scale = self
+                    .apply_half_node_scale
+                    .then_some(stack.technology_info.half_node_scale_factor)
+                    .flatten();
+                self.show_conductor_details(ui, c, scale);
             }
         }
+
+        self.show_extra_properties(ui, layer.extra_properties());
+    }
+
+    /// Techfile properties the viewer has no dedicated field for, as written
+    fn show_extra_properties(&self, ui: &mut egui::Ui, properties: &HashMap<String, PropValue>) {
+        if properties.is_empty() {
+            return;
+        }
+
+        let mut keys: Vec<&String> = properties.keys().collect();
+        keys.sort();
+        CollapsingHeader::new("Other Properties")
+            .default_open(true)
+            .show(ui, |ui| {
+                for key in keys {
+                    ui.label(format!("{key}: {}", properties[key]));
+                }
+            });
     }
 
-    fn show_dielectric_details(&self, ui: &mut egui::Ui, layer: &crate::data::DielectricLayer) {
+    fn show_dielectric_details(
+        &self,
+        ui: &mut egui::Ui,
+        stack: &ProcessStack,
+        layer: &crate::data::DielectricLayer,
+    ) {
         CollapsingHeader::new("Dielectric Properties")
             .default_open(true)
             .show(ui, |ui| {
@@ -117,16 +174,58 @@ impl LayerDetailsPanel {
                 }
 
                 if let Some(sw_t) = layer.sw_t {
-                    ui.label(format!("SW_T: {sw_t:.6} μm"));
+                    ui.label(format!("Sidewall thickness (SW_T): {sw_t:.6} μm"));
                 }
 
                 if let Some(tw_t) = layer.tw_t {
-                    ui.label(format!("TW_T: {tw_t:.6} μm"));
+                    ui.label(format!("Top thickness (TW_T): {tw_t:.6} μm"));
+                }
+
+                if layer.sw_t.is_some() || layer.tw_t.is_some() {
+                    if let Some(conductor) = stack.get_liner_conductor(layer) {
+                        ui.label(format!("Conformal over: {}", conductor.name));
+                    }
+                    let config = LintConfig::only(LintRule::ConformalThickness);
+                    let findings = stack
+                        .get_layer(&layer.name)
+                        .map(|stack_layer| stack.lint_layer(stack_layer, &config))
+                        .unwrap_or_default();
+                    for finding in findings {
+                        ui.colored_label(Color32::from_rgb(255, 165, 0), finding.message);
+                    }
                 }
             });
     }
 
-    fn show_conductor_details(&self, ui: &mut egui::Ui, layer: &crate::data::ConductorLayer) {
+    /// `scale` is the half-node scale factor when scaling is applied
+    fn show_conductor_details(
+        &mut self,
+        ui: &mut egui::Ui,
+        layer: &ConductorLayer,
+        scale: Option<f64>,
+    ) {
+        if layer.is_gate() || layer.gate_props.layer_type.is_some() {
+            CollapsingHeader::new("Gate Properties")
+                .default_open(true)
+                .show(ui, |ui| {
+                    if let Some(ref layer_type) = layer.gate_props.layer_type {
+                        ui.label(format!("Layer type: {layer_type}"));
+                    }
+
+                    if layer.gate_props.gate_forming_layer {
+                        ui.label("Gate forming layer");
+                    }
+
+                    if let Some(smin) = layer.gate_props.gate_to_contact_smin {
+                        ui.label(format!("Gate to contact SMIN: {smin:.6} μm"));
+                    }
+
+                    if layer.gate_props.has_gate_to_diffusion_cap {
+                        ui.label("Gate to diffusion capacitance tables defined");
+                    }
+                });
+        }
+
         if self.show_electrical_props {
             CollapsingHeader::new("Electrical Properties")
                 .default_open(true)
@@ -140,7 +239,12 @@ impl LayerDetailsPanel {
                     }
 
                     if let Some(rpsq) = layer.electrical_props.rpsq {
-                        ui.label(format!("Sheet resistance (RPSQ): {rpsq:.6} Ω/□"));
+                        ui.label(format!("Sheet resistance (RPSQ): {rpsq:.6} Ω/□ (given)"));
+                    }
+
+                    if let Some(rho) = layer.get_effective_resistivity() {
+                        ui.label(format!("Resistivity: {rho:.6} (derived)"))
+                            .on_hover_text("ρ = RPSQ × THICKNESS");
                     }
 
                     if let Some(rpv) = layer.electrical_props.rpv {
@@ -162,17 +266,24 @@ impl LayerDetailsPanel {
                 .default_open(true)
                 .show(ui, |ui| {
                     if let Some(wmin) = layer.physical_props.width_min {
-                        ui.label(format!("Min width (WMIN): {wmin:.6} μm"));
+                        ui.label(format!("Min width (WMIN): {}", scaled_length(wmin, scale)));
                     }
 
                     if let Some(smin) = layer.physical_props.spacing_min {
-                        ui.label(format!("Min spacing (SMIN): {smin:.6} μm"));
+                        ui.label(format!(
+                            "Min spacing (SMIN): {}",
+                            scaled_length(smin, scale)
+                        ));
                     }
 
                     if let Some(side_tangent) = layer.physical_props.side_tangent {
                         ui.label(format!("Side tangent: {side_tangent:.6}"));
-                        let angle_deg = side_tangent.atan().to_degrees();
-                        ui.label(format!("Side angle: {angle_deg:.2}°"));
+                        let text = sidewall_angle_text(side_tangent, self.sidewall_exaggeration);
+                        let (angle, drawn) = text.split_once('\n').unwrap_or((&text, ""));
+                        ui.label(angle);
+                        if !drawn.is_empty() {
+                            ui.label(RichText::new(drawn).italics().weak());
+                        }
 
                         ui.separator();
                         if side_tangent > 0.0 {
@@ -214,11 +325,112 @@ impl LayerDetailsPanel {
                 });
         }
 
+        if self.show_physical_props && layer.etch_vs_width_spacing.is_some() {
+            self.show_etched_widths(ui, layer, scale);
+        }
+
         if self.show_lookup_tables {
             self.show_lookup_tables_info(ui, layer);
         }
     }
 
+    /// Drawn against silicon dimensions at the chosen operating point
+    ///
+    /// With a half-node `scale` the etch table is looked up at the scaled dimensions.
+    fn show_etched_widths(
+        &mut self,
+        ui: &mut egui::Ui,
+        layer: &ConductorLayer,
+        scale: Option<f64>,
+    ) {
+        let (mut width, mut spacing) = self.etch_operating_point.unwrap_or_else(|| {
+            let table = layer.etch_vs_width_spacing.as_ref();
+            let first =
+                |values: Option<&Vec<f64>>| values.and_then(|v| v.first().copied()).unwrap_or(0.1);
+            (
+                layer
+                    .physical_props
+                    .width_min
+                    .unwrap_or_else(|| first(table.map(|t| &t.widths))),
+                layer
+                    .physical_props
+                    .spacing_min
+                    .unwrap_or_else(|| first(table.map(|t| &t.spacings))),
+            )
+        });
+
+        CollapsingHeader::new("Drawn vs Silicon Width")
+            .default_open(true)
+            .show(ui, |ui| {
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label("Drawn W:");
+                    changed |= ui
+                        .add(
+                            DragValue::new(&mut width)
+                                .speed(0.001)
+                                .range(0.0..=f64::MAX)
+                                .suffix(" μm"),
+                        )
+                        .changed();
+                    ui.label("S:");
+                    changed |= ui
+                        .add(
+                            DragValue::new(&mut spacing)
+                                .speed(0.001)
+                                .range(0.0..=f64::MAX)
+                                .suffix(" μm"),
+                        )
+                        .changed();
+                });
+                if changed {
+                    self.etch_operating_point = Some((width, spacing));
+                }
+
+                let factor = scale.unwrap_or(1.0);
+                let (scaled_width, scaled_spacing) = (width * factor, spacing * factor);
+                let silicon_width = layer.silicon_width(scaled_width, scaled_spacing);
+                let silicon_spacing = layer.silicon_spacing(scaled_width, scaled_spacing);
+                Grid::new("etched_widths_grid")
+                    .num_columns(if scale.is_some() { 5 } else { 4 })
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("");
+                        ui.strong("Drawn");
+                        if scale.is_some() {
+                            ui.strong("Scaled");
+                        }
+                        ui.strong("Silicon");
+                        ui.strong("Δ");
+                        ui.end_row();
+
+                        for (label, drawn, scaled, silicon) in [
+                            ("Width", width, scaled_width, silicon_width),
+                            ("Spacing", spacing, scaled_spacing, silicon_spacing),
+                        ] {
+                            ui.label(label);
+                            ui.label(format!("{drawn:.4} μm"));
+                            if scale.is_some() {
+                                ui.label(format!("{scaled:.4} μm"));
+                            }
+                            ui.label(format!("{silicon:.4} μm"));
+                            ui.label(format!("{:+.4} μm", silicon - scaled));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.label(format!(
+                    "Etch per edge: {:.4} μm",
+                    layer.etch_bias(scaled_width, scaled_spacing)
+                ));
+                if layer.etch_from_top.is_some() {
+                    ui.label(
+                        RichText::new("ETCH_FROM_TOP also defined, not included above").weak(),
+                    );
+                }
+            });
+    }
+
     fn show_lookup_tables_info(&self, ui: &mut egui::Ui, layer: &crate::data::ConductorLayer) {
         CollapsingHeader::new("Lookup Tables")
             .default_open(false)
@@ -355,7 +567,12 @@ impl LayerDetailsPanel {
             });
     }
 
-    fn show_via_details(&self, ui: &mut egui::Ui, via: &crate::data::ViaConnection) {
+    fn show_via_details(
+        &mut self,
+        ui: &mut egui::Ui,
+        stack: &ProcessStack,
+        via: &crate::data::ViaConnection,
+    ) {
         CollapsingHeader::new("Via Properties")
             .default_open(true)
             .show(ui, |ui| {
@@ -373,6 +590,55 @@ impl LayerDetailsPanel {
                 ));
             });
 
+        if via.area > 0.0 && via.resistance_per_via > 0.0 {
+            CollapsingHeader::new("Via Count")
+                .default_open(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Target resistance:");
+                        ui.add(
+                            DragValue::new(&mut self.via_target_resistance)
+                                .speed(0.01)
+                                .range(0.001..=1.0e6)
+                                .suffix(" Ω"),
+                        );
+                    });
+                    if let Some(estimate) = estimate_via_count(via, self.via_target_resistance) {
+                        ui.label(format!(
+                            "Vias needed: {} ({:.4} Ω in parallel)",
+                            estimate.via_count, estimate.resistance
+                        ));
+                        ui.label(format!("Cut area: {:.6} μm²", estimate.cut_area));
+                        ui.label(format!(
+                            "Specific resistance: {:.4} Ω·μm²",
+                            estimate.specific_resistance
+                        ))
+                        .on_hover_text(
+                            "RPV × AREA; a cut of area A has about this / A ohms per via",
+                        );
+
+                        let cut_area = match &mut self.via_cut_area {
+                            Some((name, area)) if *name == via.name => area,
+                            cut_area => &mut cut_area.insert((via.name.clone(), via.area)).1,
+                        };
+                        ui.horizontal(|ui| {
+                            ui.label("Cut area per via:");
+                            ui.add(
+                                DragValue::new(cut_area)
+                                    .speed(0.001)
+                                    .range(1.0e-6..=1.0e6)
+                                    .suffix(" μm²"),
+                            );
+                        });
+                        ui.label(format!(
+                            "Suggested RPV: {:.4} Ω",
+                            estimate.rpv_for_area(*cut_area)
+                        ))
+                        .on_hover_text("Resistance per via of a cut this size");
+                    }
+                });
+        }
+
         CollapsingHeader::new("Via Classification")
             .default_open(true)
             .show(ui, |ui| {
@@ -390,9 +656,48 @@ impl LayerDetailsPanel {
                     ui.label("(connects metal layers)");
                 }
             });
+
+        if let Some(span) = stack.get_via_dielectric_span(via) {
+            CollapsingHeader::new("Dielectric Span")
+                .default_open(true)
+                .show(ui, |ui| {
+                    Grid::new("via_dielectric_span")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Dielectric");
+                            ui.strong("Thickness");
+                            ui.strong("Crossed");
+                            ui.end_row();
+                            for dielectric in span.dielectrics.iter().rev() {
+                                ui.label(&dielectric.name);
+                                ui.label(format!("{:.6} μm", dielectric.thickness));
+                                ui.label(format!("{:.6} μm", dielectric.crossed));
+                                ui.end_row();
+                            }
+                        });
+                    ui.label(format!("Total span: {:.6} μm", span.total_span()));
+                    ui.label(format!(
+                        "Via height as drawn: {:.6} μm ({:.6} - {:.6} μm)",
+                        span.via_height(),
+                        span.bottom_z,
+                        span.top_z
+                    ));
+
+                    let config = LintConfig::only(LintRule::ViaDielectricSpan);
+                    for finding in stack.lint_via(via, &config) {
+                        ui.colored_label(Color32::from_rgb(255, 165, 0), finding.message);
+                    }
+                });
+        }
+
+        self.show_extra_properties(ui, &via.extra_properties);
     }
 
     pub fn set_selected_layer(&mut self, layer_name: Option<String>) {
+        if layer_name != self.selected_layer {
+            self.etch_operating_point = None;
+        }
         self.selected_layer = layer_name;
     }
 
@@ -400,11 +705,53 @@ impl LayerDetailsPanel {
         self.selected_layer.as_ref()
     }
 
+    pub fn set_apply_half_node_scale(&mut self, apply: bool) {
+        self.apply_half_node_scale = apply;
+    }
+
+    pub fn set_sidewall_exaggeration(&mut self, exaggeration: Option<f32>) {
+        self.sidewall_exaggeration = exaggeration;
+    }
+
     pub fn toggle_visibility(&mut self) {
         self.is_open = !self.is_open;
     }
 }
 
+/// Sidewall angle of a SIDE_TANGENT from vertical, followed by a line with the angle
+/// actually drawn when the cross-section exaggerates it
+///
+/// `exaggeration` is the factor the view applies to SIDE_TANGENT, `None` for the
+/// stylized taper.
+pub fn sidewall_angle_text(side_tangent: f64, exaggeration: Option<f32>) -> String {
+    let degrees = |tangent: f64| tangent.atan().to_degrees();
+    let angle = degrees(side_tangent);
+    let drawn = degrees(f64::from(drawn_side_tangent(
+        side_tangent as f32,
+        exaggeration,
+    )));
+    let mut text = format!("Side angle: {angle:.2}° from vertical");
+    if (drawn - angle).abs() >= 0.005 {
+        let reason = match exaggeration {
+            None => "exaggerated, View → True Sidewall Angles draws it to scale".to_string(),
+            Some(factor) if (drawn - degrees(side_tangent * f64::from(factor))).abs() >= 0.005 => {
+                "clamped to keep the short edge visible".to_string()
+            }
+            Some(factor) => format!("exaggerated ×{factor:.1}"),
+        };
+        text.push_str(&format!("\nDrawn at {drawn:.2}° ({reason})"));
+    }
+    text
+}
+
+/// Length in um, followed by its half-node scaled value when scaling is applied
+fn scaled_length(length: f64, scale: Option<f64>) -> String {
+    match scale {
+        Some(factor) => format!("{length:.6} μm (scaled {:.6} μm)", length * factor),
+        None => format!("{length:.6} μm"),
+    }
+}
+
 impl Default for LayerDetailsPanel {
     fn default() -> Self {
         Self::new()
@@ -425,6 +772,21 @@ mod tests {
         assert!(!panel.show_lookup_tables);
     }
 
+    #[test]
+    fn test_sidewall_angle_text() {
+        let text = sidewall_angle_text(0.05, None);
+        assert!(text.starts_with("Side angle: 2.86° from vertical\n"));
+        assert!(text.contains("Drawn at 26.57° (exaggerated"));
+
+        // To scale, only the real angle is shown
+        assert_eq!(
+            sidewall_angle_text(-0.05, Some(1.0)),
+            "Side angle: -2.86° from vertical"
+        );
+        assert!(sidewall_angle_text(0.05, Some(2.0)).ends_with("Drawn at 5.71° (exaggerated ×2.0)"));
+        assert!(sidewall_angle_text(20.0, Some(1.0)).contains("clamped"));
+    }
+
     #[test]
     fn test_layer_selection() {
         let mut panel = LayerDetailsPanel::new();
@@ -436,6 +798,19 @@ mod tests {
         assert_eq!(panel.get_selected_layer(), None);
     }
 
+    #[test]
+    fn test_etch_operating_point_reset_on_selection() {
+        let mut panel = LayerDetailsPanel::new();
+        panel.set_selected_layer(Some("metal1".to_string()));
+        panel.etch_operating_point = Some((0.2, 0.3));
+
+        // Reselecting the same layer keeps the point, another layer starts from WMIN/SMIN
+        panel.set_selected_layer(Some("metal1".to_string()));
+        assert_eq!(panel.etch_operating_point, Some((0.2, 0.3)));
+        panel.set_selected_layer(Some("metal2".to_string()));
+        assert_eq!(panel.etch_operating_point, None);
+    }
+
     #[test]
     fn test_panel_visibility() {
         let mut panel = LayerDetailsPanel::new();
@@ -1,12 +1,83 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use crate::data::{LayerType, ProcessStack};
-use egui::{CollapsingHeader, Color32, Context, RichText, ScrollArea, SidePanel};
+use crate::data::{
+    BondStats, Layer, LayerClass, LayerType, MaterialDatabase, ProcessStack, ViaConnection,
+};
+use crate::renderer::{ColorScheme, Theme};
+use egui::color_picker::{color_edit_button_srgba, Alpha};
+use egui::{
+    Align, Button, CollapsingHeader, Color32, Context, Grid, Id, RichText, ScrollArea, SidePanel,
+    Slider, Stroke,
+};
+use std::collections::HashMap;
+
+/// Content of the layer panel below its heading
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LayerPanelTab {
+    #[default]
+    Layers,
+    Vias,
+}
+
+/// Column the via table can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViaSortColumn {
+    Name,
+    From,
+    To,
+    Area,
+    ResistancePerVia,
+}
+
+impl ViaSortColumn {
+    pub const ALL: [ViaSortColumn; 5] = [
+        ViaSortColumn::Name,
+        ViaSortColumn::From,
+        ViaSortColumn::To,
+        ViaSortColumn::Area,
+        ViaSortColumn::ResistancePerVia,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ViaSortColumn::Name => "Via",
+            ViaSortColumn::From => "From",
+            ViaSortColumn::To => "To",
+            ViaSortColumn::Area => "Area (um^2)",
+            ViaSortColumn::ResistancePerVia => "RPV (ohm)",
+        }
+    }
+}
 
 pub struct LayerPanel {
     pub is_open: bool,
     pub selected_layer: Option<String>,
+    pub cutline_enabled: bool,
+    /// Cutline bounds as indices into the layer list ordered from bottom to top
+    pub cutline_bottom: usize,
+    pub cutline_top: usize,
+    cutline_changed: bool,
+    /// Layer name and destination index of a layer dropped at a new position
+    pending_layer_move: Option<(String, usize)>,
+    via_warnings: Vec<String>,
+    /// Holds the per-layer color overrides and supplies the default swatch colors
+    color_scheme: ColorScheme,
+    layer_colors_changed: bool,
+    layer_classes_changed: bool,
+    material_database: MaterialDatabase,
+    /// Material names picked by the user, by layer name
+    material_overrides: HashMap<String, String>,
+    layer_materials_changed: bool,
+    /// Only list layers of this class
+    class_filter: Option<LayerClass>,
+    tab: LayerPanelTab,
+    /// Column and direction (ascending when true) of the via table, file order if unset
+    via_sort: Option<(ViaSortColumn, bool)>,
+    /// Bring the selected row into view on the next frame
+    scroll_to_selection: bool,
+    /// Heights of the two stacks when the loaded stack was bonded from them
+    bond_stats: Option<BondStats>,
 }
 
 impl LayerPanel {
@@ -14,6 +85,23 @@ impl LayerPanel {
         Self {
             is_open: true,
             selected_layer: None,
+            cutline_enabled: false,
+            cutline_bottom: 0,
+            cutline_top: usize::MAX,
+            cutline_changed: false,
+            pending_layer_move: None,
+            via_warnings: Vec::new(),
+            color_scheme: ColorScheme::new(),
+            layer_colors_changed: false,
+            layer_classes_changed: false,
+            material_database: MaterialDatabase::builtin(),
+            material_overrides: HashMap::new(),
+            layer_materials_changed: false,
+            class_filter: None,
+            tab: LayerPanelTab::default(),
+            via_sort: None,
+            scroll_to_selection: false,
+            bond_stats: None,
         }
     }
 
@@ -33,12 +121,32 @@ impl LayerPanel {
                 ui.separator();
 
                 if let Some(stack) = stack {
-                    ScrollArea::vertical().id_salt("layer_list").show(ui, |ui| {
-                        self.show_process_summary(ui, stack);
-                        ui.separator();
-
-                        self.show_layer_list(ui, stack, &mut layer_selected);
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut self.tab, LayerPanelTab::Layers, "Layers");
+                        ui.selectable_value(
+                            &mut self.tab,
+                            LayerPanelTab::Vias,
+                            format!("Vias ({})", stack.via_stack.len()),
+                        );
                     });
+                    ui.separator();
+
+                    match self.tab {
+                        LayerPanelTab::Layers => {
+                            ScrollArea::vertical().id_salt("layer_list").show(ui, |ui| {
+                                self.show_process_summary(ui, stack);
+                                ui.separator();
+
+                                self.show_cutline_controls(ui, stack);
+                                ui.separator();
+
+                                self.show_layer_list(ui, stack, &mut layer_selected);
+                            });
+                        }
+                        LayerPanelTab::Vias => {
+                            self.show_via_table(ui, stack, &mut layer_selected);
+                        }
+                    }
                 } else {
                     ui.centered_and_justified(|ui| {
                         ui.label("No ITF file loaded");
@@ -49,7 +157,7 @@ impl LayerPanel {
         layer_selected
     }
 
-    fn show_process_summary(&self, ui: &mut egui::Ui, stack: &ProcessStack) {
+    fn show_process_summary(&mut self, ui: &mut egui::Ui, stack: &ProcessStack) {
         CollapsingHeader::new("Process Summary")
             .default_open(true)
             .show(ui, |ui| {
@@ -67,9 +175,162 @@ impl LayerPanel {
                 }
 
                 ui.label(format!("Total height: {:.3} um", summary.total_height));
+                ui.label(format!("Fingerprint: {}", summary.fingerprint))
+                    .on_hover_text("Identical for techfiles describing the same process");
+
+                if let Some(stats) = self.bond_stats {
+                    CollapsingHeader::new("Bonded Stack")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            ui.label(format!("Bottom stack: {:.3} um", stats.bottom_height));
+                            ui.label(format!(
+                                "Bond gap: {:.3} um ({:.3} - {:.3} um)",
+                                stats.gap,
+                                stats.bottom_height,
+                                stats.bond_top_z()
+                            ));
+                            ui.label(format!("Top stack (flipped): {:.3} um", stats.top_height));
+                            ui.label(format!("Combined height: {:.3} um", stats.total_height()));
+                        });
+                }
+
+                self.show_layer_classes(ui, stack);
+                Self::show_dielectric_gaps(ui, stack);
+            });
+    }
+
+    /// Layer count per class; clicking a class filters the layer list
+    fn show_layer_classes(&mut self, ui: &mut egui::Ui, stack: &ProcessStack) {
+        CollapsingHeader::new("Layer Classes")
+            .default_open(false)
+            .show(ui, |ui| {
+                for (class, names) in stack.get_layers_by_class(&self.color_scheme.class_overrides)
+                {
+                    let selected = self.class_filter == Some(class);
+                    let response = ui
+                        .selectable_label(selected, format!("{}: {}", class.label(), names.len()))
+                        .on_hover_text(names.join(", "));
+                    if response.clicked() {
+                        self.class_filter = (!selected).then_some(class);
+                    }
+                }
+            });
+    }
+
+    /// Dielectric between adjacent conductors, the main input to coupling estimates
+    fn show_dielectric_gaps(ui: &mut egui::Ui, stack: &ProcessStack) {
+        let gaps = stack.get_dielectric_gaps();
+        if gaps.is_empty() {
+            return;
+        }
+
+        CollapsingHeader::new("Inter-Metal Dielectrics")
+            .default_open(false)
+            .show(ui, |ui| {
+                Grid::new("dielectric_gaps")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Conductors");
+                        ui.strong("Dielectric (um)");
+                        ui.strong("Gap (um)");
+                        ui.end_row();
+
+                        // Listed top first, like the layer list
+                        for gap in gaps.iter().rev() {
+                            ui.label(format!("{} / {}", gap.upper_conductor, gap.lower_conductor));
+                            ui.label(format!("{:.4}", gap.dielectric_thickness))
+                                .on_hover_text(gap.dielectric_layers.join(", "));
+                            ui.label(format!("{:.4}", gap.vertical_gap)).on_hover_text(
+                                "Top of the lower conductor to the bottom of the upper one",
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn show_cutline_controls(&mut self, ui: &mut egui::Ui, stack: &ProcessStack) {
+        if stack.layers.is_empty() {
+            return;
+        }
+
+        // ITF lists layers from top to bottom, sliders run from the bottom upwards
+        let names: Vec<&str> = stack.layers.iter().rev().map(|l| l.name()).collect();
+        let last_index = names.len() - 1;
+        self.cutline_bottom = self.cutline_bottom.min(last_index);
+        self.cutline_top = self.cutline_top.min(last_index);
+
+        CollapsingHeader::new("Cutline")
+            .default_open(false)
+            .show(ui, |ui| {
+                if ui
+                    .checkbox(&mut self.cutline_enabled, "Show partial stack")
+                    .changed()
+                {
+                    self.cutline_changed = true;
+                }
+
+                ui.add_enabled_ui(self.cutline_enabled, |ui| {
+                    let top_response = ui.add(
+                        Slider::new(&mut self.cutline_top, 0..=last_index)
+                            .text("Top")
+                            .custom_formatter(|value, _| names[value as usize].to_string()),
+                    );
+                    let bottom_response = ui.add(
+                        Slider::new(&mut self.cutline_bottom, 0..=last_index)
+                            .text("Bottom")
+                            .custom_formatter(|value, _| names[value as usize].to_string()),
+                    );
+
+                    // Keep the range ordered, moving the bound the user is not dragging
+                    if top_response.changed() {
+                        self.cutline_bottom = self.cutline_bottom.min(self.cutline_top);
+                        self.cutline_changed = true;
+                    }
+                    if bottom_response.changed() {
+                        self.cutline_top = self.cutline_top.max(self.cutline_bottom);
+                        self.cutline_changed = true;
+                    }
+                });
             });
     }
 
+    /// Bottom and top layer names of the active cutline
+    pub fn get_cutline(&self, stack: &ProcessStack) -> Option<(String, String)> {
+        if !self.cutline_enabled || stack.layers.is_empty() {
+            return None;
+        }
+
+        let last_index = stack.layers.len() - 1;
+        let bottom = self.cutline_bottom.min(last_index);
+        let top = self.cutline_top.min(last_index).max(bottom);
+
+        Some((
+            stack.layers[last_index - bottom].name().to_string(),
+            stack.layers[last_index - top].name().to_string(),
+        ))
+    }
+
+    /// Return the new cutline if the user changed it since the last call
+    pub fn take_cutline_change(
+        &mut self,
+        stack: &ProcessStack,
+    ) -> Option<Option<(String, String)>> {
+        if std::mem::take(&mut self.cutline_changed) {
+            Some(self.get_cutline(stack))
+        } else {
+            None
+        }
+    }
+
+    pub fn reset_cutline(&mut self) {
+        self.cutline_enabled = false;
+        self.cutline_bottom = 0;
+        self.cutline_top = usize::MAX;
+        self.cutline_changed = false;
+    }
+
     fn show_layer_list(
         &mut self,
         ui: &mut egui::Ui,
@@ -79,17 +340,41 @@ impl LayerPanel {
         CollapsingHeader::new("Layer Stack")
             .default_open(true)
             .show(ui, |ui| {
+                let mut dropped = None;
+
+                ui.horizontal(|ui| {
+                    ui.label("Class:");
+                    egui::ComboBox::from_id_salt("layer_class_filter")
+                        .selected_text(self.class_filter.map_or("All", LayerClass::label))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.class_filter, None, "All");
+                            for class in LayerClass::ALL {
+                                ui.selectable_value(
+                                    &mut self.class_filter,
+                                    Some(class),
+                                    class.label(),
+                                );
+                            }
+                        });
+                });
+
                 // Show layers from top to bottom (ITF order matches visual expectation)
-                for layer in stack.layers.iter() {
+                for (index, layer) in stack.layers.iter().enumerate() {
+                    let class = self.color_scheme.get_layer_class(layer);
+                    if self.class_filter.is_some_and(|filter| filter != class) {
+                        continue;
+                    }
                     let is_selected = self.selected_layer.as_deref() == Some(layer.name());
 
                     let layer_color = match layer.layer_type() {
                         LayerType::Conductor => Color32::from_rgb(255, 140, 0),
+                        LayerType::Gate => Color32::from_rgb(255, 215, 0),
                         LayerType::Dielectric => Color32::from_rgb(100, 149, 237),
                     };
 
                     let layer_icon = match layer.layer_type() {
                         LayerType::Conductor => "C",
+                        LayerType::Gate => "G",
                         LayerType::Dielectric => "D",
                     };
 
@@ -100,12 +385,27 @@ impl LayerPanel {
                         layer.thickness()
                     );
 
-                    let response = ui.selectable_label(
-                        is_selected,
-                        RichText::new(layer_text).color(layer_color),
-                    );
-
-                    if response.clicked() {
+                    let drag = ui
+                        .horizontal(|ui| {
+                            self.show_color_button(ui, layer, index);
+                            ui.dnd_drag_source(Id::new(("layer_drag", index)), index, |ui| {
+                                ui.selectable_label(
+                                    is_selected,
+                                    RichText::new(layer_text).color(layer_color),
+                                )
+                            })
+                        })
+                        .inner;
+
+                    let row = drag
+                        .inner
+                        .on_hover_text(format!("{}\nDrag to reorder", class.label()));
+                    row.context_menu(|ui| {
+                        self.show_class_menu(ui, layer);
+                        ui.separator();
+                        self.show_material_menu(ui, layer);
+                    });
+                    if row.clicked() {
                         if is_selected {
                             self.selected_layer = None;
                         } else {
@@ -113,27 +413,99 @@ impl LayerPanel {
                             *layer_selected = Some(layer.name().to_string());
                         }
                     }
+
+                    // Mark the insertion point above or below the hovered row
+                    let rect = drag.response.rect;
+                    if drag.response.dnd_hover_payload::<usize>().is_some() {
+                        if let Some(pointer) = ui.input(|i| i.pointer.interact_pos()) {
+                            let insert_at = if pointer.y < rect.center().y {
+                                index
+                            } else {
+                                index + 1
+                            };
+                            let y = if insert_at == index {
+                                rect.top()
+                            } else {
+                                rect.bottom()
+                            };
+                            ui.painter().hline(
+                                rect.x_range(),
+                                y,
+                                Stroke::new(2.0, ui.visuals().selection.stroke.color),
+                            );
+
+                            if let Some(from) = drag.response.dnd_release_payload::<usize>() {
+                                dropped = Some((*from, insert_at));
+                            }
+                        }
+                    }
+                }
+
+                if let Some((from, insert_at)) = dropped {
+                    if let (Some(layer), Some(index)) =
+                        (stack.layers.get(from), drop_index(from, insert_at))
+                    {
+                        self.pending_layer_move = Some((layer.name().to_string(), index));
+                    }
                 }
             });
+    }
 
-        // Show via connections
-        if !stack.via_stack.is_empty() {
-            CollapsingHeader::new("Via Connections")
-                .default_open(true)
+    /// Via connections with their layers, area and resistance, sortable by any column
+    fn show_via_table(
+        &mut self,
+        ui: &mut egui::Ui,
+        stack: &ProcessStack,
+        layer_selected: &mut Option<String>,
+    ) {
+        for warning in &self.via_warnings {
+            ui.colored_label(Color32::from_rgb(255, 165, 0), format!("⚠ {warning}"));
+        }
+        if stack.via_stack.is_empty() {
+            ui.label("No via connections");
+            return;
+        }
+
+        let mut vias: Vec<&ViaConnection> = stack.via_stack.iter().collect();
+        if let Some((column, ascending)) = self.via_sort {
+            sort_vias(&mut vias, column, ascending);
+        }
+
+        ScrollArea::both().id_salt("via_table").show(ui, |ui| {
+            Grid::new("via_table_grid")
+                .num_columns(ViaSortColumn::ALL.len())
+                .striped(true)
                 .show(ui, |ui| {
-                    for via in stack.via_stack.iter() {
-                        let via_color = Color32::from_rgb(192, 192, 192);
-                        let via_text = format!(
-                            "V {} -> {} ({:.2} um^2)",
-                            via.from_layer, via.to_layer, via.area
-                        );
+                    for column in ViaSortColumn::ALL {
+                        let arrow = match self.via_sort {
+                            Some((sorted, true)) if sorted == column => " ⬆",
+                            Some((sorted, false)) if sorted == column => " ⬇",
+                            _ => "",
+                        };
+                        let header = RichText::new(format!("{}{arrow}", column.label())).strong();
+                        if ui
+                            .add(Button::new(header).frame(false))
+                            .on_hover_text("Sort by this column")
+                            .clicked()
+                        {
+                            self.sort_vias_by(column);
+                        }
+                    }
+                    ui.end_row();
 
+                    for via in vias {
                         let is_selected = self.selected_layer.as_deref() == Some(&via.name);
-                        let response = ui.selectable_label(
-                            is_selected,
-                            RichText::new(via_text).color(via_color),
-                        );
-
+                        let response = ui.selectable_label(is_selected, &via.name);
+                        ui.label(&via.from_layer);
+                        ui.label(&via.to_layer);
+                        ui.label(format!("{:.4}", via.area));
+                        ui.label(format!("{:.3}", via.resistance_per_via));
+                        ui.end_row();
+
+                        if is_selected && self.scroll_to_selection {
+                            response.scroll_to_me(Some(Align::Center));
+                            self.scroll_to_selection = false;
+                        }
                         if response.clicked() {
                             if is_selected {
                                 self.selected_layer = None;
@@ -144,10 +516,245 @@ impl LayerPanel {
                         }
                     }
                 });
+        });
+    }
+
+    /// Sort the via table by `column`, reversing the order if it is already sorted by it
+    pub fn sort_vias_by(&mut self, column: ViaSortColumn) {
+        self.via_sort = match self.via_sort {
+            Some((sorted, ascending)) if sorted == column => Some((column, !ascending)),
+            _ => Some((column, true)),
+        };
+    }
+
+    pub fn get_via_sort(&self) -> Option<(ViaSortColumn, bool)> {
+        self.via_sort
+    }
+
+    pub fn set_bond_stats(&mut self, stats: Option<BondStats>) {
+        self.bond_stats = stats;
+    }
+
+    pub fn get_bond_stats(&self) -> Option<&BondStats> {
+        self.bond_stats.as_ref()
+    }
+
+    pub fn set_tab(&mut self, tab: LayerPanelTab) {
+        self.tab = tab;
+    }
+
+    pub fn get_tab(&self) -> LayerPanelTab {
+        self.tab
+    }
+
+    fn show_color_button(&mut self, ui: &mut egui::Ui, layer: &Layer, index: usize) {
+        let mut color = self.color_scheme.get_layer_color(layer, index);
+        let overridden = self.color_scheme.layer_overrides.contains_key(layer.name());
+
+        let response = color_edit_button_srgba(ui, &mut color, Alpha::Opaque);
+        if response.changed() {
+            self.set_layer_color(layer.name(), Some(color));
+        }
+        response.context_menu(|ui| {
+            if ui
+                .add_enabled(overridden, egui::Button::new("Reset color"))
+                .clicked()
+            {
+                self.set_layer_color(layer.name(), None);
+                ui.close();
+            }
+        });
+    }
+
+    /// Class choices matching the kind of layer, plus going back to the inferred class
+    fn show_class_menu(&mut self, ui: &mut egui::Ui, layer: &Layer) {
+        let overridden = self.color_scheme.class_overrides.get(layer.name()).copied();
+        ui.label(RichText::new("Layer class").strong());
+        if ui
+            .radio(
+                overridden.is_none(),
+                format!("Auto ({})", LayerClass::infer(layer).label()),
+            )
+            .clicked()
+        {
+            self.set_layer_class(layer.name(), None);
+            ui.close();
+        }
+        for class in LayerClass::ALL
+            .into_iter()
+            .filter(|class| class.is_conductor_class() == layer.is_conductor())
+        {
+            if ui.radio(overridden == Some(class), class.label()).clicked() {
+                self.set_layer_class(layer.name(), Some(class));
+                ui.close();
+            }
+        }
+    }
+
+    /// Materials of the database matching the kind of layer, plus going back to the inferred one
+    fn show_material_menu(&mut self, ui: &mut egui::Ui, layer: &Layer) {
+        let overridden = self.material_overrides.get(layer.name()).cloned();
+        let inferred = self
+            .material_database
+            .infer(layer)
+            .map_or("unknown", |material| material.name.as_str());
+        ui.label(RichText::new("Material").strong());
+        if ui
+            .radio(overridden.is_none(), format!("Auto ({inferred})"))
+            .clicked()
+        {
+            self.set_layer_material(layer.name(), None);
+            ui.close();
+        }
+        let names: Vec<String> = self
+            .material_database
+            .materials_for(layer)
+            .map(|material| material.name.clone())
+            .collect();
+        for name in names {
+            if ui
+                .radio(overridden.as_ref() == Some(&name), name.as_str())
+                .clicked()
+            {
+                self.set_layer_material(layer.name(), Some(&name));
+                ui.close();
+            }
+        }
+    }
+
+    /// Set or clear (`None`) the material override of a layer
+    pub fn set_layer_material(&mut self, layer_name: &str, material: Option<&str>) {
+        match material {
+            Some(material) => {
+                self.material_overrides
+                    .insert(layer_name.to_string(), material.to_string());
+            }
+            None => {
+                self.material_overrides.remove(layer_name);
+            }
+        }
+        self.layer_materials_changed = true;
+    }
+
+    /// Keep a layer's material override when the layer is renamed
+    pub fn rename_layer_material(&mut self, old_name: &str, new_name: &str) {
+        if let Some(material) = self.material_overrides.remove(old_name) {
+            self.set_layer_material(new_name, Some(&material));
+        }
+    }
+
+    pub fn get_layer_material_overrides(&self) -> &HashMap<String, String> {
+        &self.material_overrides
+    }
+
+    pub fn set_layer_material_overrides(&mut self, overrides: HashMap<String, String>) {
+        self.material_overrides = overrides;
+        self.layer_materials_changed = true;
+    }
+
+    /// Return whether the material overrides changed since the last call
+    pub fn take_layer_material_change(&mut self) -> bool {
+        std::mem::take(&mut self.layer_materials_changed)
+    }
+
+    pub fn get_material_database(&self) -> &MaterialDatabase {
+        &self.material_database
+    }
+
+    pub fn set_material_database(&mut self, database: MaterialDatabase) {
+        self.material_database = database;
+        self.layer_materials_changed = true;
+    }
+
+    /// Set or clear (`None`) the class override of a layer
+    pub fn set_layer_class(&mut self, layer_name: &str, class: Option<LayerClass>) {
+        match class {
+            Some(class) => {
+                self.color_scheme
+                    .class_overrides
+                    .insert(layer_name.to_string(), class);
+            }
+            None => {
+                self.color_scheme.class_overrides.remove(layer_name);
+            }
+        }
+        self.layer_classes_changed = true;
+    }
+
+    /// Keep a layer's class override when the layer is renamed
+    pub fn rename_layer_class(&mut self, old_name: &str, new_name: &str) {
+        if let Some(class) = self.color_scheme.class_overrides.remove(old_name) {
+            self.set_layer_class(new_name, Some(class));
+        }
+    }
+
+    pub fn get_layer_class_overrides(&self) -> &HashMap<String, LayerClass> {
+        &self.color_scheme.class_overrides
+    }
+
+    pub fn set_layer_class_overrides(&mut self, overrides: HashMap<String, LayerClass>) {
+        self.color_scheme.class_overrides = overrides;
+        self.layer_classes_changed = true;
+    }
+
+    /// Return the class overrides if the user changed them since the last call
+    pub fn take_layer_class_change(&mut self) -> Option<HashMap<String, LayerClass>> {
+        std::mem::take(&mut self.layer_classes_changed)
+            .then(|| self.color_scheme.class_overrides.clone())
+    }
+
+    pub fn set_class_filter(&mut self, class: Option<LayerClass>) {
+        self.class_filter = class;
+    }
+
+    pub fn get_class_filter(&self) -> Option<LayerClass> {
+        self.class_filter
+    }
+
+    /// Set or clear (`None`) the color override of a layer
+    pub fn set_layer_color(&mut self, layer_name: &str, color: Option<Color32>) {
+        self.color_scheme.set_layer_override(layer_name, color);
+        self.layer_colors_changed = true;
+    }
+
+    /// Keep a layer's color override when the layer is renamed
+    pub fn rename_layer_color(&mut self, old_name: &str, new_name: &str) {
+        if let Some(color) = self.color_scheme.layer_overrides.remove(old_name) {
+            self.set_layer_color(new_name, Some(color));
         }
     }
 
+    pub fn get_layer_color_overrides(&self) -> &HashMap<String, Color32> {
+        &self.color_scheme.layer_overrides
+    }
+
+    pub fn set_layer_color_overrides(&mut self, overrides: HashMap<String, Color32>) {
+        self.color_scheme.layer_overrides = overrides;
+        self.layer_colors_changed = true;
+    }
+
+    /// Show swatches in the colors of `theme`, which the viewer gets separately
+    pub fn set_theme(&mut self, theme: Option<Theme>) {
+        self.color_scheme.theme = theme;
+    }
+
+    /// Return the color overrides if the user changed them since the last call
+    pub fn take_layer_color_change(&mut self) -> Option<HashMap<String, Color32>> {
+        std::mem::take(&mut self.layer_colors_changed)
+            .then(|| self.color_scheme.layer_overrides.clone())
+    }
+
+    /// Return the layer name and new index if the user dropped a layer since the last call
+    pub fn take_layer_move(&mut self) -> Option<(String, usize)> {
+        self.pending_layer_move.take()
+    }
+
+    pub fn set_via_warnings(&mut self, warnings: Vec<String>) {
+        self.via_warnings = warnings;
+    }
+
     pub fn set_selected_layer(&mut self, layer_name: Option<String>) {
+        self.scroll_to_selection |= layer_name.is_some() && layer_name != self.selected_layer;
         self.selected_layer = layer_name;
     }
 
@@ -160,6 +767,39 @@ impl LayerPanel {
     }
 }
 
+/// Destination index for a layer dragged from `from` to the gap before `insert_at`
+///
+/// Returns None when the drop leaves the layer where it was.
+/// Order `vias` by `column`, keeping the file order among equal values
+pub fn sort_vias(vias: &mut [&ViaConnection], column: ViaSortColumn, ascending: bool) {
+    vias.sort_by(|a, b| {
+        let order = match column {
+            ViaSortColumn::Name => a.name.cmp(&b.name),
+            ViaSortColumn::From => a.from_layer.cmp(&b.from_layer),
+            ViaSortColumn::To => a.to_layer.cmp(&b.to_layer),
+            ViaSortColumn::Area => a.area.total_cmp(&b.area),
+            ViaSortColumn::ResistancePerVia => {
+                a.resistance_per_via.total_cmp(&b.resistance_per_via)
+            }
+        };
+        if ascending {
+            order
+        } else {
+            order.reverse()
+        }
+    });
+}
+
+fn drop_index(from: usize, insert_at: usize) -> Option<usize> {
+    // Removing the layer first shifts every gap below it up by one
+    let index = if insert_at > from {
+        insert_at - 1
+    } else {
+        insert_at
+    };
+    (index != from).then_some(index)
+}
+
 impl Default for LayerPanel {
     fn default() -> Self {
         Self::new()
@@ -188,6 +828,167 @@ mod tests {
         assert_eq!(panel.get_selected_layer(), None);
     }
 
+    #[test]
+    fn test_via_table_sorting() {
+        let vias = [
+            ViaConnection::new("via2".into(), "m2".into(), "m3".into(), 0.04, 1.5),
+            ViaConnection::new("via1".into(), "m1".into(), "m2".into(), 0.09, 2.5),
+            ViaConnection::new("cont".into(), "poly".into(), "m1".into(), 0.01, 8.0),
+        ];
+        let names = |column, ascending| {
+            let mut sorted: Vec<&ViaConnection> = vias.iter().collect();
+            sort_vias(&mut sorted, column, ascending);
+            sorted
+                .iter()
+                .map(|via| via.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(ViaSortColumn::Name, true), ["cont", "via1", "via2"]);
+        assert_eq!(names(ViaSortColumn::Area, false), ["via1", "via2", "cont"]);
+        assert_eq!(
+            names(ViaSortColumn::ResistancePerVia, true),
+            ["via2", "via1", "cont"]
+        );
+
+        // Clicking the same header reverses the order
+        let mut panel = LayerPanel::new();
+        assert_eq!(panel.get_tab(), LayerPanelTab::Layers);
+        assert_eq!(panel.get_via_sort(), None);
+        panel.sort_vias_by(ViaSortColumn::From);
+        assert_eq!(panel.get_via_sort(), Some((ViaSortColumn::From, true)));
+        panel.sort_vias_by(ViaSortColumn::From);
+        assert_eq!(panel.get_via_sort(), Some((ViaSortColumn::From, false)));
+        panel.sort_vias_by(ViaSortColumn::Area);
+        assert_eq!(panel.get_via_sort(), Some((ViaSortColumn::Area, true)));
+        panel.set_tab(LayerPanelTab::Vias);
+        assert_eq!(panel.get_tab(), LayerPanelTab::Vias);
+    }
+
+    #[test]
+    fn test_layer_class_overrides() {
+        let mut panel = LayerPanel::new();
+        assert!(panel.take_layer_class_change().is_none());
+
+        panel.set_layer_class("imd1", Some(LayerClass::EtchStop));
+        let overrides = panel.take_layer_class_change().unwrap();
+        assert_eq!(overrides.get("imd1"), Some(&LayerClass::EtchStop));
+        assert!(panel.take_layer_class_change().is_none());
+
+        panel.rename_layer_class("imd1", "esl1");
+        assert_eq!(
+            panel.get_layer_class_overrides().get("esl1"),
+            Some(&LayerClass::EtchStop)
+        );
+        assert!(!panel.get_layer_class_overrides().contains_key("imd1"));
+
+        panel.set_layer_class("esl1", None);
+        assert!(panel.get_layer_class_overrides().is_empty());
+
+        panel.set_class_filter(Some(LayerClass::Capping));
+        assert_eq!(panel.get_class_filter(), Some(LayerClass::Capping));
+    }
+
+    #[test]
+    fn test_layer_material_overrides() {
+        let mut panel = LayerPanel::new();
+        assert!(!panel.take_layer_material_change());
+        assert!(panel.get_material_database().get("Cu").is_some());
+
+        panel.set_layer_material("metal1", Some("Al"));
+        assert!(panel.take_layer_material_change());
+        assert!(!panel.take_layer_material_change());
+
+        panel.rename_layer_material("metal1", "M1");
+        assert_eq!(
+            panel.get_layer_material_overrides().get("M1"),
+            Some(&"Al".to_string())
+        );
+        assert!(!panel.get_layer_material_overrides().contains_key("metal1"));
+
+        panel.set_layer_material("M1", None);
+        assert!(panel.get_layer_material_overrides().is_empty());
+    }
+
+    #[test]
+    fn test_drop_index() {
+        // Dropping a layer into the gaps around itself is a no-op
+        assert_eq!(drop_index(2, 2), None);
+        assert_eq!(drop_index(2, 3), None);
+
+        assert_eq!(drop_index(2, 0), Some(0));
+        assert_eq!(drop_index(0, 3), Some(2));
+        assert_eq!(drop_index(1, 4), Some(3));
+
+        let mut panel = LayerPanel::new();
+        assert!(panel.take_layer_move().is_none());
+        panel.pending_layer_move = Some(("metal1".to_string(), 0));
+        assert_eq!(panel.take_layer_move(), Some(("metal1".to_string(), 0)));
+        assert!(panel.take_layer_move().is_none());
+    }
+
+    #[test]
+    fn test_layer_color_overrides() {
+        let mut panel = LayerPanel::new();
+        assert!(panel.take_layer_color_change().is_none());
+
+        panel.set_layer_color("metal1", Some(Color32::RED));
+        let overrides = panel.take_layer_color_change().unwrap();
+        assert_eq!(overrides.get("metal1"), Some(&Color32::RED));
+        assert!(panel.take_layer_color_change().is_none());
+
+        panel.rename_layer_color("metal1", "M1");
+        let overrides = panel.take_layer_color_change().unwrap();
+        assert_eq!(overrides.get("M1"), Some(&Color32::RED));
+        assert!(!overrides.contains_key("metal1"));
+
+        // Renaming a layer without an override changes nothing
+        panel.rename_layer_color("metal2", "M2");
+        assert!(panel.take_layer_color_change().is_none());
+
+        panel.set_layer_color("M1", None);
+        assert!(panel.take_layer_color_change().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cutline_range() {
+        use crate::data::{ConductorLayer, DielectricLayer, Layer, TechnologyInfo};
+
+        let mut stack = ProcessStack::new(TechnologyInfo::new("cutline".to_string()));
+        for name in ["metal3", "metal2", "metal1"] {
+            stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+                name.to_string(),
+                0.2,
+            ))));
+        }
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "substrate".to_string(),
+            5.0,
+            11.9,
+        )));
+
+        let mut panel = LayerPanel::new();
+        assert!(panel.get_cutline(&stack).is_none());
+
+        panel.cutline_enabled = true;
+        assert_eq!(
+            panel.get_cutline(&stack),
+            Some(("substrate".to_string(), "metal3".to_string()))
+        );
+
+        panel.cutline_bottom = 2;
+        panel.cutline_top = 3;
+        assert_eq!(
+            panel.get_cutline(&stack),
+            Some(("metal2".to_string(), "metal3".to_string()))
+        );
+
+        assert!(panel.take_cutline_change(&stack).is_none());
+
+        panel.reset_cutline();
+        assert!(!panel.cutline_enabled);
+        assert!(panel.get_cutline(&stack).is_none());
+    }
+
     #[test]
     fn test_panel_visibility() {
         let mut panel = LayerPanel::new();
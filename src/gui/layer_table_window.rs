@@ -0,0 +1,419 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{MaterialDatabase, MetalDensities, ProcessStack};
+use crate::utils::{
+    export_diagnostics_csv, export_dielectric_gaps_csv, export_layer_materials_csv,
+    export_layer_table_csv, export_metal_density_csv, export_temperature_drift_csv, DerivedColumn,
+    QUERY_FIELDS,
+};
+use egui::{Context, Grid, TextEdit, Window};
+use poll_promise::Promise;
+use rfd::AsyncFileDialog;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Layer table CSV export with user-defined derived columns
+pub struct LayerTableWindow {
+    open: bool,
+    columns: Vec<DerivedColumn>,
+    variables: Vec<(String, String)>, // (name, value text) pairs as typed
+    include_gaps: bool,
+    include_diagnostics: bool,
+    include_density: bool,
+    include_temperature_drift: bool,
+    include_materials: bool,
+    /// Densities from the metal density strip, for the density table
+    metal_densities: MetalDensities,
+    /// Database and user overrides of the layer panel, for the materials table
+    material_database: MaterialDatabase,
+    material_overrides: HashMap<String, String>,
+    export_dialog_promise: Option<Promise<Option<PathBuf>>>,
+    export_status: Option<String>,
+    error_message: Option<String>,
+}
+
+impl LayerTableWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            columns: vec![DerivedColumn::new("r_wmin", "rpsq*length/wmin")],
+            variables: vec![("length".to_string(), "100".to_string())],
+            include_gaps: false,
+            include_diagnostics: true,
+            include_density: false,
+            include_temperature_drift: false,
+            include_materials: false,
+            metal_densities: MetalDensities::new(),
+            material_database: MaterialDatabase::builtin(),
+            material_overrides: HashMap::new(),
+            export_dialog_promise: None,
+            export_status: None,
+            error_message: None,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_columns(&self) -> &[DerivedColumn] {
+        &self.columns
+    }
+
+    /// Variables with a valid numeric value, ignoring unnamed rows
+    pub fn get_variables(&self) -> HashMap<String, f64> {
+        self.variables
+            .iter()
+            .filter(|(name, _)| !name.trim().is_empty())
+            .filter_map(|(name, value)| {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .map(|value| (name.trim().to_string(), value))
+            })
+            .collect()
+    }
+
+    /// Derived columns with a name and an expression
+    fn complete_columns(&self) -> Vec<DerivedColumn> {
+        self.columns
+            .iter()
+            .filter(|column| !column.name.trim().is_empty() && !column.expression.trim().is_empty())
+            .cloned()
+            .collect()
+    }
+
+    pub fn set_include_gaps(&mut self, include: bool) {
+        self.include_gaps = include;
+    }
+
+    pub fn set_include_diagnostics(&mut self, include: bool) {
+        self.include_diagnostics = include;
+    }
+
+    pub fn set_include_density(&mut self, include: bool) {
+        self.include_density = include;
+    }
+
+    pub fn set_include_temperature_drift(&mut self, include: bool) {
+        self.include_temperature_drift = include;
+    }
+
+    pub fn set_include_materials(&mut self, include: bool) {
+        self.include_materials = include;
+    }
+
+    pub fn set_materials(
+        &mut self,
+        database: MaterialDatabase,
+        overrides: HashMap<String, String>,
+    ) {
+        self.material_database = database;
+        self.material_overrides = overrides;
+    }
+
+    pub fn set_metal_densities(&mut self, densities: MetalDensities) {
+        self.metal_densities = densities;
+    }
+
+    /// Layer table, followed by the dielectric gaps, the metal densities, the temperature
+    /// drift, the layer materials and the parse diagnostics when enabled
+    ///
+    /// Each appended table is preceded by a blank line.
+    pub fn build_csv(&self, stack: &ProcessStack) -> String {
+        let mut csv =
+            export_layer_table_csv(stack, &self.complete_columns(), &self.get_variables());
+        if self.include_gaps {
+            csv.push('\n');
+            csv.push_str(&export_dielectric_gaps_csv(stack));
+        }
+        if self.include_density {
+            csv.push('\n');
+            csv.push_str(&export_metal_density_csv(stack, &self.metal_densities));
+        }
+        if self.include_temperature_drift {
+            csv.push('\n');
+            csv.push_str(&export_temperature_drift_csv(stack));
+        }
+        if self.include_materials {
+            csv.push('\n');
+            csv.push_str(&export_layer_materials_csv(
+                stack,
+                &self.material_database,
+                &self.material_overrides,
+            ));
+        }
+        if self.include_diagnostics && !stack.diagnostics.is_empty() {
+            csv.push('\n');
+            csv.push_str(&export_diagnostics_csv(&stack.diagnostics));
+        }
+        csv
+    }
+
+    pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) {
+        // Check if the export dialog promise is ready
+        if let Some(promise) = &self.export_dialog_promise {
+            if let Some(result) = promise.ready() {
+                if let (Some(path), Some(stack)) = (result.clone(), stack) {
+                    self.export_csv(&path, stack);
+                }
+                self.export_dialog_promise = None;
+            }
+        }
+
+        if !self.open {
+            return;
+        }
+
+        let mut open = self.open;
+        Window::new("Export Layer Table")
+            .open(&mut open)
+            .default_size([560.0, 360.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(format!("Standard columns: {}", QUERY_FIELDS.join(", ")));
+                ui.separator();
+
+                self.show_variables(ui);
+                ui.separator();
+                self.show_columns(ui, stack);
+                ui.separator();
+
+                ui.checkbox(
+                    &mut self.include_gaps,
+                    "Include inter-metal dielectric gaps",
+                )
+                .on_hover_text("Append the dielectric thickness between adjacent conductors");
+
+                ui.checkbox(&mut self.include_density, "Include metal densities")
+                    .on_hover_text(
+                        "Append the densities of the metal density strip and the thickness at each",
+                    );
+
+                ui.checkbox(
+                    &mut self.include_temperature_drift,
+                    "Include temperature drift",
+                )
+                .on_hover_text(
+                    "Append the resistance drift of each conductor from -40 °C to 125 °C",
+                );
+
+                ui.checkbox(&mut self.include_materials, "Include materials")
+                    .on_hover_text(
+                        "Append the material of each layer, inferred or picked in the layer panel",
+                    );
+
+                let diagnostic_count = stack.map_or(0, |stack| stack.diagnostics.len());
+                ui.checkbox(
+                    &mut self.include_diagnostics,
+                    format!("Include parse diagnostics ({diagnostic_count})"),
+                )
+                .on_hover_text("Append skipped lines and auto-created layers with their severity");
+
+                ui.horizontal(|ui| {
+                    let can_export = stack.is_some() && self.export_dialog_promise.is_none();
+                    if ui
+                        .add_enabled(can_export, egui::Button::new("Export CSV"))
+                        .clicked()
+                    {
+                        self.open_export_dialog();
+                    }
+                    if let Some(ref status) = self.export_status {
+                        ui.label(status);
+                    }
+                });
+
+                if let Some(ref error) = self.error_message {
+                    ui.colored_label(egui::Color32::RED, format!("Error: {error}"));
+                }
+            });
+        self.open = open;
+    }
+
+    fn show_variables(&mut self, ui: &mut egui::Ui) {
+        ui.label("Variables");
+        let mut remove_index = None;
+
+        Grid::new("layer_table_variables")
+            .num_columns(3)
+            .show(ui, |ui| {
+                for (index, (name, value)) in self.variables.iter_mut().enumerate() {
+                    ui.add(TextEdit::singleline(name).desired_width(120.0));
+                    let valid = value.trim().parse::<f64>().is_ok();
+                    let mut edit = TextEdit::singleline(value).desired_width(120.0);
+                    if !valid {
+                        edit = edit.text_color(egui::Color32::RED);
+                    }
+                    ui.add(edit);
+                    if ui
+                        .small_button("✖")
+                        .on_hover_text("Remove variable")
+                        .clicked()
+                    {
+                        remove_index = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(index) = remove_index {
+            self.variables.remove(index);
+        }
+        if ui.button("Add variable").clicked() {
+            self.variables.push((String::new(), "0".to_string()));
+        }
+    }
+
+    fn show_columns(&mut self, ui: &mut egui::Ui, stack: Option<&ProcessStack>) {
+        ui.label("Derived columns (evaluated per conductor)");
+        let variables = self.get_variables();
+        let mut remove_index = None;
+
+        Grid::new("layer_table_columns")
+            .num_columns(3)
+            .show(ui, |ui| {
+                for (index, column) in self.columns.iter_mut().enumerate() {
+                    ui.add(
+                        TextEdit::singleline(&mut column.name)
+                            .hint_text("name")
+                            .desired_width(120.0),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            TextEdit::singleline(&mut column.expression)
+                                .hint_text("expression, e.g. rpsq*length/wmin")
+                                .desired_width(240.0),
+                        );
+                        if let Some(stack) = stack {
+                            if let Err(e) = column.validate(stack, &variables) {
+                                ui.colored_label(egui::Color32::RED, "⚠")
+                                    .on_hover_text(e.to_string());
+                            }
+                        }
+                    });
+                    if ui
+                        .small_button("✖")
+                        .on_hover_text("Remove column")
+                        .clicked()
+                    {
+                        remove_index = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(index) = remove_index {
+            self.columns.remove(index);
+        }
+        if ui.button("Add column").clicked() {
+            self.columns.push(DerivedColumn::new("", ""));
+        }
+    }
+
+    fn open_export_dialog(&mut self) {
+        let task = AsyncFileDialog::new()
+            .add_filter("CSV Files", &["csv"])
+            .add_filter("All Files", &["*"])
+            .set_title("Export Layer Table")
+            .set_file_name("layers.csv")
+            .save_file();
+
+        let promise = Promise::spawn_thread("export_dialog", move || {
+            pollster::block_on(async move { task.await.map(|handle| handle.path().to_path_buf()) })
+        });
+
+        self.export_dialog_promise = Some(promise);
+    }
+
+    fn export_csv(&mut self, path: &Path, stack: &ProcessStack) {
+        match std::fs::write(path, self.build_csv(stack)) {
+            Ok(()) => {
+                self.export_status = Some(format!("Exported to {}", path.display()));
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.export_status = None;
+                self.error_message = Some(format!("Failed to export table: {e}"));
+            }
+        }
+    }
+}
+
+impl Default for LayerTableWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, Diagnostic, Layer, Severity, TechnologyInfo};
+
+    #[test]
+    fn test_layer_table_window() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.2);
+        metal.electrical_props.rpsq = Some(0.05);
+        metal.physical_props.width_min = Some(0.1);
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+
+        let mut window = LayerTableWindow::new();
+        assert!(!window.is_open());
+
+        // Incomplete rows are skipped
+        window.columns.push(DerivedColumn::new("", "rpsq"));
+        window
+            .variables
+            .push(("width".to_string(), "abc".to_string()));
+        assert_eq!(window.get_variables().len(), 1);
+        assert_eq!(window.complete_columns().len(), 1);
+
+        let csv = window.build_csv(&stack);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].ends_with(",r_wmin"));
+        assert!(lines[1].ends_with(",50"));
+        assert_eq!(lines.len(), 2);
+
+        stack.diagnostics.push(
+            Diagnostic::new(Severity::Warning, "Skipped unrecognized line: FOO").with_line(3),
+        );
+        let csv = window.build_csv(&stack);
+        assert!(
+            csv.ends_with("\nseverity,line,message\nwarning,3,Skipped unrecognized line: FOO\n")
+        );
+
+        window.set_include_diagnostics(false);
+        assert_eq!(window.build_csv(&stack).lines().count(), 2);
+
+        window.set_include_gaps(true);
+        let csv = window.build_csv(&stack);
+        assert!(csv.ends_with("\nlower,upper,dielectric_thickness,vertical_gap,dielectrics\n"));
+
+        let mut densities = MetalDensities::new();
+        densities.set("metal1", Some(0.25));
+        window.set_metal_densities(densities);
+        window.set_include_density(true);
+        assert!(window
+            .build_csv(&stack)
+            .ends_with("\nmetal1,0.25,user,0.2,0.2\n"));
+
+        window.set_include_temperature_drift(true);
+        assert!(window
+            .build_csv(&stack)
+            .ends_with("\nconductor,reference_temperature,crt_width,cold_drift,hot_drift,worst_drift,worst_temperature\n"));
+
+        window.set_materials(
+            MaterialDatabase::builtin(),
+            HashMap::from([("metal1".to_string(), "Al".to_string())]),
+        );
+        window.set_include_materials(true);
+        assert!(window.build_csv(&stack).contains("\nmetal1,Al,user,"));
+    }
+}
@@ -0,0 +1,297 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{LayerMaterial, Material, ProcessStack};
+use crate::renderer::{ColorScheme, VIA_COLOR};
+use egui::{Color32, Context, ScrollArea, Sense, Ui, Window};
+use std::collections::HashMap;
+
+/// Fill alpha of layers and vias outside the isolated set, 20%
+pub const DIMMED_ALPHA: u8 = 51;
+
+/// Color legend of the stack; clicking entries isolates layers by dimming all others
+pub struct LegendWindow {
+    open: bool,
+    /// Layers and vias shown at full strength, nothing dimmed when empty
+    isolated: Vec<String>,
+    changed: bool,
+}
+
+impl LegendWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            isolated: Vec::new(),
+            changed: false,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_isolated(&self) -> &[String] {
+        &self.isolated
+    }
+
+    /// Handle a click on the entry of `name`, `extend` when shift is held
+    ///
+    /// A click isolates the entry, or restores the full view if it was the only one
+    /// isolated. A shift-click adds the entry to the isolated set or removes it.
+    pub fn click_entry(&mut self, name: &str, extend: bool) {
+        let position = self.isolated.iter().position(|isolated| isolated == name);
+        if extend {
+            match position {
+                Some(index) => {
+                    self.isolated.remove(index);
+                }
+                None => self.isolated.push(name.to_string()),
+            }
+        } else if position.is_some() && self.isolated.len() == 1 {
+            self.isolated.clear();
+        } else {
+            self.isolated = vec![name.to_string()];
+        }
+        self.changed = true;
+    }
+
+    pub fn clear_isolation(&mut self) {
+        if !self.isolated.is_empty() {
+            self.isolated.clear();
+            self.changed = true;
+        }
+    }
+
+    /// Keep isolating a renamed layer or via
+    pub fn rename_layer(&mut self, old_name: &str, new_name: &str) {
+        if let Some(isolated) = self.isolated.iter_mut().find(|name| *name == old_name) {
+            *isolated = new_name.to_string();
+            self.changed = true;
+        }
+    }
+
+    /// Return whether the isolated set changed since the last call
+    pub fn take_isolation_change(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Alphas dimming every layer and via of `stack` outside the isolated set
+    pub fn get_alpha_overrides(&self, stack: &ProcessStack) -> HashMap<String, u8> {
+        if self.isolated.is_empty() {
+            return HashMap::new();
+        }
+        stack
+            .layers
+            .iter()
+            .map(|layer| layer.name())
+            .chain(stack.via_stack.iter().map(|via| via.name.as_str()))
+            .filter(|name| !self.isolated.iter().any(|isolated| isolated == name))
+            .map(|name| (name.to_string(), DIMMED_ALPHA))
+            .collect()
+    }
+
+    /// Show the legend, naming the material of each layer from `materials` in stack order
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        stack: Option<&ProcessStack>,
+        colors: &ColorScheme,
+        materials: &[LayerMaterial],
+    ) {
+        if !self.open {
+            return;
+        }
+
+        let mut open = self.open;
+        Window::new("Legend")
+            .open(&mut open)
+            .default_size([220.0, 420.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let Some(stack) = stack else {
+                    ui.label("Load a stack to show its legend.");
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    ui.weak("Click to isolate, Shift+click to add");
+                    if ui
+                        .add_enabled(!self.isolated.is_empty(), egui::Button::new("Show All"))
+                        .clicked()
+                    {
+                        self.clear_isolation();
+                    }
+                });
+                ui.separator();
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    // Top of the stack first, as in the layer panel
+                    for (index, layer) in stack.layers.iter().enumerate() {
+                        let material = materials
+                            .get(index)
+                            .filter(|material| material.layer_name == layer.name())
+                            .and_then(|material| material.material.as_ref());
+                        self.entry(
+                            ui,
+                            layer.name(),
+                            colors.get_layer_color(layer, index),
+                            material,
+                        );
+                    }
+                    if !stack.via_stack.is_empty() {
+                        ui.separator();
+                        for via in stack.via_stack.iter() {
+                            self.entry(ui, &via.name, VIA_COLOR, None);
+                        }
+                    }
+                    let used = used_materials(materials);
+                    if !used.is_empty() {
+                        ui.separator();
+                        ui.strong("Materials");
+                        for (material, count) in used {
+                            ui.horizontal(|ui| {
+                                swatch(ui, material_color(material));
+                                ui.label(format!("{} ({count})", material.name));
+                            });
+                        }
+                    }
+                });
+            });
+        self.open = open;
+    }
+
+    fn entry(&mut self, ui: &mut Ui, name: &str, color: Color32, material: Option<&Material>) {
+        let isolated = self.isolated.iter().any(|isolated| isolated == name);
+        let dimmed = !self.isolated.is_empty() && !isolated;
+        ui.horizontal(|ui| {
+            swatch(
+                ui,
+                if dimmed {
+                    color.gamma_multiply(DIMMED_ALPHA as f32 / 255.0)
+                } else {
+                    color.to_opaque()
+                },
+            );
+            let response = ui.selectable_label(isolated, name);
+            if response.clicked() {
+                let extend = ui.input(|input| input.modifiers.shift);
+                self.click_entry(name, extend);
+            }
+            if let Some(material) = material {
+                ui.weak(&material.name);
+            }
+        });
+    }
+}
+
+fn swatch(ui: &mut Ui, color: Color32) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), Sense::hover());
+    ui.painter().rect_filled(rect, 2.0, color);
+}
+
+fn material_color(material: &Material) -> Color32 {
+    let [r, g, b] = material.color;
+    Color32::from_rgb(r, g, b)
+}
+
+/// Materials tagged on at least one layer with their layer count, in order of first use
+pub fn used_materials(materials: &[LayerMaterial]) -> Vec<(&Material, usize)> {
+    let mut used: Vec<(&Material, usize)> = Vec::new();
+    for material in materials.iter().filter_map(|layer| layer.material.as_ref()) {
+        match used.iter_mut().find(|(seen, _)| seen.name == material.name) {
+            Some((_, count)) => *count += 1,
+            None => used.push((material, 1)),
+        }
+    }
+    used
+}
+
+impl Default for LegendWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{
+        ConductorLayer, DielectricLayer, Layer, MaterialDatabase, TechnologyInfo, ViaConnection,
+    };
+
+    #[test]
+    fn test_legend_isolation() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("legend".to_string()));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.2,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd".to_string(),
+            0.3,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.2,
+        ))));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            5.0,
+        ));
+
+        let mut legend = LegendWindow::new();
+        assert!(legend.get_alpha_overrides(&stack).is_empty());
+
+        legend.click_entry("metal1", false);
+        assert!(legend.take_isolation_change());
+        let overrides = legend.get_alpha_overrides(&stack);
+        assert_eq!(overrides.len(), 3);
+        assert_eq!(overrides.get("via1"), Some(&DIMMED_ALPHA));
+        assert!(!overrides.contains_key("metal1"));
+
+        // Shift-click extends the set, a plain click on another entry replaces it
+        legend.click_entry("via1", true);
+        assert_eq!(legend.get_isolated(), ["metal1", "via1"]);
+        legend.click_entry("metal1", true);
+        assert_eq!(legend.get_isolated(), ["via1"]);
+        legend.click_entry("imd", false);
+        assert_eq!(legend.get_isolated(), ["imd"]);
+
+        legend.rename_layer("imd", "imd1");
+        assert_eq!(legend.get_isolated(), ["imd1"]);
+
+        // Clicking the only isolated entry again restores the full view
+        legend.click_entry("imd1", false);
+        assert!(legend.get_isolated().is_empty());
+        assert!(legend.get_alpha_overrides(&stack).is_empty());
+    }
+
+    #[test]
+    fn test_used_materials() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("legend".to_string()));
+        for name in ["imd2", "imd1"] {
+            stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+                name.to_string(),
+                0.3,
+                4.0,
+            )));
+        }
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.2,
+        ))));
+
+        let materials = stack.get_layer_materials(&MaterialDatabase::builtin(), &HashMap::new());
+        let used = used_materials(&materials);
+        assert_eq!(used.len(), 1);
+        assert_eq!(used[0].0.name, "SiO2");
+        assert_eq!(used[0].1, 2);
+    }
+}
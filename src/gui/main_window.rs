@@ -1,43 +1,190 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use crate::data::ProcessStack;
+use crate::data::{
+    BondOptions, Layer, LayerClass, LintConfig, MetalDensities, NameRole, ProblemTarget,
+    ProcessStack, StackChange, StackDocument, StackEdit,
+};
+use crate::export::{
+    export_dxf, export_emf, export_gds, export_html, export_slide_png, render_slide, ExportFormat,
+    GdsOptions, SlideOptions,
+};
 use crate::gui::{
-    FileMenu, LayerDetailsPanel, LayerPanel, ResistancePlotWindow, StackViewer, Toolbar,
-    ToolbarAction,
+    DensityStrip, FileMenu, GuidesWindow, HistogramWindow, HistoryWindow, InputBindings,
+    LayerDetailsPanel, LayerPanel, LayerPanelTab, LayerTableWindow, LegendWindow, NamingWindow,
+    PreferencesWindow, ProblemsWindow, ResistancePlotWindow, ResourcesWindow, SearchWindow,
+    SelectionChange, SelectionSource, SelectionState, SourceWindow, StackViewer, StructuresWindow,
+    TemperatureDriftWindow, Toolbar, ToolbarAction, ViaChainWindow, ViaResistanceWindow,
+};
+use crate::parser::{read_itf_source, CancelToken, DuplicateNamePolicy, ItfParser};
+use crate::renderer::{
+    DisplayDefaults, FillStyle, FitAxis, Guide, RenderMode, StructureAnnotation, Theme, ThemeFile,
+    ViewAnchor, ViewLimits, THEME_POLL_INTERVAL,
+};
+use crate::utils::{
+    generate_random_stack, get_log_file_path, guard, import_csv_stack, open_with_system_viewer,
+    set_log_verbosity, CaughtPanic, CrashReport, LogVerbosity,
 };
-use crate::parser::parse_itf_file;
-use egui::Context;
+use egui::{Color32, Context, TopBottomPanel};
 use poll_promise::Promise;
 use rfd::AsyncFileDialog;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Storage key of the per-layer color overrides
+const LAYER_COLORS_KEY: &str = "layer_color_overrides";
+/// Storage key of the per-layer class overrides
+const LAYER_CLASSES_KEY: &str = "layer_class_overrides";
+/// Storage key of the per-layer material overrides
+const LAYER_MATERIALS_KEY: &str = "layer_material_overrides";
+/// Storage key of the end of the stack anchored at y = 0
+const VIEW_ANCHOR_KEY: &str = "view_anchor";
+/// Storage key of the stack viewer scroll behavior and key bindings
+const INPUT_BINDINGS_KEY: &str = "input_bindings";
+/// Storage key of the enabled lint rules
+const LINT_CONFIG_KEY: &str = "lint_config";
+/// Storage key of the factor beyond which drawn widths are reported as misleading
+const MAX_WIDTH_DISTORTION_KEY: &str = "max_width_distortion";
+/// Storage key of the reference guide lines
+const GUIDES_KEY: &str = "guides";
+/// Storage key of the example structures drawn over the cross-section
+const STRUCTURES_KEY: &str = "example_structures";
+/// Storage key of the assumed metal fill densities
+const METAL_DENSITIES_KEY: &str = "metal_densities";
+/// Storage key of the log file verbosity
+const LOG_VERBOSITY_KEY: &str = "log_verbosity";
+/// Storage key of the stack viewer zoom and pan limits
+const VIEW_LIMITS_KEY: &str = "view_limits";
+/// Storage key of the options of face-to-face bonding
+const BOND_OPTIONS_KEY: &str = "bond_options";
+/// Storage key of the view settings applied to opened stacks
+const DISPLAY_DEFAULTS_KEY: &str = "display_defaults";
+/// Storage key of how layers named like an earlier one are parsed
+const DUPLICATE_NAMES_KEY: &str = "duplicate_names";
+/// Storage key of the roles assigned to layer and via names
+const NAMING_OVERRIDES_KEY: &str = "naming_overrides";
+/// Storage key of whether the view is labeled with display names
+const USE_DISPLAY_NAMES_KEY: &str = "use_display_names";
+/// Storage key of the path of the theme file
+const THEME_PATH_KEY: &str = "theme_path";
+
+/// Why a background load produced no stack
+enum LoadFailure {
+    Error(String),
+    Panic(CaughtPanic),
+}
+
+/// ITF file being read and parsed on a background thread
+struct LoadJob {
+    path: PathBuf,
+    /// Fraction parsed so far as `f32` bits, written by the parsing thread
+    progress: Arc<AtomicU32>,
+    cancel: CancelToken,
+    /// The parsed stack and the text it was parsed from
+    promise: Promise<Result<(ProcessStack, String), LoadFailure>>,
+}
 
 pub struct MainWindow {
     file_menu: FileMenu,
     layer_panel: LayerPanel,
     layer_details_panel: LayerDetailsPanel,
     resistance_plot_window: ResistancePlotWindow,
+    via_resistance_window: ViaResistanceWindow,
+    search_window: SearchWindow,
+    layer_table_window: LayerTableWindow,
+    preferences_window: PreferencesWindow,
+    via_chain_window: ViaChainWindow,
+    problems_window: ProblemsWindow,
+    source_window: SourceWindow,
+    histogram_window: HistogramWindow,
+    temperature_drift_window: TemperatureDriftWindow,
+    naming_window: NamingWindow,
+    guides_window: GuidesWindow,
+    structures_window: StructuresWindow,
+    resources_window: ResourcesWindow,
+    history_window: HistoryWindow,
+    density_strip: DensityStrip,
+    legend_window: LegendWindow,
     stack_viewer: StackViewer,
     toolbar: Toolbar,
-    current_stack: Option<ProcessStack>,
+    document: Option<StackDocument>,
+    /// File the current stack was read from, if any
+    file_path: Option<PathBuf>,
+    stack_changes: Option<Receiver<StackChange>>,
+    selection: SelectionState,
+    selection_changes: Receiver<SelectionChange>,
     show_about: bool,
     error_message: Option<String>,
+    /// Panic caught while loading or rendering, shown until dismissed
+    crash_report: Option<CrashReport>,
+    /// Stack drawing stopped after a panic, until retried or another stack is loaded
+    rendering_suspended: bool,
     file_dialog_promise: Option<Promise<Option<PathBuf>>>,
+    import_dialog_promise: Option<Promise<Option<PathBuf>>>,
+    bond_dialog_promise: Option<Promise<Option<PathBuf>>>,
+    theme_dialog_promise: Option<Promise<Option<PathBuf>>>,
+    /// Theme file watched for edits, none for the built-in colors
+    theme_file: Option<ThemeFile>,
+    export_dialog_promise: Option<(ExportFormat, Promise<Option<PathBuf>>)>,
+    /// Slide image to put on the clipboard in the next frame
+    clipboard_image: Option<egui::ColorImage>,
+    /// Clipboard text to ask for in the next frame, to be read as ITF
+    paste_requested: bool,
+    /// Whether the paste event of this frame answers a request from the menu
+    awaiting_paste: bool,
+    load_job: Option<LoadJob>,
 }
 
 impl MainWindow {
     pub fn new() -> Self {
+        let mut selection = SelectionState::new();
+        let selection_changes = selection.subscribe();
         Self {
             file_menu: FileMenu::new(),
             layer_panel: LayerPanel::new(),
             layer_details_panel: LayerDetailsPanel::new(),
             resistance_plot_window: ResistancePlotWindow::new(),
+            via_resistance_window: ViaResistanceWindow::new(),
+            search_window: SearchWindow::new(),
+            layer_table_window: LayerTableWindow::new(),
+            preferences_window: PreferencesWindow::new(),
+            via_chain_window: ViaChainWindow::new(),
+            problems_window: ProblemsWindow::new(),
+            source_window: SourceWindow::new(),
+            histogram_window: HistogramWindow::new(),
+            temperature_drift_window: TemperatureDriftWindow::new(),
+            naming_window: NamingWindow::new(),
+            guides_window: GuidesWindow::new(),
+            structures_window: StructuresWindow::new(),
+            resources_window: ResourcesWindow::new(),
+            history_window: HistoryWindow::new(),
+            density_strip: DensityStrip::new(),
+            legend_window: LegendWindow::new(),
             stack_viewer: StackViewer::new(),
             toolbar: Toolbar::new(),
-            current_stack: None,
+            document: None,
+            file_path: None,
+            stack_changes: None,
+            selection,
+            selection_changes,
             show_about: false,
             error_message: None,
+            crash_report: None,
+            rendering_suspended: false,
             file_dialog_promise: None,
+            import_dialog_promise: None,
+            bond_dialog_promise: None,
+            theme_dialog_promise: None,
+            theme_file: None,
+            export_dialog_promise: None,
+            clipboard_image: None,
+            paste_requested: false,
+            awaiting_paste: false,
+            load_job: None,
         }
     }
 
@@ -47,6 +194,105 @@ impl MainWindow {
         window
     }
 
+    /// Restore preferences saved by a previous session
+    pub fn restore(&mut self, storage: Option<&dyn eframe::Storage>) {
+        if let Some(overrides) = storage.and_then(|storage| {
+            eframe::get_value::<HashMap<String, Color32>>(storage, LAYER_COLORS_KEY)
+        }) {
+            self.layer_panel.set_layer_color_overrides(overrides);
+        }
+        if let Some(overrides) = storage.and_then(|storage| {
+            eframe::get_value::<HashMap<String, LayerClass>>(storage, LAYER_CLASSES_KEY)
+        }) {
+            self.layer_panel.set_layer_class_overrides(overrides);
+        }
+        if let Some(overrides) = storage.and_then(|storage| {
+            eframe::get_value::<HashMap<String, String>>(storage, LAYER_MATERIALS_KEY)
+        }) {
+            self.layer_panel.set_layer_material_overrides(overrides);
+        }
+        if let Some(anchor) =
+            storage.and_then(|storage| eframe::get_value::<ViewAnchor>(storage, VIEW_ANCHOR_KEY))
+        {
+            self.handle_toolbar_action(ToolbarAction::SetViewAnchor(anchor));
+        }
+        if let Some(bindings) = storage
+            .and_then(|storage| eframe::get_value::<InputBindings>(storage, INPUT_BINDINGS_KEY))
+        {
+            self.preferences_window.set_input_bindings(bindings);
+        }
+        if let Some(config) =
+            storage.and_then(|storage| eframe::get_value::<LintConfig>(storage, LINT_CONFIG_KEY))
+        {
+            self.problems_window.set_lint_config(config);
+        }
+        if let Some(factor) =
+            storage.and_then(|storage| eframe::get_value::<f32>(storage, MAX_WIDTH_DISTORTION_KEY))
+        {
+            self.problems_window.set_max_width_distortion(factor);
+        }
+        if let Some(guides) =
+            storage.and_then(|storage| eframe::get_value::<Vec<Guide>>(storage, GUIDES_KEY))
+        {
+            self.stack_viewer.set_guides(guides);
+        }
+        if let Some(structures) = storage.and_then(|storage| {
+            eframe::get_value::<Vec<StructureAnnotation>>(storage, STRUCTURES_KEY)
+        }) {
+            self.stack_viewer.set_structures(structures);
+        }
+        if let Some(densities) = storage
+            .and_then(|storage| eframe::get_value::<MetalDensities>(storage, METAL_DENSITIES_KEY))
+        {
+            self.density_strip.set_densities(densities);
+        }
+        if let Some(verbosity) = storage
+            .and_then(|storage| eframe::get_value::<LogVerbosity>(storage, LOG_VERBOSITY_KEY))
+        {
+            self.preferences_window.set_log_verbosity(verbosity);
+        }
+        if let Some(limits) =
+            storage.and_then(|storage| eframe::get_value::<ViewLimits>(storage, VIEW_LIMITS_KEY))
+        {
+            self.preferences_window.set_view_limits(limits);
+        }
+        if let Some(options) =
+            storage.and_then(|storage| eframe::get_value::<BondOptions>(storage, BOND_OPTIONS_KEY))
+        {
+            self.preferences_window.set_bond_options(options);
+        }
+        if let Some(defaults) = storage
+            .and_then(|storage| eframe::get_value::<DisplayDefaults>(storage, DISPLAY_DEFAULTS_KEY))
+        {
+            self.preferences_window.set_display_defaults(defaults);
+            self.apply_display_defaults();
+        }
+        if let Some(policy) = storage.and_then(|storage| {
+            eframe::get_value::<DuplicateNamePolicy>(storage, DUPLICATE_NAMES_KEY)
+        }) {
+            self.preferences_window.set_duplicate_names(policy);
+        }
+        if let Some(overrides) = storage.and_then(|storage| {
+            eframe::get_value::<HashMap<String, NameRole>>(storage, NAMING_OVERRIDES_KEY)
+        }) {
+            self.naming_window.set_overrides(overrides);
+        }
+        if let Some(use_display_names) =
+            storage.and_then(|storage| eframe::get_value::<bool>(storage, USE_DISPLAY_NAMES_KEY))
+        {
+            self.naming_window.set_use_display_names(use_display_names);
+        }
+        if let Some(path) = storage
+            .and_then(|storage| eframe::get_value::<Option<PathBuf>>(storage, THEME_PATH_KEY))
+            .flatten()
+        {
+            // A theme that moved away is not worth a dialog at startup
+            if let Err(message) = self.load_theme_from_path(path) {
+                log::warn!("{message}");
+            }
+        }
+    }
+
     pub fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
         // Check if file dialog promise is ready
         if let Some(promise) = &self.file_dialog_promise {
@@ -57,10 +303,105 @@ impl MainWindow {
                 self.file_dialog_promise = None;
             }
         }
+        self.poll_load_job();
+
+        // Check if the CSV import dialog promise is ready
+        if let Some(promise) = &self.import_dialog_promise {
+            if let Some(result) = promise.ready() {
+                if let Some(path) = result.clone() {
+                    self.import_csv_from_path(path);
+                }
+                self.import_dialog_promise = None;
+            }
+        }
+
+        // Check if the theme dialog promise is ready
+        if let Some(promise) = &self.theme_dialog_promise {
+            if let Some(result) = promise.ready() {
+                if let Some(path) = result.clone() {
+                    if let Err(message) = self.load_theme_from_path(path) {
+                        self.show_error_dialog(&message);
+                    }
+                }
+                self.theme_dialog_promise = None;
+            }
+        }
+        self.poll_theme_file(ctx);
+
+        // Check if the bond dialog promise is ready
+        if let Some(promise) = &self.bond_dialog_promise {
+            if let Some(result) = promise.ready() {
+                if let Some(path) = result.clone() {
+                    self.bond_stack_from_path(path);
+                }
+                self.bond_dialog_promise = None;
+            }
+        }
+
+        // Check if the export dialog promise is ready
+        if let Some((format, promise)) = &self.export_dialog_promise {
+            if let Some(result) = promise.ready() {
+                let format = *format;
+                if let Some(path) = result.clone() {
+                    self.export_cross_section(format, &path);
+                }
+                self.export_dialog_promise = None;
+            }
+        }
 
         // Handle toolbar actions
+        self.toolbar.set_half_node_scale_factor(
+            self.document
+                .as_ref()
+                .and_then(|document| document.stack().technology_info.half_node_scale_factor),
+        );
         let toolbar_action = self.toolbar.show(ctx);
         self.handle_toolbar_action(toolbar_action);
+        if let Some(image) = self.clipboard_image.take() {
+            ctx.copy_image(image);
+        }
+
+        // Clipboard text arrives as a paste event, one frame after the menu asks for it.
+        // Ctrl+Shift+V sends the event directly, unless a text field takes the paste.
+        let awaiting_paste = std::mem::take(&mut self.awaiting_paste);
+        if std::mem::take(&mut self.paste_requested) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::RequestPaste);
+            self.awaiting_paste = true;
+        }
+        let shortcut_paste = !ctx.wants_keyboard_input();
+        let pasted = ctx.input(|i| {
+            let shortcut = shortcut_paste && i.modifiers.command && i.modifiers.shift;
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Paste(text) if awaiting_paste || shortcut => Some(text.clone()),
+                _ => None,
+            })
+        });
+        if let Some(text) = pasted {
+            self.load_pasted_itf(&text);
+        }
+
+        // Ctrl+F opens the search window
+        if ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::F)) {
+            self.handle_toolbar_action(ToolbarAction::ToggleSearch(true));
+        }
+
+        // Undo/redo shortcuts, unless a text field handles them itself
+        if !ctx.wants_keyboard_input() {
+            let (undo, redo) = ctx.input(|i| {
+                let undo = i.modifiers.command && !i.modifiers.shift && i.key_pressed(egui::Key::Z);
+                let redo = i.modifiers.command
+                    && (i.key_pressed(egui::Key::Y)
+                        || (i.modifiers.shift && i.key_pressed(egui::Key::Z)));
+                (undo, redo)
+            });
+            if undo {
+                self.handle_toolbar_action(ToolbarAction::Undo);
+            } else if redo {
+                self.handle_toolbar_action(ToolbarAction::Redo);
+            }
+        }
+
+        self.process_stack_changes();
 
         // Only show file menu if explicitly requested (for error display)
         if self.file_menu.is_open {
@@ -71,33 +412,234 @@ impl MainWindow {
         if self.file_menu.has_loaded_stack() {
             if let Some(stack) = self.file_menu.take_loaded_stack() {
                 self.load_stack(stack);
+                self.file_path = self.file_menu.selected_file.clone();
             }
         }
 
         // Show layer panel and handle layer selection
-        if let Some(selected_layer) = self.layer_panel.show(ctx, self.current_stack.as_ref()) {
-            self.stack_viewer
-                .set_selected_layer(Some(selected_layer.clone()));
-            self.layer_panel
-                .set_selected_layer(Some(selected_layer.clone()));
-            self.layer_details_panel
-                .set_selected_layer(Some(selected_layer));
+        if let Some(selected_layer) = self
+            .layer_panel
+            .show(ctx, self.document.as_ref().map(StackDocument::stack))
+        {
+            self.set_selection(Some(selected_layer), SelectionSource::LayerPanel);
+        }
+
+        // Apply colors picked in the layer panel
+        if let Some(overrides) = self.layer_panel.take_layer_color_change() {
+            self.stack_viewer.set_layer_color_overrides(overrides);
+        }
+        if let Some(overrides) = self.layer_panel.take_layer_class_change() {
+            self.stack_viewer.set_layer_class_overrides(overrides);
+        }
+        if self.layer_panel.take_layer_material_change() {
+            self.layer_table_window.set_materials(
+                self.layer_panel.get_material_database().clone(),
+                self.layer_panel.get_layer_material_overrides().clone(),
+            );
+        }
+
+        // Apply layers reordered by drag and drop
+        if let Some((layer_name, index)) = self.layer_panel.take_layer_move() {
+            self.apply_edit(StackEdit::MoveLayer { layer_name, index });
+        }
+
+        // Apply cutline changes and refit the view to the visible part of the stack
+        if let Some(stack) = self.document.as_ref().map(StackDocument::stack) {
+            if let Some(range) = self.layer_panel.take_cutline_change(stack) {
+                self.stack_viewer.set_visible_layer_range(range);
+                self.stack_viewer.auto_fit(stack);
+            }
         }
 
         // Show layer details panel on the right
         self.layer_details_panel
-            .show(ctx, self.current_stack.as_ref());
+            .show(ctx, self.document.as_ref().map(StackDocument::stack));
 
         // Show resistance plot window (if open)
         self.resistance_plot_window
-            .show(ctx, self.current_stack.as_ref());
+            .show(ctx, self.document.as_ref().map(StackDocument::stack));
+        if let Some(conductor) = self.resistance_plot_window.take_conductor_change() {
+            self.set_selection(Some(conductor), SelectionSource::ResistanceWindow);
+        }
 
-        // Show main stack viewer and handle layer selection from viewer
-        if let Some(selected_layer) = self.stack_viewer.show(ctx, self.current_stack.as_ref()) {
-            self.layer_panel
-                .set_selected_layer(Some(selected_layer.clone()));
-            self.layer_details_panel
-                .set_selected_layer(Some(selected_layer));
+        // Show via resistance calculator (if open)
+        self.via_resistance_window
+            .show(ctx, self.document.as_ref().map(StackDocument::stack));
+        self.toolbar
+            .set_show_via_resistance(self.via_resistance_window.is_open());
+
+        // Show search window and jump to the chosen layer or via
+        if let Some(name) = self
+            .search_window
+            .show(ctx, self.document.as_ref().map(StackDocument::stack))
+        {
+            self.set_selection(Some(name.clone()), SelectionSource::Search);
+            self.center_on_layer(&name);
+        }
+        self.toolbar.set_show_search(self.search_window.is_open());
+
+        // Show via chain resistance window (if open)
+        self.via_chain_window
+            .show(ctx, self.document.as_ref().map(StackDocument::stack));
+        self.toolbar
+            .set_show_via_chain(self.via_chain_window.is_open());
+
+        // Show problems window and go to the layer and line of a clicked problem. Drawn
+        // widths depend on the display options, so they are checked against the view.
+        if self.problems_window.is_open() {
+            let distortions = self
+                .document
+                .as_ref()
+                .map(|document| {
+                    self.stack_viewer.check_width_fidelity(
+                        document.stack(),
+                        self.problems_window.get_max_width_distortion(),
+                    )
+                })
+                .unwrap_or_default();
+            self.problems_window.set_width_distortions(distortions);
+        }
+        if let Some(target) = self.problems_window.show(ctx) {
+            self.go_to_problem(target);
+        }
+        self.toolbar
+            .set_show_problems(self.problems_window.is_open());
+
+        // Show source window (if open)
+        self.source_window.show(ctx);
+        self.toolbar.set_show_source(self.source_window.is_open());
+
+        // Show property histogram window (if open)
+        self.histogram_window
+            .show(ctx, self.document.as_ref().map(StackDocument::stack));
+        self.toolbar
+            .set_show_histogram(self.histogram_window.is_open());
+
+        // Show temperature drift window and select the layer of a clicked row
+        if let Some(name) = self
+            .temperature_drift_window
+            .show(ctx, self.document.as_ref().map(StackDocument::stack))
+        {
+            self.set_selection(Some(name.clone()), SelectionSource::TemperatureDrift);
+            self.center_on_layer(&name);
+        }
+        self.toolbar
+            .set_show_temperature_drift(self.temperature_drift_window.is_open());
+
+        // Show naming conventions window and relabel the view after mapping edits
+        self.naming_window
+            .show(ctx, self.document.as_ref().map(StackDocument::stack));
+        if self.naming_window.take_naming_change() {
+            self.refresh_display_names();
+        }
+        self.toolbar.set_show_naming(self.naming_window.is_open());
+
+        // Show guides window (if open)
+        let mut guides = self.stack_viewer.get_guides().to_vec();
+        if self.guides_window.show(ctx, &mut guides) {
+            self.stack_viewer.set_guides(guides);
+        }
+        self.toolbar.set_show_guides(self.guides_window.is_open());
+
+        // Show example structures window (if open)
+        let mut structures = self.stack_viewer.get_structures().to_vec();
+        if self.structures_window.show(
+            ctx,
+            self.document.as_ref().map(StackDocument::stack),
+            &mut structures,
+        ) {
+            self.stack_viewer.set_structures(structures);
+        }
+        self.toolbar
+            .set_show_structures(self.structures_window.is_open());
+
+        // Show resources window (if open) and drop the tables when asked
+        if self.resources_window.show(
+            ctx,
+            self.document.as_ref().map(StackDocument::stack),
+            self.source_window.source_bytes(),
+        ) {
+            self.unload_tables();
+        }
+        self.toolbar
+            .set_show_resources(self.resources_window.is_open());
+
+        // Show file history window and outline the selected revision
+        self.history_window
+            .show(ctx, self.document.as_ref(), self.file_path.as_deref());
+        self.toolbar.set_show_history(self.history_window.is_open());
+        if let Some(ghost) = self.history_window.take_ghost_change() {
+            self.stack_viewer.set_ghost_stack(ghost);
+        }
+
+        // Show legend window and dim everything outside the isolated entries
+        let materials = match &self.document {
+            Some(document) if self.legend_window.is_open() => document.stack().get_layer_materials(
+                self.layer_panel.get_material_database(),
+                self.layer_panel.get_layer_material_overrides(),
+            ),
+            _ => Vec::new(),
+        };
+        self.legend_window.show(
+            ctx,
+            self.document.as_ref().map(StackDocument::stack),
+            self.stack_viewer.get_color_scheme(),
+            &materials,
+        );
+        if self.legend_window.take_isolation_change() {
+            self.refresh_isolation();
+        }
+        self.toolbar.set_show_legend(self.legend_window.is_open());
+
+        // Show layer table export window (if open)
+        self.layer_table_window
+            .show(ctx, self.document.as_ref().map(StackDocument::stack));
+
+        // Show preferences before the viewer so a shortcut being recorded is not applied
+        self.preferences_window.show(ctx);
+        if let Some(bindings) = self.preferences_window.take_bindings_change() {
+            self.stack_viewer.set_input_bindings(bindings);
+        }
+        if let Some(verbosity) = self.preferences_window.take_log_verbosity_change() {
+            set_log_verbosity(verbosity);
+        }
+        if let Some(limits) = self.preferences_window.take_view_limits_change() {
+            self.stack_viewer.set_view_limits(limits);
+        }
+
+        // Status bar below the viewer, fed by the cursor position of the previous pass
+        self.show_status_bar(ctx);
+
+        // Metal density strip between the viewer and the status bar
+        self.density_strip
+            .show(ctx, self.document.as_ref().map(StackDocument::stack));
+        if let Some(densities) = self.density_strip.take_densities_change() {
+            self.layer_table_window.set_metal_densities(densities);
+        }
+
+        // Show main stack viewer and handle layer selection from viewer; a panic while
+        // drawing stops the drawing instead of the application
+        if self.rendering_suspended {
+            self.show_suspended_view(ctx);
+        } else {
+            let stack = self.document.as_ref().map(StackDocument::stack);
+            match guard(|| self.stack_viewer.show(ctx, stack)) {
+                Ok(selected_layer) => {
+                    if let Some(selected_layer) = selected_layer {
+                        self.set_selection(Some(selected_layer), SelectionSource::StackViewer);
+                    }
+                    if let Some(activated) = self.stack_viewer.take_activated_layer() {
+                        self.open_calculator_for(&activated);
+                    }
+                }
+                Err(panic) => {
+                    let report = CrashReport::new("rendering", panic)
+                        .with_file(self.file_path.as_deref())
+                        .with_stack(stack);
+                    self.rendering_suspended = true;
+                    self.report_crash(report);
+                }
+            }
         }
 
         // Show about dialog if requested
@@ -105,10 +647,15 @@ impl MainWindow {
             self.show_about_dialog(ctx);
         }
 
+        self.show_load_progress(ctx);
+
         // Show error dialog if there's an error
         if self.error_message.is_some() {
             self.show_error_dialog_ui(ctx);
         }
+        if self.crash_report.is_some() {
+            self.show_crash_dialog_ui(ctx);
+        }
 
         // Update toolbar state
         self.toolbar.update_zoom(self.stack_viewer.get_zoom());
@@ -122,16 +669,122 @@ impl MainWindow {
                 self.open_file_dialog();
             }
 
+            ToolbarAction::PasteItf => {
+                self.paste_requested = true;
+            }
+
+            ToolbarAction::ImportCsv => {
+                self.open_import_dialog();
+            }
+
+            ToolbarAction::BondStack => {
+                if self.document.is_some() {
+                    self.open_bond_dialog();
+                } else {
+                    self.show_error_dialog("Load the bottom stack before bonding another onto it");
+                }
+            }
+
+            ToolbarAction::NewFromTemplate(template, metal_count) => {
+                let params = template.default_params().with_metal_count(metal_count);
+                match template.build(&params) {
+                    Ok(stack) => self.load_stack(stack),
+                    Err(e) => {
+                        self.show_error_dialog(&format!("Failed to create template: {e}"));
+                    }
+                }
+            }
+
+            ToolbarAction::OpenExample(example) => match example.load() {
+                Ok(stack) => {
+                    self.load_stack(stack);
+                    self.source_window.set_source(Some(example.source()));
+                }
+                Err(e) => {
+                    self.show_error_dialog(&format!("Failed to open example: {e}"));
+                }
+            },
+
+            ToolbarAction::LoadRandomStack { seed, layer_count } => {
+                self.load_stack(generate_random_stack(seed, layer_count));
+            }
+
+            ToolbarAction::ExportLayerTable => {
+                self.layer_table_window.set_open(true);
+            }
+
+            ToolbarAction::OpenPreferences => {
+                self.preferences_window.set_open(true);
+            }
+
+            ToolbarAction::OpenLog => match get_log_file_path() {
+                Some(path) => {
+                    if let Err(e) = open_with_system_viewer(&path) {
+                        self.show_error_dialog(&format!(
+                            "Failed to open the log file {}: {e}",
+                            path.display()
+                        ));
+                    }
+                }
+                None => self.show_error_dialog("Logging to a file is not active"),
+            },
+
+            ToolbarAction::Export(format) => {
+                if self.document.is_some() && self.export_dialog_promise.is_none() {
+                    self.open_export_dialog(format);
+                }
+            }
+
+            ToolbarAction::CopySlideImage => {
+                if let Some(stack) = self.document.as_ref().map(StackDocument::stack) {
+                    let layout = self.stack_viewer.layout_stack(stack);
+                    match render_slide(&layout, &self.slide_options(stack)) {
+                        Ok(image) => self.clipboard_image = Some(image),
+                        Err(e) => self.show_error_dialog(&format!("Failed to copy slide: {e}")),
+                    }
+                }
+            }
+
             ToolbarAction::Exit => {
                 std::process::exit(0);
             }
 
+            ToolbarAction::Undo => {
+                if let Some(ref mut document) = self.document {
+                    if let Err(e) = document.undo() {
+                        self.show_error_dialog(&format!("Failed to undo: {e}"));
+                    }
+                }
+                self.process_stack_changes();
+            }
+
+            ToolbarAction::Redo => {
+                if let Some(ref mut document) = self.document {
+                    if let Err(e) = document.redo() {
+                        self.show_error_dialog(&format!("Failed to redo: {e}"));
+                    }
+                }
+                self.process_stack_changes();
+            }
+
             ToolbarAction::AutoFit => {
-                if let Some(ref stack) = self.current_stack {
+                if let Some(stack) = self.document.as_ref().map(StackDocument::stack) {
                     self.stack_viewer.auto_fit(stack);
                 }
             }
 
+            ToolbarAction::FitWidth => {
+                if let Some(stack) = self.document.as_ref().map(StackDocument::stack) {
+                    self.stack_viewer.fit_to_view(stack, FitAxis::Width);
+                }
+            }
+
+            ToolbarAction::FitHeight => {
+                if let Some(stack) = self.document.as_ref().map(StackDocument::stack) {
+                    self.stack_viewer.fit_to_view(stack, FitAxis::Height);
+                }
+            }
+
             ToolbarAction::ResetView => {
                 self.stack_viewer.reset_view();
             }
@@ -168,31 +821,361 @@ impl MainWindow {
                 self.toolbar.set_show_schematic_mode(show);
             }
 
+            ToolbarAction::ToggleSplitView(show) => {
+                self.stack_viewer.set_split_view(show);
+                self.toolbar.set_show_split_view(show);
+            }
+
+            ToolbarAction::ToggleIsometricMode(show) => {
+                self.stack_viewer.set_show_isometric_mode(show);
+                self.toolbar.set_show_isometric_mode(show);
+            }
+
+            ToolbarAction::ToggleAreaZoom(enabled) => {
+                self.stack_viewer.set_area_zoom_mode(enabled);
+                self.toolbar.set_area_zoom_mode(enabled);
+            }
+
+            ToolbarAction::ToggleMergedVias(show) => {
+                self.stack_viewer.set_show_merged_vias(show);
+                self.toolbar.set_show_merged_vias(show);
+            }
+
+            ToolbarAction::ToggleAreaAccurateVias(area_accurate) => {
+                self.stack_viewer.set_area_accurate_vias(area_accurate);
+                self.toolbar.set_area_accurate_vias(area_accurate);
+            }
+
+            ToolbarAction::ToggleHatching(show) => {
+                self.stack_viewer.set_show_hatching(show);
+                self.toolbar.set_show_hatching(show);
+            }
+
+            ToolbarAction::ToggleConformalLiners(show) => {
+                self.stack_viewer.set_show_conformal_liners(show);
+                self.toolbar.set_show_conformal_liners(show);
+            }
+
+            ToolbarAction::ToggleOutlineOnly(show) => {
+                self.stack_viewer.set_show_outline_only(show);
+                self.toolbar.set_show_outline_only(show);
+            }
+
+            ToolbarAction::ToggleTrueSidewallAngles(true_angles) => {
+                self.stack_viewer.set_true_sidewall_angles(true_angles);
+                self.layer_details_panel
+                    .set_sidewall_exaggeration(self.stack_viewer.get_sidewall_exaggeration());
+                self.toolbar.set_true_sidewall_angles(true_angles);
+            }
+
+            ToolbarAction::SetSidewallExaggeration(exaggeration) => {
+                self.stack_viewer.set_sidewall_exaggeration(exaggeration);
+                self.layer_details_panel
+                    .set_sidewall_exaggeration(self.stack_viewer.get_sidewall_exaggeration());
+                self.toolbar.set_sidewall_exaggeration(exaggeration);
+            }
+
+            ToolbarAction::ToggleHalfNodeScale(apply) => {
+                self.layer_details_panel.set_apply_half_node_scale(apply);
+                self.toolbar.set_apply_half_node_scale(apply);
+            }
+
+            ToolbarAction::SetViewAnchor(anchor) => {
+                self.stack_viewer.set_view_anchor(anchor);
+                self.toolbar.set_view_anchor(anchor);
+                // World coordinates move with the anchor, so refit the view
+                if let Some(stack) = self.document.as_ref().map(StackDocument::stack) {
+                    self.stack_viewer.auto_fit(stack);
+                }
+            }
+
             ToolbarAction::ToggleResistanceCalculator(show) => {
                 self.resistance_plot_window.set_open(show);
                 self.toolbar.set_show_resistance_calculator(show);
             }
+
+            ToolbarAction::ToggleViaResistance(show) => {
+                self.via_resistance_window.set_open(show);
+                self.toolbar.set_show_via_resistance(show);
+            }
+
+            ToolbarAction::ToggleSearch(show) => {
+                self.search_window.set_open(show);
+                self.toolbar.set_show_search(show);
+            }
+
+            ToolbarAction::ToggleViaChain(show) => {
+                self.via_chain_window.set_open(show);
+                self.toolbar.set_show_via_chain(show);
+            }
+
+            ToolbarAction::ToggleProblems(show) => {
+                self.problems_window.set_open(show);
+                self.toolbar.set_show_problems(show);
+            }
+
+            ToolbarAction::ToggleSource(show) => {
+                self.source_window.set_open(show);
+                self.toolbar.set_show_source(show);
+            }
+
+            ToolbarAction::ToggleHistogram(show) => {
+                self.histogram_window.set_open(show);
+                self.toolbar.set_show_histogram(show);
+            }
+
+            ToolbarAction::ToggleTemperatureDrift(show) => {
+                self.temperature_drift_window.set_open(show);
+                self.toolbar.set_show_temperature_drift(show);
+            }
+
+            ToolbarAction::ToggleNaming(show) => {
+                self.naming_window.set_open(show);
+                self.toolbar.set_show_naming(show);
+            }
+
+            ToolbarAction::ToggleGuides(show) => {
+                self.guides_window.set_open(show);
+                self.toolbar.set_show_guides(show);
+            }
+
+            ToolbarAction::ToggleStructures(show) => {
+                self.structures_window.set_open(show);
+                self.toolbar.set_show_structures(show);
+            }
+
+            ToolbarAction::LoadTheme => {
+                self.open_theme_dialog();
+            }
+
+            ToolbarAction::ClearTheme => {
+                self.theme_file = None;
+                self.apply_theme(None);
+            }
+
+            ToolbarAction::ToggleResources(show) => {
+                self.resources_window.set_open(show);
+                self.toolbar.set_show_resources(show);
+            }
+
+            ToolbarAction::ToggleHistory(show) => {
+                self.history_window.set_open(show);
+                self.toolbar.set_show_history(show);
+            }
+
+            ToolbarAction::ToggleDensityStrip(show) => {
+                self.density_strip.set_open(show);
+                self.toolbar.set_show_density_strip(show);
+            }
+
+            ToolbarAction::ToggleLegend(show) => {
+                self.legend_window.set_open(show);
+                self.toolbar.set_show_legend(show);
+            }
+
+            ToolbarAction::ToggleProfiler(show) => {
+                self.stack_viewer.set_show_profiler(show);
+                self.toolbar.set_show_profiler(show);
+            }
         }
     }
 
     fn load_stack(&mut self, stack: ProcessStack) {
-        self.current_stack = Some(stack);
+        // Another stack may well render where the previous one failed
+        self.rendering_suspended = false;
+
+        match self.document {
+            Some(ref mut document) => document.load_stack(stack),
+            None => {
+                let mut document = StackDocument::new(stack);
+                self.stack_changes = Some(document.subscribe());
+                self.document = Some(document);
+            }
+        }
+
+        // Callers loading from a file record its path and text afterwards
+        self.file_path = None;
+        self.source_window.set_source(None);
+
+        // A cutline from a previous file does not apply to the new stack
+        self.layer_panel.reset_cutline();
+        self.resources_window.set_unloaded_bytes(None);
+        self.layer_panel.set_bond_stats(None);
+        self.stack_viewer.set_visible_layer_range(None);
+        self.apply_display_defaults();
+        self.refresh_display_names();
+        self.legend_window.clear_isolation();
+        self.refresh_isolation();
 
         // Auto-fit the new stack
-        if let Some(ref stack) = self.current_stack {
+        if let Some(stack) = self.document.as_ref().map(StackDocument::stack) {
             self.stack_viewer.auto_fit(stack);
         }
+        self.problems_window
+            .set_stack(self.document.as_ref().map(StackDocument::shared_stack));
 
         // Clear any previous layer selection
-        self.layer_panel.set_selected_layer(None);
-        self.layer_details_panel.set_selected_layer(None);
-        self.stack_viewer.set_selected_layer(None);
+        self.set_selection(None, SelectionSource::Program);
         self.resistance_plot_window.set_selected_conductor(None);
+        self.search_window.clear_results();
 
         // Close file menu
         self.file_menu.is_open = false;
     }
 
+    /// Reset the render mode, labels and colors to the preferred ones
+    fn apply_display_defaults(&mut self) {
+        let defaults = *self.preferences_window.get_display_defaults();
+        for action in [
+            ToolbarAction::ToggleSchematicMode(defaults.render_mode == RenderMode::Schematic),
+            ToolbarAction::ToggleDimensions(defaults.show_dimensions),
+            ToolbarAction::ToggleLayerNames(defaults.show_layer_names),
+            ToolbarAction::ToggleHatching(defaults.fill_style == FillStyle::Hatched),
+            ToolbarAction::ToggleOutlineOnly(defaults.fill_style == FillStyle::OutlineOnly),
+        ] {
+            self.handle_toolbar_action(action);
+        }
+    }
+
+    /// Relabel the view after the stack, the naming mapping or the labeling changed
+    fn refresh_display_names(&mut self) {
+        let display_names = self
+            .document
+            .as_ref()
+            .map(|document| self.naming_window.get_display_names(document.stack()))
+            .unwrap_or_default();
+        self.stack_viewer.set_display_names(display_names);
+    }
+
+    /// Dim the layers and vias outside the legend's isolated set, also ones added by edits
+    fn refresh_isolation(&mut self) {
+        let overrides = self
+            .document
+            .as_ref()
+            .map(|document| self.legend_window.get_alpha_overrides(document.stack()))
+            .unwrap_or_default();
+        self.stack_viewer.set_alpha_overrides(overrides);
+    }
+
+    /// Apply an edit to the current document, reporting failures in the error dialog
+    pub fn apply_edit(&mut self, edit: StackEdit) {
+        if let Some(ref mut document) = self.document {
+            if let Err(e) = document.apply(edit) {
+                self.show_error_dialog(&format!("Failed to edit stack: {e}"));
+            }
+        }
+        self.process_stack_changes();
+    }
+
+    /// Drop the lookup tables of the current stack, keeping what is drawn
+    fn unload_tables(&mut self) {
+        let Some(ref mut document) = self.document else {
+            return;
+        };
+        let bytes = document.unload_tables();
+        log::info!("Unloaded {bytes} bytes of lookup tables");
+        let total = self.resources_window.get_unloaded_bytes().unwrap_or(0) + bytes;
+        self.resources_window.set_unloaded_bytes(Some(total));
+        self.process_stack_changes();
+    }
+
+    /// Bring the panels in line with edits made to the document
+    fn process_stack_changes(&mut self) {
+        let changes: Vec<StackChange> = match self.stack_changes {
+            Some(ref receiver) => receiver.try_iter().collect(),
+            None => Vec::new(),
+        };
+
+        for change in &changes {
+            // Selections and color, class and material overrides are by name, so follow
+            // renamed layers
+            if let StackChange::LayerRenamed { old_name, new_name } = change {
+                self.layer_panel.rename_layer_color(old_name, new_name);
+                self.layer_panel.rename_layer_class(old_name, new_name);
+                self.layer_panel.rename_layer_material(old_name, new_name);
+                self.density_strip.rename_layer(old_name, new_name);
+                self.naming_window.rename_layer(old_name, new_name);
+                self.legend_window.rename_layer(old_name, new_name);
+                if self.get_selected_layer() == Some(old_name) {
+                    self.set_selection(Some(new_name.clone()), SelectionSource::Program);
+                }
+            }
+        }
+
+        if !changes.is_empty() {
+            self.search_window.clear_results();
+            self.stack_viewer.invalidate_hit_index();
+            self.refresh_display_names();
+            self.refresh_isolation();
+
+            // Layer edits can leave vias spanning other conductors
+            if let Some(ref document) = self.document {
+                self.layer_panel
+                    .set_via_warnings(document.stack().get_via_span_warnings());
+                self.problems_window
+                    .set_stack(Some(document.shared_stack()));
+            }
+        }
+
+        let (undo, redo) = match self.document {
+            Some(ref document) => (
+                document.undo_description().map(str::to_string),
+                document.redo_description().map(str::to_string),
+            ),
+            None => (None, None),
+        };
+        self.toolbar.set_history(undo, redo);
+    }
+
+    /// Cursor position, layer under the cursor, zoom and file path
+    fn show_status_bar(&self, ctx: &Context) {
+        TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                match self.stack_viewer.get_cursor_info() {
+                    Some(cursor) => {
+                        ui.monospace(format!(
+                            "x: {:>9.3} μm  {}: {:>9.3} μm",
+                            cursor.x,
+                            self.toolbar.view_anchor.ruler_caption(),
+                            cursor.z
+                        ));
+                        if self.toolbar.show_schematic_mode {
+                            ui.weak("(schematic, not to scale)");
+                        }
+                        ui.separator();
+                        ui.label(format!("Layer: {}", cursor.layer.as_deref().unwrap_or("-")));
+                    }
+                    None => {
+                        ui.weak("Cursor outside the stack view");
+                    }
+                }
+                if let Some(heights) = self.selected_layer_heights() {
+                    ui.separator();
+                    ui.monospace(heights);
+                }
+                ui.separator();
+                ui.label(format!("Zoom: {:.2}x", self.stack_viewer.get_zoom()));
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    match (&self.file_path, &self.document) {
+                        (Some(path), _) => ui.label(path.display().to_string()),
+                        (None, Some(_)) => ui.weak("Unsaved stack"),
+                        (None, None) => ui.weak("No file loaded"),
+                    };
+                });
+            });
+        });
+    }
+
+    /// Record the file the current stack was loaded from, shown in the status bar
+    pub fn set_file_path(&mut self, path: Option<PathBuf>) {
+        self.file_path = path;
+    }
+
+    pub fn get_file_path(&self) -> Option<&PathBuf> {
+        self.file_path.as_ref()
+    }
+
     fn show_about_dialog(&mut self, ctx: &Context) {
         egui::Window::new("About ITF Viewer")
             .collapsible(false)
@@ -238,7 +1221,15 @@ impl MainWindow {
     }
 
     pub fn get_current_stack(&self) -> Option<&ProcessStack> {
-        self.current_stack.as_ref()
+        self.document.as_ref().map(StackDocument::stack)
+    }
+
+    pub fn get_document(&self) -> Option<&StackDocument> {
+        self.document.as_ref()
+    }
+
+    pub fn get_document_mut(&mut self) -> Option<&mut StackDocument> {
+        self.document.as_mut()
     }
 
     pub fn set_show_about(&mut self, show: bool) {
@@ -250,22 +1241,115 @@ impl MainWindow {
     }
 
     pub fn get_selected_layer(&self) -> Option<&String> {
-        self.layer_panel.get_selected_layer()
+        self.selection.selected()
+    }
+
+    /// Physical height and depth of the selected layer, independent of the view mode
+    fn selected_layer_heights(&self) -> Option<String> {
+        let stack = self.document.as_ref()?.stack();
+        let name = self.selection.selected()?;
+        let (bottom, top) = stack.get_layer_z_range(name)?;
+        let chip_top = stack.get_stack_top_z();
+        Some(format!(
+            "{name}: z {bottom:.3}-{top:.3} μm, depth {:.3}-{:.3} μm",
+            chip_top - top,
+            chip_top - bottom
+        ))
+    }
+
+    /// Select the layer of a problem and show its line in the source window
+    fn go_to_problem(&mut self, target: ProblemTarget) {
+        if let Some(name) = target.layer {
+            self.set_selection(Some(name.clone()), SelectionSource::Problems);
+            self.center_on_layer(&name);
+        }
+        if let Some(line) = target.line.filter(|_| self.source_window.has_source()) {
+            self.source_window.go_to_line(line);
+            self.toolbar.set_show_source(true);
+        }
     }
 
     pub fn center_on_layer(&mut self, layer_name: &str) {
-        if let Some(ref stack) = self.current_stack {
+        if let Some(stack) = self.document.as_ref().map(StackDocument::stack) {
             self.stack_viewer.center_on_layer(stack, layer_name);
         }
     }
 
+    /// Open the resistance calculator of a conductor at its WMIN, or the via calculator
+    fn open_calculator_for(&mut self, name: &str) {
+        let Some(stack) = self.document.as_ref().map(StackDocument::stack) else {
+            return;
+        };
+        if let Some(Layer::Conductor(conductor)) = stack.get_layer(name) {
+            let wmin = conductor.physical_props.width_min;
+            self.resistance_plot_window
+                .set_selected_conductor(Some(name.to_string()));
+            if let Some(wmin) = wmin.filter(|wmin| *wmin > 0.0) {
+                self.resistance_plot_window.set_width(wmin);
+            }
+            self.resistance_plot_window.set_open(true);
+            self.toolbar.set_show_resistance_calculator(true);
+        } else if stack.via_stack.get_via(name).is_some() {
+            self.via_resistance_window
+                .set_selected_via(Some(name.to_string()));
+            self.via_resistance_window.set_open(true);
+            self.toolbar.set_show_via_resistance(true);
+        }
+    }
+
     pub fn select_layer(&mut self, layer_name: Option<String>) {
-        self.layer_panel.set_selected_layer(layer_name.clone());
-        self.stack_viewer.set_selected_layer(layer_name);
+        self.set_selection(layer_name, SelectionSource::Program);
+    }
+
+    fn set_selection(&mut self, layer_name: Option<String>, source: SelectionSource) {
+        self.selection.select(layer_name, source);
+        self.process_selection_changes();
+    }
+
+    /// Bring every panel in line with the shared selection
+    fn process_selection_changes(&mut self) {
+        let changes: Vec<SelectionChange> = self.selection_changes.try_iter().collect();
+        for change in changes {
+            self.layer_panel.set_selected_layer(change.current.clone());
+            self.stack_viewer.set_selected_layer(change.current.clone());
+            self.layer_details_panel
+                .set_selected_layer(change.current.clone());
+
+            // A selected via highlights the dielectrics it passes through
+            let stack = self.document.as_ref().map(|document| document.stack());
+            let via = stack
+                .zip(change.current.as_ref())
+                .and_then(|(stack, name)| stack.via_stack.iter().find(|via| via.name == *name));
+            let spanned = stack
+                .zip(via)
+                .and_then(|(stack, via)| stack.get_via_dielectric_span(via))
+                .map(|span| span.dielectrics.into_iter().map(|d| d.name).collect())
+                .unwrap_or_default();
+            self.stack_viewer.set_highlighted_layers(spanned);
+
+            // Clicking a via in the view brings up its row in the via table
+            if change.source == SelectionSource::StackViewer && via.is_some() {
+                self.layer_panel.set_tab(LayerPanelTab::Vias);
+            }
+
+            // Selecting a conductor elsewhere pre-fills the resistance calculator
+            let is_conductor = |name: &String| {
+                self.document
+                    .as_ref()
+                    .and_then(|document| document.stack().get_layer(name))
+                    .is_some_and(Layer::is_conductor)
+            };
+            if change.source != SelectionSource::ResistanceWindow
+                && change.current.as_ref().is_some_and(is_conductor)
+            {
+                self.resistance_plot_window
+                    .set_selected_conductor(change.current);
+            }
+        }
     }
 
     pub fn has_loaded_file(&self) -> bool {
-        self.current_stack.is_some()
+        self.document.is_some()
     }
 
     fn open_file_dialog(&mut self) {
@@ -286,23 +1370,398 @@ impl MainWindow {
         self.file_dialog_promise = Some(promise);
     }
 
+    fn open_import_dialog(&mut self) {
+        let task = AsyncFileDialog::new()
+            .add_filter("CSV Files", &["csv"])
+            .add_filter("All Files", &["*"])
+            .set_title("Import CSV Stack")
+            .pick_file();
+
+        let promise = Promise::spawn_thread("import_dialog", move || {
+            pollster::block_on(async move { task.await.map(|handle| handle.path().to_path_buf()) })
+        });
+
+        self.import_dialog_promise = Some(promise);
+    }
+
+    fn open_theme_dialog(&mut self) {
+        let task = AsyncFileDialog::new()
+            .add_filter("Theme Files", &["toml", "json"])
+            .add_filter("All Files", &["*"])
+            .set_title("Load Theme")
+            .pick_file();
+
+        let promise = Promise::spawn_thread("theme_dialog", move || {
+            pollster::block_on(async move { task.await.map(|handle| handle.path().to_path_buf()) })
+        });
+
+        self.theme_dialog_promise = Some(promise);
+    }
+
+    /// Load a theme and watch its file for edits
+    fn load_theme_from_path(&mut self, path: PathBuf) -> Result<(), String> {
+        let mut file = ThemeFile::new(path);
+        let theme = file
+            .load()
+            .map_err(|e| format!("Failed to load theme {}: {e}", file.get_path().display()))?;
+        self.theme_file = Some(file);
+        self.apply_theme(Some(theme));
+        Ok(())
+    }
+
+    /// Pick up edits of the theme file, keeping the current theme while an edit does not parse
+    fn poll_theme_file(&mut self, ctx: &Context) {
+        let Some(file) = &mut self.theme_file else {
+            return;
+        };
+        match file.poll() {
+            Some(Ok(theme)) => {
+                log::info!("Reloaded theme {}", file.get_path().display());
+                self.apply_theme(Some(theme));
+            }
+            Some(Err(e)) => log::warn!("Theme {} not reloaded: {e}", file.get_path().display()),
+            None => {}
+        }
+        ctx.request_repaint_after(THEME_POLL_INTERVAL);
+    }
+
+    fn apply_theme(&mut self, theme: Option<Theme>) {
+        let name = theme
+            .as_ref()
+            .and_then(|theme| theme.name.clone())
+            .or_else(|| {
+                let path = self.theme_file.as_ref()?.get_path();
+                Some(path.file_name()?.to_string_lossy().into_owned())
+            });
+        self.toolbar
+            .set_theme_name(theme.is_some().then_some(name).flatten());
+        self.layer_panel.set_theme(theme.clone());
+        self.stack_viewer.set_theme(theme);
+    }
+
+    fn open_bond_dialog(&mut self) {
+        let task = AsyncFileDialog::new()
+            .add_filter("ITF Files", &["itf"])
+            .add_filter("All Files", &["*"])
+            .set_title("Select ITF File to Bond Face-to-Face")
+            .pick_file();
+
+        let promise = Promise::spawn_thread("bond_dialog", move || {
+            pollster::block_on(async move { task.await.map(|handle| handle.path().to_path_buf()) })
+        });
+
+        self.bond_dialog_promise = Some(promise);
+    }
+
+    /// Flip the stack of an ITF file onto the loaded one and show the combined stack
+    fn bond_stack_from_path(&mut self, path: PathBuf) {
+        let Some(bottom) = self.document.as_ref().map(StackDocument::stack) else {
+            return;
+        };
+        let options = self.preferences_window.get_bond_options();
+        let duplicate_names = self.preferences_window.get_duplicate_names();
+        let result = read_itf_source(&path)
+            .map_err(|e| format!("Failed to read file: {e}"))
+            .and_then(|source| {
+                ItfParser::new()
+                    .with_duplicate_names(duplicate_names)
+                    .parse_itf_file(&source.text)
+                    .map_err(|e| format!("Failed to parse ITF file: {e}"))
+            })
+            .and_then(|top| {
+                bottom
+                    .bond_face_to_face(&top, options)
+                    .map_err(|e| format!("Failed to bond stacks: {e}"))
+            });
+        match result {
+            Ok(bonded) => {
+                log::info!("Bonded {}: {}", path.display(), bonded.stats);
+                self.load_stack(bonded.stack);
+                self.layer_panel.set_bond_stats(Some(bonded.stats));
+            }
+            Err(message) => self.show_error_dialog(&message),
+        }
+    }
+
+    /// Show the stack of ITF text pasted from the clipboard
+    fn load_pasted_itf(&mut self, text: &str) {
+        let result = ItfParser::new()
+            .with_duplicate_names(self.preferences_window.get_duplicate_names())
+            .parse_itf_file(text);
+        match result {
+            Ok(stack) => {
+                log::info!(
+                    "Loaded {} layers from the clipboard",
+                    stack.get_layer_count()
+                );
+                self.load_stack(stack);
+                self.source_window.set_source(Some(text));
+            }
+            Err(e) => self.show_error_dialog(&format!("Failed to parse the clipboard as ITF: {e}")),
+        }
+    }
+
+    /// Build a stack from a spreadsheet CSV, named after the file
+    fn import_csv_from_path(&mut self, path: PathBuf) {
+        let technology_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "csv_stack".to_string());
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read file: {e}"))
+            .and_then(|content| {
+                import_csv_stack(&content, &technology_name)
+                    .map_err(|e| format!("Failed to import CSV stack: {e}"))
+            });
+        match result {
+            Ok(stack) => {
+                self.load_stack(stack);
+                self.file_path = Some(path);
+            }
+            Err(message) => self.show_error_dialog(&message),
+        }
+    }
+
+    fn open_export_dialog(&mut self, format: ExportFormat) {
+        let mut dialog = AsyncFileDialog::new()
+            .add_filter(format!("{} Files", format.label()), &[format.extension()]);
+        let title = if format.is_slide() {
+            // The chosen extension picks the slide format
+            let other = match format {
+                ExportFormat::Emf => ExportFormat::SlidePng,
+                _ => ExportFormat::Emf,
+            };
+            dialog = dialog.add_filter(format!("{} Files", other.label()), &[other.extension()]);
+            "Export for Slides".to_string()
+        } else {
+            format!("Export {}", format.label())
+        };
+        let task = dialog
+            .add_filter("All Files", &["*"])
+            .set_title(title)
+            .set_file_name(format!("cross_section.{}", format.extension()))
+            .save_file();
+
+        let promise = Promise::spawn_thread("export_dialog", move || {
+            pollster::block_on(async move { task.await.map(|handle| handle.path().to_path_buf()) })
+        });
+
+        self.export_dialog_promise = Some((format, promise));
+    }
+
+    /// Write the cross-section with the current view options
+    fn export_cross_section(&mut self, format: ExportFormat, path: &std::path::Path) {
+        let Some(stack) = self.document.as_ref().map(StackDocument::stack) else {
+            return;
+        };
+        let layout = self.stack_viewer.layout_stack(stack);
+        let format = match format.is_slide() {
+            true => ExportFormat::slide_from_path(path).unwrap_or(format),
+            false => format,
+        };
+        let result = match format {
+            ExportFormat::Dxf => {
+                std::fs::write(path, export_dxf(&layout)).map_err(|e| e.to_string())
+            }
+            ExportFormat::Gds => export_gds(
+                &layout,
+                &GdsOptions::new().with_outline_only(self.stack_viewer.is_outline_only()),
+            )
+            .map_err(|e| e.to_string())
+            .and_then(|gds| std::fs::write(path, gds).map_err(|e| e.to_string())),
+            ExportFormat::Html => {
+                let html = export_html(&layout, stack, &stack.technology_info.name);
+                std::fs::write(path, html).map_err(|e| e.to_string())
+            }
+            ExportFormat::Emf => export_emf(&layout, &self.slide_options(stack))
+                .map_err(|e| e.to_string())
+                .and_then(|emf| std::fs::write(path, emf).map_err(|e| e.to_string())),
+            ExportFormat::SlidePng => export_slide_png(&layout, &self.slide_options(stack))
+                .map_err(|e| e.to_string())
+                .and_then(|png| std::fs::write(path, png).map_err(|e| e.to_string())),
+        };
+        if let Err(e) = result {
+            self.show_error_dialog(&format!("Failed to export {}: {e}", format.label()));
+        }
+    }
+
+    /// Slide title and legend for `stack`, titled with its technology name
+    fn slide_options(&self, stack: &ProcessStack) -> SlideOptions {
+        SlideOptions::new()
+            .with_title(stack.technology_info.name.clone())
+            .with_vertical_exaggeration(self.stack_viewer.get_vertical_exaggeration(stack))
+    }
+
+    /// Read and parse a file on a background thread, replacing any load in progress
     fn load_file_from_path(&mut self, path: PathBuf) {
-        match std::fs::read_to_string(&path) {
-            Ok(content) => match parse_itf_file(&content) {
-                Ok(stack) => {
-                    self.load_stack(stack);
-                }
-                Err(e) => {
-                    self.show_error_dialog(&format!("Failed to parse ITF file: {e}"));
+        if let Some(job) = self.load_job.take() {
+            job.cancel.cancel();
+        }
+
+        let progress = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+        let cancel = CancelToken::new();
+        let duplicate_names = self.preferences_window.get_duplicate_names();
+        let promise = {
+            let (path, progress, cancel) = (path.clone(), progress.clone(), cancel.clone());
+            Promise::spawn_thread("parse_itf", move || {
+                // A malformed file must not take the application down with the thread
+                guard(|| {
+                    let source =
+                        read_itf_source(&path).map_err(|e| format!("Failed to read file: {e}"))?;
+                    ItfParser::new()
+                        .with_duplicate_names(duplicate_names)
+                        .parse_with_progress(
+                            &source.text,
+                            |fraction| progress.store(fraction.to_bits(), Ordering::Relaxed),
+                            &cancel,
+                        )
+                        .map(|mut stack| {
+                            source.add_diagnostics(&mut stack);
+                            (stack, source.text)
+                        })
+                        .map_err(|e| format!("Failed to parse ITF file: {e}"))
+                })
+                .map_err(LoadFailure::Panic)
+                .and_then(|result| result.map_err(LoadFailure::Error))
+            })
+        };
+
+        self.load_job = Some(LoadJob {
+            path,
+            progress,
+            cancel,
+            promise,
+        });
+    }
+
+    /// Load the stack of a finished background parse
+    fn poll_load_job(&mut self) {
+        let Some(job) = self.load_job.take() else {
+            return;
+        };
+        match job.promise.try_take() {
+            Ok(Ok((stack, source))) => {
+                log::info!(
+                    "Loaded {} with {} layers",
+                    job.path.display(),
+                    stack.get_layer_count()
+                );
+                match guard(|| self.load_stack(stack)) {
+                    Ok(()) => {
+                        self.file_path = Some(job.path);
+                        self.source_window.set_source(Some(&source));
+                    }
+                    Err(panic) => self.report_crash(
+                        CrashReport::new("loading", panic)
+                            .with_file(Some(&job.path))
+                            .with_stack(self.document.as_ref().map(StackDocument::stack)),
+                    ),
                 }
-            },
-            Err(e) => {
-                self.show_error_dialog(&format!("Failed to read file: {e}"));
             }
+            Ok(Err(LoadFailure::Error(message))) => self.show_error_dialog(&message),
+            Ok(Err(LoadFailure::Panic(panic))) => {
+                self.report_crash(CrashReport::new("loading", panic).with_file(Some(&job.path)));
+            }
+            Err(promise) => self.load_job = Some(LoadJob { promise, ..job }),
+        }
+    }
+
+    /// Keep a caught panic for the crash dialog and the log file
+    fn report_crash(&mut self, report: CrashReport) {
+        log::error!("{report}");
+        self.crash_report = Some(report);
+    }
+
+    /// Placeholder for the stack view after a rendering panic, with a way to try again
+    fn show_suspended_view(&mut self, ctx: &Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.centered_and_justified(|ui| {
+                ui.vertical_centered(|ui| {
+                    ui.label("Drawing this stack failed with an internal error.");
+                    if ui.button("Retry Rendering").clicked() {
+                        self.rendering_suspended = false;
+                    }
+                });
+            });
+        });
+    }
+
+    /// Caught panic with its diagnostic dump, ready to copy into a bug report
+    fn show_crash_dialog_ui(&mut self, ctx: &Context) {
+        let Some(report) = &self.crash_report else {
+            return;
+        };
+        let mut text = report.to_string();
+        let mut close = false;
+        egui::Window::new("Internal Error")
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(report.headline()).color(egui::Color32::RED));
+                ui.label("ITF Viewer caught the error and keeps running. Please include the report below when filing a bug.");
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut text)
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(f32::INFINITY)
+                                .interactive(false),
+                        );
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Copy Report").clicked() {
+                        ctx.copy_text(report.to_string());
+                    }
+                    if ui.button("Close").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        if close {
+            self.crash_report = None;
+        }
+    }
+
+    /// Progress of the background parse, with a button to cancel it
+    fn show_load_progress(&mut self, ctx: &Context) {
+        let Some(job) = &self.load_job else {
+            return;
+        };
+        let fraction = f32::from_bits(job.progress.load(Ordering::Relaxed));
+        let file_name = job
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+
+        let mut cancel = false;
+        egui::Window::new("Loading")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("Parsing {file_name}"));
+                ui.add(egui::ProgressBar::new(fraction).show_percentage());
+                cancel = ui.button("Cancel").clicked();
+            });
+
+        if cancel {
+            if let Some(job) = self.load_job.take() {
+                job.cancel.cancel();
+            }
+        } else {
+            // The parsing thread does not wake the UI, so poll it
+            ctx.request_repaint_after(Duration::from_millis(50));
         }
     }
 
     fn show_error_dialog(&mut self, message: &str) {
+        log::warn!("{message}");
         self.error_message = Some(message.to_string());
     }
 
@@ -344,12 +1803,104 @@ impl eframe::App for MainWindow {
     fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
         self.update(ctx, frame);
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(
+            storage,
+            LAYER_COLORS_KEY,
+            self.layer_panel.get_layer_color_overrides(),
+        );
+        eframe::set_value(
+            storage,
+            LAYER_CLASSES_KEY,
+            self.layer_panel.get_layer_class_overrides(),
+        );
+        eframe::set_value(
+            storage,
+            LAYER_MATERIALS_KEY,
+            self.layer_panel.get_layer_material_overrides(),
+        );
+        eframe::set_value(storage, VIEW_ANCHOR_KEY, &self.toolbar.view_anchor);
+        eframe::set_value(
+            storage,
+            INPUT_BINDINGS_KEY,
+            self.preferences_window.get_input_bindings(),
+        );
+        eframe::set_value(
+            storage,
+            LINT_CONFIG_KEY,
+            self.problems_window.get_lint_config(),
+        );
+        eframe::set_value(
+            storage,
+            MAX_WIDTH_DISTORTION_KEY,
+            &self.problems_window.get_max_width_distortion(),
+        );
+        eframe::set_value(
+            storage,
+            GUIDES_KEY,
+            &self.stack_viewer.get_guides().to_vec(),
+        );
+        eframe::set_value(
+            storage,
+            STRUCTURES_KEY,
+            &self.stack_viewer.get_structures().to_vec(),
+        );
+        eframe::set_value(
+            storage,
+            METAL_DENSITIES_KEY,
+            self.density_strip.get_densities(),
+        );
+        eframe::set_value(
+            storage,
+            LOG_VERBOSITY_KEY,
+            &self.preferences_window.get_log_verbosity(),
+        );
+        eframe::set_value(
+            storage,
+            VIEW_LIMITS_KEY,
+            self.preferences_window.get_view_limits(),
+        );
+        eframe::set_value(
+            storage,
+            BOND_OPTIONS_KEY,
+            self.preferences_window.get_bond_options(),
+        );
+        eframe::set_value(
+            storage,
+            DISPLAY_DEFAULTS_KEY,
+            self.preferences_window.get_display_defaults(),
+        );
+        eframe::set_value(
+            storage,
+            DUPLICATE_NAMES_KEY,
+            &self.preferences_window.get_duplicate_names(),
+        );
+        eframe::set_value(
+            storage,
+            NAMING_OVERRIDES_KEY,
+            self.naming_window.get_overrides(),
+        );
+        eframe::set_value(
+            storage,
+            USE_DISPLAY_NAMES_KEY,
+            &self.naming_window.get_use_display_names(),
+        );
+        eframe::set_value(
+            storage,
+            THEME_PATH_KEY,
+            &self.theme_file.as_ref().map(ThemeFile::get_path),
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::{ConductorLayer, DielectricLayer, Layer, TechnologyInfo};
+    use crate::data::{
+        ConductorLayer, DielectricLayer, Layer, LookupTable2D, TechnologyInfo, ViaConnection,
+        BOND_GAP_LAYER,
+    };
 
     fn create_test_stack() -> ProcessStack {
         let tech = TechnologyInfo::new("test_stack".to_string());
@@ -368,12 +1919,24 @@ mod tests {
         stack
     }
 
+    /// Finish the background load started by `load_file_from_path`
+    fn wait_for_load(window: &mut MainWindow) {
+        window
+            .load_job
+            .as_ref()
+            .unwrap()
+            .promise
+            .block_until_ready();
+        window.poll_load_job();
+        assert!(window.load_job.is_none());
+    }
+
     #[test]
     fn test_main_window_creation() {
         let window = MainWindow::new();
         assert!(!window.file_menu.is_open);
         assert!(window.layer_panel.is_open);
-        assert!(window.current_stack.is_none());
+        assert!(window.document.is_none());
         assert!(!window.show_about);
     }
 
@@ -393,6 +1956,114 @@ mod tests {
         assert!(window.layer_panel.get_selected_layer().is_none());
     }
 
+    #[test]
+    fn test_document_edits() {
+        let mut window = MainWindow::new();
+        window.load_stack(create_test_stack());
+        window.select_layer(Some("metal1".to_string()));
+        window
+            .layer_panel
+            .set_layer_class("metal1", Some(LayerClass::BarrierLiner));
+        window.layer_panel.set_layer_material("metal1", Some("Al"));
+
+        window.apply_edit(StackEdit::RenameLayer {
+            layer_name: "metal1".to_string(),
+            new_name: "M1".to_string(),
+        });
+        assert_eq!(window.get_selected_layer(), Some(&"M1".to_string()));
+        assert_eq!(
+            window.layer_panel.get_layer_class_overrides().get("M1"),
+            Some(&LayerClass::BarrierLiner)
+        );
+        assert_eq!(
+            window.layer_panel.get_layer_material_overrides().get("M1"),
+            Some(&"Al".to_string())
+        );
+        assert_eq!(
+            window.toolbar.undo_description.as_deref(),
+            Some("Rename metal1 to M1")
+        );
+
+        window.handle_toolbar_action(ToolbarAction::Undo);
+        assert_eq!(window.get_selected_layer(), Some(&"metal1".to_string()));
+        assert!(window.toolbar.undo_description.is_none());
+        assert!(window.toolbar.redo_description.is_some());
+
+        window.apply_edit(StackEdit::MoveLayer {
+            layer_name: "metal1".to_string(),
+            index: 0,
+        });
+        assert_eq!(
+            window.get_current_stack().unwrap().layers[0].name(),
+            "metal1"
+        );
+        assert!(window.error_message.is_none());
+
+        // Loading another file drops the history
+        window.load_stack(create_test_stack());
+        window.process_stack_changes();
+        assert!(!window.get_document().unwrap().can_redo());
+        assert!(window.toolbar.redo_description.is_none());
+    }
+
+    #[test]
+    fn test_unload_tables() {
+        let mut stack = create_test_stack();
+        if let Some(Layer::Conductor(metal)) = stack.layers.get_mut(1) {
+            metal.rho_vs_width_spacing = Some(LookupTable2D::new(
+                vec![0.1, 0.2],
+                vec![0.1],
+                vec![vec![0.1], vec![0.1]],
+            ));
+        }
+        let mut window = MainWindow::new();
+        window.load_stack(stack);
+        window.apply_edit(StackEdit::SetLayerThickness {
+            layer_name: "oxide1".to_string(),
+            thickness: 2.0,
+        });
+
+        window.unload_tables();
+        let stack = window.get_current_stack().unwrap();
+        assert_eq!(stack.memory_usage().table_bytes(), 0);
+        assert_eq!(stack.get_layer_count(), 2);
+        assert!(window.resources_window.get_unloaded_bytes().unwrap() > 0);
+        assert!(window.toolbar.undo_description.is_none());
+
+        window.load_stack(create_test_stack());
+        assert!(window.resources_window.get_unloaded_bytes().is_none());
+    }
+
+    #[test]
+    fn test_load_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("window_theme.toml");
+        std::fs::write(&path, "[classes.dielectric]\ncolor = \"#102030\"\n").unwrap();
+        let mut window = MainWindow::with_stack(create_test_stack());
+        window.load_theme_from_path(path.clone()).unwrap();
+
+        let expected = Color32::from_rgb(0x10, 0x20, 0x30);
+        let stack = window.get_current_stack().unwrap();
+        let oxide = stack.get_layer("oxide1").unwrap();
+        assert_eq!(
+            window
+                .stack_viewer
+                .get_color_scheme()
+                .get_layer_color(oxide, 0),
+            expected
+        );
+        assert_eq!(
+            window.toolbar.theme_name.as_deref(),
+            path.file_name().and_then(|name| name.to_str())
+        );
+
+        window.handle_toolbar_action(ToolbarAction::ClearTheme);
+        assert!(window.stack_viewer.get_color_scheme().theme.is_none());
+        assert!(window
+            .load_theme_from_path(path.with_extension("missing"))
+            .is_err());
+    }
+
     #[test]
     fn test_layer_selection() {
         let mut window = MainWindow::new();
@@ -404,6 +2075,190 @@ mod tests {
         assert_eq!(window.get_selected_layer(), None);
     }
 
+    #[test]
+    fn test_selected_layer_heights() {
+        let mut window = MainWindow::with_stack(create_test_stack());
+        assert_eq!(window.selected_layer_heights(), None);
+
+        // The conductor is embedded at the bottom of oxide1
+        window.select_layer(Some("metal1".to_string()));
+        assert_eq!(
+            window.selected_layer_heights().as_deref(),
+            Some("metal1: z 0.000-0.500 μm, depth 0.500-1.000 μm")
+        );
+
+        // Schematic mode does not change the physical heights
+        window.handle_toolbar_action(ToolbarAction::ToggleSchematicMode(true));
+        window.select_layer(Some("oxide1".to_string()));
+        assert_eq!(
+            window.selected_layer_heights().as_deref(),
+            Some("oxide1: z 0.000-1.000 μm, depth 0.000-1.000 μm")
+        );
+    }
+
+    #[test]
+    fn test_selection_synchronization() {
+        let mut window = MainWindow::new();
+        window.load_stack(create_test_stack());
+
+        // Selecting a conductor reaches every panel and pre-fills the calculator
+        window.set_selection(Some("metal1".to_string()), SelectionSource::StackViewer);
+        let metal1 = Some(&"metal1".to_string());
+        assert_eq!(window.layer_panel.get_selected_layer(), metal1);
+        assert_eq!(window.layer_details_panel.get_selected_layer(), metal1);
+        assert_eq!(window.stack_viewer.get_selected_layer(), metal1);
+        assert_eq!(
+            window.resistance_plot_window.get_selected_conductor_name(),
+            metal1
+        );
+
+        // A dielectric does not replace the calculator's conductor
+        window.set_selection(Some("oxide1".to_string()), SelectionSource::LayerPanel);
+        assert_eq!(
+            window.layer_details_panel.get_selected_layer(),
+            Some(&"oxide1".to_string())
+        );
+        assert_eq!(
+            window.resistance_plot_window.get_selected_conductor_name(),
+            metal1
+        );
+        assert_eq!(window.layer_panel.get_tab(), LayerPanelTab::Layers);
+
+        assert!(window.stack_viewer.get_highlighted_layers().is_empty());
+
+        // Clicking a via in the view switches the layer panel to the via table and
+        // highlights the dielectric it passes through
+        let mut stack = create_test_stack();
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide0".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "poly".to_string(),
+            0.2,
+        ))));
+        stack.add_via(ViaConnection::new(
+            "contact".to_string(),
+            "poly".to_string(),
+            "metal1".to_string(),
+            0.04,
+            2.0,
+        ));
+        window.load_stack(stack);
+        window.set_selection(Some("contact".to_string()), SelectionSource::StackViewer);
+        assert_eq!(window.layer_panel.get_tab(), LayerPanelTab::Vias);
+        assert_eq!(
+            window.layer_panel.get_selected_layer(),
+            Some(&"contact".to_string())
+        );
+        assert_eq!(window.stack_viewer.get_highlighted_layers(), ["oxide0"]);
+
+        // Loading a new stack clears the selection everywhere
+        window.load_stack(create_test_stack());
+        assert!(window.get_selected_layer().is_none());
+        assert!(window.stack_viewer.get_selected_layer().is_none());
+        assert!(window.stack_viewer.get_highlighted_layers().is_empty());
+        assert!(window
+            .resistance_plot_window
+            .get_selected_conductor_name()
+            .is_none());
+    }
+
+    #[test]
+    fn test_open_calculator_for() {
+        let mut window = MainWindow::new();
+        let mut stack = create_test_stack();
+        stack.add_layer(Layer::Conductor(Box::new(
+            ConductorLayer::new("metal2".to_string(), 0.5).with_width_spacing_limits(0.14, 0.14),
+        )));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            2.0,
+        ));
+        window.load_stack(stack);
+
+        // A conductor opens the resistance calculator at its WMIN
+        window.open_calculator_for("metal2");
+        assert!(window.resistance_plot_window.is_open());
+        assert!(window.toolbar.show_resistance_calculator);
+        assert_eq!(
+            window.resistance_plot_window.get_selected_conductor_name(),
+            Some(&"metal2".to_string())
+        );
+        assert_eq!(window.resistance_plot_window.get_width(), 0.14);
+
+        // Without WMIN the width is kept
+        window.open_calculator_for("metal1");
+        assert_eq!(window.resistance_plot_window.get_width(), 0.14);
+
+        // A via opens the via calculator, a dielectric nothing
+        window.open_calculator_for("oxide1");
+        assert!(!window.via_resistance_window.is_open());
+        window.open_calculator_for("via1");
+        assert!(window.via_resistance_window.is_open());
+        assert!(window.toolbar.show_via_resistance);
+        assert_eq!(
+            window.via_resistance_window.get_selected_via_name(),
+            Some(&"via1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_display_defaults() {
+        let mut window = MainWindow::new();
+        window
+            .preferences_window
+            .set_display_defaults(DisplayDefaults {
+                render_mode: RenderMode::Schematic,
+                show_dimensions: false,
+                show_layer_names: true,
+                fill_style: FillStyle::OutlineOnly,
+            });
+        window.load_stack(create_test_stack());
+        assert!(window.toolbar.show_schematic_mode);
+        assert!(!window.toolbar.show_dimensions);
+        assert!(window.toolbar.show_layer_names);
+        assert!(window.toolbar.show_outline_only);
+        assert!(!window.toolbar.show_hatching);
+        assert!(window.stack_viewer.is_outline_only());
+
+        // Changes in the View menu last until the next stack is opened
+        window.handle_toolbar_action(ToolbarAction::ToggleSchematicMode(false));
+        window.handle_toolbar_action(ToolbarAction::ToggleOutlineOnly(false));
+        assert!(!window.toolbar.show_schematic_mode);
+        window.load_stack(create_test_stack());
+        assert!(window.toolbar.show_schematic_mode);
+        assert!(window.stack_viewer.is_outline_only());
+    }
+
+    #[test]
+    fn test_legend_isolation() {
+        let mut window = MainWindow::new();
+        window.load_stack(create_test_stack());
+        window.legend_window.click_entry("metal1", false);
+        assert!(window.legend_window.take_isolation_change());
+        window.refresh_isolation();
+        let colors = window.stack_viewer.get_color_scheme();
+        assert_eq!(
+            colors.get_alpha_override("oxide1"),
+            Some(crate::gui::DIMMED_ALPHA)
+        );
+        assert_eq!(colors.get_alpha_override("metal1"), None);
+
+        // Opening a stack shows every layer again
+        window.load_stack(create_test_stack());
+        assert!(window.legend_window.get_isolated().is_empty());
+        assert!(window
+            .stack_viewer
+            .get_color_scheme()
+            .alpha_overrides
+            .is_empty());
+    }
+
     #[test]
     fn test_about_dialog() {
         let mut window = MainWindow::new();
@@ -428,6 +2283,64 @@ mod tests {
         assert_eq!(window.layer_panel.is_open, initial_state);
     }
 
+    #[test]
+    fn test_bond_stack() {
+        let mut window = MainWindow::new();
+        window.load_stack(create_test_stack());
+        window
+            .preferences_window
+            .set_bond_options(BondOptions::new().with_gap(0.5));
+        window.bond_stack_from_path(PathBuf::from("tests/data/simple_stack.itf"));
+
+        let stats = *window.layer_panel.get_bond_stats().unwrap();
+        assert_eq!(stats.gap, 0.5);
+        assert!((stats.bottom_height - 1.0).abs() < 1e-9);
+        let stack = window.document.as_ref().unwrap().stack();
+        assert!(stack.get_layer(BOND_GAP_LAYER).is_some());
+        assert!(stack.get_layer("top_metal1").is_some());
+        assert_eq!(
+            stack.layers.last().map(Layer::name),
+            Some("metal1"),
+            "the loaded stack stays at the bottom"
+        );
+
+        // Loading another stack drops the bond statistics
+        window.load_stack(create_test_stack());
+        assert!(window.layer_panel.get_bond_stats().is_none());
+    }
+
+    #[test]
+    fn test_export_for_slides() {
+        let mut window = MainWindow::new();
+        window.load_stack(create_test_stack());
+        let dir = tempfile::tempdir().unwrap();
+
+        // The extension chosen in the dialog decides the format
+        let emf_path = dir.path().join("stack.EMF");
+        window.export_cross_section(ExportFormat::SlidePng, &emf_path);
+        assert_eq!(&std::fs::read(&emf_path).unwrap()[40..44], b" EMF");
+        let png_path = dir.path().join("stack.png");
+        window.export_cross_section(ExportFormat::Emf, &png_path);
+        assert_eq!(&std::fs::read(&png_path).unwrap()[1..4], b"PNG");
+        assert!(window.error_message.is_none());
+
+        window.handle_toolbar_action(ToolbarAction::CopySlideImage);
+        assert_eq!(window.clipboard_image.as_ref().unwrap().size, [3840, 2160]);
+    }
+
+    #[test]
+    fn test_export_html() {
+        let mut window = MainWindow::new();
+        window.load_stack(create_test_stack());
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("stack.html");
+        window.export_cross_section(ExportFormat::Html, &path);
+        assert!(window.error_message.is_none());
+        let html = std::fs::read_to_string(&path).unwrap();
+        assert!(html.contains("<canvas id=\"view\">"));
+    }
+
     #[test]
     fn test_toolbar_actions() {
         let mut window = MainWindow::new();
@@ -435,7 +2348,12 @@ mod tests {
         // Skip OpenFile test to avoid opening system file browser during automated tests
         // Instead, test file loading directly using a test file
         window.load_file_from_path(PathBuf::from("tests/data/complex_test.itf"));
+        wait_for_load(&mut window);
         assert!(window.has_loaded_file());
+        assert_eq!(
+            window.get_file_path(),
+            Some(&PathBuf::from("tests/data/complex_test.itf"))
+        );
 
         // Test reset view action (should not panic)
         window.handle_toolbar_action(ToolbarAction::ResetView);
@@ -452,17 +2370,244 @@ mod tests {
         window.handle_toolbar_action(ToolbarAction::ToggleLayerNames(false));
         assert!(!window.toolbar.show_layer_names);
 
+        window.handle_toolbar_action(ToolbarAction::ToggleSearch(true));
+        assert!(window.search_window.is_open());
+        assert!(window.toolbar.show_search);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleViaChain(true));
+        assert!(window.via_chain_window.is_open());
+        assert!(window.toolbar.show_via_chain);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleProblems(true));
+        assert!(window.problems_window.is_open());
+        assert!(window.toolbar.show_problems);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleSource(true));
+        assert!(window.source_window.is_open());
+        assert!(window.source_window.has_source());
+        assert!(window.toolbar.show_source);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleHistogram(true));
+        assert!(window.histogram_window.is_open());
+        assert!(window.toolbar.show_histogram);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleViaResistance(true));
+        assert!(window.via_resistance_window.is_open());
+        assert!(window.toolbar.show_via_resistance);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleNaming(true));
+        assert!(window.naming_window.is_open());
+        assert!(window.toolbar.show_naming);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleTemperatureDrift(true));
+        assert!(window.temperature_drift_window.is_open());
+        assert!(window.toolbar.show_temperature_drift);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleGuides(true));
+        assert!(window.guides_window.is_open());
+        assert!(window.toolbar.show_guides);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleStructures(true));
+        assert!(window.structures_window.is_open());
+        assert!(window.toolbar.show_structures);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleResources(true));
+        assert!(window.resources_window.is_open());
+        assert!(window.toolbar.show_resources);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleHistory(true));
+        assert!(window.history_window.is_open());
+        assert!(window.toolbar.show_history);
+
+        window.handle_toolbar_action(ToolbarAction::ClearTheme);
+        assert!(window.toolbar.theme_name.is_none());
+
+        window.handle_toolbar_action(ToolbarAction::ToggleLegend(true));
+        assert!(window.legend_window.is_open());
+        assert!(window.toolbar.show_legend);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleOutlineOnly(true));
+        assert!(window.stack_viewer.is_outline_only());
+        assert!(window.toolbar.show_outline_only);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleSplitView(true));
+        assert!(window.stack_viewer.is_split_view());
+        assert!(window.toolbar.show_split_view);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleAreaAccurateVias(true));
+        assert!(window.toolbar.area_accurate_vias);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleTrueSidewallAngles(true));
+        assert_eq!(window.layer_details_panel.sidewall_exaggeration, Some(1.0));
+        assert!(window.toolbar.true_sidewall_angles);
+
+        window.handle_toolbar_action(ToolbarAction::SetSidewallExaggeration(3.0));
+        assert_eq!(window.layer_details_panel.sidewall_exaggeration, Some(3.0));
+        assert_eq!(window.toolbar.sidewall_exaggeration, 3.0);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleDensityStrip(true));
+        assert!(window.density_strip.is_open());
+        assert!(window.toolbar.show_density_strip);
+
+        window.handle_toolbar_action(ToolbarAction::ToggleProfiler(true));
+        assert!(window.stack_viewer.is_profiler_shown());
+        assert!(window.toolbar.show_profiler);
+
+        // Tests do not log to a file, so opening the log reports that instead
+        window.handle_toolbar_action(ToolbarAction::OpenLog);
+        assert!(window.error_message.take().unwrap().contains("not active"));
+
+        window.handle_toolbar_action(ToolbarAction::ToggleHalfNodeScale(true));
+        assert!(window.layer_details_panel.apply_half_node_scale);
+        assert!(window.toolbar.apply_half_node_scale);
+
         // Test layer width setting
         window.handle_toolbar_action(ToolbarAction::SetLayerWidth(300.0));
         assert_eq!(window.toolbar.layer_width, 300.0);
     }
 
+    #[test]
+    fn test_new_from_template() {
+        let mut window = MainWindow::new();
+
+        window.handle_toolbar_action(ToolbarAction::NewFromTemplate(
+            crate::utils::StackTemplate::GenericLogic,
+            4,
+        ));
+        assert!(window.error_message.is_none());
+        let stack = window.get_current_stack().unwrap();
+        assert!(stack.get_layer("metal4").is_some());
+        assert!(stack.get_layer("metal5").is_none());
+    }
+
+    #[test]
+    fn test_open_example() {
+        let mut window = MainWindow::new();
+        window.set_file_path(Some(PathBuf::from("previous.itf")));
+
+        window.handle_toolbar_action(ToolbarAction::OpenExample(
+            crate::utils::ExampleStack::Simple,
+        ));
+        assert!(window.error_message.is_none());
+        assert!(window
+            .get_current_stack()
+            .unwrap()
+            .get_layer("poly")
+            .is_some());
+        // Examples are embedded, saving asks for a new path
+        assert!(window.get_file_path().is_none());
+    }
+
+    #[test]
+    fn test_paste_itf() {
+        let mut window = MainWindow::new();
+        window.set_file_path(Some(PathBuf::from("previous.itf")));
+
+        window.handle_toolbar_action(ToolbarAction::PasteItf);
+        assert!(window.paste_requested);
+
+        window.load_pasted_itf(crate::utils::ExampleStack::Simple.source());
+        assert!(window.error_message.is_none());
+        assert!(window
+            .get_current_stack()
+            .unwrap()
+            .get_layer("poly")
+            .is_some());
+        assert!(window.get_file_path().is_none());
+        assert!(window.source_window.has_source());
+
+        // Text that is not ITF keeps the loaded stack
+        window.load_pasted_itf("Hi, the new stack is attached");
+        assert!(window.error_message.is_some());
+        assert!(window.get_current_stack().is_some());
+    }
+
+    #[test]
+    fn test_load_random_stack() {
+        let mut window = MainWindow::new();
+        window.set_file_path(Some(PathBuf::from("previous.itf")));
+
+        window.handle_toolbar_action(ToolbarAction::LoadRandomStack {
+            seed: 3,
+            layer_count: 11,
+        });
+        assert_eq!(window.get_current_stack().unwrap().get_layer_count(), 11);
+        // A generated stack is not backed by a file
+        assert!(window.get_file_path().is_none());
+    }
+
+    #[test]
+    fn test_background_file_loading() {
+        let mut window = MainWindow::new();
+        let path = PathBuf::from("tests/data/simple_1p3m.itf");
+        window.load_file_from_path(path.clone());
+        wait_for_load(&mut window);
+        assert!(window.has_loaded_file());
+        assert_eq!(window.get_file_path(), Some(&path));
+
+        // Going to a problem selects its layer and shows its line of the file
+        window.go_to_problem(ProblemTarget {
+            layer: Some("metal2".to_string()),
+            line: Some(61),
+        });
+        assert_eq!(window.stack_viewer.get_selected_layer().unwrap(), "metal2");
+        assert!(window.source_window.is_open());
+        assert_eq!(window.source_window.get_highlighted_line(), Some(61));
+
+        // Read errors end up in the error dialog and keep the loaded stack
+        window.load_file_from_path(PathBuf::from("tests/data/missing.itf"));
+        wait_for_load(&mut window);
+        assert!(window.error_message.as_ref().unwrap().contains("read"));
+        assert_eq!(window.get_file_path(), Some(&path));
+    }
+
+    #[test]
+    fn test_rendering_crash() {
+        let mut window = MainWindow::new();
+        window.load_stack(create_test_stack());
+
+        // A panic while drawing suspends the view and keeps a report with the stack
+        let panic = guard(|| panic!("degenerate trapezoid")).unwrap_err();
+        window.rendering_suspended = true;
+        window.report_crash(
+            CrashReport::new("rendering", panic)
+                .with_stack(window.document.as_ref().map(StackDocument::stack)),
+        );
+        assert!(window
+            .crash_report
+            .as_ref()
+            .unwrap()
+            .stack_summary
+            .is_some());
+
+        // Loading another stack gives rendering another chance
+        window.load_stack(create_test_stack());
+        assert!(!window.rendering_suspended);
+    }
+
+    #[test]
+    fn test_import_csv() {
+        let mut window = MainWindow::new();
+        let path = PathBuf::from("tests/data/prototype_stack.csv");
+        window.import_csv_from_path(path.clone());
+        let stack = window.get_current_stack().unwrap();
+        assert_eq!(stack.technology_info.name, "prototype_stack");
+        assert_eq!(stack.get_conductor_count(), 3);
+        assert_eq!(window.get_file_path(), Some(&path));
+
+        window.import_csv_from_path(PathBuf::from("tests/data/simple_1p3m.itf"));
+        assert!(window.error_message.as_ref().unwrap().contains("CSV"));
+        assert_eq!(window.get_file_path(), Some(&path));
+    }
+
     #[test]
     fn test_auto_fit_without_stack() {
         let mut window = MainWindow::new();
 
         // Should not panic when no stack is loaded
         window.handle_toolbar_action(ToolbarAction::AutoFit);
+        window.handle_toolbar_action(ToolbarAction::FitWidth);
+        window.handle_toolbar_action(ToolbarAction::FitHeight);
     }
 
     #[test]
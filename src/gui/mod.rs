@@ -1,18 +1,54 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+pub mod density_strip;
 pub mod file_menu;
+pub mod guides_window;
+pub mod histogram_window;
+pub mod history_window;
+pub mod input_bindings;
 pub mod layer_details_panel;
 pub mod layer_panel;
+pub mod layer_table_window;
+pub mod legend_window;
 pub mod main_window;
+pub mod naming_window;
+pub mod preferences_window;
+pub mod problems_window;
 pub mod resistance_plot_window;
+pub mod resources_window;
+pub mod search_window;
+pub mod selection_state;
+pub mod source_window;
 pub mod stack_viewer;
+pub mod structures_window;
+pub mod temperature_drift_window;
 pub mod toolbar;
+pub mod via_chain_window;
+pub mod via_resistance_window;
 
+pub use density_strip::*;
 pub use file_menu::*;
+pub use guides_window::*;
+pub use histogram_window::*;
+pub use history_window::*;
+pub use input_bindings::*;
 pub use layer_details_panel::*;
 pub use layer_panel::*;
+pub use layer_table_window::*;
+pub use legend_window::*;
 pub use main_window::*;
+pub use naming_window::*;
+pub use preferences_window::*;
+pub use problems_window::*;
 pub use resistance_plot_window::*;
+pub use resources_window::*;
+pub use search_window::*;
+pub use selection_state::*;
+pub use source_window::*;
 pub use stack_viewer::*;
+pub use structures_window::*;
+pub use temperature_drift_window::*;
 pub use toolbar::*;
+pub use via_chain_window::*;
+pub use via_resistance_window::*;
@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{NameGroup, NameRole, NamingEntry, ProcessStack, StackNaming};
+use egui::{CollapsingHeader, Color32, ComboBox, Context, DragValue, Grid, ScrollArea, Ui, Window};
+use std::collections::HashMap;
+
+/// Kinds of role offered by the mapping editor, numbered ones taking the number separately
+const ROLE_KINDS: [&str; 5] = ["Poly", "Contact", "Metal", "Via", "Top Metal"];
+
+fn role_kind(role: NameRole) -> (&'static str, u32) {
+    match role {
+        NameRole::Poly => (ROLE_KINDS[0], 0),
+        NameRole::Contact => (ROLE_KINDS[1], 0),
+        NameRole::Metal(n) => (ROLE_KINDS[2], n),
+        NameRole::Via(n) => (ROLE_KINDS[3], n),
+        NameRole::TopMetal(n) => (ROLE_KINDS[4], n),
+    }
+}
+
+fn role_of_kind(kind: &str, number: u32) -> Option<NameRole> {
+    match kind {
+        "Poly" => Some(NameRole::Poly),
+        "Contact" => Some(NameRole::Contact),
+        "Metal" => Some(NameRole::Metal(number.max(1))),
+        "Via" => Some(NameRole::Via(number)),
+        "Top Metal" => Some(NameRole::TopMetal(number.max(1))),
+        _ => None,
+    }
+}
+
+/// Connectivity ladder recognized from the layer and via names, with an editor mapping
+/// names no convention covers
+pub struct NamingWindow {
+    open: bool,
+    /// Roles chosen by the user, keyed by layer or via name
+    overrides: HashMap<String, NameRole>,
+    /// Label the view with display names instead of ITF names
+    use_display_names: bool,
+    changed: bool,
+}
+
+impl NamingWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            overrides: HashMap::new(),
+            use_display_names: false,
+            changed: false,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_overrides(&self) -> &HashMap<String, NameRole> {
+        &self.overrides
+    }
+
+    pub fn set_overrides(&mut self, overrides: HashMap<String, NameRole>) {
+        self.overrides = overrides;
+        self.changed = true;
+    }
+
+    /// Set or, with `None`, clear the role of a layer or via
+    pub fn set_role(&mut self, name: &str, role: Option<NameRole>) {
+        match role {
+            Some(role) => self.overrides.insert(name.to_string(), role),
+            None => self.overrides.remove(name),
+        };
+        self.changed = true;
+    }
+
+    /// Keep the role of a renamed layer or via
+    pub fn rename_layer(&mut self, old_name: &str, new_name: &str) {
+        if let Some(role) = self.overrides.remove(old_name) {
+            self.overrides.insert(new_name.to_string(), role);
+            self.changed = true;
+        }
+    }
+
+    pub fn get_use_display_names(&self) -> bool {
+        self.use_display_names
+    }
+
+    pub fn set_use_display_names(&mut self, use_display_names: bool) {
+        self.use_display_names = use_display_names;
+        self.changed = true;
+    }
+
+    /// Return whether the mapping or the labeling changed since the last call
+    pub fn take_naming_change(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Labels for the view: display names when enabled, otherwise none
+    pub fn get_display_names(&self, stack: &ProcessStack) -> HashMap<String, String> {
+        if self.use_display_names {
+            stack.get_naming(&self.overrides).get_display_names()
+        } else {
+            HashMap::new()
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) {
+        if !self.open {
+            return;
+        }
+
+        let mut open = self.open;
+        Window::new("Naming Conventions")
+            .open(&mut open)
+            .default_size([480.0, 420.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let Some(stack) = stack else {
+                    ui.label("Load a stack to recognize its layer names.");
+                    return;
+                };
+                let naming = stack.get_naming(&self.overrides);
+
+                let mut use_display_names = self.use_display_names;
+                if ui
+                    .checkbox(&mut use_display_names, "Label the view with display names")
+                    .changed()
+                {
+                    self.set_use_display_names(use_display_names);
+                }
+                if !naming.is_recognized() {
+                    ui.label("No name follows a known convention; assign roles below.");
+                }
+                for warning in &naming.warnings {
+                    ui.colored_label(Color32::from_rgb(230, 160, 40), format!("⚠ {warning}"));
+                }
+                ui.separator();
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    // Top of the stack first, as in the layer panel
+                    for group in NameGroup::ALL.into_iter().rev() {
+                        self.show_group(ui, &naming, group);
+                    }
+                    if !naming.unrecognized.is_empty() {
+                        CollapsingHeader::new(format!(
+                            "Unrecognized ({})",
+                            naming.unrecognized.len()
+                        ))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            Grid::new("naming_unrecognized")
+                                .num_columns(2)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for name in &naming.unrecognized {
+                                        ui.label(name);
+                                        self.role_editor(ui, name, None);
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                    }
+                });
+            });
+        self.open = open;
+    }
+
+    fn show_group(&mut self, ui: &mut Ui, naming: &StackNaming, group: NameGroup) {
+        let entries: Vec<_> = naming.get_group(group).rev().cloned().collect();
+        if entries.is_empty() {
+            return;
+        }
+        CollapsingHeader::new(format!("{} ({})", group.label(), entries.len()))
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new(("naming_group", group.label()))
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for entry in &entries {
+                            ui.label(&entry.name);
+                            ui.strong(entry.role.display_name());
+                            self.role_editor(ui, &entry.name, Some(entry));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// Combo box and number assigning a role to `name`
+    fn role_editor(&mut self, ui: &mut Ui, name: &str, entry: Option<&NamingEntry>) {
+        let current = entry.map(|entry| entry.role);
+        let overridden = entry.is_some_and(|entry| entry.overridden);
+        let (mut kind, mut number) = current.map_or(("", 1), role_kind);
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            ComboBox::from_id_salt(("naming_role", name))
+                .selected_text(if kind.is_empty() { "Unassigned" } else { kind })
+                .show_ui(ui, |ui| {
+                    for option in ROLE_KINDS {
+                        changed |= ui.selectable_value(&mut kind, option, option).changed();
+                    }
+                });
+            if matches!(
+                current,
+                Some(NameRole::Metal(_) | NameRole::Via(_) | NameRole::TopMetal(_))
+            ) {
+                changed |= ui.add(DragValue::new(&mut number).range(0..=99)).changed();
+            }
+            if changed {
+                self.set_role(name, role_of_kind(kind, number));
+            }
+            if overridden
+                && ui
+                    .small_button("Reset")
+                    .on_hover_text("Use the role recognized from the name")
+                    .clicked()
+            {
+                self.set_role(name, None);
+            }
+        });
+    }
+}
+
+impl Default for NamingWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, Layer, TechnologyInfo};
+
+    #[test]
+    fn test_naming_window() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("naming".to_string()));
+        for name in ["thick", "M1"] {
+            stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+                name.to_string(),
+                0.2,
+            ))));
+        }
+
+        let mut window = NamingWindow::new();
+        assert!(window.get_display_names(&stack).is_empty());
+        window.set_use_display_names(true);
+        assert!(window.take_naming_change());
+        assert!(!window.take_naming_change());
+        assert_eq!(
+            window
+                .get_display_names(&stack)
+                .get("M1")
+                .map(String::as_str),
+            Some("Metal 1")
+        );
+
+        window.set_role("thick", role_of_kind("Top Metal", 0));
+        window.rename_layer("thick", "MTOP_A");
+        assert_eq!(
+            window.get_overrides().get("MTOP_A"),
+            Some(&NameRole::TopMetal(1))
+        );
+        assert_eq!(role_kind(NameRole::Via(2)), ("Via", 2));
+        window.set_role("MTOP_A", None);
+        assert!(window.get_overrides().is_empty());
+    }
+}
@@ -0,0 +1,430 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::BondOptions;
+use crate::gui::{InputBindings, ScrollBehavior, ViewCommand};
+use crate::parser::DuplicateNamePolicy;
+use crate::renderer::{DisplayDefaults, FillStyle, RenderMode, ViewLimits};
+use crate::utils::{get_log_file_path, LogVerbosity};
+use egui::{ComboBox, Context, DragValue, Event, Grid, KeyboardShortcut, Window};
+
+/// Application preferences: scroll behavior, key bindings and view limits of the stack
+/// viewer, how opened stacks are parsed, shown and bonded, and the verbosity of the log file
+pub struct PreferencesWindow {
+    open: bool,
+    bindings: InputBindings,
+    /// Command waiting for the next key press to become its shortcut
+    capturing: Option<ViewCommand>,
+    bindings_changed: bool,
+    log_verbosity: LogVerbosity,
+    log_verbosity_changed: bool,
+    view_limits: ViewLimits,
+    view_limits_changed: bool,
+    bond_options: BondOptions,
+    display_defaults: DisplayDefaults,
+    duplicate_names: DuplicateNamePolicy,
+}
+
+impl PreferencesWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            bindings: InputBindings::default(),
+            capturing: None,
+            bindings_changed: false,
+            log_verbosity: LogVerbosity::default(),
+            log_verbosity_changed: false,
+            view_limits: ViewLimits::default(),
+            view_limits_changed: false,
+            bond_options: BondOptions::default(),
+            display_defaults: DisplayDefaults::default(),
+            duplicate_names: DuplicateNamePolicy::default(),
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+        self.capturing = None;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_input_bindings(&self) -> &InputBindings {
+        &self.bindings
+    }
+
+    pub fn set_input_bindings(&mut self, bindings: InputBindings) {
+        self.bindings = bindings;
+        self.bindings_changed = true;
+    }
+
+    /// Return the bindings if the user changed them since the last call
+    pub fn take_bindings_change(&mut self) -> Option<InputBindings> {
+        std::mem::take(&mut self.bindings_changed).then(|| self.bindings.clone())
+    }
+
+    pub fn get_log_verbosity(&self) -> LogVerbosity {
+        self.log_verbosity
+    }
+
+    pub fn set_log_verbosity(&mut self, verbosity: LogVerbosity) {
+        self.log_verbosity = verbosity;
+        self.log_verbosity_changed = true;
+    }
+
+    /// Return the log verbosity if the user changed it since the last call
+    pub fn take_log_verbosity_change(&mut self) -> Option<LogVerbosity> {
+        std::mem::take(&mut self.log_verbosity_changed).then_some(self.log_verbosity)
+    }
+
+    pub fn get_view_limits(&self) -> &ViewLimits {
+        &self.view_limits
+    }
+
+    pub fn set_view_limits(&mut self, limits: ViewLimits) {
+        self.view_limits = limits;
+        self.view_limits_changed = true;
+    }
+
+    /// Return the view limits if the user changed them since the last call
+    pub fn take_view_limits_change(&mut self) -> Option<ViewLimits> {
+        std::mem::take(&mut self.view_limits_changed).then_some(self.view_limits)
+    }
+
+    /// Options of File > Bond Stack Face-to-Face
+    pub fn get_bond_options(&self) -> &BondOptions {
+        &self.bond_options
+    }
+
+    pub fn set_bond_options(&mut self, options: BondOptions) {
+        self.bond_options = options;
+    }
+
+    /// View settings applied to each opened stack
+    pub fn get_display_defaults(&self) -> &DisplayDefaults {
+        &self.display_defaults
+    }
+
+    pub fn set_display_defaults(&mut self, defaults: DisplayDefaults) {
+        self.display_defaults = defaults;
+    }
+
+    /// How opened files with two layers of the same name are read
+    pub fn get_duplicate_names(&self) -> DuplicateNamePolicy {
+        self.duplicate_names
+    }
+
+    pub fn set_duplicate_names(&mut self, policy: DuplicateNamePolicy) {
+        self.duplicate_names = policy;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        if !self.open {
+            return;
+        }
+
+        if let Some(command) = self.capturing {
+            self.capture_shortcut(ctx, command);
+        }
+
+        let mut open = self.open;
+        Window::new("Preferences")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.heading("Scrolling");
+                for behavior in ScrollBehavior::ALL {
+                    if ui
+                        .radio_value(
+                            &mut self.bindings.scroll_behavior,
+                            behavior,
+                            behavior.label(),
+                        )
+                        .changed()
+                    {
+                        self.bindings_changed = true;
+                    }
+                }
+                ui.label("Pinch and Ctrl+scroll always zoom.");
+                ui.separator();
+
+                ui.heading("Keyboard Shortcuts");
+                self.show_key_bindings(ui);
+                ui.separator();
+
+                ui.heading("Opened Stacks");
+                self.show_display_defaults(ui);
+                ComboBox::from_label("Duplicate layer names")
+                    .selected_text(self.duplicate_names.label())
+                    .show_ui(ui, |ui| {
+                        for policy in DuplicateNamePolicy::ALL {
+                            ui.selectable_value(&mut self.duplicate_names, policy, policy.label());
+                        }
+                    });
+                ui.separator();
+
+                ui.heading("View Limits");
+                self.show_view_limits(ui);
+                ui.separator();
+
+                ui.heading("Bonding");
+                Grid::new("preferences_bonding")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Bond gap (um)");
+                        ui.add(
+                            DragValue::new(&mut self.bond_options.gap)
+                                .speed(0.01)
+                                .range(0.0..=100.0),
+                        )
+                        .on_hover_text("Bonding dielectric between the faces of bonded stacks");
+                        ui.end_row();
+                        ui.label("Gap ER");
+                        ui.add(
+                            DragValue::new(&mut self.bond_options.gap_dielectric_constant)
+                                .speed(0.01)
+                                .range(1.0..=25.0),
+                        );
+                        ui.end_row();
+                    });
+                ui.separator();
+
+                ui.heading("Logging");
+                ComboBox::from_label("Log file verbosity")
+                    .selected_text(self.log_verbosity.label())
+                    .show_ui(ui, |ui| {
+                        for verbosity in LogVerbosity::ALL {
+                            if ui
+                                .selectable_value(
+                                    &mut self.log_verbosity,
+                                    verbosity,
+                                    verbosity.label(),
+                                )
+                                .changed()
+                            {
+                                self.log_verbosity_changed = true;
+                            }
+                        }
+                    });
+                match get_log_file_path() {
+                    Some(path) => ui.label(format!("Log file: {}", path.display())),
+                    None => ui.weak("Logging to a file is not active"),
+                };
+                ui.separator();
+
+                if ui.button("Reset to defaults").clicked() {
+                    self.set_input_bindings(InputBindings::default());
+                    self.set_view_limits(ViewLimits::default());
+                    self.set_bond_options(BondOptions::default());
+                    self.set_display_defaults(DisplayDefaults::default());
+                    self.set_duplicate_names(DuplicateNamePolicy::default());
+                    self.capturing = None;
+                }
+            });
+        if !open {
+            self.set_open(false);
+        }
+    }
+
+    fn show_display_defaults(&mut self, ui: &mut egui::Ui) {
+        let defaults = &mut self.display_defaults;
+        Grid::new("preferences_display_defaults")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Render mode");
+                ComboBox::from_id_salt("preferences_render_mode")
+                    .selected_text(defaults.render_mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in RenderMode::ALL {
+                            ui.selectable_value(&mut defaults.render_mode, mode, mode.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Colors");
+                ComboBox::from_id_salt("preferences_fill_style")
+                    .selected_text(defaults.fill_style.label())
+                    .show_ui(ui, |ui| {
+                        for style in FillStyle::ALL {
+                            ui.selectable_value(&mut defaults.fill_style, style, style.label());
+                        }
+                    });
+                ui.end_row();
+            });
+        ui.checkbox(&mut defaults.show_dimensions, "Show dimensions");
+        ui.checkbox(&mut defaults.show_layer_names, "Show layer names");
+        ui.weak("Applied whenever a stack is opened; the View menu changes the current one.");
+    }
+
+    fn show_view_limits(&mut self, ui: &mut egui::Ui) {
+        let limits = &mut self.view_limits;
+        let mut changed = ui
+            .checkbox(&mut limits.enabled, "Keep the stack in view")
+            .on_hover_text(
+                "Ease the view back when the stack is panned off-screen or zoomed out of range",
+            )
+            .changed();
+        ui.add_enabled_ui(limits.enabled, |ui| {
+            Grid::new("preferences_view_limits")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Zoom out to");
+                    changed |= ui
+                        .add(
+                            DragValue::new(&mut limits.min_zoom)
+                                .range(0.01..=1.0)
+                                .speed(0.01)
+                                .suffix("× auto-fit"),
+                        )
+                        .changed();
+                    ui.end_row();
+
+                    ui.label("Zoom in to");
+                    changed |= ui
+                        .add(
+                            DragValue::new(&mut limits.max_zoom)
+                                .range(1.0..=100_000.0)
+                                .speed(10.0)
+                                .suffix("× auto-fit"),
+                        )
+                        .changed();
+                    ui.end_row();
+
+                    ui.label("Keep visible");
+                    changed |= ui
+                        .add(
+                            DragValue::new(&mut limits.keep_visible)
+                                .range(1.0..=500.0)
+                                .suffix(" px"),
+                        )
+                        .changed();
+                    ui.end_row();
+                });
+        });
+        self.view_limits_changed |= changed;
+    }
+
+    fn show_key_bindings(&mut self, ui: &mut egui::Ui) {
+        Grid::new("preferences_key_bindings")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for command in ViewCommand::ALL {
+                    ui.label(command.label());
+                    if self.capturing == Some(command) {
+                        ui.label("Press a key... (Esc cancels)");
+                    } else {
+                        let shortcuts: Vec<String> = self
+                            .bindings
+                            .shortcuts(command)
+                            .iter()
+                            .map(|shortcut| ui.ctx().format_shortcut(shortcut))
+                            .collect();
+                        if shortcuts.is_empty() {
+                            ui.weak("Unbound");
+                        } else {
+                            ui.label(shortcuts.join(", "));
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Change").clicked() {
+                            self.capturing = Some(command);
+                        }
+                        if ui
+                            .small_button("✖")
+                            .on_hover_text("Remove shortcut")
+                            .clicked()
+                        {
+                            self.bindings.clear_binding(command);
+                            self.bindings_changed = true;
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// Take the first key press of this frame as the new shortcut of `command`
+    fn capture_shortcut(&mut self, ctx: &Context, command: ViewCommand) {
+        let pressed = ctx.input_mut(|i| {
+            let pressed = i.events.iter().find_map(|event| match event {
+                Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some(KeyboardShortcut::new(*modifiers, *key)),
+                _ => None,
+            });
+            // Keep the key from also triggering its old command
+            if pressed.is_some() {
+                i.events.retain(|event| !matches!(event, Event::Key { .. }));
+            }
+            pressed
+        });
+
+        match pressed {
+            Some(shortcut) if shortcut.logical_key == egui::Key::Escape => {
+                self.capturing = None;
+            }
+            Some(shortcut) => {
+                self.bindings.set_binding(command, shortcut);
+                self.bindings_changed = true;
+                self.capturing = None;
+            }
+            None => {}
+        }
+    }
+}
+
+impl Default for PreferencesWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferences_window() {
+        let mut window = PreferencesWindow::new();
+        assert!(!window.is_open());
+        assert!(window.take_bindings_change().is_none());
+
+        let mut bindings = InputBindings::default();
+        bindings.scroll_behavior = ScrollBehavior::Zoom;
+        window.set_input_bindings(bindings.clone());
+        assert_eq!(window.take_bindings_change(), Some(bindings));
+        assert!(window.take_bindings_change().is_none());
+
+        assert!(window.take_log_verbosity_change().is_none());
+        window.set_log_verbosity(LogVerbosity::Debug);
+        assert_eq!(
+            window.take_log_verbosity_change(),
+            Some(LogVerbosity::Debug)
+        );
+        assert_eq!(window.get_log_verbosity(), LogVerbosity::Debug);
+        assert!(window.take_log_verbosity_change().is_none());
+
+        assert!(window.take_view_limits_change().is_none());
+        let limits = ViewLimits {
+            min_zoom: 0.5,
+            ..ViewLimits::default()
+        };
+        window.set_view_limits(limits);
+        assert_eq!(window.take_view_limits_change(), Some(limits));
+        assert!(window.take_view_limits_change().is_none());
+
+        assert_eq!(window.get_bond_options(), &BondOptions::default());
+        window.set_bond_options(BondOptions::new().with_gap(0.5));
+        assert_eq!(window.get_bond_options().gap, 0.5);
+
+        assert_eq!(window.get_duplicate_names(), DuplicateNamePolicy::Rename);
+        window.set_duplicate_names(DuplicateNamePolicy::Error);
+        assert_eq!(window.get_duplicate_names(), DuplicateNamePolicy::Error);
+    }
+}
@@ -0,0 +1,338 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{LintConfig, LintFinding, LintRule, ProblemTarget, ProcessStack, Severity};
+use crate::renderer::{WidthDistortion, DEFAULT_MAX_WIDTH_DISTORTION};
+use crate::utils::AnalysisRunner;
+use egui::{CollapsingHeader, Color32, Context, DragValue, Grid, RichText, ScrollArea, Window};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Name of the lint pass in the analysis runner
+const LINT_ANALYSIS: &str = "lint";
+
+/// Parse diagnostics and lint findings of the loaded stack
+///
+/// Linting runs on a background thread, so a large stack does not stall the UI.
+pub struct ProblemsWindow {
+    open: bool,
+    lint_config: LintConfig,
+    runner: AnalysisRunner<Vec<LintFinding>>,
+    stack: Option<Arc<ProcessStack>>,
+    findings: Vec<LintFinding>,
+    /// Whether the findings are out of date with the stack or the lint config
+    stale: bool,
+    /// Factor beyond which drawn conductor widths are reported as misleading
+    max_width_distortion: f32,
+    /// Conductors the current view draws out of proportion with their WMIN
+    width_distortions: Vec<WidthDistortion>,
+}
+
+impl ProblemsWindow {
+    pub fn new() -> Self {
+        let lint_config = LintConfig::new();
+        let mut runner = AnalysisRunner::new(1);
+        register_lint(&mut runner, &lint_config);
+        Self {
+            open: false,
+            lint_config,
+            runner,
+            stack: None,
+            findings: Vec::new(),
+            stale: false,
+            max_width_distortion: DEFAULT_MAX_WIDTH_DISTORTION,
+            width_distortions: Vec::new(),
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn get_lint_config(&self) -> &LintConfig {
+        &self.lint_config
+    }
+
+    pub fn set_lint_config(&mut self, config: LintConfig) {
+        self.lint_config = config;
+        register_lint(&mut self.runner, &self.lint_config);
+        self.stale = true;
+    }
+
+    /// Stack to check, shared with the lint thread
+    pub fn set_stack(&mut self, stack: Option<Arc<ProcessStack>>) {
+        self.stack = stack;
+        self.findings.clear();
+        self.stale = true;
+    }
+
+    pub fn get_max_width_distortion(&self) -> f32 {
+        self.max_width_distortion
+    }
+
+    pub fn set_max_width_distortion(&mut self, factor: f32) {
+        self.max_width_distortion = factor.max(1.0);
+    }
+
+    /// Conductors drawn out of proportion, checked against the view as it is drawn
+    pub fn set_width_distortions(&mut self, distortions: Vec<WidthDistortion>) {
+        self.width_distortions = distortions;
+    }
+
+    pub fn get_width_distortions(&self) -> &[WidthDistortion] {
+        &self.width_distortions
+    }
+
+    /// Lint findings of the latest finished check
+    pub fn get_findings(&self) -> &[LintFinding] {
+        &self.findings
+    }
+
+    /// Whether a check is still running
+    pub fn is_checking(&self) -> bool {
+        self.runner.is_busy()
+    }
+
+    /// Collect finished checks and start one if the findings are stale
+    fn update_findings(&mut self) {
+        for result in self.runner.poll() {
+            match result.output {
+                Ok(findings) => self.findings = findings,
+                Err(panic) => log::error!("Lint check failed: {}", panic.message),
+            }
+        }
+        if self.stale {
+            if let Some(stack) = &self.stack {
+                self.runner.submit(Arc::clone(stack));
+            }
+            self.stale = false;
+        }
+    }
+
+    /// Number of diagnostics and enabled lint findings for `stack`
+    pub fn problem_count(&self, stack: &ProcessStack) -> usize {
+        stack.diagnostics.len() + stack.lint(&self.lint_config).len()
+    }
+
+    /// Show the window, returning the target of the problem whose "Go to" link was clicked
+    pub fn show(&mut self, ctx: &Context) -> Option<ProblemTarget> {
+        if !self.open {
+            return None;
+        }
+
+        self.update_findings();
+        if self.is_checking() {
+            ctx.request_repaint_after(Duration::from_millis(50));
+        }
+
+        let mut jump_to = None;
+        let mut open = self.open;
+        Window::new("Problems")
+            .open(&mut open)
+            .default_size([560.0, 360.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.show_rules(ui);
+                ui.separator();
+
+                let Some(stack) = self.stack.as_deref() else {
+                    ui.label("Load a stack to check it for problems.");
+                    return;
+                };
+
+                let checking = self.runner.is_busy();
+                if checking {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Checking...");
+                    });
+                }
+                let findings = &self.findings;
+                let distortions = &self.width_distortions;
+                if stack.diagnostics.is_empty() && findings.is_empty() && distortions.is_empty() {
+                    if !checking {
+                        ui.label("No problems found.");
+                    }
+                    return;
+                }
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    Grid::new("problems_list")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Severity");
+                            ui.strong("Where");
+                            ui.strong("Message");
+                            ui.end_row();
+
+                            for diagnostic in &stack.diagnostics {
+                                severity_label(ui, diagnostic.severity);
+                                let target = diagnostic.target(stack);
+                                if target_link(ui, &target) {
+                                    jump_to = Some(target);
+                                }
+                                ui.label(&diagnostic.message);
+                                ui.end_row();
+                            }
+
+                            for finding in findings {
+                                severity_label(ui, finding.severity());
+                                let target = finding.target(stack);
+                                if target_link(ui, &target) {
+                                    jump_to = Some(target);
+                                }
+                                ui.label(format!("{} [{}]", finding.message, finding.rule.id()))
+                                    .on_hover_text(finding.rule.explanation());
+                                ui.end_row();
+                            }
+
+                            for distortion in distortions {
+                                severity_label(ui, Severity::Warning);
+                                let target = ProblemTarget {
+                                    line: stack.get_source_line(&distortion.layer),
+                                    layer: Some(distortion.layer.clone()),
+                                };
+                                if target_link(ui, &target) {
+                                    jump_to = Some(target);
+                                }
+                                ui.label(format!("{} [display]", distortion.message()))
+                                    .on_hover_text(
+                                        "The view does not draw widths to scale; \
+                                         compare WMIN values rather than the picture",
+                                    );
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+        jump_to
+    }
+
+    fn show_rules(&mut self, ui: &mut egui::Ui) {
+        CollapsingHeader::new("Lint Rules")
+            .default_open(false)
+            .show(ui, |ui| {
+                for rule in LintRule::ALL {
+                    let mut enabled = self.lint_config.is_enabled(rule);
+                    if ui
+                        .checkbox(
+                            &mut enabled,
+                            format!("{} ({})", rule.id(), rule.severity().label()),
+                        )
+                        .on_hover_text(rule.explanation())
+                        .changed()
+                    {
+                        let mut config = self.lint_config.clone();
+                        config.set_enabled(rule, enabled);
+                        self.set_lint_config(config);
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Flag drawn widths off by more than");
+                    ui.add(
+                        DragValue::new(&mut self.max_width_distortion)
+                            .range(1.0..=100.0)
+                            .speed(0.1)
+                            .suffix("x"),
+                    )
+                    .on_hover_text(
+                        "Compares the drawn width of each conductor to its WMIN, \
+                         relative to the other conductors",
+                    );
+                });
+            });
+    }
+}
+
+fn register_lint(runner: &mut AnalysisRunner<Vec<LintFinding>>, config: &LintConfig) {
+    let config = config.clone();
+    runner.register(LINT_ANALYSIS, move |stack: &ProcessStack| {
+        stack.lint(&config)
+    });
+}
+
+/// Link naming the layer and line of a problem, returning whether it was clicked
+fn target_link(ui: &mut egui::Ui, target: &ProblemTarget) -> bool {
+    let text = match (&target.layer, target.line) {
+        (Some(layer), Some(line)) => format!("{layer}, line {line}"),
+        (Some(layer), None) => layer.clone(),
+        (None, Some(line)) => format!("line {line}"),
+        (None, None) => {
+            ui.weak("-");
+            return false;
+        }
+    };
+    let hover = match (&target.layer, target.line) {
+        (Some(_), Some(_)) => "Go to: select the layer and show its source line",
+        (Some(_), None) => "Go to: select the layer",
+        _ => "Go to: show the source line",
+    };
+    ui.link(text).on_hover_text(hover).clicked()
+}
+
+fn severity_label(ui: &mut egui::Ui, severity: Severity) {
+    let color = match severity {
+        Severity::Info => Color32::LIGHT_BLUE,
+        Severity::Warning => Color32::from_rgb(255, 165, 0),
+        Severity::Error => Color32::RED,
+    };
+    ui.label(RichText::new(severity.label()).color(color));
+}
+
+impl Default for ProblemsWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Diagnostic, DielectricLayer, Layer, TechnologyInfo};
+
+    #[test]
+    fn test_problems_window() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide".to_string(),
+            1.0,
+            40.0,
+        )));
+        stack
+            .diagnostics
+            .push(Diagnostic::new(Severity::Warning, "Skipped line").with_line(3));
+
+        let mut window = ProblemsWindow::new();
+        assert!(!window.is_open());
+        assert_eq!(window.problem_count(&stack), 2);
+
+        let mut config = LintConfig::new();
+        config.set_enabled(LintRule::DielectricConstantRange, false);
+        window.set_lint_config(config.clone());
+        assert_eq!(window.get_lint_config(), &config);
+        assert_eq!(window.problem_count(&stack), 1);
+
+        // Findings arrive from the lint thread
+        window.set_lint_config(LintConfig::new());
+        window.set_stack(Some(Arc::new(stack)));
+        window.update_findings();
+        while window.is_checking() {
+            std::thread::sleep(Duration::from_millis(1));
+            window.update_findings();
+        }
+        assert_eq!(window.get_findings().len(), 1);
+
+        window.set_stack(None);
+        assert!(window.get_findings().is_empty());
+
+        window.set_max_width_distortion(0.5);
+        assert_eq!(window.get_max_width_distortion(), 1.0);
+    }
+}
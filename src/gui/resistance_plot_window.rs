@@ -1,27 +1,133 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+use crate::analysis::{
+    calculate_resistance, CrtSource, OperatingConditions, ResistanceResult, ResistivitySource,
+    WireGeometry,
+};
 use crate::data::{ConductorLayer, Layer, ProcessStack};
+use crate::utils::csv_field;
 use egui::{CollapsingHeader, ComboBox, Context, DragValue, Grid, Window};
-use egui_plot::{Line, Plot, PlotPoints};
+use egui_plot::{
+    HLine, Line, LineStyle, MarkerShape, Plot, PlotPoint, PlotPoints, Points, Text, VLine,
+};
+use poll_promise::Promise;
+use rfd::AsyncFileDialog;
+use std::path::{Path, PathBuf};
+
+/// Palette assigned to curves in the order they are generated
+const CURVE_COLORS: [egui::Color32; 8] = [
+    egui::Color32::BLUE,
+    egui::Color32::RED,
+    egui::Color32::GREEN,
+    egui::Color32::YELLOW,
+    egui::Color32::from_rgb(128, 0, 128),   // Purple
+    egui::Color32::from_rgb(255, 165, 0),   // Orange
+    egui::Color32::from_rgb(0, 255, 255),   // Cyan
+    egui::Color32::from_rgb(255, 192, 203), // Pink
+];
+
+/// Line style selectable per curve
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CurveLineStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl CurveLineStyle {
+    pub const ALL: [CurveLineStyle; 3] = [
+        CurveLineStyle::Solid,
+        CurveLineStyle::Dashed,
+        CurveLineStyle::Dotted,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CurveLineStyle::Solid => "Solid",
+            CurveLineStyle::Dashed => "Dashed",
+            CurveLineStyle::Dotted => "Dotted",
+        }
+    }
+
+    /// Plot style for a segment, switching pattern for extrapolated stretches
+    fn to_plot_style(self, extrapolated: bool) -> LineStyle {
+        match (self, extrapolated) {
+            (CurveLineStyle::Solid, false) => LineStyle::Solid,
+            (CurveLineStyle::Dashed, false) | (CurveLineStyle::Solid, true) => {
+                LineStyle::dashed_dense()
+            }
+            (CurveLineStyle::Dotted, false) | (CurveLineStyle::Dashed, true) => {
+                LineStyle::dotted_dense()
+            }
+            (CurveLineStyle::Dotted, true) => LineStyle::dotted_loose(),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct ResistanceCurve {
     pub name: String,
-    pub data_points: Vec<(f64, f64)>, // (temperature, resistance) pairs
+    pub data_points: Vec<(f64, f64)>, // (temperature or width, resistance) pairs
+    pub extrapolated: Vec<bool>,      // Per-point flag for values outside the lookup tables
+    pub wmin_marker: Option<(f64, f64)>, // (WMIN, resistance) for width sweeps
     pub color: egui::Color32,
+    pub visible: bool,
+    pub line_style: CurveLineStyle,
+    pub line_width: f32,
+}
+
+impl ResistanceCurve {
+    pub fn new(name: String, data_points: Vec<(f64, f64)>, color: egui::Color32) -> Self {
+        Self {
+            name,
+            data_points,
+            extrapolated: Vec::new(),
+            wmin_marker: None,
+            color,
+            visible: true,
+            line_style: CurveLineStyle::Solid,
+            line_width: 1.5,
+        }
+    }
+}
+
+/// A value read off a curve under the crosshair
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurveReadout {
+    pub curve_name: String,
+    pub x: f64,
+    pub resistance: f64,
+}
+
+/// Quantity swept along the x axis of the resistance plot
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SweepMode {
+    #[default]
+    Temperature,
+    Width,
 }
 
 pub struct ResistancePlotWindow {
     open: bool,
     // Input parameters
     selected_conductor: Option<String>,
+    /// Set when the user picks a conductor in this window
+    conductor_picked: bool,
     width: f64,             // Line width in micrometers
     length: f64,            // Line length in micrometers
     temperature_start: f64, // Start temperature for plot
     temperature_end: f64,   // End temperature for plot
     reference_temp: f64,    // Reference temperature (usually 25°C)
 
+    // Width sweep
+    sweep_mode: SweepMode,
+    width_start: f64,       // Start width for width sweep
+    width_end: f64,         // End width for width sweep
+    sweep_temperature: f64, // Fixed temperature for width sweep
+    log_width_axis: bool,
+
     // Multi-thickness plotting
     enable_multi_thickness: bool,
     thickness_values: Vec<f64>, // Additional thickness values to plot
@@ -29,13 +135,24 @@ pub struct ResistancePlotWindow {
     // Results
     calculated_resistance: Option<f64>,
     calculated_sheet_resistance: Option<f64>,
+    range_warnings: Vec<String>,
     curves: Vec<ResistanceCurve>,
     curves_generated: bool,
+    keep_previous_curves: bool, // Accumulate curves across calculations for comparison
     error_message: Option<String>,
 
     // Calculation details for display
     calculation_details: Option<String>,
 
+    // Crosshair readout
+    crosshair_enabled: bool,
+    snap_to_points: bool,
+    pinned_readouts: Vec<CurveReadout>,
+
+    // CSV export
+    export_dialog_promise: Option<Promise<Option<PathBuf>>>,
+    export_status: Option<String>,
+
     // Display settings
     plot_title: String,
     x_axis_label: String,
@@ -48,12 +165,20 @@ impl ResistancePlotWindow {
             open: false,
             // Input parameters
             selected_conductor: None,
+            conductor_picked: false,
             width: 0.1,               // Default 0.1 μm
             length: 100.0,            // Default 100 μm
             temperature_start: -40.0, // -40°C
             temperature_end: 150.0,   // 150°C
             reference_temp: 25.0,     // 25°C
 
+            // Width sweep
+            sweep_mode: SweepMode::Temperature,
+            width_start: 0.05,
+            width_end: 10.0,
+            sweep_temperature: 25.0,
+            log_width_axis: false,
+
             // Multi-thickness plotting
             enable_multi_thickness: false,
             thickness_values: vec![0.1, 0.2, 0.3, 0.5], // Default thickness values
@@ -61,11 +186,22 @@ impl ResistancePlotWindow {
             // Results
             calculated_resistance: None,
             calculated_sheet_resistance: None,
+            range_warnings: Vec::new(),
             curves: Vec::new(),
             curves_generated: false,
+            keep_previous_curves: false,
             error_message: None,
             calculation_details: None,
 
+            // Crosshair readout
+            crosshair_enabled: false,
+            snap_to_points: false,
+            pinned_readouts: Vec::new(),
+
+            // CSV export
+            export_dialog_promise: None,
+            export_status: None,
+
             // Display settings
             plot_title: "Resistance vs Temperature".to_string(),
             x_axis_label: "Temperature (°C)".to_string(),
@@ -78,11 +214,32 @@ impl ResistancePlotWindow {
         // Clear calculated values when layer changes
         self.calculated_resistance = None;
         self.calculated_sheet_resistance = None;
-        self.curves_generated = false;
-        self.curves.clear();
+        self.range_warnings.clear();
+        self.discard_curves();
         self.error_message = None;
     }
 
+    /// Set the line width in micrometers
+    pub fn set_width(&mut self, width: f64) {
+        self.width = width;
+        self.clear_results();
+    }
+
+    pub fn get_width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn get_selected_conductor_name(&self) -> Option<&String> {
+        self.selected_conductor.as_ref()
+    }
+
+    /// Return the conductor if the user picked one in this window since the last call
+    pub fn take_conductor_change(&mut self) -> Option<String> {
+        std::mem::take(&mut self.conductor_picked)
+            .then(|| self.selected_conductor.clone())
+            .flatten()
+    }
+
     pub fn set_open(&mut self, open: bool) {
         self.open = open;
     }
@@ -92,6 +249,16 @@ impl ResistancePlotWindow {
     }
 
     pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) {
+        // Check if the export dialog promise is ready
+        if let Some(promise) = &self.export_dialog_promise {
+            if let Some(result) = promise.ready() {
+                if let Some(path) = result.clone() {
+                    self.export_csv(&path);
+                }
+                self.export_dialog_promise = None;
+            }
+        }
+
         if !self.open {
             return;
         }
@@ -128,9 +295,9 @@ impl ResistancePlotWindow {
 
         // Plot display
         if self.curves_generated && !self.curves.is_empty() {
-            self.show_temperature_plot(ui);
+            self.show_resistance_plot(ui);
         } else {
-            ui.label("Calculate resistance first to generate temperature curves");
+            ui.label("Calculate resistance first to generate resistance curves");
         }
 
         // Error message display
@@ -169,14 +336,11 @@ impl ResistancePlotWindow {
                                                 )
                                                 .clicked()
                                             {
-                                                self.selected_conductor =
-                                                    Some(conductor.name.clone());
                                                 // Clear calculated values when layer changes
-                                                self.calculated_resistance = None;
-                                                self.calculated_sheet_resistance = None;
-                                                self.curves_generated = false;
-                                                self.curves.clear();
-                                                self.error_message = None;
+                                                self.set_selected_conductor(Some(
+                                                    conductor.name.clone(),
+                                                ));
+                                                self.conductor_picked = true;
                                             }
                                         }
                                     }
@@ -210,36 +374,123 @@ impl ResistancePlotWindow {
                         }
                         ui.end_row();
 
-                        // Temperature range for plot
-                        ui.label("Temperature Range:");
+                        // Sweep mode selection
+                        ui.label("Sweep:");
                         ui.horizontal(|ui| {
-                            ui.add(
-                                DragValue::new(&mut self.temperature_start)
-                                    .range(-100.0..=200.0)
-                                    .speed(1.0)
-                                    .suffix("°C"),
-                            );
-                            ui.label("to");
-                            ui.add(
-                                DragValue::new(&mut self.temperature_end)
-                                    .range(-100.0..=200.0)
-                                    .speed(1.0)
-                                    .suffix("°C"),
+                            let previous_mode = self.sweep_mode;
+                            ui.radio_value(
+                                &mut self.sweep_mode,
+                                SweepMode::Temperature,
+                                "Temperature",
                             );
+                            ui.radio_value(&mut self.sweep_mode, SweepMode::Width, "Width");
+                            if self.sweep_mode != previous_mode {
+                                // Curves of different sweeps cannot share an axis
+                                self.curves_generated = false;
+                                self.curves.clear();
+                                self.pinned_readouts.clear();
+                            }
                         });
                         ui.end_row();
 
+                        match self.sweep_mode {
+                            SweepMode::Temperature => {
+                                // Temperature range for plot
+                                ui.label("Temperature Range:");
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        DragValue::new(&mut self.temperature_start)
+                                            .range(-100.0..=200.0)
+                                            .speed(1.0)
+                                            .suffix("°C"),
+                                    );
+                                    ui.label("to");
+                                    ui.add(
+                                        DragValue::new(&mut self.temperature_end)
+                                            .range(-100.0..=200.0)
+                                            .speed(1.0)
+                                            .suffix("°C"),
+                                    );
+                                });
+                                ui.end_row();
+                            }
+                            SweepMode::Width => {
+                                // Width range for plot
+                                ui.label("Width Range:");
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        DragValue::new(&mut self.width_start)
+                                            .range(0.001..=1000.0)
+                                            .speed(0.01)
+                                            .suffix(" μm"),
+                                    );
+                                    ui.label("to");
+                                    ui.add(
+                                        DragValue::new(&mut self.width_end)
+                                            .range(0.001..=1000.0)
+                                            .speed(0.01)
+                                            .suffix(" μm"),
+                                    );
+
+                                    let table_widths = stack
+                                        .and_then(|stack| self.get_selected_conductor(stack))
+                                        .and_then(|c| c.rho_vs_si_width_thickness.as_ref())
+                                        .map(|table| table.widths.clone());
+                                    if let Some(widths) = table_widths {
+                                        if ui
+                                            .button("Table range")
+                                            .on_hover_text(
+                                                "Use the width axis of the RHO_VS_SI_WIDTH_AND_THICKNESS table",
+                                            )
+                                            .clicked()
+                                        {
+                                            self.set_width_range_from_table(&widths);
+                                        }
+                                    }
+                                });
+                                ui.end_row();
+
+                                ui.label("Temperature:");
+                                ui.add(
+                                    DragValue::new(&mut self.sweep_temperature)
+                                        .range(-100.0..=200.0)
+                                        .speed(1.0)
+                                        .suffix("°C"),
+                                );
+                                ui.end_row();
+
+                                ui.label("Width axis:");
+                                ui.checkbox(&mut self.log_width_axis, "Logarithmic");
+                                ui.end_row();
+                            }
+                        }
+
                         // Multi-thickness option
                         ui.label("Multi-thickness plot:");
                         ui.checkbox(&mut self.enable_multi_thickness, "Enable");
                         ui.end_row();
 
+                        if self.enable_multi_thickness {
+                            ui.label("Thicknesses:");
+                            let table_thicknesses = stack
+                                .and_then(|stack| self.get_selected_conductor(stack))
+                                .and_then(|c| c.rho_vs_si_width_thickness.as_ref())
+                                .map(|table| table.spacings.clone());
+                            self.show_thickness_list(ui, table_thicknesses.as_deref());
+                            ui.end_row();
+                        }
+
+                        ui.label("Previous curves:");
+                        ui.checkbox(&mut self.keep_previous_curves, "Keep for comparison")
+                            .on_hover_text("Add new curves to the plot instead of replacing it");
+                        ui.end_row();
+
                         // Calculate button
                         ui.label("");
                         if ui.button("Calculate & Plot").clicked() {
                             if let Some(stack) = stack {
                                 self.calculate_resistance(stack);
-                                self.generate_temperature_curves(stack);
+                                self.generate_curves(stack);
                             }
                         }
                         ui.end_row();
@@ -247,6 +498,93 @@ impl ResistancePlotWindow {
             });
     }
 
+    fn show_thickness_list(&mut self, ui: &mut egui::Ui, table_thicknesses: Option<&[f64]>) {
+        ui.vertical(|ui| {
+            let mut remove_index = None;
+            let mut changed = false;
+
+            for (index, thickness) in self.thickness_values.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .add(
+                            DragValue::new(thickness)
+                                .range(0.001..=100.0)
+                                .speed(0.01)
+                                .suffix(" μm"),
+                        )
+                        .changed();
+                    if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                        remove_index = Some(index);
+                    }
+                });
+            }
+
+            if let Some(index) = remove_index {
+                self.remove_thickness_value(index);
+                changed = true;
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("Add").clicked() {
+                    self.add_thickness_value();
+                    changed = true;
+                }
+
+                if let Some(thicknesses) = table_thicknesses {
+                    if ui
+                        .button("From table")
+                        .on_hover_text(
+                            "Use the thickness axis of the RHO_VS_SI_WIDTH_AND_THICKNESS table",
+                        )
+                        .clicked()
+                    {
+                        self.set_thickness_values(thicknesses);
+                        changed = true;
+                    }
+                }
+            });
+
+            if changed {
+                self.clear_results();
+            }
+        });
+    }
+
+    pub fn get_thickness_values(&self) -> &[f64] {
+        &self.thickness_values
+    }
+
+    /// Replace the thickness list with the given values, sorted and deduplicated
+    pub fn set_thickness_values(&mut self, values: &[f64]) {
+        let mut values: Vec<f64> = values
+            .iter()
+            .copied()
+            .filter(|t| t.is_finite() && *t > 0.0)
+            .collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        values.dedup();
+        if !values.is_empty() {
+            self.thickness_values = values;
+        }
+    }
+
+    /// Append a thickness continuing the spacing of the last two values
+    fn add_thickness_value(&mut self) {
+        let next = match self.thickness_values.as_slice() {
+            [] => 0.1,
+            [only] => only * 2.0,
+            [.., previous, last] => last + (last - previous).abs().max(0.01),
+        };
+        self.thickness_values.push(next);
+    }
+
+    /// Remove a thickness, always keeping at least one entry
+    fn remove_thickness_value(&mut self, index: usize) {
+        if self.thickness_values.len() > 1 && index < self.thickness_values.len() {
+            self.thickness_values.remove(index);
+        }
+    }
+
     fn show_results(&mut self, ui: &mut egui::Ui) {
         CollapsingHeader::new("Results")
             .default_open(true)
@@ -273,6 +611,13 @@ impl ResistancePlotWindow {
                         }
                         ui.end_row();
                     });
+
+                for warning in &self.range_warnings {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 165, 0),
+                        format!("⚠ Extrapolated: {warning}"),
+                    );
+                }
             });
     }
 
@@ -310,47 +655,268 @@ impl ResistancePlotWindow {
         }
     }
 
-    fn show_temperature_plot(&mut self, ui: &mut egui::Ui) {
-        CollapsingHeader::new("Temperature vs Resistance Plot")
+    fn show_resistance_plot(&mut self, ui: &mut egui::Ui) {
+        let header = match self.sweep_mode {
+            SweepMode::Temperature => "Temperature vs Resistance Plot",
+            SweepMode::Width => "Width vs Resistance Plot",
+        };
+
+        CollapsingHeader::new(header)
+            .id_salt("resistance_plot")
             .default_open(true)
             .show(ui, |ui| {
-                // Update plot title with current parameters
+                // Update plot title and axis with current parameters
                 if let Some(ref conductor_name) = self.selected_conductor {
-                    self.plot_title = format!(
-                        "Resistance vs Temperature - {} (W={:.3}μm, L={:.1}μm)",
-                        conductor_name, self.width, self.length
-                    );
+                    self.plot_title = match self.sweep_mode {
+                        SweepMode::Temperature => format!(
+                            "Resistance vs Temperature - {} (W={:.3}μm, L={:.1}μm)",
+                            conductor_name, self.width, self.length
+                        ),
+                        SweepMode::Width => format!(
+                            "Resistance vs Width - {} (T={:.1}°C, L={:.1}μm)",
+                            conductor_name, self.sweep_temperature, self.length
+                        ),
+                    };
                 }
+                self.x_axis_label = match self.sweep_mode {
+                    SweepMode::Temperature => "Temperature (°C)".to_string(),
+                    SweepMode::Width => "Width (μm)".to_string(),
+                };
+
+                // Widths are plotted as log10 values when the log axis is enabled
+                let log_x = self.is_log_x();
+                let to_plot_x = |x: f64| if log_x { x.log10() } else { x };
+                let from_plot_x = |x: f64| if log_x { 10f64.powf(x) } else { x };
+                let x_unit = match self.sweep_mode {
+                    SweepMode::Temperature => "°C",
+                    SweepMode::Width => "μm",
+                };
 
-                // Create the plot
-                Plot::new("resistance_temperature_plot")
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.crosshair_enabled, "Crosshair")
+                        .on_hover_text("Read values off the curves; click to pin a marker");
+                    ui.add_enabled(
+                        self.crosshair_enabled,
+                        egui::Checkbox::new(&mut self.snap_to_points, "Snap to points"),
+                    );
+                });
+
+                let mut plot = Plot::new(("resistance_plot", self.sweep_mode as u8, log_x))
                     .view_aspect(2.0)
                     .legend(egui_plot::Legend::default())
                     .x_axis_label(&self.x_axis_label)
-                    .y_axis_label(&self.y_axis_label)
-                    .show(ui, |plot_ui| {
-                        for curve in &self.curves {
-                            if !curve.data_points.is_empty() {
-                                let points: PlotPoints = curve
-                                    .data_points
-                                    .iter()
-                                    .map(|(temp, resistance)| [*temp, *resistance])
-                                    .collect();
-
-                                let line = Line::new(&curve.name, points).color(curve.color);
-
-                                plot_ui.line(line);
-                            }
+                    .y_axis_label(&self.y_axis_label);
+                if log_x {
+                    plot =
+                        plot.x_axis_formatter(|mark, _range| format!("{}", 10f64.powf(mark.value)));
+                }
+
+                let plot_response = plot.show(ui, |plot_ui| {
+                    let visible_curves = self.curves.iter().filter(|curve| curve.visible);
+
+                    for curve in visible_curves.clone() {
+                        // Extrapolated stretches switch pattern under the same legend entry
+                        for (extrapolated, segment) in split_extrapolated_segments(curve) {
+                            let points: PlotPoints = segment
+                                .iter()
+                                .map(|(x, resistance)| [to_plot_x(*x), *resistance])
+                                .collect();
+
+                            let line = Line::new(&curve.name, points)
+                                .color(curve.color)
+                                .width(curve.line_width)
+                                .style(curve.line_style.to_plot_style(extrapolated));
+
+                            plot_ui.line(line);
                         }
-                    });
+                    }
+
+                    let wmin_markers: Vec<(f64, f64)> = visible_curves
+                        .filter_map(|curve| curve.wmin_marker)
+                        .collect();
+                    let mut wmin_values: Vec<f64> = wmin_markers.iter().map(|(x, _)| *x).collect();
+                    wmin_values.sort_by(|a, b| a.total_cmp(b));
+                    wmin_values.dedup();
+                    for wmin in wmin_values {
+                        plot_ui.vline(
+                            VLine::new("WMIN", to_plot_x(wmin))
+                                .color(egui::Color32::GRAY)
+                                .style(LineStyle::dashed_loose()),
+                        );
+                    }
+                    if !wmin_markers.is_empty() {
+                        let points: PlotPoints = wmin_markers
+                            .iter()
+                            .map(|(x, resistance)| [to_plot_x(*x), *resistance])
+                            .collect();
+                        plot_ui.points(
+                            Points::new("WMIN", points)
+                                .shape(MarkerShape::Diamond)
+                                .radius(5.0)
+                                .color(egui::Color32::GRAY),
+                        );
+                    }
+
+                    if !self.pinned_readouts.is_empty() {
+                        let points: PlotPoints = self
+                            .pinned_readouts
+                            .iter()
+                            .map(|pin| [to_plot_x(pin.x), pin.resistance])
+                            .collect();
+                        plot_ui.points(
+                            Points::new("Pinned", points)
+                                .shape(MarkerShape::Circle)
+                                .radius(4.0)
+                                .color(egui::Color32::WHITE),
+                        );
+                    }
+
+                    if !self.crosshair_enabled {
+                        return None;
+                    }
+
+                    let pointer = plot_ui.pointer_coordinate()?;
+                    let readout = find_curve_readout(
+                        &self.curves,
+                        from_plot_x(pointer.x),
+                        pointer.y,
+                        self.snap_to_points,
+                    )?;
+
+                    let position = PlotPoint::new(to_plot_x(readout.x), readout.resistance);
+                    plot_ui.vline(VLine::new("", position.x).color(egui::Color32::LIGHT_GRAY));
+                    plot_ui.hline(HLine::new("", position.y).color(egui::Color32::LIGHT_GRAY));
+                    plot_ui.text(
+                        Text::new(
+                            "",
+                            position,
+                            format!("{:.4} {x_unit}\n{:.6} Ω", readout.x, readout.resistance),
+                        )
+                        .anchor(egui::Align2::LEFT_BOTTOM),
+                    );
+
+                    Some(readout)
+                });
+
+                if let Some(readout) = plot_response.inner {
+                    if plot_response.response.clicked() {
+                        self.pinned_readouts.push(readout);
+                    }
+                }
+
+                self.show_pinned_readouts(ui, x_unit);
+
+                ui.horizontal(|ui| {
+                    let exporting = self.export_dialog_promise.is_some();
+                    if ui
+                        .add_enabled(!exporting, egui::Button::new("Export data"))
+                        .on_hover_text("Save the curve points to a CSV file")
+                        .clicked()
+                    {
+                        self.open_export_dialog();
+                    }
+                    if let Some(ref status) = self.export_status {
+                        ui.label(status);
+                    }
+                });
 
                 ui.separator();
 
+                // Per-curve visibility and styling
+                self.show_curve_list(ui);
+
                 // Show curve statistics
                 self.show_curve_statistics(ui);
             });
     }
 
+    fn show_pinned_readouts(&mut self, ui: &mut egui::Ui, x_unit: &str) {
+        if self.pinned_readouts.is_empty() {
+            return;
+        }
+
+        CollapsingHeader::new("Pinned Markers")
+            .default_open(true)
+            .show(ui, |ui| {
+                let mut remove_index = None;
+
+                Grid::new("pinned_readouts")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, pin) in self.pinned_readouts.iter().enumerate() {
+                            ui.label(&pin.curve_name);
+                            ui.monospace(format!("{:.4} {x_unit}", pin.x));
+                            ui.monospace(format!("{:.6} Ω", pin.resistance));
+                            if ui
+                                .small_button("✖")
+                                .on_hover_text("Remove marker")
+                                .clicked()
+                            {
+                                remove_index = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(index) = remove_index {
+                    self.pinned_readouts.remove(index);
+                }
+
+                if ui.button("Clear markers").clicked() {
+                    self.pinned_readouts.clear();
+                }
+            });
+    }
+
+    fn show_curve_list(&mut self, ui: &mut egui::Ui) {
+        CollapsingHeader::new("Curves")
+            .default_open(true)
+            .show(ui, |ui| {
+                let mut remove_index = None;
+
+                for (index, curve) in self.curves.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut curve.visible, "")
+                            .on_hover_text("Show or hide this curve");
+                        ui.color_edit_button_srgba(&mut curve.color);
+                        ComboBox::from_id_salt(("curve_line_style", index))
+                            .selected_text(curve.line_style.label())
+                            .width(70.0)
+                            .show_ui(ui, |ui| {
+                                for style in CurveLineStyle::ALL {
+                                    ui.selectable_value(
+                                        &mut curve.line_style,
+                                        style,
+                                        style.label(),
+                                    );
+                                }
+                            });
+                        ui.add(
+                            DragValue::new(&mut curve.line_width)
+                                .range(0.5..=5.0)
+                                .speed(0.1)
+                                .suffix(" px"),
+                        );
+                        if ui.small_button("✖").on_hover_text("Remove curve").clicked() {
+                            remove_index = Some(index);
+                        }
+                        ui.label(&curve.name);
+                    });
+                }
+
+                if let Some(index) = remove_index {
+                    self.remove_curve(index);
+                }
+
+                if ui.button("Clear all").clicked() {
+                    self.curves.clear();
+                    self.curves_generated = false;
+                    self.pinned_readouts.clear();
+                }
+            });
+    }
+
     fn show_curve_statistics(&self, ui: &mut egui::Ui) {
         if self.curves.is_empty() {
             return;
@@ -383,13 +949,185 @@ impl ResistancePlotWindow {
         });
     }
 
+    /// Build CSV text with a sweep column followed by one resistance column per visible curve
+    pub fn curves_to_csv(&self) -> String {
+        let curves: Vec<&ResistanceCurve> =
+            self.curves.iter().filter(|curve| curve.visible).collect();
+
+        let mut csv = String::from(match self.sweep_mode {
+            SweepMode::Temperature => "temperature_c",
+            SweepMode::Width => "width_um",
+        });
+        for curve in &curves {
+            csv.push(',');
+            csv.push_str(&csv_field(&curve.name));
+        }
+        csv.push('\n');
+
+        // All curves share the same sweep grid, but a curve may be missing
+        // points where the resistance could not be calculated
+        let temperatures = curves
+            .iter()
+            .max_by_key(|curve| curve.data_points.len())
+            .map(|curve| {
+                curve
+                    .data_points
+                    .iter()
+                    .map(|(t, _)| *t)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for temperature in temperatures {
+            csv.push_str(&temperature.to_string());
+            for curve in &curves {
+                csv.push(',');
+                if let Some((_, resistance)) =
+                    curve.data_points.iter().find(|(t, _)| *t == temperature)
+                {
+                    csv.push_str(&resistance.to_string());
+                }
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    fn open_export_dialog(&mut self) {
+        let file_name = format!(
+            "resistance_{}.csv",
+            self.selected_conductor.as_deref().unwrap_or("curves")
+        );
+        let task = AsyncFileDialog::new()
+            .add_filter("CSV Files", &["csv"])
+            .add_filter("All Files", &["*"])
+            .set_title("Export Resistance Data")
+            .set_file_name(file_name)
+            .save_file();
+
+        let promise = Promise::spawn_thread("export_dialog", move || {
+            pollster::block_on(async move { task.await.map(|handle| handle.path().to_path_buf()) })
+        });
+
+        self.export_dialog_promise = Some(promise);
+    }
+
+    fn export_csv(&mut self, path: &Path) {
+        match std::fs::write(path, self.curves_to_csv()) {
+            Ok(()) => {
+                self.export_status = Some(format!("Exported to {}", path.display()));
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.export_status = None;
+                self.error_message = Some(format!("Failed to export data: {e}"));
+            }
+        }
+    }
+
     fn clear_results(&mut self) {
         self.calculated_resistance = None;
         self.calculated_sheet_resistance = None;
-        self.curves_generated = false;
-        self.curves.clear();
+        self.range_warnings.clear();
+        self.discard_curves();
         self.error_message = None;
         self.calculation_details = None;
+        self.export_status = None;
+    }
+
+    /// Drop generated curves unless they are kept for comparison
+    fn discard_curves(&mut self) {
+        if !self.keep_previous_curves {
+            self.curves_generated = false;
+            self.curves.clear();
+        }
+    }
+
+    fn remove_curve(&mut self, index: usize) {
+        if index < self.curves.len() {
+            self.curves.remove(index);
+        }
+        self.curves_generated = !self.curves.is_empty();
+    }
+
+    /// Add a curve, refreshing the data of an existing curve with the same name
+    ///
+    /// Refreshed curves keep their visibility and styling.
+    fn add_curve(&mut self, curve: ResistanceCurve) {
+        if let Some(existing) = self.curves.iter_mut().find(|c| c.name == curve.name) {
+            existing.data_points = curve.data_points;
+            existing.extrapolated = curve.extrapolated;
+            existing.wmin_marker = curve.wmin_marker;
+        } else {
+            self.curves.push(curve);
+        }
+    }
+
+    fn is_log_x(&self) -> bool {
+        self.sweep_mode == SweepMode::Width && self.log_width_axis
+    }
+
+    fn set_width_range_from_table(&mut self, widths: &[f64]) {
+        let min = widths.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = widths.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if min.is_finite() && max > min {
+            self.width_start = min;
+            self.width_end = max;
+        }
+    }
+
+    /// Sample positions along the x axis for the current sweep mode
+    fn sweep_values(&self, num_points: usize) -> Vec<f64> {
+        let (start, end) = match self.sweep_mode {
+            SweepMode::Temperature => (self.temperature_start, self.temperature_end),
+            SweepMode::Width => (self.width_start, self.width_end),
+        };
+        let step = |i: usize| i as f64 / (num_points as f64 - 1.0);
+
+        if self.is_log_x() && start > 0.0 && end > 0.0 {
+            let (log_start, log_end) = (start.log10(), end.log10());
+            (0..num_points)
+                .map(|i| 10f64.powf(log_start + step(i) * (log_end - log_start)))
+                .collect()
+        } else {
+            (0..num_points)
+                .map(|i| start + step(i) * (end - start))
+                .collect()
+        }
+    }
+
+    /// Resistance at sweep value `x` of a wire `thickness` thick
+    fn evaluate_resistance(
+        &self,
+        conductor: &ConductorLayer,
+        thickness: f64,
+        x: f64,
+    ) -> Option<ResistanceResult> {
+        let (wire, conditions) = match self.sweep_mode {
+            SweepMode::Temperature => (
+                WireGeometry::new(self.width, self.length),
+                OperatingConditions::new(x, self.reference_temp),
+            ),
+            SweepMode::Width => (
+                WireGeometry::new(x, self.length),
+                OperatingConditions::new(self.sweep_temperature, self.reference_temp),
+            ),
+        };
+        calculate_resistance(conductor, &wire.with_thickness(thickness), &conditions).ok()
+    }
+
+    fn curve_name(&self, conductor_name: &str, thickness: f64) -> String {
+        match self.sweep_mode {
+            SweepMode::Temperature => format!(
+                "{} (W={:.3}μm, T={:.3}μm)",
+                conductor_name, self.width, thickness
+            ),
+            SweepMode::Width => format!(
+                "{} (@{:.1}°C, T={:.3}μm)",
+                conductor_name, self.sweep_temperature, thickness
+            ),
+        }
     }
 
     fn calculate_resistance(&mut self, stack: &ProcessStack) {
@@ -397,6 +1135,7 @@ impl ResistancePlotWindow {
         self.calculated_resistance = None;
         self.calculated_sheet_resistance = None;
         self.calculation_details = None;
+        self.range_warnings.clear();
 
         let conductor = match self.get_selected_conductor(stack) {
             Some(c) => c,
@@ -417,58 +1156,74 @@ impl ResistancePlotWindow {
             self.reference_temp
         ));
 
-        // Check available data
-        if conductor.rho_vs_si_width_thickness.is_some() {
-            details.push_str("Data source: RHO_VS_SI_WIDTH_AND_THICKNESS table\n");
-        } else if conductor.rho_vs_width_spacing.is_some() {
-            details.push_str("Data source: RHO_VS_WIDTH_SPACING table\n");
-        } else if conductor.electrical_props.rpsq.is_some() {
-            details.push_str("Data source: Fixed RPSQ value\n");
-        }
-
-        if conductor.crt_vs_si_width.is_some() {
-            details.push_str("CRT source: CRT_VS_SI_WIDTH table (interpolated)\n");
-        } else {
-            details.push_str("CRT source: Fixed CRT1, CRT2 values\n");
-        }
-
         // Calculate resistance at reference temperature
-        match conductor.calculate_resistance(
-            self.width,
-            self.length,
-            self.reference_temp,
-            self.reference_temp,
+        match calculate_resistance(
+            conductor,
+            &WireGeometry::new(self.width, self.length),
+            &OperatingConditions::at_reference(self.reference_temp),
         ) {
-            Some(resistance) => {
-                self.calculated_resistance = Some(resistance);
-                // Calculate sheet resistance: Rsq = R * (W/L)
-                self.calculated_sheet_resistance = Some(resistance * self.width / self.length);
+            Ok(result) => {
+                match result.resistivity_source {
+                    ResistivitySource::Rpsq => {
+                        details.push_str("Data source: Fixed RPSQ value\n");
+                        if let Some(rho) = conductor.get_effective_resistivity() {
+                            details.push_str(&format!(
+                                "Effective resistivity: {rho:.6} (derived, RPSQ × thickness)\n"
+                            ));
+                        }
+                    }
+                    source => {
+                        details.push_str(&format!("Data source: {} table\n", source.keyword()));
+                    }
+                }
+                match result.crt_source {
+                    CrtSource::CrtVsSiWidth => {
+                        details.push_str("CRT source: CRT_VS_SI_WIDTH table (interpolated)\n")
+                    }
+                    CrtSource::Fixed => details.push_str("CRT source: Fixed CRT1, CRT2 values\n"),
+                }
 
-                details.push_str(&format!("Calculated resistance: {resistance:.6e} Ω\n"));
+                self.calculated_resistance = Some(result.resistance);
+                self.calculated_sheet_resistance = Some(result.sheet_resistance);
+
+                details.push_str(&format!(
+                    "Calculated resistance: {:.6e} Ω\n",
+                    result.resistance
+                ));
                 details.push_str(&format!(
                     "Sheet resistance: {:.6e} Ω/sq",
-                    resistance * self.width / self.length
+                    result.sheet_resistance
                 ));
+
+                // Lookups outside the table axes are clamped to the nearest edge
+                for warning in &result.range_warnings {
+                    details.push_str(&format!("\nExtrapolated: {warning}"));
+                }
+                self.range_warnings = result
+                    .range_warnings
+                    .iter()
+                    .map(|w| w.to_string())
+                    .collect();
                 self.calculation_details = Some(details);
             }
-            None => {
-                self.error_message =
-                    Some("Cannot calculate resistance - missing electrical properties".to_string());
+            Err(e) => {
+                self.error_message = Some(format!("Cannot calculate resistance: {e}"));
             }
         }
     }
 
-    fn generate_temperature_curves(&mut self, stack: &ProcessStack) {
-        self.curves.clear();
+    fn generate_curves(&mut self, stack: &ProcessStack) {
+        if !self.keep_previous_curves {
+            self.curves.clear();
+        }
 
         let conductor = match self.get_selected_conductor(stack) {
             Some(c) => c,
             None => return,
         };
 
-        // Generate points from temperature_start to temperature_end
-        let num_points = 100;
-        let temp_step = (self.temperature_end - self.temperature_start) / (num_points as f64 - 1.0);
+        // Generate points across the sweep range
+        let sweep_values = self.sweep_values(100);
 
         let conductor_name = self
             .selected_conductor
@@ -476,78 +1231,42 @@ impl ResistancePlotWindow {
             .unwrap_or(&"Unknown".to_string())
             .clone();
 
-        // Define colors for different thickness curves
-        let colors = [
-            egui::Color32::BLUE,
-            egui::Color32::RED,
-            egui::Color32::GREEN,
-            egui::Color32::YELLOW,
-            egui::Color32::from_rgb(128, 0, 128),   // Purple
-            egui::Color32::from_rgb(255, 165, 0),   // Orange
-            egui::Color32::from_rgb(0, 255, 255),   // Cyan
-            egui::Color32::from_rgb(255, 192, 203), // Pink
-        ];
-
-        if self.enable_multi_thickness && conductor.rho_vs_si_width_thickness.is_some() {
-            // Generate curves for multiple thickness values
-            for (thickness_idx, &thickness) in self.thickness_values.iter().enumerate() {
-                let mut curve_data = Vec::new();
-
-                // Create a modified conductor with the target thickness
-                let mut modified_conductor = conductor.clone();
-                modified_conductor.thickness = thickness;
-
-                for i in 0..num_points {
-                    let temperature = self.temperature_start + (i as f64) * temp_step;
-
-                    if let Some(resistance) = modified_conductor.calculate_resistance(
-                        self.width,
-                        self.length,
-                        temperature,
-                        self.reference_temp,
-                    ) {
-                        curve_data.push((temperature, resistance));
-                    }
-                }
+        let thicknesses =
+            if self.enable_multi_thickness && conductor.rho_vs_si_width_thickness.is_some() {
+                // Generate curves for multiple thickness values
+                self.thickness_values.clone()
+            } else {
+                // Generate single curve for the current layer thickness
+                vec![conductor.thickness]
+            };
+
+        let wmin = match self.sweep_mode {
+            SweepMode::Temperature => None,
+            SweepMode::Width => conductor.physical_props.width_min,
+        };
 
-                if !curve_data.is_empty() {
-                    let curve = ResistanceCurve {
-                        name: format!(
-                            "{} (W={:.3}μm, T={:.3}μm)",
-                            conductor_name, self.width, thickness
-                        ),
-                        data_points: curve_data,
-                        color: colors[thickness_idx % colors.len()],
-                    };
-                    self.curves.push(curve);
-                }
-            }
-        } else {
-            // Generate single curve for the current layer thickness
-            let mut curve_data = Vec::new();
-            for i in 0..num_points {
-                let temperature = self.temperature_start + (i as f64) * temp_step;
-
-                if let Some(resistance) = conductor.calculate_resistance(
-                    self.width,
-                    self.length,
-                    temperature,
-                    self.reference_temp,
-                ) {
-                    curve_data.push((temperature, resistance));
-                }
-            }
+        for &thickness in &thicknesses {
+            let (curve_data, extrapolated): (Vec<(f64, f64)>, Vec<bool>) = sweep_values
+                .iter()
+                .filter_map(|&x| {
+                    self.evaluate_resistance(conductor, thickness, x)
+                        .map(|result| ((x, result.resistance), !result.range_warnings.is_empty()))
+                })
+                .unzip();
 
             if !curve_data.is_empty() {
-                let curve = ResistanceCurve {
-                    name: format!(
-                        "{} (W={:.3}μm, T={:.3}μm)",
-                        conductor_name, self.width, conductor.thickness
-                    ),
-                    data_points: curve_data,
-                    color: egui::Color32::BLUE,
-                };
-                self.curves.push(curve);
+                let color = CURVE_COLORS[self.curves.len() % CURVE_COLORS.len()];
+                let mut curve = ResistanceCurve::new(
+                    self.curve_name(&conductor_name, thickness),
+                    curve_data,
+                    color,
+                );
+                curve.extrapolated = extrapolated;
+                curve.wmin_marker = wmin.and_then(|wmin| {
+                    self.evaluate_resistance(conductor, thickness, wmin)
+                        .map(|result| (wmin, result.resistance))
+                });
+                self.add_curve(curve);
             }
         }
 
@@ -568,6 +1287,73 @@ impl ResistancePlotWindow {
     }
 }
 
+/// Linearly interpolate a curve at `x`, returning `None` outside its sampled range
+fn interpolate_curve(points: &[(f64, f64)], x: f64) -> Option<f64> {
+    points.windows(2).find_map(|pair| {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        if x < x0.min(x1) || x > x0.max(x1) {
+            return None;
+        }
+        if x1 == x0 {
+            return Some(y0);
+        }
+        Some(y0 + (x - x0) / (x1 - x0) * (y1 - y0))
+    })
+}
+
+/// Read the visible curve closest to the cursor in resistance at the cursor's x position
+///
+/// With `snap` set, the readout moves to the sampled point nearest to `x` on that curve.
+fn find_curve_readout(
+    curves: &[ResistanceCurve],
+    x: f64,
+    y: f64,
+    snap: bool,
+) -> Option<CurveReadout> {
+    let (curve, resistance) = curves
+        .iter()
+        .filter(|curve| curve.visible)
+        .filter_map(|curve| interpolate_curve(&curve.data_points, x).map(|r| (curve, r)))
+        .min_by(|(_, a), (_, b)| (a - y).abs().total_cmp(&(b - y).abs()))?;
+
+    let (x, resistance) = if snap {
+        curve
+            .data_points
+            .iter()
+            .copied()
+            .min_by(|(a, _), (b, _)| (a - x).abs().total_cmp(&(b - x).abs()))?
+    } else {
+        (x, resistance)
+    };
+
+    Some(CurveReadout {
+        curve_name: curve.name.clone(),
+        x,
+        resistance,
+    })
+}
+
+/// Split a curve into runs of in-range and extrapolated points
+///
+/// Adjacent runs share their boundary point so the drawn line stays continuous.
+fn split_extrapolated_segments(curve: &ResistanceCurve) -> Vec<(bool, Vec<(f64, f64)>)> {
+    let mut segments: Vec<(bool, Vec<(f64, f64)>)> = Vec::new();
+
+    for (index, &point) in curve.data_points.iter().enumerate() {
+        let extrapolated = curve.extrapolated.get(index).copied().unwrap_or(false);
+        match segments.last_mut() {
+            Some((flag, points)) if *flag == extrapolated => points.push(point),
+            Some((_, points)) => {
+                let boundary = *points.last().unwrap_or(&point);
+                segments.push((extrapolated, vec![boundary, point]));
+            }
+            None => segments.push((extrapolated, vec![point])),
+        }
+    }
+
+    segments
+}
+
 impl Default for ResistancePlotWindow {
     fn default() -> Self {
         Self::new()
@@ -628,11 +1414,11 @@ mod tests {
         window.calculated_resistance = Some(1.5);
         window.calculated_sheet_resistance = Some(0.5);
         window.curves_generated = true;
-        window.curves.push(ResistanceCurve {
-            name: "test".to_string(),
-            data_points: vec![(25.0, 1.0)],
-            color: egui::Color32::RED,
-        });
+        window.curves.push(ResistanceCurve::new(
+            "test".to_string(),
+            vec![(25.0, 1.0)],
+            egui::Color32::RED,
+        ));
         window.error_message = Some("test error".to_string());
         window.calculation_details = Some("test details".to_string());
 
@@ -649,18 +1435,248 @@ mod tests {
 
     #[test]
     fn test_resistance_curve() {
-        let curve = ResistanceCurve {
-            name: "Test".to_string(),
-            data_points: vec![(0.0, 1.0), (100.0, 1.5)],
-            color: egui::Color32::BLUE,
-        };
+        let curve = ResistanceCurve::new(
+            "Test".to_string(),
+            vec![(0.0, 1.0), (100.0, 1.5)],
+            egui::Color32::BLUE,
+        );
 
+        assert!(curve.visible);
+        assert_eq!(curve.line_style, CurveLineStyle::Solid);
         assert_eq!(curve.name, "Test");
         assert_eq!(curve.data_points.len(), 2);
         assert_eq!(curve.data_points[0], (0.0, 1.0));
         assert_eq!(curve.data_points[1], (100.0, 1.5));
     }
 
+    #[test]
+    fn test_curves_to_csv() {
+        let mut window = ResistancePlotWindow::new();
+        window.curves.push(ResistanceCurve::new(
+            "metal1 (W=0.100μm, T=0.100μm)".to_string(),
+            vec![(-40.0, 1.0), (25.0, 1.5), (150.0, 2.0)],
+            egui::Color32::BLUE,
+        ));
+        window.curves.push(ResistanceCurve::new(
+            "metal1 (W=0.100μm, T=0.200μm)".to_string(),
+            vec![(-40.0, 0.5), (150.0, 1.0)],
+            egui::Color32::RED,
+        ));
+
+        let csv = window.curves_to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            "temperature_c,\"metal1 (W=0.100μm, T=0.100μm)\",\"metal1 (W=0.100μm, T=0.200μm)\""
+        );
+        assert_eq!(lines[1], "-40,1,0.5");
+        assert_eq!(lines[2], "25,1.5,");
+        assert_eq!(lines[3], "150,2,1");
+
+        // Hidden curves are left out of the export
+        window.curves[1].visible = false;
+        let csv = window.curves_to_csv();
+        assert_eq!(csv.lines().nth(1), Some("-40,1"));
+    }
+
+    #[test]
+    fn test_crosshair_readout() {
+        let mut curves = vec![
+            ResistanceCurve::new(
+                "low".to_string(),
+                vec![(0.0, 1.0), (10.0, 2.0), (20.0, 3.0)],
+                egui::Color32::BLUE,
+            ),
+            ResistanceCurve::new(
+                "high".to_string(),
+                vec![(0.0, 10.0), (20.0, 20.0)],
+                egui::Color32::RED,
+            ),
+        ];
+
+        assert_eq!(interpolate_curve(&curves[0].data_points, 5.0), Some(1.5));
+        assert_eq!(interpolate_curve(&curves[0].data_points, 25.0), None);
+
+        let readout = find_curve_readout(&curves, 4.0, 1.0, false).unwrap();
+        assert_eq!(readout.curve_name, "low");
+        assert!((readout.resistance - 1.4).abs() < 1e-12);
+
+        let readout = find_curve_readout(&curves, 4.0, 12.0, false).unwrap();
+        assert_eq!(readout.curve_name, "high");
+        assert!((readout.resistance - 12.0).abs() < 1e-12);
+
+        // Snapping moves to the nearest sampled point
+        let readout = find_curve_readout(&curves, 8.0, 1.0, true).unwrap();
+        assert_eq!((readout.x, readout.resistance), (10.0, 2.0));
+
+        // Hidden curves are ignored
+        curves[1].visible = false;
+        let readout = find_curve_readout(&curves, 4.0, 12.0, false).unwrap();
+        assert_eq!(readout.curve_name, "low");
+        assert!(find_curve_readout(&curves, 30.0, 1.0, false).is_none());
+    }
+
+    #[test]
+    fn test_keep_previous_curves() {
+        use crate::data::TechnologyInfo;
+
+        let mut conductor = ConductorLayer::new("metal1".to_string(), 0.2);
+        conductor.electrical_props.rpsq = Some(0.05);
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        stack.add_layer(Layer::Conductor(Box::new(conductor)));
+
+        let mut window = ResistancePlotWindow::new();
+        window.set_selected_conductor(Some("metal1".to_string()));
+        window.generate_curves(&stack);
+        window.width = 0.2;
+        window.generate_curves(&stack);
+        assert_eq!(window.curves.len(), 1);
+
+        window.keep_previous_curves = true;
+        window.curves[0].line_style = CurveLineStyle::Dashed;
+        window.width = 0.1;
+        window.clear_results();
+        assert_eq!(window.curves.len(), 1);
+
+        window.generate_curves(&stack);
+        window.width = 0.2;
+        window.generate_curves(&stack);
+        assert_eq!(window.curves.len(), 2);
+        assert_ne!(window.curves[0].color, window.curves[1].color);
+
+        // Regenerating the same parameters refreshes the curve in place
+        window.width = 0.1;
+        window.generate_curves(&stack);
+        assert_eq!(window.curves.len(), 2);
+        assert_eq!(window.curves[0].line_style, CurveLineStyle::Dashed);
+
+        window.remove_curve(0);
+        assert_eq!(window.curves.len(), 1);
+        assert!(window.curves_generated);
+        window.remove_curve(0);
+        assert!(!window.curves_generated);
+    }
+
+    #[test]
+    fn test_width_sweep() {
+        use crate::data::TechnologyInfo;
+
+        let mut conductor = ConductorLayer::new("metal1".to_string(), 0.2);
+        conductor.electrical_props.rpsq = Some(0.05);
+        conductor.physical_props.width_min = Some(0.1);
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        stack.add_layer(Layer::Conductor(Box::new(conductor)));
+
+        let mut window = ResistancePlotWindow::new();
+        window.set_selected_conductor(Some("metal1".to_string()));
+        window.sweep_mode = SweepMode::Width;
+        window.width_start = 0.01;
+        window.width_end = 10.0;
+        window.log_width_axis = true;
+        window.generate_curves(&stack);
+
+        assert!(window.curves_generated);
+        assert_eq!(window.curves.len(), 1);
+        let points = &window.curves[0].data_points;
+        assert_eq!(points.len(), 100);
+        assert!((points[0].0 - 0.01).abs() < 1e-12);
+        assert!((points[99].0 - 10.0).abs() < 1e-9);
+        // Log spacing puts the midpoint at the geometric mean
+        assert!((points[0].0 * points[99].0).sqrt() > points[49].0);
+        // R = Rsq * L / W decreases with width
+        assert!(points[0].1 > points[99].1);
+
+        let (wmin, resistance) = window.curves[0].wmin_marker.unwrap();
+        assert_eq!(wmin, 0.1);
+        assert!((resistance - 0.05 * 100.0 / 0.1).abs() < 1e-9);
+        assert!(window.curves_to_csv().starts_with("width_um,"));
+    }
+
+    #[test]
+    fn test_extrapolated_width_sweep() {
+        use crate::data::{LookupTable2D, TechnologyInfo};
+
+        let mut conductor = ConductorLayer::new("metal1".to_string(), 0.2);
+        conductor.rho_vs_si_width_thickness = Some(LookupTable2D::new(
+            vec![0.1, 1.0],
+            vec![0.1, 0.3],
+            vec![vec![0.02, 0.018], vec![0.021, 0.019]],
+        ));
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        stack.add_layer(Layer::Conductor(Box::new(conductor)));
+
+        let mut window = ResistancePlotWindow::new();
+        window.set_selected_conductor(Some("metal1".to_string()));
+        window.width = 5.0;
+        window.calculate_resistance(&stack);
+        assert_eq!(window.range_warnings.len(), 1);
+        assert!(window
+            .calculation_details
+            .as_ref()
+            .unwrap()
+            .contains("Extrapolated: Width"));
+
+        window.sweep_mode = SweepMode::Width;
+        window.width_start = 0.05;
+        window.width_end = 2.0;
+        window.generate_curves(&stack);
+
+        let curve = &window.curves[0];
+        assert_eq!(curve.extrapolated.len(), curve.data_points.len());
+        assert!(curve.extrapolated[0]);
+        assert!(!curve.extrapolated[10]);
+        assert!(curve.extrapolated[99]);
+
+        let segments = split_extrapolated_segments(curve);
+        assert_eq!(segments.len(), 3);
+        assert!(segments[0].0 && !segments[1].0 && segments[2].0);
+        // Boundary points are shared between neighbouring segments
+        assert_eq!(segments[0].1.last(), segments[1].1.first());
+        let total: usize = segments.iter().map(|(_, points)| points.len()).sum();
+        assert_eq!(total, curve.data_points.len() + 2);
+    }
+
+    #[test]
+    fn test_width_range_from_table() {
+        let mut window = ResistancePlotWindow::new();
+        window.set_width_range_from_table(&[0.05, 0.1, 1.0]);
+        assert_eq!(window.width_start, 0.05);
+        assert_eq!(window.width_end, 1.0);
+
+        // A single-point axis leaves the range untouched
+        window.set_width_range_from_table(&[0.2]);
+        assert_eq!(window.width_start, 0.05);
+        assert_eq!(window.width_end, 1.0);
+    }
+
+    #[test]
+    fn test_thickness_list_editing() {
+        let mut window = ResistancePlotWindow::new();
+        assert_eq!(window.get_thickness_values(), &[0.1, 0.2, 0.3, 0.5]);
+
+        window.add_thickness_value();
+        assert_eq!(window.thickness_values.len(), 5);
+        assert!((window.thickness_values[4] - 0.7).abs() < 1e-12);
+
+        window.remove_thickness_value(0);
+        assert_eq!(window.thickness_values.len(), 4);
+        assert_eq!(window.thickness_values[0], 0.2);
+
+        // Table thickness axes are sorted and deduplicated
+        window.set_thickness_values(&[0.35, 0.15, 0.25, 0.15, -1.0]);
+        assert_eq!(window.get_thickness_values(), &[0.15, 0.25, 0.35]);
+
+        // The list never becomes empty
+        window.set_thickness_values(&[]);
+        assert_eq!(window.thickness_values.len(), 3);
+        window.remove_thickness_value(0);
+        window.remove_thickness_value(0);
+        window.remove_thickness_value(0);
+        assert_eq!(window.get_thickness_values(), &[0.35]);
+    }
+
     #[test]
     fn test_default_implementation() {
         let window1 = ResistancePlotWindow::new();
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{LayerMemoryUsage, ProcessStack, StackMemoryUsage};
+use crate::utils::format_file_size;
+use egui::{Context, Grid, ScrollArea, Window};
+
+/// Approximate memory held by the open stack, with the option to drop its lookup tables
+pub struct ResourcesWindow {
+    open: bool,
+    /// Bytes freed by the last unload of the current stack
+    unloaded_bytes: Option<usize>,
+}
+
+impl ResourcesWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            unloaded_bytes: None,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Record the tables of the current stack as unloaded, or clear it for a new stack
+    pub fn set_unloaded_bytes(&mut self, bytes: Option<usize>) {
+        self.unloaded_bytes = bytes;
+    }
+
+    pub fn get_unloaded_bytes(&self) -> Option<usize> {
+        self.unloaded_bytes
+    }
+
+    /// Show the window; returns whether the user asked to unload the tables
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        stack: Option<&ProcessStack>,
+        source_bytes: usize,
+    ) -> bool {
+        if !self.open {
+            return false;
+        }
+
+        let usage = stack.map(ProcessStack::memory_usage);
+        let mut unload = false;
+        let mut open = self.open;
+        Window::new("Resources")
+            .open(&mut open)
+            .default_size([360.0, 320.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let Some(usage) = usage else {
+                    ui.label("No stack loaded");
+                    return;
+                };
+
+                Grid::new("resources_summary")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Parsed stack:");
+                        ui.label(format_bytes(usage.total_bytes()));
+                        ui.end_row();
+                        ui.label("Lookup tables:");
+                        ui.label(format_bytes(usage.table_bytes()));
+                        ui.end_row();
+                        ui.label("Source text:");
+                        ui.label(format_bytes(source_bytes));
+                        ui.end_row();
+                    });
+
+                ui.separator();
+                let layers = layers_by_table_size(&usage);
+                if layers.is_empty() {
+                    ui.label("No lookup tables loaded");
+                } else {
+                    ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        Grid::new("resources_layers")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Layer");
+                                ui.strong("Tables");
+                                ui.strong("Size");
+                                ui.end_row();
+                                for layer in layers {
+                                    ui.label(&layer.layer_name);
+                                    ui.label(layer.table_count.to_string());
+                                    ui.label(format_bytes(layer.table_bytes));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                }
+
+                ui.separator();
+                if let Some(bytes) = self.unloaded_bytes {
+                    ui.label(format!(
+                        "Unloaded {} of tables; reopen the file to restore them",
+                        format_bytes(bytes)
+                    ));
+                }
+                unload = ui
+                    .add_enabled(usage.table_bytes() > 0, egui::Button::new("Unload Tables"))
+                    .on_hover_text(
+                        "Drop the resistivity, etch and CRT tables while keeping the geometry. \
+                         Clears the undo history.",
+                    )
+                    .clicked();
+            });
+        self.open = open;
+        unload
+    }
+}
+
+impl Default for ResourcesWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Layers holding lookup tables, largest first
+fn layers_by_table_size(usage: &StackMemoryUsage) -> Vec<&LayerMemoryUsage> {
+    let mut layers: Vec<&LayerMemoryUsage> = usage
+        .layers
+        .iter()
+        .filter(|layer| layer.table_bytes > 0)
+        .collect();
+    layers.sort_by_key(|layer| std::cmp::Reverse(layer.table_bytes));
+    layers
+}
+
+fn format_bytes(bytes: usize) -> String {
+    format!("~{}", format_file_size(bytes as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(name: &str, table_bytes: usize) -> LayerMemoryUsage {
+        LayerMemoryUsage {
+            layer_name: name.to_string(),
+            geometry_bytes: 100,
+            table_bytes,
+            table_count: usize::from(table_bytes > 0),
+        }
+    }
+
+    #[test]
+    fn test_layers_by_table_size() {
+        let usage = StackMemoryUsage {
+            layers: vec![layer("imd", 0), layer("metal1", 200), layer("metal2", 800)],
+            other_bytes: 0,
+        };
+        let names: Vec<&str> = layers_by_table_size(&usage)
+            .iter()
+            .map(|layer| layer.layer_name.as_str())
+            .collect();
+        assert_eq!(names, ["metal2", "metal1"]);
+    }
+}
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use crate::utils::{search_stack, SearchHit, SearchTarget};
+use egui::{Context, Grid, RichText, ScrollArea, TextEdit, Window};
+
+pub struct SearchWindow {
+    open: bool,
+    query: String,
+    searched_query: Option<String>,
+    hits: Vec<SearchHit>,
+    focus_query: bool,
+}
+
+impl SearchWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            searched_query: None,
+            hits: Vec::new(),
+            focus_query: false,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        if open && !self.open {
+            self.focus_query = true;
+        }
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Drop results from a previously loaded stack
+    pub fn clear_results(&mut self) {
+        self.searched_query = None;
+        self.hits.clear();
+    }
+
+    pub fn get_hits(&self) -> &[SearchHit] {
+        &self.hits
+    }
+
+    /// Re-run the search when the query changed
+    pub fn update_results(&mut self, stack: Option<&ProcessStack>) {
+        if self.searched_query.as_deref() == Some(self.query.as_str()) {
+            return;
+        }
+
+        self.hits = stack
+            .map(|stack| search_stack(stack, &self.query))
+            .unwrap_or_default();
+        self.searched_query = Some(self.query.clone());
+    }
+
+    /// Show the window, returning the layer or via name to jump to
+    pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) -> Option<String> {
+        if !self.open {
+            return None;
+        }
+
+        let mut jump_to = None;
+        let mut open = self.open;
+        Window::new("Search")
+            .open(&mut open)
+            .default_size([520.0, 400.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    TextEdit::singleline(&mut self.query)
+                        .hint_text("Name, keyword or comparison, e.g. SW_T or ER<3.0")
+                        .desired_width(f32::INFINITY),
+                );
+                if self.focus_query {
+                    response.request_focus();
+                    self.focus_query = false;
+                }
+
+                self.update_results(stack);
+
+                ui.separator();
+
+                if stack.is_none() {
+                    ui.label("No ITF file loaded");
+                    return;
+                }
+                if self.query.trim().is_empty() {
+                    ui.label("Type to search layer names, via names, keywords and values");
+                    return;
+                }
+
+                ui.label(format!("{} matches", self.hits.len()));
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    Grid::new("search_results")
+                        .num_columns(3)
+                        .striped(true)
+                        .spacing([16.0, 4.0])
+                        .show(ui, |ui| {
+                            for hit in &self.hits {
+                                let (kind, name) = match &hit.target {
+                                    SearchTarget::Technology => ("Technology", ""),
+                                    SearchTarget::Layer(name) => ("Layer", name.as_str()),
+                                    SearchTarget::Via(name) => ("Via", name.as_str()),
+                                };
+
+                                match hit.target.name() {
+                                    Some(target_name) => {
+                                        if ui
+                                            .link(format!("{kind} {name}"))
+                                            .on_hover_text("Select in the viewer")
+                                            .clicked()
+                                        {
+                                            jump_to = Some(target_name.to_string());
+                                        }
+                                    }
+                                    None => {
+                                        ui.label(kind);
+                                    }
+                                }
+                                ui.monospace(hit.key);
+                                ui.label(RichText::new(hit.value.to_string()).strong());
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+
+        jump_to
+    }
+}
+
+impl Default for SearchWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, Layer, TechnologyInfo};
+
+    #[test]
+    fn test_search_window_results() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide1".to_string(),
+            1.0,
+            2.9,
+        )));
+
+        let mut window = SearchWindow::new();
+        assert!(!window.is_open());
+        window.set_open(true);
+        assert!(window.is_open());
+
+        window.query = "er<3".to_string();
+        window.update_results(Some(&stack));
+        assert_eq!(window.get_hits().len(), 1);
+        assert_eq!(window.get_hits()[0].target.name(), Some("oxide1"));
+
+        window.clear_results();
+        assert!(window.get_hits().is_empty());
+        window.update_results(None);
+        assert!(window.get_hits().is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// Panel or action that changed the selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionSource {
+    LayerPanel,
+    StackViewer,
+    Search,
+    ResistanceWindow,
+    Problems,
+    TemperatureDrift,
+    /// Set by the application itself, e.g. cleared when a new stack is loaded
+    Program,
+}
+
+/// Notification sent to subscribers after the selected layer changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionChange {
+    pub previous: Option<String>,
+    pub current: Option<String>,
+    pub source: SelectionSource,
+}
+
+/// The selected layer or via, shared by every panel
+///
+/// Panels report what the user picked through `select`; subscribers then receive a
+/// `SelectionChange` and bring their own view in line, so a selection made in one
+/// place shows up everywhere.
+pub struct SelectionState {
+    selected: Option<String>,
+    subscribers: Vec<Sender<SelectionChange>>,
+}
+
+impl SelectionState {
+    pub fn new() -> Self {
+        Self {
+            selected: None,
+            subscribers: Vec::new(),
+        }
+    }
+
+    pub fn selected(&self) -> Option<&String> {
+        self.selected.as_ref()
+    }
+
+    /// Receive a `SelectionChange` for every subsequent change
+    pub fn subscribe(&mut self) -> Receiver<SelectionChange> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Select a layer by name, notifying subscribers if the selection changed
+    pub fn select(&mut self, name: Option<String>, source: SelectionSource) -> bool {
+        if self.selected == name {
+            return false;
+        }
+
+        let previous = std::mem::replace(&mut self.selected, name);
+        let change = SelectionChange {
+            previous,
+            current: self.selected.clone(),
+            source,
+        };
+        // Drop subscribers whose receiver has gone away
+        self.subscribers
+            .retain(|subscriber| subscriber.send(change.clone()).is_ok());
+        true
+    }
+
+    pub fn clear(&mut self) -> bool {
+        self.select(None, SelectionSource::Program)
+    }
+}
+
+impl Default for SelectionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_notifications() {
+        let mut selection = SelectionState::new();
+        let receiver = selection.subscribe();
+
+        assert!(selection.select(Some("metal1".to_string()), SelectionSource::LayerPanel));
+        assert_eq!(selection.selected(), Some(&"metal1".to_string()));
+
+        // Selecting the same layer again is not a change
+        assert!(!selection.select(Some("metal1".to_string()), SelectionSource::StackViewer));
+
+        assert!(selection.clear());
+        assert!(selection.selected().is_none());
+
+        let changes: Vec<SelectionChange> = receiver.try_iter().collect();
+        assert_eq!(
+            changes,
+            vec![
+                SelectionChange {
+                    previous: None,
+                    current: Some("metal1".to_string()),
+                    source: SelectionSource::LayerPanel,
+                },
+                SelectionChange {
+                    previous: Some("metal1".to_string()),
+                    current: None,
+                    source: SelectionSource::Program,
+                },
+            ]
+        );
+
+        // A dropped receiver does not break later changes
+        drop(receiver);
+        assert!(selection.select(Some("via1".to_string()), SelectionSource::Search));
+    }
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use egui::{Context, Label, RichText, ScrollArea, TextStyle, Window};
+
+/// Text of the opened ITF file with line numbers, scrolled to lines picked elsewhere
+pub struct SourceWindow {
+    open: bool,
+    lines: Vec<String>,
+    /// 1-based line marked in the text
+    highlighted_line: Option<usize>,
+    /// Whether the text still has to scroll to the highlighted line
+    scroll_pending: bool,
+}
+
+impl SourceWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            lines: Vec::new(),
+            highlighted_line: None,
+            scroll_pending: false,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Text to show, none for stacks that were not read from an ITF file
+    pub fn set_source(&mut self, source: Option<&str>) {
+        self.lines = source
+            .map(|source| source.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        self.highlighted_line = None;
+        self.scroll_pending = false;
+    }
+
+    pub fn has_source(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    /// Approximate bytes of the text held for display
+    pub fn source_bytes(&self) -> usize {
+        self.lines.iter().map(|line| line.len() + 1).sum()
+    }
+
+    pub fn get_highlighted_line(&self) -> Option<usize> {
+        self.highlighted_line
+    }
+
+    /// Open the window scrolled to `line`, marking it
+    pub fn go_to_line(&mut self, line: usize) {
+        self.open = true;
+        self.highlighted_line = Some(line.clamp(1, self.lines.len().max(1)));
+        self.scroll_pending = true;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        if !self.open {
+            return;
+        }
+
+        let mut open = self.open;
+        Window::new("Source")
+            .open(&mut open)
+            .default_size([560.0, 420.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                if self.lines.is_empty() {
+                    ui.label("The source is shown for stacks opened from an ITF file.");
+                    return;
+                }
+
+                let row_height = ui.text_style_height(&TextStyle::Monospace);
+                let mut area = ScrollArea::both().auto_shrink(false);
+                if let (true, Some(line)) = (
+                    std::mem::take(&mut self.scroll_pending),
+                    self.highlighted_line,
+                ) {
+                    // Put the line a third down the window, with context above it
+                    let pitch = row_height + ui.spacing().item_spacing.y;
+                    let offset = (line - 1) as f32 * pitch - ui.available_height() / 3.0;
+                    area = area.vertical_scroll_offset(offset.max(0.0));
+                }
+
+                let digits = self.lines.len().to_string().len();
+                area.show_rows(ui, row_height, self.lines.len(), |ui, rows| {
+                    for index in rows {
+                        let number = index + 1;
+                        let mut text =
+                            RichText::new(format!("{number:>digits$}  {}", self.lines[index]))
+                                .monospace();
+                        if self.highlighted_line == Some(number) {
+                            text = text.background_color(ui.visuals().selection.bg_fill);
+                        }
+                        ui.add(Label::new(text).extend());
+                    }
+                });
+            });
+        self.open = open;
+    }
+}
+
+impl Default for SourceWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_window() {
+        let mut window = SourceWindow::new();
+        assert!(!window.has_source());
+
+        window.set_source(Some("TECHNOLOGY = t\n\nCONDUCTOR m1 {THICKNESS=0.2}\n"));
+        assert!(window.has_source());
+        window.go_to_line(3);
+        assert!(window.is_open());
+        assert_eq!(window.get_highlighted_line(), Some(3));
+
+        // Lines past the end mark the last one
+        window.go_to_line(40);
+        assert_eq!(window.get_highlighted_line(), Some(3));
+
+        window.set_source(None);
+        assert!(!window.has_source());
+        assert_eq!(window.get_highlighted_line(), None);
+    }
+}
@@ -1,9 +1,43 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use crate::data::ProcessStack;
-use crate::renderer::{StackRenderer, ViewTransform};
-use egui::{CentralPanel, Color32, Context, CursorIcon, Frame, Pos2, Sense, Vec2};
+use crate::data::{Layer, LayerClass, Micrometers, ProcessStack};
+use crate::gui::{sidewall_angle_text, InputBindings, ViewCommand};
+use crate::renderer::{
+    exaggeration_label, ColorScheme, FitAxis, Guide, HitIndex, RenderProfiler, StackLayout,
+    StackRenderer, StructureAnnotation, Theme, ViewAnchor, ViewLimits, ViewTransform,
+    WidthDistortion,
+};
+use egui::{
+    Align2, CentralPanel, Color32, Context, CursorIcon, Event, FontId, Frame, Modifiers,
+    MouseWheelUnit, PointerButton, Pos2, Rect, Sense, Stroke, StrokeKind, Vec2,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Rubber-band selections smaller than this, in pixels, are ignored
+const MIN_ZOOM_SELECTION: f32 = 5.0;
+/// Pixels panned per line of a mouse wheel scroll
+const LINE_SCROLL_STEP: f32 = 40.0;
+/// Width of the ruler strip along the left edge that new guides are dragged out of
+const RULER_GRAB_WIDTH: f32 = 50.0;
+/// Distance in pixels within which a guide line can be grabbed
+const GUIDE_GRAB_DISTANCE: f32 = 4.0;
+/// Distance in pixels within which guides snap to layer boundaries
+const SNAP_DISTANCE: f32 = 6.0;
+/// Rate, per second, at which the view eases back inside its limits
+const SNAP_BACK_RATE: f32 = 12.0;
+
+/// Stack position and layer under the mouse cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorInfo {
+    /// Horizontal world coordinate in um
+    pub x: f32,
+    /// Vertical ruler reading in um, height or depth depending on the view anchor
+    pub z: f32,
+    pub layer: Option<String>,
+}
 
 pub struct StackViewer {
     renderer: StackRenderer,
@@ -12,6 +46,35 @@ pub struct StackViewer {
     last_mouse_pos: Option<Pos2>,
     zoom_sensitivity: f32,
     pan_sensitivity: f32,
+    /// Zoom to a rectangle dragged with the primary button instead of panning
+    area_zoom_mode: bool,
+    /// Start and current corner of a rubber-band zoom in progress
+    zoom_selection: Option<(Pos2, Pos2)>,
+    input_bindings: InputBindings,
+    view_limits: ViewLimits,
+    cursor_info: Option<CursorInfo>,
+    /// Hit index of the shown stack and the viewport width it was laid out for
+    hit_index: RefCell<Option<(f32, HitIndex)>>,
+    /// Layer or via double-clicked since the last `take_activated_layer`
+    activated_layer: Option<String>,
+    /// Index of the guide being dragged
+    guide_drag: Option<usize>,
+    /// Per-stage frame timings, drawn over the view when `show_profiler` is set
+    profiler: RenderProfiler,
+    show_profiler: bool,
+    /// Show the stack a second time, in the other thickness mode, right of the main view
+    split_view: bool,
+    /// Hit index of the companion view of the split view
+    companion_hit_index: RefCell<Option<(f32, HitIndex)>>,
+    /// View to restore once the solo key is released, set while soloing
+    solo: Option<SoloState>,
+}
+
+/// View replaced by solo mode
+struct SoloState {
+    layer: String,
+    transform: ViewTransform,
+    visible_range: Option<(String, String)>,
 }
 
 impl StackViewer {
@@ -23,6 +86,19 @@ impl StackViewer {
             last_mouse_pos: None,
             zoom_sensitivity: 1.1,
             pan_sensitivity: 1.0,
+            area_zoom_mode: false,
+            zoom_selection: None,
+            input_bindings: InputBindings::default(),
+            view_limits: ViewLimits::default(),
+            cursor_info: None,
+            hit_index: RefCell::new(None),
+            activated_layer: None,
+            guide_drag: None,
+            profiler: RenderProfiler::new(),
+            show_profiler: false,
+            split_view: false,
+            companion_hit_index: RefCell::new(None),
+            solo: None,
         }
     }
 
@@ -32,8 +108,14 @@ impl StackViewer {
         CentralPanel::default()
             .frame(Frame::canvas(&ctx.style()))
             .show(ctx, |ui| {
-                // Update viewport size
-                let viewport_rect = ui.available_rect_before_wrap();
+                // Update viewport size, the left half of the panel in split view
+                let panel_rect = ui.available_rect_before_wrap();
+                let (viewport_rect, companion_rect) = if self.split_view && stack.is_some() {
+                    let (left, right) = panel_rect.split_left_right_at_fraction(0.5);
+                    (left, Some(right))
+                } else {
+                    (panel_rect, None)
+                };
                 self.transform.viewport_size = viewport_rect.size();
 
                 // Handle input
@@ -43,39 +125,106 @@ impl StackViewer {
                 self.handle_mouse_input(ui, &response);
 
                 // Handle keyboard shortcuts
-                self.handle_keyboard_input(ui);
+                self.handle_keyboard_input(ui, stack);
 
                 if let Some(stack) = stack {
+                    self.handle_guide_drag(ui, &response, stack);
+
+                    // Ease the view back once the user lets go
+                    let interacting = self.is_panning
+                        || self.zoom_selection.is_some()
+                        || self.guide_drag.is_some()
+                        || ui.input(|i| i.pointer.any_down() || i.multi_touch().is_some());
+                    if !interacting {
+                        let dt = ui.input(|i| i.stable_dt);
+                        if self.settle_view(stack, viewport_rect, dt) {
+                            ctx.request_repaint();
+                        }
+                    }
+
                     // Get painter for the viewport
                     let painter = ui.painter_at(viewport_rect);
 
                     // Render the stack with text using painter
-                    self.renderer.render_stack_with_painter(
+                    let timings = self.renderer.render_stack_with_painter(
                         stack,
                         &self.transform,
                         viewport_rect,
                         &painter,
                     );
+                    self.profiler.record(timings);
+
+                    // Track the position under the cursor for the status bar, snapped to
+                    // layer boundaries like guides unless Alt is held
+                    let snap = !ui.input(|i| i.modifiers.alt);
+                    let mut cursor_info = response
+                        .hover_pos()
+                        .map(|pos| self.cursor_info_at(stack, viewport_rect, pos, snap));
+                    if let Some(Layer::Conductor(conductor)) = cursor_info
+                        .as_ref()
+                        .and_then(|info| stack.get_layer(info.layer.as_deref()?))
+                    {
+                        if let Some(side_tangent) = conductor.physical_props.side_tangent {
+                            response.clone().on_hover_text_at_pointer(format!(
+                                "{}\n{}",
+                                conductor.name,
+                                sidewall_angle_text(
+                                    side_tangent,
+                                    self.renderer.get_sidewall_exaggeration()
+                                )
+                            ));
+                        }
+                    }
+                    if let Some(companion_rect) = companion_rect {
+                        let (companion_info, clicked) =
+                            self.show_companion(ui, stack, viewport_rect, companion_rect);
+                        cursor_info = cursor_info.or(companion_info);
+                        if clicked.is_some() {
+                            selected_layer = clicked;
+                        }
+                    }
+                    self.update_cursor_info(ctx, cursor_info);
 
                     // Handle layer selection via mouse click
                     if response.clicked() {
                         if let Some(mouse_pos) = response.interact_pointer_pos() {
-                            selected_layer = self.renderer.hit_test(
-                                stack,
-                                &self.transform,
-                                viewport_rect,
-                                mouse_pos,
-                            );
+                            selected_layer = self.hit_test(stack, viewport_rect, mouse_pos);
 
                             if let Some(ref layer_name) = selected_layer {
                                 self.renderer.set_selected_layer(Some(layer_name.clone()));
                             }
                         }
                     }
+                    if response.double_clicked() {
+                        if let Some(mouse_pos) = response.interact_pointer_pos() {
+                            self.activated_layer = self.hit_test(stack, viewport_rect, mouse_pos);
+                        }
+                    }
+
+                    // Rubber band of an area zoom in progress
+                    if let Some((start, end)) = self.zoom_selection {
+                        let selection = Rect::from_two_pos(start, end);
+                        let color = ui.visuals().selection.stroke.color;
+                        painter.rect_filled(selection, 0.0, color.gamma_multiply(0.15));
+                        painter.rect_stroke(
+                            selection,
+                            0.0,
+                            Stroke::new(1.0, color),
+                            StrokeKind::Inside,
+                        );
+                    }
 
                     // Show status information
                     self.show_status_overlay(ui, stack, viewport_rect);
+                    if let Some(exaggeration) = self.renderer.get_vertical_exaggeration(stack) {
+                        paint_exaggeration_badge(ui, viewport_rect, exaggeration);
+                    }
+                    if self.show_profiler {
+                        self.show_profiler_overlay(ui, viewport_rect);
+                    }
                 } else {
+                    self.update_cursor_info(ctx, None);
+
                     // Show message when no file is loaded
                     ui.centered_and_justified(|ui| {
                         ui.label("No ITF file loaded. Use File menu to open an ITF file.");
@@ -86,27 +235,228 @@ impl StackViewer {
         selected_layer
     }
 
-    fn handle_mouse_input(&mut self, ui: &mut egui::Ui, response: &egui::Response) {
-        // Handle scrolling for zoom
+    /// Store the cursor info, repainting so the status bar catches up within the frame
+    fn update_cursor_info(&mut self, ctx: &Context, cursor_info: Option<CursorInfo>) {
+        if self.cursor_info != cursor_info {
+            self.cursor_info = cursor_info;
+            ctx.request_repaint();
+        }
+    }
+
+    /// Stack coordinates and layer at a screen position
+    ///
+    /// With `snap`, the height is that of a layer boundary within `SNAP_DISTANCE` pixels.
+    pub fn cursor_info_at(
+        &self,
+        stack: &ProcessStack,
+        viewport_rect: Rect,
+        screen_pos: Pos2,
+        snap: bool,
+    ) -> CursorInfo {
+        let world_pos = self.transform.screen_to_world(screen_pos);
+        let world_y = self.world_y_at(stack, screen_pos, snap);
+        CursorInfo {
+            x: world_pos.x,
+            z: self.renderer.get_view_anchor().ruler_value(world_y),
+            layer: self.hit_test(stack, viewport_rect, screen_pos),
+        }
+    }
+
+    /// Layer at a screen position, using the cached hit index
+    fn hit_test(
+        &self,
+        stack: &ProcessStack,
+        viewport_rect: Rect,
+        screen_pos: Pos2,
+    ) -> Option<String> {
+        cached_hit_test(
+            &self.hit_index,
+            &self.renderer,
+            stack,
+            viewport_rect.width(),
+            self.transform.screen_to_world(screen_pos),
+        )
+    }
+
+    /// Drop the cached hit index; call whenever the stack passed to `show` changes
+    pub fn invalidate_hit_index(&mut self) {
+        self.hit_index.get_mut().take();
+        self.companion_hit_index.get_mut().take();
+    }
+
+    /// Renderer and transform of the companion view of the split view
+    ///
+    /// The companion shows the other thickness mode at the same zoom, with the stack
+    /// position at the middle of the main view at the same screen height.
+    pub fn companion_view(
+        &self,
+        stack: &ProcessStack,
+        viewport_rect: Rect,
+        companion_rect: Rect,
+    ) -> (StackRenderer, ViewTransform) {
+        let mut renderer = self.renderer.clone();
+        renderer.set_show_schematic_mode(!self.renderer.show_schematic_mode);
+
+        let mut transform = self.transform.clone();
+        transform.viewport_size = companion_rect.size();
+        transform.offset.x += companion_rect.min.x - viewport_rect.min.x;
+        let world_y = self.transform.screen_to_world(viewport_rect.center()).y;
+        let companion_y = self.layout_stack(stack).map_world_y(
+            &renderer.layout_stack(stack, companion_rect.width()),
+            world_y,
+        );
+        transform.offset.y = viewport_rect.center().y
+            - companion_y * transform.scale
+            - transform.viewport_size.y * 0.5;
+        (renderer, transform)
+    }
+
+    /// Draw and handle the companion view of the split view
+    ///
+    /// Scrolling and dragging move the main view, which the companion follows. Returns
+    /// the cursor info over the companion and the layer clicked in it.
+    fn show_companion(
+        &mut self,
+        ui: &mut egui::Ui,
+        stack: &ProcessStack,
+        viewport_rect: Rect,
+        companion_rect: Rect,
+    ) -> (Option<CursorInfo>, Option<String>) {
+        let response = ui.allocate_rect(companion_rect, Sense::click_and_drag());
         if response.hovered() {
-            let scroll_delta = ui.input(|i| i.raw_scroll_delta);
-            if scroll_delta.y != 0.0 {
-                let zoom_factor = if scroll_delta.y > 0.0 {
-                    self.zoom_sensitivity
-                } else {
-                    1.0 / self.zoom_sensitivity
-                };
+            self.handle_scroll_and_gestures(ui, &response);
+        }
+        if response.dragged_by(PointerButton::Primary) {
+            self.transform
+                .pan(response.drag_delta() * self.pan_sensitivity);
+        }
 
-                let zoom_center = response
-                    .interact_pointer_pos()
-                    .unwrap_or_else(|| response.rect.center());
+        let (renderer, transform) = self.companion_view(stack, viewport_rect, companion_rect);
+        let painter = ui.painter_at(companion_rect);
+        renderer.render_stack_with_painter(stack, &transform, companion_rect, &painter);
+        painter.line_segment(
+            [companion_rect.left_top(), companion_rect.left_bottom()],
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+        if let Some(exaggeration) = renderer.get_vertical_exaggeration(stack) {
+            paint_exaggeration_badge(ui, companion_rect, exaggeration);
+        }
+        for (rect, renderer) in [(viewport_rect, &self.renderer), (companion_rect, &renderer)] {
+            let label = if renderer.show_schematic_mode {
+                "Schematic"
+            } else {
+                "True scale"
+            };
+            ui.painter().text(
+                rect.center_top() + Vec2::new(0.0, 8.0),
+                Align2::CENTER_TOP,
+                label,
+                FontId::proportional(14.0),
+                ui.visuals().strong_text_color(),
+            );
+        }
+
+        let hit = |screen_pos: Pos2| {
+            cached_hit_test(
+                &self.companion_hit_index,
+                &renderer,
+                stack,
+                companion_rect.width(),
+                transform.screen_to_world(screen_pos),
+            )
+        };
+        let cursor_info = response.hover_pos().map(|pos| {
+            let world_pos = transform.screen_to_world(pos);
+            CursorInfo {
+                x: world_pos.x,
+                z: renderer.get_view_anchor().ruler_value(world_pos.y),
+                layer: hit(pos),
+            }
+        });
+
+        let mut clicked = None;
+        if response.clicked() {
+            if let Some(mouse_pos) = response.interact_pointer_pos() {
+                clicked = hit(mouse_pos);
+                if let Some(ref layer_name) = clicked {
+                    self.renderer.set_selected_layer(Some(layer_name.clone()));
+                }
+            }
+        }
+        if response.double_clicked() {
+            if let Some(mouse_pos) = response.interact_pointer_pos() {
+                self.activated_layer = hit(mouse_pos);
+            }
+        }
+        (cursor_info, clicked)
+    }
+
+    /// Vertical exaggeration of the main view, none when thicknesses are to scale
+    pub fn get_vertical_exaggeration(&self, stack: &ProcessStack) -> Option<f32> {
+        self.renderer.get_vertical_exaggeration(stack)
+    }
+
+    /// Conductors the view draws out of proportion with their WMIN by more than `max_factor`
+    pub fn check_width_fidelity(
+        &self,
+        stack: &ProcessStack,
+        max_factor: f32,
+    ) -> Vec<WidthDistortion> {
+        self.renderer.check_width_fidelity(stack, max_factor)
+    }
+
+    pub fn set_split_view(&mut self, split: bool) {
+        self.split_view = split;
+    }
+
+    pub fn is_split_view(&self) -> bool {
+        self.split_view
+    }
+
+    /// Return the layer or via double-clicked since the last call
+    pub fn take_activated_layer(&mut self) -> Option<String> {
+        self.activated_layer.take()
+    }
+
+    /// Position and layer under the cursor, if it is over a loaded stack
+    pub fn get_cursor_info(&self) -> Option<&CursorInfo> {
+        self.cursor_info.as_ref()
+    }
+
+    fn handle_mouse_input(&mut self, ui: &mut egui::Ui, response: &egui::Response) {
+        if response.hovered() {
+            self.handle_scroll_and_gestures(ui, response);
+        }
 
-                self.transform.zoom(zoom_factor, zoom_center);
+        // Rubber-band zoom with the secondary button, or the primary one in area zoom mode
+        let zoom_button = if self.area_zoom_mode {
+            PointerButton::Primary
+        } else {
+            PointerButton::Secondary
+        };
+        if response.drag_started_by(zoom_button) {
+            if let Some(start) = ui.input(|i| i.pointer.press_origin()) {
+                self.zoom_selection = Some((start, start));
+            }
+        }
+        if response.dragged_by(zoom_button) {
+            if let (Some((start, _)), Some(current_pos)) =
+                (self.zoom_selection, response.interact_pointer_pos())
+            {
+                self.zoom_selection = Some((start, current_pos));
+            }
+        }
+        if response.drag_stopped_by(zoom_button) {
+            if let Some((start, end)) = self.zoom_selection.take() {
+                self.zoom_to_screen_rect(Rect::from_two_pos(start, end), response.rect);
             }
         }
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.zoom_selection = None;
+        }
 
-        // Handle panning
-        if response.dragged() {
+        // Handle panning with the remaining buttons, unless a guide is being dragged
+        if response.dragged() && !response.dragged_by(zoom_button) && self.guide_drag.is_none() {
             if let Some(current_pos) = response.interact_pointer_pos() {
                 if let Some(last_pos) = self.last_mouse_pos {
                     let delta = (current_pos - last_pos) * self.pan_sensitivity;
@@ -121,7 +471,11 @@ impl StackViewer {
         }
 
         // Set cursor icon once at the end based on final state - prevents race conditions
-        let cursor_icon = if self.is_panning || response.dragged() {
+        let cursor_icon = if self.zoom_selection.is_some()
+            || (self.area_zoom_mode && response.hovered() && !self.is_panning)
+        {
+            CursorIcon::Crosshair
+        } else if self.is_panning || response.dragged() {
             CursorIcon::Grabbing
         } else if response.hovered() {
             CursorIcon::Grab
@@ -137,43 +491,268 @@ impl StackViewer {
         ui.output_mut(|output| output.cursor_icon = cursor_icon);
     }
 
-    fn handle_keyboard_input(&mut self, ui: &mut egui::Ui) {
-        let input = ui.input(|i| i.clone());
+    /// Drag guides out of the ruler, move them, and drop them back on the ruler to remove them
+    fn handle_guide_drag(
+        &mut self,
+        ui: &mut egui::Ui,
+        response: &egui::Response,
+        stack: &ProcessStack,
+    ) {
+        if self.renderer.show_schematic_mode {
+            self.guide_drag = None;
+            return;
+        }
 
-        // Zoom controls
-        if input.key_pressed(egui::Key::Plus) || input.key_pressed(egui::Key::Equals) {
-            let center = self.transform.viewport_size * 0.5;
-            self.transform
-                .zoom(self.zoom_sensitivity, Pos2::new(center.x, center.y));
+        let viewport_rect = response.rect;
+        // Alt places guides freely between layer boundaries
+        let snap = !ui.input(|i| i.modifiers.alt);
+        if response.drag_started_by(PointerButton::Primary) && !self.area_zoom_mode {
+            if let Some(origin) = ui.input(|i| i.pointer.press_origin()) {
+                self.guide_drag = self.guide_at(stack, origin).or_else(|| {
+                    (origin.x < viewport_rect.min.x + RULER_GRAB_WIDTH).then(|| {
+                        let mut guides = self.renderer.get_guides().to_vec();
+                        guides.push(Guide::new(self.guide_z_at(stack, origin, snap)));
+                        self.renderer.set_guides(guides);
+                        self.renderer.get_guides().len() - 1
+                    })
+                });
+            }
         }
 
-        if input.key_pressed(egui::Key::Minus) {
-            let center = self.transform.viewport_size * 0.5;
-            self.transform
-                .zoom(1.0 / self.zoom_sensitivity, Pos2::new(center.x, center.y));
+        let Some(index) = self.guide_drag else {
+            if response
+                .hover_pos()
+                .is_some_and(|pos| self.guide_at(stack, pos).is_some())
+            {
+                ui.output_mut(|output| output.cursor_icon = CursorIcon::ResizeVertical);
+            }
+            return;
+        };
+
+        let mut guides = self.renderer.get_guides().to_vec();
+        if let (Some(pos), Some(guide)) = (response.interact_pointer_pos(), guides.get_mut(index)) {
+            guide.z = self.guide_z_at(stack, pos, snap);
+            if response.drag_stopped() {
+                self.guide_drag = None;
+                if pos.x < viewport_rect.min.x + RULER_GRAB_WIDTH {
+                    guides.remove(index);
+                }
+            }
+        } else {
+            self.guide_drag = None;
         }
+        self.renderer.set_guides(guides);
+        ui.output_mut(|output| output.cursor_icon = CursorIcon::ResizeVertical);
+    }
 
-        // Pan controls
-        let pan_step = 20.0 / self.transform.scale;
-        if input.key_pressed(egui::Key::ArrowLeft) {
-            self.transform.pan(Vec2::new(pan_step, 0.0));
+    /// Height above the bottom of the stack at a screen position, rounded to 1 nm
+    ///
+    /// With `snap`, positions within `SNAP_DISTANCE` pixels of a layer boundary give the
+    /// height of the boundary.
+    pub fn guide_z_at(&self, stack: &ProcessStack, screen_pos: Pos2, snap: bool) -> f32 {
+        let world_y = self.world_y_at(stack, screen_pos, snap);
+        let z = Micrometers(f64::from(self.renderer.guide_z(stack, world_y)));
+        z.round_to_nm().um() as f32
+    }
+
+    /// World y at a screen position, on the nearest layer boundary within `SNAP_DISTANCE`
+    /// pixels with `snap`
+    fn world_y_at(&self, stack: &ProcessStack, screen_pos: Pos2, snap: bool) -> f32 {
+        let world_y = self.transform.screen_to_world(screen_pos).y;
+        if !snap {
+            return world_y;
+        }
+        self.renderer
+            .snap_to_boundary(stack, world_y, SNAP_DISTANCE / self.transform.scale)
+    }
+
+    /// Index of the guide line within grabbing distance of a screen position
+    fn guide_at(&self, stack: &ProcessStack, screen_pos: Pos2) -> Option<usize> {
+        self.renderer
+            .get_guides()
+            .iter()
+            .map(|guide| {
+                let world_y = self.renderer.guide_world_y(stack, guide.z);
+                (self.transform.world_to_screen(Pos2::new(0.0, world_y)).y - screen_pos.y).abs()
+            })
+            .enumerate()
+            .filter(|(_, distance)| *distance <= GUIDE_GRAB_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+
+    pub fn set_guides(&mut self, guides: Vec<Guide>) {
+        self.renderer.set_guides(guides);
+    }
+
+    pub fn get_guides(&self) -> &[Guide] {
+        self.renderer.get_guides()
+    }
+
+    pub fn set_structures(&mut self, structures: Vec<StructureAnnotation>) {
+        self.renderer.set_structures(structures);
+    }
+
+    pub fn set_ghost_stack(&mut self, stack: Option<ProcessStack>) {
+        self.renderer.set_ghost_stack(stack);
+    }
+
+    pub fn get_ghost_stack(&self) -> Option<&ProcessStack> {
+        self.renderer.get_ghost_stack()
+    }
+
+    pub fn get_structures(&self) -> &[StructureAnnotation] {
+        self.renderer.get_structures()
+    }
+
+    /// Pinch and ctrl+scroll zoom, two-finger pan, and wheel scrolling per the scroll behavior
+    fn handle_scroll_and_gestures(&mut self, ui: &mut egui::Ui, response: &egui::Response) {
+        let zoom_center = response
+            .hover_pos()
+            .unwrap_or_else(|| response.rect.center());
+        let (zoom_delta, touch_pan, scrolls) = ui.input(|i| {
+            // Scrolls with the zoom modifier are already part of zoom_delta
+            let scrolls: Vec<(MouseWheelUnit, Vec2)> = i
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::MouseWheel {
+                        unit,
+                        delta,
+                        modifiers,
+                    } if !modifiers.matches_any(Modifiers::COMMAND) => Some((*unit, *delta)),
+                    _ => None,
+                })
+                .collect();
+            let touch_pan = i.multi_touch().map(|touch| touch.translation_delta);
+            (i.zoom_delta(), touch_pan, scrolls)
+        });
+
+        if zoom_delta != 1.0 {
+            self.transform.zoom(zoom_delta, zoom_center);
         }
-        if input.key_pressed(egui::Key::ArrowRight) {
-            self.transform.pan(Vec2::new(-pan_step, 0.0));
+        if let Some(delta) = touch_pan {
+            self.transform.pan(delta * self.pan_sensitivity);
+        }
+
+        for (unit, delta) in scrolls {
+            if self.input_bindings.scroll_behavior.pans(unit) {
+                let pixels = match unit {
+                    MouseWheelUnit::Point => delta,
+                    MouseWheelUnit::Line => delta * LINE_SCROLL_STEP,
+                    MouseWheelUnit::Page => delta * response.rect.height(),
+                };
+                self.transform.pan(pixels * self.pan_sensitivity);
+            } else if delta.y != 0.0 {
+                // One step per wheel notch, proportional for smooth scrolling
+                let steps = match unit {
+                    MouseWheelUnit::Point => delta.y / LINE_SCROLL_STEP,
+                    MouseWheelUnit::Line | MouseWheelUnit::Page => delta.y.signum(),
+                };
+                self.transform
+                    .zoom(self.zoom_sensitivity.powf(steps), zoom_center);
+            }
+        }
+    }
+
+    fn handle_keyboard_input(&mut self, ui: &mut egui::Ui, stack: Option<&ProcessStack>) {
+        // Letter shortcuts must not fire while typing in a text field
+        if ui.ctx().wants_keyboard_input() {
+            self.end_solo();
+            return;
         }
-        if input.key_pressed(egui::Key::ArrowUp) {
-            self.transform.pan(Vec2::new(0.0, pan_step));
+        if let Some(stack) = stack {
+            let held = ui.input(|i| self.input_bindings.held(ViewCommand::Solo, i));
+            self.set_solo(stack, held);
         }
-        if input.key_pressed(egui::Key::ArrowDown) {
-            self.transform.pan(Vec2::new(0.0, -pan_step));
+        let commands = ui.input(|i| self.input_bindings.triggered(i));
+        for command in commands {
+            self.run_command(command, stack);
         }
+    }
 
-        // Reset view
-        if input.key_pressed(egui::Key::R) && input.modifiers.ctrl {
-            self.reset_view();
+    /// Apply a view command bound to a keyboard shortcut
+    ///
+    /// Fit commands need the loaded `stack` and do nothing without one.
+    pub fn run_command(&mut self, command: ViewCommand, stack: Option<&ProcessStack>) {
+        let pan_step = 20.0 / self.transform.scale;
+        match command {
+            ViewCommand::ZoomIn => self.zoom_in(),
+            ViewCommand::ZoomOut => self.zoom_out(),
+            ViewCommand::PanLeft => self.transform.pan(Vec2::new(pan_step, 0.0)),
+            ViewCommand::PanRight => self.transform.pan(Vec2::new(-pan_step, 0.0)),
+            ViewCommand::PanUp => self.transform.pan(Vec2::new(0.0, pan_step)),
+            ViewCommand::PanDown => self.transform.pan(Vec2::new(0.0, -pan_step)),
+            ViewCommand::ResetView => self.reset_view(),
+            ViewCommand::FitWidth => {
+                if let Some(stack) = stack {
+                    self.fit_to_view(stack, FitAxis::Width);
+                }
+            }
+            ViewCommand::FitHeight => {
+                if let Some(stack) = stack {
+                    self.fit_to_view(stack, FitAxis::Height);
+                }
+            }
+            // Acts while held rather than when pressed, see `set_solo`
+            ViewCommand::Solo => {}
         }
     }
 
+    /// Show only the selected layer and the nearest dielectric above and below it
+    ///
+    /// The range is fitted to the view height so thin liners fill the view. Turning solo
+    /// off restores the view and cutline from before. Does nothing without a selected layer.
+    pub fn set_solo(&mut self, stack: &ProcessStack, enabled: bool) {
+        if !enabled {
+            self.end_solo();
+            return;
+        }
+        if self.solo.is_some() {
+            return;
+        }
+        let Some(selected) = self.renderer.get_selected_layer() else {
+            return;
+        };
+        let Some(index) = stack
+            .layers
+            .iter()
+            .position(|layer| layer.name() == selected)
+        else {
+            return;
+        };
+        let is_dielectric = |layer: &&Layer| matches!(layer, Layer::Dielectric(_));
+        let selected = selected.clone();
+        let above = stack.layers[..index].iter().rev().find(is_dielectric);
+        let below = stack.layers[index + 1..].iter().find(is_dielectric);
+        let range = (
+            below.map_or(selected.clone(), |layer| layer.name().to_string()),
+            above.map_or(selected.clone(), |layer| layer.name().to_string()),
+        );
+
+        self.solo = Some(SoloState {
+            layer: selected,
+            transform: self.transform.clone(),
+            visible_range: self.renderer.get_visible_layer_range().cloned(),
+        });
+        self.renderer.set_visible_layer_range(Some(range));
+        self.invalidate_hit_index();
+        self.fit_to_view(stack, FitAxis::Height);
+    }
+
+    fn end_solo(&mut self) {
+        if let Some(solo) = self.solo.take() {
+            self.transform = solo.transform;
+            self.renderer.set_visible_layer_range(solo.visible_range);
+            self.invalidate_hit_index();
+        }
+    }
+
+    /// Layer shown alone while the solo key is held
+    pub fn get_solo_layer(&self) -> Option<&str> {
+        self.solo.as_ref().map(|solo| solo.layer.as_str())
+    }
+
     fn show_status_overlay(
         &self,
         ui: &mut egui::Ui,
@@ -196,21 +775,180 @@ impl StackViewer {
                     if let Some(selected) = self.renderer.get_selected_layer() {
                         ui.label(format!("Selected: {selected}"));
                     }
+                    if let Some(layer) = self.get_solo_layer() {
+                        ui.label(format!("Solo: {layer}"));
+                    }
 
                     ui.separator();
                     ui.label("Controls:");
-                    ui.label("• Mouse wheel: Zoom");
-                    ui.label("• Drag: Pan");
+                    ui.label(format!(
+                        "• Scroll: {}",
+                        self.input_bindings.scroll_behavior.label()
+                    ));
+                    ui.label("• Pinch / Ctrl+scroll: Zoom");
+                    if self.area_zoom_mode {
+                        ui.label("• Drag: Zoom to area");
+                    } else {
+                        ui.label("• Drag: Pan");
+                        ui.label("• Right-drag: Zoom to area");
+                    }
                     ui.label("• Click: Select layer");
-                    ui.label("• Ctrl+R: Reset view");
+                    if let Some(shortcut) = self
+                        .input_bindings
+                        .shortcuts(ViewCommand::ResetView)
+                        .first()
+                    {
+                        ui.label(format!(
+                            "• {}: Reset view",
+                            ui.ctx().format_shortcut(shortcut)
+                        ));
+                    }
+                    if let Some(shortcut) = self.input_bindings.shortcuts(ViewCommand::Solo).first()
+                    {
+                        ui.label(format!(
+                            "• Hold {}: Solo selected layer",
+                            ui.ctx().format_shortcut(shortcut)
+                        ));
+                    }
                 });
         });
     }
 
+    /// Frame timings in the top right corner, averaged over the recent frames
+    fn show_profiler_overlay(&self, ui: &mut egui::Ui, viewport_rect: egui::Rect) {
+        let (Some(latest), Some(average)) = (self.profiler.latest(), self.profiler.average())
+        else {
+            return;
+        };
+        let overlay_rect = egui::Rect::from_min_size(
+            Pos2::new(viewport_rect.max.x - 250.0, viewport_rect.min.y + 10.0),
+            Vec2::new(240.0, 140.0),
+        );
+        let millis = |duration: Duration| format!("{:.2} ms", duration.as_secs_f64() * 1000.0);
+
+        ui.scope_builder(egui::UiBuilder::new().max_rect(overlay_rect), |ui| {
+            egui::Frame::popup(ui.style())
+                .fill(Color32::from_black_alpha(200))
+                .show(ui, |ui| {
+                    ui.label(format!(
+                        "Render profile ({} frames)",
+                        self.profiler.frame_count()
+                    ));
+                    egui::Grid::new("render_profiler_grid")
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            ui.label("");
+                            ui.label("last");
+                            ui.label("avg");
+                            ui.end_row();
+                            for (stage, last, avg) in [
+                                ("Geometry", latest.geometry, average.geometry),
+                                ("Text", latest.text, average.text),
+                                ("Painting", latest.painting, average.painting),
+                                ("Total", latest.total(), average.total()),
+                            ] {
+                                ui.label(stage);
+                                ui.monospace(millis(last));
+                                ui.monospace(millis(avg));
+                                ui.end_row();
+                            }
+                        });
+                    ui.label(format!(
+                        "Geometries: {}  Shapes: {}",
+                        latest.geometry_count, latest.shape_count
+                    ));
+                    if let Some(worst) = self.profiler.worst_total() {
+                        ui.label(format!("Worst total: {}", millis(worst)));
+                    }
+                });
+        });
+    }
+
+    pub fn set_show_profiler(&mut self, show: bool) {
+        self.show_profiler = show;
+        if !show {
+            self.profiler.clear();
+        }
+    }
+
+    pub fn is_profiler_shown(&self) -> bool {
+        self.show_profiler
+    }
+
+    pub fn get_profiler(&self) -> &RenderProfiler {
+        &self.profiler
+    }
+
     pub fn auto_fit(&mut self, stack: &ProcessStack) {
         self.renderer.auto_fit(stack, &mut self.transform);
     }
 
+    /// Fit the stack to the width or height of the view only
+    pub fn fit_to_view(&mut self, stack: &ProcessStack, axis: FitAxis) {
+        self.renderer.fit_to_view(stack, &mut self.transform, axis);
+    }
+
+    /// Zoom so the dragged screen region fills the viewport
+    ///
+    /// Returns false and leaves the view unchanged for selections too small to be
+    /// deliberate, such as a click.
+    pub fn zoom_to_screen_rect(&mut self, selection: Rect, viewport_rect: Rect) -> bool {
+        if selection.width() < MIN_ZOOM_SELECTION || selection.height() < MIN_ZOOM_SELECTION {
+            return false;
+        }
+        self.transform.zoom_to_screen_rect(selection, viewport_rect);
+        true
+    }
+
+    pub fn set_area_zoom_mode(&mut self, enabled: bool) {
+        self.area_zoom_mode = enabled;
+        self.zoom_selection = None;
+    }
+
+    /// Step the view towards the nearest transform inside the view limits
+    ///
+    /// Returns whether the view is still moving.
+    pub fn settle_view(&mut self, stack: &ProcessStack, viewport_rect: Rect, dt: f32) -> bool {
+        if !self.view_limits.enabled {
+            return false;
+        }
+        let Some(fit_scale) = self.renderer.fit_scale(stack, self.transform.viewport_size) else {
+            return false;
+        };
+        let target = self.transform.constrained(
+            self.layout_stack(stack).get_bounds(),
+            viewport_rect,
+            self.view_limits.scale_range(fit_scale),
+            self.view_limits.keep_visible,
+        );
+
+        let remaining = (target.offset - self.transform.offset).length()
+            + (target.scale / self.transform.scale).ln().abs() * viewport_rect.width();
+        if remaining < 0.5 {
+            self.transform = target;
+            return false;
+        }
+        self.transform
+            .approach(&target, 1.0 - (-SNAP_BACK_RATE * dt).exp());
+        true
+    }
+
+    pub fn set_view_limits(&mut self, limits: ViewLimits) {
+        self.view_limits = limits;
+    }
+
+    pub fn get_view_limits(&self) -> &ViewLimits {
+        &self.view_limits
+    }
+
+    pub fn set_input_bindings(&mut self, bindings: InputBindings) {
+        self.input_bindings = bindings;
+    }
+
+    pub fn get_input_bindings(&self) -> &InputBindings {
+        &self.input_bindings
+    }
+
     pub fn reset_view(&mut self) {
         self.transform = ViewTransform::new(self.transform.viewport_size);
     }
@@ -223,6 +961,15 @@ impl StackViewer {
         self.renderer.get_selected_layer()
     }
 
+    /// Draw these layers like the selected one
+    pub fn set_highlighted_layers(&mut self, layer_names: Vec<String>) {
+        self.renderer.set_highlighted_layers(layer_names);
+    }
+
+    pub fn get_highlighted_layers(&self) -> &[String] {
+        self.renderer.get_highlighted_layers()
+    }
+
     pub fn set_show_dimensions(&mut self, show: bool) {
         self.renderer.set_show_dimensions(show);
     }
@@ -233,10 +980,101 @@ impl StackViewer {
 
     pub fn set_show_schematic_mode(&mut self, show: bool) {
         self.renderer.set_show_schematic_mode(show);
+        self.invalidate_hit_index();
+    }
+
+    pub fn set_show_isometric_mode(&mut self, show: bool) {
+        self.renderer.set_show_isometric_mode(show);
+        self.invalidate_hit_index();
+    }
+
+    pub fn set_show_merged_vias(&mut self, show: bool) {
+        self.renderer.set_show_merged_vias(show);
+        self.invalidate_hit_index();
+    }
+
+    pub fn set_area_accurate_vias(&mut self, area_accurate: bool) {
+        self.renderer.set_area_accurate_vias(area_accurate);
+        self.invalidate_hit_index();
+    }
+
+    pub fn set_show_hatching(&mut self, show: bool) {
+        self.renderer.set_show_hatching(show);
+    }
+
+    pub fn set_show_conformal_liners(&mut self, show: bool) {
+        self.renderer.set_show_conformal_liners(show);
+    }
+
+    pub fn set_show_outline_only(&mut self, show: bool) {
+        self.renderer.set_show_outline_only(show);
+    }
+
+    pub fn is_outline_only(&self) -> bool {
+        self.renderer.show_outline_only
+    }
+
+    pub fn set_true_sidewall_angles(&mut self, true_angles: bool) {
+        self.renderer.set_true_sidewall_angles(true_angles);
+    }
+
+    pub fn set_sidewall_exaggeration(&mut self, exaggeration: f32) {
+        self.renderer.set_sidewall_exaggeration(exaggeration);
+    }
+
+    /// Factor applied to SIDE_TANGENT when drawing, `None` while sidewalls are stylized
+    pub fn get_sidewall_exaggeration(&self) -> Option<f32> {
+        self.renderer.get_sidewall_exaggeration()
+    }
+
+    pub fn set_view_anchor(&mut self, anchor: ViewAnchor) {
+        self.renderer.set_view_anchor(anchor);
+        self.invalidate_hit_index();
+    }
+
+    pub fn set_visible_layer_range(&mut self, range: Option<(String, String)>) {
+        // A cutline picked while soloing applies once solo ends
+        if let Some(solo) = &mut self.solo {
+            solo.visible_range = range;
+            return;
+        }
+        self.renderer.set_visible_layer_range(range);
+        self.invalidate_hit_index();
+    }
+
+    /// Layout of the stack as currently shown, for exporters
+    pub fn layout_stack(&self, stack: &ProcessStack) -> StackLayout {
+        self.renderer
+            .layout_stack(stack, self.transform.viewport_size.x)
+    }
+
+    pub fn set_layer_color_overrides(&mut self, overrides: HashMap<String, Color32>) {
+        self.renderer.set_layer_color_overrides(overrides);
+    }
+
+    pub fn set_layer_class_overrides(&mut self, overrides: HashMap<String, LayerClass>) {
+        self.renderer.set_layer_class_overrides(overrides);
+    }
+
+    pub fn set_display_names(&mut self, display_names: HashMap<String, String>) {
+        self.renderer.set_display_names(display_names);
+    }
+
+    pub fn set_alpha_overrides(&mut self, overrides: HashMap<String, u8>) {
+        self.renderer.set_alpha_overrides(overrides);
+    }
+
+    pub fn set_theme(&mut self, theme: Option<Theme>) {
+        self.renderer.set_theme(theme);
+    }
+
+    pub fn get_color_scheme(&self) -> &ColorScheme {
+        self.renderer.get_color_scheme()
     }
 
     pub fn set_layer_width(&mut self, width: f32) {
         self.renderer.set_layer_width(width);
+        self.invalidate_hit_index();
     }
 
     pub fn get_zoom(&self) -> f32 {
@@ -269,9 +1107,8 @@ impl StackViewer {
     }
 
     pub fn center_on_layer(&mut self, stack: &ProcessStack, layer_name: &str) {
-        if let Some(layer) = stack.get_layer(layer_name) {
-            let layer_center_z = (layer.get_bottom_z() + layer.get_top_z()) * 0.5;
-            let world_center = Pos2::new(0.0, -(layer_center_z as f32));
+        if let Some((y_bottom, y_top)) = self.renderer.get_layer_world_y_range(stack, layer_name) {
+            let world_center = Pos2::new(0.0, (y_bottom + y_top) * 0.5);
             let screen_center = self.transform.viewport_size * 0.5;
 
             // Calculate offset to center the layer
@@ -290,6 +1127,38 @@ impl StackViewer {
     }
 }
 
+/// Badge in the bottom right corner of `rect` stating that thicknesses are not to scale
+fn paint_exaggeration_badge(ui: &egui::Ui, rect: Rect, exaggeration: f32) {
+    let galley = ui.painter().layout_no_wrap(
+        exaggeration_label(exaggeration),
+        FontId::proportional(13.0),
+        Color32::WHITE,
+    );
+    let badge = Rect::from_min_size(
+        rect.right_bottom() - galley.size() - Vec2::new(18.0, 14.0),
+        galley.size() + Vec2::new(8.0, 4.0),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(badge, 4.0, Color32::from_rgba_unmultiplied(160, 60, 0, 220));
+    painter.galley(badge.min + Vec2::new(4.0, 2.0), galley, Color32::WHITE);
+}
+
+/// Layer at a world position, rebuilding the cached hit index when the width changed
+fn cached_hit_test(
+    cache: &RefCell<Option<(f32, HitIndex)>>,
+    renderer: &StackRenderer,
+    stack: &ProcessStack,
+    width: f32,
+    world_pos: Pos2,
+) -> Option<String> {
+    let mut cache = cache.borrow_mut();
+    if !matches!(*cache, Some((cached_width, _)) if cached_width == width) {
+        *cache = Some((width, renderer.build_hit_index(stack, width)));
+    }
+    let (_, index) = cache.as_ref()?;
+    index.hit(world_pos).map(str::to_string)
+}
+
 impl Default for StackViewer {
     fn default() -> Self {
         Self::new()
@@ -394,6 +1263,55 @@ mod tests {
 
         // These methods should not panic and should update internal state
         // The actual rendering is tested in the renderer module
+
+        // Only schematic mode is stretched vertically
+        let stack = create_test_stack();
+        assert_eq!(viewer.get_vertical_exaggeration(&stack), None);
+        viewer.set_show_schematic_mode(true);
+        assert!(viewer.get_vertical_exaggeration(&stack).unwrap() > 1.0);
+    }
+
+    #[test]
+    fn test_settle_view() {
+        let mut viewer = StackViewer::new();
+        let stack = create_test_stack();
+        let viewport_rect = Rect::from_min_size(Pos2::new(300.0, 40.0), Vec2::new(800.0, 600.0));
+        viewer.transform.viewport_size = viewport_rect.size();
+        viewer.auto_fit(&stack);
+        let fit_scale = viewer.transform.scale;
+        let settle = |viewer: &mut StackViewer| {
+            for _ in 0..200 {
+                if !viewer.settle_view(&stack, viewport_rect, 0.1) {
+                    return;
+                }
+            }
+            panic!("view did not settle");
+        };
+
+        // Lost off the left edge: eased back until part of the stack shows
+        viewer.pan(Vec2::new(-5000.0, 0.0));
+        settle(&mut viewer);
+        let on_screen = viewer
+            .layout_stack(&stack)
+            .to_screen(&viewer.transform)
+            .get_bounds();
+        assert!(on_screen.intersects(viewport_rect));
+
+        // Zoomed out to a dot: back to the smallest allowed zoom
+        viewer.transform.scale = fit_scale * 1e-3;
+        settle(&mut viewer);
+        let min_zoom = viewer.get_view_limits().min_zoom;
+        assert!((viewer.get_zoom() / (fit_scale * min_zoom) - 1.0).abs() < 1e-3);
+
+        // Without limits the view stays where it was left
+        viewer.set_view_limits(ViewLimits {
+            enabled: false,
+            ..ViewLimits::default()
+        });
+        viewer.pan(Vec2::new(0.0, 5000.0));
+        let offset = viewer.transform.offset;
+        assert!(!viewer.settle_view(&stack, viewport_rect, 0.1));
+        assert_eq!(viewer.transform.offset, offset);
     }
 
     #[test]
@@ -412,6 +1330,90 @@ mod tests {
         assert_ne!(viewer.transform.offset, initial_offset);
     }
 
+    #[test]
+    fn test_split_view() {
+        let mut viewer = StackViewer::new();
+        let stack = create_test_stack();
+        let (viewport_rect, companion_rect) =
+            Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0))
+                .split_left_right_at_fraction(0.5);
+        viewer.transform.viewport_size = viewport_rect.size();
+        viewer.set_split_view(true);
+        assert!(viewer.is_split_view());
+
+        // The layer in the middle of the true-scale view is level with it in the schematic
+        for layer in ["oxide1", "metal1", "oxide2"] {
+            viewer.center_on_layer(&stack, layer);
+            let primary = viewer
+                .cursor_info_at(&stack, viewport_rect, viewport_rect.center(), false)
+                .layer;
+            assert!(primary.is_some());
+
+            let (renderer, transform) =
+                viewer.companion_view(&stack, viewport_rect, companion_rect);
+            assert!(renderer.show_schematic_mode);
+            let hit = renderer
+                .build_hit_index(&stack, companion_rect.width())
+                .hit(transform.screen_to_world(companion_rect.center()))
+                .map(str::to_string);
+            assert_eq!(hit, primary);
+        }
+    }
+
+    #[test]
+    fn test_solo() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("solo".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.3,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "liner".to_string(),
+            0.01,
+            7.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.3,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "ild".to_string(),
+            1.0,
+            4.2,
+        )));
+
+        let mut viewer = StackViewer::new();
+        viewer.auto_fit(&stack);
+        let fitted = viewer.transform.clone();
+
+        // Nothing to solo without a selection
+        viewer.set_solo(&stack, true);
+        assert_eq!(viewer.get_solo_layer(), None);
+
+        viewer.set_selected_layer(Some("metal2".to_string()));
+        viewer.set_solo(&stack, true);
+        assert_eq!(viewer.get_solo_layer(), Some("metal2"));
+        assert_eq!(
+            viewer.renderer.get_visible_layer_range(),
+            Some(&("liner".to_string(), "imd".to_string()))
+        );
+        assert!(viewer.get_zoom() > fitted.scale);
+
+        // A cutline set while soloing waits for the release
+        let cutline = ("metal1".to_string(), "metal2".to_string());
+        viewer.set_visible_layer_range(Some(cutline.clone()));
+        viewer.set_solo(&stack, false);
+        assert_eq!(viewer.get_solo_layer(), None);
+        assert_eq!(viewer.renderer.get_visible_layer_range(), Some(&cutline));
+        assert_eq!(viewer.transform.scale, fitted.scale);
+        assert_eq!(viewer.transform.offset, fitted.offset);
+    }
+
     #[test]
     fn test_center_on_layer() {
         let mut viewer = StackViewer::new();
@@ -423,6 +1425,133 @@ mod tests {
 
         // Centering should change the offset
         assert_ne!(viewer.transform.offset, initial_offset);
+
+        // The drawn layer ends up in the middle of the viewport for either anchor
+        for anchor in ViewAnchor::ALL {
+            viewer.set_view_anchor(anchor);
+            viewer.center_on_layer(&stack, "metal1");
+            let (y_bottom, y_top) = viewer
+                .renderer
+                .get_layer_world_y_range(&stack, "metal1")
+                .unwrap();
+            let screen = viewer
+                .transform
+                .world_to_screen(Pos2::new(0.0, (y_bottom + y_top) * 0.5));
+            assert!((screen.y - viewer.transform.viewport_size.y * 0.5).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_cursor_info() {
+        let mut viewer = StackViewer::new();
+        let stack = create_test_stack();
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, viewer.transform.viewport_size);
+        let center = viewport_rect.center();
+        assert!(viewer.get_cursor_info().is_none());
+
+        // Height above the substrate in the middle of the bottom layer
+        viewer.center_on_layer(&stack, "oxide2");
+        let cursor = viewer.cursor_info_at(&stack, viewport_rect, center, false);
+        assert!((cursor.z - 0.75).abs() < 1e-3);
+        assert_eq!(cursor.layer.as_deref(), Some("oxide2"));
+
+        // Depth below the top of the chip with the other anchor
+        viewer.set_view_anchor(ViewAnchor::ChipTop);
+        viewer.center_on_layer(&stack, "oxide2");
+        let cursor = viewer.cursor_info_at(&stack, viewport_rect, center, false);
+        assert!((cursor.z - 1.75).abs() < 1e-3);
+
+        // Near a layer boundary the reading snaps onto it like a guide
+        viewer.set_view_anchor(ViewAnchor::Substrate);
+        viewer.set_zoom(100.0);
+        let near_top = viewer.transform.world_to_screen(Pos2::new(0.0, -1.5)) + Vec2::new(0.0, 3.0);
+        let snapped = viewer.cursor_info_at(&stack, viewport_rect, near_top, true);
+        assert_eq!(snapped.z, 1.5);
+        let free = viewer.cursor_info_at(&stack, viewport_rect, near_top, false);
+        assert!((free.z - 1.47).abs() < 1e-3);
+        assert_eq!(free.layer, snapped.layer);
+    }
+
+    #[test]
+    fn test_guides() {
+        let mut viewer = StackViewer::new();
+        let stack = create_test_stack();
+        viewer.center_on_layer(&stack, "oxide2");
+        let center = Rect::from_min_size(Pos2::ZERO, viewer.transform.viewport_size).center();
+        assert!(viewer.guide_at(&stack, center).is_none());
+
+        // A guide at the cursor height is grabbed there, and keeps its z with either anchor
+        let z = viewer.guide_z_at(&stack, center, false);
+        assert!((z - 0.75).abs() < 1e-3);
+        viewer.set_guides(vec![Guide::new(3.0), Guide::new(z)]);
+        assert_eq!(viewer.guide_at(&stack, center), Some(1));
+        assert!(viewer
+            .guide_at(&stack, center + Vec2::new(0.0, 2.0 * GUIDE_GRAB_DISTANCE))
+            .is_none());
+
+        viewer.set_view_anchor(ViewAnchor::ChipTop);
+        viewer.center_on_layer(&stack, "oxide2");
+        assert_eq!(viewer.guide_at(&stack, center), Some(1));
+        assert_eq!(viewer.get_guides()[1].z, z);
+
+        // Close to a layer boundary the guide snaps onto it, unless snapping is off
+        viewer.set_view_anchor(ViewAnchor::Substrate);
+        viewer.set_zoom(100.0);
+        let near_top = viewer.transform.world_to_screen(Pos2::new(0.0, -1.5)) + Vec2::new(0.0, 3.0);
+        assert_eq!(viewer.guide_z_at(&stack, near_top, true), 1.5);
+        assert!((viewer.guide_z_at(&stack, near_top, false) - 1.47).abs() < 1e-3);
+        let far = near_top + Vec2::new(0.0, 2.0 * SNAP_DISTANCE);
+        assert!(viewer.guide_z_at(&stack, far, true) < 1.45);
+    }
+
+    #[test]
+    fn test_hit_index_cache() {
+        let mut viewer = StackViewer::new();
+        let mut stack = create_test_stack();
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, viewer.transform.viewport_size);
+        let center = viewport_rect.center();
+
+        viewer.center_on_layer(&stack, "oxide2");
+        let layer = |viewer: &StackViewer, stack: &ProcessStack| {
+            viewer
+                .cursor_info_at(stack, viewport_rect, center, false)
+                .layer
+        };
+        assert_eq!(layer(&viewer, &stack).as_deref(), Some("oxide2"));
+
+        // The index is in world space, so it survives panning
+        viewer.center_on_layer(&stack, "metal1");
+        assert_eq!(layer(&viewer, &stack).as_deref(), Some("metal1"));
+
+        // Stack edits are only picked up once the cache is invalidated
+        stack.layers[1] =
+            Layer::Conductor(Box::new(ConductorLayer::new("metal1a".to_string(), 0.5)));
+        assert_eq!(layer(&viewer, &stack).as_deref(), Some("metal1"));
+        viewer.invalidate_hit_index();
+        assert_eq!(layer(&viewer, &stack).as_deref(), Some("metal1a"));
+    }
+
+    #[test]
+    fn test_zoom_to_screen_rect() {
+        let mut viewer = StackViewer::new();
+        let viewport = Rect::from_min_size(Pos2::new(0.0, 30.0), Vec2::new(800.0, 600.0));
+
+        // A click-sized selection keeps the view
+        let click = Rect::from_min_size(Pos2::new(100.0, 100.0), Vec2::new(2.0, 2.0));
+        assert!(!viewer.zoom_to_screen_rect(click, viewport));
+        assert_eq!(viewer.get_zoom(), 1.0);
+
+        let selection = Rect::from_min_size(Pos2::new(100.0, 100.0), Vec2::new(80.0, 60.0));
+        let world_center = viewer.transform.screen_to_world(selection.center());
+        assert!(viewer.zoom_to_screen_rect(selection, viewport));
+        assert!((viewer.get_zoom() - 10.0).abs() < 1e-4);
+        let center = viewer.transform.world_to_screen(world_center);
+        assert!((center - viewport.center()).length() < 1e-3);
+
+        viewer.zoom_selection = Some((Pos2::ZERO, Pos2::new(10.0, 10.0)));
+        viewer.set_area_zoom_mode(true);
+        assert!(viewer.area_zoom_mode);
+        assert!(viewer.zoom_selection.is_none());
     }
 
     #[test]
@@ -437,6 +1566,34 @@ mod tests {
         assert_eq!(viewer.transform.offset, initial_offset + delta);
     }
 
+    #[test]
+    fn test_view_commands() {
+        let mut viewer = StackViewer::new();
+        let initial_offset = viewer.transform.offset;
+        let initial_zoom = viewer.get_zoom();
+
+        viewer.run_command(ViewCommand::PanLeft, None);
+        assert!(viewer.transform.offset.x > initial_offset.x);
+        viewer.run_command(ViewCommand::PanRight, None);
+        assert_eq!(viewer.transform.offset, initial_offset);
+
+        viewer.run_command(ViewCommand::ZoomIn, None);
+        assert!(viewer.get_zoom() > initial_zoom);
+        viewer.run_command(ViewCommand::ResetView, None);
+        assert_eq!(viewer.get_zoom(), initial_zoom);
+
+        // Fitting needs a stack
+        viewer.run_command(ViewCommand::FitWidth, None);
+        assert_eq!(viewer.get_zoom(), initial_zoom);
+        viewer.run_command(ViewCommand::FitHeight, Some(&create_test_stack()));
+        assert_ne!(viewer.get_zoom(), initial_zoom);
+
+        let mut bindings = InputBindings::default();
+        bindings.scroll_behavior = crate::gui::ScrollBehavior::Pan;
+        viewer.set_input_bindings(bindings.clone());
+        assert_eq!(viewer.get_input_bindings(), &bindings);
+    }
+
     #[test]
     fn test_visible_bounds() {
         let viewer = StackViewer::new();
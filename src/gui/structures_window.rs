@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use crate::renderer::{ExampleStructure, StructureAnnotation};
+use egui::{ComboBox, Context, Grid, Slider, Window};
+
+/// Placement of example structures drawn over the cross-section
+pub struct StructuresWindow {
+    open: bool,
+}
+
+impl StructuresWindow {
+    pub fn new() -> Self {
+        Self { open: false }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Show the window, editing `structures` in place; returns whether they changed
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        stack: Option<&ProcessStack>,
+        structures: &mut Vec<StructureAnnotation>,
+    ) -> bool {
+        if !self.open {
+            return false;
+        }
+
+        let conductors: Vec<&str> = stack.map_or_else(Vec::new, |stack| {
+            stack
+                .layers
+                .iter()
+                .filter(|layer| layer.is_conductor())
+                .map(|layer| layer.name())
+                .collect()
+        });
+        let mut changed = false;
+        let mut open = self.open;
+        Window::new("Example Structures")
+            .open(&mut open)
+            .default_size([420.0, 240.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label("Structures drawn with the WMIN, SMIN and via sizes of the stack, keeping their true proportions on each metal.");
+                ui.separator();
+
+                let mut remove = None;
+                Grid::new("structures_list")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, annotation) in structures.iter_mut().enumerate() {
+                            ComboBox::from_id_salt(("structure_kind", index))
+                                .selected_text(annotation.structure.label())
+                                .show_ui(ui, |ui| {
+                                    for structure in ExampleStructure::ALL {
+                                        changed |= ui
+                                            .selectable_value(
+                                                &mut annotation.structure,
+                                                structure,
+                                                structure.label(),
+                                            )
+                                            .changed();
+                                    }
+                                });
+                            changed |= ui
+                                .add(
+                                    Slider::new(&mut annotation.position, 0.0..=1.0)
+                                        .text("Position"),
+                                )
+                                .changed();
+                            if annotation.structure.uses_layer() {
+                                ComboBox::from_id_salt(("structure_layer", index))
+                                    .selected_text(annotation.layer.as_deref().unwrap_or("Top metal"))
+                                    .show_ui(ui, |ui| {
+                                        changed |= ui
+                                            .selectable_value(&mut annotation.layer, None, "Top metal")
+                                            .changed();
+                                        for &name in &conductors {
+                                            changed |= ui
+                                                .selectable_value(
+                                                    &mut annotation.layer,
+                                                    Some(name.to_string()),
+                                                    name,
+                                                )
+                                                .changed();
+                                        }
+                                    });
+                            } else {
+                                ui.label("");
+                            }
+                            if ui.small_button("Remove").clicked() {
+                                remove = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(index) = remove {
+                    structures.remove(index);
+                    changed = true;
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    for structure in ExampleStructure::ALL {
+                        if ui.button(format!("Add {}", structure.label().to_lowercase())).clicked() {
+                            structures.push(next_structure(structures, structure));
+                            changed = true;
+                        }
+                    }
+                });
+            });
+        self.open = open;
+        changed
+    }
+}
+
+/// New structure placed clear of the ones already shown
+fn next_structure(
+    structures: &[StructureAnnotation],
+    structure: ExampleStructure,
+) -> StructureAnnotation {
+    let position = [0.5, 0.25, 0.75, 0.125, 0.375, 0.625, 0.875]
+        .into_iter()
+        .find(|position| {
+            structures
+                .iter()
+                .all(|existing| (existing.position - position).abs() > 0.05)
+        })
+        .unwrap_or(0.5);
+    StructureAnnotation::new(structure, position)
+}
+
+impl Default for StructuresWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_structure() {
+        let mut structures = Vec::new();
+        for structure in ExampleStructure::ALL {
+            structures.push(next_structure(&structures, structure));
+        }
+        let positions: Vec<f32> = structures.iter().map(|s| s.position).collect();
+        assert_eq!(positions, [0.5, 0.25, 0.75]);
+        assert_eq!(structures[1].structure, ExampleStructure::PowerLine);
+        assert_eq!(structures[1].layer, None);
+    }
+}
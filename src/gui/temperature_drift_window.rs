@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{ProcessStack, DRIFT_TEMPERATURE_RANGE};
+use egui::{
+    vec2, Color32, Context, Grid, Pos2, Rect, Response, ScrollArea, Sense, Stroke, Ui, Window,
+};
+
+const SPARKLINE_SIZE: [f32; 2] = [140.0, 22.0];
+
+/// Resistance drift of each conductor over temperature, worst case first
+pub struct TemperatureDriftWindow {
+    open: bool,
+}
+
+impl TemperatureDriftWindow {
+    pub fn new() -> Self {
+        Self { open: false }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Show the window, returning the layer whose name was clicked
+    pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) -> Option<String> {
+        if !self.open {
+            return None;
+        }
+
+        let mut clicked = None;
+        let mut open = self.open;
+        let (cold, hot) = DRIFT_TEMPERATURE_RANGE;
+        Window::new("Temperature Drift")
+            .open(&mut open)
+            .default_size([560.0, 320.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let Some(stack) = stack else {
+                    ui.label("Load a stack to see the drift of its conductors.");
+                    return;
+                };
+                let summary = stack.get_temperature_drift();
+                if summary.conductors.is_empty() {
+                    ui.label("No conductor defines CRT1, CRT2 or CRT_VS_SI_WIDTH.");
+                    return;
+                }
+                ui.label(format!(
+                    "Resistance change from {cold:.0} to {hot:.0}, relative to {:.0}",
+                    summary.reference
+                ));
+                if let Some(worst) = summary.worst() {
+                    ui.label(format!(
+                        "Worst case: {} {:+.2} % at {:.0}",
+                        worst.layer_name, worst.worst_drift_percent, worst.worst_temperature
+                    ));
+                }
+                ui.separator();
+
+                // One scale for every sparkline so their slopes compare
+                let scale = summary
+                    .conductors
+                    .iter()
+                    .map(|drift| drift.worst_drift_percent.abs())
+                    .fold(0.0, f64::max);
+                ScrollArea::vertical().show(ui, |ui| {
+                    Grid::new("temperature_drift_grid")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Layer");
+                            ui.strong(format!("{cold:.0}"));
+                            ui.strong(format!("{hot:.0}"));
+                            ui.strong("Worst");
+                            ui.strong("Drift");
+                            ui.end_row();
+
+                            for drift in &summary.conductors {
+                                let crt = match drift.crt_width {
+                                    Some(width) => format!("CRT_VS_SI_WIDTH at width {width} um"),
+                                    None => "CRT1/CRT2".to_string(),
+                                };
+                                if ui
+                                    .link(&drift.layer_name)
+                                    .on_hover_text(format!(
+                                        "CRT1 {:e}, CRT2 {:e} from {crt}",
+                                        drift.crt1, drift.crt2
+                                    ))
+                                    .clicked()
+                                {
+                                    clicked = Some(drift.layer_name.clone());
+                                }
+                                let first = drift.drift_percent.first().copied().unwrap_or(0.0);
+                                let last = drift.drift_percent.last().copied().unwrap_or(0.0);
+                                ui.label(format!("{first:+.2} %"));
+                                ui.label(format!("{last:+.2} %"));
+                                ui.label(format!(
+                                    "{:+.2} % at {:.0}",
+                                    drift.worst_drift_percent, drift.worst_temperature
+                                ));
+                                sparkline(ui, &drift.drift_percent, scale);
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+        clicked
+    }
+}
+
+impl Default for TemperatureDriftWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Small line plot of `values` between `-scale` and `scale`, with the zero line
+pub fn sparkline(ui: &mut Ui, values: &[f64], scale: f64) -> Response {
+    let (rect, response) =
+        ui.allocate_exact_size(vec2(SPARKLINE_SIZE[0], SPARKLINE_SIZE[1]), Sense::hover());
+    let points = sparkline_points(rect, values, scale);
+    let painter = ui.painter_at(rect);
+    painter.hline(
+        rect.x_range(),
+        rect.center().y,
+        Stroke::new(1.0, ui.visuals().weak_text_color()),
+    );
+    painter.line(points, Stroke::new(1.5, Color32::from_rgb(230, 140, 40)));
+    response
+}
+
+/// Screen points of a sparkline in `rect`, zero at its vertical center
+pub fn sparkline_points(rect: Rect, values: &[f64], scale: f64) -> Vec<Pos2> {
+    let last = values.len().saturating_sub(1).max(1) as f32;
+    let half_height = rect.height() * 0.5;
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let normalized = if scale > 0.0 {
+                (value / scale) as f32
+            } else {
+                0.0
+            };
+            Pos2::new(
+                rect.left() + rect.width() * index as f32 / last,
+                rect.center().y - normalized.clamp(-1.0, 1.0) * half_height,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_points() {
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 20.0));
+        let points = sparkline_points(rect, &[-10.0, 0.0, 20.0], 20.0);
+        assert_eq!(
+            points,
+            vec![
+                Pos2::new(0.0, 15.0),
+                Pos2::new(50.0, 10.0),
+                Pos2::new(100.0, 0.0)
+            ]
+        );
+        // Flat without a scale
+        assert!(sparkline_points(rect, &[1.0, 2.0], 0.0)
+            .iter()
+            .all(|p| p.y == 10.0));
+
+        let mut window = TemperatureDriftWindow::new();
+        assert!(!window.is_open());
+        window.set_open(true);
+        assert!(window.is_open());
+    }
+}
@@ -1,13 +1,52 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use egui::{Context, Slider, TopBottomPanel};
+use crate::export::ExportFormat;
+use crate::renderer::{ViewAnchor, SIDEWALL_EXAGGERATION_RANGE};
+use crate::utils::{ExampleStack, StackTemplate};
+use egui::{Color32, Context, RichText, Slider, TopBottomPanel};
 
 pub struct Toolbar {
     pub show_dimensions: bool,
     pub show_layer_names: bool,
     pub show_schematic_mode: bool,
+    pub show_split_view: bool,
+    pub show_merged_vias: bool,
+    pub area_accurate_vias: bool,
+    pub show_isometric_mode: bool,
+    pub area_zoom_mode: bool,
+    pub show_hatching: bool,
+    pub show_conformal_liners: bool,
+    pub show_outline_only: bool,
+    pub true_sidewall_angles: bool,
+    pub sidewall_exaggeration: f32,
+    pub apply_half_node_scale: bool,
+    /// HALF_NODE_SCALE_FACTOR of the loaded stack; the toggle is only shown when set
+    pub half_node_scale_factor: Option<f64>,
+    pub view_anchor: ViewAnchor,
     pub show_resistance_calculator: bool,
+    pub show_search: bool,
+    pub show_via_chain: bool,
+    pub show_via_resistance: bool,
+    pub show_problems: bool,
+    pub show_source: bool,
+    pub show_histogram: bool,
+    pub show_temperature_drift: bool,
+    pub show_naming: bool,
+    pub show_guides: bool,
+    pub show_structures: bool,
+    pub show_resources: bool,
+    pub show_history: bool,
+    pub show_density_strip: bool,
+    pub show_legend: bool,
+    pub show_profiler: bool,
+    pub undo_description: Option<String>,
+    pub redo_description: Option<String>,
+    /// Name or file name of the loaded theme, none for the built-in colors
+    pub theme_name: Option<String>,
+    pub template_metal_count: usize,
+    pub random_seed: u64,
+    pub random_layer_count: usize,
     pub layer_width: f32,
     pub zoom_level: f32,
 }
@@ -18,7 +57,41 @@ impl Toolbar {
             show_dimensions: true,
             show_layer_names: true,
             show_schematic_mode: false,
+            show_split_view: false,
+            show_merged_vias: true,
+            area_accurate_vias: false,
+            show_isometric_mode: false,
+            area_zoom_mode: false,
+            show_hatching: false,
+            show_conformal_liners: false,
+            show_outline_only: false,
+            true_sidewall_angles: false,
+            sidewall_exaggeration: 1.0,
+            apply_half_node_scale: false,
+            half_node_scale_factor: None,
+            view_anchor: ViewAnchor::Substrate,
             show_resistance_calculator: false,
+            show_search: false,
+            show_via_chain: false,
+            show_via_resistance: false,
+            show_problems: false,
+            show_source: false,
+            show_histogram: false,
+            show_temperature_drift: false,
+            show_naming: false,
+            show_guides: false,
+            show_structures: false,
+            show_resources: false,
+            show_history: false,
+            show_density_strip: false,
+            show_legend: false,
+            show_profiler: false,
+            undo_description: None,
+            redo_description: None,
+            theme_name: None,
+            template_metal_count: 5,
+            random_seed: 1,
+            random_layer_count: 21,
             layer_width: 200.0,
             zoom_level: 1.0,
         }
@@ -39,8 +112,123 @@ impl Toolbar {
                             ui.close();
                         }
 
+                        if ui
+                            .add(
+                                egui::Button::new("Paste ITF from Clipboard")
+                                    .shortcut_text("Ctrl+Shift+V"),
+                            )
+                            .on_hover_text("Show a technology file snippet copied from a mail or chat")
+                            .clicked()
+                        {
+                            action = ToolbarAction::PasteItf;
+                            ui.close();
+                        }
+
+                        if ui
+                            .button("Import CSV Stack...")
+                            .on_hover_text("One layer per row: name,type,thickness,er,rpsq")
+                            .clicked()
+                        {
+                            action = ToolbarAction::ImportCsv;
+                            ui.close();
+                        }
+
+                        if ui
+                            .button("Bond Stack Face-to-Face...")
+                            .on_hover_text(
+                                "Flip a second ITF file onto the loaded stack, as in a bonded chiplet",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::BondStack;
+                            ui.close();
+                        }
+
+                        ui.menu_button("New from Template", |ui| {
+                            ui.add(
+                                Slider::new(&mut self.template_metal_count, 1..=12)
+                                    .text("Metal layers"),
+                            );
+                            ui.separator();
+
+                            for template in StackTemplate::ALL {
+                                if ui
+                                    .button(template.label())
+                                    .on_hover_text(template.description())
+                                    .clicked()
+                                {
+                                    action = ToolbarAction::NewFromTemplate(
+                                        template,
+                                        self.template_metal_count,
+                                    );
+                                    ui.close();
+                                }
+                            }
+                        });
+
+                        if ui.button("Export Layer Table...").clicked() {
+                            action = ToolbarAction::ExportLayerTable;
+                            ui.close();
+                        }
+
+                        if ui
+                            .button("Export DXF...")
+                            .on_hover_text("Cross-section as shown, for CAD tools")
+                            .clicked()
+                        {
+                            action = ToolbarAction::Export(ExportFormat::Dxf);
+                            ui.close();
+                        }
+
+                        if ui
+                            .button("Export GDS...")
+                            .on_hover_text(
+                                "Cross-section as GDSII polygons, one layer per ITF layer",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::Export(ExportFormat::Gds);
+                            ui.close();
+                        }
+
+                        if ui
+                            .button("Export Interactive HTML...")
+                            .on_hover_text(
+                                "Single HTML file to pan, zoom and hover the stack in a browser",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::Export(ExportFormat::Html);
+                            ui.close();
+                        }
+
+                        if ui
+                            .button("Export for Slides...")
+                            .on_hover_text(
+                                "16:9 slide with title and legend, as EMF or 4x resolution PNG",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::Export(ExportFormat::default_slide());
+                            ui.close();
+                        }
+
+                        if ui
+                            .button("Copy Slide Image")
+                            .on_hover_text("4x resolution slide image to paste into a presentation")
+                            .clicked()
+                        {
+                            action = ToolbarAction::CopySlideImage;
+                            ui.close();
+                        }
+
                         ui.separator();
 
+                        if ui.button("Preferences...").clicked() {
+                            action = ToolbarAction::OpenPreferences;
+                            ui.close();
+                        }
+
                         if ui.button("Exit").clicked() {
                             action = ToolbarAction::Exit;
                             ui.close();
@@ -49,6 +237,41 @@ impl Toolbar {
 
                     ui.separator();
 
+                    // Edit history
+                    ui.menu_button("Edit", |ui| {
+                        let undo_label = match self.undo_description {
+                            Some(ref description) => format!("Undo {description}"),
+                            None => "Undo".to_string(),
+                        };
+                        if ui
+                            .add_enabled(
+                                self.undo_description.is_some(),
+                                egui::Button::new(undo_label).shortcut_text("Ctrl+Z"),
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::Undo;
+                            ui.close();
+                        }
+
+                        let redo_label = match self.redo_description {
+                            Some(ref description) => format!("Redo {description}"),
+                            None => "Redo".to_string(),
+                        };
+                        if ui
+                            .add_enabled(
+                                self.redo_description.is_some(),
+                                egui::Button::new(redo_label).shortcut_text("Ctrl+Y"),
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::Redo;
+                            ui.close();
+                        }
+                    });
+
+                    ui.separator();
+
                     // Tools menu
                     ui.menu_button("Tools", |ui| {
                         if ui
@@ -62,6 +285,116 @@ impl Toolbar {
                                 self.show_resistance_calculator,
                             );
                         }
+
+                        if ui
+                            .checkbox(&mut self.show_via_resistance, "Via Resistance Calculator")
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleViaResistance(self.show_via_resistance);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_search, "Search (Ctrl+F)")
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleSearch(self.show_search);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_via_chain, "Via Chain Resistance")
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleViaChain(self.show_via_chain);
+                        }
+
+                        if ui.checkbox(&mut self.show_problems, "Problems").clicked() {
+                            action = ToolbarAction::ToggleProblems(self.show_problems);
+                        }
+
+                        if ui.checkbox(&mut self.show_source, "Source").clicked() {
+                            action = ToolbarAction::ToggleSource(self.show_source);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_histogram, "Property Histogram")
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleHistogram(self.show_histogram);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_temperature_drift, "Temperature Drift")
+                            .clicked()
+                        {
+                            action =
+                                ToolbarAction::ToggleTemperatureDrift(self.show_temperature_drift);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_naming, "Naming Conventions")
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleNaming(self.show_naming);
+                        }
+
+                        if ui.checkbox(&mut self.show_guides, "Guides").clicked() {
+                            action = ToolbarAction::ToggleGuides(self.show_guides);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_structures, "Example Structures")
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleStructures(self.show_structures);
+                        }
+
+                        if ui.checkbox(&mut self.show_resources, "Resources").clicked() {
+                            action = ToolbarAction::ToggleResources(self.show_resources);
+                        }
+
+                        if ui.checkbox(&mut self.show_history, "File History").clicked() {
+                            action = ToolbarAction::ToggleHistory(self.show_history);
+                        }
+                    });
+
+                    ui.separator();
+
+                    // Debug tools; the profiler also helps with slow stacks in release builds
+                    ui.menu_button("Debug", |ui| {
+                        if ui
+                            .checkbox(&mut self.show_profiler, "Render Profiler")
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleProfiler(self.show_profiler);
+                        }
+                        if ui.button("Open Log").clicked() {
+                            action = ToolbarAction::OpenLog;
+                            ui.close();
+                        }
+
+                        // Random stacks only in debug builds
+                        if cfg!(debug_assertions) {
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                ui.label("Seed:");
+                                ui.add(egui::DragValue::new(&mut self.random_seed));
+                            });
+                            ui.add(
+                                Slider::new(&mut self.random_layer_count, 1..=400)
+                                    .text("Layers")
+                                    .logarithmic(true),
+                            );
+
+                            if ui.button("Load Random Stack").clicked() {
+                                action = ToolbarAction::LoadRandomStack {
+                                    seed: self.random_seed,
+                                    layer_count: self.random_layer_count,
+                                };
+                                // Next click gives a different stack
+                                self.random_seed = self.random_seed.wrapping_add(1);
+                                ui.close();
+                            }
+                        }
                     });
 
                     ui.separator();
@@ -82,6 +415,144 @@ impl Toolbar {
                             action = ToolbarAction::ToggleLayerNames(self.show_layer_names);
                         }
 
+                        if ui
+                            .checkbox(&mut self.show_merged_vias, "Merge Via Arrays")
+                            .on_hover_text(
+                                "Draw via arrays as a single bar instead of discrete cuts",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleMergedVias(self.show_merged_vias);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.area_accurate_vias, "Area-Accurate Vias")
+                            .on_hover_text(
+                                "Draw vias sqrt(AREA) wide at the scale of the metal widths \
+                                 instead of filling the narrowest metal edge",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleAreaAccurateVias(self.area_accurate_vias);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_hatching, "Hatch Dielectrics")
+                            .on_hover_text(
+                                "Texture nitrides, low-k dielectrics and the substrate, \
+                                 for grayscale prints and colorblind viewing",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleHatching(self.show_hatching);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_conformal_liners, "Conformal Liners")
+                            .on_hover_text(
+                                "Outline the SW_T/TW_T dielectrics around their conductors",
+                            )
+                            .clicked()
+                        {
+                            action =
+                                ToolbarAction::ToggleConformalLiners(self.show_conformal_liners);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_outline_only, "Outline Only")
+                            .on_hover_text(
+                                "Draw layers and vias as outlines without fills, for dense \
+                                 stacks and printing; GDS exports write outlines too",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleOutlineOnly(self.show_outline_only);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.true_sidewall_angles, "True Sidewall Angles")
+                            .on_hover_text(
+                                "Draw conductor sidewalls at their SIDE_TANGENT slope; \
+                                 otherwise the taper is exaggerated to show its direction",
+                            )
+                            .clicked()
+                        {
+                            action =
+                                ToolbarAction::ToggleTrueSidewallAngles(self.true_sidewall_angles);
+                        }
+                        if ui
+                            .add_enabled(
+                                self.true_sidewall_angles,
+                                Slider::new(
+                                    &mut self.sidewall_exaggeration,
+                                    SIDEWALL_EXAGGERATION_RANGE,
+                                )
+                                .step_by(0.5)
+                                .suffix("×")
+                                .text("Exaggeration"),
+                            )
+                            .on_hover_text("1× draws sidewalls physically accurate")
+                            .changed()
+                        {
+                            action =
+                                ToolbarAction::SetSidewallExaggeration(self.sidewall_exaggeration);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_density_strip, "Metal Density Strip")
+                            .on_hover_text(
+                                "Assumed fill density per metal and the thickness it leads to",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleDensityStrip(self.show_density_strip);
+                        }
+
+                        if ui
+                            .checkbox(&mut self.show_legend, "Legend")
+                            .on_hover_text(
+                                "Layer colors; click an entry to isolate it, Shift+click to add",
+                            )
+                            .clicked()
+                        {
+                            action = ToolbarAction::ToggleLegend(self.show_legend);
+                        }
+
+                        ui.menu_button("Anchor y = 0 at", |ui| {
+                            for anchor in ViewAnchor::ALL {
+                                if ui
+                                    .radio_value(&mut self.view_anchor, anchor, anchor.label())
+                                    .clicked()
+                                {
+                                    action = ToolbarAction::SetViewAnchor(anchor);
+                                    ui.close();
+                                }
+                            }
+                        });
+
+                        ui.separator();
+
+                        if ui
+                            .button("Load Theme...")
+                            .on_hover_text("Layer class colors and hatches from a TOML or JSON file")
+                            .clicked()
+                        {
+                            action = ToolbarAction::LoadTheme;
+                            ui.close();
+                        }
+
+                        let clear_label = match self.theme_name {
+                            Some(ref name) => format!("Clear Theme ({name})"),
+                            None => "Clear Theme".to_string(),
+                        };
+                        if ui
+                            .add_enabled(self.theme_name.is_some(), egui::Button::new(clear_label))
+                            .clicked()
+                        {
+                            action = ToolbarAction::ClearTheme;
+                            ui.close();
+                        }
+
                         ui.separator();
 
                         if ui.button("Auto Fit").clicked() {
@@ -89,6 +560,24 @@ impl Toolbar {
                             ui.close();
                         }
 
+                        if ui
+                            .button("Fit Width")
+                            .on_hover_text("Fit horizontally, keeping the vertical position")
+                            .clicked()
+                        {
+                            action = ToolbarAction::FitWidth;
+                            ui.close();
+                        }
+
+                        if ui
+                            .button("Fit Height")
+                            .on_hover_text("Fit vertically, keeping the horizontal position")
+                            .clicked()
+                        {
+                            action = ToolbarAction::FitHeight;
+                            ui.close();
+                        }
+
                         if ui.button("Reset View").clicked() {
                             action = ToolbarAction::ResetView;
                             ui.close();
@@ -109,6 +598,23 @@ impl Toolbar {
 
                     ui.separator();
 
+                    ui.menu_button("Help", |ui| {
+                        ui.menu_button("Open Example", |ui| {
+                            for example in ExampleStack::ALL {
+                                if ui
+                                    .button(example.label())
+                                    .on_hover_text(example.description())
+                                    .clicked()
+                                {
+                                    action = ToolbarAction::OpenExample(example);
+                                    ui.close();
+                                }
+                            }
+                        });
+                    });
+
+                    ui.separator();
+
                     if ui.button("Zoom+").on_hover_text("Zoom in").clicked() {
                         action = ToolbarAction::ZoomIn;
                     }
@@ -172,6 +678,44 @@ impl Toolbar {
                     if schematic_response.clicked() {
                         action = ToolbarAction::ToggleSchematicMode(self.show_schematic_mode);
                     }
+
+                    let split_response = ui
+                        .checkbox(&mut self.show_split_view, "Split View")
+                        .on_hover_text("Show the schematic and true-scale stacks side by side");
+                    if split_response.clicked() {
+                        action = ToolbarAction::ToggleSplitView(self.show_split_view);
+                    }
+
+                    let isometric_response = ui.checkbox(&mut self.show_isometric_mode, "3D View");
+                    if isometric_response.clicked() {
+                        action = ToolbarAction::ToggleIsometricMode(self.show_isometric_mode);
+                    }
+
+                    let area_zoom_response = ui
+                        .checkbox(&mut self.area_zoom_mode, "Area Zoom")
+                        .on_hover_text(
+                        "Drag a rectangle to zoom into it; right-drag does the same in any mode",
+                    );
+                    if area_zoom_response.clicked() {
+                        action = ToolbarAction::ToggleAreaZoom(self.area_zoom_mode);
+                    }
+
+                    if let Some(factor) = self.half_node_scale_factor {
+                        let half_node_response = ui
+                            .checkbox(
+                                &mut self.apply_half_node_scale,
+                                format!("Half-Node ×{factor}"),
+                            )
+                            .on_hover_text(
+                                "Show layout widths and spacings scaled by HALF_NODE_SCALE_FACTOR",
+                            );
+                        if half_node_response.clicked() {
+                            action = ToolbarAction::ToggleHalfNodeScale(self.apply_half_node_scale);
+                        }
+                        if self.apply_half_node_scale {
+                            ui.label(RichText::new("Scaled").color(Color32::from_rgb(255, 165, 0)));
+                        }
+                    }
                 });
             });
 
@@ -194,6 +738,58 @@ impl Toolbar {
         self.show_schematic_mode = show;
     }
 
+    pub fn set_show_split_view(&mut self, show: bool) {
+        self.show_split_view = show;
+    }
+
+    pub fn set_show_isometric_mode(&mut self, show: bool) {
+        self.show_isometric_mode = show;
+    }
+
+    pub fn set_show_merged_vias(&mut self, show: bool) {
+        self.show_merged_vias = show;
+    }
+
+    pub fn set_area_accurate_vias(&mut self, area_accurate: bool) {
+        self.area_accurate_vias = area_accurate;
+    }
+
+    pub fn set_area_zoom_mode(&mut self, enabled: bool) {
+        self.area_zoom_mode = enabled;
+    }
+
+    pub fn set_show_hatching(&mut self, show: bool) {
+        self.show_hatching = show;
+    }
+
+    pub fn set_show_conformal_liners(&mut self, show: bool) {
+        self.show_conformal_liners = show;
+    }
+
+    pub fn set_show_outline_only(&mut self, show: bool) {
+        self.show_outline_only = show;
+    }
+
+    pub fn set_true_sidewall_angles(&mut self, true_angles: bool) {
+        self.true_sidewall_angles = true_angles;
+    }
+
+    pub fn set_sidewall_exaggeration(&mut self, exaggeration: f32) {
+        self.sidewall_exaggeration = exaggeration;
+    }
+
+    pub fn set_apply_half_node_scale(&mut self, apply: bool) {
+        self.apply_half_node_scale = apply;
+    }
+
+    pub fn set_half_node_scale_factor(&mut self, factor: Option<f64>) {
+        self.half_node_scale_factor = factor;
+    }
+
+    pub fn set_view_anchor(&mut self, anchor: ViewAnchor) {
+        self.view_anchor = anchor;
+    }
+
     pub fn set_layer_width(&mut self, width: f32) {
         self.layer_width = width;
     }
@@ -201,6 +797,75 @@ impl Toolbar {
     pub fn set_show_resistance_calculator(&mut self, show: bool) {
         self.show_resistance_calculator = show;
     }
+
+    pub fn set_show_search(&mut self, show: bool) {
+        self.show_search = show;
+    }
+
+    pub fn set_show_via_chain(&mut self, show: bool) {
+        self.show_via_chain = show;
+    }
+
+    pub fn set_show_via_resistance(&mut self, show: bool) {
+        self.show_via_resistance = show;
+    }
+
+    pub fn set_show_problems(&mut self, show: bool) {
+        self.show_problems = show;
+    }
+
+    pub fn set_show_source(&mut self, show: bool) {
+        self.show_source = show;
+    }
+
+    pub fn set_show_histogram(&mut self, show: bool) {
+        self.show_histogram = show;
+    }
+
+    pub fn set_show_temperature_drift(&mut self, show: bool) {
+        self.show_temperature_drift = show;
+    }
+
+    pub fn set_show_naming(&mut self, show: bool) {
+        self.show_naming = show;
+    }
+
+    pub fn set_show_guides(&mut self, show: bool) {
+        self.show_guides = show;
+    }
+
+    pub fn set_show_structures(&mut self, show: bool) {
+        self.show_structures = show;
+    }
+
+    pub fn set_show_resources(&mut self, show: bool) {
+        self.show_resources = show;
+    }
+
+    pub fn set_show_history(&mut self, show: bool) {
+        self.show_history = show;
+    }
+
+    pub fn set_show_density_strip(&mut self, show: bool) {
+        self.show_density_strip = show;
+    }
+
+    pub fn set_show_legend(&mut self, show: bool) {
+        self.show_legend = show;
+    }
+
+    pub fn set_show_profiler(&mut self, show: bool) {
+        self.show_profiler = show;
+    }
+
+    pub fn set_history(&mut self, undo: Option<String>, redo: Option<String>) {
+        self.undo_description = undo;
+        self.redo_description = redo;
+    }
+
+    pub fn set_theme_name(&mut self, name: Option<String>) {
+        self.theme_name = name;
+    }
 }
 
 impl Default for Toolbar {
@@ -213,8 +878,28 @@ impl Default for Toolbar {
 pub enum ToolbarAction {
     None,
     OpenFile,
+    /// Read the clipboard text as an ITF file
+    PasteItf,
+    ImportCsv,
+    BondStack,
+    /// Template and requested number of metal layers
+    NewFromTemplate(StackTemplate, usize),
+    OpenExample(ExampleStack),
+    LoadRandomStack {
+        seed: u64,
+        layer_count: usize,
+    },
+    ExportLayerTable,
+    Export(ExportFormat),
+    CopySlideImage,
+    OpenPreferences,
+    OpenLog,
     Exit,
+    Undo,
+    Redo,
     AutoFit,
+    FitWidth,
+    FitHeight,
     ResetView,
     ZoomIn,
     ZoomOut,
@@ -223,7 +908,36 @@ pub enum ToolbarAction {
     ToggleDimensions(bool),
     ToggleLayerNames(bool),
     ToggleSchematicMode(bool),
+    ToggleSplitView(bool),
+    ToggleMergedVias(bool),
+    ToggleAreaAccurateVias(bool),
+    ToggleHatching(bool),
+    ToggleConformalLiners(bool),
+    ToggleOutlineOnly(bool),
+    ToggleTrueSidewallAngles(bool),
+    SetSidewallExaggeration(f32),
+    ToggleHalfNodeScale(bool),
+    ToggleDensityStrip(bool),
+    ToggleLegend(bool),
+    SetViewAnchor(ViewAnchor),
+    LoadTheme,
+    ClearTheme,
+    ToggleIsometricMode(bool),
+    ToggleAreaZoom(bool),
     ToggleResistanceCalculator(bool),
+    ToggleViaResistance(bool),
+    ToggleSearch(bool),
+    ToggleViaChain(bool),
+    ToggleProblems(bool),
+    ToggleSource(bool),
+    ToggleHistogram(bool),
+    ToggleTemperatureDrift(bool),
+    ToggleNaming(bool),
+    ToggleGuides(bool),
+    ToggleStructures(bool),
+    ToggleResources(bool),
+    ToggleHistory(bool),
+    ToggleProfiler(bool),
 }
 
 #[cfg(test)]
@@ -252,6 +966,36 @@ mod tests {
         toolbar.set_show_layer_names(false);
         assert!(!toolbar.show_layer_names);
 
+        toolbar.set_show_isometric_mode(true);
+        assert!(toolbar.show_isometric_mode);
+
+        toolbar.set_show_merged_vias(false);
+        assert!(!toolbar.show_merged_vias);
+
+        toolbar.set_area_accurate_vias(true);
+        assert!(toolbar.area_accurate_vias);
+
+        toolbar.set_show_hatching(true);
+        assert!(toolbar.show_hatching);
+
+        toolbar.set_show_conformal_liners(true);
+        assert!(toolbar.show_conformal_liners);
+
+        toolbar.set_show_outline_only(true);
+        assert!(toolbar.show_outline_only);
+
+        toolbar.set_show_split_view(true);
+        assert!(toolbar.show_split_view);
+
+        toolbar.set_true_sidewall_angles(true);
+        assert!(toolbar.true_sidewall_angles);
+
+        toolbar.set_sidewall_exaggeration(2.5);
+        assert_eq!(toolbar.sidewall_exaggeration, 2.5);
+
+        toolbar.set_apply_half_node_scale(true);
+        assert!(toolbar.apply_half_node_scale);
+
         toolbar.set_layer_width(350.0);
         assert_eq!(toolbar.layer_width, 350.0);
     }
@@ -309,8 +1053,29 @@ mod tests {
         let actions = vec![
             ToolbarAction::None,
             ToolbarAction::OpenFile,
+            ToolbarAction::PasteItf,
+            ToolbarAction::ImportCsv,
+            ToolbarAction::BondStack,
+            ToolbarAction::NewFromTemplate(StackTemplate::Interposer, 2),
+            ToolbarAction::OpenExample(ExampleStack::Simple),
+            ToolbarAction::LoadRandomStack {
+                seed: 7,
+                layer_count: 9,
+            },
+            ToolbarAction::ExportLayerTable,
+            ToolbarAction::Export(ExportFormat::Dxf),
+            ToolbarAction::Export(ExportFormat::Gds),
+            ToolbarAction::Export(ExportFormat::Html),
+            ToolbarAction::Export(ExportFormat::Emf),
+            ToolbarAction::CopySlideImage,
+            ToolbarAction::OpenPreferences,
+            ToolbarAction::OpenLog,
             ToolbarAction::Exit,
+            ToolbarAction::Undo,
+            ToolbarAction::Redo,
             ToolbarAction::AutoFit,
+            ToolbarAction::FitWidth,
+            ToolbarAction::FitHeight,
             ToolbarAction::ResetView,
             ToolbarAction::ZoomIn,
             ToolbarAction::ZoomOut,
@@ -318,15 +1083,59 @@ mod tests {
             ToolbarAction::SetLayerWidth(300.0),
             ToolbarAction::ToggleDimensions(false),
             ToolbarAction::ToggleLayerNames(true),
+            ToolbarAction::ToggleSplitView(true),
+            ToolbarAction::ToggleMergedVias(false),
+            ToolbarAction::ToggleAreaAccurateVias(true),
+            ToolbarAction::ToggleHatching(true),
+            ToolbarAction::ToggleConformalLiners(true),
+            ToolbarAction::ToggleOutlineOnly(true),
+            ToolbarAction::ToggleTrueSidewallAngles(true),
+            ToolbarAction::SetSidewallExaggeration(2.0),
+            ToolbarAction::ToggleHalfNodeScale(true),
+            ToolbarAction::ToggleDensityStrip(true),
+            ToolbarAction::ToggleLegend(true),
+            ToolbarAction::SetViewAnchor(ViewAnchor::ChipTop),
+            ToolbarAction::LoadTheme,
+            ToolbarAction::ClearTheme,
+            ToolbarAction::ToggleIsometricMode(true),
+            ToolbarAction::ToggleAreaZoom(true),
             ToolbarAction::ToggleResistanceCalculator(true),
+            ToolbarAction::ToggleViaResistance(true),
+            ToolbarAction::ToggleSearch(true),
+            ToolbarAction::ToggleViaChain(true),
+            ToolbarAction::ToggleProblems(true),
+            ToolbarAction::ToggleSource(true),
+            ToolbarAction::ToggleHistogram(true),
+            ToolbarAction::ToggleTemperatureDrift(true),
+            ToolbarAction::ToggleNaming(true),
+            ToolbarAction::ToggleGuides(true),
+            ToolbarAction::ToggleStructures(true),
+            ToolbarAction::ToggleResources(true),
+            ToolbarAction::ToggleHistory(true),
+            ToolbarAction::ToggleProfiler(true),
         ];
 
         for action in actions {
             match action {
                 ToolbarAction::None => {}
                 ToolbarAction::OpenFile => {}
+                ToolbarAction::PasteItf => {}
+                ToolbarAction::ImportCsv => {}
+                ToolbarAction::BondStack => {}
+                ToolbarAction::NewFromTemplate(_, _) => {}
+                ToolbarAction::OpenExample(_) => {}
+                ToolbarAction::LoadRandomStack { .. } => {}
+                ToolbarAction::ExportLayerTable => {}
+                ToolbarAction::Export(_) => {}
+                ToolbarAction::CopySlideImage => {}
+                ToolbarAction::OpenPreferences => {}
+                ToolbarAction::OpenLog => {}
                 ToolbarAction::Exit => {}
+                ToolbarAction::Undo => {}
+                ToolbarAction::Redo => {}
                 ToolbarAction::AutoFit => {}
+                ToolbarAction::FitWidth => {}
+                ToolbarAction::FitHeight => {}
                 ToolbarAction::ResetView => {}
                 ToolbarAction::ZoomIn => {}
                 ToolbarAction::ZoomOut => {}
@@ -335,7 +1144,36 @@ mod tests {
                 ToolbarAction::ToggleDimensions(_) => {}
                 ToolbarAction::ToggleLayerNames(_) => {}
                 ToolbarAction::ToggleSchematicMode(_) => {}
+                ToolbarAction::ToggleSplitView(_) => {}
+                ToolbarAction::ToggleMergedVias(_) => {}
+                ToolbarAction::ToggleAreaAccurateVias(_) => {}
+                ToolbarAction::ToggleHatching(_) => {}
+                ToolbarAction::ToggleConformalLiners(_) => {}
+                ToolbarAction::ToggleOutlineOnly(_) => {}
+                ToolbarAction::ToggleTrueSidewallAngles(_) => {}
+                ToolbarAction::SetSidewallExaggeration(_) => {}
+                ToolbarAction::ToggleHalfNodeScale(_) => {}
+                ToolbarAction::ToggleDensityStrip(_) => {}
+                ToolbarAction::ToggleLegend(_) => {}
+                ToolbarAction::SetViewAnchor(_) => {}
+                ToolbarAction::LoadTheme => {}
+                ToolbarAction::ClearTheme => {}
+                ToolbarAction::ToggleIsometricMode(_) => {}
+                ToolbarAction::ToggleAreaZoom(_) => {}
                 ToolbarAction::ToggleResistanceCalculator(_) => {}
+                ToolbarAction::ToggleViaResistance(_) => {}
+                ToolbarAction::ToggleSearch(_) => {}
+                ToolbarAction::ToggleViaChain(_) => {}
+                ToolbarAction::ToggleProblems(_) => {}
+                ToolbarAction::ToggleSource(_) => {}
+                ToolbarAction::ToggleHistogram(_) => {}
+                ToolbarAction::ToggleTemperatureDrift(_) => {}
+                ToolbarAction::ToggleNaming(_) => {}
+                ToolbarAction::ToggleGuides(_) => {}
+                ToolbarAction::ToggleStructures(_) => {}
+                ToolbarAction::ToggleResources(_) => {}
+                ToolbarAction::ToggleHistory(_) => {}
+                ToolbarAction::ToggleProfiler(_) => {}
             }
         }
     }
@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{Layer, ProcessStack};
+use egui::{Color32, ComboBox, Context, Grid, RichText, ScrollArea, Window};
+
+/// Via chain resistance from the first metal to the top of the stack, hop by hop
+pub struct ViaChainWindow {
+    open: bool,
+    /// Chosen chain ends; the stack's default endpoints are used when unset or unknown
+    from_layer: Option<String>,
+    to_layer: Option<String>,
+}
+
+impl ViaChainWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            from_layer: None,
+            to_layer: None,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Chain ends to analyze, falling back to the defaults for layers not in `stack`
+    pub fn get_endpoints(&self, stack: &ProcessStack) -> Option<(String, String)> {
+        let (default_from, default_to) = stack.default_via_chain_endpoints()?;
+        let valid = |name: &Option<String>| {
+            name.clone()
+                .filter(|name| stack.get_layer(name).is_some_and(Layer::is_conductor))
+        };
+        Some((
+            valid(&self.from_layer).unwrap_or(default_from),
+            valid(&self.to_layer).unwrap_or(default_to),
+        ))
+    }
+
+    pub fn set_endpoints(&mut self, from_layer: Option<String>, to_layer: Option<String>) {
+        self.from_layer = from_layer;
+        self.to_layer = to_layer;
+    }
+
+    pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) {
+        if !self.open {
+            return;
+        }
+
+        let mut open = self.open;
+        Window::new("Via Chain Resistance")
+            .open(&mut open)
+            .default_size([520.0, 320.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let Some((stack, (from, to))) =
+                    stack.and_then(|stack| Some((stack, self.get_endpoints(stack)?)))
+                else {
+                    ui.label("Load a stack with conductor layers to analyze its via chain.");
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label("From");
+                    self.conductor_combo(ui, "via_chain_from", stack, &from, true);
+                    ui.label("to");
+                    self.conductor_combo(ui, "via_chain_to", stack, &to, false);
+                    if ui.button("Defaults").clicked() {
+                        self.set_endpoints(None, None);
+                    }
+                });
+                ui.label(
+                    "One single-cut via per level, using the smallest-area via of each level.",
+                );
+                ui.separator();
+
+                let report = match stack.via_chain(&from, &to) {
+                    Ok(report) => report,
+                    Err(e) => {
+                        ui.colored_label(Color32::RED, format!("Error: {e}"));
+                        return;
+                    }
+                };
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    Grid::new("via_chain_hops")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Hop");
+                            ui.strong("Via");
+                            ui.strong("Area (μm²)");
+                            ui.strong("RPV (Ω)");
+                            ui.strong("Total (Ω)");
+                            ui.end_row();
+
+                            for (hop, total) in
+                                report.hops.iter().zip(report.cumulative_resistances())
+                            {
+                                ui.label(format!("{} → {}", hop.lower_layer, hop.upper_layer));
+                                ui.label(hop.via_name.as_deref().unwrap_or("-"));
+                                ui.label(format!("{:.6}", hop.area));
+                                match hop.resistance {
+                                    Some(resistance) => ui.label(format!("{resistance:.4}")),
+                                    None => ui
+                                        .colored_label(Color32::from_rgb(255, 165, 0), "missing")
+                                        .on_hover_text("No via with a positive RPV at this level"),
+                                };
+                                ui.label(format!("{total:.4}"));
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                ui.separator();
+                let total = RichText::new(format!(
+                    "Total {from} → {to}: {:.4} Ω over {} hops",
+                    report.total_resistance(),
+                    report.hops.len()
+                ))
+                .strong();
+                ui.label(total);
+                if !report.is_continuous() {
+                    ui.colored_label(
+                        Color32::from_rgb(255, 165, 0),
+                        "⚠ Some levels have no RPV; the total only covers the others",
+                    );
+                }
+            });
+        self.open = open;
+    }
+
+    fn conductor_combo(
+        &mut self,
+        ui: &mut egui::Ui,
+        id: &str,
+        stack: &ProcessStack,
+        current: &str,
+        is_from: bool,
+    ) {
+        ComboBox::from_id_salt(id)
+            .selected_text(current)
+            .show_ui(ui, |ui| {
+                for layer in stack.layers.iter().filter(|layer| layer.is_conductor()) {
+                    let name = layer.name();
+                    if ui.selectable_label(name == current, name).clicked() {
+                        let target = if is_from {
+                            &mut self.from_layer
+                        } else {
+                            &mut self.to_layer
+                        };
+                        *target = Some(name.to_string());
+                    }
+                }
+            });
+    }
+}
+
+impl Default for ViaChainWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, TechnologyInfo};
+
+    #[test]
+    fn test_via_chain_window_endpoints() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        for name in ["metal2", "metal1"] {
+            stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+                name.to_string(),
+                0.2,
+            ))));
+        }
+
+        let mut window = ViaChainWindow::new();
+        assert!(!window.is_open());
+        assert_eq!(
+            window.get_endpoints(&stack),
+            Some(("metal1".to_string(), "metal2".to_string()))
+        );
+
+        // Layers missing from the stack fall back to the defaults
+        window.set_endpoints(Some("metal2".to_string()), Some("metal9".to_string()));
+        assert_eq!(
+            window.get_endpoints(&stack),
+            Some(("metal2".to_string(), "metal2".to_string()))
+        );
+    }
+}
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{ProcessStack, ViaConnection};
+use egui::{ComboBox, Context, DragValue, Grid, Window};
+
+/// Resistance of a via array built from one via of the stack
+pub struct ViaResistanceWindow {
+    open: bool,
+    selected_via: Option<String>,
+    /// Columns and rows of cuts; the via's own array, or a single cut, while unset
+    cut_grid: Option<(u32, u32)>,
+}
+
+impl ViaResistanceWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            selected_via: None,
+            cut_grid: None,
+        }
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Pick the via to calculate, resetting the cuts to its own array
+    pub fn set_selected_via(&mut self, via_name: Option<String>) {
+        self.selected_via = via_name;
+        self.cut_grid = None;
+    }
+
+    pub fn get_selected_via_name(&self) -> Option<&String> {
+        self.selected_via.as_ref()
+    }
+
+    /// Columns and rows of cuts used for `via`
+    pub fn get_cut_grid(&self, via: &ViaConnection) -> (u32, u32) {
+        self.cut_grid.unwrap_or_else(|| {
+            via.array
+                .as_ref()
+                .map(|array| (array.columns, array.rows))
+                .unwrap_or((1, 1))
+        })
+    }
+
+    pub fn set_cut_grid(&mut self, columns: u32, rows: u32) {
+        self.cut_grid = Some((columns.max(1), rows.max(1)));
+    }
+
+    pub fn show(&mut self, ctx: &Context, stack: Option<&ProcessStack>) {
+        if !self.open {
+            return;
+        }
+
+        let mut open = self.open;
+        Window::new("Via Resistance Calculator")
+            .open(&mut open)
+            .default_size([360.0, 240.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                let Some(stack) = stack.filter(|stack| !stack.via_stack.is_empty()) else {
+                    ui.label("Load a stack with vias to calculate their resistance.");
+                    return;
+                };
+
+                let current = self
+                    .selected_via
+                    .as_deref()
+                    .and_then(|name| stack.via_stack.get_via(name));
+                ComboBox::from_label("Via")
+                    .selected_text(current.map_or("Select a via", |via| via.name.as_str()))
+                    .show_ui(ui, |ui| {
+                        for via in stack.via_stack.iter() {
+                            let selected = current.is_some_and(|c| c.name == via.name);
+                            if ui.selectable_label(selected, &via.name).clicked() && !selected {
+                                self.set_selected_via(Some(via.name.clone()));
+                            }
+                        }
+                    });
+                let Some(via) = self
+                    .selected_via
+                    .as_deref()
+                    .and_then(|name| stack.via_stack.get_via(name))
+                else {
+                    return;
+                };
+                ui.separator();
+
+                let (mut columns, mut rows) = self.get_cut_grid(via);
+                Grid::new("via_resistance_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Connects:");
+                        ui.label(format!("{} → {}", via.from_layer, via.to_layer));
+                        ui.end_row();
+
+                        ui.label("RPV:");
+                        ui.label(format!("{:.4}", via.get_resistance_per_via()));
+                        ui.end_row();
+
+                        ui.label("Cuts:");
+                        ui.horizontal(|ui| {
+                            let changed = ui
+                                .add(DragValue::new(&mut columns).range(1..=1000))
+                                .changed()
+                                | ui.add(DragValue::new(&mut rows).range(1..=1000)).changed();
+                            if changed {
+                                self.set_cut_grid(columns, rows);
+                            }
+                            ui.label("columns × rows");
+                        });
+                        ui.end_row();
+
+                        ui.label("Resistance:");
+                        ui.strong(format!(
+                            "{:.4}",
+                            via.get_resistance_per_via().parallel(columns * rows)
+                        ));
+                        ui.end_row();
+                    });
+
+                if via.array.is_some()
+                    && self.cut_grid.is_some()
+                    && ui.button("Use the array of the techfile").clicked()
+                {
+                    self.cut_grid = None;
+                }
+            });
+        self.open = open;
+    }
+}
+
+impl Default for ViaResistanceWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ViaArray;
+
+    #[test]
+    fn test_via_resistance_window_cut_grid() {
+        let single = ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            8.0,
+        );
+        let array = single
+            .clone()
+            .with_array(ViaArray::new(2, 3, 0.2, 0.4, 0.4));
+
+        let mut window = ViaResistanceWindow::new();
+        window.set_selected_via(Some("via1".to_string()));
+        assert_eq!(window.get_selected_via_name().unwrap(), "via1");
+        assert_eq!(window.get_cut_grid(&single), (1, 1));
+        assert_eq!(window.get_cut_grid(&array), (2, 3));
+
+        window.set_cut_grid(4, 0);
+        assert_eq!(window.get_cut_grid(&array), (4, 1));
+
+        // Picking a via again starts from its own array
+        window.set_selected_via(Some("via1".to_string()));
+        assert_eq!(window.get_cut_grid(&array), (2, 3));
+    }
+}
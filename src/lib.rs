@@ -36,19 +36,62 @@
 //!
 //! The library is organized into several modules:
 //!
+//! - `analysis`: Headless electrical calculations, such as wire resistance
 //! - `data`: Core data structures for layers, vias, and process stacks
+//! - `export`: CAD exporters for the rendered cross-section
 //! - `parser`: ITF file parsing with lexical analysis and syntax parsing
 //! - `renderer`: Visualization rendering with colors and geometry
 //! - `gui`: Interactive GUI components using egui framework
 //! - `utils`: Utility functions for file I/O and helpers
+//!
+//! # Feature flags
+//!
+//! - `gui` (default): the `renderer`, `export` and `gui` modules, [`run_app`] and the
+//!   `itf-viewer` binary, which pull in egui and eframe
+//!
+//! Tools that only read techfiles can depend on the library without the GUI:
+//!
+//! ```toml
+//! [dependencies]
+//! itf-viewer = { version = "0.1", default-features = false }
+//! ```
+//!
+//! That leaves `analysis`, `data`, `parser` and `utils`, which do not depend on egui.
+//!
+//! ```rust
+//! use itf_viewer::analysis::{calculate_resistance, OperatingConditions, WireGeometry};
+//! use itf_viewer::ConductorLayer;
+//!
+//! let mut metal1 = ConductorLayer::new("metal1".to_string(), 0.2);
+//! metal1.electrical_props.rpsq = Some(0.1);
+//! metal1.electrical_props.crt1 = Some(4.0e-3);
+//!
+//! // 20 squares of 0.1 ohm/sq, 40% higher at 125 °C
+//! let wire = WireGeometry::new(0.5, 10.0);
+//! let result = calculate_resistance(&metal1, &wire, &OperatingConditions::new(125.0, 25.0))?;
+//! assert!((result.resistance - 2.8).abs() < 1e-9);
+//! # Ok::<(), itf_viewer::analysis::ResistanceError>(())
+//! ```
+//!
+//! # Stability
+//!
+//! The public items of `analysis`, `data`, `parser` and `utils` follow semantic
+//! versioning: within a 0.x release series, a breaking change to them bumps the minor
+//! version. The `renderer`, `export` and `gui` modules serve the application and may
+//! change in any release.
 
+pub mod analysis;
 pub mod data;
+#[cfg(feature = "gui")]
+pub mod export;
+#[cfg(feature = "gui")]
 pub mod gui;
 pub mod parser;
+#[cfg(feature = "gui")]
 pub mod renderer;
 pub mod utils;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "gui"))]
 mod integration_tests;
 
 // Re-export commonly used types
@@ -57,12 +100,15 @@ pub use data::{
     ViaType,
 };
 
-pub use parser::{parse_itf_file, ItfParser, ParseError};
+pub use parser::{parse_itf_file, CancelToken, ItfParser, ParseError};
 
+#[cfg(feature = "gui")]
 pub use renderer::{
-    ColorScheme, LayerGeometry, RectangleShape, StackRenderer, TrapezoidShape, ViewTransform,
+    format_primitives, ColorScheme, LayerGeometry, PrimitiveKind, RectangleShape, RenderPrimitive,
+    StackLayout, StackRenderer, TrapezoidShape, ViewTransform,
 };
 
+#[cfg(feature = "gui")]
 pub use gui::{FileMenu, LayerPanel, MainWindow, StackViewer, Toolbar};
 
 /// Library version information
@@ -81,7 +127,9 @@ pub fn get_library_info() -> String {
 
 /// Parse an ITF file from a file path
 ///
-/// This is a convenience function that reads the file and parses it.
+/// This is a convenience function that reads the file and parses it. Byte order marks and
+/// CRLF line endings are handled, and files that are not valid UTF-8 are read as Latin-1
+/// with a warning in the stack's diagnostics.
 ///
 /// # Arguments
 ///
@@ -105,8 +153,9 @@ pub fn get_library_info() -> String {
 pub fn parse_itf_from_file<P: AsRef<std::path::Path>>(
     file_path: P,
 ) -> Result<ProcessStack, Box<dyn std::error::Error>> {
-    let content = std::fs::read_to_string(file_path)?;
-    let stack = parse_itf_file(&content)?;
+    let source = parser::read_itf_source(file_path)?;
+    let mut stack = parse_itf_file(&source.text)?;
+    source.add_diagnostics(&mut stack);
     Ok(stack)
 }
 
@@ -163,11 +212,13 @@ pub fn validate_itf_content(content: &str) -> bool {
 /// # Returns
 ///
 /// * `AppConfig` - Default application configuration
+#[cfg(feature = "gui")]
 pub fn get_default_config() -> AppConfig {
     AppConfig::default()
 }
 
 /// Application configuration structure
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     /// Window title
@@ -186,8 +237,11 @@ pub struct AppConfig {
     pub layer_panel_open: bool,
     /// Pre-loaded process stack data
     pub preloaded_stack: Option<ProcessStack>,
+    /// File the pre-loaded stack was read from
+    pub preloaded_path: Option<std::path::PathBuf>,
 }
 
+#[cfg(feature = "gui")]
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -199,6 +253,7 @@ impl Default for AppConfig {
             default_layer_width: 200.0,
             layer_panel_open: true,
             preloaded_stack: None,
+            preloaded_path: None,
         }
     }
 }
@@ -226,7 +281,15 @@ impl Default for AppConfig {
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(feature = "gui")]
 pub fn run_app(config: AppConfig) -> Result<(), eframe::Error> {
+    // GUI users have no terminal, so keep a log file for bug reports
+    if let Some(dir) = utils::default_log_dir() {
+        if let Err(e) = utils::start_file_logging(&dir, utils::LogVerbosity::default()) {
+            eprintln!("Failed to open the log file in {}: {e}", dir.display());
+        }
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([config.window_width, config.window_height])
@@ -236,15 +299,18 @@ pub fn run_app(config: AppConfig) -> Result<(), eframe::Error> {
 
     // Create app with preloaded data if available
     let preloaded_stack = config.preloaded_stack;
+    let preloaded_path = config.preloaded_path;
     eframe::run_native(
         &config.window_title,
         options,
-        Box::new(move |_cc| {
-            if let Some(stack) = preloaded_stack {
-                Ok(Box::new(MainWindow::with_stack(stack)) as Box<dyn eframe::App>)
-            } else {
-                Ok(Box::new(MainWindow::new()) as Box<dyn eframe::App>)
-            }
+        Box::new(move |cc| {
+            let mut window = match preloaded_stack {
+                Some(stack) => MainWindow::with_stack(stack),
+                None => MainWindow::new(),
+            };
+            window.set_file_path(preloaded_path);
+            window.restore(cc.storage);
+            Ok(Box::new(window) as Box<dyn eframe::App>)
         }),
     )
 }
@@ -287,6 +353,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "gui")]
     fn test_app_config() {
         let config = AppConfig::default();
         assert!(config.window_width > 0.0);
@@ -312,6 +379,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "gui")]
     fn test_config_clone_and_debug() {
         let config = AppConfig::default();
         let cloned = config.clone();
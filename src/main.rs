@@ -6,83 +6,1313 @@
 //! A cross-platform GUI application for viewing and analyzing ITF
 //! (Interconnect Technology Format) files used in semiconductor process design.
 
-use itf_viewer::{get_default_config, parse_itf_from_file, run_app};
+use itf_viewer::data::{ProcessStack, Severity};
+use itf_viewer::utils::{ValidationReport, ValidationStatus};
+use itf_viewer::{get_default_config, run_app, CancelToken, ItfParser};
 use std::env;
+use std::io::IsTerminal;
+
+/// Files at least this large show parse progress on an interactive terminal
+const PROGRESS_MIN_BYTES: usize = 1 << 20;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging
-    env_logger::init();
+    // Initialize logging; the GUI adds its log file once it starts
+    itf_viewer::utils::init_logging();
+
+    // Parse command line arguments
+    let args: Vec<String> = env::args().collect();
+
+    // Subcommands are dispatched before the GUI argument handling
+    if args.len() >= 2 && args[1] == "query" {
+        return run_query(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "table" {
+        return run_table(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "template" {
+        return run_template(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "example" {
+        return run_example(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "dxf" {
+        return run_dxf(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "gds" {
+        return run_gds(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "slide" {
+        return run_slide(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "merge" {
+        return run_merge(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "bond" {
+        return run_bond(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "diff" {
+        return run_diff(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "fingerprint" {
+        return run_fingerprint(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "import-csv" {
+        return run_import_csv(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "via-chain" {
+        return run_via_chain(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "lint" {
+        return run_lint(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "validate" {
+        return run_validate(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "schema" {
+        return run_schema(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "fragments" {
+        return run_fragments(&args[2..]);
+    }
+    if args.len() >= 2 && args[1] == "--bench-render" {
+        return run_bench_render(&args[2..]);
+    }
+
+    match args.len() {
+        1 => {
+            // No arguments - run GUI application
+            run_gui_app()
+        }
+        2 => {
+            // One argument - either file path or command
+            let arg = &args[1];
+            match arg.as_str() {
+                "--help" | "-h" => {
+                    print_help();
+                    Ok(())
+                }
+                "--version" | "-v" => {
+                    print_version();
+                    Ok(())
+                }
+                _ => {
+                    // Assume it's a file path
+                    run_with_file(arg)
+                }
+            }
+        }
+        _ => {
+            eprintln!("Error: Too many arguments");
+            print_usage();
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Read and parse an ITF file, showing the percentage parsed on stderr for large files
+fn load_itf(file_path: &str) -> Result<ProcessStack, Box<dyn std::error::Error>> {
+    load_itf_with(file_path, ItfParser::new())
+}
+
+fn load_itf_with(
+    file_path: &str,
+    mut parser: ItfParser,
+) -> Result<ProcessStack, Box<dyn std::error::Error>> {
+    let source = itf_viewer::parser::read_itf_source(file_path)?;
+    let content = &source.text;
+    if content.len() < PROGRESS_MIN_BYTES || !std::io::stderr().is_terminal() {
+        let mut stack = parser.parse_itf_file(content)?;
+        source.add_diagnostics(&mut stack);
+        return Ok(stack);
+    }
+
+    let mut last_percent = None;
+    let result = parser.parse_with_progress(
+        content,
+        |fraction| {
+            let percent = (fraction * 100.0) as u32;
+            if last_percent != Some(percent) {
+                last_percent = Some(percent);
+                eprint!("\rParsing {file_path}: {percent:3}%");
+            }
+        },
+        &CancelToken::new(),
+    );
+    eprintln!();
+    let mut stack = result?;
+    source.add_diagnostics(&mut stack);
+    Ok(stack)
+}
+
+fn run_gui_app() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Starting ITF Viewer...");
+
+    let config = get_default_config();
+    run_app(config).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+}
+
+fn run_with_file(file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Loading ITF file: {file_path}");
+
+    // Validate and parse the file
+    match load_itf(file_path) {
+        Ok(stack) => {
+            // Print file information
+            print_file_info(&stack);
+
+            // Start GUI with the loaded file
+            println!("Starting ITF Viewer with loaded file...");
+            let mut config = get_default_config();
+            config.preloaded_stack = Some(stack);
+            config.preloaded_path = Some(std::path::PathBuf::from(file_path));
+            config.window_title = format!(
+                "{} - {}",
+                config.window_title,
+                std::path::Path::new(file_path)
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            );
+
+            run_app(config).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+        }
+        Err(e) => {
+            eprintln!("Error loading ITF file: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_query(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut layer: Option<&str> = None;
+    let mut field: Option<&str> = None;
+    let mut expr: Option<&str> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--layer" => layer = iter.next().map(|s| s.as_str()),
+            "--field" => field = iter.next().map(|s| s.as_str()),
+            "--expr" => expr = iter.next().map(|s| s.as_str()),
+            other if file_path.is_none() && !other.starts_with("--") => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected query argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let Some(file_path) = file_path else {
+        eprintln!("Error: query requires an ITF file");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let stack = match load_itf(file_path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let result = match (layer, field, expr) {
+        (_, _, Some(expr)) => {
+            itf_viewer::utils::evaluate_query_expression(&stack, expr).map(|v| v.to_string())
+        }
+        (Some(layer), Some(field), None) => {
+            itf_viewer::utils::query_layer_field(&stack, layer, field).map(|v| v.to_string())
+        }
+        _ => {
+            eprintln!("Error: query requires either --layer and --field, or --expr");
+            print_usage();
+            std::process::exit(1);
+        }
+    };
+
+    match result {
+        Ok(value) => {
+            println!("{value}");
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_table(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut column_definitions: Vec<&str> = Vec::new();
+    let mut variable_definitions: Vec<&str> = Vec::new();
+    let mut with_diagnostics = false;
+    let mut with_gaps = false;
+    let mut density_definitions: Vec<&str> = Vec::new();
+    let mut with_density = false;
+    let mut with_drift = false;
+    let mut material_overrides = std::collections::HashMap::new();
+    let mut with_materials = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--column" => column_definitions.extend(iter.next().map(|s| s.as_str())),
+            "--var" => variable_definitions.extend(iter.next().map(|s| s.as_str())),
+            "--with-diagnostics" => with_diagnostics = true,
+            "--with-gaps" => with_gaps = true,
+            "--density" => {
+                density_definitions.extend(iter.next().map(|s| s.as_str()));
+                with_density = true;
+            }
+            "--with-density" => with_density = true,
+            "--with-drift" => with_drift = true,
+            "--material" => {
+                let definition = iter.next().map(|s| s.as_str()).unwrap_or_default();
+                let Some((layer, material)) = definition.split_once('=') else {
+                    eprintln!("Error: Expected LAYER=NAME for --material, got '{definition}'");
+                    std::process::exit(1);
+                };
+                material_overrides.insert(layer.trim().to_string(), material.trim().to_string());
+                with_materials = true;
+            }
+            "--with-materials" => with_materials = true,
+            other if file_path.is_none() && !other.starts_with("--") => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected table argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let parsed = column_definitions
+        .iter()
+        .map(|definition| itf_viewer::utils::DerivedColumn::parse(definition))
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(|columns| {
+            variable_definitions
+                .iter()
+                .map(|definition| itf_viewer::utils::parse_variable(definition))
+                .collect::<Result<std::collections::HashMap<_, _>, _>>()
+                .map(|variables| (columns, variables))
+        });
+    let (columns, variables) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    let mut densities = itf_viewer::data::MetalDensities::new();
+    for definition in &density_definitions {
+        match itf_viewer::utils::parse_variable(definition) {
+            Ok((layer, density)) => densities.set(&layer, Some(density)),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let Some(file_path) = file_path else {
+        eprintln!("Error: table requires an ITF file");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let stack = match load_itf(file_path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    for column in &columns {
+        if let Err(e) = column.validate(&stack, &variables) {
+            eprintln!("Error in column '{}': {e}", column.name);
+            std::process::exit(1);
+        }
+    }
+
+    print!(
+        "{}",
+        itf_viewer::utils::export_layer_table_csv(&stack, &columns, &variables)
+    );
+    if with_gaps {
+        println!();
+        print!("{}", itf_viewer::utils::export_dielectric_gaps_csv(&stack));
+    }
+    if with_density {
+        println!();
+        print!(
+            "{}",
+            itf_viewer::utils::export_metal_density_csv(&stack, &densities)
+        );
+    }
+    if with_drift {
+        println!();
+        print!(
+            "{}",
+            itf_viewer::utils::export_temperature_drift_csv(&stack)
+        );
+    }
+    if with_materials {
+        println!();
+        print!(
+            "{}",
+            itf_viewer::utils::export_layer_materials_csv(
+                &stack,
+                &itf_viewer::data::MaterialDatabase::builtin(),
+                &material_overrides
+            )
+        );
+    }
+    if with_diagnostics && !stack.diagnostics.is_empty() {
+        println!();
+        print!(
+            "{}",
+            itf_viewer::utils::export_diagnostics_csv(&stack.diagnostics)
+        );
+    }
+    Ok(())
+}
+
+fn run_template(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut name: Option<&str> = None;
+    let mut metal_count: Option<&str> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--metals" => metal_count = iter.next().map(|s| s.as_str()),
+            other if name.is_none() && !other.starts_with("--") => name = Some(other),
+            other => {
+                eprintln!("Error: Unexpected template argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let ids: Vec<&str> = itf_viewer::utils::StackTemplate::ALL
+        .iter()
+        .map(|template| template.id())
+        .collect();
+    let Some(template) = name.and_then(itf_viewer::utils::StackTemplate::from_id) else {
+        eprintln!("Error: template requires one of: {}", ids.join(", "));
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let mut params = template.default_params();
+    if let Some(metal_count) = metal_count {
+        match metal_count.parse::<usize>() {
+            Ok(metal_count) => params = params.with_metal_count(metal_count),
+            Err(_) => {
+                eprintln!("Error: Invalid metal count: {metal_count}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    print!("{}", template.to_itf(&params));
+    Ok(())
+}
+
+fn run_example(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let ids: Vec<&str> = itf_viewer::utils::ExampleStack::ALL
+        .iter()
+        .map(|example| example.id())
+        .collect();
+    let example = match args {
+        [name] => itf_viewer::utils::ExampleStack::from_id(name),
+        _ => None,
+    };
+    let Some(example) = example else {
+        eprintln!("Error: example requires one of: {}", ids.join(", "));
+        print_usage();
+        std::process::exit(1);
+    };
+
+    print!("{}", example.source());
+    Ok(())
+}
+
+fn run_dxf(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut schematic = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--schematic" => schematic = true,
+            other if file_path.is_none() && !other.starts_with("--") => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected dxf argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let Some(file_path) = file_path else {
+        eprintln!("Error: dxf requires an ITF file");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let stack = match load_itf(file_path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut renderer = itf_viewer::renderer::StackRenderer::new();
+    renderer.set_show_schematic_mode(schematic);
+    // Same default layer width as a freshly opened viewer window
+    let layout = renderer.layout_stack(&stack, 1200.0);
+
+    print!("{}", itf_viewer::export::export_dxf(&layout));
+    Ok(())
+}
+
+fn run_gds(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut output: Option<&str> = None;
+    let mut layer_map_path: Option<&str> = None;
+    let mut schematic = false;
+    let mut options = itf_viewer::export::GdsOptions::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--schematic" => schematic = true,
+            "--outline" => options = options.with_outline_only(true),
+            "-o" | "--output" => output = iter.next().map(String::as_str),
+            "--layer-map" => layer_map_path = iter.next().map(String::as_str),
+            "--scale" => match iter.next().and_then(|value| value.parse::<f64>().ok()) {
+                Some(scale) if scale > 0.0 => options = options.with_scale(scale),
+                _ => {
+                    eprintln!("Error: --scale requires a positive number");
+                    std::process::exit(1);
+                }
+            },
+            other if file_path.is_none() && !other.starts_with('-') => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected gds argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (Some(file_path), Some(output)) = (file_path, output) else {
+        eprintln!("Error: gds requires an ITF file and an output file");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let stack = match load_itf(file_path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(path) = layer_map_path {
+        let content = std::fs::read_to_string(path)?;
+        match itf_viewer::export::GdsLayerMap::parse(&content) {
+            Ok(layer_map) => options = options.with_layer_map(layer_map),
+            Err(e) => {
+                eprintln!("Error in layer map {path}: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut renderer = itf_viewer::renderer::StackRenderer::new();
+    renderer.set_show_schematic_mode(schematic);
+    let layout = renderer.layout_stack(&stack, 1200.0);
+
+    std::fs::write(output, itf_viewer::export::export_gds(&layout, &options)?)?;
+    // Report the mapping so unmapped layers can be found in the layout tool
+    for (name, layer, datatype) in
+        itf_viewer::export::resolve_gds_layers(&layout, &options.layer_map)
+    {
+        eprintln!("{name}\t{layer}/{datatype}");
+    }
+    Ok(())
+}
+
+fn run_slide(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    use itf_viewer::export::{ExportFormat, LabelDensity, RenderTheme, SlideOptions};
+
+    let mut file_path: Option<&str> = None;
+    let mut output: Option<&str> = None;
+    let mut title: Option<&str> = None;
+    let mut schematic = false;
+    let mut options = SlideOptions::new();
+    let mut theme = RenderTheme::new();
+    let mut label_density: Option<LabelDensity> = None;
+    let mut outline_width: Option<f32> = None;
+    let mut font_path: Option<&str> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--schematic" => schematic = true,
+            "--no-legend" => options = options.with_legend(false),
+            "-o" | "--output" => output = iter.next().map(String::as_str),
+            "--title" => title = iter.next().map(String::as_str),
+            "--scale" => match iter.next().and_then(|value| value.parse::<f32>().ok()) {
+                Some(scale) if scale > 0.0 => options = options.with_scale(scale),
+                _ => {
+                    eprintln!("Error: --scale requires a positive number");
+                    std::process::exit(1);
+                }
+            },
+            "--theme" => match iter.next().and_then(|value| RenderTheme::from_name(value)) {
+                Some(value) => theme = value,
+                None => {
+                    eprintln!("Error: --theme requires light or dark");
+                    std::process::exit(1);
+                }
+            },
+            "--labels" => match iter.next().and_then(|value| LabelDensity::from_name(value)) {
+                Some(value) => label_density = Some(value),
+                None => {
+                    eprintln!("Error: --labels requires none, conductors or all");
+                    std::process::exit(1);
+                }
+            },
+            "--outline-width" => match iter.next().and_then(|value| value.parse::<f32>().ok()) {
+                Some(width) if width >= 0.0 => outline_width = Some(width),
+                _ => {
+                    eprintln!("Error: --outline-width requires a non-negative number");
+                    std::process::exit(1);
+                }
+            },
+            "--font" => font_path = iter.next().map(String::as_str),
+            other if file_path.is_none() && !other.starts_with('-') => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected slide argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (Some(file_path), Some(output)) = (file_path, output) else {
+        eprintln!("Error: slide requires an ITF file and an output file");
+        print_usage();
+        std::process::exit(1);
+    };
+    let Some(format) = ExportFormat::slide_from_path(std::path::Path::new(output)) else {
+        eprintln!("Error: slide output must end in .emf or .png");
+        std::process::exit(1);
+    };
+
+    let stack = match load_itf(file_path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file: {e}");
+            std::process::exit(1);
+        }
+    };
+    options = options.with_title(title.unwrap_or(&stack.technology_info.name));
+
+    // Options refine the chosen theme whatever their order on the command line
+    if let Some(label_density) = label_density {
+        theme = theme.with_label_density(label_density);
+    }
+    if let Some(width) = outline_width {
+        theme.outline_width = width;
+    }
+    if let Some(path) = font_path {
+        let face = std::path::Path::new(path)
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        theme = theme.with_font(std::fs::read(path)?, face);
+    }
+    options = options.with_theme(theme);
+
+    let mut renderer = itf_viewer::renderer::StackRenderer::new();
+    renderer.set_show_schematic_mode(schematic);
+    let layout = renderer.layout_stack(&stack, 1200.0);
+    options = options.with_vertical_exaggeration(renderer.get_vertical_exaggeration(&stack));
+
+    let data = match format {
+        ExportFormat::Emf => itf_viewer::export::export_emf(&layout, &options)?,
+        _ => itf_viewer::export::export_slide_png(&layout, &options)?,
+    };
+    std::fs::write(output, data)?;
+    Ok(())
+}
+
+fn run_diff(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_paths: Vec<&str> = Vec::new();
+    let mut html_output: Option<&str> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--html" => html_output = iter.next().map(String::as_str),
+            other if file_paths.len() < 2 && !other.starts_with('-') => file_paths.push(other),
+            other => {
+                eprintln!("Error: Unexpected diff argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let &[old_path, new_path] = &file_paths[..] else {
+        eprintln!("Error: diff requires two ITF files");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let load = |path: &str| match load_itf(path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file {path}: {e}");
+            std::process::exit(1);
+        }
+    };
+    let diff = load(old_path).diff(&load(new_path));
+
+    print!(
+        "{}",
+        itf_viewer::utils::diff_report_text(&diff, old_path, new_path)
+    );
+    if let Some(html_output) = html_output {
+        std::fs::write(
+            html_output,
+            itf_viewer::utils::diff_report_html(&diff, old_path, new_path),
+        )?;
+    }
+    Ok(())
+}
+
+fn run_fingerprint(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if args.is_empty() || args.iter().any(|arg| arg.starts_with('-')) {
+        eprintln!("Error: fingerprint requires one or more ITF files");
+        print_usage();
+        std::process::exit(1);
+    }
+
+    for file_path in args {
+        match load_itf(file_path) {
+            Ok(stack) => println!("{}  {file_path}", stack.fingerprint()),
+            Err(e) => {
+                eprintln!("Error loading ITF file {file_path}: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_import_csv(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut output: Option<&str> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = iter.next().map(String::as_str),
+            other if file_path.is_none() && !other.starts_with('-') => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected import-csv argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let Some(file_path) = file_path else {
+        eprintln!("Error: import-csv requires a CSV file");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let technology_name = std::path::Path::new(file_path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "csv_stack".to_string());
+    let content = std::fs::read_to_string(file_path)?;
+    let stack = match itf_viewer::utils::import_csv_stack(&content, &technology_name) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error importing {file_path}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let itf = itf_viewer::utils::write_itf(&stack);
+    match output {
+        Some(output) => std::fs::write(output, itf)?,
+        None => print!("{itf}"),
+    }
+    Ok(())
+}
+
+fn run_merge(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_paths: Vec<&str> = Vec::new();
+    let mut output: Option<&str> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = iter.next().map(String::as_str),
+            other if file_paths.len() < 2 && !other.starts_with('-') => file_paths.push(other),
+            other => {
+                eprintln!("Error: Unexpected merge argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (&[base_path, overlay_path], Some(output)) = (&file_paths[..], output) else {
+        eprintln!("Error: merge requires a base ITF file, an overlay ITF file and an output file");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let load = |path: &str| match load_itf(path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file {path}: {e}");
+            std::process::exit(1);
+        }
+    };
+    let mut stack = load(base_path);
+    let overlay = load(overlay_path);
+
+    let report = stack.merge(&overlay);
+    std::fs::write(output, itf_viewer::utils::write_itf(&stack))?;
+
+    for conflict in &report.conflicts {
+        eprintln!("Override: {conflict}");
+    }
+    for name in &report.added_layers {
+        eprintln!("Added layer: {name}");
+    }
+    for name in &report.added_vias {
+        eprintln!("Added via: {name}");
+    }
+    for label in &report.added_regions {
+        eprintln!("Added region: {label}");
+    }
+    Ok(())
+}
+
+fn run_bond(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_paths: Vec<&str> = Vec::new();
+    let mut output: Option<&str> = None;
+    let mut options = itf_viewer::data::BondOptions::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = iter.next().map(String::as_str),
+            "--gap" => match iter.next().and_then(|value| value.parse::<f64>().ok()) {
+                Some(gap) if gap >= 0.0 => options = options.with_gap(gap),
+                _ => {
+                    eprintln!("Error: --gap requires a non-negative number");
+                    std::process::exit(1);
+                }
+            },
+            "--gap-er" => match iter.next().and_then(|value| value.parse::<f64>().ok()) {
+                Some(er) if er > 0.0 => options = options.with_gap_dielectric_constant(er),
+                _ => {
+                    eprintln!("Error: --gap-er requires a positive number");
+                    std::process::exit(1);
+                }
+            },
+            "--prefix" => match iter.next() {
+                Some(prefix) => options = options.with_top_prefix(prefix.as_str()),
+                None => {
+                    eprintln!("Error: --prefix requires a value");
+                    std::process::exit(1);
+                }
+            },
+            other if file_paths.len() < 2 && !other.starts_with('-') => file_paths.push(other),
+            other => {
+                eprintln!("Error: Unexpected bond argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (&[bottom_path, top_path], Some(output)) = (&file_paths[..], output) else {
+        eprintln!("Error: bond requires a bottom ITF file, a top ITF file and an output file");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let load = |path: &str| match load_itf(path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file {path}: {e}");
+            std::process::exit(1);
+        }
+    };
+    let bottom = load(bottom_path);
+    let top = load(top_path);
+
+    let bonded = bottom.bond_face_to_face(&top, &options)?;
+    std::fs::write(output, itf_viewer::utils::write_itf(&bonded.stack))?;
+    eprintln!("Bonded height: {}", bonded.stats);
+    eprintln!(
+        "Bond gap: {:.4} - {:.4} um",
+        bonded.stats.bottom_height,
+        bonded.stats.bond_top_z()
+    );
+    Ok(())
+}
+
+fn run_via_chain(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut from: Option<&str> = None;
+    let mut to: Option<&str> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--from" => from = iter.next().map(String::as_str),
+            "--to" => to = iter.next().map(String::as_str),
+            other if file_path.is_none() && !other.starts_with("--") => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected via-chain argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let Some(file_path) = file_path else {
+        eprintln!("Error: via-chain requires an ITF file");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let stack = match load_itf(file_path) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let Some((default_from, default_to)) = stack.default_via_chain_endpoints() else {
+        eprintln!("Error: Stack has no conductor layers");
+        std::process::exit(1);
+    };
+    let from = from.unwrap_or(&default_from);
+    let to = to.unwrap_or(&default_to);
+
+    let report = match stack.via_chain(from, to) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    println!(
+        "{:<24} {:<16} {:>12} {:>12} {:>14}",
+        "hop", "via", "area(um^2)", "rpv(ohm)", "total(ohm)"
+    );
+    for (hop, total) in report.hops.iter().zip(report.cumulative_resistances()) {
+        let resistance = hop
+            .resistance
+            .map_or_else(|| "-".to_string(), |r| format!("{r:.4}"));
+        println!(
+            "{:<24} {:<16} {:>12.6} {:>12} {:>14.4}",
+            format!("{} -> {}", hop.lower_layer, hop.upper_layer),
+            hop.via_name.as_deref().unwrap_or("-"),
+            hop.area,
+            resistance,
+            total
+        );
+        if !hop.is_connected() {
+            eprintln!(
+                "Warning: no via with a positive RPV between {} and {}",
+                hop.lower_layer, hop.upper_layer
+            );
+        }
+    }
+    println!(
+        "Total {from} -> {to}: {:.4} ohm over {} hops",
+        report.total_resistance(),
+        report.hops.len()
+    );
+    Ok(())
+}
+
+/// Parser and lint options shared by `lint` and `validate`
+struct CheckOptions {
+    config: itf_viewer::data::LintConfig,
+    number_format: itf_viewer::parser::NumberFormat,
+    duplicate_names: itf_viewer::parser::DuplicateNamePolicy,
+}
+
+impl CheckOptions {
+    fn new() -> Self {
+        Self {
+            config: itf_viewer::data::LintConfig::new(),
+            number_format: itf_viewer::parser::NumberFormat::Lenient,
+            duplicate_names: itf_viewer::parser::DuplicateNamePolicy::default(),
+        }
+    }
+
+    /// Take `arg`, and its value from `iter`, if it is one of the shared options
+    ///
+    /// Returns whether `arg` was taken, or the problem with its value; each command picks
+    /// its own exit code for a bad value.
+    fn parse(
+        &mut self,
+        arg: &str,
+        iter: &mut std::slice::Iter<'_, String>,
+    ) -> Result<bool, String> {
+        match arg {
+            "--disable" => {
+                let Some(rule) = iter
+                    .next()
+                    .and_then(|id| itf_viewer::data::LintRule::from_id(id))
+                else {
+                    return Err(format!(
+                        "--disable requires a rule, one of: {}",
+                        lint_rule_ids()
+                    ));
+                };
+                self.config.set_enabled(rule, false);
+            }
+            "--strict-numbers" => self.number_format = itf_viewer::parser::NumberFormat::Strict,
+            "--duplicates" => {
+                let Some(policy) = iter
+                    .next()
+                    .and_then(|id| itf_viewer::parser::DuplicateNamePolicy::from_id(id))
+                else {
+                    return Err("--duplicates requires error, rename or merge".to_string());
+                };
+                self.duplicate_names = policy;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn parser(&self) -> ItfParser {
+        ItfParser::new()
+            .with_number_format(self.number_format)
+            .with_duplicate_names(self.duplicate_names)
+    }
+}
+
+fn run_lint(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut options = CheckOptions::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match options.parse(arg, &mut iter) {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(message) => {
+                eprintln!("Error: {message}");
+                std::process::exit(1);
+            }
+        }
+        match arg.as_str() {
+            "--list-rules" => {
+                for rule in itf_viewer::data::LintRule::ALL {
+                    println!(
+                        "{:<20} {:<8} {}",
+                        rule.id(),
+                        rule.severity().label(),
+                        rule.explanation()
+                    );
+                }
+                return Ok(());
+            }
+            other if file_path.is_none() && !other.starts_with("--") => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected lint argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let Some(file_path) = file_path else {
+        eprintln!("Error: lint requires an ITF file");
+        print_usage();
+        std::process::exit(1);
+    };
 
-    // Parse command line arguments
-    let args: Vec<String> = env::args().collect();
+    let stack = match load_itf_with(file_path, options.parser()) {
+        Ok(stack) => stack,
+        Err(e) => {
+            eprintln!("Error loading ITF file: {e}");
+            std::process::exit(1);
+        }
+    };
 
-    match args.len() {
-        1 => {
-            // No arguments - run GUI application
-            run_gui_app()
+    for diagnostic in &stack.diagnostics {
+        println!("{diagnostic}");
+    }
+    let findings = stack.lint(&options.config);
+    for finding in &findings {
+        println!("{finding}");
+    }
+    eprintln!(
+        "{} diagnostics, {} lint findings",
+        stack.diagnostics.len(),
+        findings.len()
+    );
+    Ok(())
+}
+
+/// Check a techfile for CI: exits 0 when clean, 1 with warnings and 2 with errors
+fn run_validate(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut options = CheckOptions::new();
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        // Usage errors exit with 2, since 1 only means warnings
+        match options.parse(arg, &mut iter) {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(message) => {
+                eprintln!("Error: {message}");
+                std::process::exit(2);
+            }
         }
-        2 => {
-            // One argument - either file path or command
-            let arg = &args[1];
-            match arg.as_str() {
-                "--help" | "-h" => {
-                    print_help();
-                    Ok(())
-                }
-                "--version" | "-v" => {
-                    print_version();
-                    Ok(())
-                }
+        match arg.as_str() {
+            "--format" => match iter.next().map(String::as_str) {
+                Some("text") => json = false,
+                Some("json") => json = true,
                 _ => {
-                    // Assume it's a file path
-                    run_with_file(arg)
+                    eprintln!("Error: --format requires text or json");
+                    std::process::exit(2);
                 }
+            },
+            other if file_path.is_none() && !other.starts_with("--") => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected validate argument: {other}");
+                print_usage();
+                std::process::exit(2);
             }
         }
-        _ => {
-            eprintln!("Error: Too many arguments");
-            print_usage();
-            std::process::exit(1);
+    }
+
+    let Some(file_path) = file_path else {
+        eprintln!("Error: validate requires an ITF file");
+        print_usage();
+        std::process::exit(2);
+    };
+
+    let report = match load_itf_with(file_path, options.parser()) {
+        Ok(stack) => ValidationReport::from_stack(file_path, &stack, &options.config),
+        Err(e) => ValidationReport::load_failure(file_path, e.to_string()),
+    };
+    if json {
+        print!("{}", report.to_json());
+    } else {
+        for issue in &report.issues {
+            println!("{issue}");
         }
+        eprintln!(
+            "{file_path}: {} errors, {} warnings, {} infos",
+            report.count(Severity::Error),
+            report.count(Severity::Warning),
+            report.count(Severity::Info)
+        );
     }
-}
 
-fn run_gui_app() -> Result<(), Box<dyn std::error::Error>> {
-    println!("Starting ITF Viewer...");
+    let status = report.status();
+    if status != ValidationStatus::Ok {
+        std::process::exit(status.exit_code());
+    }
+    Ok(())
+}
 
-    let config = get_default_config();
-    run_app(config).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+fn lint_rule_ids() -> String {
+    itf_viewer::data::LintRule::ALL
+        .iter()
+        .map(|rule| rule.id())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-fn run_with_file(file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Loading ITF file: {file_path}");
+fn run_schema(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut format = "json-schema";
 
-    // Validate and parse the file
-    match parse_itf_from_file(file_path) {
-        Ok(stack) => {
-            // Print file information
-            print_file_info(&stack);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match (arg.as_str(), iter.next()) {
+            ("--format", Some(value)) => format = value,
+            _ => {
+                eprintln!("Error: Unexpected schema argument: {arg}");
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+    }
 
-            // Start GUI with the loaded file
-            println!("Starting ITF Viewer with loaded file...");
-            let mut config = get_default_config();
-            config.preloaded_stack = Some(stack);
-            config.window_title = format!(
-                "{} - {}",
-                config.window_title,
-                std::path::Path::new(file_path)
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-            );
+    let schema = itf_viewer::utils::process_stack_schema()?;
+    match format {
+        "json-schema" => print!("{}", schema.to_json_schema()),
+        "markdown" => print!("{}", schema.to_markdown()),
+        other => {
+            eprintln!("Error: Unknown schema format '{other}', expected json-schema or markdown");
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
 
-            run_app(config).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+fn run_fragments(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    use itf_viewer::utils::FragmentFormat;
+
+    let mut file_path: Option<&str> = None;
+    let mut output: Option<&str> = None;
+    let mut format = FragmentFormat::Json;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = iter.next().map(String::as_str),
+            "--format" => match iter
+                .next()
+                .and_then(|value| FragmentFormat::from_name(value))
+            {
+                Some(value) => format = value,
+                None => {
+                    eprintln!("Error: --format requires json or yaml");
+                    std::process::exit(1);
+                }
+            },
+            other if file_path.is_none() && !other.starts_with('-') => file_path = Some(other),
+            other => {
+                eprintln!("Error: Unexpected fragments argument: {other}");
+                print_usage();
+                std::process::exit(1);
+            }
         }
+    }
+
+    let (Some(file_path), Some(output)) = (file_path, output) else {
+        eprintln!("Error: fragments requires an ITF file and an output directory");
+        print_usage();
+        std::process::exit(1);
+    };
+
+    let stack = match load_itf(file_path) {
+        Ok(stack) => stack,
         Err(e) => {
             eprintln!("Error loading ITF file: {e}");
             std::process::exit(1);
         }
+    };
+
+    for path in
+        itf_viewer::utils::export_layer_fragments(&stack, std::path::Path::new(output), format)?
+    {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn run_bench_render(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file_path: Option<&str> = None;
+    let mut frames: usize = 100;
+    let mut layer_count: usize = 200;
+    let mut lod = true;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--frames" => iter.next().and_then(|s| s.parse().ok()).map(|n| frames = n),
+            "--layers" => iter
+                .next()
+                .and_then(|s| s.parse().ok())
+                .map(|n| layer_count = n),
+            "--no-lod" => {
+                lod = false;
+                Some(())
+            }
+            other if file_path.is_none() && !other.starts_with("--") => {
+                file_path = Some(other);
+                Some(())
+            }
+            _ => None,
+        };
+        if parsed.is_none() {
+            eprintln!("Error: Unexpected or invalid benchmark argument: {arg}");
+            print_usage();
+            std::process::exit(1);
+        }
+    }
+
+    // Without a file, measure a synthetic stack so runs are comparable across machines
+    let stack = match file_path {
+        Some(file_path) => match load_itf(file_path) {
+            Ok(stack) => stack,
+            Err(e) => {
+                eprintln!("Error loading ITF file: {e}");
+                std::process::exit(1);
+            }
+        },
+        None => itf_viewer::utils::generate_random_stack(0, layer_count),
+    };
+
+    let ctx = egui::Context::default();
+    let mut renderer = itf_viewer::renderer::StackRenderer::new();
+    if !lod {
+        renderer.set_lod_policy(itf_viewer::renderer::LodPolicy::disabled());
+    }
+    let transform =
+        itf_viewer::renderer::fitted_transform(&renderer, &stack, egui::Vec2::new(1280.0, 800.0));
+
+    // The first frame builds the font atlas, keep it out of the measurement
+    itf_viewer::renderer::render_frame_headless(&ctx, &renderer, &stack, &transform);
+
+    let mut times = Vec::with_capacity(frames);
+    let mut vertices = 0;
+    for _ in 0..frames.max(1) {
+        let start = std::time::Instant::now();
+        vertices = itf_viewer::renderer::render_frame_headless(&ctx, &renderer, &stack, &transform);
+        times.push(start.elapsed().as_secs_f64() * 1000.0);
     }
+    times.sort_by(f64::total_cmp);
+
+    let mean = times.iter().sum::<f64>() / times.len() as f64;
+    println!(
+        "Layers: {}, vias: {}, vertices per frame: {vertices}",
+        stack.get_layer_count(),
+        stack.get_via_count()
+    );
+    println!(
+        "Frame build time over {} frames: mean {mean:.3} ms, median {:.3} ms, min {:.3} ms, max {:.3} ms",
+        times.len(),
+        times[times.len() / 2],
+        times[0],
+        times[times.len() - 1]
+    );
+    Ok(())
 }
 
 fn print_file_info(stack: &itf_viewer::ProcessStack) {
@@ -101,6 +1331,7 @@ fn print_file_info(stack: &itf_viewer::ProcessStack) {
     }
 
     println!("  Total stack height: {:.3} um", summary.total_height);
+    println!("  Fingerprint: {}", summary.fingerprint);
     println!();
 }
 
@@ -109,6 +1340,67 @@ fn print_help() {
     println!();
     println!("USAGE:");
     println!("    {} [OPTIONS] [FILE]", env!("CARGO_PKG_NAME"));
+    println!(
+        "    {} query <FILE> --layer <LAYER> --field <FIELD>",
+        env!("CARGO_PKG_NAME")
+    );
+    println!("    {} query <FILE> --expr <EXPR>", env!("CARGO_PKG_NAME"));
+    println!(
+        "    {} table <FILE> [--column <NAME=EXPR>]... [--var <NAME=VALUE>]... [--with-gaps] [--density <METAL=D>]... [--with-density] [--with-drift] [--material <LAYER=NAME>]... [--with-materials] [--with-diagnostics]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} template <logic|rf|interposer> [--metals <N>]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!("    {} example <simple|complex>", env!("CARGO_PKG_NAME"));
+    println!("    {} dxf <FILE> [--schematic]", env!("CARGO_PKG_NAME"));
+    println!(
+        "    {} gds <FILE> -o <OUT> [--layer-map <MAP>] [--scale <N>] [--schematic] [--outline]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} slide <FILE> -o <OUT.emf|OUT.png> [--title <TEXT>] [--no-legend] [--scale <N>] [--schematic] [--theme <light|dark>] [--labels <none|conductors|all>] [--outline-width <PT>] [--font <TTF>]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} merge <BASE> <OVERLAY> -o <OUT>",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} bond <BOTTOM> <TOP> -o <OUT> [--gap <UM>] [--gap-er <ER>] [--prefix <PREFIX>]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} diff <OLD> <NEW> [--html <OUT>]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!("    {} fingerprint <FILE>...", env!("CARGO_PKG_NAME"));
+    println!("    {} import-csv <CSV> [-o <OUT>]", env!("CARGO_PKG_NAME"));
+    println!(
+        "    {} via-chain <FILE> [--from <LAYER>] [--to <LAYER>]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} lint <FILE> [--disable <RULE>]... [--strict-numbers] [--duplicates <POLICY>] | lint --list-rules",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} validate <FILE> [--format <text|json>] [lint options]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} schema [--format <json-schema|markdown>]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} fragments <FILE> -o <DIR> [--format <json|yaml>]",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} --bench-render [FILE] [--frames <N>] [--layers <N>] [--no-lod]",
+        env!("CARGO_PKG_NAME")
+    );
     println!();
     println!("ARGS:");
     println!("    <FILE>    ITF file to load and display");
@@ -117,6 +1409,88 @@ fn print_help() {
     println!("    -h, --help       Print this help message");
     println!("    -v, --version    Print version information");
     println!();
+    println!("QUERY OPTIONS:");
+    println!("    --layer <LAYER>  Layer to query (case-insensitive)");
+    println!(
+        "    --field <FIELD>  One of: {}",
+        itf_viewer::utils::QUERY_FIELDS.join(", ")
+    );
+    println!("    --expr <EXPR>    Arithmetic over fields, e.g. \"thickness('metal3')*2\"");
+    println!();
+    println!("TABLE OPTIONS:");
+    println!(
+        "    --column <NAME=EXPR>  Derived per-conductor column, e.g. \"fom=rpsq*length/wmin\""
+    );
+    println!("    --var <NAME=VALUE>    Constant usable in column expressions, e.g. length=100");
+    println!("    --with-gaps           Append the dielectric gaps between adjacent conductors");
+    println!(
+        "    --density <METAL=D>   Assumed fill density (0-1) of a metal, implies --with-density"
+    );
+    println!(
+        "    --with-density        Append metal densities and thickness at density (default 0.5)"
+    );
+    println!(
+        "    --with-drift          Append the resistance drift of each conductor from -40 °C to 125 °C"
+    );
+    println!(
+        "    --material <LAYER=NAME>  Material of a layer instead of the inferred one, implies --with-materials"
+    );
+    println!(
+        "    --with-materials      Append the material of each layer (Cu, Al, W, SiO2, SiN, low-k)"
+    );
+    println!("    --with-diagnostics    Append the parse diagnostics");
+    println!();
+    println!("TEMPLATE OPTIONS:");
+    println!("    --metals <N>     Number of metal layers in the generated example stack");
+    println!();
+    println!("VIA-CHAIN OPTIONS:");
+    println!("    --from <LAYER>   Bottom conductor (default: first metal, e.g. METAL1)");
+    println!("    --to <LAYER>     Top conductor (default: topmost conductor)");
+    println!();
+    println!("LINT OPTIONS:");
+    println!("    --disable <RULE> Skip a rule, e.g. thickness-limit (repeatable)");
+    println!("    --list-rules     Print every rule with its severity and explanation");
+    println!("    --strict-numbers Do not read locale-formatted values such as 0,25");
+    println!("    --duplicates <POLICY>");
+    println!("                     Layers named like an earlier one: error, rename (default,");
+    println!("                     adds _1, _2, ...) or merge into the first definition");
+    println!();
+    println!("VALIDATE OPTIONS:");
+    println!("    --format <FMT>   text (default) or json, listing every diagnostic");
+    println!("                     Takes the lint options above. Exits with 0 when clean,");
+    println!("                     1 with warnings and 2 with errors or an unreadable file");
+    println!();
+    println!("SCHEMA OPTIONS:");
+    println!("    --format <FMT>   json-schema (default) or markdown");
+    println!();
+    println!("FRAGMENTS OPTIONS:");
+    println!("    -o <DIR>         Directory receiving one file per layer, created if missing");
+    println!("    --format <FMT>   json (default) or yaml");
+    println!();
+    println!("DIFF OPTIONS:");
+    println!("    --html <OUT>     Also write the report as an HTML page");
+    println!();
+    println!("IMPORT-CSV OPTIONS:");
+    println!("    <CSV>            Rows of name,type,thickness,er,rpsq, top of the stack first");
+    println!("    -o <OUT>         Write the ITF file there instead of to standard output");
+    println!();
+    println!("DXF OPTIONS:");
+    println!("    --schematic      Export the schematic (thickness-normalized) cross-section");
+    println!();
+    println!("SLIDE OPTIONS:");
+    println!("    --theme <NAME>   Built-in render theme, light (default) or dark");
+    println!(
+        "    --labels <WHICH> Name none (default), conductors or all layers inside the section"
+    );
+    println!("    --outline-width <PT>  Width of layer outlines in points, 0 for none");
+    println!("    --font <TTF>     Font file for the text, also named in EMF output");
+    println!();
+    println!("BENCHMARK OPTIONS:");
+    println!("    --bench-render   Time headless frame building for FILE or a random stack");
+    println!("    --frames <N>     Number of measured frames (default 100)");
+    println!("    --layers <N>     Layer count of the random stack (default 200)");
+    println!("    --no-lod         Draw every layer in full detail, for comparison");
+    println!();
     println!("DESCRIPTION:");
     println!("    ITF Viewer is a cross-platform application for visualizing semiconductor");
     println!("    process stacks defined in ITF (Interconnect Technology Format) files.");
@@ -154,6 +1528,50 @@ fn print_help() {
         "    {} --version                 # Show version information",
         env!("CARGO_PKG_NAME")
     );
+    println!(
+        "    {} query process.itf --layer metal3 --field thickness",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} table process.itf --var length=100 --column \"r=rpsq*length/wmin\"",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} template logic --metals 7 > demo.itf",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} dxf process.itf > cross_section.dxf",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} gds process.itf -o cross_section.gds --layer-map layers.map",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} merge process.itf customer_option.itf -o merged.itf",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} bond logic.itf memory.itf -o bonded.itf --gap 0.5",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} via-chain process.itf --from metal1 --to metal6",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} lint process.itf --disable zero-rpsq",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} validate process.itf --format json > report.json",
+        env!("CARGO_PKG_NAME")
+    );
+    println!(
+        "    {} schema --format json-schema > stack.schema.json",
+        env!("CARGO_PKG_NAME")
+    );
 }
 
 fn print_version() {
@@ -173,6 +1591,69 @@ fn print_version() {
 
 fn print_usage() {
     eprintln!("Usage: {} [OPTIONS] [FILE]", env!("CARGO_PKG_NAME"));
+    eprintln!(
+        "       {} query <FILE> (--layer <LAYER> --field <FIELD> | --expr <EXPR>)",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} table <FILE> [--column <NAME=EXPR>]... [--var <NAME=VALUE>]... [--with-gaps] [--density <METAL=D>]... [--with-density] [--with-drift] [--material <LAYER=NAME>]... [--with-materials] [--with-diagnostics]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} template <logic|rf|interposer> [--metals <N>]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!("       {} example <simple|complex>", env!("CARGO_PKG_NAME"));
+    eprintln!("       {} dxf <FILE> [--schematic]", env!("CARGO_PKG_NAME"));
+    eprintln!(
+        "       {} gds <FILE> -o <OUT> [--layer-map <MAP>] [--scale <N>] [--schematic] [--outline]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} slide <FILE> -o <OUT.emf|OUT.png> [--title <TEXT>] [--no-legend] [--scale <N>] [--schematic] [--theme <light|dark>] [--labels <none|conductors|all>] [--outline-width <PT>] [--font <TTF>]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} merge <BASE> <OVERLAY> -o <OUT>",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} bond <BOTTOM> <TOP> -o <OUT> [--gap <UM>] [--gap-er <ER>] [--prefix <PREFIX>]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} diff <OLD> <NEW> [--html <OUT>]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!("       {} fingerprint <FILE>...", env!("CARGO_PKG_NAME"));
+    eprintln!(
+        "       {} import-csv <CSV> [-o <OUT>]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} via-chain <FILE> [--from <LAYER>] [--to <LAYER>]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} lint <FILE> [--disable <RULE>]... [--strict-numbers] [--duplicates <POLICY>] | lint --list-rules",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} validate <FILE> [--format <text|json>] [lint options]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} schema [--format <json-schema|markdown>]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} fragments <FILE> -o <DIR> [--format <json|yaml>]",
+        env!("CARGO_PKG_NAME")
+    );
+    eprintln!(
+        "       {} --bench-render [FILE] [--frames <N>] [--layers <N>] [--no-lod]",
+        env!("CARGO_PKG_NAME")
+    );
     eprintln!(
         "Try '{} --help' for more information.",
         env!("CARGO_PKG_NAME")
@@ -217,4 +1698,16 @@ mod tests {
         // Should not panic
         print_file_info(&stack);
     }
+
+    #[test]
+    fn test_check_options_parse_errors() {
+        let mut options = CheckOptions::new();
+        let values = ["no-such-rule".to_string()];
+        assert!(options.parse("--disable", &mut values.iter()).is_err());
+        assert!(options.parse("--duplicates", &mut [].iter()).is_err());
+
+        let values = ["rename".to_string()];
+        assert_eq!(options.parse("--duplicates", &mut values.iter()), Ok(true));
+        assert_eq!(options.parse("--format", &mut [].iter()), Ok(false));
+    }
 }
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use serde::{Deserialize, Serialize};
+
+/// How the parser treats a CONDUCTOR or DIELECTRIC whose name is already taken
+///
+/// Vias, hit testing and selection look layers up by name, so duplicates never stay as is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DuplicateNamePolicy {
+    /// Fail the parse
+    Error,
+    /// Keep both layers, suffixing the later ones `_1`, `_2`, ... with a warning
+    #[default]
+    Rename,
+    /// Keep one layer in the first position, with the values of the later definition
+    Merge,
+}
+
+impl DuplicateNamePolicy {
+    pub const ALL: [DuplicateNamePolicy; 3] = [
+        DuplicateNamePolicy::Error,
+        DuplicateNamePolicy::Rename,
+        DuplicateNamePolicy::Merge,
+    ];
+
+    /// Short identifier used on the command line
+    pub fn id(self) -> &'static str {
+        match self {
+            DuplicateNamePolicy::Error => "error",
+            DuplicateNamePolicy::Rename => "rename",
+            DuplicateNamePolicy::Merge => "merge",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.id().eq_ignore_ascii_case(id))
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DuplicateNamePolicy::Error => "Reject the file",
+            DuplicateNamePolicy::Rename => "Rename with a suffix",
+            DuplicateNamePolicy::Merge => "Merge into the first",
+        }
+    }
+}
+
+/// `name` with the first suffix `_1`, `_2`, ... that no layer of `stack` uses
+pub fn unique_layer_name(stack: &ProcessStack, name: &str) -> String {
+    (1..)
+        .map(|suffix| format!("{name}_{suffix}"))
+        .find(|candidate| stack.get_layer(candidate).is_none())
+        .expect("a free suffix exists")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, Layer, TechnologyInfo};
+
+    #[test]
+    fn test_unique_layer_name() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("names".to_string()));
+        for name in ["ild", "ild_1"] {
+            stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+                name.to_string(),
+                0.5,
+                4.2,
+            )));
+        }
+        assert_eq!(unique_layer_name(&stack, "ild"), "ild_2");
+        assert_eq!(unique_layer_name(&stack, "imd"), "imd_1");
+
+        assert_eq!(
+            DuplicateNamePolicy::from_id("MERGE"),
+            Some(DuplicateNamePolicy::Merge)
+        );
+        assert_eq!(DuplicateNamePolicy::from_id("keep"), None);
+    }
+}
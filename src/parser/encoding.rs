@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{Diagnostic, ProcessStack, Severity};
+use std::path::Path;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Encoding a techfile was decoded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Fallback for files that are not valid UTF-8, every byte taken as one character
+    Latin1,
+}
+
+impl SourceEncoding {
+    pub fn label(self) -> &'static str {
+        match self {
+            SourceEncoding::Utf8 => "UTF-8",
+            SourceEncoding::Utf16Le => "UTF-16LE",
+            SourceEncoding::Utf16Be => "UTF-16BE",
+            SourceEncoding::Latin1 => "Latin-1",
+        }
+    }
+}
+
+/// Techfile text ready for the lexer, with what was done to get it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedSource {
+    pub text: String,
+    pub encoding: SourceEncoding,
+    /// Whether a byte order mark was stripped
+    pub had_bom: bool,
+    /// Whether CRLF or lone CR line endings were turned into LF
+    pub normalized_line_endings: bool,
+    /// Offset of the first byte that is not valid UTF-8, for the Latin-1 fallback
+    pub invalid_utf8_offset: Option<usize>,
+}
+
+impl DecodedSource {
+    /// Warning worth showing the user, when the text may not read as the foundry intended
+    pub fn warning(&self) -> Option<String> {
+        let offset = self.invalid_utf8_offset?;
+        Some(format!(
+            "File is not valid UTF-8 (byte {offset}), decoded as {}",
+            self.encoding.label()
+        ))
+    }
+
+    /// Record the warning, if any, in the diagnostics of `stack`
+    pub fn add_diagnostics(&self, stack: &mut ProcessStack) {
+        if let Some(warning) = self.warning() {
+            stack
+                .diagnostics
+                .insert(0, Diagnostic::new(Severity::Warning, warning));
+        }
+    }
+}
+
+/// Decode the raw bytes of a techfile
+///
+/// Strips a UTF-8 or UTF-16 byte order mark, decodes UTF-16 when its BOM is present, falls
+/// back to Latin-1 when the bytes are not valid UTF-8 and turns CRLF and CR into LF.
+pub fn decode_itf_bytes(bytes: &[u8]) -> DecodedSource {
+    let (text, encoding, had_bom, invalid_utf8_offset) =
+        if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+            (
+                decode_utf16(rest, u16::from_le_bytes),
+                SourceEncoding::Utf16Le,
+                true,
+                None,
+            )
+        } else if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+            (
+                decode_utf16(rest, u16::from_be_bytes),
+                SourceEncoding::Utf16Be,
+                true,
+                None,
+            )
+        } else {
+            let (rest, had_bom) = match bytes.strip_prefix(UTF8_BOM) {
+                Some(rest) => (rest, true),
+                None => (bytes, false),
+            };
+            match std::str::from_utf8(rest) {
+                Ok(text) => (text.to_string(), SourceEncoding::Utf8, had_bom, None),
+                Err(e) => {
+                    let text = rest.iter().map(|&byte| byte as char).collect();
+                    (text, SourceEncoding::Latin1, had_bom, Some(e.valid_up_to()))
+                }
+            }
+        };
+
+    let normalized_line_endings = text.contains('\r');
+    let text = if normalized_line_endings {
+        text.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        text
+    };
+    DecodedSource {
+        text,
+        encoding,
+        had_bom,
+        normalized_line_endings,
+        invalid_utf8_offset,
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Read a techfile from disk and decode it with [`decode_itf_bytes`]
+pub fn read_itf_source<P: AsRef<Path>>(path: P) -> std::io::Result<DecodedSource> {
+    let source = decode_itf_bytes(&std::fs::read(path.as_ref())?);
+    if let Some(warning) = source.warning() {
+        log::warn!("{}: {warning}", path.as_ref().display());
+    }
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bom_and_line_endings() {
+        let source = decode_itf_bytes(b"\xEF\xBB\xBFTECHNOLOGY = t\r\nDIELECTRIC d\rEND\n");
+        assert_eq!(source.text, "TECHNOLOGY = t\nDIELECTRIC d\nEND\n");
+        assert_eq!(source.encoding, SourceEncoding::Utf8);
+        assert!(source.had_bom);
+        assert!(source.normalized_line_endings);
+        assert_eq!(source.warning(), None);
+
+        let plain = decode_itf_bytes(b"TECHNOLOGY = t\n");
+        assert!(!plain.had_bom && !plain.normalized_line_endings);
+    }
+
+    #[test]
+    fn test_latin1_fallback() {
+        // "µm" in Latin-1
+        let source = decode_itf_bytes(b"$ 0.1 \xB5m\r\nTECHNOLOGY = t\n");
+        assert_eq!(source.text, "$ 0.1 µm\nTECHNOLOGY = t\n");
+        assert_eq!(source.encoding, SourceEncoding::Latin1);
+        assert_eq!(source.invalid_utf8_offset, Some(6));
+        assert_eq!(
+            source.warning().as_deref(),
+            Some("File is not valid UTF-8 (byte 6), decoded as Latin-1")
+        );
+    }
+
+    #[test]
+    fn test_utf16() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(
+            "TECHNOLOGY = t\r\n"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        );
+        let source = decode_itf_bytes(&bytes);
+        assert_eq!(source.encoding, SourceEncoding::Utf16Le);
+        assert_eq!(source.text, "TECHNOLOGY = t\n");
+
+        let mut bytes = vec![0xFE, 0xFF];
+        bytes.extend("END".encode_utf16().flat_map(u16::to_be_bytes));
+        assert_eq!(decode_itf_bytes(&bytes).text, "END");
+    }
+}
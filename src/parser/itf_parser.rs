@@ -2,22 +2,266 @@
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
 use crate::data::*;
+use crate::parser::duplicate_names::{unique_layer_name, DuplicateNamePolicy};
+use crate::parser::keywords::{
+    conductor_keywords, technology_keywords, via_keywords, CustomKeywordHandler, KeywordRegistry,
+    KeywordScope,
+};
 use crate::parser::lexer::*;
+use crate::parser::number_format::{find_locale_numbers, NumberFormat};
 use nom::{
     branch::alt,
-    character::complete::multispace0,
-    combinator::{opt, value},
+    character::complete::{multispace0, satisfy},
+    combinator::{not, opt, value},
     number::complete::double,
-    sequence::preceded,
+    sequence::{preceded, terminated},
     IResult, Parser,
 };
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Smallest progress step, as a fraction of the file, passed to a progress callback
+const PROGRESS_STEP: f32 = 0.01;
 
-pub struct ItfParser {}
+/// Shared flag that stops a parse in progress from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Lines skipped back to back, reported as one diagnostic
+struct SkippedRun {
+    /// Index of the diagnostic reporting the run
+    index: usize,
+    first_line: usize,
+    last_line: usize,
+    first_text: String,
+}
+
+pub struct ItfParser {
+    /// Diagnostics raised by the block parsers, which only borrow the parser
+    ///
+    /// Behind a mutex rather than a `RefCell` so the parser stays `Sync`.
+    diagnostics: Mutex<Vec<Diagnostic>>,
+    skipped_run: Mutex<Option<SkippedRun>>,
+    /// Byte offset of every line start in the content being parsed
+    line_starts: Vec<usize>,
+    source_len: usize,
+    number_format: NumberFormat,
+    duplicate_names: DuplicateNamePolicy,
+    technology_keywords: KeywordRegistry<TechnologyInfo>,
+    conductor_keywords: KeywordRegistry<ConductorLayer>,
+    via_keywords: KeywordRegistry<ViaConnection>,
+    /// Handlers registered by the caller, keyed by upper case keyword
+    custom_keywords: HashMap<KeywordScope, HashMap<String, CustomKeywordHandler>>,
+}
 
 impl ItfParser {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            diagnostics: Mutex::new(Vec::new()),
+            skipped_run: Mutex::new(None),
+            line_starts: Vec::new(),
+            source_len: 0,
+            number_format: NumberFormat::default(),
+            duplicate_names: DuplicateNamePolicy::default(),
+            technology_keywords: technology_keywords(),
+            conductor_keywords: conductor_keywords(),
+            via_keywords: via_keywords(),
+            custom_keywords: HashMap::new(),
+        }
+    }
+
+    /// Whether values like `0,25` are read (with a warning) or only reported
+    pub fn with_number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    /// What happens to a layer named like one defined before it
+    pub fn with_duplicate_names(mut self, duplicate_names: DuplicateNamePolicy) -> Self {
+        self.duplicate_names = duplicate_names;
+        self
+    }
+
+    /// Parse `keyword` in `scope` blocks with `handler`, storing its value in `extra_properties`
+    ///
+    /// For vendor extensions the built-in parsing skips or cannot read, such as free text or
+    /// braced blocks; see [`parse_assigned_text`](crate::parser::parse_assigned_text) and
+    /// [`parse_braced_text`](crate::parser::parse_braced_text). Keywords match case-insensitively
+    /// and are tried before the built-in ones.
+    pub fn with_keyword_handler(
+        mut self,
+        scope: KeywordScope,
+        keyword: &str,
+        handler: impl Fn(&str) -> Option<(&str, PropValue)> + Send + Sync + 'static,
+    ) -> Self {
+        self.custom_keywords
+            .entry(scope)
+            .or_default()
+            .insert(keyword.to_uppercase(), Arc::new(handler));
+        self
+    }
+
+    /// Apply the custom handler for the keyword at the start of `input`, if one is registered
+    fn parse_custom_keyword<'a>(
+        &self,
+        scope: KeywordScope,
+        input: &'a str,
+    ) -> Option<(&'a str, String, PropValue)> {
+        let handlers = self.custom_keywords.get(&scope)?;
+        let (rest, keyword) = parse_identifier(input).ok()?;
+        let (keyword, handler) = handlers.get_key_value(keyword.to_uppercase().as_str())?;
+        let (rest, value) = handler(rest)?;
+        Some((rest, keyword.clone(), value))
+    }
+
+    /// Apply the built-in handler for the keyword at the start of `input` to `target`
+    fn parse_builtin_keyword<'a, T>(
+        &self,
+        registry: &KeywordRegistry<T>,
+        input: &'a str,
+        target: &mut T,
+    ) -> Option<&'a str> {
+        let (rest, keyword) = parse_identifier(input).ok()?;
+        let handler = registry.get(keyword)?;
+        handler(self, rest, target).ok().map(|(rest, ())| rest)
+    }
+
+    /// Diagnostics collected so far, kept even if a block parser panicked
+    fn diagnostics(&self) -> MutexGuard<'_, Vec<Diagnostic>> {
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn skipped_run(&self) -> MutexGuard<'_, Option<SkippedRun>> {
+        self.skipped_run
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 1-based line of the start of `remaining`, a suffix of the content being parsed
+    fn line_of(&self, remaining: &str) -> usize {
+        let offset = self.source_len.saturating_sub(remaining.len());
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+
+    /// Record a warning for the line at the start of `remaining` unless it is blank or a comment
+    fn warn_skipped_line(&self, remaining: &str, context: &str) {
+        self.warn_skipped(
+            remaining,
+            remaining.lines().next().unwrap_or_default(),
+            context,
+        );
+    }
+
+    /// Record a warning for `skipped`, the text at the start of `remaining`
+    fn warn_skipped(&self, remaining: &str, skipped: &str, context: &str) {
+        let text = skipped.trim();
+        if text.is_empty() || text.starts_with('$') {
+            return;
+        }
+        log::warn!("Skipping unrecognized {context}: {text}");
+
+        let line = self.line_of(remaining);
+        let mut diagnostics = self.diagnostics();
+        let mut run = self.skipped_run();
+
+        // Consecutive skipped lines, e.g. an unsupported table, become a single warning
+        if let Some(run) = run.as_mut() {
+            if run.index + 1 == diagnostics.len() && run.last_line + 1 == line {
+                run.last_line = line;
+                diagnostics[run.index].message = format!(
+                    "Skipped {} unrecognized lines ({}-{}), starting with: {}",
+                    run.last_line - run.first_line + 1,
+                    run.first_line,
+                    run.last_line,
+                    run.first_text
+                );
+                return;
+            }
+        }
+
+        *run = Some(SkippedRun {
+            index: diagnostics.len(),
+            first_line: line,
+            last_line: line,
+            first_text: text.to_string(),
+        });
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Warning,
+                format!("Skipped unrecognized {context}: {text}"),
+            )
+            .with_line(line),
+        );
+    }
+
+    /// Add a parsed layer, resolving a taken name by the duplicate name policy
+    ///
+    /// `block` is the remaining content from the start of the layer's block.
+    fn add_layer(
+        &self,
+        stack: &mut ProcessStack,
+        mut layer: Layer,
+        block: &str,
+    ) -> Result<(), ParseError> {
+        let line = self.line_of(block);
+        let Some(existing) = stack.get_layer(layer.name()) else {
+            stack.set_source_line(layer.name(), line);
+            stack.add_layer(layer);
+            return Ok(());
+        };
+
+        let name = layer.name().to_string();
+        let same_kind = existing.is_dielectric() == layer.is_dielectric();
+        let mut target = name.clone();
+        let message = match self.duplicate_names {
+            DuplicateNamePolicy::Error => {
+                return Err(ParseError::DuplicateLayer { name, line });
+            }
+            DuplicateNamePolicy::Merge if same_kind => {
+                stack.replace_layer(layer);
+                format!("Merged duplicate layer '{name}' into its first definition")
+            }
+            DuplicateNamePolicy::Rename | DuplicateNamePolicy::Merge => {
+                let unique = unique_layer_name(stack, &name);
+                layer.set_name(unique.clone());
+                stack.set_source_line(&unique, line);
+                stack.add_layer(layer);
+                target = unique.clone();
+                if same_kind {
+                    format!("Renamed duplicate layer '{name}' to '{unique}'")
+                } else {
+                    format!(
+                        "Renamed duplicate layer '{name}' to '{unique}', a conductor and a \
+                         dielectric cannot be merged"
+                    )
+                }
+            }
+        };
+        log::warn!("Line {line}: {message}");
+        self.diagnostics().push(
+            Diagnostic::new(Severity::Warning, message)
+                .with_line(line)
+                .with_layer(target),
+        );
+        Ok(())
     }
 
     /// Check if the ITF file contains encrypted values
@@ -32,6 +276,19 @@ impl ItfParser {
     }
 
     pub fn parse_itf_file(&mut self, content: &str) -> Result<ProcessStack, ParseError> {
+        self.parse_with_progress(content, |_| {}, &CancelToken::new())
+    }
+
+    /// Parse like `parse_itf_file`, reporting progress and stopping when `cancel` is set
+    ///
+    /// `progress` receives the fraction of `content` parsed so far, from 0.0 to 1.0, in
+    /// steps of at least one percent. A cancelled parse returns `ParseError::Cancelled`.
+    pub fn parse_with_progress(
+        &mut self,
+        content: &str,
+        mut progress: impl FnMut(f32),
+        cancel: &CancelToken,
+    ) -> Result<ProcessStack, ParseError> {
         // Check if the ITF file is encrypted (contains @ symbol followed by hex)
         if Self::is_encrypted_itf(content) {
             return Err(ParseError::EncryptedFile(
@@ -47,14 +304,54 @@ impl ItfParser {
         // let _tokens = lexer.tokenize()
         //     .map_err(|e| ParseError::LexError(format!("{e:?}")))?;
 
+        self.diagnostics().clear();
+        *self.skipped_run() = None;
+
+        // Hand-edited files sometimes carry decimal commas or thousands separators
+        let lenient = self.number_format == NumberFormat::Lenient;
+        let (content, locale_numbers) = find_locale_numbers(content, lenient);
+        let content = content.as_ref();
+        for number in locale_numbers {
+            let message = if lenient {
+                format!(
+                    "Read locale-formatted number '{}' as {}",
+                    number.original, number.normalized
+                )
+            } else {
+                format!(
+                    "Locale-formatted number '{}' was not read, write it as {}",
+                    number.original, number.normalized
+                )
+            };
+            log::warn!("Line {}: {message}", number.line);
+            self.diagnostics()
+                .push(Diagnostic::new(Severity::Warning, message).with_line(number.line));
+        }
+
+        self.source_len = content.len();
+        self.line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
         let (remaining, technology_info) = self
             .parse_header(content)
             .map_err(|e| ParseError::ParseError(format!("Header parse error: {e:?}")))?;
 
         let mut stack = ProcessStack::new(technology_info);
         let mut remaining = remaining;
+        let mut reported = 0.0;
+        progress(reported);
 
         while !remaining.trim().is_empty() {
+            if cancel.is_cancelled() {
+                return Err(ParseError::Cancelled);
+            }
+            let fraction = 1.0 - remaining.len() as f32 / content.len() as f32;
+            if fraction - reported >= PROGRESS_STEP {
+                reported = fraction;
+                progress(reported);
+            }
+
             // Skip empty lines and comments
             let trimmed = remaining.trim_start();
             if trimmed.is_empty() || trimmed.starts_with("$") {
@@ -66,46 +363,25 @@ impl ItfParser {
                 continue;
             }
 
+            let diagnostics_mark = self.diagnostics().len();
             if let Ok((rest, layer)) = self.parse_dielectric_layer(remaining) {
-                stack.add_layer(Layer::Dielectric(layer));
+                self.add_layer(&mut stack, Layer::Dielectric(layer), trimmed)?;
                 remaining = rest;
             } else if let Ok((rest, layer)) = self.parse_conductor_layer(remaining) {
-                stack.add_layer(Layer::Conductor(Box::new(layer)));
+                self.add_layer(&mut stack, Layer::Conductor(Box::new(layer)), trimmed)?;
                 remaining = rest;
             } else if let Ok((rest, via)) = self.parse_via(remaining) {
+                stack.set_source_line(&via.name, self.line_of(trimmed));
                 stack.add_via(via);
                 remaining = rest;
-            } else if let Ok((rest, temp)) = preceded(
-                (
-                    multispace0,
-                    parse_keyword("GLOBAL_TEMPERATURE"),
-                    parse_equals,
-                ),
-                preceded(multispace0, double),
-            )
-            .parse(remaining)
-            {
-                stack.technology_info.global_temperature = Some(temp);
-                remaining = rest;
-            } else if let Ok((rest, direction)) = preceded(
-                (
-                    multispace0,
-                    parse_keyword("REFERENCE_DIRECTION"),
-                    parse_equals,
-                ),
-                preceded(multispace0, parse_identifier),
-            )
-            .parse(remaining)
-            {
-                stack.technology_info.reference_direction = Some(direction);
+            } else if let Ok((rest, region)) = self.parse_er_region(remaining) {
+                stack.add_er_region(region);
                 remaining = rest;
-            } else if let Ok((rest, er)) = preceded(
-                (multispace0, parse_keyword("BACKGROUND_ER"), parse_equals),
-                preceded(multispace0, double),
-            )
-            .parse(remaining)
-            {
-                stack.technology_info.background_er = Some(er);
+            } else if let Some(rest) = self.parse_builtin_keyword(
+                &self.technology_keywords,
+                remaining,
+                &mut stack.technology_info,
+            ) {
                 remaining = rest;
             } else if let Ok((rest, table)) =
                 preceded((multispace0, parse_keyword("CRT_VS_SI_WIDTH")), |input| {
@@ -116,18 +392,28 @@ impl ItfParser {
                 // Associate CRT_VS_SI_WIDTH table with the most recent conductor layer
                 if let Some(Layer::Conductor(conductor)) = stack.layers.last_mut() {
                     conductor.crt_vs_si_width = Some(table);
-                    println!(
-                        "INFO: Associated CRT_VS_SI_WIDTH table with conductor '{}'",
+                    log::info!(
+                        "Associated CRT_VS_SI_WIDTH table with conductor '{}'",
                         conductor.name
                     );
+                    self.diagnostics().push(
+                        Diagnostic::new(
+                            Severity::Info,
+                            format!(
+                                "Associated a top-level CRT_VS_SI_WIDTH table with conductor '{}'",
+                                conductor.name
+                            ),
+                        )
+                        .with_line(self.line_of(remaining.trim_start()))
+                        .with_layer(conductor.name.clone()),
+                    );
                 }
                 remaining = rest;
             } else {
+                // Drop what a block parser reported before the whole block failed
+                self.diagnostics().truncate(diagnostics_mark);
+                self.warn_skipped_line(remaining, "line");
                 let next_line_end = remaining.find('\n').unwrap_or(remaining.len());
-                let skipped_line = &remaining[..next_line_end];
-                if !skipped_line.trim().is_empty() && !skipped_line.trim().starts_with("$") {
-                    eprintln!("WARN: Skipping unrecognized line: {}", skipped_line.trim());
-                }
                 remaining = &remaining[next_line_end..];
                 if remaining.starts_with('\n') {
                     remaining = &remaining[1..];
@@ -135,6 +421,8 @@ impl ItfParser {
             }
         }
 
+        stack.diagnostics.append(&mut self.diagnostics());
+
         // Auto-create missing layers before validation
         stack.ensure_via_layers_exist();
 
@@ -149,7 +437,10 @@ impl ItfParser {
                     Ok(warnings) => {
                         // Print warnings for missing layer references but continue
                         for warning in warnings {
-                            eprintln!("WARN: {warning}");
+                            log::warn!("{warning}");
+                            stack
+                                .diagnostics
+                                .push(Diagnostic::new(Severity::Warning, warning));
                         }
                     }
                     Err(e) => {
@@ -160,20 +451,16 @@ impl ItfParser {
             }
         }
 
+        progress(1.0);
         Ok(stack)
     }
 
     fn parse_header<'a>(&self, input: &'a str) -> IResult<&'a str, TechnologyInfo> {
         let mut remaining = input;
-        let mut tech_name: Option<String> = None;
-        let mut global_temperature: Option<f64> = None;
-        let mut reference_direction: Option<String> = None;
-        let mut background_er: Option<f64> = None;
-        let mut half_node_scale_factor: Option<f64> = None;
-        let mut use_si_density: Option<bool> = None;
-        let mut drop_factor_lateral_spacing: Option<f64> = None;
-
-        // Parse header fields in any order until we hit a CONDUCTOR, DIELECTRIC, or VIA
+        // TECHNOLOGY is required, use default if not found
+        let mut tech_info = TechnologyInfo::new("unknown_technology".to_string());
+
+        // Parse header fields in any order until we hit a layer, via or region block
         while !remaining.trim().is_empty() {
             let trimmed = remaining.trim_start();
 
@@ -191,99 +478,20 @@ impl ItfParser {
             if trimmed.starts_with("CONDUCTOR")
                 || trimmed.starts_with("DIELECTRIC")
                 || trimmed.starts_with("VIA")
+                || trimmed.starts_with("SUBSTRATE")
+                || trimmed.starts_with("FIELD")
             {
                 break;
             }
 
-            // Try to parse various header fields
-            if let Ok((rest, name)) = preceded(
-                (parse_keyword("TECHNOLOGY"), parse_equals),
-                preceded(multispace0, parse_identifier),
-            )
-            .parse(remaining)
-            {
-                tech_name = Some(name);
-                remaining = rest;
-            } else if let Ok((rest, temp)) = preceded(
-                (
-                    multispace0,
-                    parse_keyword("GLOBAL_TEMPERATURE"),
-                    parse_equals,
-                ),
-                preceded(multispace0, double),
-            )
-            .parse(remaining)
-            {
-                global_temperature = Some(temp);
-                remaining = rest;
-            } else if let Ok((rest, direction)) = preceded(
-                (
-                    multispace0,
-                    parse_keyword("REFERENCE_DIRECTION"),
-                    parse_equals,
-                ),
-                preceded(multispace0, parse_identifier),
-            )
-            .parse(remaining)
-            {
-                reference_direction = Some(direction);
-                remaining = rest;
-            } else if let Ok((rest, er)) = preceded(
-                (multispace0, parse_keyword("BACKGROUND_ER"), parse_equals),
-                preceded(multispace0, double),
-            )
-            .parse(remaining)
-            {
-                background_er = Some(er);
-                remaining = rest;
-            } else if let Ok((rest, factor)) = preceded(
-                (
-                    multispace0,
-                    parse_keyword("HALF_NODE_SCALE_FACTOR"),
-                    parse_equals,
-                ),
-                preceded(multispace0, double),
-            )
-            .parse(remaining)
-            {
-                half_node_scale_factor = Some(factor);
-                remaining = rest;
-            } else if let Ok((rest, use_si)) = preceded(
-                (multispace0, parse_keyword("USE_SI_DENSITY"), parse_equals),
-                preceded(
-                    multispace0,
-                    alt((
-                        value(true, parse_keyword("YES")),
-                        value(false, parse_keyword("NO")),
-                    )),
-                ),
-            )
-            .parse(remaining)
-            {
-                use_si_density = Some(use_si);
-                remaining = rest;
-            } else if let Ok((rest, drop_factor)) = preceded(
-                (
-                    multispace0,
-                    parse_keyword("DROP_FACTOR_LATERAL_SPACING"),
-                    parse_equals,
-                ),
-                preceded(multispace0, double),
-            )
-            .parse(remaining)
+            if let Some(rest) =
+                self.parse_builtin_keyword(&self.technology_keywords, remaining, &mut tech_info)
             {
-                drop_factor_lateral_spacing = Some(drop_factor);
                 remaining = rest;
             } else {
                 // If we can't parse this line as a header field, skip it
+                self.warn_skipped_line(remaining, "header line");
                 let next_line_end = remaining.find('\n').unwrap_or(remaining.len());
-                let skipped_line = &remaining[..next_line_end];
-                if !skipped_line.trim().is_empty() && !skipped_line.trim().starts_with("$") {
-                    eprintln!(
-                        "WARN: Skipping unrecognized header line: {}",
-                        skipped_line.trim()
-                    );
-                }
                 remaining = &remaining[next_line_end..];
                 if remaining.starts_with('\n') {
                     remaining = &remaining[1..];
@@ -291,17 +499,6 @@ impl ItfParser {
             }
         }
 
-        // TECHNOLOGY is required, use default if not found
-        let technology_name = tech_name.unwrap_or_else(|| "unknown_technology".to_string());
-
-        let mut tech_info = TechnologyInfo::new(technology_name);
-        tech_info.global_temperature = global_temperature;
-        tech_info.reference_direction = reference_direction;
-        tech_info.background_er = background_er;
-        tech_info.half_node_scale_factor = half_node_scale_factor;
-        tech_info.use_si_density = use_si_density;
-        tech_info.drop_factor_lateral_spacing = drop_factor_lateral_spacing;
-
         Ok((remaining, tech_info))
     }
 
@@ -313,56 +510,84 @@ impl ItfParser {
         )
             .parse(input)?;
 
-        let mut layer = DielectricLayer::new(name, 0.0, 0.0);
-        let (input, properties) = self.parse_dielectric_properties(input)?;
+        let mut layer = DielectricLayer::new(name.to_string(), 0.0, 0.0);
+        let (input, mut properties) = self.parse_dielectric_properties(input)?;
 
-        layer.thickness = properties.get("THICKNESS").copied().unwrap_or(0.0);
-        layer.dielectric_constant = properties.get("ER").copied().unwrap_or(1.0);
+        let mut number = |key: &str| properties.remove(key).and_then(|v| v.as_number());
+        layer.thickness = number("THICKNESS").unwrap_or(0.0);
+        layer.dielectric_constant = number("ER").unwrap_or(1.0);
+        layer.sw_t = number("SW_T");
+        layer.tw_t = number("TW_T");
         layer.measured_from = properties
-            .get("MEASURED_FROM")
-            .map(|_| "TOP_OF_CHIP".to_string());
-        layer.sw_t = properties.get("SW_T").copied();
-        layer.tw_t = properties.get("TW_T").copied();
+            .remove("MEASURED_FROM")
+            .map(|value| value.to_string());
+        layer.extra_properties = properties
+            .into_iter()
+            .map(|(key, value)| (key.into_owned(), value))
+            .collect();
 
         let (input, _) = preceded(multispace0, parse_right_brace).parse(input)?;
 
         Ok((input, layer))
     }
 
+    /// `SUBSTRATE [name] { ... }` or `FIELD [name] { ... }`, read like a dielectric block
+    fn parse_er_region<'a>(&self, input: &'a str) -> IResult<&'a str, ErRegion> {
+        let keyword = |kind: ErRegionKind| {
+            value(
+                kind,
+                terminated(
+                    parse_keyword(kind.keyword()),
+                    not(satisfy(|c: char| c.is_alphanumeric() || c == '_')),
+                ),
+            )
+        };
+        let (input, (kind, name, _)) = (
+            alt((
+                keyword(ErRegionKind::Substrate),
+                keyword(ErRegionKind::Field),
+            )),
+            opt(preceded(multispace0, parse_identifier)),
+            preceded(multispace0, parse_left_brace),
+        )
+            .parse(input)?;
+
+        let (input, mut properties) = self.parse_dielectric_properties(input)?;
+        let name = name.map_or_else(|| kind.keyword().to_lowercase(), str::to_string);
+        let mut number = |key: &str| properties.remove(key).and_then(|v| v.as_number());
+        let mut region = ErRegion::new(kind, name, number("ER").unwrap_or(1.0));
+        region.thickness = number("THICKNESS");
+        region.extra_properties = properties
+            .into_iter()
+            .map(|(key, value)| (key.into_owned(), value))
+            .collect();
+
+        let (input, _) = preceded(multispace0, parse_right_brace).parse(input)?;
+
+        Ok((input, region))
+    }
+
     fn parse_dielectric_properties<'a>(
         &self,
         input: &'a str,
-    ) -> IResult<&'a str, HashMap<String, f64>> {
+    ) -> IResult<&'a str, HashMap<Cow<'a, str>, PropValue>> {
         let mut properties = HashMap::new();
         let mut remaining = input;
 
         while !remaining.trim_start().starts_with('}') && !remaining.trim().is_empty() {
-            if let Ok((rest, (prop_name, _, value))) = (
-                preceded(multispace0, parse_identifier),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
+            if let Some((rest, key, value)) =
+                self.parse_custom_keyword(KeywordScope::Dielectric, remaining)
             {
-                properties.insert(prop_name.to_uppercase(), value);
+                properties.insert(Cow::Owned(key), value);
                 remaining = rest;
-            } else if let Ok((rest, prop_name)) =
-                preceded(multispace0, parse_identifier).parse(remaining)
+            } else if let Ok((rest, (prop_name, value))) = self.parse_property_assignment(remaining)
             {
-                if prop_name.to_uppercase() == "MEASURED_FROM" {
-                    if let Ok((rest2, _)) =
-                        preceded((multispace0, parse_equals, multispace0), parse_identifier)
-                            .parse(rest)
-                    {
-                        properties.insert("MEASURED_FROM".to_string(), 1.0);
-                        remaining = rest2;
-                    } else {
-                        remaining = rest;
-                    }
-                } else {
-                    remaining = rest;
-                }
+                properties.insert(prop_name, value);
+                remaining = rest;
+            } else if let Ok((rest, _)) = preceded(multispace0, parse_identifier).parse(remaining) {
+                remaining = rest;
             } else {
+                self.warn_skipped_line(remaining, "DIELECTRIC line");
                 let next_line_end = remaining.find('\n').unwrap_or(remaining.len());
                 remaining = &remaining[next_line_end..];
                 if remaining.starts_with('\n') {
@@ -374,6 +599,39 @@ impl ItfParser {
         Ok((remaining, properties))
     }
 
+    /// `KEY = value` with a number or a single word as value, the key in upper case
+    fn parse_property_assignment<'a>(
+        &self,
+        input: &'a str,
+    ) -> IResult<&'a str, (Cow<'a, str>, PropValue)> {
+        let (input, (name, _)) = (
+            preceded(multispace0, parse_identifier),
+            preceded(multispace0, parse_equals),
+        )
+            .parse(input)?;
+        let input = input.trim_start_matches([' ', '\t']);
+
+        // A number must end at a delimiter, so words such as INFINITE_PLANE stay text
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let value = match double::<&str, nom::error::Error<&str>>(input) {
+            Ok((rest, number)) if !rest.starts_with(is_word_char) => {
+                (rest, PropValue::Number(number))
+            }
+            _ => {
+                let (rest, text) = parse_identifier(input)?;
+                (rest, PropValue::Text(text.to_string()))
+            }
+        };
+
+        // Keys are nearly always upper case already, so only fold the others
+        let key = if name.chars().any(char::is_lowercase) {
+            Cow::Owned(name.to_uppercase())
+        } else {
+            Cow::Borrowed(name)
+        };
+        Ok((value.0, (key, value.1)))
+    }
+
     fn parse_conductor_layer<'a>(&self, input: &'a str) -> IResult<&'a str, ConductorLayer> {
         let (input, (_, name, _)) = (
             preceded(multispace0, parse_keyword("CONDUCTOR")),
@@ -382,8 +640,19 @@ impl ItfParser {
         )
             .parse(input)?;
 
-        let mut layer = ConductorLayer::new(name, 0.0);
+        let mut layer = ConductorLayer::new(name.to_string(), 0.0);
         let (input, _) = self.parse_conductor_properties(input, &mut layer)?;
+
+        // Plain assignments that do have a field
+        for (key, field) in [
+            ("RESISTIVE_ONLY_ETCH", &mut layer.resistive_only_etch),
+            ("CAPACITIVE_ONLY_ETCH", &mut layer.capacitive_only_etch),
+        ] {
+            if let Some(PropValue::Number(value)) = layer.extra_properties.get(key) {
+                *field = Some(*value);
+                layer.extra_properties.remove(key);
+            }
+        }
         let (input, _) = preceded(multispace0, parse_right_brace).parse(input)?;
 
         Ok((input, layer))
@@ -397,121 +666,21 @@ impl ItfParser {
         let mut remaining = input;
 
         while !remaining.trim_start().starts_with('}') && !remaining.trim().is_empty() {
-            if let Ok((rest, (_, _, thickness))) = (
-                preceded(multispace0, parse_keyword("THICKNESS")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
+            if let Some((rest, key, value)) =
+                self.parse_custom_keyword(KeywordScope::Conductor, remaining)
             {
-                layer.thickness = thickness;
-                layer.physical_props.thickness = thickness;
+                layer.extra_properties.insert(key, value);
                 remaining = rest;
-            } else if let Ok((rest, (_, _, crt1))) = (
-                preceded(multispace0, parse_keyword("CRT1")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
-            {
-                layer.electrical_props.crt1 = Some(crt1);
-                remaining = rest;
-            } else if let Ok((rest, (_, _, crt2))) = (
-                preceded(multispace0, parse_keyword("CRT2")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
+            } else if let Some(rest) =
+                self.parse_builtin_keyword(&self.conductor_keywords, remaining, layer)
             {
-                layer.electrical_props.crt2 = Some(crt2);
                 remaining = rest;
-            } else if let Ok((rest, (_, _, rpsq))) = (
-                preceded(multispace0, parse_keyword("RPSQ")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
-            {
-                layer.electrical_props.rpsq = Some(rpsq);
-                remaining = rest;
-            } else if let Ok((rest, (_, _, wmin))) = (
-                preceded(multispace0, parse_keyword("WMIN")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
+            } else if let Ok((rest, (prop_name, value))) = self.parse_property_assignment(remaining)
             {
-                layer.physical_props.width_min = Some(wmin);
-                remaining = rest;
-            } else if let Ok((rest, (_, _, smin))) = (
-                preceded(multispace0, parse_keyword("SMIN")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
-            {
-                layer.physical_props.spacing_min = Some(smin);
-                remaining = rest;
-            } else if let Ok((rest, (_, _, side_tangent))) = (
-                preceded(multispace0, parse_keyword("SIDE_TANGENT")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
-            {
-                layer.physical_props.side_tangent = Some(side_tangent);
-                remaining = rest;
-            } else if let Ok((rest, table)) = preceded(
-                (multispace0, parse_keyword("RHO_VS_WIDTH_AND_SPACING")),
-                |input| self.parse_lookup_table_2d(input),
-            )
-            .parse(remaining)
-            {
-                layer.rho_vs_width_spacing = Some(table);
-                remaining = rest;
-            } else if let Ok((rest, table)) = preceded(
-                (multispace0, parse_keyword("ETCH_VS_WIDTH_AND_SPACING")),
-                |input| self.parse_etch_table(input),
-            )
-            .parse(remaining)
-            {
-                layer.etch_vs_width_spacing = Some(table);
-                remaining = rest;
-            } else if let Ok((rest, table)) = preceded(
-                (multispace0, parse_keyword("THICKNESS_VS_WIDTH_AND_SPACING")),
-                |input| self.parse_lookup_table_2d(input),
-            )
-            .parse(remaining)
-            {
-                layer.thickness_vs_width_spacing = Some(table);
-                remaining = rest;
-            } else if let Ok((rest, _)) = preceded(
-                (
-                    multispace0,
-                    parse_keyword("POLYNOMIAL_BASED_THICKNESS_VARIATION"),
-                ),
-                |input| self.skip_complex_block(input),
-            )
-            .parse(remaining)
-            {
-                remaining = rest;
-            } else if let Ok((rest, table)) = preceded(
-                (multispace0, parse_keyword("RHO_VS_SI_WIDTH_AND_THICKNESS")),
-                |input| self.parse_rho_vs_si_width_thickness_table(input),
-            )
-            .parse(remaining)
-            {
-                layer.rho_vs_si_width_thickness = Some(table);
-                remaining = rest;
-            } else if let Ok((rest, table)) =
-                preceded((multispace0, parse_keyword("CRT_VS_SI_WIDTH")), |input| {
-                    self.parse_crt_vs_si_width_table(input)
-                })
-                .parse(remaining)
-            {
-                layer.crt_vs_si_width = Some(table);
+                layer.extra_properties.insert(prop_name.into_owned(), value);
                 remaining = rest;
             } else {
+                self.warn_skipped_line(remaining, "CONDUCTOR line");
                 let next_line_end = remaining.find('\n').unwrap_or(remaining.len());
                 remaining = &remaining[next_line_end..];
                 if remaining.starts_with('\n') {
@@ -523,7 +692,10 @@ impl ItfParser {
         Ok((remaining, ()))
     }
 
-    fn parse_lookup_table_2d<'a>(&self, input: &'a str) -> IResult<&'a str, LookupTable2D> {
+    pub(crate) fn parse_lookup_table_2d<'a>(
+        &self,
+        input: &'a str,
+    ) -> IResult<&'a str, LookupTable2D> {
         let (input, _) = preceded(multispace0, parse_left_brace).parse(input)?;
 
         let (input, widths) =
@@ -543,7 +715,7 @@ impl ItfParser {
         Ok((input, LookupTable2D::new(widths, spacings, values)))
     }
 
-    fn parse_etch_table<'a>(&self, input: &'a str) -> IResult<&'a str, LookupTable2D> {
+    pub(crate) fn parse_etch_table<'a>(&self, input: &'a str) -> IResult<&'a str, LookupTable2D> {
         let (input, _) = opt(preceded(
             multispace0,
             parse_identifier, // Parse optional modifiers like "ETCH_FROM_TOP", "CAPACITIVE_ONLY", etc.
@@ -553,7 +725,7 @@ impl ItfParser {
         self.parse_lookup_table_2d(input)
     }
 
-    fn skip_complex_block<'a>(&self, input: &'a str) -> IResult<&'a str, ()> {
+    pub(crate) fn skip_complex_block<'a>(&self, input: &'a str) -> IResult<&'a str, ()> {
         let mut remaining = input;
         let mut brace_count = 0;
         let mut in_brace = false;
@@ -598,48 +770,22 @@ impl ItfParser {
         )
             .parse(input)?;
 
-        let mut from_layer = String::new();
-        let mut to_layer = String::new();
-        let mut area = 0.0;
-        let mut rpv = 0.0;
+        let mut via = ViaConnection::new(name.to_string(), String::new(), String::new(), 0.0, 0.0);
         let mut remaining = input;
 
         while !remaining.trim_start().starts_with('}') && !remaining.trim().is_empty() {
-            if let Ok((rest, (_, _, layer_name))) = (
-                preceded(multispace0, parse_keyword("FROM")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, parse_identifier),
-            )
-                .parse(remaining)
-            {
-                from_layer = layer_name;
-                remaining = rest;
-            } else if let Ok((rest, (_, _, layer_name))) = (
-                preceded(multispace0, parse_keyword("TO")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, parse_identifier),
-            )
-                .parse(remaining)
+            if let Some((rest, key, value)) =
+                self.parse_custom_keyword(KeywordScope::Via, remaining)
             {
-                to_layer = layer_name;
+                via.extra_properties.insert(key, value);
                 remaining = rest;
-            } else if let Ok((rest, (_, _, area_val))) = (
-                preceded(multispace0, parse_keyword("AREA")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
+            } else if let Some(rest) =
+                self.parse_builtin_keyword(&self.via_keywords, remaining, &mut via)
             {
-                area = area_val;
                 remaining = rest;
-            } else if let Ok((rest, (_, _, rpv_val))) = (
-                preceded(multispace0, parse_keyword("RPV")),
-                preceded(multispace0, parse_equals),
-                preceded(multispace0, double),
-            )
-                .parse(remaining)
+            } else if let Ok((rest, (prop_name, value))) = self.parse_property_assignment(remaining)
             {
-                rpv = rpv_val;
+                via.extra_properties.insert(prop_name.into_owned(), value);
                 remaining = rest;
             } else {
                 // Check if there's a closing brace on this line - if so, we should stop here
@@ -648,10 +794,12 @@ impl ItfParser {
 
                 if let Some(brace_pos) = current_line.find('}') {
                     // Found closing brace on this line - only skip content before the brace
+                    self.warn_skipped(remaining, &current_line[..brace_pos], "VIA text");
                     remaining = &remaining[brace_pos..];
                     break; // Exit the parsing loop, let the main parser handle the closing brace
                 } else {
                     // No closing brace, skip the entire line
+                    self.warn_skipped_line(remaining, "VIA line");
                     remaining = &remaining[next_line_end..];
                     if remaining.starts_with('\n') {
                         remaining = &remaining[1..];
@@ -662,13 +810,10 @@ impl ItfParser {
 
         let (input, _) = preceded(multispace0, parse_right_brace).parse(remaining)?;
 
-        Ok((
-            input,
-            ViaConnection::new(name, from_layer, to_layer, area, rpv),
-        ))
+        Ok((input, via))
     }
 
-    fn parse_crt_vs_si_width_table<'a>(
+    pub(crate) fn parse_crt_vs_si_width_table<'a>(
         &self,
         input: &'a str,
     ) -> IResult<&'a str, CrtVsSiWidthTable> {
@@ -732,7 +877,7 @@ impl ItfParser {
         ))
     }
 
-    fn parse_rho_vs_si_width_thickness_table<'a>(
+    pub(crate) fn parse_rho_vs_si_width_thickness_table<'a>(
         &self,
         input: &'a str,
     ) -> IResult<&'a str, LookupTable2D> {
@@ -776,6 +921,12 @@ pub enum ParseError {
     #[error("Encrypted ITF file: {0}")]
     EncryptedFile(String),
 
+    #[error("Parsing was cancelled")]
+    Cancelled,
+
+    #[error("Duplicate layer '{name}' on line {line}")]
+    DuplicateLayer { name: String, line: usize },
+
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 }
@@ -0,0 +1,308 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{ConductorLayer, PropValue, TechnologyInfo, ViaConnection};
+use crate::parser::itf_parser::ItfParser;
+use crate::parser::lexer::{parse_equals, parse_identifier, parse_keyword};
+use nom::{
+    branch::alt, character::complete::multispace0, combinator::value, number::complete::double,
+    sequence::preceded, IResult, Parser,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Block a custom keyword is recognized in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeywordScope {
+    Conductor,
+    Dielectric,
+    Via,
+}
+
+/// Parses the text after a custom keyword into the value stored in `extra_properties`
+///
+/// Returns the remaining input and the value, or `None` to leave the line to the built-in
+/// parsing.
+pub type CustomKeywordHandler = Arc<dyn Fn(&str) -> Option<(&str, PropValue)> + Send + Sync>;
+
+/// Parses the text after a built-in keyword into its field of `T`
+pub(crate) type KeywordHandler<T> = for<'a> fn(&ItfParser, &'a str, &mut T) -> IResult<&'a str, ()>;
+
+/// Built-in keywords of one kind of block and their handlers
+pub(crate) struct KeywordRegistry<T> {
+    handlers: HashMap<&'static str, KeywordHandler<T>>,
+}
+
+impl<T> KeywordRegistry<T> {
+    fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    fn with(mut self, keyword: &'static str, handler: KeywordHandler<T>) -> Self {
+        self.handlers.insert(keyword, handler);
+        self
+    }
+
+    pub(crate) fn get(&self, keyword: &str) -> Option<KeywordHandler<T>> {
+        self.handlers.get(keyword).copied()
+    }
+}
+
+/// `= <number>`
+fn assigned_number(input: &str) -> IResult<&str, f64> {
+    preceded(parse_equals, preceded(multispace0, double)).parse(input)
+}
+
+/// `= <word>`
+fn assigned_identifier(input: &str) -> IResult<&str, &str> {
+    preceded(parse_equals, parse_identifier).parse(input)
+}
+
+/// Header keywords, also accepted between layers
+pub(crate) fn technology_keywords() -> KeywordRegistry<TechnologyInfo> {
+    KeywordRegistry::<TechnologyInfo>::new()
+        .with("TECHNOLOGY", |_, input, info| {
+            let (rest, name) = assigned_identifier(input)?;
+            info.name = name.to_string();
+            Ok((rest, ()))
+        })
+        .with("GLOBAL_TEMPERATURE", |_, input, info| {
+            let (rest, temperature) = assigned_number(input)?;
+            info.global_temperature = Some(temperature);
+            Ok((rest, ()))
+        })
+        .with("REFERENCE_DIRECTION", |_, input, info| {
+            let (rest, direction) = assigned_identifier(input)?;
+            info.reference_direction = Some(direction.to_string());
+            Ok((rest, ()))
+        })
+        .with("BACKGROUND_ER", |_, input, info| {
+            let (rest, er) = assigned_number(input)?;
+            info.background_er = Some(er);
+            Ok((rest, ()))
+        })
+        .with("HALF_NODE_SCALE_FACTOR", |_, input, info| {
+            let (rest, factor) = assigned_number(input)?;
+            info.half_node_scale_factor = Some(factor);
+            Ok((rest, ()))
+        })
+        .with("USE_SI_DENSITY", |_, input, info| {
+            let (rest, use_si) = preceded(
+                parse_equals,
+                preceded(
+                    multispace0,
+                    alt((
+                        value(true, parse_keyword("YES")),
+                        value(false, parse_keyword("NO")),
+                    )),
+                ),
+            )
+            .parse(input)?;
+            info.use_si_density = Some(use_si);
+            Ok((rest, ()))
+        })
+        .with("DROP_FACTOR_LATERAL_SPACING", |_, input, info| {
+            let (rest, factor) = assigned_number(input)?;
+            info.drop_factor_lateral_spacing = Some(factor);
+            Ok((rest, ()))
+        })
+}
+
+/// Conductor keywords with a dedicated field; the others go to `extra_properties`
+pub(crate) fn conductor_keywords() -> KeywordRegistry<ConductorLayer> {
+    KeywordRegistry::<ConductorLayer>::new()
+        .with("THICKNESS", |_, input, layer| {
+            let (rest, thickness) = assigned_number(input)?;
+            layer.thickness = thickness;
+            layer.physical_props.thickness = thickness;
+            Ok((rest, ()))
+        })
+        .with("CRT1", |_, input, layer| {
+            let (rest, crt1) = assigned_number(input)?;
+            layer.electrical_props.crt1 = Some(crt1);
+            Ok((rest, ()))
+        })
+        .with("CRT2", |_, input, layer| {
+            let (rest, crt2) = assigned_number(input)?;
+            layer.electrical_props.crt2 = Some(crt2);
+            Ok((rest, ()))
+        })
+        .with("RPSQ", |_, input, layer| {
+            let (rest, rpsq) = assigned_number(input)?;
+            layer.electrical_props.rpsq = Some(rpsq);
+            Ok((rest, ()))
+        })
+        .with("WMIN", |_, input, layer| {
+            let (rest, wmin) = assigned_number(input)?;
+            layer.physical_props.width_min = Some(wmin);
+            Ok((rest, ()))
+        })
+        .with("SMIN", |_, input, layer| {
+            let (rest, smin) = assigned_number(input)?;
+            layer.physical_props.spacing_min = Some(smin);
+            Ok((rest, ()))
+        })
+        .with("SIDE_TANGENT", |_, input, layer| {
+            let (rest, side_tangent) = assigned_number(input)?;
+            layer.physical_props.side_tangent = Some(side_tangent);
+            Ok((rest, ()))
+        })
+        .with("LAYER_TYPE", |_, input, layer| {
+            let (rest, layer_type) = assigned_identifier(input)?;
+            layer.gate_props.layer_type = Some(layer_type.to_string());
+            Ok((rest, ()))
+        })
+        .with("GATE_FORMING_LAYER", |_, input, layer| {
+            let (rest, value) = assigned_identifier(input)?;
+            layer.gate_props.gate_forming_layer =
+                value.eq_ignore_ascii_case("TRUE") || value.eq_ignore_ascii_case("YES");
+            Ok((rest, ()))
+        })
+        .with("GATE_TO_CONTACT_SMIN", |_, input, layer| {
+            let (rest, smin) = assigned_number(input)?;
+            layer.gate_props.gate_to_contact_smin = Some(smin);
+            Ok((rest, ()))
+        })
+        .with("GATE_TO_DIFFUSION_CAP", |parser, input, layer| {
+            let (rest, ()) = parser.skip_complex_block(input)?;
+            layer.gate_props.has_gate_to_diffusion_cap = true;
+            Ok((rest, ()))
+        })
+        .with(
+            "POLYNOMIAL_BASED_THICKNESS_VARIATION",
+            |parser, input, _| parser.skip_complex_block(input),
+        )
+        .with("RHO_VS_WIDTH_AND_SPACING", |parser, input, layer| {
+            let (rest, table) = parser.parse_lookup_table_2d(input)?;
+            layer.rho_vs_width_spacing = Some(table);
+            Ok((rest, ()))
+        })
+        .with("ETCH_VS_WIDTH_AND_SPACING", |parser, input, layer| {
+            let (rest, table) = parser.parse_etch_table(input)?;
+            layer.etch_vs_width_spacing = Some(table);
+            Ok((rest, ()))
+        })
+        .with("THICKNESS_VS_WIDTH_AND_SPACING", |parser, input, layer| {
+            let (rest, table) = parser.parse_lookup_table_2d(input)?;
+            layer.thickness_vs_width_spacing = Some(table);
+            Ok((rest, ()))
+        })
+        .with("RHO_VS_SI_WIDTH_AND_THICKNESS", |parser, input, layer| {
+            let (rest, table) = parser.parse_rho_vs_si_width_thickness_table(input)?;
+            layer.rho_vs_si_width_thickness = Some(table);
+            Ok((rest, ()))
+        })
+        .with("CRT_VS_SI_WIDTH", |parser, input, layer| {
+            let (rest, table) = parser.parse_crt_vs_si_width_table(input)?;
+            layer.crt_vs_si_width = Some(table);
+            Ok((rest, ()))
+        })
+}
+
+/// Via keywords with a dedicated field; the others go to `extra_properties`
+pub(crate) fn via_keywords() -> KeywordRegistry<ViaConnection> {
+    KeywordRegistry::<ViaConnection>::new()
+        .with("FROM", |_, input, via| {
+            let (rest, layer_name) = assigned_identifier(input)?;
+            via.from_layer = layer_name.to_string();
+            Ok((rest, ()))
+        })
+        .with("TO", |_, input, via| {
+            let (rest, layer_name) = assigned_identifier(input)?;
+            via.to_layer = layer_name.to_string();
+            Ok((rest, ()))
+        })
+        .with("AREA", |_, input, via| {
+            let (rest, area) = assigned_number(input)?;
+            via.area = area;
+            Ok((rest, ()))
+        })
+        .with("RPV", |_, input, via| {
+            let (rest, rpv) = assigned_number(input)?;
+            via.resistance_per_via = rpv;
+            Ok((rest, ()))
+        })
+}
+
+/// Custom handler for `KEYWORD = free text`, keeping the text up to the end of the line or
+/// the closing brace of the block
+///
+/// Unlike the built-in assignments, the value may hold spaces and punctuation.
+pub fn parse_assigned_text(input: &str) -> Option<(&str, PropValue)> {
+    let (input, ()) = parse_equals(input).ok()?;
+    let end = input.find(['\n', '}']).unwrap_or(input.len());
+    let text = input[..end].trim();
+    (!text.is_empty()).then(|| (&input[end..], PropValue::Text(text.to_string())))
+}
+
+/// Custom handler for `KEYWORD { ... }`, keeping what is between the braces as text
+///
+/// Nested braces are kept with the text, so a vendor block can be parsed later.
+pub fn parse_braced_text(input: &str) -> Option<(&str, PropValue)> {
+    let input = input.trim_start().strip_prefix('{')?;
+    let mut depth = 1;
+    for (index, c) in input.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    let text = input[..index].trim().to_string();
+                    return Some((&input[index + 1..], PropValue::Text(text)));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_keyword_helpers() {
+        assert_eq!(
+            parse_assigned_text(" = rev 2.1, 2025-03\nRPSQ = 0.1"),
+            Some((
+                "\nRPSQ = 0.1",
+                PropValue::Text("rev 2.1, 2025-03".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_assigned_text(" = low-k } "),
+            Some(("} ", PropValue::Text("low-k".to_string())))
+        );
+        assert_eq!(parse_assigned_text(" = \n"), None);
+
+        assert_eq!(
+            parse_braced_text(" { MODE = 2 { A B } }\nRPSQ = 0.1"),
+            Some((
+                "\nRPSQ = 0.1",
+                PropValue::Text("MODE = 2 { A B }".to_string())
+            ))
+        );
+        assert_eq!(parse_braced_text(" { unterminated"), None);
+        assert_eq!(parse_braced_text(" = 1"), None);
+    }
+
+    #[test]
+    fn test_builtin_registries() {
+        let parser = ItfParser::new();
+        let mut layer = ConductorLayer::new("metal1".to_string(), 0.0);
+        let handler = conductor_keywords().get("THICKNESS").unwrap();
+        let (rest, ()) = handler(&parser, " = 0.25 RPSQ = 1", &mut layer).unwrap();
+        assert_eq!(rest, " RPSQ = 1");
+        assert_eq!(layer.thickness, 0.25);
+        assert_eq!(layer.physical_props.thickness, 0.25);
+        assert!(conductor_keywords().get("thickness").is_none());
+
+        let mut info = TechnologyInfo::new("unknown".to_string());
+        let handler = technology_keywords().get("USE_SI_DENSITY").unwrap();
+        handler(&parser, " = NO", &mut info).unwrap();
+        assert_eq!(info.use_si_density, Some(false));
+    }
+}
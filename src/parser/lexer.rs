@@ -6,7 +6,7 @@ use nom::{
     bytes::complete::{tag, take_until, take_while1},
     character::complete::{char, digit1, multispace0},
     combinator::{map, opt, recognize, value},
-    multi::{separated_list0, separated_list1},
+    multi::separated_list0,
     number::complete::double,
     sequence::{delimited, preceded, terminated},
     IResult, Parser,
@@ -245,31 +245,70 @@ pub fn parse_number_list(input: &str) -> IResult<&str, Vec<f64>> {
     .parse(input)
 }
 
+/// Matrix of numbers in braces, one row per line
+///
+/// Lookup table bodies can hold thousands of values, so rows are split on line breaks
+/// and parsed without nom. With the `fast-tables` feature, numbers are parsed with
+/// lexical and large bodies are spread over all cores.
 pub fn parse_2d_number_matrix(input: &str) -> IResult<&str, Vec<Vec<f64>>> {
-    use nom::character::complete::space1;
-    use nom::multi::many1;
+    use nom::error::{Error, ErrorKind};
 
-    preceded(
-        multispace0,
-        delimited(
-            char('{'),
-            preceded(
-                multispace0,
-                many1(preceded(multispace0, separated_list1(space1, double))),
-            ),
-            preceded(multispace0, char('}')),
-        ),
-    )
-    .parse(input)
+    let (body, _) = preceded(multispace0, char('{')).parse(input)?;
+    let (body, rest) = body
+        .split_once('}')
+        .ok_or_else(|| nom::Err::Error(Error::new(body, ErrorKind::Char)))?;
+
+    let rows: Vec<&str> = body.lines().filter(|row| !row.trim().is_empty()).collect();
+    if rows.is_empty() {
+        return Err(nom::Err::Error(Error::new(body, ErrorKind::Many1)));
+    }
+
+    let matrix = parse_matrix_rows(body.len(), &rows)
+        .ok_or_else(|| nom::Err::Error(Error::new(body, ErrorKind::Float)))?;
+    Ok((rest, matrix))
+}
+
+/// Table bodies of at least this many bytes are parsed in parallel
+#[cfg(feature = "fast-tables")]
+const PARALLEL_TABLE_BYTES: usize = 16 * 1024;
+
+#[cfg(feature = "fast-tables")]
+fn parse_matrix_rows(body_len: usize, rows: &[&str]) -> Option<Vec<Vec<f64>>> {
+    use rayon::prelude::*;
+
+    if body_len >= PARALLEL_TABLE_BYTES {
+        rows.par_iter().map(|row| parse_matrix_row(row)).collect()
+    } else {
+        rows.iter().map(|row| parse_matrix_row(row)).collect()
+    }
 }
 
-pub fn parse_identifier(input: &str) -> IResult<&str, String> {
+#[cfg(not(feature = "fast-tables"))]
+fn parse_matrix_rows(_body_len: usize, rows: &[&str]) -> Option<Vec<Vec<f64>>> {
+    rows.iter().map(|row| parse_matrix_row(row)).collect()
+}
+
+fn parse_matrix_row(row: &str) -> Option<Vec<f64>> {
+    row.split_ascii_whitespace()
+        .map(parse_table_value)
+        .collect()
+}
+
+#[cfg(feature = "fast-tables")]
+fn parse_table_value(text: &str) -> Option<f64> {
+    lexical_core::parse(text.as_bytes()).ok()
+}
+
+#[cfg(not(feature = "fast-tables"))]
+fn parse_table_value(text: &str) -> Option<f64> {
+    text.parse().ok()
+}
+
+/// Name or word borrowed from the input; callers copy it only when they keep it
+pub fn parse_identifier(input: &str) -> IResult<&str, &str> {
     preceded(
         multispace0,
-        map(
-            take_while1(|c: char| c.is_alphanumeric() || c == '_' || c == '+' || c == '-'),
-            |s: &str| s.to_string(),
-        ),
+        take_while1(|c: char| c.is_alphanumeric() || c == '_' || c == '+' || c == '-'),
     )
     .parse(input)
 }
@@ -335,11 +374,30 @@ mod tests {
         assert_eq!(numbers, vec![1.0, 2.0, 3.0]);
     }
 
+    #[test]
+    fn test_parse_identifier_borrows() {
+        let input = "  metal1 {";
+        let (rest, name) = parse_identifier(input).unwrap();
+        assert_eq!(name, "metal1");
+        assert_eq!(rest, " {");
+        assert!(std::ptr::eq(name.as_ptr(), input[2..].as_ptr()));
+    }
+
     #[test]
     fn test_parse_2d_matrix() {
         let input = "{ 1.0 2.0\n3.0 4.0 }";
         let (_, matrix) = parse_2d_number_matrix(input).unwrap();
         assert_eq!(matrix, vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+
+        // Blank lines, tabs, exponents and text after the closing brace
+        let input = "{\n  1.5e-2\t-2\n\n  .5 3. }\nNEXT";
+        let (rest, matrix) = parse_2d_number_matrix(input).unwrap();
+        assert_eq!(matrix, vec![vec![0.015, -2.0], vec![0.5, 3.0]]);
+        assert_eq!(rest, "\nNEXT");
+
+        assert!(parse_2d_number_matrix("{ 1.0 abc }").is_err());
+        assert!(parse_2d_number_matrix("{ }").is_err());
+        assert!(parse_2d_number_matrix("{ 1.0 2.0").is_err());
     }
 
     #[test]
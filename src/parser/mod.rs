@@ -1,8 +1,16 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+pub mod duplicate_names;
+pub mod encoding;
 pub mod itf_parser;
+pub mod keywords;
 pub mod lexer;
+pub mod number_format;
 
+pub use duplicate_names::*;
+pub use encoding::*;
 pub use itf_parser::*;
+pub use keywords::*;
 pub use lexer::*;
+pub use number_format::*;
@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// How the parser treats assigned numbers written with a locale's separators
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NumberFormat {
+    /// Only `1234.5` style numbers; locale-formatted values are reported, not read
+    Strict,
+    /// Also read values such as `0,25`, `1.234,5` or `1'234.5`, with a warning
+    #[default]
+    Lenient,
+}
+
+/// A locale-formatted number found on the right-hand side of an assignment
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocaleNumber {
+    /// 1-based line of the value
+    pub line: usize,
+    pub original: String,
+    /// The value in standard notation
+    pub normalized: String,
+}
+
+/// Standard notation of a number written with a decimal comma or group separators
+///
+/// Apostrophes always group thousands. With both `.` and `,` the last one is the
+/// decimal separator; a single `.` or `,` on its own is the decimal separator too, so
+/// `1,234` reads as 1.234. Repeated separators group thousands and must be followed by
+/// exactly three digits. Returns `None` for plain numbers and for anything ambiguous.
+pub fn normalize_locale_number(token: &str) -> Option<String> {
+    let (sign, unsigned) = match token.strip_prefix(['+', '-']) {
+        Some(rest) => (&token[..1], rest),
+        None => ("", token),
+    };
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(index) => unsigned.split_at(index),
+        None => (unsigned, ""),
+    };
+    if !mantissa.contains([',', '\'']) {
+        return None;
+    }
+
+    let last_comma = mantissa.rfind(',');
+    let last_dot = mantissa.rfind('.');
+    let decimal = match (last_comma, last_dot) {
+        (Some(comma), Some(dot)) => Some(if comma > dot { ',' } else { '.' }),
+        (Some(_), None) if mantissa.matches(',').count() == 1 => Some(','),
+        (None, Some(_)) if mantissa.matches('.').count() == 1 => Some('.'),
+        _ => None,
+    };
+
+    let (integer, fraction) = match decimal {
+        Some(separator) => {
+            let (integer, fraction) = mantissa.rsplit_once(separator)?;
+            if fraction.contains([',', '.', '\'']) {
+                return None;
+            }
+            (integer, fraction)
+        }
+        None => (mantissa, ""),
+    };
+
+    // Every group after the first has exactly three digits
+    let mut groups = integer.split([',', '.', '\'']);
+    let first = groups.next()?;
+    if first.is_empty() && integer.len() != first.len() {
+        return None;
+    }
+    let mut digits = first.to_string();
+    for group in groups {
+        if group.len() != 3 || first.len() > 3 {
+            return None;
+        }
+        digits.push_str(group);
+    }
+    if digits.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if !digits
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let normalized = if fraction.is_empty() {
+        format!("{sign}{digits}{exponent}")
+    } else {
+        format!("{sign}{digits}.{fraction}{exponent}")
+    };
+    normalized.parse::<f64>().ok().map(|_| normalized)
+}
+
+/// Assigned values of `content` that use a locale's separators, and `content` with
+/// them rewritten in standard notation when `rewrite` is set
+///
+/// Only the value right after an `=` is considered, so the comma separated tuples of
+/// CRT_VS_SI_WIDTH tables are left alone.
+pub fn find_locale_numbers(content: &str, rewrite: bool) -> (Cow<'_, str>, Vec<LocaleNumber>) {
+    static VALUE: OnceLock<Regex> = OnceLock::new();
+    let value = VALUE.get_or_init(|| {
+        Regex::new(r"=[ \t]*([+-]?[0-9.,']*[0-9](?:[eE][+-]?[0-9]+)?)([ \t}\r\n$]|$)").unwrap()
+    });
+
+    let mut found = Vec::new();
+    let mut output = String::new();
+    let mut copied = 0;
+    for captures in value.captures_iter(content) {
+        let token = captures.get(1).unwrap();
+        let Some(normalized) = normalize_locale_number(token.as_str()) else {
+            continue;
+        };
+        found.push(LocaleNumber {
+            line: content[..token.start()].matches('\n').count() + 1,
+            original: token.as_str().to_string(),
+            normalized: normalized.clone(),
+        });
+        if rewrite {
+            output.push_str(&content[copied..token.start()]);
+            output.push_str(&normalized);
+            copied = token.end();
+        }
+    }
+
+    if !rewrite || found.is_empty() {
+        return (Cow::Borrowed(content), found);
+    }
+    output.push_str(&content[copied..]);
+    (Cow::Owned(output), found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_locale_number() {
+        let cases = [
+            ("0,25", Some("0.25")),
+            ("-1,5e-3", Some("-1.5e-3")),
+            ("1.234,5", Some("1234.5")),
+            ("1,234.5", Some("1234.5")),
+            ("1'234.5", Some("1234.5")),
+            ("1,234,567", Some("1234567")),
+            ("1.234.567,25", Some("1234567.25")),
+            (",5", Some(".5")),
+            // Plain numbers need no rewriting
+            ("0.25", None),
+            ("1234", None),
+            // Malformed groups are left for the parser to report
+            ("1,23,4", None),
+            ("12345,678.9", None),
+            ("1.2.3,4", None),
+        ];
+        for (token, expected) in cases {
+            assert_eq!(
+                normalize_locale_number(token).as_deref(),
+                expected,
+                "{token}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_locale_numbers() {
+        let content = "DIELECTRIC ild {THICKNESS=0,25 ER=4.2}\n\
+                       CONDUCTOR m1 {THICKNESS = 1.234,5\n\
+                       CRT_VS_SI_WIDTH {(0.1, 1e-3, 2e-4)}\n\
+                       RPSQ=0,05}\n";
+
+        let (rewritten, found) = find_locale_numbers(content, true);
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].line, 1);
+        assert_eq!(found[1].original, "1.234,5");
+        assert_eq!(found[1].normalized, "1234.5");
+        assert_eq!(found[2].line, 4);
+        assert!(rewritten.contains("THICKNESS=0.25 ER=4.2}"));
+        assert!(rewritten.contains("(0.1, 1e-3, 2e-4)"));
+        assert!(rewritten.contains("RPSQ=0.05}"));
+        assert_eq!(rewritten.lines().count(), content.lines().count());
+
+        // Reporting only leaves the content untouched
+        let (unchanged, found) = find_locale_numbers(content, false);
+        assert!(matches!(unchanged, Cow::Borrowed(_)));
+        assert_eq!(found.len(), 3);
+
+        let (plain, found) = find_locale_numbers("DIELECTRIC d {THICKNESS=0.5}", true);
+        assert!(matches!(plain, Cow::Borrowed(_)));
+        assert!(found.is_empty());
+    }
+}
@@ -1,20 +1,44 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use crate::data::{Layer, LayerType, ViaType};
-use egui::Color32;
-
+use crate::data::{ErRegionKind, Layer, LayerClass, LayerType, ViaType};
+use crate::renderer::hatching::{
+    default_hatch_rules, select_hatch_pattern, HatchPattern, HatchRule,
+};
+use crate::renderer::theme::{Theme, ThemeStyle};
+use egui::{Color32, Stroke};
+use std::collections::HashMap;
+
+#[derive(Clone)]
 pub struct ColorScheme {
     pub conductor_base: Color32,
     pub dielectric_base: Color32,
     pub via_metal: Color32,
     pub via_contact: Color32,
     pub substrate: Color32,
+    /// Field regions below the stack
+    pub field: Color32,
     pub poly: Color32,
+    pub gate_outline: Color32,
+    pub barrier: Color32,
+    pub seed: Color32,
+    pub capping: Color32,
+    pub etch_stop: Color32,
+    pub passivation: Color32,
     pub metal_colors: Vec<Color32>,
     pub selection_highlight: Color32,
     pub text_color: Color32,
     pub background: Color32,
+    /// User-chosen colors by layer name, taking precedence over the defaults
+    pub layer_overrides: HashMap<String, Color32>,
+    /// User-chosen layer classes by layer name, taking precedence over the inferred ones
+    pub class_overrides: HashMap<String, LayerClass>,
+    /// Hatch patterns of dielectrics, first matching rule wins
+    pub hatch_rules: Vec<HatchRule>,
+    /// Fill alpha by layer or via name, replacing the default transparency
+    pub alpha_overrides: HashMap<String, u8>,
+    /// Class colors and hatches from a theme file, taking precedence over the defaults
+    pub theme: Option<Theme>,
 }
 
 impl ColorScheme {
@@ -28,9 +52,18 @@ impl ColorScheme {
             // Blue/gray tones for dielectrics (silicon dioxide)
             dielectric_base: Color32::from_rgb(100, 149, 237), // Cornflower blue
             substrate: Color32::from_rgb(47, 79, 79),          // Dark slate gray
+            field: Color32::from_rgb(85, 107, 47),             // Dark olive green
 
             // Special conductor colors
             poly: Color32::from_rgb(255, 215, 0), // Gold for polysilicon
+            gate_outline: Color32::from_rgb(218, 112, 214), // Orchid outline for gate layers
+            barrier: Color32::from_rgb(112, 128, 144), // Slate gray for barriers and liners
+            seed: Color32::from_rgb(184, 115, 51), // Copper for seed layers
+
+            // Thin functional dielectrics
+            capping: Color32::from_rgb(0, 139, 139), // Dark cyan for metal caps
+            etch_stop: Color32::from_rgb(72, 61, 139), // Dark slate blue for etch stops
+            passivation: Color32::from_rgb(60, 100, 120), // Darker blue for passivation
 
             // Metal layer progression (orange to red tones)
             metal_colors: vec![
@@ -48,25 +81,43 @@ impl ColorScheme {
             selection_highlight: Color32::from_rgb(255, 255, 0), // Yellow
             text_color: Color32::WHITE,
             background: Color32::from_rgb(25, 25, 25), // Dark gray
+
+            layer_overrides: HashMap::new(),
+            class_overrides: HashMap::new(),
+            hatch_rules: default_hatch_rules(),
+            alpha_overrides: HashMap::new(),
+            theme: None,
         }
     }
 
     pub fn get_layer_color(&self, layer: &Layer, layer_index: usize) -> Color32 {
-        match layer {
-            Layer::Dielectric(d) => {
-                if d.name.to_lowercase().contains("substrate") {
-                    self.substrate
-                } else {
-                    self.get_dielectric_color(&d.name)
-                }
-            }
-            Layer::Conductor(c) => {
-                if c.name.to_lowercase().contains("poly") {
+        if let Some(color) = self.layer_overrides.get(layer.name()) {
+            return *color;
+        }
+        self.get_default_layer_color(layer, layer_index)
+    }
+
+    /// Layer color ignoring user overrides
+    pub fn get_default_layer_color(&self, layer: &Layer, layer_index: usize) -> Color32 {
+        let name_lower = layer.name().to_lowercase();
+        let class = self.get_layer_class(layer);
+        if let Some(color) = self.get_theme_style(class).color {
+            return color;
+        }
+        match class {
+            LayerClass::Substrate => self.substrate,
+            LayerClass::Passivation => self.passivation,
+            LayerClass::Capping => self.capping,
+            LayerClass::EtchStop => self.etch_stop,
+            LayerClass::Dielectric => self.get_dielectric_color(layer.name()),
+            LayerClass::Gate => self.poly,
+            LayerClass::BarrierLiner => self.barrier,
+            LayerClass::Seed => self.seed,
+            LayerClass::SignalMetal => {
+                if name_lower.contains("poly") {
                     self.poly
-                } else if c.name.to_lowercase().starts_with("metal")
-                    || c.name.to_lowercase().starts_with("alpa")
-                {
-                    self.get_metal_color(&c.name, layer_index)
+                } else if name_lower.starts_with("metal") || name_lower.starts_with("alpa") {
+                    self.get_metal_color(layer.name(), layer_index)
                 } else {
                     self.conductor_base
                 }
@@ -74,6 +125,53 @@ impl ColorScheme {
         }
     }
 
+    /// Layer class, honoring the user's class overrides
+    pub fn get_layer_class(&self, layer: &Layer) -> LayerClass {
+        LayerClass::of(layer, &self.class_overrides)
+    }
+
+    /// Set or clear (`None`) the color override of a layer
+    pub fn set_layer_override(&mut self, layer_name: &str, color: Option<Color32>) {
+        match color {
+            Some(color) => {
+                self.layer_overrides.insert(layer_name.to_string(), color);
+            }
+            None => {
+                self.layer_overrides.remove(layer_name);
+            }
+        }
+    }
+
+    /// Hatch pattern drawn over the fill; conductors are never hatched
+    pub fn get_layer_hatch(&self, layer: &Layer) -> HatchPattern {
+        match layer {
+            Layer::Dielectric(d) => self
+                .get_theme_style(self.get_layer_class(layer))
+                .hatch
+                .unwrap_or_else(|| select_hatch_pattern(&self.hatch_rules, d)),
+            Layer::Conductor(_) => HatchPattern::None,
+        }
+    }
+
+    /// Style the theme gives `class`, empty without a theme
+    pub fn get_theme_style(&self, class: LayerClass) -> ThemeStyle {
+        self.theme
+            .as_ref()
+            .map(|theme| theme.get_style(class))
+            .unwrap_or_default()
+    }
+
+    /// Fill of a substrate or field region, the substrate following the theme
+    pub fn get_er_region_color(&self, kind: ErRegionKind) -> Color32 {
+        match kind {
+            ErRegionKind::Substrate => self
+                .get_theme_style(LayerClass::Substrate)
+                .color
+                .unwrap_or(self.substrate),
+            ErRegionKind::Field => self.field,
+        }
+    }
+
     pub fn get_via_color(&self, via_type: ViaType) -> Color32 {
         match via_type {
             ViaType::Contact => self.via_contact,
@@ -89,8 +187,6 @@ impl ColorScheme {
             Color32::from_rgb(70, 130, 180) // Steel blue for nitride
         } else if name_lower.contains("oxide") {
             Color32::from_rgb(100, 149, 237) // Cornflower blue for oxide
-        } else if name_lower.contains("pass") {
-            Color32::from_rgb(60, 100, 120) // Darker blue for passivation
         } else if name_lower.contains("pmd")
             || name_lower.contains("imd")
             || name_lower.contains("ild")
@@ -149,16 +245,33 @@ impl ColorScheme {
     pub fn get_layer_alpha(&self, layer: &Layer, is_selected: bool) -> u8 {
         let base_alpha = match layer.layer_type() {
             LayerType::Conductor => 220,
+            LayerType::Gate => 240,
             LayerType::Dielectric => 100,
         };
 
         if is_selected {
             255 // Fully opaque when selected
         } else {
-            base_alpha
+            self.get_alpha_override(layer.name()).unwrap_or(base_alpha)
         }
     }
 
+    /// Set or clear (`None`) the fill alpha of a layer or via
+    pub fn set_alpha_override(&mut self, name: &str, alpha: Option<u8>) {
+        match alpha {
+            Some(alpha) => {
+                self.alpha_overrides.insert(name.to_string(), alpha);
+            }
+            None => {
+                self.alpha_overrides.remove(name);
+            }
+        }
+    }
+
+    pub fn get_alpha_override(&self, name: &str) -> Option<u8> {
+        self.alpha_overrides.get(name).copied()
+    }
+
     pub fn apply_alpha(&self, color: Color32, alpha: u8) -> Color32 {
         Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), alpha)
     }
@@ -167,6 +280,17 @@ impl ColorScheme {
         Color32::LIGHT_GRAY
     }
 
+    pub fn get_layer_stroke(&self, layer: &Layer, is_selected: bool) -> Stroke {
+        if is_selected {
+            Stroke::new(2.0, self.selection_highlight)
+        } else if layer.is_gate() {
+            // Gate layers get a bold outline so they stand out at the bottom of the stack
+            Stroke::new(2.0, self.gate_outline)
+        } else {
+            Stroke::new(1.0, self.get_layer_outline_color(false))
+        }
+    }
+
     pub fn get_layer_outline_color(&self, is_selected: bool) -> Color32 {
         if is_selected {
             self.selection_highlight
@@ -234,6 +358,31 @@ mod tests {
         assert_eq!(alpa_color, *scheme.metal_colors.last().unwrap());
     }
 
+    #[test]
+    fn test_gate_layer_style() {
+        let scheme = ColorScheme::new();
+
+        let gate = Layer::Conductor(Box::new(
+            ConductorLayer::new("n_gpoly".to_string(), 0.04)
+                .with_gate_layer_type("GATE".to_string()),
+        ));
+        let metal1 = Layer::Conductor(Box::new(ConductorLayer::new("metal1".to_string(), 0.3)));
+
+        assert_eq!(scheme.get_layer_color(&gate, 0), scheme.poly);
+        assert_eq!(
+            scheme.get_layer_stroke(&gate, false).color,
+            scheme.gate_outline
+        );
+        assert_ne!(
+            scheme.get_layer_stroke(&metal1, false).color,
+            scheme.gate_outline
+        );
+        assert_eq!(
+            scheme.get_layer_stroke(&gate, true).color,
+            scheme.selection_highlight
+        );
+    }
+
     #[test]
     fn test_metal_number_extraction() {
         let scheme = ColorScheme::new();
@@ -246,6 +395,60 @@ mod tests {
         assert_eq!(scheme.extract_metal_number("alpa"), None);
     }
 
+    #[test]
+    fn test_layer_color_override() {
+        let mut scheme = ColorScheme::new();
+        let metal = Layer::Conductor(Box::new(ConductorLayer::new("metal1".to_string(), 0.3)));
+        let default_color = scheme.get_layer_color(&metal, 0);
+
+        scheme.set_layer_override("metal1", Some(Color32::from_rgb(0, 200, 0)));
+        assert_eq!(
+            scheme.get_layer_color(&metal, 0),
+            Color32::from_rgb(0, 200, 0)
+        );
+        assert_eq!(scheme.get_default_layer_color(&metal, 0), default_color);
+
+        scheme.set_layer_override("metal1", None);
+        assert_eq!(scheme.get_layer_color(&metal, 0), default_color);
+    }
+
+    #[test]
+    fn test_layer_class_colors() {
+        let mut scheme = ColorScheme::new();
+        let cap = Layer::Dielectric(DielectricLayer::new("imd2a".to_string(), 0.03, 5.7));
+        let liner = Layer::Conductor(Box::new(ConductorLayer::new("m1_tan".to_string(), 0.01)));
+        let pass = Layer::Dielectric(DielectricLayer::new("pass1".to_string(), 0.5, 4.0));
+
+        assert_eq!(scheme.get_layer_color(&cap, 0), scheme.capping);
+        assert_eq!(scheme.get_layer_color(&liner, 1), scheme.barrier);
+        assert_eq!(scheme.get_layer_color(&pass, 2), scheme.passivation);
+
+        scheme
+            .class_overrides
+            .insert("imd2a".to_string(), LayerClass::EtchStop);
+        assert_eq!(scheme.get_layer_color(&cap, 0), scheme.etch_stop);
+    }
+
+    #[test]
+    fn test_theme_styles() {
+        let mut scheme = ColorScheme::new();
+        let cap = Layer::Dielectric(DielectricLayer::new("imd2a".to_string(), 0.03, 5.7));
+        let pass = Layer::Dielectric(DielectricLayer::new("pass1".to_string(), 0.5, 4.0));
+        scheme.theme = Some(
+            Theme::parse("[classes.capping]\ncolor = \"#102030\"\nhatch = \"cross_hatch\"\n")
+                .unwrap(),
+        );
+
+        assert_eq!(
+            scheme.get_layer_color(&cap, 0),
+            Color32::from_rgb(0x10, 0x20, 0x30)
+        );
+        assert_eq!(scheme.get_layer_hatch(&cap), HatchPattern::CrossHatch);
+        // Classes the theme leaves out keep the built-in style
+        assert_eq!(scheme.get_layer_color(&pass, 1), scheme.passivation);
+        assert_eq!(scheme.get_layer_hatch(&pass), HatchPattern::None);
+    }
+
     #[test]
     fn test_via_colors() {
         let scheme = ColorScheme::new();
@@ -294,4 +497,19 @@ mod tests {
         assert_eq!(dielectric_alpha, 100);
         assert_eq!(selected_alpha, 255);
     }
+
+    #[test]
+    fn test_alpha_override() {
+        let mut scheme = ColorScheme::new();
+        let conductor = Layer::Conductor(Box::new(ConductorLayer::new("metal1".to_string(), 0.3)));
+
+        scheme.set_alpha_override("metal1", Some(51));
+        assert_eq!(scheme.get_layer_alpha(&conductor, false), 51);
+        // Selection still shows the layer fully
+        assert_eq!(scheme.get_layer_alpha(&conductor, true), 255);
+
+        scheme.set_alpha_override("metal1", None);
+        assert_eq!(scheme.get_layer_alpha(&conductor, false), 220);
+        assert_eq!(scheme.get_alpha_override("metal1"), None);
+    }
 }
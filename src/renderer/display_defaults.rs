@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use serde::{Deserialize, Serialize};
+
+/// How layer thicknesses are scaled in the cross-section
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RenderMode {
+    /// Thicknesses to scale
+    #[default]
+    TrueScale,
+    /// Thin layers enlarged so every layer stays readable
+    Schematic,
+}
+
+impl RenderMode {
+    pub const ALL: [RenderMode; 2] = [RenderMode::TrueScale, RenderMode::Schematic];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RenderMode::TrueScale => "True Scale",
+            RenderMode::Schematic => "Schematic",
+        }
+    }
+}
+
+/// How layers are colored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FillStyle {
+    #[default]
+    Filled,
+    /// Filled, with nitrides, low-k dielectrics and the substrate hatched
+    Hatched,
+    /// Outlines without fills
+    OutlineOnly,
+}
+
+impl FillStyle {
+    pub const ALL: [FillStyle; 3] = [
+        FillStyle::Filled,
+        FillStyle::Hatched,
+        FillStyle::OutlineOnly,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FillStyle::Filled => "Filled",
+            FillStyle::Hatched => "Hatched Dielectrics",
+            FillStyle::OutlineOnly => "Outline Only",
+        }
+    }
+}
+
+/// View settings applied whenever a stack is opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayDefaults {
+    pub render_mode: RenderMode,
+    pub show_dimensions: bool,
+    pub show_layer_names: bool,
+    pub fill_style: FillStyle,
+}
+
+impl Default for DisplayDefaults {
+    fn default() -> Self {
+        Self {
+            render_mode: RenderMode::TrueScale,
+            show_dimensions: true,
+            show_layer_names: true,
+            fill_style: FillStyle::Filled,
+        }
+    }
+}
@@ -2,7 +2,101 @@
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
 use crate::data::ConductorLayer;
+use crate::renderer::hatching::HatchPattern;
 use egui::{Color32, Pos2, Rect, Shape, Stroke, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// Default factor applied to SIDE_TANGENT by the single and multi-segment trapezoid shapes
+pub const SIDEWALL_EXAGGERATION: f32 = 3.0;
+
+/// Range of the adjustable sidewall exaggeration, 1.0 being the true angle
+pub const SIDEWALL_EXAGGERATION_RANGE: std::ops::RangeInclusive<f32> = 1.0..=10.0;
+
+/// Sidewall slope, as a tangent from vertical, of conductors drawn without true angles
+///
+/// The cross-section draws every conductor with a short edge half its long edge so the
+/// direction of the taper is visible however small SIDE_TANGENT is.
+pub const STYLIZED_SIDE_TANGENT: f32 = 0.5;
+
+/// Top and bottom width of a conductor trapezoid `height` tall whose long edge is
+/// twice its height
+///
+/// With an `exaggeration` the short edge follows SIDE_TANGENT times that factor, down to
+/// a tenth of the long edge; with `None` the stylized [`STYLIZED_SIDE_TANGENT`] slope is
+/// drawn.
+pub fn conductor_edge_widths(
+    height: f32,
+    side_tangent: f32,
+    exaggeration: Option<f32>,
+) -> (f32, f32) {
+    let long_edge = height * 2.0;
+    let short_edge = match exaggeration {
+        Some(factor) => {
+            (long_edge - 2.0 * height * (side_tangent * factor).abs()).max(long_edge * 0.1)
+        }
+        None => long_edge * 0.5,
+    };
+    if side_tangent >= 0.0 {
+        // Top wider (negative trapezoid - like etched metal)
+        (long_edge, short_edge)
+    } else {
+        // Top narrower (positive trapezoid - like deposited metal)
+        (short_edge, long_edge)
+    }
+}
+
+/// Smallest stroke width of a layer drawn in outline-only mode
+pub const OUTLINE_WIDTH: f32 = 1.0;
+
+/// A filled shape as its outline only, stroked in the fill color
+///
+/// Shapes without a fill, such as existing strokes and text, are returned unchanged.
+pub fn outline_shape(shape: Shape) -> Shape {
+    match shape {
+        Shape::Path(mut path) if path.fill != Color32::TRANSPARENT => {
+            path.stroke = egui::epaint::PathStroke::new(
+                path.stroke.width.max(OUTLINE_WIDTH),
+                path.fill.to_opaque(),
+            );
+            path.fill = Color32::TRANSPARENT;
+            Shape::Path(path)
+        }
+        Shape::Rect(mut rect) if rect.fill != Color32::TRANSPARENT => {
+            rect.stroke = Stroke::new(rect.stroke.width.max(OUTLINE_WIDTH), rect.fill.to_opaque());
+            rect.fill = Color32::TRANSPARENT;
+            Shape::Rect(rect)
+        }
+        Shape::Vec(shapes) => Shape::Vec(shapes.into_iter().map(outline_shape).collect()),
+        shape => shape,
+    }
+}
+
+/// A filled shape as a thin outline in `color`, for a stack drawn over another one
+pub fn ghost_shape(shape: Shape, color: Color32) -> Shape {
+    match outline_shape(shape) {
+        Shape::Path(mut path) => {
+            path.stroke = egui::epaint::PathStroke::new(OUTLINE_WIDTH, color);
+            Shape::Path(path)
+        }
+        Shape::Rect(mut rect) => {
+            rect.stroke = Stroke::new(OUTLINE_WIDTH, color);
+            Shape::Rect(rect)
+        }
+        Shape::Vec(shapes) => Shape::Vec(
+            shapes
+                .into_iter()
+                .map(|shape| ghost_shape(shape, color))
+                .collect(),
+        ),
+        shape => shape,
+    }
+}
+
+/// Sidewall slope as drawn, as a signed tangent from vertical
+pub fn drawn_side_tangent(side_tangent: f32, exaggeration: Option<f32>) -> f32 {
+    let (top_width, bottom_width) = conductor_edge_widths(1.0, side_tangent, exaggeration);
+    (top_width - bottom_width) * 0.5
+}
 
 #[derive(Debug, Clone)]
 pub struct TrapezoidShape {
@@ -60,10 +154,30 @@ impl TrapezoidShape {
         fill_color: Color32,
         stroke: Stroke,
     ) -> Self {
-        let side_tangent = layer.physical_props.side_tangent.unwrap_or(0.0) as f32;
+        // Exaggerate the angle for better visualization
+        Self::from_conductor_layer_exaggerated(
+            layer,
+            bottom_center,
+            width,
+            height,
+            fill_color,
+            stroke,
+            SIDEWALL_EXAGGERATION,
+        )
+    }
 
-        // Exaggerate the angle for better visualization (multiply by factor)
-        let exaggerated_tangent = side_tangent * 3.0; // Make 60 degree trapezoids more visible
+    /// Trapezoid with SIDE_TANGENT multiplied by `exaggeration`, 1.0 for the true shape
+    pub fn from_conductor_layer_exaggerated(
+        layer: &ConductorLayer,
+        bottom_center: Pos2,
+        width: f32,
+        height: f32,
+        fill_color: Color32,
+        stroke: Stroke,
+        exaggeration: f32,
+    ) -> Self {
+        let side_tangent = layer.physical_props.side_tangent.unwrap_or(0.0) as f32;
+        let exaggerated_tangent = side_tangent * exaggeration;
 
         Self::new(
             bottom_center,
@@ -126,6 +240,18 @@ impl TrapezoidShape {
 
         Rect::from_min_max(Pos2::new(min_x, min_y), Pos2::new(max_x, max_y))
     }
+
+    /// Map the corners from world to screen coordinates
+    pub fn to_screen(&self, transform: &ViewTransform) -> Self {
+        Self {
+            bottom_left: transform.world_to_screen(self.bottom_left),
+            bottom_right: transform.world_to_screen(self.bottom_right),
+            top_left: transform.world_to_screen(self.top_left),
+            top_right: transform.world_to_screen(self.top_right),
+            fill_color: self.fill_color,
+            stroke: self.stroke,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -169,7 +295,7 @@ impl RectangleShape {
 
         // Debug output for coordinate transformation
         if cfg!(debug_assertions) {
-            println!(
+            eprintln!(
                 "DEBUG Rect Transform: World ({}, {}) -> Screen ({}, {}), Scale: {}",
                 world_width, world_height, screen_width, screen_height, transform.scale
             );
@@ -209,6 +335,17 @@ impl RectangleShape {
     pub fn get_bounds(&self) -> Rect {
         self.rect
     }
+
+    pub fn to_screen(&self, transform: &ViewTransform) -> Self {
+        Self {
+            rect: Rect::from_two_pos(
+                transform.world_to_screen(self.rect.min),
+                transform.world_to_screen(self.rect.max),
+            ),
+            fill_color: self.fill_color,
+            stroke: self.stroke,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -218,6 +355,8 @@ pub struct LayerGeometry {
     pub z_top: f32,
     pub shape: LayerShape,
     pub is_selected: bool,
+    /// Texture drawn over the fill when hatching is enabled
+    pub hatch: HatchPattern,
 }
 
 #[derive(Debug, Clone)]
@@ -265,7 +404,7 @@ impl MultiTrapezoidShape {
             };
 
             // Create segment-specific tangent for smooth transition
-            let segment_tangent = side_tangent * 3.0; // Exaggerated for visibility
+            let segment_tangent = side_tangent * SIDEWALL_EXAGGERATION;
 
             let trapezoid = TrapezoidShape::new(
                 segment_center,
@@ -301,6 +440,16 @@ impl MultiTrapezoidShape {
         }
         bounds
     }
+
+    pub fn to_screen(&self, transform: &ViewTransform) -> Self {
+        Self {
+            trapezoids: self
+                .trapezoids
+                .iter()
+                .map(|trapezoid| trapezoid.to_screen(transform))
+                .collect(),
+        }
+    }
 }
 
 /// Parameters for creating three-column trapezoid shape
@@ -312,6 +461,8 @@ pub struct ThreeColumnTrapezoidParams<'a> {
     pub stroke: Stroke,
     pub reference_trapezoid_width: Option<f32>,
     pub view_transform: Option<&'a ViewTransform>,
+    /// Factor applied to SIDE_TANGENT, `None` for the stylized slope
+    pub sidewall_exaggeration: Option<f32>,
 }
 
 impl ThreeColumnTrapezoidShape {
@@ -337,6 +488,7 @@ impl ThreeColumnTrapezoidShape {
             stroke,
             reference_trapezoid_width,
             view_transform,
+            sidewall_exaggeration: None,
         };
         Self::from_params(&params)
     }
@@ -354,7 +506,7 @@ impl ThreeColumnTrapezoidShape {
 
         // Debug output to track the unified approach
         if cfg!(debug_assertions) {
-            println!(
+            eprintln!(
                 "DEBUG Unified Layout: Layer '{}' - Distribution base: {}, Current trapezoid: {}",
                 params.layer.name, distribution_base_width, current_trapezoid_width
             );
@@ -421,23 +573,10 @@ impl ThreeColumnTrapezoidShape {
             screen_bottom_center.y,
         );
 
-        // Current layer's actual trapezoid width (may be smaller than base width)
-        // Scale to screen coordinates if transform is provided
-        let actual_trapezoid_width = if let Some(transform) = params.view_transform {
-            params.world_height * 2.0 * transform.scale // Screen coordinates
-        } else {
-            params.world_height * 2.0 // World coordinates
-        };
-        let short_edge_width = actual_trapezoid_width * 0.5; // Half of actual width
-
-        // Determine top and bottom widths based on side_tangent
-        let (top_width, bottom_width) = if side_tangent >= 0.0 {
-            // Top wider (negative trapezoid - like etched metal)
-            (actual_trapezoid_width, short_edge_width)
-        } else {
-            // Top narrower (positive trapezoid - like deposited metal)
-            (short_edge_width, actual_trapezoid_width)
-        };
+        // Current layer's actual trapezoid width (may be smaller than base width), in
+        // screen coordinates if a transform is provided, is twice the layer height
+        let (top_width, bottom_width) =
+            conductor_edge_widths(screen_height, side_tangent, params.sidewall_exaggeration);
 
         // Create 3 trapezoids, all trapezoids align to fixed three-column positions
         let left_trapezoid = Self::create_custom_trapezoid(
@@ -547,6 +686,14 @@ impl ThreeColumnTrapezoidShape {
         left_bounds.union(center_bounds).union(right_bounds)
     }
 
+    pub fn to_screen(&self, transform: &ViewTransform) -> Self {
+        Self {
+            left_trapezoid: self.left_trapezoid.to_screen(transform),
+            center_trapezoid: self.center_trapezoid.to_screen(transform),
+            right_trapezoid: self.right_trapezoid.to_screen(transform),
+        }
+    }
+
     /// Calculate the spacing between trapezoids
     pub fn get_spacing_info(&self) -> SpacingInfo {
         // Calculate spacing between trapezoids (edge to edge distance)
@@ -724,6 +871,7 @@ impl LayerGeometry {
             z_top,
             shape: LayerShape::Trapezoid(trapezoid),
             is_selected: false,
+            hatch: HatchPattern::None,
         }
     }
 
@@ -739,6 +887,7 @@ impl LayerGeometry {
             z_top,
             shape: LayerShape::MultiTrapezoid(multi_trapezoids),
             is_selected: false,
+            hatch: HatchPattern::None,
         }
     }
 
@@ -754,6 +903,7 @@ impl LayerGeometry {
             z_top,
             shape: LayerShape::ThreeColumnTrapezoid(three_column_trapezoids),
             is_selected: false,
+            hatch: HatchPattern::None,
         }
     }
 
@@ -769,9 +919,15 @@ impl LayerGeometry {
             z_top,
             shape: LayerShape::Rectangle(rectangle),
             is_selected: false,
+            hatch: HatchPattern::None,
         }
     }
 
+    pub fn with_hatch(mut self, hatch: HatchPattern) -> Self {
+        self.hatch = hatch;
+        self
+    }
+
     pub fn to_egui_shapes(&self) -> Vec<Shape> {
         let mut shapes = Vec::new();
 
@@ -814,13 +970,157 @@ impl LayerGeometry {
         }
     }
 
+    /// Front-face polygons of this geometry in screen coordinates, left to right
+    pub fn get_polygons(&self) -> Vec<(Vec<Pos2>, Color32, Stroke)> {
+        let trapezoid_polygon = |trap: &TrapezoidShape| {
+            (
+                vec![
+                    trap.bottom_left,
+                    trap.bottom_right,
+                    trap.top_right,
+                    trap.top_left,
+                ],
+                trap.fill_color,
+                trap.stroke,
+            )
+        };
+
+        match &self.shape {
+            LayerShape::Trapezoid(trap) => vec![trapezoid_polygon(trap)],
+            LayerShape::MultiTrapezoid(multi_trap) => multi_trap
+                .trapezoids
+                .iter()
+                .map(trapezoid_polygon)
+                .collect(),
+            LayerShape::ThreeColumnTrapezoid(three_trap) => vec![
+                trapezoid_polygon(&three_trap.left_trapezoid),
+                trapezoid_polygon(&three_trap.center_trapezoid),
+                trapezoid_polygon(&three_trap.right_trapezoid),
+            ],
+            LayerShape::Rectangle(rect) => {
+                let stroke = if self.is_selected {
+                    rect.stroke
+                } else {
+                    Stroke::NONE
+                };
+                vec![(
+                    vec![
+                        rect.rect.left_bottom(),
+                        rect.rect.right_bottom(),
+                        rect.rect.right_top(),
+                        rect.rect.left_top(),
+                    ],
+                    rect.fill_color,
+                    stroke,
+                )]
+            }
+        }
+    }
+
+    /// Extrude the front faces into a pseudo-3D block shifted by `depth_offset`
+    pub fn to_isometric_shapes(&self, depth_offset: Vec2) -> Vec<Shape> {
+        self.get_polygons()
+            .into_iter()
+            .flat_map(|(points, fill_color, stroke)| {
+                extrude_polygon(&points, depth_offset, fill_color, stroke)
+            })
+            .collect()
+    }
+
     pub fn set_selected(&mut self, selected: bool) {
         self.is_selected = selected;
     }
 
+    /// A single unhatched rectangle over the bounds, in the color of the main polygon
+    pub fn simplified(&self) -> Self {
+        let (fill_color, stroke) = match &self.shape {
+            LayerShape::Trapezoid(trap) => (trap.fill_color, trap.stroke),
+            LayerShape::MultiTrapezoid(multi_trap) => multi_trap
+                .trapezoids
+                .first()
+                .map_or((Color32::TRANSPARENT, Stroke::NONE), |trap| {
+                    (trap.fill_color, trap.stroke)
+                }),
+            LayerShape::ThreeColumnTrapezoid(three_trap) => (
+                three_trap.center_trapezoid.fill_color,
+                three_trap.center_trapezoid.stroke,
+            ),
+            LayerShape::Rectangle(rect) => (rect.fill_color, rect.stroke),
+        };
+
+        Self {
+            layer_name: self.layer_name.clone(),
+            z_bottom: self.z_bottom,
+            z_top: self.z_top,
+            shape: LayerShape::Rectangle(RectangleShape {
+                rect: self.get_bounds(),
+                fill_color,
+                stroke,
+            }),
+            is_selected: self.is_selected,
+            hatch: HatchPattern::None,
+        }
+    }
+
     pub fn get_thickness(&self) -> f32 {
         self.z_top - self.z_bottom
     }
+
+    /// The same geometry with its shape mapped from world to screen coordinates
+    pub fn to_screen(&self, transform: &ViewTransform) -> Self {
+        let shape = match &self.shape {
+            LayerShape::Trapezoid(trap) => LayerShape::Trapezoid(trap.to_screen(transform)),
+            LayerShape::MultiTrapezoid(multi_trap) => {
+                LayerShape::MultiTrapezoid(multi_trap.to_screen(transform))
+            }
+            LayerShape::ThreeColumnTrapezoid(three_trap) => {
+                LayerShape::ThreeColumnTrapezoid(three_trap.to_screen(transform))
+            }
+            LayerShape::Rectangle(rect) => LayerShape::Rectangle(rect.to_screen(transform)),
+        };
+
+        Self {
+            layer_name: self.layer_name.clone(),
+            z_bottom: self.z_bottom,
+            z_top: self.z_top,
+            shape,
+            is_selected: self.is_selected,
+            hatch: self.hatch,
+        }
+    }
+}
+
+/// Zoom range, relative to the auto-fit zoom, and panning limit of the stack viewer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewLimits {
+    /// Ease the view back when the stack is panned off-screen or zoomed out of range
+    pub enabled: bool,
+    /// Smallest zoom as a fraction of the auto-fit zoom
+    pub min_zoom: f32,
+    /// Largest zoom as a multiple of the auto-fit zoom
+    pub max_zoom: f32,
+    /// Pixels of the stack, or all of it if smaller, that stay inside the viewport
+    pub keep_visible: f32,
+}
+
+impl ViewLimits {
+    /// Allowed scales for a stack whose auto-fit scale is `fit_scale`
+    pub fn scale_range(&self, fit_scale: f32) -> std::ops::RangeInclusive<f32> {
+        let min_zoom = self.min_zoom.max(f32::EPSILON);
+        (fit_scale * min_zoom)..=(fit_scale * self.max_zoom.max(min_zoom))
+    }
+}
+
+impl Default for ViewLimits {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_zoom: 0.25,
+            max_zoom: 1000.0,
+            keep_visible: 40.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -839,6 +1139,11 @@ impl ViewTransform {
         }
     }
 
+    /// Transform that maps world coordinates onto themselves
+    pub fn identity() -> Self {
+        Self::new(Vec2::ZERO)
+    }
+
     pub fn world_to_screen(&self, world_pos: Pos2) -> Pos2 {
         let scaled = Pos2::new(world_pos.x * self.scale, world_pos.y * self.scale);
 
@@ -887,6 +1192,69 @@ impl ViewTransform {
         self.offset = Vec2::new(-bounds_center.x * self.scale, -bounds_center.y * self.scale);
     }
 
+    /// Zoom so the screen region `selection` fills `target`, keeping its aspect ratio
+    pub fn zoom_to_screen_rect(&mut self, selection: Rect, target: Rect) {
+        let world = Rect::from_two_pos(
+            self.screen_to_world(selection.min),
+            self.screen_to_world(selection.max),
+        );
+        self.scale = (target.width() / world.width())
+            .min(target.height() / world.height())
+            .max(0.01);
+
+        // Put the center of the selection at the center of the target
+        self.offset = target.center().to_vec2()
+            - self.viewport_size * 0.5
+            - world.center().to_vec2() * self.scale;
+    }
+
+    /// Closest transform whose scale lies in `scale_range` and that keeps `keep_visible`
+    /// pixels of the world rectangle `content` inside the screen rectangle `viewport`
+    ///
+    /// A scale out of range is clamped around the center of the viewport.
+    pub fn constrained(
+        &self,
+        content: Rect,
+        viewport: Rect,
+        scale_range: std::ops::RangeInclusive<f32>,
+        keep_visible: f32,
+    ) -> Self {
+        let mut target = self.clone();
+        target.scale = self.scale.clamp(*scale_range.start(), *scale_range.end());
+        if target.scale != self.scale {
+            let center = self.screen_to_world(viewport.center());
+            target.offset += center.to_vec2() * (self.scale - target.scale);
+        }
+
+        let screen = Rect::from_two_pos(
+            target.world_to_screen(content.min),
+            target.world_to_screen(content.max),
+        );
+        // Shift along one axis to bring `keep` pixels of [min, max] into [view_min, view_max]
+        let shift = |min: f32, max: f32, view_min: f32, view_max: f32| {
+            let keep = keep_visible.min(max - min).min(view_max - view_min);
+            if max < view_min + keep {
+                view_min + keep - max
+            } else if min > view_max - keep {
+                view_max - keep - min
+            } else {
+                0.0
+            }
+        };
+        target.offset += Vec2::new(
+            shift(screen.min.x, screen.max.x, viewport.min.x, viewport.max.x),
+            shift(screen.min.y, screen.max.y, viewport.min.y, viewport.max.y),
+        );
+        target
+    }
+
+    /// Move a fraction `t` of the way towards `target`, zooming geometrically
+    pub fn approach(&mut self, target: &ViewTransform, t: f32) {
+        let t = t.clamp(0.0, 1.0);
+        self.scale *= (target.scale / self.scale).powf(t);
+        self.offset += (target.offset - self.offset) * t;
+    }
+
     pub fn get_visible_world_bounds(&self) -> Rect {
         let top_left = self.screen_to_world(Pos2::ZERO);
         let bottom_right =
@@ -896,6 +1264,69 @@ impl ViewTransform {
     }
 }
 
+/// Screen offset of the back face for the isometric view, 30 degrees up and to the right
+pub fn isometric_depth_offset(depth: f32) -> Vec2 {
+    let angle = 30.0_f32.to_radians();
+    Vec2::new(depth * angle.cos(), -depth * angle.sin())
+}
+
+/// Extrude a convex front-face polygon into a shallow prism
+///
+/// Only the faces turned towards the viewer (those whose outward normal points along the
+/// depth offset) are emitted, followed by the front face, so painting geometries from
+/// bottom to top and left to right gives a correct painter's-algorithm result.
+pub fn extrude_polygon(
+    points: &[Pos2],
+    depth_offset: Vec2,
+    fill_color: Color32,
+    stroke: Stroke,
+) -> Vec<Shape> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let centroid = points.iter().fold(Vec2::ZERO, |acc, p| acc + p.to_vec2()) / points.len() as f32;
+    let edge_stroke = Stroke::new(stroke.width.min(1.0), Color32::from_gray(64));
+    let mut shapes = Vec::new();
+
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        let edge = b - a;
+
+        // Pick the edge normal pointing away from the polygon center
+        let mut normal = Vec2::new(edge.y, -edge.x);
+        if normal.dot((a + edge * 0.5).to_vec2() - centroid) < 0.0 {
+            normal = -normal;
+        }
+
+        if normal.dot(depth_offset) <= 0.0 || edge.length() <= f32::EPSILON {
+            continue;
+        }
+
+        // Faces pointing up are lit, faces pointing sideways are shaded
+        let shade = if normal.y < 0.0 { 1.25 } else { 0.7 };
+        shapes.push(Shape::convex_polygon(
+            vec![a, b, b + depth_offset, a + depth_offset],
+            shade_color(fill_color, shade),
+            edge_stroke,
+        ));
+    }
+
+    shapes.push(Shape::convex_polygon(points.to_vec(), fill_color, stroke));
+    shapes
+}
+
+fn shade_color(color: Color32, factor: f32) -> Color32 {
+    let scale = |c: u8| ((c as f32 * factor).round() as u8).min(color.a());
+    Color32::from_rgba_premultiplied(
+        scale(color.r()),
+        scale(color.g()),
+        scale(color.b()),
+        color.a(),
+    )
+}
+
 pub fn calculate_optimal_layer_width(stack_height: f32, viewport_width: f32, margin: f32) -> f32 {
     // Calculate width based on aspect ratio for good visualization
     let aspect_ratio = 2.0; // Width:Height ratio
@@ -967,7 +1398,7 @@ pub fn find_max_conductor_trapezoid_width_with_scaler(
 
             // Debug output for troubleshooting schematic mode
             if cfg!(debug_assertions) {
-                println!(
+                eprintln!(
                     "DEBUG Scaler: Layer '{}' - Original: {}, Scaled: {}",
                     layer.name, layer.thickness, scaled_thickness
                 );
@@ -981,7 +1412,7 @@ pub fn find_max_conductor_trapezoid_width_with_scaler(
         let max_width = max_scaled_thickness * 2.0; // long_edge_width = scaled_thickness * 2.0
 
         if cfg!(debug_assertions) {
-            println!("DEBUG Scaler: Max scaled thickness: {max_scaled_thickness}, Max trapezoid width: {max_width}");
+            eprintln!("DEBUG Scaler: Max scaled thickness: {max_scaled_thickness}, Max trapezoid width: {max_width}");
         }
 
         Some(max_width)
@@ -1033,6 +1464,40 @@ mod tests {
         assert!(trapezoid.top_right.x < trapezoid.bottom_right.x);
     }
 
+    #[test]
+    fn test_trapezoid_exaggeration() {
+        let conductor = ConductorLayer::new("metal1".to_string(), 0.5).with_side_tangent(0.1);
+        let top_width = |exaggeration: f32| {
+            let trapezoid = TrapezoidShape::from_conductor_layer_exaggerated(
+                &conductor,
+                Pos2::new(100.0, 100.0),
+                20.0,
+                10.0,
+                Color32::RED,
+                Stroke::new(1.0, Color32::BLACK),
+                exaggeration,
+            );
+            trapezoid.top_right.x - trapezoid.top_left.x
+        };
+
+        // To scale the top grows by 1.0 per side, tripled by the default exaggeration
+        assert_relative_eq!(top_width(1.0), 22.0, epsilon = 1e-4);
+        let exaggerated = TrapezoidShape::from_conductor_layer(
+            &conductor,
+            Pos2::new(100.0, 100.0),
+            20.0,
+            10.0,
+            Color32::RED,
+            Stroke::new(1.0, Color32::BLACK),
+        );
+        assert_relative_eq!(
+            exaggerated.top_right.x - exaggerated.top_left.x,
+            top_width(SIDEWALL_EXAGGERATION),
+            epsilon = 1e-4
+        );
+        assert_relative_eq!(top_width(3.0), 26.0, epsilon = 1e-4);
+    }
+
     #[test]
     fn test_rectangle_creation() {
         let rectangle = RectangleShape::new(
@@ -1117,6 +1582,76 @@ mod tests {
         assert!(visible_bounds.contains_rect(bounds));
     }
 
+    #[test]
+    fn test_constrained() {
+        let viewport = Rect::from_min_size(Pos2::new(0.0, 40.0), Vec2::new(800.0, 600.0));
+        let content = Rect::from_min_max(Pos2::new(-50.0, -100.0), Pos2::new(50.0, 100.0));
+        let mut transform = ViewTransform::new(viewport.size());
+
+        // In range and on screen
+        let target = transform.constrained(content, viewport, 0.5..=10.0, 40.0);
+        assert_eq!(target.scale, transform.scale);
+        assert_eq!(target.offset, transform.offset);
+
+        // Panned far to the left: 40 px come back at the left edge
+        transform.pan(Vec2::new(-2000.0, 0.0));
+        let target = transform.constrained(content, viewport, 0.5..=10.0, 40.0);
+        assert_relative_eq!(
+            target.world_to_screen(content.max).x,
+            viewport.min.x + 40.0,
+            epsilon = 1e-3
+        );
+        assert_eq!(target.offset.y, transform.offset.y);
+
+        // Zoomed out to a dot: clamped around the viewport center, which stays put
+        let viewport = Rect::from_min_size(Pos2::ZERO, viewport.size());
+        let mut transform = ViewTransform::new(viewport.size());
+        transform.scale = 0.01;
+        transform.pan(Vec2::new(0.3, -0.2));
+        let center = transform.screen_to_world(viewport.center());
+        let target = transform.constrained(content, viewport, 0.5..=10.0, 40.0);
+        assert_relative_eq!(target.scale, 0.5);
+        let moved = target.world_to_screen(center);
+        assert_relative_eq!(moved.x, viewport.center().x, epsilon = 1e-3);
+        assert_relative_eq!(moved.y, viewport.center().y, epsilon = 1e-3);
+
+        // Easing reaches the target
+        transform.approach(&target, 0.5);
+        assert!(transform.scale > 0.01 && transform.scale < 0.5);
+        transform.approach(&target, 1.0);
+        assert_relative_eq!(transform.scale, 0.5, epsilon = 1e-5);
+
+        let limits = ViewLimits::default();
+        let range = limits.scale_range(2.0);
+        assert_relative_eq!(*range.start(), 0.5);
+        assert_relative_eq!(*range.end(), 2000.0);
+    }
+
+    #[test]
+    fn test_zoom_to_screen_rect() {
+        let mut transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        transform.pan(Vec2::new(30.0, -20.0));
+        let target = Rect::from_min_size(Pos2::new(0.0, 40.0), Vec2::new(800.0, 600.0));
+        let selection = Rect::from_min_max(Pos2::new(100.0, 100.0), Pos2::new(300.0, 200.0));
+        let world = Rect::from_two_pos(
+            transform.screen_to_world(selection.min),
+            transform.screen_to_world(selection.max),
+        );
+
+        transform.zoom_to_screen_rect(selection, target);
+
+        // 200x100 px fills 800x600 px limited by the width
+        assert_relative_eq!(transform.scale, 4.0, epsilon = 1e-4);
+        let center = transform.world_to_screen(world.center());
+        assert_relative_eq!(center.x, target.center().x, epsilon = 1e-3);
+        assert_relative_eq!(center.y, target.center().y, epsilon = 1e-3);
+        assert_relative_eq!(
+            transform.world_to_screen(world.min).x,
+            target.min.x,
+            epsilon = 1e-3
+        );
+    }
+
     #[test]
     fn test_layer_geometry() {
         let rectangle = RectangleShape::new(
@@ -1766,4 +2301,36 @@ mod tests {
             "Right trapezoid should be at 5.5x offset from left edge"
         );
     }
+
+    #[test]
+    fn test_isometric_extrusion() {
+        let offset = isometric_depth_offset(20.0);
+        assert!(offset.x > 0.0);
+        assert!(offset.y < 0.0);
+        assert!((offset.length() - 20.0).abs() < 1e-4);
+
+        let rect = RectangleShape::new(
+            Pos2::new(100.0, 100.0),
+            40.0,
+            20.0,
+            Color32::from_rgb(100, 149, 237),
+            Stroke::new(1.0, Color32::WHITE),
+        );
+        let geometry = LayerGeometry::new_rectangle("oxide".to_string(), 0.0, 1.0, rect);
+
+        // Top face, right face and the front face
+        let shapes = geometry.to_isometric_shapes(offset);
+        assert_eq!(shapes.len(), 3);
+
+        // The front face is painted last so it covers the extruded faces
+        if let Shape::Path(path) = &shapes[2] {
+            assert_eq!(path.points.len(), 4);
+            assert!(path.points.contains(&Pos2::new(80.0, 110.0)));
+        } else {
+            panic!("Expected a polygon for the front face");
+        }
+
+        // Degenerate polygons are skipped
+        assert!(extrude_polygon(&[Pos2::ZERO], offset, Color32::RED, Stroke::NONE).is_empty());
+    }
 }
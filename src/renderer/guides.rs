@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use serde::{Deserialize, Serialize};
+
+/// Horizontal reference line at a fixed height above the substrate
+///
+/// Guides are anchored in stack coordinates, so they follow the stack when panning,
+/// zooming or switching the view anchor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Guide {
+    /// Height above the bottom of the stack in um
+    pub z: f32,
+    /// Optional note such as "bump height", shown next to the value
+    pub label: String,
+}
+
+impl Guide {
+    pub fn new(z: f32) -> Self {
+        Self {
+            z,
+            label: String::new(),
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Text drawn at the end of the line
+    pub fn caption(&self) -> String {
+        if self.label.is_empty() {
+            format!("z = {:.3} μm", self.z)
+        } else {
+            format!("{}: z = {:.3} μm", self.label, self.z)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guide_caption() {
+        assert_eq!(Guide::new(1.5).caption(), "z = 1.500 μm");
+        assert_eq!(
+            Guide::new(12.0).with_label("bump").caption(),
+            "bump: z = 12.000 μm"
+        );
+    }
+}
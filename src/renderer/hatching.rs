@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::DielectricLayer;
+use egui::{Color32, Pos2, Rect, Shape, Stroke, Vec2};
+
+/// Distance between hatch lines or dots in screen pixels
+pub const HATCH_SPACING: f32 = 8.0;
+
+/// Texture drawn over the flat fill of a dielectric
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HatchPattern {
+    #[default]
+    None,
+    /// Diagonal lines, used for nitrides
+    Diagonal,
+    /// Stipple dots, used for low-k dielectrics
+    Dots,
+    /// Crossed diagonal lines, used for the substrate
+    CrossHatch,
+}
+
+impl HatchPattern {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HatchPattern::None => "None",
+            HatchPattern::Diagonal => "Diagonal",
+            HatchPattern::Dots => "Dots",
+            HatchPattern::CrossHatch => "Cross-hatch",
+        }
+    }
+
+    /// Identifier of the pattern in theme files
+    pub fn id(&self) -> &'static str {
+        match self {
+            HatchPattern::None => "none",
+            HatchPattern::Diagonal => "diagonal",
+            HatchPattern::Dots => "dots",
+            HatchPattern::CrossHatch => "cross_hatch",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        [
+            HatchPattern::None,
+            HatchPattern::Diagonal,
+            HatchPattern::Dots,
+            HatchPattern::CrossHatch,
+        ]
+        .into_iter()
+        .find(|pattern| pattern.id() == id)
+    }
+}
+
+/// Selects a hatch pattern by layer name or dielectric constant
+///
+/// A rule with both conditions needs both to match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HatchRule {
+    pub pattern: HatchPattern,
+    /// Case-insensitive substring of the layer name
+    pub name_contains: Option<String>,
+    /// Inclusive range of the dielectric constant
+    pub er_range: Option<(f64, f64)>,
+}
+
+impl HatchRule {
+    pub fn by_name(pattern: HatchPattern, name_contains: &str) -> Self {
+        Self {
+            pattern,
+            name_contains: Some(name_contains.to_lowercase()),
+            er_range: None,
+        }
+    }
+
+    pub fn by_er(pattern: HatchPattern, min: f64, max: f64) -> Self {
+        Self {
+            pattern,
+            name_contains: None,
+            er_range: Some((min, max)),
+        }
+    }
+
+    pub fn matches(&self, layer: &DielectricLayer) -> bool {
+        if self.name_contains.is_none() && self.er_range.is_none() {
+            return false;
+        }
+        let name_matches = self
+            .name_contains
+            .as_ref()
+            .is_none_or(|text| layer.name.to_lowercase().contains(text));
+        let er_matches = self
+            .er_range
+            .is_none_or(|(min, max)| (min..=max).contains(&layer.dielectric_constant));
+        name_matches && er_matches
+    }
+}
+
+/// Rules used by the default color scheme, checked in order
+///
+/// Silicon nitride has an ER around 7, oxide around 3.9-4.2; anything below 3.5 is
+/// treated as low-k.
+pub fn default_hatch_rules() -> Vec<HatchRule> {
+    vec![
+        HatchRule::by_name(HatchPattern::CrossHatch, "substrate"),
+        HatchRule::by_name(HatchPattern::Diagonal, "nitride"),
+        HatchRule::by_er(HatchPattern::Diagonal, 6.0, 8.5),
+        HatchRule::by_er(HatchPattern::Dots, 0.0, 3.5),
+    ]
+}
+
+/// Pattern of the first matching rule
+pub fn select_hatch_pattern(rules: &[HatchRule], layer: &DielectricLayer) -> HatchPattern {
+    rules
+        .iter()
+        .find(|rule| rule.matches(layer))
+        .map(|rule| rule.pattern)
+        .unwrap_or_default()
+}
+
+/// Hatch color that stays visible on top of `fill`
+pub fn hatch_color(fill: Color32) -> Color32 {
+    let luminance = 0.299 * fill.r() as f32 + 0.587 * fill.g() as f32 + 0.114 * fill.b() as f32;
+    if luminance > 110.0 {
+        Color32::from_black_alpha(140)
+    } else {
+        Color32::from_white_alpha(110)
+    }
+}
+
+/// Screen-space shapes of `pattern` filling `rect`
+///
+/// Lines and dots sit on a grid anchored at the screen origin, so the texture stays put
+/// like a bitmap fill when a layer is resized or scrolled partly out of view.
+pub fn hatch_shapes(rect: Rect, pattern: HatchPattern, color: Color32, spacing: f32) -> Vec<Shape> {
+    if !rect.is_positive() || spacing <= 0.0 {
+        return Vec::new();
+    }
+
+    let stroke = Stroke::new(1.0, color);
+    let mut shapes = Vec::new();
+
+    match pattern {
+        HatchPattern::None => {}
+        HatchPattern::Diagonal => shapes.extend(diagonal_lines(rect, spacing, true, stroke)),
+        HatchPattern::CrossHatch => {
+            shapes.extend(diagonal_lines(rect, spacing, true, stroke));
+            shapes.extend(diagonal_lines(rect, spacing, false, stroke));
+        }
+        HatchPattern::Dots => {
+            let dot = Vec2::splat(1.5);
+            let first_row = (rect.min.y / spacing).ceil() as i64;
+            let last_row = (rect.max.y / spacing).floor() as i64;
+            for row in first_row..=last_row {
+                // Every other row is shifted by half a cell for a stipple look
+                let shift = if row % 2 == 0 { 0.0 } else { spacing * 0.5 };
+                let first_column = ((rect.min.x - shift) / spacing).ceil() as i64;
+                let last_column = ((rect.max.x - shift) / spacing).floor() as i64;
+                for column in first_column..=last_column {
+                    let center = Pos2::new(column as f32 * spacing + shift, row as f32 * spacing);
+                    shapes.push(Shape::rect_filled(
+                        Rect::from_center_size(center, dot).intersect(rect),
+                        0.0,
+                        color,
+                    ));
+                }
+            }
+        }
+    }
+
+    shapes
+}
+
+/// 45 degree lines clipped to `rect`, rising to the right when `rising` is set
+fn diagonal_lines(rect: Rect, spacing: f32, rising: bool, stroke: Stroke) -> Vec<Shape> {
+    // Rising lines on screen (y down) satisfy x + y = c, falling lines x - y = c
+    let (c_min, c_max) = if rising {
+        (rect.min.x + rect.min.y, rect.max.x + rect.max.y)
+    } else {
+        (rect.min.x - rect.max.y, rect.max.x - rect.min.y)
+    };
+
+    let first = (c_min / spacing).ceil() as i64;
+    let last = (c_max / spacing).floor() as i64;
+    (first..=last)
+        .filter_map(|index| {
+            let c = index as f32 * spacing;
+            let (x_start, x_end, y_at) = if rising {
+                (
+                    rect.min.x.max(c - rect.max.y),
+                    rect.max.x.min(c - rect.min.y),
+                    c,
+                )
+            } else {
+                (
+                    rect.min.x.max(c + rect.min.y),
+                    rect.max.x.min(c + rect.max.y),
+                    -c,
+                )
+            };
+            if x_end <= x_start {
+                return None;
+            }
+            let y = |x: f32| if rising { y_at - x } else { x + y_at };
+            Some(Shape::line_segment(
+                [Pos2::new(x_start, y(x_start)), Pos2::new(x_end, y(x_end))],
+                stroke,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dielectric(name: &str, er: f64) -> DielectricLayer {
+        DielectricLayer::new(name.to_string(), 0.5, er)
+    }
+
+    #[test]
+    fn test_default_hatch_rules() {
+        let rules = default_hatch_rules();
+        let pattern = |name, er| select_hatch_pattern(&rules, &dielectric(name, er));
+
+        assert_eq!(pattern("substrate", 11.9), HatchPattern::CrossHatch);
+        assert_eq!(pattern("cap_nitride", 4.0), HatchPattern::Diagonal);
+        assert_eq!(pattern("etch_stop", 7.0), HatchPattern::Diagonal);
+        assert_eq!(pattern("imd_lowk", 2.7), HatchPattern::Dots);
+        assert_eq!(pattern("ild1", 4.2), HatchPattern::None);
+
+        let empty = HatchRule {
+            pattern: HatchPattern::Dots,
+            name_contains: None,
+            er_range: None,
+        };
+        assert!(!empty.matches(&dielectric("ild1", 4.2)));
+    }
+
+    #[test]
+    fn test_hatch_shapes_stay_inside_rect() {
+        let rect = Rect::from_min_max(Pos2::new(3.0, 5.0), Pos2::new(83.0, 37.0));
+        let inside = |p: &Pos2| rect.expand(1e-3).contains(*p);
+
+        for pattern in [HatchPattern::Diagonal, HatchPattern::CrossHatch] {
+            let shapes = hatch_shapes(rect, pattern, Color32::BLACK, HATCH_SPACING);
+            assert!(!shapes.is_empty(), "{}", pattern.label());
+            for shape in &shapes {
+                let Shape::LineSegment { points, .. } = shape else {
+                    panic!("expected line segments");
+                };
+                assert!(points.iter().all(inside), "{points:?}");
+                // 45 degree lines
+                let d = points[1] - points[0];
+                assert!((d.x.abs() - d.y.abs()).abs() < 1e-3);
+            }
+        }
+
+        let crossing = hatch_shapes(rect, HatchPattern::CrossHatch, Color32::BLACK, 8.0).len();
+        let diagonal = hatch_shapes(rect, HatchPattern::Diagonal, Color32::BLACK, 8.0).len();
+        assert!(crossing > diagonal);
+
+        let dots = hatch_shapes(rect, HatchPattern::Dots, Color32::BLACK, 8.0);
+        assert!(!dots.is_empty());
+        assert!(dots
+            .iter()
+            .all(|s| rect.contains_rect(s.visual_bounding_rect())));
+
+        assert!(hatch_shapes(rect, HatchPattern::None, Color32::BLACK, 8.0).is_empty());
+        assert!(hatch_shapes(Rect::NOTHING, HatchPattern::Dots, Color32::BLACK, 8.0).is_empty());
+    }
+
+    #[test]
+    fn test_hatch_color_contrast() {
+        assert_eq!(hatch_color(Color32::WHITE), Color32::from_black_alpha(140));
+        assert_eq!(
+            hatch_color(Color32::from_rgb(47, 79, 79)),
+            Color32::from_white_alpha(110)
+        );
+    }
+}
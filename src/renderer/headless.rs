@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use crate::renderer::{StackRenderer, ViewTransform};
+use egui::{CentralPanel, Context, Frame, Pos2, RawInput, Rect, Vec2};
+
+/// Build one frame of the stack view without a window
+///
+/// Runs a full egui pass with the renderer drawing into a central panel and tessellates
+/// the result, which is the CPU work a real frame does before reaching the GPU.
+/// Returns the number of mesh vertices produced.
+pub fn render_frame_headless(
+    ctx: &Context,
+    renderer: &StackRenderer,
+    stack: &ProcessStack,
+    transform: &ViewTransform,
+) -> usize {
+    let raw_input = RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, transform.viewport_size)),
+        ..Default::default()
+    };
+
+    let output = ctx.run(raw_input, |ctx| {
+        CentralPanel::default().frame(Frame::NONE).show(ctx, |ui| {
+            let viewport_rect = ui.max_rect();
+            let painter = ui.painter_at(viewport_rect);
+            renderer.render_stack_with_painter(stack, transform, viewport_rect, &painter);
+        });
+    });
+
+    ctx.tessellate(output.shapes, output.pixels_per_point)
+        .iter()
+        .map(|clipped| match &clipped.primitive {
+            egui::epaint::Primitive::Mesh(mesh) => mesh.vertices.len(),
+            egui::epaint::Primitive::Callback(_) => 0,
+        })
+        .sum()
+}
+
+/// A transform fitted to the whole stack, as the viewer shows it after loading
+pub fn fitted_transform(
+    renderer: &StackRenderer,
+    stack: &ProcessStack,
+    viewport_size: Vec2,
+) -> ViewTransform {
+    let mut transform = ViewTransform::new(viewport_size);
+    renderer.auto_fit(stack, &mut transform);
+    transform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::generate_random_stack;
+
+    #[test]
+    fn test_render_frame_headless() {
+        let ctx = Context::default();
+        let renderer = StackRenderer::new();
+        let stack = generate_random_stack(5, 15);
+        let transform = fitted_transform(&renderer, &stack, Vec2::new(1280.0, 800.0));
+
+        assert!(render_frame_headless(&ctx, &renderer, &stack, &transform) > 0);
+        // Later frames reuse the font atlas and must still produce output
+        assert!(render_frame_headless(&ctx, &renderer, &stack, &transform) > 0);
+    }
+}
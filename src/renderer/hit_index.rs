@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::renderer::{geometry::LayerGeometry, layout::StackLayout};
+use egui::{Pos2, Rect};
+
+/// Drawing order of a geometry kind; higher ranks are drawn on top
+const DIELECTRIC_RANK: u8 = 0;
+const CONDUCTOR_RANK: u8 = 1;
+const VIA_RANK: u8 = 2;
+
+#[derive(Debug, Clone)]
+struct HitEntry {
+    /// Layer name reported for a hit, without the via column suffix
+    name: String,
+    bounds: Rect,
+    /// Drawing order: kind rank, then position within that kind
+    priority: (u8, usize),
+    geometry: LayerGeometry,
+}
+
+/// Point lookup over a world-space layout, sorted by the vertical extent of each geometry
+///
+/// Building it costs one pass over the layout; queries only test the geometries whose
+/// z interval can contain the point. Since it is in world coordinates, panning and
+/// zooming do not invalidate it, only changes to the stack or to the layout options.
+#[derive(Debug, Clone, Default)]
+pub struct HitIndex {
+    /// Sorted by the top edge (minimum world y) of their bounds
+    entries: Vec<HitEntry>,
+    /// Tallest geometry, bounding how far back a query has to scan
+    max_height: f32,
+}
+
+impl HitIndex {
+    pub fn new(layout: &StackLayout) -> Self {
+        let dielectrics = layout
+            .dielectrics()
+            .enumerate()
+            .map(|(order, geometry)| (DIELECTRIC_RANK, order, geometry));
+        let conductors = layout
+            .conductors()
+            .enumerate()
+            .map(|(order, geometry)| (CONDUCTOR_RANK, order, geometry));
+        let vias = layout
+            .vias
+            .iter()
+            .enumerate()
+            .map(|(order, geometry)| (VIA_RANK, order, geometry));
+
+        let mut entries: Vec<HitEntry> = dielectrics
+            .chain(conductors)
+            .chain(vias)
+            .map(|(rank, order, geometry)| HitEntry {
+                name: if rank == VIA_RANK {
+                    via_base_name(&geometry.layer_name).to_string()
+                } else {
+                    geometry.layer_name.clone()
+                },
+                bounds: geometry.get_bounds(),
+                priority: (rank, order),
+                geometry: geometry.clone(),
+            })
+            .filter(|entry| entry.bounds.is_positive())
+            .collect();
+        entries.sort_by(|a, b| a.bounds.min.y.total_cmp(&b.bounds.min.y));
+
+        let max_height = entries
+            .iter()
+            .fold(0.0, |max: f32, entry| max.max(entry.bounds.height()));
+
+        Self {
+            entries,
+            max_height,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Name of the topmost layer drawn at a world position
+    pub fn hit(&self, point: Pos2) -> Option<&str> {
+        // Only entries starting between point.y - max_height and point.y can reach it
+        let end = self
+            .entries
+            .partition_point(|entry| entry.bounds.min.y <= point.y);
+        let start = self.entries[..end]
+            .partition_point(|entry| entry.bounds.min.y < point.y - self.max_height);
+
+        self.entries[start..end]
+            .iter()
+            .filter(|entry| entry.bounds.contains(point) && entry.geometry.contains_point(point))
+            .max_by_key(|entry| entry.priority)
+            .map(|entry| entry.name.as_str())
+    }
+}
+
+/// Via geometries are named "via_name_N", one per column; strip the column index
+fn via_base_name(name: &str) -> &str {
+    match name.rsplit_once('_') {
+        Some((base, suffix)) if suffix.chars().all(|c| c.is_ascii_digit()) => base,
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, Layer, ProcessStack, TechnologyInfo};
+    use crate::renderer::StackRenderer;
+
+    #[test]
+    fn test_hit_index() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("hits".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd1".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.5,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "substrate".to_string(),
+            2.0,
+            11.9,
+        )));
+
+        let layout = StackRenderer::new().layout_stack(&stack, 800.0);
+        let index = HitIndex::new(&layout);
+        assert_eq!(index.len(), 3);
+
+        // The embedded conductor is drawn over its dielectric
+        let metal = layout.conductors().next().unwrap().get_bounds();
+        assert_eq!(index.hit(metal.center()), Some("metal1"));
+
+        // Every layer answers at the center of its own exclusive area
+        let substrate = layout.layers[0].get_bounds();
+        assert_eq!(index.hit(substrate.center()), Some("substrate"));
+        assert_eq!(index.hit(Pos2::new(0.0, metal.min.y - 0.1)), Some("imd1"));
+        assert_eq!(index.hit(Pos2::new(0.0, 1.0)), None);
+        assert!(HitIndex::default().hit(Pos2::ZERO).is_none());
+
+        assert_eq!(via_base_name("via12_3"), "via12");
+        assert_eq!(via_base_name("via_top"), "via_top");
+    }
+}
@@ -0,0 +1,273 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::renderer::geometry::{LayerGeometry, LayerShape, ViewTransform};
+use crate::renderer::snapshot::{PrimitiveKind, RenderPrimitive};
+use egui::{Pos2, Rect};
+use serde::{Deserialize, Serialize};
+
+/// Which end of the stack sits at world y = 0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ViewAnchor {
+    /// Bottom of the stack at y = 0, the ruler counts height upwards
+    #[default]
+    Substrate,
+    /// Top of the chip at y = 0, the ruler counts depth downwards
+    ChipTop,
+}
+
+impl ViewAnchor {
+    pub const ALL: [ViewAnchor; 2] = [ViewAnchor::Substrate, ViewAnchor::ChipTop];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ViewAnchor::Substrate => "Substrate",
+            ViewAnchor::ChipTop => "Top of Chip",
+        }
+    }
+
+    /// Caption above the ruler, showing which way its values grow
+    pub fn ruler_caption(self) -> &'static str {
+        match self {
+            ViewAnchor::Substrate => "z ↑",
+            ViewAnchor::ChipTop => "depth ↓",
+        }
+    }
+
+    /// Ruler reading at world y: height above the substrate or depth below the top
+    pub fn ruler_value(self, world_y: f32) -> f32 {
+        match self {
+            ViewAnchor::Substrate => -world_y,
+            ViewAnchor::ChipTop => world_y,
+        }
+    }
+
+    /// World y where the ruler reads `value`, the inverse of `ruler_value`
+    pub fn ruler_world_y(self, value: f32) -> f32 {
+        self.ruler_value(value)
+    }
+}
+
+/// Physical layout of a stack, produced by `StackRenderer::layout_stack`
+///
+/// Geometries are in world coordinates, where x is centered on the stack and y = -z so
+/// the stack grows upwards on screen; with `ViewAnchor::ChipTop` y is shifted so the top
+/// of the stack is at y = 0. Drawing maps them to the screen with `to_screen`;
+/// exporters can use the world coordinates directly.
+#[derive(Debug, Clone, Default)]
+pub struct StackLayout {
+    /// Layer geometries from the bottom of the stack to the top
+    pub layers: Vec<LayerGeometry>,
+    /// One geometry per via column or cut
+    pub vias: Vec<LayerGeometry>,
+}
+
+impl StackLayout {
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty() && self.vias.is_empty()
+    }
+
+    pub fn is_conductor(geometry: &LayerGeometry) -> bool {
+        matches!(geometry.shape, LayerShape::ThreeColumnTrapezoid(_))
+    }
+
+    pub fn dielectrics(&self) -> impl Iterator<Item = &LayerGeometry> {
+        self.layers.iter().filter(|g| !Self::is_conductor(g))
+    }
+
+    pub fn conductors(&self) -> impl Iterator<Item = &LayerGeometry> {
+        self.layers.iter().filter(|g| Self::is_conductor(g))
+    }
+
+    /// Map every geometry from world to screen coordinates
+    pub fn to_screen(&self, transform: &ViewTransform) -> Self {
+        Self {
+            layers: self
+                .layers
+                .iter()
+                .map(|geometry| geometry.to_screen(transform))
+                .collect(),
+            vias: self
+                .vias
+                .iter()
+                .map(|geometry| geometry.to_screen(transform))
+                .collect(),
+        }
+    }
+
+    /// Drop geometries entirely outside `rect`, in the coordinates of the layout
+    pub fn retain_visible(&mut self, rect: Rect) {
+        self.layers
+            .retain(|geometry| geometry.get_bounds().intersects(rect));
+        self.vias
+            .retain(|geometry| geometry.get_bounds().intersects(rect));
+    }
+
+    /// World y in `other`, a layout of the same stack, of the position at `world_y` here
+    ///
+    /// The position keeps its fraction of the dielectric it falls into, or else of the
+    /// conductor, so layers line up between layouts with different thickness scaling. Positions outside the stack keep
+    /// their distance to its top or bottom.
+    pub fn map_world_y(&self, other: &StackLayout, world_y: f32) -> f32 {
+        for geometry in self.dielectrics().chain(self.conductors()) {
+            let bounds = geometry.get_bounds();
+            if bounds.height() <= 0.0 || !(bounds.min.y..=bounds.max.y).contains(&world_y) {
+                continue;
+            }
+            if let Some(target) = other
+                .layers
+                .iter()
+                .find(|target| target.layer_name == geometry.layer_name)
+            {
+                let target = target.get_bounds();
+                let fraction = (world_y - bounds.min.y) / bounds.height();
+                return target.min.y + fraction * target.height();
+            }
+        }
+
+        let (from, to) = (self.get_bounds(), other.get_bounds());
+        if from == Rect::NOTHING || to == Rect::NOTHING || from.height() <= 0.0 {
+            world_y
+        } else if world_y < from.min.y {
+            to.min.y - (from.min.y - world_y)
+        } else if world_y > from.max.y {
+            to.max.y + (world_y - from.max.y)
+        } else {
+            to.min.y + (world_y - from.min.y) / from.height() * to.height()
+        }
+    }
+
+    pub fn get_bounds(&self) -> Rect {
+        self.layers
+            .iter()
+            .chain(&self.vias)
+            .fold(Rect::NOTHING, |bounds, geometry| {
+                bounds.union(geometry.get_bounds())
+            })
+    }
+
+    /// Flatten into filled polygons in drawing order: dielectrics, conductors, then vias
+    ///
+    /// Unlike the geometries, primitive points have z pointing up.
+    pub fn primitives(&self) -> Vec<RenderPrimitive> {
+        self.dielectrics()
+            .map(|geometry| (PrimitiveKind::Dielectric, geometry))
+            .chain(
+                self.conductors()
+                    .map(|geometry| (PrimitiveKind::Conductor, geometry)),
+            )
+            .chain(
+                self.vias
+                    .iter()
+                    .map(|geometry| (PrimitiveKind::Via, geometry)),
+            )
+            .flat_map(|(kind, geometry)| {
+                geometry
+                    .get_polygons()
+                    .into_iter()
+                    .map(move |(points, fill_color, _)| RenderPrimitive {
+                        kind,
+                        name: geometry.layer_name.clone(),
+                        points: points.into_iter().map(|p| Pos2::new(p.x, -p.y)).collect(),
+                        fill_color,
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, Layer, ProcessStack, TechnologyInfo};
+    use crate::renderer::StackRenderer;
+    use egui::Vec2;
+
+    fn create_test_stack() -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("layout".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd1".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.5,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "substrate".to_string(),
+            2.0,
+            11.9,
+        )));
+        stack
+    }
+
+    #[test]
+    fn test_layout_is_world_space() {
+        let renderer = StackRenderer::new();
+        let stack = create_test_stack();
+        let layout = renderer.layout_stack(&stack, 800.0);
+
+        assert_eq!(layout.dielectrics().count(), 2);
+        assert_eq!(layout.conductors().count(), 1);
+
+        // The stack starts at z = 0 and is centered on x = 0
+        let bounds = layout.get_bounds();
+        assert!((bounds.max.y - 0.0).abs() < 1e-4);
+        assert!((bounds.min.y + 3.0).abs() < 1e-4);
+        assert!((bounds.center().x).abs() < 1e-4);
+
+        // Two dielectrics, then the conductor as three columns
+        let primitives = layout.primitives();
+        assert_eq!(primitives.len(), 5);
+        assert_eq!(primitives[4].kind, PrimitiveKind::Conductor);
+    }
+
+    #[test]
+    fn test_map_world_y() {
+        let stack = create_test_stack();
+        let mut renderer = StackRenderer::new();
+        let true_scale = renderer.layout_stack(&stack, 800.0);
+        renderer.set_show_schematic_mode(true);
+        let schematic = renderer.layout_stack(&stack, 800.0);
+
+        // Middle of imd1, the top dielectric, maps to the middle of imd1 in the schematic
+        let imd1 = |layout: &StackLayout| {
+            layout
+                .dielectrics()
+                .find(|geometry| geometry.layer_name == "imd1")
+                .unwrap()
+                .get_bounds()
+        };
+        let mapped = true_scale.map_world_y(&schematic, imd1(&true_scale).center().y);
+        assert!((mapped - imd1(&schematic).center().y).abs() < 1e-4);
+
+        // Above the stack the distance to its top is kept
+        let above = true_scale.get_bounds().min.y - 2.0;
+        let mapped = true_scale.map_world_y(&schematic, above);
+        assert!((mapped - (schematic.get_bounds().min.y - 2.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_layout_to_screen_matches_direct_rendering() {
+        let renderer = StackRenderer::new();
+        let stack = create_test_stack();
+        let mut transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        transform.scale = 40.0;
+        transform.offset = Vec2::new(-25.0, 60.0);
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, transform.viewport_size);
+
+        let screen = renderer.layout_stack(&stack, 800.0).to_screen(&transform);
+        let scaler = renderer.get_current_scaler(&stack);
+        let direct =
+            renderer.create_layer_geometries_ordered(&stack, &scaler, &transform, viewport_rect);
+
+        assert_eq!(screen.layers.len(), direct.len());
+        for (mapped, direct) in screen.layers.iter().zip(&direct) {
+            assert_eq!(mapped.layer_name, direct.layer_name);
+            let (a, b) = (mapped.get_bounds(), direct.get_bounds());
+            assert!((a.min - b.min).length() < 1e-2, "{}", mapped.layer_name);
+            assert!((a.max - b.max).length() < 1e-2, "{}", mapped.layer_name);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::renderer::{geometry::LayerGeometry, layout::StackLayout};
+use egui::Rect;
+
+/// When to draw simplified geometry so huge stacks stay interactive
+///
+/// Once more than `max_detailed_layers` layers are on screen, geometries outside the
+/// viewport are skipped and layers thinner than `min_detailed_height` points are drawn
+/// as single unlabeled rectangles. Zooming in makes layers taller and brings fewer of
+/// them on screen, so the full detail comes back gradually.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodPolicy {
+    pub enabled: bool,
+    pub max_detailed_layers: usize,
+    pub min_detailed_height: f32,
+}
+
+impl LodPolicy {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            max_detailed_layers: 64,
+            min_detailed_height: 6.0,
+        }
+    }
+
+    /// Always draw every layer in full detail
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::new()
+        }
+    }
+
+    pub fn with_max_detailed_layers(mut self, count: usize) -> Self {
+        self.max_detailed_layers = count;
+        self
+    }
+
+    pub fn with_min_detailed_height(mut self, height: f32) -> Self {
+        self.min_detailed_height = height;
+        self
+    }
+
+    /// Whether a screen-space layout has too many layers in view for full detail
+    pub fn is_active(&self, layout: &StackLayout, viewport_rect: Rect) -> bool {
+        self.enabled
+            && layout
+                .layers
+                .iter()
+                .filter(|geometry| geometry.get_bounds().intersects(viewport_rect))
+                .count()
+                > self.max_detailed_layers
+    }
+
+    /// Whether a screen-space geometry is too thin to show its outline and label
+    pub fn simplifies(&self, geometry: &LayerGeometry) -> bool {
+        geometry.get_bounds().height() < self.min_detailed_height
+    }
+}
+
+impl Default for LodPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,11 +2,35 @@
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
 pub mod colors;
+pub mod display_defaults;
 pub mod geometry;
+pub mod guides;
+pub mod hatching;
+pub mod headless;
+pub mod hit_index;
+pub mod layout;
+pub mod lod;
+pub mod profiler;
+pub mod snapshot;
 pub mod stack_renderer;
+pub mod structures;
+pub mod theme;
 pub mod thickness_scaler;
+pub mod width_fidelity;
 
 pub use colors::*;
+pub use display_defaults::*;
 pub use geometry::*;
+pub use guides::*;
+pub use hatching::*;
+pub use headless::*;
+pub use hit_index::*;
+pub use layout::*;
+pub use lod::*;
+pub use profiler::*;
+pub use snapshot::*;
 pub use stack_renderer::*;
+pub use structures::*;
+pub use theme::*;
 pub use thickness_scaler::*;
+pub use width_fidelity::*;
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Number of frames the profiler averages over
+pub const PROFILER_HISTORY: usize = 60;
+
+/// Time spent in each stage of drawing one frame of the stack view
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameTimings {
+    /// Layout of layers and vias in screen coordinates, including level-of-detail
+    pub geometry: Duration,
+    /// Layer names, dimension rulers and guide captions
+    pub text: Duration,
+    /// Building the layer, hatch, liner and via shapes and handing them to the painter
+    pub painting: Duration,
+    /// Layer and via geometries in the screen layout
+    pub geometry_count: usize,
+    /// Shapes generated for layers, hatches, liners and vias
+    pub shape_count: usize,
+}
+
+impl FrameTimings {
+    pub fn total(&self) -> Duration {
+        self.geometry + self.text + self.painting
+    }
+}
+
+/// Rolling history of the last [`PROFILER_HISTORY`] frame timings
+#[derive(Debug, Clone, Default)]
+pub struct RenderProfiler {
+    frames: VecDeque<FrameTimings>,
+}
+
+impl RenderProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, timings: FrameTimings) {
+        if self.frames.len() == PROFILER_HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(timings);
+    }
+
+    pub fn latest(&self) -> Option<&FrameTimings> {
+        self.frames.back()
+    }
+
+    /// Mean of every stage and count over the recorded frames
+    pub fn average(&self) -> Option<FrameTimings> {
+        let count = self.frames.len();
+        if count == 0 {
+            return None;
+        }
+        let sum = self
+            .frames
+            .iter()
+            .fold(FrameTimings::default(), |sum, frame| FrameTimings {
+                geometry: sum.geometry + frame.geometry,
+                text: sum.text + frame.text,
+                painting: sum.painting + frame.painting,
+                geometry_count: sum.geometry_count + frame.geometry_count,
+                shape_count: sum.shape_count + frame.shape_count,
+            });
+        Some(FrameTimings {
+            geometry: sum.geometry / count as u32,
+            text: sum.text / count as u32,
+            painting: sum.painting / count as u32,
+            geometry_count: sum.geometry_count / count,
+            shape_count: sum.shape_count / count,
+        })
+    }
+
+    /// Slowest total frame time in the history
+    pub fn worst_total(&self) -> Option<Duration> {
+        self.frames.iter().map(FrameTimings::total).max()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(millis: u64, shape_count: usize) -> FrameTimings {
+        FrameTimings {
+            geometry: Duration::from_millis(millis),
+            text: Duration::from_millis(2 * millis),
+            painting: Duration::from_millis(3 * millis),
+            geometry_count: 10,
+            shape_count,
+        }
+    }
+
+    #[test]
+    fn test_render_profiler() {
+        let mut profiler = RenderProfiler::new();
+        assert!(profiler.average().is_none());
+        assert!(profiler.worst_total().is_none());
+
+        profiler.record(frame(1, 100));
+        profiler.record(frame(3, 300));
+        assert_eq!(profiler.latest().unwrap().shape_count, 300);
+        let average = profiler.average().unwrap();
+        assert_eq!(average.geometry, Duration::from_millis(2));
+        assert_eq!(average.text, Duration::from_millis(4));
+        assert_eq!(average.shape_count, 200);
+        assert_eq!(average.total(), Duration::from_millis(12));
+        assert_eq!(profiler.worst_total(), Some(Duration::from_millis(18)));
+
+        // Only the most recent frames are kept
+        for _ in 0..PROFILER_HISTORY {
+            profiler.record(frame(1, 100));
+        }
+        assert_eq!(profiler.frame_count(), PROFILER_HISTORY);
+        assert_eq!(profiler.average().unwrap().shape_count, 100);
+
+        profiler.clear();
+        assert!(profiler.latest().is_none());
+    }
+}
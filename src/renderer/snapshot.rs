@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use egui::{Color32, Pos2};
+use std::fmt::Write;
+
+/// What a dumped primitive stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimitiveKind {
+    Dielectric,
+    Conductor,
+    Via,
+}
+
+impl PrimitiveKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PrimitiveKind::Dielectric => "dielectric",
+            PrimitiveKind::Conductor => "conductor",
+            PrimitiveKind::Via => "via",
+        }
+    }
+}
+
+/// One filled polygon of the rendered stack, independent of the view
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderPrimitive {
+    pub kind: PrimitiveKind,
+    /// Layer name, or the via name with its column index
+    pub name: String,
+    /// Corners in world coordinates with z pointing up, counter-clockwise from bottom left
+    pub points: Vec<Pos2>,
+    pub fill_color: Color32,
+}
+
+impl RenderPrimitive {
+    /// Layer or via name, without the column index of via primitives
+    pub fn base_name(&self) -> &str {
+        if self.kind != PrimitiveKind::Via {
+            return &self.name;
+        }
+        match self.name.rsplit_once('_') {
+            Some((base, column)) if column.chars().all(|c| c.is_ascii_digit()) => base,
+            _ => &self.name,
+        }
+    }
+
+    /// Horizontal center of the polygon
+    pub fn center_x(&self) -> f32 {
+        let (min, max) = self
+            .points
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| {
+                (min.min(p.x), max.max(p.x))
+            });
+        (min + max) * 0.5
+    }
+}
+
+/// Text form of a shape dump, one primitive per line
+///
+/// Coordinates are rounded to 1e-3 so the output is stable across platforms and
+/// suitable for golden files. Colors are left out, the dump tracks layout only.
+pub fn format_primitives(primitives: &[RenderPrimitive]) -> String {
+    let mut dump = String::new();
+    for primitive in primitives {
+        let _ = write!(dump, "{} {}", primitive.kind.label(), primitive.name);
+        for point in &primitive.points {
+            let _ = write!(dump, " ({},{})", round(point.x), round(point.y));
+        }
+        dump.push('\n');
+    }
+    dump
+}
+
+fn round(value: f32) -> String {
+    // Adding zero turns -0.0 into 0.0
+    format!("{:.3}", (value * 1000.0).round() / 1000.0 + 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_primitives() {
+        let primitive = RenderPrimitive {
+            kind: PrimitiveKind::Via,
+            name: "via1_0".to_string(),
+            points: vec![
+                Pos2::new(-0.0001, 1.0),
+                Pos2::new(0.5, 1.0),
+                Pos2::new(0.5, 1.23456),
+                Pos2::new(-0.0001, 1.23456),
+            ],
+            fill_color: Color32::GRAY,
+        };
+
+        assert_eq!(
+            format_primitives(std::slice::from_ref(&primitive)),
+            "via via1_0 (0.000,1.000) (0.500,1.000) (0.500,1.235) (0.000,1.235)\n"
+        );
+        assert!((primitive.center_x() - 0.25).abs() < 1e-3);
+        assert_eq!(primitive.base_name(), "via1");
+    }
+}
@@ -1,10 +1,48 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use crate::data::{Layer, ProcessStack};
-use crate::renderer::{colors::ColorScheme, geometry::*, thickness_scaler::ThicknessScaler};
+use crate::data::{ErRegion, Layer, LayerClass, ProcessStack, ViaArray};
+use crate::renderer::{
+    colors::ColorScheme,
+    geometry::*,
+    guides::Guide,
+    hatching::*,
+    hit_index::HitIndex,
+    layout::{StackLayout, ViewAnchor},
+    lod::LodPolicy,
+    profiler::FrameTimings,
+    snapshot::RenderPrimitive,
+    structures::{layout_structure, StructureAnnotation},
+    theme::Theme,
+    thickness_scaler::ThicknessScaler,
+    width_fidelity::{find_width_distortions, WidthDistortion},
+};
 use egui::{Align2, Color32, FontId, Pos2, Rect, Shape, Stroke, Vec2};
 use std::collections::HashMap;
+use std::time::Instant;
+
+/// Height of each substrate or field band below the stack, as a share of the drawn stack
+pub const ER_REGION_BAND_FRACTION: f32 = 0.08;
+
+/// Fill of vias that are not selected
+pub const VIA_COLOR: Color32 = Color32::from_rgb(192, 192, 192);
+
+/// Fraction of the pad width the passivation overlaps on each side of an opening
+const PASSIVATION_OVERLAP: f32 = 0.15;
+
+/// Outline and caption color of the example structures
+const STRUCTURE_COLOR: Color32 = Color32::from_rgb(255, 140, 0);
+
+/// Outline color of the ghost stack, such as an older revision of the file
+pub const GHOST_COLOR: Color32 = Color32::from_rgba_premultiplied(180, 0, 180, 180);
+
+/// Viewport axes a fit zooms to, the other one keeping its view center
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitAxis {
+    Both,
+    Width,
+    Height,
+}
 
 /// Parameters for creating a single layer geometry
 struct LayerGeometryParams<'a> {
@@ -12,18 +50,57 @@ struct LayerGeometryParams<'a> {
     layer_index: usize,
     z_bottom: f32,
     z_top: f32,
+    origin_y: f32, // World y of z = 0
     exaggerated_height: f32,
     layer_width: f32,
     max_trapezoid_width: Option<f32>, // Reference width for three-column alignment
 }
 
+/// A ruler tick mark at world height `world_y`, reading `value` in um
+struct RulerTick {
+    world_y: f32,
+    value: f32,
+    is_major: bool,
+}
+
 pub struct StackRenderer {
     color_scheme: ColorScheme,
     layer_width: f32,
     show_dimensions: bool,
     pub show_layer_names: bool,
     pub show_schematic_mode: bool,
+    pub show_merged_vias: bool,
+    /// Size vias from their AREA at the scale of the metal widths, instead of filling
+    /// the narrowest edge of the metals they connect
+    pub area_accurate_vias: bool,
+    pub show_isometric_mode: bool,
+    /// Texture dielectrics with their hatch pattern on top of the flat fill
+    pub show_hatching: bool,
+    /// Outline the SW_T/TW_T dielectric liners around the conductors they cover
+    pub show_conformal_liners: bool,
+    /// Draw conductor sidewalls at their SIDE_TANGENT slope instead of the stylized one
+    pub true_sidewall_angles: bool,
+    /// Factor applied to SIDE_TANGENT when drawing true angles, 1.0 being to scale
+    sidewall_exaggeration: f32,
+    /// Draw layers and vias as outlines in their color, without fills or hatching
+    pub show_outline_only: bool,
+    /// Bottom and top layer names of the cutline, limiting the view to a sub-range of the stack
+    visible_layer_range: Option<(String, String)>,
+    /// End of the stack placed at world y = 0, which also sets the ruler direction
+    view_anchor: ViewAnchor,
     selected_layer: Option<String>,
+    /// Layers drawn like the selection, such as the dielectrics a selected via passes through
+    highlighted_layers: Vec<String>,
+    /// Reference lines at fixed heights, drawn across the view
+    guides: Vec<Guide>,
+    /// Example structures drawn over the cross-section
+    structures: Vec<StructureAnnotation>,
+    /// Another version of the stack outlined over this one to show what changed
+    ghost_stack: Option<ProcessStack>,
+    /// Labels drawn instead of the ITF names of conductors and vias, keyed by ITF name
+    display_names: HashMap<String, String>,
+    /// Simplification of huge stacks at low zoom
+    lod_policy: LodPolicy,
     pub thickness_scaler: ThicknessScaler,
 }
 
@@ -35,11 +112,51 @@ impl StackRenderer {
             show_dimensions: true,
             show_layer_names: true,
             show_schematic_mode: false,
+            show_merged_vias: true,
+            area_accurate_vias: false,
+            show_isometric_mode: false,
+            show_hatching: false,
+            show_conformal_liners: false,
+            true_sidewall_angles: false,
+            sidewall_exaggeration: 1.0,
+            show_outline_only: false,
+            visible_layer_range: None,
+            view_anchor: ViewAnchor::Substrate,
             selected_layer: None,
+            highlighted_layers: Vec::new(),
+            guides: Vec::new(),
+            structures: Vec::new(),
+            ghost_stack: None,
+            display_names: HashMap::new(),
+            lod_policy: LodPolicy::new(),
             thickness_scaler: ThicknessScaler::new(),
         }
     }
 
+    /// Layout stage: place every layer and via in world coordinates
+    ///
+    /// The result only depends on the stack, the renderer options and `viewport_width`,
+    /// which sets the default layer width. Drawing, hit testing and exporters all start
+    /// from this layout.
+    pub fn layout_stack(&self, stack: &ProcessStack, viewport_width: f32) -> StackLayout {
+        let scaler = self.get_current_scaler(stack);
+        let transform = ViewTransform::identity();
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(viewport_width, 0.0));
+
+        StackLayout {
+            layers: self.filter_to_visible_range(
+                stack,
+                &scaler,
+                self.create_layer_geometries_ordered(stack, &scaler, &transform, viewport_rect),
+            ),
+            vias: self.filter_to_visible_range(
+                stack,
+                &scaler,
+                self.create_via_geometries_with_scaler(stack, &scaler, &transform, viewport_rect),
+            ),
+        }
+    }
+
     pub fn render_stack(
         &self,
         stack: &ProcessStack,
@@ -47,67 +164,209 @@ impl StackRenderer {
         viewport_rect: Rect,
     ) -> Vec<Shape> {
         let mut shapes = Vec::new();
+        let (mut layout, _) = self.screen_layout(stack, transform, viewport_rect);
+        let markers = take_markers(stack, &mut layout);
 
-        // Choose the appropriate scaler based on mode
-        let scaler = if self.show_schematic_mode {
-            self.create_schematic_scaler(stack)
+        if self.show_isometric_mode {
+            shapes.extend(self.styled(self.create_isometric_shapes(&layout.layers, &layout.vias)));
         } else {
-            // For normal mode, use 1:1 scaling (no exaggeration)
-            self.create_normal_scaler(stack)
-        };
+            shapes.extend(self.styled(self.create_er_region_shapes(stack, &layout, transform)));
+            shapes.extend(self.styled(self.create_flat_shapes(&layout, viewport_rect)));
+            shapes.extend(self.create_liner_shapes(stack, &layout));
+            shapes.extend(self.create_marker_shapes(stack, &markers));
+        }
 
-        // Calculate layer positions and create geometries with proper stacking order
-        let layer_geometries =
-            self.create_layer_geometries_ordered(stack, &scaler, transform, viewport_rect);
-        let via_geometries =
-            self.create_via_geometries_with_scaler(stack, &scaler, transform, viewport_rect);
+        // Add dimension annotations (but not in schematic mode)
+        if self.show_dimensions && !self.show_schematic_mode {
+            let scaler = self.get_current_scaler(stack);
+            shapes.extend(self.create_dimension_shapes_with_scaler(
+                stack,
+                &scaler,
+                transform,
+                viewport_rect,
+            ));
+        }
 
-        // Separate geometries by layer type for proper z-ordering
-        let mut dielectric_geometries = Vec::new();
-        let mut conductor_geometries = Vec::new();
+        shapes
+    }
 
-        for geometry in &layer_geometries {
-            // Check if this is a conductor layer by looking at the shape type
-            match &geometry.shape {
-                LayerShape::ThreeColumnTrapezoid(_) => {
-                    // All conductor layers use ThreeColumnTrapezoid
-                    conductor_geometries.push(geometry);
-                }
-                _ => {
-                    // All other shapes are dielectric layers
-                    dielectric_geometries.push(geometry);
+    /// Filled polygons of the stack in world coordinates, in drawing order
+    ///
+    /// A deterministic dump of `layout_stack` for regression tests. Text and dimension
+    /// annotations are not included.
+    pub fn dump_primitives(
+        &self,
+        stack: &ProcessStack,
+        viewport_width: f32,
+    ) -> Vec<RenderPrimitive> {
+        self.layout_stack(stack, viewport_width).primitives()
+    }
+
+    /// Layout in screen coordinates, simplified when the level-of-detail policy applies
+    ///
+    /// Returns whether the policy is active, in which case off-screen geometries are
+    /// dropped and the thin ones replaced by plain rectangles.
+    fn screen_layout(
+        &self,
+        stack: &ProcessStack,
+        transform: &ViewTransform,
+        viewport_rect: Rect,
+    ) -> (StackLayout, bool) {
+        let mut layout = self
+            .layout_stack(stack, viewport_rect.width())
+            .to_screen(transform);
+        let lod_active = self.lod_policy.is_active(&layout, viewport_rect);
+        if lod_active {
+            layout.retain_visible(viewport_rect);
+            for geometry in layout.layers.iter_mut().chain(layout.vias.iter_mut()) {
+                if self.lod_policy.simplifies(geometry) {
+                    *geometry = geometry.simplified();
                 }
             }
         }
+        (layout, lod_active)
+    }
 
-        // Render dielectric layers first (bottom z-index)
-        for geometry in &dielectric_geometries {
-            shapes.extend(geometry.to_egui_shapes());
-        }
+    /// Drawing stage of the flat view, with the layout already in screen coordinates
+    fn create_flat_shapes(&self, layout: &StackLayout, viewport_rect: Rect) -> Vec<Shape> {
+        // Dielectrics first (bottom z-index), conductors on top of them, vias on top of all
+        layout
+            .dielectrics()
+            .flat_map(|geometry| {
+                let mut shapes = geometry.to_egui_shapes();
+                shapes.extend(self.create_hatch_shapes(geometry, viewport_rect));
+                shapes
+            })
+            .chain(
+                layout
+                    .conductors()
+                    .chain(&layout.vias)
+                    .flat_map(LayerGeometry::to_egui_shapes),
+            )
+            .collect()
+    }
 
-        // Render conductor layers second (higher z-index, will appear on top)
-        for geometry in &conductor_geometries {
-            shapes.extend(geometry.to_egui_shapes());
+    /// Layer and via shapes as outlines when outline-only mode is on
+    fn styled(&self, shapes: Vec<Shape>) -> Vec<Shape> {
+        if !self.show_outline_only {
+            return shapes;
         }
+        shapes.into_iter().map(outline_shape).collect()
+    }
 
-        // Render vias on top of all layers (highest z-index)
-        for geometry in &via_geometries {
-            shapes.extend(geometry.to_egui_shapes());
+    /// Hatch texture of a screen-space geometry, limited to the visible part
+    fn create_hatch_shapes(&self, geometry: &LayerGeometry, viewport_rect: Rect) -> Vec<Shape> {
+        let LayerShape::Rectangle(rect) = &geometry.shape else {
+            return Vec::new();
+        };
+        if !self.show_hatching || self.show_outline_only || geometry.hatch == HatchPattern::None {
+            return Vec::new();
         }
 
-        // Add dimension annotations (but not in schematic mode)
-        if self.show_dimensions && !self.show_schematic_mode {
-            shapes.extend(self.create_dimension_shapes_with_scaler(
-                stack,
-                &scaler,
-                transform,
-                viewport_rect,
-            ));
+        hatch_shapes(
+            rect.rect.intersect(viewport_rect),
+            geometry.hatch,
+            hatch_color(rect.fill_color),
+            HATCH_SPACING,
+        )
+    }
+
+    /// Outlines of the conformal dielectrics around their conductors' trapezoids
+    ///
+    /// SW_T and TW_T are scaled like the conductor thickness, so the outline follows any
+    /// thickness exaggeration of the view.
+    fn create_liner_shapes(&self, stack: &ProcessStack, layout: &StackLayout) -> Vec<Shape> {
+        if !self.show_conformal_liners {
+            return Vec::new();
         }
 
+        let mut shapes = Vec::new();
+        for liner in stack.get_conformal_liners() {
+            let Some(geometry) = layout
+                .conductors()
+                .find(|geometry| geometry.layer_name == liner.conductor)
+            else {
+                continue;
+            };
+            let LayerShape::ThreeColumnTrapezoid(columns) = &geometry.shape else {
+                continue;
+            };
+            let (Some(conductor), Some(index)) = (
+                stack.get_layer(&liner.conductor),
+                stack
+                    .layers
+                    .iter()
+                    .position(|layer| layer.name() == liner.dielectric),
+            ) else {
+                continue;
+            };
+            if conductor.thickness() <= 0.0 {
+                continue;
+            }
+
+            let scale = geometry.get_bounds().height() / conductor.thickness() as f32;
+            let sidewall = liner.sidewall_thickness as f32 * scale;
+            let top = liner.top_thickness as f32 * scale;
+            let stroke = Stroke::new(
+                1.0,
+                hatch_color(
+                    self.color_scheme
+                        .get_layer_color(&stack.layers[index], index),
+                ),
+            );
+
+            for trapezoid in [
+                &columns.left_trapezoid,
+                &columns.center_trapezoid,
+                &columns.right_trapezoid,
+            ] {
+                shapes.push(Shape::line(
+                    vec![
+                        trapezoid.bottom_left - Vec2::new(sidewall, 0.0),
+                        trapezoid.top_left - Vec2::new(sidewall, top),
+                        trapezoid.top_right + Vec2::new(sidewall, -top),
+                        trapezoid.bottom_right + Vec2::new(sidewall, 0.0),
+                    ],
+                    stroke,
+                ));
+            }
+        }
         shapes
     }
 
+    /// Extrude all geometries into a shallow 3D block using the painter's algorithm
+    fn create_isometric_shapes(
+        &self,
+        layer_geometries: &[LayerGeometry],
+        via_geometries: &[LayerGeometry],
+    ) -> Vec<Shape> {
+        // Block depth follows the widest layer so the extrusion stays proportional on zoom
+        let max_width = layer_geometries
+            .iter()
+            .map(|geometry| geometry.get_bounds().width())
+            .fold(0.0_f32, f32::max);
+        let depth_offset = isometric_depth_offset(max_width * 0.25);
+
+        // Paint from the bottom of the stack upwards so upper layers cover the top faces
+        // of the layers below; at the same height dielectrics go first, then conductors, then vias
+        let mut ordered: Vec<(u8, &LayerGeometry)> = layer_geometries
+            .iter()
+            .map(|geometry| match geometry.shape {
+                LayerShape::ThreeColumnTrapezoid(_) => (1, geometry),
+                _ => (0, geometry),
+            })
+            .chain(via_geometries.iter().map(|geometry| (2, geometry)))
+            .collect();
+        ordered.sort_by(|(rank_a, a), (rank_b, b)| {
+            a.z_bottom.total_cmp(&b.z_bottom).then(rank_a.cmp(rank_b))
+        });
+
+        ordered
+            .into_iter()
+            .flat_map(|(_, geometry)| geometry.to_isometric_shapes(depth_offset))
+            .collect()
+    }
+
     /// Calculate appropriate dielectric layer width to contain conductor layers
     /// Using ideal DCDCDCD layout: 7x max trapezoid width for proper spacing
     fn calculate_dielectric_width_for_conductors(
@@ -130,7 +389,7 @@ impl StackRenderer {
 
         // Debug output for dielectric width calculation
         if cfg!(debug_assertions) {
-            println!("DEBUG Dielectric Unified: Mode: {}, Max trapezoid width: {}, DCDCDCD width: {}, Original layer width: {}", 
+            eprintln!("DEBUG Dielectric Unified: Mode: {}, Max trapezoid width: {}, DCDCDCD width: {}, Original layer width: {}", 
                 if self.show_schematic_mode { "Schematic" } else { "Normal" },
                 max_trapezoid_width, ideal_dielectric_width, original_layer_width);
         }
@@ -147,55 +406,323 @@ impl StackRenderer {
     }
 
     /// Render the stack with text, using a painter for proper text rendering
+    ///
+    /// Returns how long each stage took, for the profiler overlay.
     pub fn render_stack_with_painter(
         &self,
         stack: &ProcessStack,
         transform: &ViewTransform,
         viewport_rect: Rect,
         painter: &egui::Painter,
-    ) {
-        // Create scaler for layer thickness
-        let scaler = if self.show_schematic_mode {
-            self.create_schematic_scaler(stack)
-        } else {
-            self.create_normal_scaler(stack)
+    ) -> FrameTimings {
+        let mut timings = FrameTimings::default();
+
+        let start = Instant::now();
+        let (mut layout, lod_active) = self.screen_layout(stack, transform, viewport_rect);
+        let markers = take_markers(stack, &mut layout);
+        timings.geometry = start.elapsed();
+        timings.geometry_count = layout.layers.len() + layout.vias.len();
+
+        let start = Instant::now();
+        let mut paint = |shapes: Vec<Shape>| {
+            timings.shape_count += shapes.len();
+            painter.extend(shapes);
         };
-
-        // Get all layer geometries
-        let layer_geometries =
-            self.create_layer_geometries_ordered(stack, &scaler, transform, viewport_rect);
-        let via_geometries =
-            self.create_via_geometries_with_scaler(stack, &scaler, transform, viewport_rect);
-
-        // Render all layer geometries
-        for geometry in &layer_geometries {
-            // Add layer shapes
-            for shape in geometry.to_egui_shapes() {
-                painter.add(shape);
+        if self.show_isometric_mode {
+            paint(self.styled(self.create_isometric_shapes(&layout.layers, &layout.vias)));
+        } else {
+            // Regions under the stack, layers from the bottom up, then liners, then vias on
+            // top of all (highest z-index)
+            paint(self.styled(self.create_er_region_shapes(stack, &layout, transform)));
+            for geometry in &layout.layers {
+                paint(self.styled(geometry.to_egui_shapes()));
+                paint(self.create_hatch_shapes(geometry, viewport_rect));
             }
-        }
-
-        // Render vias on top of all layers (highest z-index)
-        for geometry in &via_geometries {
-            for shape in geometry.to_egui_shapes() {
-                painter.add(shape);
+            paint(self.create_liner_shapes(stack, &layout));
+            for geometry in &layout.vias {
+                paint(self.styled(geometry.to_egui_shapes()));
             }
+            paint(self.create_marker_shapes(stack, &markers));
+            paint(self.create_ghost_shapes(transform, viewport_rect));
         }
+        timings.painting = start.elapsed();
 
+        let start = Instant::now();
         // Render text with smart positioning based on layer type and height
-        if self.show_layer_names {
+        if self.show_layer_names && lod_active {
+            // Simplified layers are too thin for a readable label
+            let labeled = |geometries: &[LayerGeometry]| -> Vec<LayerGeometry> {
+                geometries
+                    .iter()
+                    .filter(|geometry| !self.lod_policy.simplifies(geometry))
+                    .cloned()
+                    .collect()
+            };
             self.render_text_with_smart_positioning(
-                &layer_geometries,
-                &via_geometries,
+                &labeled(&layout.layers),
+                &labeled(&layout.vias),
                 painter,
                 transform,
             );
+        } else if self.show_layer_names {
+            self.render_text_with_smart_positioning(
+                &layout.layers,
+                &layout.vias,
+                painter,
+                transform,
+            );
+        }
+        if self.show_layer_names && !self.show_isometric_mode {
+            self.render_marker_text(stack, &markers, painter);
+            self.render_er_region_text(stack, &layout, transform, painter);
         }
 
         // Add dimension annotations with text using painter (but not in schematic mode)
         if self.show_dimensions && !self.show_schematic_mode {
             self.render_dimensions_with_painter(stack, transform, viewport_rect, painter);
         }
+
+        // Guides are physical heights, meaningless when thicknesses are not to scale
+        if !self.show_schematic_mode {
+            self.render_guides_with_painter(stack, transform, viewport_rect, painter);
+        }
+        if !self.show_isometric_mode {
+            self.render_structures_with_painter(stack, transform, viewport_rect, painter);
+        }
+        timings.text = start.elapsed();
+
+        timings
+    }
+
+    /// Substrate and field regions with the z interval of their bands, from just below the
+    /// stack downward
+    ///
+    /// The bands are a fixed share of the drawn stack rather than to scale, a substrate
+    /// being far thicker than the interconnect. They are left out while a cutline is set.
+    pub fn get_er_region_bands<'a>(
+        &self,
+        stack: &'a ProcessStack,
+        scaler: &ThicknessScaler,
+    ) -> Vec<(&'a ErRegion, f32, f32)> {
+        if self.visible_layer_range.is_some() {
+            return Vec::new();
+        }
+        let band = scaler.get_exaggerated_total_height(stack) * ER_REGION_BAND_FRACTION;
+        stack
+            .get_er_regions_from_top()
+            .into_iter()
+            .enumerate()
+            .map(|(index, region)| {
+                let z_top = -band * index as f32;
+                (region, z_top - band, z_top)
+            })
+            .collect()
+    }
+
+    /// Screen rectangles of the region bands, as wide as the layers of the screen layout
+    fn er_region_rects<'a>(
+        &self,
+        stack: &'a ProcessStack,
+        layout: &StackLayout,
+        transform: &ViewTransform,
+    ) -> Vec<(&'a ErRegion, Rect)> {
+        let Some((min_x, max_x)) = layout
+            .layers
+            .iter()
+            .map(LayerGeometry::get_bounds)
+            .map(|bounds| (bounds.min.x, bounds.max.x))
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+        else {
+            return Vec::new();
+        };
+        let scaler = self.get_current_scaler(stack);
+        let origin_y = self.get_origin_y(stack, &scaler);
+        let screen_y = |z: f32| transform.world_to_screen(Pos2::new(0.0, origin_y - z)).y;
+        self.get_er_region_bands(stack, &scaler)
+            .into_iter()
+            .map(|(region, z_bottom, z_top)| {
+                let rect = Rect::from_x_y_ranges(
+                    min_x..=max_x,
+                    screen_y(z_top).min(screen_y(z_bottom))
+                        ..=screen_y(z_top).max(screen_y(z_bottom)),
+                );
+                (region, rect)
+            })
+            .collect()
+    }
+
+    /// Filled bands of the substrate and field regions pinned below the stack
+    fn create_er_region_shapes(
+        &self,
+        stack: &ProcessStack,
+        layout: &StackLayout,
+        transform: &ViewTransform,
+    ) -> Vec<Shape> {
+        let stroke = Stroke::new(1.0, self.color_scheme.get_layer_outline_color(false));
+        self.er_region_rects(stack, layout, transform)
+            .into_iter()
+            .flat_map(|(region, rect)| {
+                let color = self.color_scheme.get_er_region_color(region.kind);
+                [
+                    Shape::rect_filled(rect, 0.0, color),
+                    Shape::rect_stroke(rect, 0.0, stroke, egui::StrokeKind::Inside),
+                ]
+            })
+            .collect()
+    }
+
+    /// Region names with their ER in the middle of the bands
+    fn render_er_region_text(
+        &self,
+        stack: &ProcessStack,
+        layout: &StackLayout,
+        transform: &ViewTransform,
+        painter: &egui::Painter,
+    ) {
+        for (region, rect) in self.er_region_rects(stack, layout, transform) {
+            let font_id = FontId::proportional((rect.height() * 0.6).clamp(8.0, 12.0));
+            let text = format!("{} (ER={})", region.name, region.dielectric_constant);
+            self.render_outlined_text_centered(rect.center(), &text, &font_id, painter);
+        }
+    }
+
+    /// Outlines of the ghost stack's layers and vias, laid out like the current stack
+    fn create_ghost_shapes(&self, transform: &ViewTransform, viewport_rect: Rect) -> Vec<Shape> {
+        let Some(ghost) = &self.ghost_stack else {
+            return Vec::new();
+        };
+        let layout = self
+            .layout_stack(ghost, viewport_rect.width())
+            .to_screen(transform);
+        layout
+            .layers
+            .iter()
+            .chain(&layout.vias)
+            .flat_map(|geometry| geometry.to_egui_shapes())
+            .map(|shape| ghost_shape(shape, GHOST_COLOR))
+            .collect()
+    }
+
+    /// Dashed boundary lines of marker layers, which have no height to fill
+    fn create_marker_shapes(&self, stack: &ProcessStack, markers: &[LayerGeometry]) -> Vec<Shape> {
+        markers
+            .iter()
+            .flat_map(|geometry| {
+                let bounds = geometry.get_bounds();
+                let y = bounds.center().y;
+                Shape::dashed_line(
+                    &[Pos2::new(bounds.min.x, y), Pos2::new(bounds.max.x, y)],
+                    Stroke::new(1.5, self.marker_color(stack, &geometry.layer_name)),
+                    6.0,
+                    3.0,
+                )
+            })
+            .collect()
+    }
+
+    /// Marker names at the right end of their boundary lines
+    fn render_marker_text(
+        &self,
+        stack: &ProcessStack,
+        markers: &[LayerGeometry],
+        painter: &egui::Painter,
+    ) {
+        for geometry in markers {
+            let bounds = geometry.get_bounds();
+            painter.text(
+                Pos2::new(bounds.max.x + 4.0, bounds.center().y),
+                Align2::LEFT_CENTER,
+                self.get_label(&geometry.layer_name),
+                FontId::proportional(10.0),
+                self.marker_color(stack, &geometry.layer_name),
+            );
+        }
+    }
+
+    fn marker_color(&self, stack: &ProcessStack, name: &str) -> Color32 {
+        stack
+            .layers
+            .iter()
+            .enumerate()
+            .find(|(_, layer)| layer.name() == name)
+            .map_or(Color32::GRAY, |(index, layer)| {
+                self.color_scheme.get_layer_color(layer, index).to_opaque()
+            })
+    }
+
+    /// Dashed guide lines across the viewport, captioned at the right edge
+    fn render_guides_with_painter(
+        &self,
+        stack: &ProcessStack,
+        transform: &ViewTransform,
+        viewport_rect: Rect,
+        painter: &egui::Painter,
+    ) {
+        let color = Color32::from_rgb(0, 200, 255);
+        for guide in &self.guides {
+            let world_y = self.guide_world_y(stack, guide.z);
+            let screen_y = transform.world_to_screen(Pos2::new(0.0, world_y)).y;
+            if !(viewport_rect.min.y..=viewport_rect.max.y).contains(&screen_y) {
+                continue;
+            }
+
+            painter.extend(Shape::dashed_line(
+                &[
+                    Pos2::new(viewport_rect.min.x, screen_y),
+                    Pos2::new(viewport_rect.max.x, screen_y),
+                ],
+                Stroke::new(1.0, color),
+                8.0,
+                4.0,
+            ));
+            painter.text(
+                Pos2::new(viewport_rect.max.x - 4.0, screen_y - 2.0),
+                Align2::RIGHT_BOTTOM,
+                guide.caption(),
+                FontId::monospace(10.0),
+                color,
+            );
+        }
+    }
+
+    /// Example structures as translucent outlined rectangles with their dimensions
+    fn render_structures_with_painter(
+        &self,
+        stack: &ProcessStack,
+        transform: &ViewTransform,
+        viewport_rect: Rect,
+        painter: &egui::Painter,
+    ) {
+        if self.structures.is_empty() {
+            return;
+        }
+        // The world layout, since the screen layout may have dropped off-screen layers
+        let layout = self.layout_stack(stack, viewport_rect.width());
+        for annotation in &self.structures {
+            for shape in layout_structure(stack, &layout, annotation) {
+                let rect = Rect::from_two_pos(
+                    transform.world_to_screen(shape.rect.min),
+                    transform.world_to_screen(shape.rect.max),
+                );
+                let fill = if shape.is_via { 0.6 } else { 0.35 };
+                painter.rect(
+                    rect,
+                    0.0,
+                    STRUCTURE_COLOR.gamma_multiply(fill),
+                    Stroke::new(1.5, STRUCTURE_COLOR),
+                    egui::StrokeKind::Inside,
+                );
+                if let Some(caption) = shape.caption {
+                    painter.text(
+                        Pos2::new(rect.max.x + 4.0, rect.center().y),
+                        Align2::LEFT_CENTER,
+                        caption,
+                        FontId::monospace(10.0),
+                        STRUCTURE_COLOR,
+                    );
+                }
+            }
+        }
     }
 
     /// Render text with smart positioning based on layer type and height constraints
@@ -306,7 +833,12 @@ impl StackRenderer {
         );
 
         // Render text with outline, centered alignment
-        self.render_outlined_text_centered(text_pos, layer_name, &adjusted_font_id, painter);
+        self.render_outlined_text_centered(
+            text_pos,
+            self.get_label(layer_name),
+            &adjusted_font_id,
+            painter,
+        );
     }
 
     /// Render text for vias on the right side, constrained by via height
@@ -349,7 +881,12 @@ impl StackRenderer {
             );
 
             // Render text with outline using base name, centered alignment
-            self.render_outlined_text_centered(text_pos, base_name, &adjusted_font_id, painter);
+            self.render_outlined_text_centered(
+                text_pos,
+                self.get_label(base_name),
+                &adjusted_font_id,
+                painter,
+            );
         }
     }
 
@@ -467,6 +1004,8 @@ impl StackRenderer {
             crate::renderer::geometry::find_max_conductor_trapezoid_width(&conductor_layers)
         };
 
+        let origin_y = self.get_origin_y(stack, scaler);
+
         // ITF layers are defined from top to bottom, but we need to render from bottom to top
         // So we reverse the layer order for rendering to match the physical stack
         let mut current_z = 0.0f32;
@@ -526,6 +1065,7 @@ impl StackRenderer {
                 layer_index,
                 z_bottom,
                 z_top,
+                origin_y,
                 exaggerated_height,
                 layer_width,
                 max_trapezoid_width,
@@ -535,9 +1075,71 @@ impl StackRenderer {
             geometries.push(geometry);
         }
 
+        Self::cut_passivation_opening(stack, &mut geometries);
         geometries
     }
 
+    /// Notch the dielectrics opened above the last metal, down to the top of the metal
+    ///
+    /// The opening spans the middle of the center column of the metal, with the
+    /// passivation overlapping the pad edges as on silicon.
+    fn cut_passivation_opening(stack: &ProcessStack, geometries: &mut [LayerGeometry]) {
+        let Some(opening) = stack.get_passivation_opening() else {
+            return;
+        };
+        let Some(LayerShape::ThreeColumnTrapezoid(columns)) = geometries
+            .iter()
+            .find(|geometry| geometry.layer_name == opening.conductor)
+            .map(|geometry| &geometry.shape)
+        else {
+            return;
+        };
+        // Geometry y grows downwards
+        let pad = &columns.center_trapezoid;
+        let pad_top = pad.top_left.y.min(pad.top_right.y);
+        let overlap = (pad.top_right.x - pad.top_left.x) * PASSIVATION_OVERLAP;
+        let (left, right) = (pad.top_left.x + overlap, pad.top_right.x - overlap);
+        if right <= left {
+            return;
+        }
+
+        for geometry in geometries
+            .iter_mut()
+            .filter(|geometry| opening.dielectrics.contains(&geometry.layer_name))
+        {
+            let LayerShape::Rectangle(rectangle) = &geometry.shape else {
+                continue;
+            };
+            let bounds = rectangle.rect;
+            if bounds.top() >= pad_top || bounds.left() >= left || bounds.right() <= right {
+                continue;
+            }
+            let piece = |min: Pos2, max: Pos2| {
+                let rect = Rect::from_min_max(min, max);
+                TrapezoidShape::new(
+                    rect.center_bottom(),
+                    rect.width(),
+                    rect.height(),
+                    0.0,
+                    rectangle.fill_color,
+                    rectangle.stroke,
+                )
+            };
+            let mut trapezoids = vec![
+                piece(bounds.min, Pos2::new(left, bounds.max.y)),
+                piece(Pos2::new(right, bounds.min.y), bounds.max),
+            ];
+            // A dielectric embedding the metal keeps its part below the metal top
+            if bounds.bottom() > pad_top {
+                trapezoids.push(piece(
+                    Pos2::new(left, pad_top),
+                    Pos2::new(right, bounds.max.y),
+                ));
+            }
+            geometry.shape = LayerShape::MultiTrapezoid(MultiTrapezoidShape { trapezoids });
+        }
+    }
+
     fn create_single_layer_geometry(
         &self,
         params: &LayerGeometryParams,
@@ -545,9 +1147,6 @@ impl StackRenderer {
     ) -> LayerGeometry {
         let center_x = 0.0;
 
-        // Convert to world coordinates
-        let _world_bottom = Pos2::new(center_x, -params.z_bottom); // Flip Y axis
-
         // Calculate appropriate width based on layer type
         // For both conductor and dielectric layers, use world coordinates
         // Let the shape objects handle screen coordinate conversion
@@ -573,33 +1172,38 @@ impl StackRenderer {
             }
         };
 
-        let is_selected = self.selected_layer.as_deref() == Some(params.layer.name());
+        let is_selected = self.selected_layer.as_deref() == Some(params.layer.name())
+            || self
+                .highlighted_layers
+                .iter()
+                .any(|name| name == params.layer.name());
         let base_color = self
             .color_scheme
             .get_layer_color(params.layer, params.layer_index);
         let alpha = self.color_scheme.get_layer_alpha(params.layer, is_selected);
         let color = self.color_scheme.apply_alpha(base_color, alpha);
-        let outline_color = self.color_scheme.get_layer_outline_color(is_selected);
-        let stroke = Stroke::new(if is_selected { 2.0 } else { 1.0 }, outline_color);
+        let stroke = self
+            .color_scheme
+            .get_layer_stroke(params.layer, is_selected);
 
         match params.layer {
             Layer::Conductor(conductor) => {
                 // All conductor layers use three-column trapezoid layout with max trapezoid width as reference
                 // Pass world coordinates to geometry function, let it handle screen conversion
-                let world_bottom = Pos2::new(center_x, -params.z_bottom); // World coordinates
+                let world_bottom = Pos2::new(center_x, params.origin_y - params.z_bottom); // World coordinates
                 let world_height = params.exaggerated_height; // World height (not scaled)
 
                 let three_column_trapezoid =
-                    ThreeColumnTrapezoidShape::from_conductor_layer_with_reference(
-                        conductor,
-                        world_bottom,
-                        world_width,
+                    ThreeColumnTrapezoidShape::from_params(&ThreeColumnTrapezoidParams {
+                        layer: conductor,
+                        world_bottom_center: world_bottom,
                         world_height,
-                        color,
+                        fill_color: color,
                         stroke,
-                        params.max_trapezoid_width,
-                        Some(transform), // Pass transform for coordinate conversion
-                    );
+                        reference_trapezoid_width: params.max_trapezoid_width,
+                        view_transform: Some(transform), // Pass transform for coordinate conversion
+                        sidewall_exaggeration: self.get_sidewall_exaggeration(),
+                    });
                 LayerGeometry::new_three_column_trapezoid(
                     params.layer.name().to_string(),
                     params.z_bottom,
@@ -609,12 +1213,12 @@ impl StackRenderer {
             }
             Layer::Dielectric(_) => {
                 // Use world coordinates like conductor layers
-                let world_bottom = Pos2::new(center_x, -params.z_bottom); // World coordinates
+                let world_bottom = Pos2::new(center_x, params.origin_y - params.z_bottom); // World coordinates
                 let world_height = params.exaggerated_height; // World height (not scaled)
 
                 // Debug output for dielectric layer rendering
                 if cfg!(debug_assertions) {
-                    println!("DEBUG Dielectric Render: Layer '{}' - World width: {}, World height: {}, Mode: {}", 
+                    eprintln!("DEBUG Dielectric Render: Layer '{}' - World width: {}, World height: {}, Mode: {}", 
                         params.layer.name(), world_width, world_height,
                         if self.show_schematic_mode { "Schematic" } else { "Normal" });
                 }
@@ -633,6 +1237,7 @@ impl StackRenderer {
                     params.z_top,
                     rectangle,
                 )
+                .with_hatch(self.color_scheme.get_layer_hatch(params.layer))
             }
         }
     }
@@ -648,12 +1253,16 @@ impl StackRenderer {
 
         // Get layer boundaries for precise VIA positioning
         let layer_boundaries = self.calculate_ordered_layer_boundaries(stack, scaler);
+        let origin_y = self.get_origin_y(stack, scaler);
 
         // Calculate optimal layer width for metal positioning (not used in new 7x layout)
         let total_exaggerated_height = scaler.get_exaggerated_total_height(stack);
         let _layer_width =
             calculate_optimal_layer_width(total_exaggerated_height, viewport_rect.width(), 50.0);
 
+        // Smallest via AREA in the stack is taken as a single cut when approximating arrays
+        let unit_cut_area = stack.via_stack.get_min_via_area().unwrap_or(0.0);
+
         for via in stack.via_stack.iter() {
             // Find boundary positions for FROM and TO layers
             let from_bounds = layer_boundaries.get(&via.from_layer);
@@ -697,10 +1306,11 @@ impl StackRenderer {
 
                 // Calculate via width based on connected metal layers (narrowest edge)
                 let via_width = self.calculate_via_width(via, stack, scaler);
+                let via_array = via.get_array(unit_cut_area);
 
                 // Convert via center to screen coordinates
                 let via_center_z = (via_z_start + via_z_end) * 0.5;
-                let world_center = Pos2::new(0.0, -via_center_z); // Center in world coords
+                let world_center = Pos2::new(0.0, origin_y - via_center_z); // Center in world coords
                 let screen_center = transform.world_to_screen(world_center);
                 let screen_height = via_height * transform.scale;
                 let screen_width = via_width * transform.scale;
@@ -728,96 +1338,26 @@ impl StackRenderer {
                     ];
 
                     // VIAs now perfectly aligned with the new 7x layout trapezoid columns
-
                     for (i, &screen_x) in screen_positions.iter().enumerate() {
-                        // Create via rectangle directly in screen coordinates
-                        let via_screen_center = Pos2::new(screen_x, screen_center.y);
-
-                        // Check if this VIA will be selected
-                        let via_name = format!("{}_{}", via.name, i);
-                        let is_selected = self.selected_layer.as_deref() == Some(&via_name)
-                            || self.selected_layer.as_deref() == Some(&via.name);
-
-                        // Use different colors for selected vs normal VIAs
-                        let via_color = if is_selected {
-                            Color32::from_rgb(255, 215, 0) // Gold color for selected VIA
-                        } else {
-                            Color32::from_rgb(192, 192, 192) // Silver-gray color for normal VIA
-                        };
-                        let stroke = Stroke::new(
-                            if is_selected { 3.0 } else { 2.0 },
-                            if is_selected {
-                                Color32::YELLOW
-                            } else {
-                                Color32::DARK_GRAY
-                            },
-                        );
-
-                        let rectangle = RectangleShape::new(
-                            via_screen_center,
-                            screen_width,
-                            screen_height,
-                            via_color,
-                            stroke,
-                        );
-
-                        let via_name = format!("{}_{}", via.name, i);
-                        let mut geometry = LayerGeometry::new_rectangle(
-                            via_name.clone(),
-                            via_z_start.min(via_z_end),
-                            via_z_start.max(via_z_end),
-                            rectangle,
-                        );
-
-                        // Check if this VIA is selected (check both full name and base name)
-                        let is_selected = self.selected_layer.as_deref() == Some(&via_name)
-                            || self.selected_layer.as_deref() == Some(&via.name);
-                        geometry.set_selected(is_selected);
-
-                        geometries.push(geometry);
+                        geometries.extend(self.create_via_column_geometries(
+                            format!("{}_{}", via.name, i),
+                            &via.name,
+                            Pos2::new(screen_x, screen_center.y),
+                            Vec2::new(screen_width, screen_height),
+                            (via_z_start.min(via_z_end), via_z_start.max(via_z_end)),
+                            &via_array,
+                        ));
                     }
                 } else {
                     // Fallback: if no conductor layers found, use simple center alignment
-                    let screen_positions = [screen_center.x]; // Just center
-
-                    for (i, &screen_x) in screen_positions.iter().enumerate() {
-                        let via_screen_center = Pos2::new(screen_x, screen_center.y);
-                        let via_name = format!("{}_{}", via.name, i);
-                        let is_selected = self.selected_layer.as_deref() == Some(&via_name)
-                            || self.selected_layer.as_deref() == Some(&via.name);
-
-                        let via_color = if is_selected {
-                            Color32::from_rgb(255, 215, 0)
-                        } else {
-                            Color32::from_rgb(192, 192, 192)
-                        };
-                        let stroke = Stroke::new(
-                            if is_selected { 3.0 } else { 2.0 },
-                            if is_selected {
-                                Color32::YELLOW
-                            } else {
-                                Color32::DARK_GRAY
-                            },
-                        );
-
-                        let rectangle = RectangleShape::new(
-                            via_screen_center,
-                            screen_width,
-                            screen_height,
-                            via_color,
-                            stroke,
-                        );
-
-                        let mut geometry = LayerGeometry::new_rectangle(
-                            via_name.clone(),
-                            via_z_start.min(via_z_end),
-                            via_z_start.max(via_z_end),
-                            rectangle,
-                        );
-
-                        geometry.set_selected(is_selected);
-                        geometries.push(geometry);
-                    }
+                    geometries.extend(self.create_via_column_geometries(
+                        format!("{}_0", via.name),
+                        &via.name,
+                        screen_center,
+                        Vec2::new(screen_width, screen_height),
+                        (via_z_start.min(via_z_end), via_z_start.max(via_z_end)),
+                        &via_array,
+                    ));
                 }
             }
         }
@@ -825,6 +1365,76 @@ impl StackRenderer {
         geometries
     }
 
+    /// Create the shapes of one via column, drawn as a merged bar or as discrete cuts
+    fn create_via_column_geometries(
+        &self,
+        via_name: String,
+        base_name: &str,
+        screen_center: Pos2,
+        screen_size: Vec2,
+        z_range: (f32, f32),
+        via_array: &ViaArray,
+    ) -> Vec<LayerGeometry> {
+        // Check if this VIA is selected (check both full name and base name)
+        let is_selected = self.selected_layer.as_deref() == Some(&via_name)
+            || self.selected_layer.as_deref() == Some(base_name);
+
+        // Use different colors for selected vs normal VIAs
+        let via_color = if is_selected {
+            Color32::from_rgb(255, 215, 0) // Gold color for selected VIA
+        } else {
+            match self.color_scheme.get_alpha_override(base_name) {
+                Some(alpha) => self.color_scheme.apply_alpha(VIA_COLOR, alpha),
+                None => VIA_COLOR,
+            }
+        };
+        let stroke = Stroke::new(
+            if is_selected { 3.0 } else { 2.0 },
+            if is_selected {
+                Color32::YELLOW
+            } else {
+                Color32::DARK_GRAY
+            },
+        );
+
+        let span = via_array.span_x() as f32;
+        let cuts: Vec<(f32, f32)> =
+            if self.show_merged_vias || via_array.columns <= 1 || span <= 0.0 {
+                // A single bar covering the whole array
+                vec![(screen_center.x, screen_size.x)]
+            } else {
+                // Fit the array span into the column width and draw each cut separately
+                let scale = screen_size.x / span;
+                via_array
+                    .cut_offsets_x()
+                    .into_iter()
+                    .map(|offset| {
+                        (
+                            screen_center.x + offset as f32 * scale,
+                            via_array.cut_width as f32 * scale,
+                        )
+                    })
+                    .collect()
+            };
+
+        cuts.into_iter()
+            .map(|(x, width)| {
+                let rectangle = RectangleShape::new(
+                    Pos2::new(x, screen_center.y),
+                    width,
+                    screen_size.y,
+                    via_color,
+                    stroke,
+                );
+
+                let mut geometry =
+                    LayerGeometry::new_rectangle(via_name.clone(), z_range.0, z_range.1, rectangle);
+                geometry.set_selected(is_selected);
+                geometry
+            })
+            .collect()
+    }
+
     fn calculate_via_width(
         &self,
         via: &crate::data::ViaConnection,
@@ -833,24 +1443,23 @@ impl StackRenderer {
     ) -> f32 {
         // Via width should be the minimum of the narrowest edges of connected metal layers
         let mut min_metal_width = f32::INFINITY;
+        // World units per um of width, the smallest of the connected metals
+        let mut min_width_scale = f32::INFINITY;
         let mut found_metal = false;
 
-        // Check from_layer
-        if let Some(Layer::Conductor(conductor)) = stack.get_layer(&via.from_layer) {
-            let conductor_height =
-                scaler.get_exaggerated_thickness_for_layer(&Layer::Conductor(conductor.clone()));
-            let effective_width = self.calculate_metal_effective_width(conductor, conductor_height);
-            min_metal_width = min_metal_width.min(effective_width);
-            found_metal = true;
-        }
-
-        // Check to_layer
-        if let Some(Layer::Conductor(conductor)) = stack.get_layer(&via.to_layer) {
-            let conductor_height =
-                scaler.get_exaggerated_thickness_for_layer(&Layer::Conductor(conductor.clone()));
-            let effective_width = self.calculate_metal_effective_width(conductor, conductor_height);
-            min_metal_width = min_metal_width.min(effective_width);
-            found_metal = true;
+        for layer_name in [&via.from_layer, &via.to_layer] {
+            if let Some(layer @ Layer::Conductor(conductor)) = stack.get_layer(layer_name) {
+                let conductor_height = scaler.get_exaggerated_thickness_for_layer(layer);
+                let effective_width =
+                    self.calculate_metal_effective_width(conductor, conductor_height);
+                min_metal_width = min_metal_width.min(effective_width);
+                if let Some(scale) =
+                    Self::metal_width_scale(conductor, conductor_height, effective_width)
+                {
+                    min_width_scale = min_width_scale.min(scale);
+                }
+                found_metal = true;
+            }
         }
 
         // If no metal layers found, use a default based on via area
@@ -858,6 +1467,10 @@ impl StackRenderer {
             return via.get_via_width() as f32 * 10.0; // Scale up for visibility
         }
 
+        if self.area_accurate_vias && min_width_scale.is_finite() {
+            return via.get_via_width() as f32 * min_width_scale;
+        }
+
         // For contact vias (connecting to substrate), use a smaller width
         if via.is_contact_via() {
             min_metal_width * 0.8
@@ -873,26 +1486,57 @@ impl StackRenderer {
         conductor_height: f32,
     ) -> f32 {
         // Calculate the narrowest edge width of the metal trapezoid
-        // This matches the logic in ThreeColumnTrapezoidShape::from_conductor_layer
-
-        // Metal trapezoid dimensions: long_edge = height * 2.0, short_edge = height * 1.0
-        let long_edge_width = conductor_height * 2.0;
-        let short_edge_width = conductor_height * 1.0;
-
+        // This matches the logic in ThreeColumnTrapezoidShape::from_params
         let side_tangent = conductor.physical_props.side_tangent.unwrap_or(0.0) as f32;
-
-        let (top_width, bottom_width) = if side_tangent >= 0.0 {
-            // Top wider (negative trapezoid - like etched metal)
-            (long_edge_width, short_edge_width)
-        } else {
-            // Top narrower (positive trapezoid - like deposited metal)
-            (short_edge_width, long_edge_width)
-        };
+        let (top_width, bottom_width) = conductor_edge_widths(
+            conductor_height,
+            side_tangent,
+            self.get_sidewall_exaggeration(),
+        );
 
         // Return the narrowest edge width
         top_width.min(bottom_width)
     }
 
+    /// World units per um of width at which a conductor is drawn
+    ///
+    /// The narrowest edge of the trapezoid stands for WMIN when the techfile gives it;
+    /// otherwise widths are taken at the same scale as the drawn thickness.
+    fn metal_width_scale(
+        conductor: &crate::data::ConductorLayer,
+        conductor_height: f32,
+        effective_width: f32,
+    ) -> Option<f32> {
+        match conductor.physical_props.width_min {
+            Some(width_min) if width_min > 0.0 => Some(effective_width / width_min as f32),
+            _ if conductor.thickness > 0.0 => Some(conductor_height / conductor.thickness as f32),
+            _ => None,
+        }
+    }
+
+    /// Conductors whose drawn width misrepresents their WMIN by more than `max_factor`
+    pub fn check_width_fidelity(
+        &self,
+        stack: &ProcessStack,
+        max_factor: f32,
+    ) -> Vec<WidthDistortion> {
+        let scaler = self.get_current_scaler(stack);
+        let widths: Vec<(String, f32, f64)> = stack
+            .layers
+            .iter()
+            .filter_map(|layer| match layer {
+                Layer::Conductor(conductor) => {
+                    let width_min = conductor.physical_props.width_min?;
+                    let height = scaler.get_exaggerated_thickness_for_layer(layer);
+                    let drawn = self.calculate_metal_effective_width(conductor, height);
+                    Some((conductor.name.clone(), drawn, width_min))
+                }
+                Layer::Dielectric(_) => None,
+            })
+            .collect();
+        find_width_distortions(&widths, max_factor)
+    }
+
     pub fn calculate_ordered_layer_boundaries(
         &self,
         stack: &ProcessStack,
@@ -963,62 +1607,60 @@ impl StackRenderer {
         transform: &ViewTransform,
         viewport_rect: Rect,
     ) -> Vec<Shape> {
-        let mut shapes = Vec::new();
-
         // Create simple tick marks along the left edge
         let ruler_color = egui::Color32::WHITE;
         let ruler_x = viewport_rect.min.x + 1.0; // Just 1 pixel from left edge
 
-        // Get total stack height in world coordinates
-        let total_height = scaler.get_exaggerated_total_height(stack);
-
-        // Convert world coordinates to screen coordinates for ruler boundaries
-        let world_bottom = Pos2::new(0.0, 0.0); // Bottom of stack
-        let world_top = Pos2::new(0.0, -total_height); // Top of stack (negative Y)
-        let screen_bottom = transform.world_to_screen(world_bottom);
-        let screen_top = transform.world_to_screen(world_top);
+        self.create_ruler_ticks(stack, scaler)
+            .into_iter()
+            .map(|tick| {
+                let screen_y = transform.world_to_screen(Pos2::new(0.0, tick.world_y)).y;
+                let (length, width) = if tick.is_major {
+                    (15.0, 2.0)
+                } else {
+                    (8.0, 1.0)
+                };
+                Shape::line_segment(
+                    [
+                        Pos2::new(ruler_x, screen_y),
+                        Pos2::new(ruler_x + length, screen_y),
+                    ],
+                    egui::Stroke::new(width, ruler_color),
+                )
+            })
+            .collect()
+    }
 
-        // Calculate tick marks
-        let major_tick_interval = self.calculate_major_tick_interval(total_height);
+    /// Ruler ticks over the visible z range, valued in the direction of the view anchor
+    fn create_ruler_ticks(&self, stack: &ProcessStack, scaler: &ThicknessScaler) -> Vec<RulerTick> {
+        // Get the ruler extent in world coordinates, limited to the cutline if one is set
+        let stack_top_z = self.get_stack_top_z(stack, scaler);
+        let (z_min, z_max) = self
+            .get_visible_z_range(stack, scaler)
+            .unwrap_or((0.0, stack_top_z));
+        let origin_y = self.get_origin_y(stack, scaler);
+
+        let anchor = self.view_anchor;
+        let value_a = anchor.ruler_value(origin_y - z_min);
+        let value_b = anchor.ruler_value(origin_y - z_max);
+        let (low, high) = (value_a.min(value_b), value_a.max(value_b));
+
+        // Calculate tick marks, every fifth minor tick is a major one
+        let major_tick_interval = self.calculate_major_tick_interval(high - low);
         let minor_tick_interval = major_tick_interval / 5.0;
-
-        // Draw tick marks
-        let mut current_world_z = 0.0;
-        while current_world_z <= total_height {
-            let world_pos = Pos2::new(0.0, -current_world_z);
-            let screen_pos = transform.world_to_screen(world_pos);
-
-            // Check if this position is visible
-            if screen_pos.y >= screen_top.y && screen_pos.y <= screen_bottom.y {
-                let is_major_tick = (current_world_z / major_tick_interval).round()
-                    * major_tick_interval
-                    == current_world_z;
-
-                if is_major_tick {
-                    // Major tick mark (longer line)
-                    let tick_start = Pos2::new(ruler_x, screen_pos.y);
-                    let tick_end = Pos2::new(ruler_x + 15.0, screen_pos.y);
-                    shapes.push(Shape::line_segment(
-                        [tick_start, tick_end],
-                        egui::Stroke::new(2.0, ruler_color),
-                    ));
-                } else if (current_world_z / minor_tick_interval).round() * minor_tick_interval
-                    == current_world_z
-                {
-                    // Minor tick mark (shorter line)
-                    let tick_start = Pos2::new(ruler_x, screen_pos.y);
-                    let tick_end = Pos2::new(ruler_x + 8.0, screen_pos.y);
-                    shapes.push(Shape::line_segment(
-                        [tick_start, tick_end],
-                        egui::Stroke::new(1.0, ruler_color),
-                    ));
+        let first = (low / minor_tick_interval - 1e-3).ceil() as i64;
+        let last = (high / minor_tick_interval + 1e-3).floor() as i64;
+
+        (first..=last)
+            .map(|index| {
+                let value = index as f32 * minor_tick_interval;
+                RulerTick {
+                    world_y: anchor.ruler_world_y(value),
+                    value,
+                    is_major: index % 5 == 0,
                 }
-            }
-
-            current_world_z += minor_tick_interval;
-        }
-
-        shapes
+            })
+            .collect()
     }
 
     // Helper function to calculate appropriate tick interval
@@ -1056,64 +1698,56 @@ impl StackRenderer {
         // Create simple tick marks along the left edge
         let ruler_color = egui::Color32::WHITE;
         let ruler_x = viewport_rect.min.x + 1.0; // Just 1 pixel from left edge
+        let font_id = FontId::monospace(10.0);
+
+        let ticks = self.create_ruler_ticks(stack, &scaler);
+        for tick in &ticks {
+            let screen_y = transform.world_to_screen(Pos2::new(0.0, tick.world_y)).y;
+            let tick_start = Pos2::new(ruler_x, screen_y);
+
+            if tick.is_major {
+                // Major tick mark (longer line)
+                let tick_end = Pos2::new(ruler_x + 15.0, screen_y);
+                painter.line_segment([tick_start, tick_end], egui::Stroke::new(2.0, ruler_color));
+
+                // Add text label for major ticks
+                let label = format!("{:.1}μm", tick.value);
+                let text_pos = Pos2::new(ruler_x + 20.0, screen_y);
+
+                // Add small background for text readability
+                painter.rect_filled(
+                    Rect::from_center_size(text_pos, Vec2::new(30.0, 14.0)),
+                    2.0,
+                    Color32::from_black_alpha(120),
+                );
 
-        // Get total stack height in world coordinates
-        let total_height = scaler.get_exaggerated_total_height(stack);
-
-        // Convert world coordinates to screen coordinates for ruler boundaries
-        let world_bottom = Pos2::new(0.0, 0.0); // Bottom of stack
-        let world_top = Pos2::new(0.0, -total_height); // Top of stack (negative Y)
-        let screen_bottom = transform.world_to_screen(world_bottom);
-        let screen_top = transform.world_to_screen(world_top);
-
-        // Calculate tick marks
-        let major_tick_interval = self.calculate_major_tick_interval(total_height);
-        let minor_tick_interval = major_tick_interval / 5.0;
-
-        // Draw tick marks with text labels
-        let mut current_world_z = 0.0;
-        while current_world_z <= total_height {
-            let world_pos = Pos2::new(0.0, -current_world_z);
-            let screen_pos = transform.world_to_screen(world_pos);
-
-            // Check if this position is visible
-            if screen_pos.y >= screen_top.y && screen_pos.y <= screen_bottom.y {
-                let is_major_tick = (current_world_z / major_tick_interval).round()
-                    * major_tick_interval
-                    == current_world_z;
-
-                if is_major_tick {
-                    // Major tick mark (longer line)
-                    let tick_start = Pos2::new(ruler_x, screen_pos.y);
-                    let tick_end = Pos2::new(ruler_x + 15.0, screen_pos.y);
-                    painter
-                        .line_segment([tick_start, tick_end], egui::Stroke::new(2.0, ruler_color));
-
-                    // Add text label for major ticks
-                    let label = format!("{current_world_z:.1}μm");
-                    let text_pos = Pos2::new(ruler_x + 20.0, screen_pos.y);
-                    let font_id = FontId::monospace(10.0);
-
-                    // Add small background for text readability
-                    painter.rect_filled(
-                        Rect::from_center_size(text_pos, Vec2::new(30.0, 14.0)),
-                        2.0,
-                        Color32::from_black_alpha(120),
-                    );
-
-                    painter.text(text_pos, Align2::LEFT_CENTER, label, font_id, ruler_color);
-                } else if (current_world_z / minor_tick_interval).round() * minor_tick_interval
-                    == current_world_z
-                {
-                    // Minor tick mark (shorter line)
-                    let tick_start = Pos2::new(ruler_x, screen_pos.y);
-                    let tick_end = Pos2::new(ruler_x + 8.0, screen_pos.y);
-                    painter
-                        .line_segment([tick_start, tick_end], egui::Stroke::new(1.0, ruler_color));
-                }
+                painter.text(
+                    text_pos,
+                    Align2::LEFT_CENTER,
+                    label,
+                    font_id.clone(),
+                    ruler_color,
+                );
+            } else {
+                // Minor tick mark (shorter line)
+                let tick_end = Pos2::new(ruler_x + 8.0, screen_y);
+                painter.line_segment([tick_start, tick_end], egui::Stroke::new(1.0, ruler_color));
             }
+        }
 
-            current_world_z += minor_tick_interval;
+        // Caption above the topmost tick, showing which way the values grow
+        if let Some(top_y) = ticks
+            .iter()
+            .map(|tick| transform.world_to_screen(Pos2::new(0.0, tick.world_y)).y)
+            .reduce(f32::min)
+        {
+            painter.text(
+                Pos2::new(ruler_x + 2.0, top_y - 8.0),
+                Align2::LEFT_BOTTOM,
+                self.view_anchor.ruler_caption(),
+                font_id,
+                ruler_color,
+            );
         }
     }
 
@@ -1133,6 +1767,169 @@ impl StackRenderer {
         self.show_schematic_mode = show;
     }
 
+    pub fn set_show_merged_vias(&mut self, show: bool) {
+        self.show_merged_vias = show;
+    }
+
+    pub fn set_area_accurate_vias(&mut self, area_accurate: bool) {
+        self.area_accurate_vias = area_accurate;
+    }
+
+    pub fn set_show_isometric_mode(&mut self, show: bool) {
+        self.show_isometric_mode = show;
+    }
+
+    pub fn set_show_hatching(&mut self, show: bool) {
+        self.show_hatching = show;
+    }
+
+    pub fn set_show_conformal_liners(&mut self, show: bool) {
+        self.show_conformal_liners = show;
+    }
+
+    pub fn set_show_outline_only(&mut self, show: bool) {
+        self.show_outline_only = show;
+    }
+
+    pub fn set_true_sidewall_angles(&mut self, true_angles: bool) {
+        self.true_sidewall_angles = true_angles;
+    }
+
+    pub fn set_sidewall_exaggeration(&mut self, exaggeration: f32) {
+        self.sidewall_exaggeration = exaggeration.clamp(
+            *SIDEWALL_EXAGGERATION_RANGE.start(),
+            *SIDEWALL_EXAGGERATION_RANGE.end(),
+        );
+    }
+
+    /// Factor applied to SIDE_TANGENT when drawing, `None` while sidewalls are stylized
+    pub fn get_sidewall_exaggeration(&self) -> Option<f32> {
+        self.true_sidewall_angles
+            .then_some(self.sidewall_exaggeration)
+    }
+
+    /// Replace the per-layer color overrides, keyed by layer name
+    pub fn set_layer_color_overrides(&mut self, overrides: HashMap<String, Color32>) {
+        self.color_scheme.layer_overrides = overrides;
+    }
+
+    pub fn get_layer_color_overrides(&self) -> &HashMap<String, Color32> {
+        &self.color_scheme.layer_overrides
+    }
+
+    pub fn set_layer_class_overrides(&mut self, overrides: HashMap<String, LayerClass>) {
+        self.color_scheme.class_overrides = overrides;
+    }
+
+    /// Replace the per-layer and per-via fill alphas, keyed by name
+    pub fn set_alpha_overrides(&mut self, overrides: HashMap<String, u8>) {
+        self.color_scheme.alpha_overrides = overrides;
+    }
+
+    /// Use the class colors and hatches of `theme`, or the built-in ones for none
+    pub fn set_theme(&mut self, theme: Option<Theme>) {
+        self.color_scheme.theme = theme;
+    }
+
+    pub fn get_color_scheme(&self) -> &ColorScheme {
+        &self.color_scheme
+    }
+
+    /// Label conductors and vias with these names instead of their ITF names
+    pub fn set_display_names(&mut self, display_names: HashMap<String, String>) {
+        self.display_names = display_names;
+    }
+
+    /// Label drawn for a conductor or via
+    pub fn get_label<'a>(&'a self, name: &'a str) -> &'a str {
+        self.display_names
+            .get(name)
+            .map(String::as_str)
+            .unwrap_or(name)
+    }
+
+    /// Restrict rendering, auto-fit and the ruler to the layers from `bottom` to `top`
+    pub fn set_visible_layer_range(&mut self, range: Option<(String, String)>) {
+        self.visible_layer_range = range;
+    }
+
+    pub fn get_visible_layer_range(&self) -> Option<&(String, String)> {
+        self.visible_layer_range.as_ref()
+    }
+
+    /// End of the stack placed at world y = 0
+    pub fn set_view_anchor(&mut self, anchor: ViewAnchor) {
+        self.view_anchor = anchor;
+    }
+
+    pub fn get_view_anchor(&self) -> ViewAnchor {
+        self.view_anchor
+    }
+
+    /// World y of a layer's drawn bottom and top edges, for the current mode and anchor
+    pub fn get_layer_world_y_range(&self, stack: &ProcessStack, name: &str) -> Option<(f32, f32)> {
+        let scaler = self.get_current_scaler(stack);
+        let &(z_bottom, z_top) = self
+            .calculate_ordered_layer_boundaries(stack, &scaler)
+            .get(name)?;
+        let origin_y = self.get_origin_y(stack, &scaler);
+        Some((origin_y - z_bottom, origin_y - z_top))
+    }
+
+    /// Height of the top of the drawn stack; conductors sit inside their dielectrics
+    fn get_stack_top_z(&self, stack: &ProcessStack, scaler: &ThicknessScaler) -> f32 {
+        self.calculate_ordered_layer_boundaries(stack, scaler)
+            .values()
+            .map(|&(_, z_top)| z_top)
+            .fold(0.0, f32::max)
+    }
+
+    /// World y of z = 0 for the current view anchor
+    fn get_origin_y(&self, stack: &ProcessStack, scaler: &ThicknessScaler) -> f32 {
+        match self.view_anchor {
+            ViewAnchor::Substrate => 0.0,
+            ViewAnchor::ChipTop => self.get_stack_top_z(stack, scaler),
+        }
+    }
+
+    /// World z interval covered by the cutline, or `None` when the whole stack is shown
+    pub fn get_visible_z_range(
+        &self,
+        stack: &ProcessStack,
+        scaler: &ThicknessScaler,
+    ) -> Option<(f32, f32)> {
+        let (bottom_layer, top_layer) = self.visible_layer_range.as_ref()?;
+        let boundaries = self.calculate_ordered_layer_boundaries(stack, scaler);
+
+        let &(bottom_z_bottom, bottom_z_top) = boundaries.get(bottom_layer)?;
+        let &(top_z_bottom, top_z_top) = boundaries.get(top_layer)?;
+
+        Some((
+            bottom_z_bottom.min(top_z_bottom),
+            bottom_z_top.max(top_z_top),
+        ))
+    }
+
+    /// Drop geometries that do not overlap the cutline z interval
+    fn filter_to_visible_range(
+        &self,
+        stack: &ProcessStack,
+        scaler: &ThicknessScaler,
+        geometries: Vec<LayerGeometry>,
+    ) -> Vec<LayerGeometry> {
+        let Some((z_min, z_max)) = self.get_visible_z_range(stack, scaler) else {
+            return geometries;
+        };
+
+        let epsilon = (z_max - z_min).abs() * 1e-4;
+        geometries
+            .into_iter()
+            .filter(|geometry| {
+                geometry.z_top > z_min + epsilon && geometry.z_bottom < z_max - epsilon
+            })
+            .collect()
+    }
+
     /// Get the appropriate scaler based on current mode
     pub fn get_current_scaler(&self, stack: &ProcessStack) -> ThicknessScaler {
         if self.show_schematic_mode {
@@ -1142,86 +1939,107 @@ impl StackRenderer {
         }
     }
 
-    pub fn set_selected_layer(&mut self, layer_name: Option<String>) {
-        self.selected_layer = layer_name;
+    /// Vertical exaggeration of the drawing of `stack`, none when thicknesses are to scale
+    pub fn get_vertical_exaggeration(&self, stack: &ProcessStack) -> Option<f32> {
+        let exaggeration = self
+            .get_current_scaler(stack)
+            .get_vertical_exaggeration(stack);
+        (exaggeration > 1.001).then_some(exaggeration)
     }
 
-    pub fn get_selected_layer(&self) -> Option<&String> {
-        self.selected_layer.as_ref()
+    pub fn set_lod_policy(&mut self, policy: LodPolicy) {
+        self.lod_policy = policy;
     }
 
-    pub fn hit_test(
-        &self,
-        stack: &ProcessStack,
-        transform: &ViewTransform,
-        viewport_rect: Rect,
-        point: Pos2,
-    ) -> Option<String> {
-        // Use the same scaler configuration as rendering to ensure coordinate consistency
+    pub fn get_lod_policy(&self) -> LodPolicy {
+        self.lod_policy
+    }
+
+    pub fn set_guides(&mut self, guides: Vec<Guide>) {
+        self.guides = guides;
+    }
+
+    pub fn get_guides(&self) -> &[Guide] {
+        &self.guides
+    }
+
+    pub fn set_structures(&mut self, structures: Vec<StructureAnnotation>) {
+        self.structures = structures;
+    }
+
+    pub fn get_structures(&self) -> &[StructureAnnotation] {
+        &self.structures
+    }
+
+    /// Outline `stack` over the drawn one, or stop for none
+    pub fn set_ghost_stack(&mut self, stack: Option<ProcessStack>) {
+        self.ghost_stack = stack;
+    }
+
+    pub fn get_ghost_stack(&self) -> Option<&ProcessStack> {
+        self.ghost_stack.as_ref()
+    }
+
+    /// World y of a height above the bottom of the stack, for the current view anchor
+    pub fn guide_world_y(&self, stack: &ProcessStack, z: f32) -> f32 {
+        self.get_origin_y(stack, &self.get_current_scaler(stack)) - z
+    }
+
+    /// World y of the layer boundary nearest to `world_y` within `tolerance`, else `world_y`
+    ///
+    /// Boundaries are the bottoms and tops of the drawn layers, so guides and the status bar
+    /// height readout can land exactly on a layer edge.
+    pub fn snap_to_boundary(&self, stack: &ProcessStack, world_y: f32, tolerance: f32) -> f32 {
         let scaler = self.get_current_scaler(stack);
-        let layer_geometries =
-            self.create_layer_geometries_ordered(stack, &scaler, transform, viewport_rect);
+        let origin_y = self.get_origin_y(stack, &scaler);
+        self.calculate_ordered_layer_boundaries(stack, &scaler)
+            .values()
+            .flat_map(|&(z_bottom, z_top)| [origin_y - z_bottom, origin_y - z_top])
+            .filter(|boundary_y| (boundary_y - world_y).abs() <= tolerance)
+            .min_by(|a, b| (a - world_y).abs().total_cmp(&(b - world_y).abs()))
+            .unwrap_or(world_y)
+    }
 
-        // Also get VIA geometries for hit testing (VIAs have highest z-order)
-        let via_geometries =
-            self.create_via_geometries_with_scaler(stack, &scaler, transform, viewport_rect);
-
-        // Test VIAs first (highest z-index, rendered on top of everything)
-        for geometry in via_geometries.iter().rev() {
-            if geometry.contains_point(point) {
-                // For VIA geometries, extract the base name (remove the "_N" suffix)
-                // VIA geometry names are in format "via_name_0", "via_name_1", etc.
-                let base_name = if let Some(underscore_pos) = geometry.layer_name.rfind('_') {
-                    // Check if the part after the last underscore is a number
-                    let suffix = &geometry.layer_name[underscore_pos + 1..];
-                    if suffix.chars().all(|c| c.is_ascii_digit()) {
-                        // This is a VIA with index suffix, return the base name
-                        geometry.layer_name[..underscore_pos].to_string()
-                    } else {
-                        // Not a VIA index, return full name
-                        geometry.layer_name.clone()
-                    }
-                } else {
-                    // No underscore, return full name
-                    geometry.layer_name.clone()
-                };
-                return Some(base_name);
-            }
-        }
+    /// Height above the bottom of the stack at world y, the inverse of `guide_world_y`
+    pub fn guide_z(&self, stack: &ProcessStack, world_y: f32) -> f32 {
+        self.get_origin_y(stack, &self.get_current_scaler(stack)) - world_y
+    }
 
-        // Separate layer geometries by type for proper z-ordering hit testing
-        let mut dielectric_geometries = Vec::new();
-        let mut conductor_geometries = Vec::new();
+    pub fn set_selected_layer(&mut self, layer_name: Option<String>) {
+        self.selected_layer = layer_name;
+    }
 
-        for geometry in &layer_geometries {
-            // Check if this is a conductor layer by looking at the shape type
-            match &geometry.shape {
-                LayerShape::ThreeColumnTrapezoid(_) => {
-                    // All conductor layers use ThreeColumnTrapezoid
-                    conductor_geometries.push(geometry);
-                }
-                _ => {
-                    // All other shapes are dielectric layers
-                    dielectric_geometries.push(geometry);
-                }
-            }
-        }
+    pub fn get_selected_layer(&self) -> Option<&String> {
+        self.selected_layer.as_ref()
+    }
 
-        // Test conductor layers second (medium z-index)
-        for geometry in conductor_geometries.iter().rev() {
-            if geometry.contains_point(point) {
-                return Some(geometry.layer_name.clone());
-            }
-        }
+    pub fn set_highlighted_layers(&mut self, layer_names: Vec<String>) {
+        self.highlighted_layers = layer_names;
+    }
 
-        // Finally test dielectric layers (lowest z-index, rendered below)
-        for geometry in dielectric_geometries.iter().rev() {
-            if geometry.contains_point(point) {
-                return Some(geometry.layer_name.clone());
-            }
-        }
+    pub fn get_highlighted_layers(&self) -> &[String] {
+        &self.highlighted_layers
+    }
+
+    /// World-space hit index of the layout, valid until the stack or layout options change
+    pub fn build_hit_index(&self, stack: &ProcessStack, viewport_width: f32) -> HitIndex {
+        HitIndex::new(&self.layout_stack(stack, viewport_width))
+    }
 
-        None
+    /// Topmost layer at a screen position; vias win over conductors over dielectrics
+    ///
+    /// Builds a fresh `HitIndex`; callers testing many points should build one with
+    /// `build_hit_index` and query it in world coordinates instead.
+    pub fn hit_test(
+        &self,
+        stack: &ProcessStack,
+        transform: &ViewTransform,
+        viewport_rect: Rect,
+        point: Pos2,
+    ) -> Option<String> {
+        self.build_hit_index(stack, viewport_rect.width())
+            .hit(transform.screen_to_world(point))
+            .map(str::to_string)
     }
 
     pub fn get_stack_bounds(&self, stack: &ProcessStack) -> Rect {
@@ -1236,16 +2054,55 @@ impl StackRenderer {
             self.create_normal_scaler(stack)
         };
 
-        let total_height = scaler.get_exaggerated_total_height(stack);
+        let regions_bottom = self
+            .get_er_region_bands(stack, &scaler)
+            .last()
+            .map_or(0.0, |&(_, z_bottom, _)| z_bottom);
+        let (z_min, z_max) = self
+            .get_visible_z_range(stack, &scaler)
+            .unwrap_or((regions_bottom, scaler.get_exaggerated_total_height(stack)));
         let half_width = self.layer_width * 0.5;
+        let origin_y = self.get_origin_y(stack, &scaler);
 
         Rect::from_min_max(
-            Pos2::new(-half_width, -total_height),
-            Pos2::new(half_width, 0.0),
+            Pos2::new(-half_width, origin_y - z_max),
+            Pos2::new(half_width, origin_y - z_min),
         )
     }
 
     pub fn auto_fit(&self, stack: &ProcessStack, transform: &mut ViewTransform) {
+        self.fit_to_view(stack, transform, FitAxis::Both);
+    }
+
+    /// Scale at which [`auto_fit`](Self::auto_fit) shows the stack in a viewport of
+    /// `viewport_size`
+    pub fn fit_scale(&self, stack: &ProcessStack, viewport_size: Vec2) -> Option<f32> {
+        let bounds = self.get_stack_bounds(stack);
+        if bounds.width() > 0.0 && bounds.height() > 0.0 {
+            let (scale_x, scale_y) = Self::fit_scales(bounds, viewport_size);
+            Some(scale_x.min(scale_y).max(0.01))
+        } else {
+            None
+        }
+    }
+
+    /// Scales fitting `bounds` horizontally and vertically, leaving room for the ruler
+    fn fit_scales(bounds: Rect, viewport_size: Vec2) -> (f32, f32) {
+        // Reserve space for the ruler on the left (about 30 pixels)
+        let ruler_space = 30.0;
+        let margin = 20.0; // Fixed margin in pixels
+        (
+            (viewport_size.x - ruler_space - margin * 2.0) / bounds.width(),
+            (viewport_size.y - margin * 2.0) / bounds.height(),
+        )
+    }
+
+    /// Zoom so the stack fits along `axis`
+    ///
+    /// Fitting a single axis centers the stack along it and keeps the point at the
+    /// center of the other axis in place, so a tall stack can be read at full width and
+    /// scrolled through.
+    pub fn fit_to_view(&self, stack: &ProcessStack, transform: &mut ViewTransform, axis: FitAxis) {
         let bounds = self.get_stack_bounds(stack);
         if bounds.width() > 0.0 && bounds.height() > 0.0 {
             // Reserve space for the ruler on the left (about 30 pixels)
@@ -1257,21 +2114,37 @@ impl StackRenderer {
             let effective_viewport_height = viewport_size.y;
 
             // Calculate scale to fit both width and height with some margin
-            let margin = 20.0; // Fixed margin in pixels
-            let scale_x = (effective_viewport_width - margin * 2.0) / bounds.width();
-            let scale_y = (effective_viewport_height - margin * 2.0) / bounds.height();
+            let (scale_x, scale_y) = Self::fit_scales(bounds, viewport_size);
 
             // Use the smaller scale to ensure everything fits
-            transform.scale = scale_x.min(scale_y).max(0.01);
+            let old_scale = transform.scale;
+            transform.scale = match axis {
+                FitAxis::Both => scale_x.min(scale_y),
+                FitAxis::Width => scale_x,
+                FitAxis::Height => scale_y,
+            }
+            .max(0.01);
 
             // Center the stack in the effective viewport area
             let bounds_center = bounds.center();
             let viewport_center_x = ruler_space + effective_viewport_width * 0.5;
             let viewport_center_y = effective_viewport_height * 0.5;
 
+            // World coordinate currently at the center of an axis that is not fitted
+            let kept_center =
+                |offset: f32, viewport_center: f32| (viewport_center - offset) / old_scale;
+            let center_x = match axis {
+                FitAxis::Height => kept_center(transform.offset.x, viewport_center_x),
+                _ => bounds_center.x,
+            };
+            let center_y = match axis {
+                FitAxis::Width => kept_center(transform.offset.y, viewport_center_y),
+                _ => bounds_center.y,
+            };
+
             transform.offset = Vec2::new(
-                viewport_center_x - bounds_center.x * transform.scale,
-                viewport_center_y - bounds_center.y * transform.scale,
+                viewport_center_x - center_x * transform.scale,
+                viewport_center_y - center_y * transform.scale,
             );
         }
     }
@@ -1338,21 +2211,52 @@ impl Default for StackRenderer {
 impl Clone for StackRenderer {
     fn clone(&self) -> Self {
         Self {
-            color_scheme: ColorScheme::new(), // Create new color scheme
+            color_scheme: self.color_scheme.clone(),
             layer_width: self.layer_width,
             show_dimensions: self.show_dimensions,
             show_layer_names: self.show_layer_names,
             show_schematic_mode: self.show_schematic_mode,
+            show_merged_vias: self.show_merged_vias,
+            area_accurate_vias: self.area_accurate_vias,
+            show_isometric_mode: self.show_isometric_mode,
+            show_hatching: self.show_hatching,
+            show_conformal_liners: self.show_conformal_liners,
+            true_sidewall_angles: self.true_sidewall_angles,
+            sidewall_exaggeration: self.sidewall_exaggeration,
+            show_outline_only: self.show_outline_only,
+            visible_layer_range: self.visible_layer_range.clone(),
+            view_anchor: self.view_anchor,
             selected_layer: self.selected_layer.clone(),
+            highlighted_layers: self.highlighted_layers.clone(),
+            guides: self.guides.clone(),
+            structures: self.structures.clone(),
+            ghost_stack: self.ghost_stack.clone(),
+            display_names: self.display_names.clone(),
+            lod_policy: self.lod_policy,
             thickness_scaler: self.thickness_scaler.clone(),
         }
     }
 }
 
+/// Remove the geometries of marker layers from `layout` and return them
+///
+/// Markers have no height, they are drawn as boundary lines instead of filled shapes.
+fn take_markers(stack: &ProcessStack, layout: &mut StackLayout) -> Vec<LayerGeometry> {
+    let (markers, layers) = std::mem::take(&mut layout.layers)
+        .into_iter()
+        .partition(|geometry| {
+            stack
+                .get_layer(&geometry.layer_name)
+                .is_some_and(Layer::is_marker)
+        });
+    layout.layers = layers;
+    markers
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+    use crate::data::{ConductorLayer, DielectricLayer, ErRegionKind, TechnologyInfo};
     use egui::Vec2;
 
     fn create_test_stack() -> ProcessStack {
@@ -1394,6 +2298,9 @@ mod tests {
 
         renderer.set_selected_layer(None);
         assert_eq!(renderer.get_selected_layer(), None);
+
+        renderer.set_highlighted_layers(vec!["imd1".to_string()]);
+        assert_eq!(renderer.clone().get_highlighted_layers(), ["imd1"]);
     }
 
     #[test]
@@ -1406,6 +2313,10 @@ mod tests {
         renderer.set_show_layer_names(false);
         assert!(!renderer.show_layer_names);
 
+        renderer.set_display_names(HashMap::from([("M1".to_string(), "Metal 1".to_string())]));
+        assert_eq!(renderer.get_label("M1"), "Metal 1");
+        assert_eq!(renderer.clone().get_label("VIA1"), "VIA1");
+
         renderer.set_layer_width(300.0);
         assert_eq!(renderer.layer_width, 300.0);
 
@@ -1441,6 +2352,45 @@ mod tests {
         assert_eq!(bounds.max.y, 0.0);
     }
 
+    #[test]
+    fn test_er_region_bands() {
+        let mut renderer = StackRenderer::new();
+        let mut stack = create_test_stack();
+        stack.add_er_region(ErRegion::new(
+            ErRegionKind::Substrate,
+            "psub".to_string(),
+            11.9,
+        ));
+        stack.add_er_region(ErRegion::new(ErRegionKind::Field, "sti".to_string(), 3.9));
+
+        let scaler = renderer.get_current_scaler(&stack);
+        let height = scaler.get_exaggerated_total_height(&stack);
+        let band = height * ER_REGION_BAND_FRACTION;
+        let bands = renderer.get_er_region_bands(&stack, &scaler);
+        assert_eq!(bands[0].0.name, "sti");
+        assert_eq!((bands[0].1, bands[0].2), (-band, 0.0));
+        assert_eq!(bands[1].0.name, "psub");
+        assert_eq!((bands[1].1, bands[1].2), (-2.0 * band, -band));
+
+        // The bands hang below the substrate end of the stack
+        let bounds = renderer.get_stack_bounds(&stack);
+        assert_eq!(bounds.min.y, -height);
+        assert!((bounds.max.y - 2.0 * band).abs() < 1e-4);
+
+        let transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        let substrate = renderer
+            .get_color_scheme()
+            .get_er_region_color(ErRegionKind::Substrate);
+        let shapes = renderer.render_stack(&stack, &transform, viewport_rect);
+        assert!(shapes
+            .iter()
+            .any(|shape| matches!(shape, Shape::Rect(rect) if rect.fill == substrate)));
+
+        renderer.set_visible_layer_range(Some(("oxide1".to_string(), "oxide2".to_string())));
+        assert!(renderer.get_er_region_bands(&stack, &scaler).is_empty());
+    }
+
     #[test]
     fn test_layer_geometry_creation() {
         let renderer = StackRenderer::new();
@@ -1489,6 +2439,220 @@ mod tests {
         assert!(shapes.len() >= shapes_minimal.len());
     }
 
+    #[test]
+    fn test_marker_layers() {
+        let mut stack = create_test_stack();
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "marker".to_string(),
+            0.0,
+            1.0,
+        )));
+        let mut renderer = StackRenderer::new();
+        renderer.set_show_dimensions(false);
+        let mut transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        transform.scale = 40.0;
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+
+        // The marker is a dashed line instead of a zero-height rectangle
+        let (mut layout, _) = renderer.screen_layout(&stack, &transform, viewport_rect);
+        let markers = take_markers(&stack, &mut layout);
+        assert_eq!(markers.len(), 1);
+        assert!(layout
+            .layers
+            .iter()
+            .all(|geometry| geometry.layer_name != "marker"));
+        let dashes = renderer.create_marker_shapes(&stack, &markers);
+        assert!(dashes.len() > 1);
+        assert!(dashes
+            .iter()
+            .all(|shape| matches!(shape, Shape::LineSegment { .. })));
+        assert_eq!(
+            renderer
+                .get_current_scaler(&stack)
+                .get_exaggerated_total_height(&stack),
+            3.0
+        );
+    }
+
+    #[test]
+    fn test_passivation_opening() {
+        let itf = "TECHNOLOGY = pad\n\
+                   DIELECTRIC pass2 {THICKNESS=0.6 ER=7.0}\n\
+                   DIELECTRIC pass1 {THICKNESS=2.0 ER=4.1}\n\
+                   CONDUCTOR ap {THICKNESS=1.5 RPSQ=0.01 EXPOSED=YES}\n\
+                   DIELECTRIC imd {THICKNESS=1.0 ER=4.0}\n\
+                   CONDUCTOR metal1 {THICKNESS=0.3 RPSQ=0.1}\n\
+                   DIELECTRIC ild {THICKNESS=1.0 ER=4.0}\n";
+        let stack = crate::parser::parse_itf_file(itf).unwrap();
+        let layout = StackRenderer::new().layout_stack(&stack, 800.0);
+        let geometry = |name: &str| {
+            layout
+                .layers
+                .iter()
+                .find(|geometry| geometry.layer_name == name)
+                .unwrap()
+        };
+        let pad = geometry("ap").get_bounds();
+        let above_pad = Pos2::new(pad.center().x, pad.top() - 0.1);
+
+        // pass1 embeds the pad and keeps its part beside and below the pad top
+        let LayerShape::MultiTrapezoid(pass1) = &geometry("pass1").shape else {
+            panic!("pass1 is not opened");
+        };
+        assert_eq!(pass1.trapezoids.len(), 3);
+        assert!(!geometry("pass1").contains_point(above_pad));
+        assert!(geometry("pass1").contains_point(Pos2::new(pad.left() - 1.0, pad.top() - 0.1)));
+        let LayerShape::MultiTrapezoid(pass2) = &geometry("pass2").shape else {
+            panic!("pass2 is not opened");
+        };
+        assert_eq!(pass2.trapezoids.len(), 2);
+        assert!(matches!(geometry("imd").shape, LayerShape::Rectangle(_)));
+
+        // Without EXPOSED the passivation stays closed
+        let closed = crate::parser::parse_itf_file(&itf.replace(" EXPOSED=YES", "")).unwrap();
+        let layout = StackRenderer::new().layout_stack(&closed, 800.0);
+        assert!(layout
+            .dielectrics()
+            .all(|geometry| matches!(geometry.shape, LayerShape::Rectangle(_))));
+    }
+
+    #[test]
+    fn test_dielectric_hatching() {
+        let mut stack = create_test_stack();
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "substrate".to_string(),
+            2.0,
+            11.9,
+        )));
+        let mut renderer = StackRenderer::new();
+        renderer.set_show_dimensions(false);
+        let mut transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        transform.scale = 40.0;
+        transform.offset = Vec2::new(0.0, -150.0);
+
+        let layout = renderer.layout_stack(&stack, viewport_rect.width());
+        let hatch_of = |name: &str| {
+            layout
+                .layers
+                .iter()
+                .find(|g| g.layer_name == name)
+                .map(|g| g.hatch)
+        };
+        assert_eq!(hatch_of("substrate"), Some(HatchPattern::CrossHatch));
+        assert_eq!(hatch_of("oxide1"), Some(HatchPattern::None));
+        assert_eq!(hatch_of("metal1"), Some(HatchPattern::None));
+
+        // Hatching only adds shapes on top of the flat fills when enabled
+        let flat = renderer.render_stack(&stack, &transform, viewport_rect);
+        renderer.set_show_hatching(true);
+        let hatched = renderer.render_stack(&stack, &transform, viewport_rect);
+        assert!(hatched.len() > flat.len());
+        assert!(hatched
+            .iter()
+            .filter(|shape| matches!(shape, Shape::LineSegment { .. }))
+            .all(|shape| viewport_rect.contains_rect(shape.visual_bounding_rect().shrink(1.0))));
+    }
+
+    #[test]
+    fn test_conformal_liners() {
+        let mut stack = create_test_stack();
+        if let Some(Layer::Dielectric(oxide)) = stack.layers.first_mut() {
+            oxide.sw_t = Some(0.05);
+            oxide.tw_t = Some(0.1);
+        }
+        let mut renderer = StackRenderer::new();
+        renderer.set_show_dimensions(false);
+        let transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+
+        // One outline per trapezoid column of the covered conductor
+        let plain = renderer.render_stack(&stack, &transform, viewport_rect);
+        renderer.set_show_conformal_liners(true);
+        let lined = renderer.render_stack(&stack, &transform, viewport_rect);
+        assert_eq!(lined.len(), plain.len() + 3);
+        assert!(renderer.clone().show_conformal_liners);
+    }
+
+    #[test]
+    fn test_outline_only() {
+        let stack = create_test_stack();
+        let mut renderer = StackRenderer::new();
+        renderer.set_show_dimensions(false);
+        renderer.set_show_hatching(true);
+        let transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        let filled = |shape: &Shape| match shape {
+            Shape::Path(path) => path.fill != Color32::TRANSPARENT,
+            Shape::Rect(rect) => rect.fill != Color32::TRANSPARENT,
+            _ => false,
+        };
+
+        assert!(renderer
+            .render_stack(&stack, &transform, viewport_rect)
+            .iter()
+            .any(filled));
+
+        renderer.set_show_outline_only(true);
+        let outlined = renderer.render_stack(&stack, &transform, viewport_rect);
+        assert!(!outlined.is_empty());
+        assert!(!outlined.iter().any(filled));
+        // Hatching is a fill texture and is left out as well
+        assert!(!outlined
+            .iter()
+            .any(|shape| matches!(shape, Shape::LineSegment { .. })));
+        assert!(renderer.clone().show_outline_only);
+    }
+
+    #[test]
+    fn test_true_sidewall_angles() {
+        let mut stack = create_test_stack();
+        for layer in &mut stack.layers {
+            if let Layer::Conductor(conductor) = layer {
+                conductor.physical_props.side_tangent = Some(-0.05);
+            }
+        }
+        let mut renderer = StackRenderer::new();
+        let widths = |renderer: &StackRenderer| {
+            let layout = renderer.layout_stack(&stack, 800.0);
+            layout
+                .layers
+                .iter()
+                .find_map(|geometry| match &geometry.shape {
+                    LayerShape::ThreeColumnTrapezoid(shape) => Some((
+                        shape.center_trapezoid.top_right.x - shape.center_trapezoid.top_left.x,
+                        shape.center_trapezoid.bottom_right.x
+                            - shape.center_trapezoid.bottom_left.x,
+                    )),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        // The stylized taper halves the top edge
+        let (top, bottom) = widths(&renderer);
+        assert!((top / bottom - 0.5).abs() < 1e-4);
+
+        // To scale, a 0.05 tangent narrows a trapezoid twice as wide as tall by 5%
+        renderer.set_true_sidewall_angles(true);
+        let (top, bottom) = widths(&renderer);
+        assert!((top / bottom - 0.95).abs() < 1e-4);
+
+        // Exaggerated, the taper grows with the factor
+        renderer.set_sidewall_exaggeration(4.0);
+        assert_eq!(renderer.get_sidewall_exaggeration(), Some(4.0));
+        let (top, bottom) = widths(&renderer);
+        assert!((top / bottom - 0.8).abs() < 1e-4);
+        renderer.set_sidewall_exaggeration(100.0);
+        assert_eq!(renderer.get_sidewall_exaggeration(), Some(10.0));
+
+        renderer.set_true_sidewall_angles(false);
+        assert_eq!(renderer.get_sidewall_exaggeration(), None);
+
+        assert_eq!(drawn_side_tangent(-0.05, None), -STYLIZED_SIDE_TANGENT);
+        assert!((drawn_side_tangent(-0.05, Some(1.0)) + 0.05).abs() < 1e-6);
+    }
+
     #[test]
     fn test_layer_stacking_order() {
         let renderer = StackRenderer::new();
@@ -2104,6 +3268,118 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_visible_layer_range() {
+        let mut renderer = StackRenderer::new();
+        let stack = create_test_stack();
+        let transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        renderer.set_show_dimensions(false);
+
+        let full_bounds = renderer.get_stack_bounds(&stack);
+        let full_shapes = renderer.render_stack(&stack, &transform, viewport_rect);
+
+        // Only the topmost dielectric remains visible
+        renderer.set_visible_layer_range(Some(("oxide2".to_string(), "oxide2".to_string())));
+        let scaler = renderer.get_current_scaler(&stack);
+        let (z_min, z_max) = renderer.get_visible_z_range(&stack, &scaler).unwrap();
+        assert!(z_max > z_min);
+
+        let range_bounds = renderer.get_stack_bounds(&stack);
+        assert!(range_bounds.height() < full_bounds.height());
+        assert!((range_bounds.height() - (z_max - z_min)).abs() < 1e-4);
+
+        let range_shapes = renderer.render_stack(&stack, &transform, viewport_rect);
+        assert!(range_shapes.len() < full_shapes.len());
+
+        // Unknown layer names fall back to the whole stack
+        renderer.set_visible_layer_range(Some(("missing".to_string(), "oxide2".to_string())));
+        assert!(renderer.get_visible_z_range(&stack, &scaler).is_none());
+        assert_eq!(renderer.get_stack_bounds(&stack), full_bounds);
+
+        renderer.set_visible_layer_range(None);
+        assert!(renderer.get_visible_layer_range().is_none());
+    }
+
+    #[test]
+    fn test_isometric_mode_rendering() {
+        let mut renderer = StackRenderer::new();
+        let stack = create_test_stack();
+        let transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        renderer.set_show_dimensions(false);
+
+        let flat_shapes = renderer.render_stack(&stack, &transform, viewport_rect);
+
+        renderer.set_show_isometric_mode(true);
+        assert!(renderer.show_isometric_mode);
+        let isometric_shapes = renderer.render_stack(&stack, &transform, viewport_rect);
+
+        // Every front face gains extruded top and side faces
+        assert!(isometric_shapes.len() > flat_shapes.len());
+
+        // The mode is kept when the renderer is cloned
+        let cloned = renderer.clone();
+        assert!(cloned.show_isometric_mode);
+    }
+
+    #[test]
+    fn test_via_array_discrete_cuts() {
+        let mut renderer = StackRenderer::new();
+
+        let tech = TechnologyInfo::new("test_via_array".to_string());
+        let mut stack = ProcessStack::new(tech);
+
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.5,
+        ))));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal2".to_string(),
+            0.3,
+        ))));
+
+        use crate::data::{ViaArray, ViaConnection};
+        let via = ViaConnection::new(
+            "via_bar".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.01,
+            5.0,
+        )
+        .with_array(ViaArray::new(4, 1, 0.1, 0.2, 0.2));
+        stack.add_via(via);
+
+        let transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+
+        let mut scaler = ThicknessScaler::new();
+        scaler.analyze_stack(&stack);
+
+        // Merged bars keep one shape per via column
+        let merged =
+            renderer.create_via_geometries_with_scaler(&stack, &scaler, &transform, viewport_rect);
+        assert_eq!(merged.len(), 3);
+
+        // Discrete cuts draw every cut of the array inside each column
+        renderer.set_show_merged_vias(false);
+        let discrete =
+            renderer.create_via_geometries_with_scaler(&stack, &scaler, &transform, viewport_rect);
+        assert_eq!(discrete.len(), 12);
+        assert!(discrete
+            .iter()
+            .all(|g| g.layer_name.starts_with("via_bar_")));
+
+        // Cuts stay within the bar of their column
+        let bar_bounds = merged[0].get_bounds();
+        for cut in discrete.iter().filter(|g| g.layer_name == "via_bar_0") {
+            let bounds = cut.get_bounds();
+            assert!(bounds.width() < bar_bounds.width());
+            assert!(bounds.min.x >= bar_bounds.min.x - 1e-3);
+            assert!(bounds.max.x <= bar_bounds.max.x + 1e-3);
+        }
+    }
+
     #[test]
     fn test_via_metal_alignment() {
         let renderer = StackRenderer::new();
@@ -2279,6 +3555,84 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_area_accurate_via_width() {
+        use crate::data::ViaConnection;
+
+        let mut stack = ProcessStack::new(TechnologyInfo::new("area_vias".to_string()));
+        stack.add_layer(Layer::Conductor(Box::new(
+            ConductorLayer::new("metal1".to_string(), 0.4).with_width_spacing_limits(0.2, 0.2),
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(
+            ConductorLayer::new("metal2".to_string(), 0.4).with_width_spacing_limits(0.2, 0.2),
+        )));
+        // A 0.1 x 0.1 um cut, half of WMIN
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.01,
+            5.0,
+        ));
+
+        let transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        let mut scaler = ThicknessScaler::new();
+        scaler.analyze_stack(&stack);
+        let via_width = |renderer: &StackRenderer| {
+            renderer.create_via_geometries_with_scaler(&stack, &scaler, &transform, viewport_rect)
+                [0]
+            .get_bounds()
+            .width()
+        };
+
+        // Schematic sizing fills the narrowest metal edge
+        let mut renderer = StackRenderer::new();
+        let schematic = via_width(&renderer);
+
+        // Area-accurate sizing draws sqrt(AREA) where the narrowest edge stands for WMIN
+        renderer.set_area_accurate_vias(true);
+        assert!((via_width(&renderer) - schematic * 0.5).abs() < 1e-3);
+
+        // Without WMIN the width follows the drawn thickness
+        let mut stack_without_wmin = stack.clone();
+        for layer in &mut stack_without_wmin.layers {
+            if let Layer::Conductor(conductor) = layer {
+                conductor.physical_props.width_min = None;
+            }
+        }
+        let metal_height = scaler.get_exaggerated_thickness_for_layer(&stack.layers[0]);
+        let width = renderer.create_via_geometries_with_scaler(
+            &stack_without_wmin,
+            &scaler,
+            &transform,
+            viewport_rect,
+        )[0]
+        .get_bounds()
+        .width();
+        assert!((width - 0.1 * metal_height / 0.4 * transform.scale).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_check_width_fidelity() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("widths".to_string()));
+        for (name, width_min) in [("metal3", 2.0), ("metal2", 0.2), ("metal1", 0.2)] {
+            stack.add_layer(Layer::Conductor(Box::new(
+                ConductorLayer::new(name.to_string(), 0.4)
+                    .with_width_spacing_limits(width_min, width_min),
+            )));
+        }
+
+        // Equal thicknesses draw equal widths, hiding the ten times wider top metal
+        let renderer = StackRenderer::new();
+        let distortions = renderer.check_width_fidelity(&stack, 2.0);
+        assert_eq!(distortions.len(), 1);
+        assert_eq!(distortions[0].layer, "metal3");
+        assert!(distortions[0].factor < 0.2);
+
+        assert!(renderer.check_width_fidelity(&stack, 20.0).is_empty());
+    }
+
     #[test]
     fn test_auto_fit() {
         let renderer = StackRenderer::new();
@@ -2315,6 +3669,32 @@ mod tests {
         assert!(stack_height_screen <= effective_height + 1.0);
     }
 
+    #[test]
+    fn test_fit_single_axis() {
+        let renderer = StackRenderer::new();
+        let stack = create_test_stack();
+        let bounds = renderer.get_stack_bounds(&stack);
+        let mut transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        renderer.auto_fit(&stack, &mut transform);
+
+        // Fitting the width fills it and keeps the world height at the view center, the
+        // point auto fit centers the stack on
+        let center = transform.world_to_screen(bounds.center());
+        let world_center = transform.screen_to_world(center);
+        renderer.fit_to_view(&stack, &mut transform, FitAxis::Width);
+        assert!((bounds.width() * transform.scale - (800.0 - 30.0 - 40.0)).abs() < 0.01);
+        assert!((transform.screen_to_world(center).y - world_center.y).abs() < 1e-3);
+        assert!((transform.screen_to_world(center).x - bounds.center().x).abs() < 1e-3);
+
+        // Fitting the height afterwards keeps the horizontal position instead
+        transform.pan(Vec2::new(25.0, 0.0));
+        let world_center = transform.screen_to_world(center);
+        renderer.fit_to_view(&stack, &mut transform, FitAxis::Height);
+        assert!((bounds.height() * transform.scale - (600.0 - 40.0)).abs() < 0.01);
+        assert!((transform.screen_to_world(center).x - world_center.x).abs() < 1e-3);
+        assert!((transform.screen_to_world(center).y - bounds.center().y).abs() < 1e-3);
+    }
+
     #[test]
     fn test_hit_test_z_order_priority() {
         let renderer = StackRenderer::new();
@@ -2533,4 +3913,120 @@ mod tests {
         );
         println!("  Dielectric width adaptation test PASSED");
     }
+
+    #[test]
+    fn test_view_anchor() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("anchor".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd".to_string(),
+            1.0,
+            4.2,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.5,
+        ))));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "substrate".to_string(),
+            2.0,
+            11.9,
+        )));
+
+        let mut renderer = StackRenderer::new();
+        let scaler = renderer.get_current_scaler(&stack);
+        let substrate_bounds = renderer.layout_stack(&stack, 800.0).get_bounds();
+        let substrate_ticks = renderer.create_ruler_ticks(&stack, &scaler);
+        assert!((substrate_bounds.max.y - 0.0).abs() < 1e-4);
+        assert_eq!(substrate_ticks[0].value, 0.0);
+        assert_eq!(substrate_ticks[0].world_y, 0.0);
+
+        renderer.set_view_anchor(ViewAnchor::ChipTop);
+        assert_eq!(renderer.get_view_anchor(), ViewAnchor::ChipTop);
+
+        // Same shape, shifted so the top of the stack is at y = 0
+        let top_bounds = renderer.layout_stack(&stack, 800.0).get_bounds();
+        assert!((top_bounds.min.y - 0.0).abs() < 1e-4);
+        assert!((top_bounds.height() - substrate_bounds.height()).abs() < 1e-4);
+        assert!((renderer.get_stack_bounds(&stack).max.y - 3.0).abs() < 1e-4);
+
+        // The ruler counts depth from the top, growing downwards on screen
+        let ticks = renderer.create_ruler_ticks(&stack, &scaler);
+        assert_eq!(ticks.len(), substrate_ticks.len());
+        let zero = ticks.iter().find(|tick| tick.value == 0.0).unwrap();
+        assert_eq!(zero.world_y, 0.0);
+        assert!(zero.is_major);
+        let deepest = ticks
+            .iter()
+            .max_by(|a, b| a.value.total_cmp(&b.value))
+            .unwrap();
+        assert!((deepest.value - 3.0).abs() < 1e-4);
+        assert!((deepest.world_y - 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_level_of_detail() {
+        let stack = crate::utils::generate_random_stack(3, 200);
+        let mut renderer = StackRenderer::new();
+        let mut transform = ViewTransform::new(Vec2::new(1280.0, 800.0));
+        transform.fit_bounds(renderer.layout_stack(&stack, 1280.0).get_bounds(), 0.0);
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, transform.viewport_size);
+
+        // The whole stack fits on screen, far more layers than the policy allows
+        let (layout, lod_active) = renderer.screen_layout(&stack, &transform, viewport_rect);
+        assert!(lod_active);
+        let policy = renderer.get_lod_policy();
+        assert!(layout
+            .layers
+            .iter()
+            .filter(|geometry| policy.simplifies(geometry))
+            .all(|geometry| matches!(geometry.shape, LayerShape::Rectangle(_))));
+        let simplified_shapes = renderer
+            .render_stack(&stack, &transform, viewport_rect)
+            .len();
+
+        renderer.set_lod_policy(LodPolicy::disabled());
+        let (_, lod_active) = renderer.screen_layout(&stack, &transform, viewport_rect);
+        assert!(!lod_active);
+        let detailed_shapes = renderer
+            .render_stack(&stack, &transform, viewport_rect)
+            .len();
+        assert!(simplified_shapes < detailed_shapes);
+
+        // Zoomed in on a few layers, everything is drawn in full detail again
+        renderer.set_lod_policy(LodPolicy::new());
+        transform.zoom(50.0, viewport_rect.center());
+        let (layout, lod_active) = renderer.screen_layout(&stack, &transform, viewport_rect);
+        assert!(!lod_active);
+        assert!(layout
+            .layers
+            .iter()
+            .any(|geometry| matches!(geometry.shape, LayerShape::ThreeColumnTrapezoid(_))));
+    }
+
+    #[test]
+    fn test_frame_timings() {
+        let stack = create_test_stack();
+        let renderer = StackRenderer::new();
+        let transform = ViewTransform::new(Vec2::new(800.0, 600.0));
+        let viewport_rect = Rect::from_min_size(Pos2::ZERO, transform.viewport_size);
+
+        let ctx = egui::Context::default();
+        let mut timings = FrameTimings::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            let painter = ctx.layer_painter(egui::LayerId::background());
+            timings =
+                renderer.render_stack_with_painter(&stack, &transform, viewport_rect, &painter);
+        });
+
+        let layout = renderer.layout_stack(&stack, viewport_rect.width());
+        assert_eq!(
+            timings.geometry_count,
+            layout.layers.len() + layout.vias.len()
+        );
+        assert!(timings.shape_count >= timings.geometry_count);
+        assert_eq!(
+            timings.total(),
+            timings.geometry + timings.text + timings.painting
+        );
+    }
 }
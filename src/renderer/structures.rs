@@ -0,0 +1,368 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{ConductorLayer, Layer, ProcessStack};
+use crate::renderer::layout::StackLayout;
+use egui::{Pos2, Rect};
+use serde::{Deserialize, Serialize};
+
+/// Width of the example power line in multiples of WMIN
+pub const POWER_LINE_WMIN_MULTIPLE: f64 = 10.0;
+
+/// Typical structure drawn over the cross-section from the layer parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExampleStructure {
+    /// One cut of every via, landing on pads of the metals it joins
+    ViaStack,
+    /// Line of [`POWER_LINE_WMIN_MULTIPLE`] times WMIN
+    PowerLine,
+    /// Two WMIN lines SMIN apart
+    MinPitchPair,
+}
+
+impl ExampleStructure {
+    pub const ALL: [ExampleStructure; 3] = [
+        ExampleStructure::ViaStack,
+        ExampleStructure::PowerLine,
+        ExampleStructure::MinPitchPair,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExampleStructure::ViaStack => "Via stack",
+            ExampleStructure::PowerLine => "Wide power line",
+            ExampleStructure::MinPitchPair => "Minimum-pitch pair",
+        }
+    }
+
+    /// Whether the structure sits on one chosen conductor
+    pub fn uses_layer(self) -> bool {
+        !matches!(self, ExampleStructure::ViaStack)
+    }
+}
+
+/// Example structure placed in the cross-section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructureAnnotation {
+    pub structure: ExampleStructure,
+    /// Horizontal position of the center, 0 at the left edge of the stack and 1 at the right
+    pub position: f32,
+    /// Conductor of power lines and pitch pairs, the top metal when unset or missing
+    pub layer: Option<String>,
+}
+
+impl StructureAnnotation {
+    pub fn new(structure: ExampleStructure, position: f32) -> Self {
+        Self {
+            structure,
+            position,
+            layer: None,
+        }
+    }
+
+    pub fn with_layer(mut self, layer: impl Into<String>) -> Self {
+        self.layer = Some(layer.into());
+        self
+    }
+}
+
+/// Rectangle of an example structure in world coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureShape {
+    pub rect: Rect,
+    pub is_via: bool,
+    /// Dimensions written next to the shape
+    pub caption: Option<String>,
+}
+
+/// Shapes of `annotation` over `layout`, a layout of `stack` in world coordinates
+///
+/// Widths in um are converted with the drawn thickness per um of thickness of the metal
+/// they sit on, so each structure keeps its true aspect ratio also in schematic mode.
+/// Structures whose conductors or WMIN/SMIN are missing yield no shapes.
+pub fn layout_structure(
+    stack: &ProcessStack,
+    layout: &StackLayout,
+    annotation: &StructureAnnotation,
+) -> Vec<StructureShape> {
+    let bounds = layout.get_bounds();
+    if bounds == Rect::NOTHING {
+        return Vec::new();
+    }
+    let center_x = bounds.min.x + annotation.position.clamp(0.0, 1.0) * bounds.width();
+
+    match annotation.structure {
+        ExampleStructure::ViaStack => via_stack_shapes(stack, layout, center_x),
+        ExampleStructure::PowerLine => {
+            let Some(metal) = annotation_metal(stack, layout, annotation) else {
+                return Vec::new();
+            };
+            let Some(width_min) = metal.conductor.physical_props.width_min else {
+                return Vec::new();
+            };
+            let width = POWER_LINE_WMIN_MULTIPLE * width_min;
+            vec![StructureShape {
+                rect: metal.line(center_x, width),
+                is_via: false,
+                caption: Some(format!(
+                    "{POWER_LINE_WMIN_MULTIPLE}×WMIN = {} μm",
+                    format_um(width)
+                )),
+            }]
+        }
+        ExampleStructure::MinPitchPair => {
+            let Some(metal) = annotation_metal(stack, layout, annotation) else {
+                return Vec::new();
+            };
+            let props = &metal.conductor.physical_props;
+            let (Some(width), Some(spacing)) = (props.width_min, props.spacing_min) else {
+                return Vec::new();
+            };
+            let offset = (width + spacing) / 2.0 * metal.scale;
+            vec![
+                StructureShape {
+                    rect: metal.line(center_x - offset as f32, width),
+                    is_via: false,
+                    caption: Some(format!(
+                        "pitch {} μm (W {} + S {})",
+                        format_um(width + spacing),
+                        format_um(width),
+                        format_um(spacing)
+                    )),
+                },
+                StructureShape {
+                    rect: metal.line(center_x + offset as f32, width),
+                    is_via: false,
+                    caption: None,
+                },
+            ]
+        }
+    }
+}
+
+/// Conductor with its drawn extent
+struct PlacedMetal<'a> {
+    conductor: &'a ConductorLayer,
+    bounds: Rect,
+    /// World units per um
+    scale: f64,
+}
+
+impl PlacedMetal<'_> {
+    /// Full-height line of `width` um centered on `center_x`
+    fn line(&self, center_x: f32, width: f64) -> Rect {
+        let half = (width * self.scale / 2.0) as f32;
+        Rect::from_min_max(
+            Pos2::new(center_x - half, self.bounds.min.y),
+            Pos2::new(center_x + half, self.bounds.max.y),
+        )
+    }
+}
+
+fn placed_metal<'a>(
+    stack: &'a ProcessStack,
+    layout: &StackLayout,
+    name: &str,
+) -> Option<PlacedMetal<'a>> {
+    let Some(Layer::Conductor(conductor)) = stack.get_layer(name) else {
+        return None;
+    };
+    let bounds = layout
+        .conductors()
+        .find(|geometry| geometry.layer_name == name)?
+        .get_bounds();
+    if conductor.thickness <= 0.0 || bounds.height() <= 0.0 {
+        return None;
+    }
+    Some(PlacedMetal {
+        conductor,
+        bounds,
+        scale: bounds.height() as f64 / conductor.thickness,
+    })
+}
+
+/// The chosen conductor, else the topmost one in the layout
+fn annotation_metal<'a>(
+    stack: &'a ProcessStack,
+    layout: &StackLayout,
+    annotation: &StructureAnnotation,
+) -> Option<PlacedMetal<'a>> {
+    annotation
+        .layer
+        .as_deref()
+        .and_then(|name| placed_metal(stack, layout, name))
+        .or_else(|| {
+            // ITF lists layers top first
+            stack
+                .layers
+                .iter()
+                .filter(|layer| layer.is_conductor())
+                .find_map(|layer| placed_metal(stack, layout, layer.name()))
+        })
+}
+
+/// A cut of the first via between each pair of metals, with WMIN pads on the metals
+fn via_stack_shapes(
+    stack: &ProcessStack,
+    layout: &StackLayout,
+    center_x: f32,
+) -> Vec<StructureShape> {
+    let mut cuts = Vec::new();
+    let mut pads: Vec<(String, Rect)> = Vec::new();
+    let mut joined: Vec<(&str, &str)> = Vec::new();
+    for via in stack.via_stack.iter() {
+        let pair = (via.from_layer.as_str(), via.to_layer.as_str());
+        if joined.contains(&pair) || joined.contains(&(pair.1, pair.0)) {
+            continue;
+        }
+        let (Some(from), Some(to)) = (
+            placed_metal(stack, layout, &via.from_layer),
+            placed_metal(stack, layout, &via.to_layer),
+        ) else {
+            continue;
+        };
+        joined.push(pair);
+
+        // World y grows downwards, so the upper metal has the smaller y
+        let (lower, upper) = if from.bounds.min.y > to.bounds.min.y {
+            (from, to)
+        } else {
+            (to, from)
+        };
+        let size = via.area.max(0.0).sqrt();
+        let half = (size * lower.scale / 2.0) as f32;
+        cuts.push(StructureShape {
+            rect: Rect::from_min_max(
+                Pos2::new(center_x - half, upper.bounds.max.y),
+                Pos2::new(center_x + half, lower.bounds.min.y),
+            ),
+            is_via: true,
+            caption: Some(format!("{} {} μm", via.name, format_um(size))),
+        });
+        for metal in [lower, upper] {
+            let width = metal
+                .conductor
+                .physical_props
+                .width_min
+                .unwrap_or(0.0)
+                .max(size);
+            let pad = metal.line(center_x, width);
+            match pads
+                .iter_mut()
+                .find(|(name, _)| *name == metal.conductor.name)
+            {
+                Some((_, existing)) => *existing = existing.union(pad),
+                None => pads.push((metal.conductor.name.clone(), pad)),
+            }
+        }
+    }
+
+    pads.into_iter()
+        .map(|(_, rect)| StructureShape {
+            rect,
+            is_via: false,
+            caption: None,
+        })
+        .chain(cuts)
+        .collect()
+}
+
+/// Length in um without trailing float noise
+fn format_um(value: f64) -> String {
+    let rounded = (value * 1e4).round() / 1e4;
+    rounded.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, TechnologyInfo, ViaConnection};
+    use crate::renderer::StackRenderer;
+
+    fn create_stack() -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("structures".to_string()));
+        let mut metal2 = ConductorLayer::new("metal2".to_string(), 0.4);
+        metal2.physical_props.width_min = Some(0.2);
+        metal2.physical_props.spacing_min = Some(0.3);
+        stack.add_layer(Layer::Conductor(Box::new(metal2)));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "imd".to_string(),
+            0.5,
+            4.0,
+        )));
+        let mut metal1 = ConductorLayer::new("metal1".to_string(), 0.2);
+        metal1.physical_props.width_min = Some(0.1);
+        stack.add_layer(Layer::Conductor(Box::new(metal1)));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.0225,
+            5.0,
+        ));
+        stack
+    }
+
+    #[test]
+    fn test_power_line_and_pitch_pair() {
+        let stack = create_stack();
+        let layout = StackRenderer::new().layout_stack(&stack, 800.0);
+        let metal2 = layout
+            .conductors()
+            .find(|geometry| geometry.layer_name == "metal2")
+            .unwrap()
+            .get_bounds();
+        let scale = metal2.height() / 0.4;
+
+        // The top metal by default
+        let line = layout_structure(
+            &stack,
+            &layout,
+            &StructureAnnotation::new(ExampleStructure::PowerLine, 0.5),
+        );
+        assert_eq!(line.len(), 1);
+        assert!((line[0].rect.width() - 2.0 * scale).abs() < 1e-3);
+        assert_eq!(line[0].rect.min.y, metal2.min.y);
+        assert_eq!(line[0].caption.as_deref(), Some("10×WMIN = 2 μm"));
+
+        let pair = layout_structure(
+            &stack,
+            &layout,
+            &StructureAnnotation::new(ExampleStructure::MinPitchPair, 0.5).with_layer("metal2"),
+        );
+        assert_eq!(pair.len(), 2);
+        let gap = pair[1].rect.min.x - pair[0].rect.max.x;
+        assert!((gap - 0.3 * scale).abs() < 1e-3);
+
+        // metal1 has no SMIN
+        let missing =
+            StructureAnnotation::new(ExampleStructure::MinPitchPair, 0.5).with_layer("metal1");
+        assert!(layout_structure(&stack, &layout, &missing).is_empty());
+    }
+
+    #[test]
+    fn test_via_stack() {
+        let stack = create_stack();
+        let layout = StackRenderer::new().layout_stack(&stack, 800.0);
+        let bounds = |name: &str| {
+            layout
+                .conductors()
+                .find(|geometry| geometry.layer_name == name)
+                .unwrap()
+                .get_bounds()
+        };
+
+        let shapes = layout_structure(
+            &stack,
+            &layout,
+            &StructureAnnotation::new(ExampleStructure::ViaStack, 0.25),
+        );
+        assert_eq!(shapes.len(), 3);
+        let cut = shapes.iter().find(|shape| shape.is_via).unwrap();
+        assert_eq!(cut.rect.min.y, bounds("metal2").max.y);
+        assert_eq!(cut.rect.max.y, bounds("metal1").min.y);
+        assert_eq!(cut.caption.as_deref(), Some("via1 0.15 μm"));
+        let center = layout.get_bounds().min.x + 0.25 * layout.get_bounds().width();
+        assert!((cut.rect.center().x - center).abs() < 1e-3);
+    }
+}
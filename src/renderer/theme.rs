@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::LayerClass;
+use crate::renderer::hatching::HatchPattern;
+use egui::Color32;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use thiserror::Error;
+
+/// How often a theme file is checked for changes
+pub const THEME_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Fill and hatch of one layer class, each falling back to the built-in scheme when unset
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ThemeStyle {
+    pub color: Option<Color32>,
+    /// Drawn on dielectric classes only, like the built-in hatching
+    pub hatch: Option<HatchPattern>,
+}
+
+/// Layer class styles read from a theme file
+///
+/// Themes are TOML, with a `[classes.<class>]` table per class, or JSON with the same
+/// layout; any valid TOML or JSON of that shape is read. Colors are `#rrggbb` and hatches `none`, `diagonal`, `dots` or `cross_hatch`:
+///
+/// ```toml
+/// name = "Site standard"
+///
+/// [classes.signal_metal]
+/// color = "#b87333"
+///
+/// [classes.etch_stop]
+/// color = "#483d8b"
+/// hatch = "dots"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Theme {
+    pub name: Option<String>,
+    pub styles: HashMap<LayerClass, ThemeStyle>,
+}
+
+/// Theme file as written, before the class names and values are checked
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeDocument {
+    name: Option<String>,
+    #[serde(default)]
+    classes: HashMap<String, StyleDocument>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StyleDocument {
+    color: Option<String>,
+    hatch: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum ThemeError {
+    #[error("Failed to read theme file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unknown layer class '{0}'")]
+    UnknownClass(String),
+
+    #[error("Invalid color '{0}', expected #rrggbb")]
+    InvalidColor(String),
+
+    #[error("Unknown hatch '{0}', expected none, diagonal, dots or cross_hatch")]
+    UnknownHatch(String),
+}
+
+impl Theme {
+    /// Parse a theme, as JSON when the text starts with `{` and as TOML otherwise
+    pub fn parse(text: &str) -> Result<Self, ThemeError> {
+        let document: ThemeDocument = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text)?
+        } else {
+            toml::from_str(text)?
+        };
+        Self::from_document(document)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn get_style(&self, class: LayerClass) -> ThemeStyle {
+        self.styles.get(&class).copied().unwrap_or_default()
+    }
+
+    fn from_document(document: ThemeDocument) -> Result<Self, ThemeError> {
+        let mut styles = HashMap::new();
+        for (class, style) in document.classes {
+            let class = LayerClass::from_id(&class).ok_or(ThemeError::UnknownClass(class))?;
+            let color = style
+                .color
+                .map(|value| {
+                    Color32::from_hex(&value)
+                        .ok()
+                        .filter(|_| value.len() == 7)
+                        .ok_or(ThemeError::InvalidColor(value))
+                })
+                .transpose()?;
+            let hatch = style
+                .hatch
+                .map(|value| HatchPattern::from_id(&value).ok_or(ThemeError::UnknownHatch(value)))
+                .transpose()?;
+            styles.insert(class, ThemeStyle { color, hatch });
+        }
+        Ok(Theme {
+            name: document.name,
+            styles,
+        })
+    }
+}
+
+/// Theme file watched for changes, so edits show without restarting
+pub struct ThemeFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_poll: Option<Instant>,
+}
+
+impl ThemeFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            modified: None,
+            last_poll: None,
+        }
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the theme, remembering the modification time it was read at
+    pub fn load(&mut self) -> Result<Theme, ThemeError> {
+        self.modified = modified_time(&self.path);
+        Theme::load(&self.path)
+    }
+
+    /// Reload the theme if the file changed since it was last read
+    pub fn reload_if_modified(&mut self) -> Option<Result<Theme, ThemeError>> {
+        let modified = modified_time(&self.path);
+        // A file being replaced can be missing for a moment; keep the loaded theme
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        Some(self.load())
+    }
+
+    /// [`ThemeFile::reload_if_modified`], checking at most every [`THEME_POLL_INTERVAL`]
+    pub fn poll(&mut self) -> Option<Result<Theme, ThemeError>> {
+        let now = Instant::now();
+        if self
+            .last_poll
+            .is_some_and(|last| now.duration_since(last) < THEME_POLL_INTERVAL)
+        {
+            return None;
+        }
+        self.last_poll = Some(now);
+        self.reload_if_modified()
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toml_theme() {
+        let theme = Theme::parse(
+            "# Site colors\n\
+             name = \"Site standard\"\n\
+             \n\
+             [classes.signal_metal]\n\
+             color = \"#b87333\" # copper\n\
+             \n\
+             [classes.etch_stop]\n\
+             hatch = \"dots\"\n",
+        )
+        .unwrap();
+        assert_eq!(theme.name.as_deref(), Some("Site standard"));
+        assert_eq!(
+            theme.get_style(LayerClass::SignalMetal).color,
+            Some(Color32::from_rgb(0xb8, 0x73, 0x33))
+        );
+        assert_eq!(
+            theme.get_style(LayerClass::EtchStop),
+            ThemeStyle {
+                color: None,
+                hatch: Some(HatchPattern::Dots)
+            }
+        );
+        assert_eq!(theme.get_style(LayerClass::Seed), ThemeStyle::default());
+
+        assert!(matches!(
+            Theme::parse("[classes.metal]\ncolor = \"#000000\""),
+            Err(ThemeError::UnknownClass(_))
+        ));
+        assert!(matches!(
+            Theme::parse("[classes.seed]\ncolor = \"red\""),
+            Err(ThemeError::InvalidColor(_))
+        ));
+        assert!(matches!(
+            Theme::parse("[classes.seed]\ncolor = #000000"),
+            Err(ThemeError::Toml(_))
+        ));
+        assert!(matches!(
+            Theme::parse("[classes.seed]\nshade = \"#000000\""),
+            Err(ThemeError::Toml(_))
+        ));
+
+        // Dotted keys, literal strings and inline tables are TOML too
+        let theme = Theme::parse(
+            "[classes]\n\
+             signal_metal.color = '#b87333'\n\
+             etch_stop = { hatch = \"dots\" }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            theme.get_style(LayerClass::SignalMetal).color,
+            Some(Color32::from_rgb(0xb8, 0x73, 0x33))
+        );
+        assert_eq!(
+            theme.get_style(LayerClass::EtchStop).hatch,
+            Some(HatchPattern::Dots)
+        );
+    }
+
+    #[test]
+    fn test_parse_json_theme() {
+        let theme = Theme::parse(
+            r##"{"name": "json", "classes": {"substrate": {"color": "#2f4f4f", "hatch": "none"}}}"##,
+        )
+        .unwrap();
+        assert_eq!(
+            theme.get_style(LayerClass::Substrate),
+            ThemeStyle {
+                color: Some(Color32::from_rgb(0x2f, 0x4f, 0x4f)),
+                hatch: Some(HatchPattern::None)
+            }
+        );
+        assert!(matches!(
+            Theme::parse(r#"{"classes": {"gate": {"hatch": "zigzag"}}}"#),
+            Err(ThemeError::UnknownHatch(_))
+        ));
+        assert!(matches!(
+            Theme::parse("{\"classes\": "),
+            Err(ThemeError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_theme_file_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("theme.toml");
+        std::fs::write(&path, "[classes.gate]\ncolor = \"#ff0000\"\n").unwrap();
+        let mut file = ThemeFile::new(&path);
+        let theme = file.load().unwrap();
+        assert_eq!(theme.get_style(LayerClass::Gate).color, Some(Color32::RED));
+        assert!(file.reload_if_modified().is_none());
+
+        std::fs::write(&path, "[classes.gate]\ncolor = \"#00ff00\"\n").unwrap();
+        // Make the change visible on file systems with a coarse timestamp
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|handle| handle.set_modified(later))
+            .unwrap();
+        let theme = file.reload_if_modified().unwrap().unwrap();
+        assert_eq!(
+            theme.get_style(LayerClass::Gate).color,
+            Some(Color32::from_rgb(0, 255, 0))
+        );
+    }
+}
@@ -122,6 +122,11 @@ impl ThicknessScaler {
 
     /// Get the exaggerated thickness for a layer, with special handling for auto-created layers
     pub fn get_exaggerated_thickness_for_layer(&self, layer: &crate::data::Layer) -> f32 {
+        // Markers take no height in either mode
+        if layer.is_marker() {
+            return 0.0;
+        }
+
         // In normal mode, always return original thickness regardless of layer type
         if !self.schematic_mode {
             return layer.thickness() as f32;
@@ -181,6 +186,33 @@ impl ThicknessScaler {
             .map(|layer| self.get_exaggerated_thickness_for_layer(layer))
             .sum()
     }
+
+    /// How many times more the most stretched layer of `stack` is drawn than the least
+    /// stretched one, 1.0 when thicknesses are to scale relative to each other
+    pub fn get_vertical_exaggeration(&self, stack: &ProcessStack) -> f32 {
+        let (min_factor, max_factor) = stack
+            .layers
+            .iter()
+            .filter(|layer| !layer.is_marker())
+            .map(|layer| self.get_exaggerated_thickness_for_layer(layer) / layer.thickness() as f32)
+            .fold((f32::INFINITY, 0.0f32), |(min, max), factor| {
+                (min.min(factor), max.max(factor))
+            });
+        if min_factor.is_finite() && min_factor > 0.0 {
+            max_factor / min_factor
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Badge text warning that a drawing is stretched vertically by `exaggeration`
+pub fn exaggeration_label(exaggeration: f32) -> String {
+    if exaggeration >= 10.0 {
+        format!("vertical ×{exaggeration:.0} not to scale")
+    } else {
+        format!("vertical ×{exaggeration:.1} not to scale")
+    }
 }
 
 impl Default for ThicknessScaler {
@@ -434,6 +466,22 @@ mod tests {
         assert!((exaggerated_height - sum_heights).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_vertical_exaggeration() {
+        let stack = create_test_stack_varied_thickness();
+        let mut scaler = ThicknessScaler::new();
+        scaler.analyze_stack(&stack);
+        scaler.set_normal_mode();
+        assert!((scaler.get_vertical_exaggeration(&stack) - 1.0).abs() < 1e-6);
+
+        // 0.1 um is drawn at 30% of 2.0 um, six times its thickness, 2.0 um at 60%
+        scaler.set_schematic_mode(0.1, 2.0);
+        assert!((scaler.get_vertical_exaggeration(&stack) - 10.0).abs() < 1e-4);
+
+        assert_eq!(exaggeration_label(10.0), "vertical ×10 not to scale");
+        assert_eq!(exaggeration_label(2.54), "vertical ×2.5 not to scale");
+    }
+
     #[test]
     fn test_proportional_scaling() {
         let mut scaler = ThicknessScaler::new();
@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+/// Factor by which a conductor may be drawn too wide or too narrow before it is flagged
+pub const DEFAULT_MAX_WIDTH_DISTORTION: f32 = 2.0;
+
+/// Conductor whose drawn width is out of proportion with its WMIN
+#[derive(Debug, Clone, PartialEq)]
+pub struct WidthDistortion {
+    pub layer: String,
+    pub width_min: f64,
+    /// Drawn width per um of WMIN over that of the other conductors, above 1 when too wide
+    pub factor: f32,
+}
+
+impl WidthDistortion {
+    pub fn message(&self) -> String {
+        let (ratio, direction) = if self.factor >= 1.0 {
+            (self.factor, "wide")
+        } else {
+            (1.0 / self.factor, "narrow")
+        };
+        format!(
+            "{} is drawn {ratio:.1}x too {direction} for its WMIN of {} um compared to the other conductors",
+            self.layer, self.width_min
+        )
+    }
+}
+
+/// Conductors drawn more than `max_factor` out of proportion with the others
+///
+/// `widths` holds the name, drawn width and WMIN of each conductor. The reference is the
+/// median drawn width per um of WMIN, so one odd layer does not flag all the others.
+pub fn find_width_distortions(
+    widths: &[(String, f32, f64)],
+    max_factor: f32,
+) -> Vec<WidthDistortion> {
+    let scales: Vec<(&str, f32, f64)> = widths
+        .iter()
+        .filter(|(_, drawn, width_min)| *drawn > 0.0 && *width_min > 0.0)
+        .map(|(name, drawn, width_min)| (name.as_str(), drawn / *width_min as f32, *width_min))
+        .collect();
+    if scales.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut sorted: Vec<f32> = scales.iter().map(|&(_, scale, _)| scale).collect();
+    sorted.sort_by(f32::total_cmp);
+    let middle = sorted.len() / 2;
+    let reference = if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    };
+
+    let max_factor = max_factor.max(1.0);
+    scales
+        .into_iter()
+        .map(|(name, scale, width_min)| WidthDistortion {
+            layer: name.to_string(),
+            width_min,
+            factor: scale / reference,
+        })
+        .filter(|distortion| distortion.factor > max_factor || distortion.factor * max_factor < 1.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_width_distortions() {
+        let widths = vec![
+            ("metal1".to_string(), 10.0, 0.05),
+            ("metal2".to_string(), 10.0, 0.05),
+            ("metal3".to_string(), 20.0, 0.1),
+            // Drawn like metal1 while ten times wider in silicon
+            ("metal4".to_string(), 10.0, 0.5),
+        ];
+        let distortions = find_width_distortions(&widths, 2.0);
+        assert_eq!(distortions.len(), 1);
+        assert_eq!(distortions[0].layer, "metal4");
+        assert!((distortions[0].factor - 0.1).abs() < 1e-4);
+        assert!(distortions[0].message().contains("10.0x too narrow"));
+
+        assert!(find_width_distortions(&widths, 20.0).is_empty());
+        assert!(find_width_distortions(&widths[..1], 2.0).is_empty());
+    }
+}
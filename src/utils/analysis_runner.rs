@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use crate::utils::{guard, CaughtPanic};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+
+type AnalysisFn<T> = Arc<dyn Fn(&ProcessStack) -> T + Send + Sync>;
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Output of one registered analysis for one submitted stack
+#[derive(Debug)]
+pub struct AnalysisResult<T> {
+    pub name: String,
+    /// Generation returned by the `submit` call the stack was passed to
+    pub generation: u64,
+    /// What the analysis returned, or the panic it raised
+    pub output: Result<T, CaughtPanic>,
+}
+
+/// Runs registered analyses of a shared stack on a pool of worker threads
+///
+/// `submit` queues every analysis for a stack and returns at once; `poll` hands back
+/// the results finished so far without blocking, so the GUI can call it every frame.
+/// A newer `submit` supersedes the previous one: its queued analyses are skipped and
+/// its results dropped.
+pub struct AnalysisRunner<T> {
+    analyses: Vec<(String, AnalysisFn<T>)>,
+    jobs: Option<Sender<Job>>,
+    results_sender: Sender<AnalysisResult<T>>,
+    results: Receiver<AnalysisResult<T>>,
+    workers: Vec<JoinHandle<()>>,
+    /// Generation of the latest submit, shared with the queued jobs
+    generation: Arc<AtomicU64>,
+    /// Results of the latest generation not yet received
+    pending: usize,
+}
+
+impl<T: Send + 'static> AnalysisRunner<T> {
+    /// Runner with `threads` worker threads, at least one
+    pub fn new(threads: usize) -> Self {
+        let (jobs, queue) = channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        let workers = (0..threads.max(1))
+            .map(|index| {
+                let queue = Arc::clone(&queue);
+                thread::Builder::new()
+                    .name(format!("analysis-{index}"))
+                    .spawn(move || loop {
+                        // The lock is released before the job runs
+                        let job = queue.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => break,
+                        }
+                    })
+                    .expect("failed to spawn analysis thread")
+            })
+            .collect();
+
+        let (results_sender, results) = channel();
+        Self {
+            analyses: Vec::new(),
+            jobs: Some(jobs),
+            results_sender,
+            results,
+            workers,
+            generation: Arc::new(AtomicU64::new(0)),
+            pending: 0,
+        }
+    }
+
+    /// Register `analysis` under `name`, replacing an analysis of the same name
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        analysis: impl Fn(&ProcessStack) -> T + Send + Sync + 'static,
+    ) {
+        let name = name.into();
+        let analysis: AnalysisFn<T> = Arc::new(analysis);
+        match self
+            .analyses
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some((_, existing)) => *existing = analysis,
+            None => self.analyses.push((name, analysis)),
+        }
+    }
+
+    pub fn with_analysis(
+        mut self,
+        name: impl Into<String>,
+        analysis: impl Fn(&ProcessStack) -> T + Send + Sync + 'static,
+    ) -> Self {
+        self.register(name, analysis);
+        self
+    }
+
+    /// Names of the registered analyses, in registration order
+    pub fn analysis_names(&self) -> impl Iterator<Item = &str> {
+        self.analyses.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Queue every registered analysis of `stack`, returning the new generation
+    pub fn submit(&mut self, stack: Arc<ProcessStack>) -> u64 {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.pending = self.analyses.len();
+
+        let Some(jobs) = &self.jobs else {
+            return generation;
+        };
+        for (name, analysis) in &self.analyses {
+            let (name, analysis) = (name.clone(), Arc::clone(analysis));
+            let stack = Arc::clone(&stack);
+            let latest = Arc::clone(&self.generation);
+            let results = self.results_sender.clone();
+            let job: Job = Box::new(move || {
+                // Skip work for a stack that has been superseded while queued
+                if latest.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                let output = guard(|| analysis(&stack));
+                let _ = results.send(AnalysisResult {
+                    name,
+                    generation,
+                    output,
+                });
+            });
+            let _ = jobs.send(job);
+        }
+        generation
+    }
+
+    /// Results of the latest submit finished since the last call
+    pub fn poll(&mut self) -> Vec<AnalysisResult<T>> {
+        let latest = self.generation();
+        let results: Vec<_> = self
+            .results
+            .try_iter()
+            .filter(|result| result.generation == latest)
+            .collect();
+        self.pending = self.pending.saturating_sub(results.len());
+        results
+    }
+
+    /// Block until every analysis of the latest submit has finished
+    pub fn wait(&mut self) -> Vec<AnalysisResult<T>> {
+        let latest = self.generation();
+        let mut results = Vec::new();
+        while self.pending > 0 {
+            let Ok(result) = self.results.recv() else {
+                break;
+            };
+            if result.generation == latest {
+                self.pending -= 1;
+                results.push(result);
+            }
+        }
+        results
+    }
+
+    /// Whether analyses of the latest submit are still running or queued
+    pub fn is_busy(&self) -> bool {
+        self.pending > 0
+    }
+
+    /// Generation of the latest submit, 0 before the first
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> Drop for AnalysisRunner<T> {
+    fn drop(&mut self) {
+        // Closing the queue stops the workers once the running jobs return
+        self.jobs = None;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DielectricLayer, Layer, TechnologyInfo};
+
+    fn create_test_stack(layers: usize) -> Arc<ProcessStack> {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("test".to_string()));
+        for i in 0..layers {
+            stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+                format!("d{i}"),
+                1.0,
+                4.2,
+            )));
+        }
+        Arc::new(stack)
+    }
+
+    #[test]
+    fn test_shared_types_are_thread_safe() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ProcessStack>();
+        assert_send_sync::<crate::data::StackDocument>();
+        assert_send_sync::<crate::parser::ItfParser>();
+    }
+
+    #[test]
+    fn test_analysis_runner() {
+        let mut runner = AnalysisRunner::new(2)
+            .with_analysis("layers", |stack: &ProcessStack| stack.get_layer_count())
+            .with_analysis("height", |stack: &ProcessStack| {
+                stack.get_total_height() as usize
+            });
+        assert!(!runner.is_busy());
+        assert_eq!(
+            runner.analysis_names().collect::<Vec<_>>(),
+            ["layers", "height"]
+        );
+
+        let generation = runner.submit(create_test_stack(3));
+        assert_eq!(generation, 1);
+        assert!(runner.is_busy());
+
+        let mut results = runner.wait();
+        assert!(!runner.is_busy());
+        results.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "height");
+        assert_eq!(results[1].output.as_ref().ok(), Some(&3));
+        assert!(results.iter().all(|result| result.generation == 1));
+        assert!(runner.poll().is_empty());
+
+        // Replacing an analysis keeps its place
+        runner.register("layers", |stack: &ProcessStack| {
+            stack.get_layer_count() * 10
+        });
+        assert_eq!(runner.analysis_names().count(), 2);
+        runner.submit(create_test_stack(2));
+        let results = runner.wait();
+        let layers = results.iter().find(|result| result.name == "layers");
+        assert_eq!(layers.unwrap().output.as_ref().ok(), Some(&20));
+    }
+
+    #[test]
+    fn test_superseded_and_panicking_analyses() {
+        let mut runner = AnalysisRunner::new(1).with_analysis("count", |stack: &ProcessStack| {
+            if stack.get_layer_count() == 0 {
+                panic!("empty stack");
+            }
+            stack.get_layer_count()
+        });
+
+        // Only the latest stack is reported
+        runner.submit(create_test_stack(1));
+        runner.submit(create_test_stack(4));
+        let results = runner.wait();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].generation, 2);
+        assert_eq!(results[0].output.as_ref().ok(), Some(&4));
+
+        // A panic becomes an error result and the worker keeps going
+        runner.submit(create_test_stack(0));
+        let results = runner.wait();
+        let panic = results[0].output.as_ref().unwrap_err();
+        assert_eq!(panic.message, "empty stack");
+        runner.submit(create_test_stack(5));
+        assert_eq!(runner.wait()[0].output.as_ref().ok(), Some(&5));
+    }
+}
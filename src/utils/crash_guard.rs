@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+thread_local! {
+    /// Location and backtrace of the last panic on this thread, taken by [`guard`]
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Record the location and backtrace of every panic, then run the previous hook
+fn install_panic_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let location = info
+                .location()
+                .map(|location| location.to_string())
+                .unwrap_or_default();
+            let backtrace = Backtrace::force_capture().to_string();
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some((location, backtrace)));
+            previous(info);
+        }));
+    });
+}
+
+/// A panic caught by [`guard`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaughtPanic {
+    pub message: String,
+    /// Source location of the panic, empty if unknown
+    pub location: String,
+    pub backtrace: String,
+}
+
+/// Run `f`, turning a panic into an error instead of unwinding further
+///
+/// The panic is still reported by the previous panic hook, so it shows up on stderr.
+pub fn guard<T>(f: impl FnOnce() -> T) -> Result<T, CaughtPanic> {
+    install_panic_hook();
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let (location, backtrace) = LAST_PANIC
+            .with(|last| last.borrow_mut().take())
+            .unwrap_or_default();
+        CaughtPanic {
+            message: panic_message(payload.as_ref()),
+            location,
+            backtrace,
+        }
+    })
+}
+
+/// Text of a panic payload, for the `&str` and `String` payloads of `panic!`
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Diagnostic dump of a caught panic, meant to be attached to a bug report
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    /// What the application was doing, such as "loading" or "rendering"
+    pub activity: String,
+    pub file: Option<PathBuf>,
+    pub panic: CaughtPanic,
+    /// One-line description of the loaded stack, if any
+    pub stack_summary: Option<String>,
+}
+
+impl CrashReport {
+    pub fn new(activity: &str, panic: CaughtPanic) -> Self {
+        Self {
+            activity: activity.to_string(),
+            file: None,
+            panic,
+            stack_summary: None,
+        }
+    }
+
+    pub fn with_file(mut self, file: Option<&Path>) -> Self {
+        self.file = file.map(Path::to_path_buf);
+        self
+    }
+
+    pub fn with_stack(mut self, stack: Option<&ProcessStack>) -> Self {
+        self.stack_summary = stack.map(|stack| {
+            let summary = stack.get_process_summary();
+            format!(
+                "{}: {} layers ({} conductors), {} vias, {:.3} um, fingerprint {}",
+                summary.technology_name,
+                summary.total_layers,
+                summary.conductor_layers,
+                summary.via_connections,
+                summary.total_height,
+                summary.fingerprint
+            )
+        });
+        self
+    }
+
+    /// Short description for the error dialog
+    pub fn headline(&self) -> String {
+        format!(
+            "Internal error while {}: {}",
+            self.activity, self.panic.message
+        )
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ITF Viewer {} crash report", env!("CARGO_PKG_VERSION"))?;
+        writeln!(f, "Activity: {}", self.activity)?;
+        match &self.file {
+            Some(file) => writeln!(f, "File: {}", file.display())?,
+            None => writeln!(f, "File: none")?,
+        }
+        if let Some(summary) = &self.stack_summary {
+            writeln!(f, "Stack: {summary}")?;
+        }
+        writeln!(f, "Panic: {}", self.panic.message)?;
+        if !self.panic.location.is_empty() {
+            writeln!(f, "Location: {}", self.panic.location)?;
+        }
+        writeln!(f, "Backtrace:")?;
+        write!(f, "{}", self.panic.backtrace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::TechnologyInfo;
+
+    #[test]
+    fn test_guard() {
+        assert_eq!(guard(|| 42), Ok(42));
+
+        let caught =
+            guard(|| -> usize { panic!("layer {} has no geometry", "metal1") }).unwrap_err();
+        assert_eq!(caught.message, "layer metal1 has no geometry");
+        assert!(caught.location.contains("crash_guard.rs"));
+        assert!(!caught.backtrace.is_empty());
+
+        // Nothing is left over for the next guarded call
+        assert_eq!(guard(|| "ok"), Ok("ok"));
+    }
+
+    #[test]
+    fn test_crash_report() {
+        let caught = guard(|| panic!("bad table")).unwrap_err();
+        let stack = ProcessStack::new(TechnologyInfo::new("n7".to_string()));
+        let report = CrashReport::new("loading", caught)
+            .with_file(Some(Path::new("broken.itf")))
+            .with_stack(Some(&stack));
+
+        assert_eq!(report.headline(), "Internal error while loading: bad table");
+        let text = report.to_string();
+        assert!(text.contains("File: broken.itf"));
+        assert!(text.contains("Stack: n7: 0 layers"));
+        assert!(text.contains("Panic: bad table"));
+        assert!(text.contains("Backtrace:"));
+    }
+}
@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{ConductorLayer, DielectricLayer, Layer, ProcessStack, TechnologyInfo};
+use std::collections::HashSet;
+
+/// Columns of a CSV stack description, in order
+pub const CSV_STACK_COLUMNS: [&str; 5] = ["name", "type", "thickness", "er", "rpsq"];
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CsvImportError {
+    #[error("Line {line}: {message}")]
+    InvalidRow { line: usize, message: String },
+
+    #[error("The CSV file defines no layers")]
+    NoLayers,
+}
+
+/// Build a stack from a spreadsheet-style CSV with one layer per row
+///
+/// Rows hold `name,type,thickness,er,rpsq` and are listed from the top of the stack
+/// down, like the layers of an ITF file. The type is `dielectric` or `conductor` (`d`
+/// and `c` for short); dielectrics need ER and conductors may set RPSQ. A header
+/// row starting with `name`, blank lines and lines starting with `#` are skipped.
+pub fn import_csv_stack(
+    content: &str,
+    technology_name: &str,
+) -> Result<ProcessStack, CsvImportError> {
+    let mut stack = ProcessStack::new(TechnologyInfo::new(technology_name.to_string()));
+    let mut names = HashSet::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let error = |message: String| CsvImportError::InvalidRow {
+            line: index + 1,
+            message,
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let fields = split_csv_row(trimmed);
+        if names.is_empty()
+            && fields
+                .first()
+                .is_some_and(|field| field.eq_ignore_ascii_case(CSV_STACK_COLUMNS[0]))
+        {
+            continue;
+        }
+        if fields.len() < 3 || fields.len() > CSV_STACK_COLUMNS.len() {
+            return Err(error(format!(
+                "Expected {} columns, found {}",
+                CSV_STACK_COLUMNS.join(","),
+                fields.len()
+            )));
+        }
+        let field = |column: usize| fields.get(column).map_or("", |field| field.as_str());
+
+        let name = field(0);
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(error(format!("Invalid layer name '{name}'")));
+        }
+        if !names.insert(name.to_uppercase()) {
+            return Err(error(format!("Duplicate layer '{name}'")));
+        }
+
+        let number = |column: usize| -> Result<Option<f64>, CsvImportError> {
+            let text = field(column);
+            if text.is_empty() {
+                return Ok(None);
+            }
+            match text.parse::<f64>() {
+                Ok(value) if value.is_finite() && value >= 0.0 => Ok(Some(value)),
+                _ => Err(error(format!(
+                    "Invalid {} '{text}' for '{name}'",
+                    CSV_STACK_COLUMNS[column]
+                ))),
+            }
+        };
+        let thickness =
+            number(2)?.ok_or_else(|| error(format!("Missing thickness for '{name}'")))?;
+        let er = number(3)?;
+        let rpsq = number(4)?;
+
+        let layer = match field(1).to_ascii_lowercase().as_str() {
+            "dielectric" | "d" => {
+                let er = er.ok_or_else(|| error(format!("Missing er for dielectric '{name}'")))?;
+                if rpsq.is_some() {
+                    return Err(error(format!("Dielectric '{name}' cannot have an rpsq")));
+                }
+                Layer::Dielectric(DielectricLayer::new(name.to_string(), thickness, er))
+            }
+            "conductor" | "c" => {
+                let mut conductor = ConductorLayer::new(name.to_string(), thickness);
+                conductor.electrical_props.rpsq = rpsq;
+                conductor.physical_props.dielectric_constant = er;
+                Layer::Conductor(Box::new(conductor))
+            }
+            other => {
+                return Err(error(format!(
+                    "Unknown layer type '{other}', expected dielectric or conductor"
+                )))
+            }
+        };
+        stack.add_layer(layer);
+    }
+
+    if stack.layers.is_empty() {
+        return Err(CsvImportError::NoLayers);
+    }
+    Ok(stack)
+}
+
+/// Trimmed fields of a CSV row, honoring double-quoted fields with `""` escapes
+fn split_csv_row(row: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_csv_stack() {
+        let csv = "\
+name,type,thickness,er,rpsq
+# top of the stack first
+passivation,dielectric,0.5,7.0,
+ild1,D,1.0,4.2
+metal1,conductor,0.3,,0.08
+\"substrate\",dielectric,2.0,11.9,
+";
+        let stack = import_csv_stack(csv, "prototype").unwrap();
+        assert_eq!(stack.technology_info.name, "prototype");
+        assert_eq!(stack.get_layer_count(), 4);
+        assert_eq!(stack.layers[0].name(), "passivation");
+
+        let Some(Layer::Conductor(metal1)) = stack.get_layer("metal1") else {
+            panic!("metal1 should be a conductor");
+        };
+        assert_eq!(metal1.thickness, 0.3);
+        assert_eq!(metal1.electrical_props.rpsq, Some(0.08));
+        let Some(Layer::Dielectric(substrate)) = stack.get_layer("substrate") else {
+            panic!("substrate should be a dielectric");
+        };
+        assert_eq!(substrate.dielectric_constant, 11.9);
+        assert_eq!(stack.get_layer_z_range("metal1"), Some((2.0, 2.3)));
+    }
+
+    #[test]
+    fn test_import_csv_stack_errors() {
+        let error = |csv: &str| import_csv_stack(csv, "bad").unwrap_err().to_string();
+
+        assert_eq!(
+            error("ild,dielectric,1.0\n"),
+            "Line 1: Missing er for dielectric 'ild'"
+        );
+        assert_eq!(
+            error("ild,dielectric,1.0,4.2\nild,conductor,0.2\n"),
+            "Line 2: Duplicate layer 'ild'"
+        );
+        assert_eq!(
+            error("m1,metal,0.2\n"),
+            "Line 1: Unknown layer type 'metal', expected dielectric or conductor"
+        );
+        assert_eq!(
+            error("m1,conductor,-0.2\n"),
+            "Line 1: Invalid thickness '-0.2' for 'm1'"
+        );
+        assert_eq!(error("m1,conductor\n").split(':').next(), Some("Line 1"));
+        assert!(matches!(
+            import_csv_stack("name,type,thickness,er,rpsq\n", "empty"),
+            Err(CsvImportError::NoLayers)
+        ));
+        assert_eq!(
+            split_csv_row(r#"a, "b,""c""" ,d"#),
+            vec!["a", "b,\"c\"", "d"]
+        );
+    }
+}
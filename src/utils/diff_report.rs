@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{PropertyChange, StackDiff};
+use std::fmt::Write;
+
+/// Plain text report of a stack diff, one line per difference
+///
+/// `old_label` and `new_label` name the compared stacks, typically their file paths.
+pub fn diff_report_text(diff: &StackDiff, old_label: &str, new_label: &str) -> String {
+    let mut report = format!("--- {old_label}\n+++ {new_label}\n");
+    if diff.is_empty() {
+        report.push_str("No differences\n");
+        return report;
+    }
+
+    for (title, names, marker) in [
+        ("Removed layers", &diff.removed_layers, '-'),
+        ("Added layers", &diff.added_layers, '+'),
+        ("Removed vias", &diff.removed_vias, '-'),
+        ("Added vias", &diff.added_vias, '+'),
+        ("Removed regions", &diff.removed_regions, '-'),
+        ("Added regions", &diff.added_regions, '+'),
+    ] {
+        if !names.is_empty() {
+            let _ = writeln!(report, "\n{title}:");
+            for name in names {
+                let _ = writeln!(report, "{marker} {name}");
+            }
+        }
+    }
+    for (title, changes) in [
+        ("Changed layers", &diff.layer_changes),
+        ("Changed vias", &diff.via_changes),
+        ("Changed regions", &diff.region_changes),
+    ] {
+        if !changes.is_empty() {
+            let _ = writeln!(report, "\n{title}:");
+            for change in changes {
+                let _ = writeln!(report, "~ {change}");
+            }
+        }
+    }
+    report
+}
+
+/// Standalone HTML page of a stack diff, with one table per kind of difference
+pub fn diff_report_html(diff: &StackDiff, old_label: &str, new_label: &str) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>ITF diff</title>\n\
+         <style>\n\
+         body { font-family: sans-serif; }\n\
+         table { border-collapse: collapse; margin-bottom: 1em; }\n\
+         th, td { border: 1px solid #ccc; padding: 2px 8px; text-align: left; }\n\
+         .added { color: #080; }\n\
+         .removed { color: #b00; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    let _ = writeln!(
+        html,
+        "<h1>ITF diff</h1>\n<p>Old: <code>{}</code><br>New: <code>{}</code></p>",
+        escape_html(old_label),
+        escape_html(new_label)
+    );
+
+    if diff.is_empty() {
+        html.push_str("<p>No differences</p>\n");
+    }
+    for (title, names, class) in [
+        ("Removed layers", &diff.removed_layers, "removed"),
+        ("Added layers", &diff.added_layers, "added"),
+        ("Removed vias", &diff.removed_vias, "removed"),
+        ("Added vias", &diff.added_vias, "added"),
+        ("Removed regions", &diff.removed_regions, "removed"),
+        ("Added regions", &diff.added_regions, "added"),
+    ] {
+        if !names.is_empty() {
+            let _ = writeln!(html, "<h2>{title}</h2>\n<ul class=\"{class}\">");
+            for name in names {
+                let _ = writeln!(html, "<li>{}</li>", escape_html(name));
+            }
+            html.push_str("</ul>\n");
+        }
+    }
+    for (title, changes) in [
+        ("Changed layers", &diff.layer_changes),
+        ("Changed vias", &diff.via_changes),
+        ("Changed regions", &diff.region_changes),
+    ] {
+        if !changes.is_empty() {
+            let _ = writeln!(html, "<h2>{title}</h2>");
+            html.push_str(&changes_table(changes));
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn changes_table(changes: &[PropertyChange]) -> String {
+    let mut table = String::from(
+        "<table>\n<tr><th>Name</th><th>Property</th><th>Old</th><th>New</th><th>Delta</th></tr>\n",
+    );
+    for change in changes {
+        let delta = change
+            .percent_delta()
+            .map(|percent| format!("{percent:+.1}%"))
+            .unwrap_or_default();
+        let _ = writeln!(
+            table,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{delta}</td></tr>",
+            escape_html(&change.item),
+            escape_html(&change.property),
+            escape_html(&change.old_value.to_string()),
+            escape_html(&change.new_value.to_string()),
+        );
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::DiffValue;
+
+    fn sample_diff() -> StackDiff {
+        StackDiff {
+            added_layers: vec!["rdl".to_string()],
+            removed_vias: vec!["via<1>".to_string()],
+            layer_changes: vec![PropertyChange {
+                item: "metal1".to_string(),
+                property: "THICKNESS".to_string(),
+                old_value: DiffValue::Number(0.2),
+                new_value: DiffValue::Number(0.25),
+            }],
+            ..StackDiff::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_report_text() {
+        let report = diff_report_text(&sample_diff(), "a.itf", "b.itf");
+        assert!(report.starts_with("--- a.itf\n+++ b.itf\n"));
+        assert!(report.contains("Added layers:\n+ rdl\n"));
+        assert!(report.contains("Removed vias:\n- via<1>\n"));
+        assert!(report.contains("~ metal1 THICKNESS: 0.2 -> 0.25 (+25.0%)\n"));
+
+        let report = diff_report_text(&StackDiff::default(), "a.itf", "a.itf");
+        assert!(report.ends_with("No differences\n"));
+    }
+
+    #[test]
+    fn test_diff_report_html() {
+        let html = diff_report_html(&sample_diff(), "a.itf", "b&c.itf");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<code>b&amp;c.itf</code>"));
+        assert!(html.contains("<li>via&lt;1&gt;</li>"));
+        assert!(html
+            .contains("<td>metal1</td><td>THICKNESS</td><td>0.2</td><td>0.25</td><td>+25.0%</td>"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+}
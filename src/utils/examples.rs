@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use crate::parser::{parse_itf_file, ParseError};
+
+/// Small ITF files embedded in the binary, to explore the viewer without a foundry file
+///
+/// They are the sanitized files of the test suite, so examples always parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExampleStack {
+    /// One poly and three metal layers with simple vias
+    Simple,
+    /// One poly, seven metals and an aluminum pad layer with via stacks
+    Complex,
+}
+
+impl ExampleStack {
+    pub const ALL: [ExampleStack; 2] = [ExampleStack::Simple, ExampleStack::Complex];
+
+    /// Short identifier used on the command line
+    pub fn id(&self) -> &'static str {
+        match self {
+            ExampleStack::Simple => "simple",
+            ExampleStack::Complex => "complex",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|example| example.id().eq_ignore_ascii_case(id))
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExampleStack::Simple => "Simple 1P3M",
+            ExampleStack::Complex => "Complex 1P7M",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ExampleStack::Simple => "Poly and three metals, a quick first look",
+            ExampleStack::Complex => "Seven metals and a pad layer, closer to a real stack",
+        }
+    }
+
+    /// Name of the file the example was taken from
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ExampleStack::Simple => "simple_1p3m.itf",
+            ExampleStack::Complex => "complex_1p7m.itf",
+        }
+    }
+
+    /// ITF text of the example
+    pub fn source(&self) -> &'static str {
+        match self {
+            ExampleStack::Simple => include_str!("../../tests/data/simple_1p3m.itf"),
+            ExampleStack::Complex => include_str!("../../tests/data/complex_1p7m.itf"),
+        }
+    }
+
+    pub fn load(&self) -> Result<ProcessStack, ParseError> {
+        parse_itf_file(self.source())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_load() {
+        for example in ExampleStack::ALL {
+            let stack = example.load().unwrap();
+            assert!(stack.get_conductor_count() >= 4);
+            assert!(stack.validate_stack().is_ok());
+            assert_eq!(ExampleStack::from_id(example.id()), Some(example));
+        }
+        assert_eq!(ExampleStack::from_id("none"), None);
+    }
+}
@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::parser::decode_itf_bytes;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+const RECORD_SEPARATOR: char = '\u{1e}';
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Commit that changed a file, newest first in [`file_history`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileRevision {
+    pub commit: String,
+    pub short_commit: String,
+    /// Author date as YYYY-MM-DD
+    pub date: String,
+    pub author: String,
+    pub summary: String,
+    /// Path of the file in this commit relative to the repository root, which differs from
+    /// the current one across renames
+    pub path: String,
+}
+
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error("Failed to run git: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{} is not in a git repository", .0.display())]
+    NotInRepository(PathBuf),
+
+    #[error("git failed: {0}")]
+    Git(String),
+}
+
+/// Run git in the directory of `file`
+fn git(file: &Path, args: &[&str]) -> Result<Vec<u8>, HistoryError> {
+    let directory = file
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()?;
+    if output.status.success() {
+        return Ok(output.stdout);
+    }
+    let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if message.contains("not a git repository") {
+        Err(HistoryError::NotInRepository(file.to_path_buf()))
+    } else {
+        Err(HistoryError::Git(message))
+    }
+}
+
+/// Commits that changed `file`, newest first, following renames
+pub fn file_history(file: &Path) -> Result<Vec<FileRevision>, HistoryError> {
+    let name = file
+        .file_name()
+        .ok_or_else(|| HistoryError::Git(format!("{} is not a file", file.display())))?;
+    let format = format!(
+        "--format={RECORD_SEPARATOR}%H{FIELD_SEPARATOR}%h{FIELD_SEPARATOR}%ad\
+         {FIELD_SEPARATOR}%an{FIELD_SEPARATOR}%s"
+    );
+    let output = git(
+        file,
+        &[
+            "log",
+            "--follow",
+            "--date=short",
+            "--name-only",
+            &format,
+            "--",
+            &name.to_string_lossy(),
+        ],
+    )?;
+    Ok(parse_log(&String::from_utf8_lossy(&output)))
+}
+
+/// Revisions in the output of `git log --name-only` with the format of [`file_history`]
+fn parse_log(output: &str) -> Vec<FileRevision> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let mut lines = record.lines().filter(|line| !line.trim().is_empty());
+            let mut fields = lines.next()?.split(FIELD_SEPARATOR);
+            let mut field = || fields.next().map(str::to_string);
+            Some(FileRevision {
+                commit: field()?,
+                short_commit: field()?,
+                date: field()?,
+                author: field()?,
+                summary: field().unwrap_or_default(),
+                path: lines.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Text of the file at `revision`, decoded like a techfile read from disk
+pub fn read_revision(file: &Path, revision: &FileRevision) -> Result<String, HistoryError> {
+    let object = format!("{}:{}", revision.commit, revision.path);
+    let bytes = git(file, &["show", &object])?;
+    Ok(decode_itf_bytes(&bytes).text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = "\u{1e}aaaa1111\u{1f}aaaa\u{1f}2025-03-02\u{1f}Ann\u{1f}Thinner metal1\n\n\
+                      tech/stack.itf\n\
+                      \u{1e}bbbb2222\u{1f}bbbb\u{1f}2025-01-10\u{1f}Bo\u{1f}\n\nold.itf\n";
+        let revisions = parse_log(output);
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].commit, "aaaa1111");
+        assert_eq!(revisions[0].summary, "Thinner metal1");
+        assert_eq!(revisions[0].path, "tech/stack.itf");
+        assert_eq!(revisions[1].date, "2025-01-10");
+        assert_eq!(revisions[1].summary, "");
+        assert_eq!(revisions[1].path, "old.itf");
+        assert!(parse_log("").is_empty());
+    }
+}
@@ -54,12 +54,13 @@ pub fn load_itf_file<P: AsRef<Path>>(file_path: P) -> Result<ProcessStack, FileE
     }
 
     // Read file content
-    let content =
-        fs::read_to_string(path).map_err(|e| FileError::ReadError(path.to_path_buf(), e))?;
+    let source = crate::parser::read_itf_source(path)
+        .map_err(|e| FileError::ReadError(path.to_path_buf(), e))?;
 
     // Parse content
-    let stack = crate::parser::parse_itf_file(&content)
+    let mut stack = crate::parser::parse_itf_file(&source.text)
         .map_err(|e| FileError::ParseError(path.to_path_buf(), e))?;
+    source.add_diagnostics(&mut stack);
 
     Ok(stack)
 }
@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{CrtVsSiWidthTable, Layer, LookupTable2D, ProcessStack, PropValue};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Write a stack back out as ITF text
+///
+/// Covers everything the parser reads: the technology header, substrate and field regions,
+/// the layer properties and lookup tables including passed-through extra properties, and
+/// vias, so parsing the text gives a stack of the same fingerprint. Layers keep their list
+/// order; layers the parser auto-created and via values left unset are not written.
+pub fn write_itf(stack: &ProcessStack) -> String {
+    let info = &stack.technology_info;
+    let mut itf = String::new();
+
+    let _ = writeln!(itf, "TECHNOLOGY = {}", info.name);
+    let header = [
+        ("GLOBAL_TEMPERATURE", info.global_temperature),
+        ("BACKGROUND_ER", info.background_er),
+        ("HALF_NODE_SCALE_FACTOR", info.half_node_scale_factor),
+        (
+            "DROP_FACTOR_LATERAL_SPACING",
+            info.drop_factor_lateral_spacing,
+        ),
+    ];
+    for (keyword, value) in header {
+        if let Some(value) = value {
+            let _ = writeln!(itf, "{keyword} = {value}");
+        }
+    }
+    if let Some(ref direction) = info.reference_direction {
+        let _ = writeln!(itf, "REFERENCE_DIRECTION = {direction}");
+    }
+    if let Some(use_si_density) = info.use_si_density {
+        let _ = writeln!(
+            itf,
+            "USE_SI_DENSITY = {}",
+            if use_si_density { "YES" } else { "NO" }
+        );
+    }
+    itf.push('\n');
+
+    for region in &stack.er_regions {
+        let thickness = region
+            .thickness
+            .map(|thickness| format!(" THICKNESS={thickness}"))
+            .unwrap_or_default();
+        let _ = writeln!(
+            itf,
+            "{} {} {{ER={}{thickness}{}}}",
+            region.kind.keyword(),
+            region.name,
+            region.dielectric_constant,
+            extra_fields(&region.extra_properties)
+        );
+    }
+    if !stack.er_regions.is_empty() {
+        itf.push('\n');
+    }
+
+    for layer in stack.layers.iter().filter(|layer| !layer.is_auto_created()) {
+        match layer {
+            Layer::Dielectric(dielectric) => {
+                let mut fields = String::new();
+                if let Some(ref measured_from) = dielectric.measured_from {
+                    let _ = write!(fields, " MEASURED_FROM={measured_from}");
+                }
+                for (keyword, value) in [("SW_T", dielectric.sw_t), ("TW_T", dielectric.tw_t)] {
+                    if let Some(value) = value {
+                        let _ = write!(fields, " {keyword}={value}");
+                    }
+                }
+                let _ = writeln!(
+                    itf,
+                    "DIELECTRIC {} {{THICKNESS={} ER={}{fields}{}}}",
+                    dielectric.name,
+                    dielectric.thickness,
+                    dielectric.dielectric_constant,
+                    extra_fields(&dielectric.extra_properties)
+                );
+            }
+            Layer::Conductor(conductor) => {
+                let electrical = &conductor.electrical_props;
+                let physical = &conductor.physical_props;
+                let gate = &conductor.gate_props;
+                let fields = [
+                    ("CRT1", electrical.crt1),
+                    ("CRT2", electrical.crt2),
+                    ("RPSQ", electrical.rpsq),
+                    ("RPV", electrical.rpv),
+                    ("WMIN", physical.width_min),
+                    ("SMIN", physical.spacing_min),
+                    ("SIDE_TANGENT", physical.side_tangent),
+                    ("ER", physical.dielectric_constant),
+                    ("GATE_TO_CONTACT_SMIN", gate.gate_to_contact_smin),
+                    ("RESISTIVE_ONLY_ETCH", conductor.resistive_only_etch),
+                    ("CAPACITIVE_ONLY_ETCH", conductor.capacitive_only_etch),
+                ];
+
+                let _ = writeln!(itf, "CONDUCTOR {} {{", conductor.name);
+                let _ = writeln!(itf, "    THICKNESS={}", conductor.thickness);
+                for (keyword, value) in fields {
+                    if let Some(value) = value {
+                        let _ = writeln!(itf, "    {keyword}={value}");
+                    }
+                }
+                if let Some(ref layer_type) = gate.layer_type {
+                    let _ = writeln!(itf, "    LAYER_TYPE={layer_type}");
+                }
+                if gate.gate_forming_layer {
+                    let _ = writeln!(itf, "    GATE_FORMING_LAYER=YES");
+                }
+                if gate.has_gate_to_diffusion_cap {
+                    // Only the presence of the block is kept
+                    let _ = writeln!(itf, "    GATE_TO_DIFFUSION_CAP {{ }}");
+                }
+
+                let tables = [
+                    (
+                        "RHO_VS_WIDTH_AND_SPACING",
+                        &conductor.rho_vs_width_spacing,
+                        ["WIDTHS", "SPACINGS"],
+                    ),
+                    (
+                        "RHO_VS_SI_WIDTH_AND_THICKNESS",
+                        &conductor.rho_vs_si_width_thickness,
+                        ["WIDTH", "THICKNESS"],
+                    ),
+                    (
+                        "ETCH_VS_WIDTH_AND_SPACING",
+                        &conductor.etch_vs_width_spacing,
+                        ["WIDTHS", "SPACINGS"],
+                    ),
+                    (
+                        "THICKNESS_VS_WIDTH_AND_SPACING",
+                        &conductor.thickness_vs_width_spacing,
+                        ["WIDTHS", "SPACINGS"],
+                    ),
+                ];
+                for (keyword, table, axes) in tables {
+                    if let Some(table) = table {
+                        write_table(&mut itf, keyword, axes, table);
+                    }
+                }
+                if let Some(ref table) = conductor.crt_vs_si_width {
+                    write_crt_table(&mut itf, table);
+                }
+
+                let extra = extra_fields(&conductor.extra_properties);
+                if !extra.is_empty() {
+                    let _ = writeln!(itf, "   {extra}");
+                }
+                let _ = writeln!(itf, "}}");
+            }
+        }
+    }
+
+    if !stack.via_stack.is_empty() {
+        itf.push('\n');
+    }
+    for via in stack.via_stack.iter() {
+        let mut fields = String::new();
+        for (keyword, layer) in [("FROM", &via.from_layer), ("TO", &via.to_layer)] {
+            if !layer.is_empty() {
+                let _ = write!(fields, " {keyword}={layer}");
+            }
+        }
+        // The parser leaves an omitted AREA or RPV at 0
+        for (keyword, value) in [("AREA", via.area), ("RPV", via.resistance_per_via)] {
+            if value != 0.0 {
+                let _ = write!(fields, " {keyword}={value}");
+            }
+        }
+        let _ = writeln!(
+            itf,
+            "VIA {} {{{fields}{} }}",
+            via.name,
+            extra_fields(&via.extra_properties)
+        );
+    }
+
+    itf
+}
+
+/// `KEYWORD { AXIS { ... } AXIS { ... } VALUES { ... } }`, one row of values per line
+fn write_table(itf: &mut String, keyword: &str, axes: [&str; 2], table: &LookupTable2D) {
+    let _ = writeln!(itf, "    {keyword} {{");
+    let _ = writeln!(itf, "        {} {{{}}}", axes[0], numbers(&table.widths));
+    let _ = writeln!(itf, "        {} {{{}}}", axes[1], numbers(&table.spacings));
+    let _ = writeln!(itf, "        VALUES {{");
+    for row in &table.values {
+        let _ = writeln!(itf, "            {}", numbers(row));
+    }
+    let _ = writeln!(itf, "        }}");
+    let _ = writeln!(itf, "    }}");
+}
+
+/// `CRT_VS_SI_WIDTH { (width, crt1, crt2) ... }`
+fn write_crt_table(itf: &mut String, table: &CrtVsSiWidthTable) {
+    let _ = writeln!(itf, "    CRT_VS_SI_WIDTH {{");
+    for ((width, crt1), crt2) in table
+        .widths
+        .iter()
+        .zip(&table.crt1_values)
+        .zip(&table.crt2_values)
+    {
+        let _ = writeln!(itf, "        ({width}, {crt1}, {crt2})");
+    }
+    let _ = writeln!(itf, "    }}");
+}
+
+fn numbers(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(f64::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// ` KEY=value` for each extra property, sorted by key for a stable output
+fn extra_fields(properties: &HashMap<String, PropValue>) -> String {
+    let mut keys: Vec<&String> = properties.keys().collect();
+    keys.sort();
+    keys.iter()
+        .map(|key| format!(" {key}={}", properties[*key]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_itf_file;
+    use crate::utils::generate_random_stack;
+
+    #[test]
+    fn test_write_itf_round_trip() {
+        let stack = generate_random_stack(11, 25);
+        let parsed = parse_itf_file(&write_itf(&stack)).unwrap();
+
+        assert_eq!(parsed.technology_info, stack.technology_info);
+        assert_eq!(parsed.get_layer_count(), stack.get_layer_count());
+        for (written, read) in stack.layers.iter().zip(&parsed.layers) {
+            assert_eq!(written, read);
+        }
+        assert_eq!(parsed.get_via_count(), stack.get_via_count());
+        assert_eq!(parsed.via_stack.vias[0].area, stack.via_stack.vias[0].area);
+    }
+
+    #[test]
+    fn test_write_itf_keeps_extra_properties() {
+        let content = "TECHNOLOGY = extra\n\
+            DIELECTRIC ild { THICKNESS = 0.3 ER = 4.2 STRESS = -1e9 }\n\
+            CONDUCTOR metal1 { THICKNESS = 0.1 NOTE = tensile CTE = 1.7e-5 }\n\
+            DIELECTRIC sub { THICKNESS = 1 ER = 11.9 }\n\
+            SUBSTRATE psub { ER = 11.9 THICKNESS = 5 DOPING = p }\n\
+            FIELD { ER = 3.9 }\n\
+            VIA via1 { FROM = metal1 TO = substrate AREA = 0.01 RPV = 2 EM = 0.4 }\n";
+        let stack = parse_itf_file(content).unwrap();
+        let parsed = parse_itf_file(&write_itf(&stack)).unwrap();
+
+        for (written, read) in stack.layers.iter().zip(&parsed.layers) {
+            assert_eq!(written.extra_properties(), read.extra_properties());
+        }
+        assert_eq!(
+            parsed.get_layer("metal1").unwrap().extra_properties().len(),
+            2
+        );
+        assert_eq!(
+            parsed.via_stack.vias[0].extra_properties,
+            stack.via_stack.vias[0].extra_properties
+        );
+        assert_eq!(parsed.er_regions, stack.er_regions);
+        assert_eq!(parsed.er_regions[0].thickness, Some(5.0));
+        assert_eq!(parsed.er_regions[1].name, "field");
+    }
+
+    #[test]
+    fn test_write_itf_keeps_test_data_fingerprints() {
+        let mut paths: Vec<_> = std::fs::read_dir("tests/data")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "itf"))
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty());
+        for path in paths {
+            let stack = parse_itf_file(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            let written = write_itf(&stack);
+            let parsed = parse_itf_file(&written).unwrap();
+            assert_eq!(
+                parsed.fingerprint(),
+                stack.fingerprint(),
+                "{}",
+                path.display()
+            );
+            assert_eq!(parsed.get_layer_count(), stack.get_layer_count());
+        }
+    }
+
+    #[test]
+    fn test_write_itf_skips_placeholders_and_unset_via_values() {
+        let stack = parse_itf_file(
+            "TECHNOLOGY = sparse\n\
+             CONDUCTOR metal1 { THICKNESS = 0.1 }\n\
+             VIA via1 { FROM = metal1 TO = poly }\n",
+        )
+        .unwrap();
+        assert!(stack.layers.iter().any(Layer::is_auto_created));
+
+        let written = write_itf(&stack);
+        assert!(written.contains("VIA via1 { FROM=metal1 TO=poly }"));
+        assert!(!written.contains("DIELECTRIC"));
+        assert_eq!(
+            parse_itf_file(&written).unwrap().fingerprint(),
+            stack.fingerprint()
+        );
+    }
+}
@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use std::fmt::Write;
+
+/// Minimal JSON document model for the writers that emit JSON or YAML
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub(crate) fn string(value: impl Into<String>) -> Self {
+        Json::String(value.into())
+    }
+
+    /// Number, or null when unset
+    pub(crate) fn number(value: Option<f64>) -> Self {
+        value.map_or(Json::Null, Json::Number)
+    }
+
+    pub(crate) fn numbers(values: &[f64]) -> Self {
+        Json::Array(values.iter().copied().map(Json::Number).collect())
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    /// Block-style YAML, with arrays of scalars kept on one line
+    pub(crate) fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        match self {
+            Json::Object(entries) if !entries.is_empty() => {
+                write_yaml_entries(&mut out, entries, 0, false)
+            }
+            Json::Array(items) if !self.is_flow() => write_yaml_items(&mut out, items, 0),
+            _ => {
+                self.write_flow(&mut out);
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    pub(crate) fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Json::Integer(value) => out.push_str(&value.to_string()),
+            // JSON has no NaN or infinity
+            Json::Number(value) if !value.is_finite() => out.push_str("null"),
+            Json::Number(value) => out.push_str(&value.to_string()),
+            Json::String(value) => write_json_string(out, value),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            Json::Array(items) => {
+                out.push_str("[\n");
+                for (index, item) in items.iter().enumerate() {
+                    pad(out, indent + 1);
+                    item.write(out, indent + 1);
+                    out.push_str(if index + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push(']');
+            }
+            Json::Object(entries) if entries.is_empty() => out.push_str("{}"),
+            Json::Object(entries) => {
+                out.push_str("{\n");
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    pad(out, indent + 1);
+                    write_json_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                    out.push_str(if index + 1 < entries.len() {
+                        ",\n"
+                    } else {
+                        "\n"
+                    });
+                }
+                pad(out, indent);
+                out.push('}');
+            }
+        }
+    }
+
+    /// Whether the value fits on one line in YAML
+    fn is_flow(&self) -> bool {
+        match self {
+            Json::Array(items) => items.iter().all(Json::is_flow),
+            Json::Object(entries) => entries.is_empty(),
+            _ => true,
+        }
+    }
+
+    /// One-line form, which is both JSON and YAML
+    fn write_flow(&self, out: &mut String) {
+        match self {
+            Json::Array(items) => {
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write_flow(out);
+                }
+                out.push(']');
+            }
+            value => value.write(out, 0),
+        }
+    }
+}
+
+fn pad(out: &mut String, level: usize) {
+    out.push_str(&"  ".repeat(level));
+}
+
+/// Mapping entries, the first one continuing the line of a `- ` list marker if `inline_first`
+fn write_yaml_entries(
+    out: &mut String,
+    entries: &[(String, Json)],
+    indent: usize,
+    inline_first: bool,
+) {
+    for (index, (key, value)) in entries.iter().enumerate() {
+        if index > 0 || !inline_first {
+            pad(out, indent);
+        }
+        if !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            out.push_str(key);
+        } else {
+            write_json_string(out, key);
+        }
+        out.push(':');
+        match value {
+            Json::Object(entries) if !value.is_flow() => {
+                out.push('\n');
+                write_yaml_entries(out, entries, indent + 1, false);
+            }
+            Json::Array(items) if !value.is_flow() => {
+                out.push('\n');
+                write_yaml_items(out, items, indent + 1);
+            }
+            _ => {
+                out.push(' ');
+                value.write_flow(out);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+fn write_yaml_items(out: &mut String, items: &[Json], indent: usize) {
+    for item in items {
+        pad(out, indent);
+        match item {
+            Json::Object(entries) if !item.is_flow() => {
+                out.push_str("- ");
+                write_yaml_entries(out, entries, indent + 1, true);
+            }
+            Json::Array(inner) if !item.is_flow() => {
+                out.push_str("-\n");
+                write_yaml_items(out, inner, indent + 1);
+            }
+            _ => {
+                out.push_str("- ");
+                item.write_flow(out);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_and_yaml_output() {
+        let document = Json::Object(vec![
+            ("name".to_string(), Json::string("metal \"1\"")),
+            ("thickness".to_string(), Json::Number(0.25)),
+            ("rpsq".to_string(), Json::number(None)),
+            ("widths".to_string(), Json::numbers(&[0.1, 0.2])),
+            (
+                "vias".to_string(),
+                Json::Array(vec![Json::Object(vec![
+                    ("name".to_string(), Json::string("via1")),
+                    ("rpv".to_string(), Json::Number(f64::NAN)),
+                ])]),
+            ),
+            (
+                "geometry".to_string(),
+                Json::Object(vec![("z top".to_string(), Json::Integer(2))]),
+            ),
+        ]);
+
+        assert_eq!(
+            document.to_json(),
+            "{\n  \"name\": \"metal \\\"1\\\"\",\n  \"thickness\": 0.25,\n  \"rpsq\": null,\n  \"widths\": [\n    0.1,\n    0.2\n  ],\n  \"vias\": [\n    {\n      \"name\": \"via1\",\n      \"rpv\": null\n    }\n  ],\n  \"geometry\": {\n    \"z top\": 2\n  }\n}\n"
+        );
+        assert_eq!(
+            document.to_yaml(),
+            "name: \"metal \\\"1\\\"\"\nthickness: 0.25\nrpsq: null\nwidths: [0.1, 0.2]\nvias:\n  - name: \"via1\"\n    rpv: null\ngeometry:\n  \"z top\": 2\n"
+        );
+    }
+}
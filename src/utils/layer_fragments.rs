@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{CrtVsSiWidthTable, Layer, LookupTable2D, ProcessStack};
+use crate::utils::json::Json;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FragmentError {
+    #[error("Failed to create directory {path}: {source}")]
+    CreateDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// File format of the per-layer fragments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FragmentFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl FragmentFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            FragmentFormat::Json => "json",
+            FragmentFormat::Yaml => "yaml",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(FragmentFormat::Json),
+            "yaml" | "yml" => Some(FragmentFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Documentation fragment of one layer: name, geometry, electrical values and tables
+///
+/// Every key is always present, unset values are null, so documentation templates can
+/// rely on one structure for all layers. Lengths are in um.
+pub fn layer_fragment(stack: &ProcessStack, layer: &Layer, format: FragmentFormat) -> String {
+    let document = fragment_json(stack, layer);
+    match format {
+        FragmentFormat::Json => document.to_json(),
+        FragmentFormat::Yaml => document.to_yaml(),
+    }
+}
+
+/// Write one fragment per layer into `dir`, creating it if needed, and return the paths
+///
+/// Files are named after the layer with characters unsafe in file names replaced by `_`.
+/// A name that cleans up to the stem of an earlier file, ignoring case for file systems
+/// that do, gets a `_1`, `_2`, ... suffix instead of overwriting it. Layers auto-created for dangling vias are not part of the techfile and are skipped.
+pub fn export_layer_fragments(
+    stack: &ProcessStack,
+    dir: &Path,
+    format: FragmentFormat,
+) -> Result<Vec<PathBuf>, FragmentError> {
+    std::fs::create_dir_all(dir).map_err(|source| FragmentError::CreateDir {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+
+    let mut paths = Vec::new();
+    let mut used_stems = HashSet::new();
+    for layer in stack.layers.iter().filter(|layer| !layer.is_auto_created()) {
+        let stem = unique_stem(fragment_file_stem(layer.name()), &mut used_stems);
+        let path = dir.join(format!("{stem}.{}", format.extension()));
+        std::fs::write(&path, layer_fragment(stack, layer, format)).map_err(|source| {
+            FragmentError::Write {
+                path: path.clone(),
+                source,
+            }
+        })?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn fragment_file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.trim_matches('.').is_empty() {
+        "_".to_string()
+    } else {
+        stem
+    }
+}
+
+/// `stem`, or `stem_<n>` with the lowest `n` not in `used`, which it is added to
+fn unique_stem(stem: String, used: &mut HashSet<String>) -> String {
+    let unique = std::iter::once(stem.clone())
+        .chain((1..).map(|n| format!("{stem}_{n}")))
+        .find(|candidate| !used.contains(&candidate.to_lowercase()))
+        .unwrap_or(stem);
+    used.insert(unique.to_lowercase());
+    unique
+}
+
+fn entry(key: &str, value: Json) -> (String, Json) {
+    (key.to_string(), value)
+}
+
+fn fragment_json(stack: &ProcessStack, layer: &Layer) -> Json {
+    // Position from the top of the stack, as listed in the techfile
+    let order = stack
+        .layers
+        .iter()
+        .position(|other| other.name() == layer.name())
+        .map_or(Json::Null, |index| Json::Integer(index as i64));
+    let z_range = stack.get_layer_z_range(layer.name());
+
+    let mut geometry = vec![
+        entry("thickness", Json::Number(layer.thickness())),
+        entry("z_bottom", Json::number(z_range.map(|(bottom, _)| bottom))),
+        entry("z_top", Json::number(z_range.map(|(_, top)| top))),
+    ];
+    let (layer_type, electrical, tables) = match layer {
+        Layer::Dielectric(dielectric) => {
+            geometry.extend([
+                entry(
+                    "measured_from",
+                    dielectric
+                        .measured_from
+                        .as_ref()
+                        .map_or(Json::Null, Json::string),
+                ),
+                entry("sw_t", Json::number(dielectric.sw_t)),
+                entry("tw_t", Json::number(dielectric.tw_t)),
+            ]);
+            let electrical = vec![entry(
+                "dielectric_constant",
+                Json::Number(dielectric.dielectric_constant),
+            )];
+            ("dielectric", electrical, Vec::new())
+        }
+        Layer::Conductor(conductor) => {
+            let physical = &conductor.physical_props;
+            geometry.extend([
+                entry("width_min", Json::number(physical.width_min)),
+                entry("spacing_min", Json::number(physical.spacing_min)),
+                entry("side_tangent", Json::number(physical.side_tangent)),
+            ]);
+            let electrical_props = &conductor.electrical_props;
+            let electrical = vec![
+                entry("rpsq", Json::number(electrical_props.rpsq)),
+                entry("crt1", Json::number(electrical_props.crt1)),
+                entry("crt2", Json::number(electrical_props.crt2)),
+                entry(
+                    "dielectric_constant",
+                    Json::number(physical.dielectric_constant),
+                ),
+            ];
+            let tables = vec![
+                entry(
+                    "rho_vs_width_spacing",
+                    table_json(conductor.rho_vs_width_spacing.as_ref()),
+                ),
+                entry(
+                    "rho_vs_si_width_thickness",
+                    table_json(conductor.rho_vs_si_width_thickness.as_ref()),
+                ),
+                entry(
+                    "etch_vs_width_spacing",
+                    table_json(conductor.etch_vs_width_spacing.as_ref()),
+                ),
+                entry(
+                    "etch_from_top",
+                    table_json(conductor.etch_from_top.as_ref()),
+                ),
+                entry(
+                    "thickness_vs_width_spacing",
+                    table_json(conductor.thickness_vs_width_spacing.as_ref()),
+                ),
+                entry(
+                    "crt_vs_si_width",
+                    crt_table_json(conductor.crt_vs_si_width.as_ref()),
+                ),
+            ];
+            ("conductor", electrical, tables)
+        }
+    };
+
+    let vias = stack
+        .via_stack
+        .get_vias_for_layer(layer.name())
+        .into_iter()
+        .map(|via| {
+            Json::Object(vec![
+                entry("name", Json::string(&via.name)),
+                entry("from", Json::string(&via.from_layer)),
+                entry("to", Json::string(&via.to_layer)),
+                entry("area", Json::Number(via.area)),
+                entry("rpv", Json::Number(via.resistance_per_via)),
+            ])
+        })
+        .collect();
+
+    Json::Object(vec![
+        entry("name", Json::string(layer.name())),
+        entry("type", Json::string(layer_type)),
+        entry("technology", Json::string(&stack.technology_info.name)),
+        entry("order", order),
+        entry("geometry", Json::Object(geometry)),
+        entry("electrical", Json::Object(electrical)),
+        entry("tables", Json::Object(tables)),
+        entry("vias", Json::Array(vias)),
+    ])
+}
+
+fn table_json(table: Option<&LookupTable2D>) -> Json {
+    table.map_or(Json::Null, |table| {
+        Json::Object(vec![
+            entry("widths", Json::numbers(&table.widths)),
+            entry("spacings", Json::numbers(&table.spacings)),
+            entry(
+                "values",
+                Json::Array(table.values.iter().map(|row| Json::numbers(row)).collect()),
+            ),
+        ])
+    })
+}
+
+fn crt_table_json(table: Option<&CrtVsSiWidthTable>) -> Json {
+    table.map_or(Json::Null, |table| {
+        Json::Object(vec![
+            entry("widths", Json::numbers(&table.widths)),
+            entry("crt1", Json::numbers(&table.crt1_values)),
+            entry("crt2", Json::numbers(&table.crt2_values)),
+        ])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo, ViaConnection};
+
+    fn stack() -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("fragments".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "ild".to_string(),
+            1.0,
+            4.2,
+        )));
+        let mut metal = ConductorLayer::new("metal/1".to_string(), 0.2);
+        metal.electrical_props.rpsq = Some(0.1);
+        metal.rho_vs_width_spacing = Some(LookupTable2D::new(
+            vec![0.1, 0.2],
+            vec![0.1],
+            vec![vec![0.02], vec![0.018]],
+        ));
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+        stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "ild".to_string(),
+            "metal/1".to_string(),
+            0.04,
+            5.0,
+        ));
+        stack
+    }
+
+    #[test]
+    fn test_layer_fragment() {
+        let stack = stack();
+        let metal = stack.get_layer("metal/1").unwrap();
+        let json = layer_fragment(&stack, metal, FragmentFormat::Json);
+        assert!(json.starts_with("{\n  \"name\": \"metal/1\",\n  \"type\": \"conductor\""));
+        assert!(json.contains("\"rpsq\": 0.1"));
+        assert!(json.contains("\"crt1\": null"));
+        assert!(json.contains("\"etch_from_top\": null"));
+        assert!(json.contains("\"name\": \"via1\""));
+
+        let yaml = layer_fragment(&stack, metal, FragmentFormat::Yaml);
+        assert!(yaml.contains("\n    widths: [0.1, 0.2]\n"));
+        assert!(yaml.contains("\n    values: [[0.02], [0.018]]\n"));
+        assert!(yaml.contains("\n  - name: \"via1\"\n"));
+
+        let ild = layer_fragment(
+            &stack,
+            stack.get_layer("ild").unwrap(),
+            FragmentFormat::Yaml,
+        );
+        assert!(ild.contains("type: \"dielectric\""));
+        assert!(ild.contains("tables: {}"));
+    }
+
+    #[test]
+    fn test_export_layer_fragments() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = export_layer_fragments(&stack(), dir.path(), FragmentFormat::Json).unwrap();
+        let names: Vec<_> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["ild.json", "metal_1.json"]);
+        assert!(std::fs::read_to_string(&paths[1])
+            .unwrap()
+            .contains("\"name\": \"metal/1\""));
+
+        assert_eq!(FragmentFormat::from_name("YML"), Some(FragmentFormat::Yaml));
+        assert_eq!(FragmentFormat::from_name("toml"), None);
+        assert_eq!(fragment_file_stem(".."), "_");
+    }
+
+    #[test]
+    fn test_export_layer_fragments_unique_names() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("names".to_string()));
+        for name in ["m1/a", "m1_a", "M1", "m1", "m1_a_1"] {
+            stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+                name.to_string(),
+                0.1,
+                4.0,
+            )));
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let paths = export_layer_fragments(&stack, dir.path(), FragmentFormat::Yaml).unwrap();
+        let names: Vec<_> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "m1_a.yaml",
+                "m1_a_1.yaml",
+                "M1.yaml",
+                "m1_1.yaml",
+                "m1_a_1_1.yaml"
+            ]
+        );
+        assert!(std::fs::read_to_string(&paths[1])
+            .unwrap()
+            .contains("name: \"m1_a\""));
+    }
+}
@@ -0,0 +1,363 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the current log file in the log directory
+pub const LOG_FILE_NAME: &str = "itf-viewer.log";
+/// Size in bytes after which the log file is rotated
+pub const MAX_LOG_SIZE: u64 = 1 << 20;
+/// Number of rotated log files kept next to the current one
+pub const LOG_FILES_KEPT: usize = 3;
+
+/// Amount of detail written to the log file, set from the preferences
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogVerbosity {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogVerbosity {
+    pub const ALL: [LogVerbosity; 5] = [
+        LogVerbosity::Error,
+        LogVerbosity::Warn,
+        LogVerbosity::Info,
+        LogVerbosity::Debug,
+        LogVerbosity::Trace,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogVerbosity::Error => "Errors only",
+            LogVerbosity::Warn => "Warnings",
+            LogVerbosity::Info => "Info",
+            LogVerbosity::Debug => "Debug",
+            LogVerbosity::Trace => "Trace",
+        }
+    }
+
+    pub fn level_filter(self) -> LevelFilter {
+        match self {
+            LogVerbosity::Error => LevelFilter::Error,
+            LogVerbosity::Warn => LevelFilter::Warn,
+            LogVerbosity::Info => LevelFilter::Info,
+            LogVerbosity::Debug => LevelFilter::Debug,
+            LogVerbosity::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Append-only log file that moves itself aside once it grows past `max_size`
+///
+/// `name.log` becomes `name.log.1`, the previous `name.log.1` becomes `name.log.2` and
+/// so on; the oldest beyond `kept` files is deleted.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    kept: usize,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_size: u64, kept: usize) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_size,
+            kept,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > self.max_size {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let _ = fs::remove_file(self.rotated_path(self.kept));
+        for index in (1..self.kept).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        if self.kept > 0 {
+            fs::rename(&self.path, self.rotated_path(1))?;
+        } else {
+            fs::remove_file(&self.path)?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Logger of the application: `RUST_LOG` controlled stderr output like env_logger,
+/// plus the log file of the GUI at the verbosity chosen in the preferences
+struct AppLogger {
+    stderr: env_logger::Logger,
+    file: Mutex<Option<RotatingFile>>,
+    /// `LevelFilter` of the file as `usize`
+    file_level: AtomicUsize,
+}
+
+impl AppLogger {
+    fn file_level(&self) -> LevelFilter {
+        LevelFilter::iter()
+            .nth(self.file_level.load(Ordering::Relaxed))
+            .unwrap_or(LevelFilter::Off)
+    }
+
+    fn update_max_level(&self) {
+        log::set_max_level(self.stderr.filter().max(self.file_level()));
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.stderr.enabled(metadata) || metadata.level() <= self.file_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.stderr.matches(record) {
+            self.stderr.log(record);
+        }
+        if record.level() <= self.file_level() {
+            if let Ok(mut file) = self.file.lock() {
+                if let Some(file) = file.as_mut() {
+                    let _ = file.write_line(&format_record(record, SystemTime::now()));
+                }
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+    }
+}
+
+static LOGGER: OnceLock<AppLogger> = OnceLock::new();
+
+fn logger() -> &'static AppLogger {
+    LOGGER.get_or_init(|| AppLogger {
+        // Load warnings and notes of the parser reach stderr unless RUST_LOG says otherwise
+        stderr: env_logger::Builder::from_env(
+            env_logger::Env::default().default_filter_or("error,itf_viewer=info"),
+        )
+        .build(),
+        file: Mutex::new(None),
+        file_level: AtomicUsize::new(LevelFilter::Off as usize),
+    })
+}
+
+/// Install the application logger, writing to stderr as configured by `RUST_LOG`
+///
+/// Call once at startup; the log file is added later by [`start_file_logging`].
+pub fn init_logging() {
+    let logger = logger();
+    if log::set_logger(logger).is_ok() {
+        logger.update_max_level();
+    }
+}
+
+/// Also write log records to [`LOG_FILE_NAME`] in `dir`, returning the file path
+pub fn start_file_logging(dir: &Path, verbosity: LogVerbosity) -> io::Result<PathBuf> {
+    let file = RotatingFile::open(&dir.join(LOG_FILE_NAME), MAX_LOG_SIZE, LOG_FILES_KEPT)?;
+    let path = file.path().to_path_buf();
+    let logger = logger();
+    if let Ok(mut current) = logger.file.lock() {
+        *current = Some(file);
+    }
+    set_log_verbosity(verbosity);
+    log::info!("{} {} started", crate::NAME, crate::VERSION);
+    Ok(path)
+}
+
+pub fn set_log_verbosity(verbosity: LogVerbosity) {
+    let logger = logger();
+    logger
+        .file_level
+        .store(verbosity.level_filter() as usize, Ordering::Relaxed);
+    logger.update_max_level();
+}
+
+/// Path of the log file, if file logging was started
+pub fn get_log_file_path() -> Option<PathBuf> {
+    logger()
+        .file
+        .lock()
+        .ok()?
+        .as_ref()
+        .map(|file| file.path().to_path_buf())
+}
+
+/// Directory the GUI writes its log file to, next to the saved preferences
+#[cfg(feature = "gui")]
+pub fn default_log_dir() -> Option<PathBuf> {
+    eframe::storage_dir(crate::NAME)
+}
+
+/// Open a file with the default application of the desktop
+pub fn open_with_system_viewer(path: &Path) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command.arg(path).spawn().map(|_| ())
+}
+
+/// One log file line: UTC time, level, target and message
+fn format_record(record: &Record, time: SystemTime) -> String {
+    format!(
+        "{} {:<5} {}: {}",
+        format_utc(time),
+        record.level(),
+        record.target(),
+        record.args()
+    )
+}
+
+/// `YYYY-MM-DD HH:MM:SS.mmmZ` without pulling in a date crate
+fn format_utc(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (days, second_of_day) = (seconds / 86_400, seconds % 86_400);
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's algorithm
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}.{:03}Z",
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_record() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_760_608_452_345);
+        assert_eq!(format_utc(time), "2025-10-16 09:54:12.345Z");
+        assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01 00:00:00.000Z");
+        // Leap day
+        let time = UNIX_EPOCH + Duration::from_secs(951_782_400);
+        assert_eq!(format_utc(time), "2000-02-29 00:00:00.000Z");
+
+        let line = format_record(
+            &Record::builder()
+                .level(log::Level::Warn)
+                .target("itf_viewer::parser")
+                .args(format_args!("unknown keyword FOO"))
+                .build(),
+            UNIX_EPOCH,
+        );
+        assert_eq!(
+            line,
+            "1970-01-01 00:00:00.000Z WARN  itf_viewer::parser: unknown keyword FOO"
+        );
+    }
+
+    #[test]
+    fn test_parser_warnings_reach_log_file() {
+        let dir = TempDir::new().unwrap();
+        init_logging();
+        let path = start_file_logging(dir.path(), LogVerbosity::Info).unwrap();
+        crate::parser::parse_itf_file(
+            "TECHNOLOGY = logged\nBOGUS_HEADER_LINE\nDIELECTRIC ild { THICKNESS = 1 ER = 4 }\n",
+        )
+        .unwrap();
+
+        let log = fs::read_to_string(&path).unwrap();
+        assert!(log
+            .lines()
+            .any(|line| line.contains("WARN") && line.contains("BOGUS_HEADER_LINE")));
+    }
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("logs").join(LOG_FILE_NAME);
+        let mut file = RotatingFile::open(&path, 20, 2).unwrap();
+
+        for line in ["first line", "second line", "third line", "fourth line"] {
+            file.write_line(line).unwrap();
+        }
+
+        // Every line overflows the 20 byte limit, and only two old files are kept
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth line\n");
+        let rotated = |index: usize| {
+            fs::read_to_string(format!("{}.{index}", path.display())).unwrap_or_default()
+        };
+        assert_eq!(rotated(1), "third line\n");
+        assert_eq!(rotated(2), "second line\n");
+        assert_eq!(rotated(3), "");
+
+        // Reopening appends to the current file
+        let mut file = RotatingFile::open(&path, 100, 2).unwrap();
+        file.write_line("fifth line").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "fourth line\nfifth line\n"
+        );
+    }
+}
@@ -1,6 +1,39 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+pub mod analysis_runner;
+pub mod crash_guard;
+pub mod csv_import;
+pub mod diff_report;
+pub mod examples;
+pub mod file_history;
 pub mod file_utils;
+pub mod itf_writer;
+pub(crate) mod json;
+pub mod layer_fragments;
+pub mod logging;
+pub mod query;
+pub mod random_stack;
+pub mod schema;
+pub mod search;
+pub mod table_export;
+pub mod templates;
+pub mod validation;
 
+pub use analysis_runner::*;
+pub use crash_guard::*;
+pub use csv_import::*;
+pub use diff_report::*;
+pub use examples::*;
+pub use file_history::*;
 pub use file_utils::*;
+pub use itf_writer::*;
+pub use layer_fragments::*;
+pub use logging::*;
+pub use query::*;
+pub use random_stack::*;
+pub use schema::*;
+pub use search::*;
+pub use table_export::*;
+pub use templates::*;
+pub use validation::*;
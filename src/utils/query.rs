@@ -0,0 +1,503 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{Layer, ProcessStack};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Field names accepted by `query_layer_field` and as expression functions
+pub const QUERY_FIELDS: &[&str] = &[
+    "name",
+    "type",
+    "thickness",
+    "z_bottom",
+    "z_top",
+    "er",
+    "crt1",
+    "crt2",
+    "rpsq",
+    "resistivity",
+    "wmin",
+    "smin",
+    "side_tangent",
+];
+
+/// A single value extracted from a process stack
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryValue {
+    Number(f64),
+    Text(String),
+}
+
+impl QueryValue {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            QueryValue::Number(value) => Some(*value),
+            QueryValue::Text(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for QueryValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryValue::Number(value) => write!(f, "{value}"),
+            QueryValue::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// Errors reported by the query helpers
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    #[error("Unknown layer: {0}")]
+    UnknownLayer(String),
+
+    #[error("Unknown field: {0}")]
+    UnknownField(String),
+
+    #[error("Field '{field}' is not defined for layer '{layer}'")]
+    MissingValue { layer: String, field: String },
+
+    #[error("Field '{0}' is not numeric")]
+    NotNumeric(String),
+
+    #[error("Expression error: {0}")]
+    ExpressionError(String),
+}
+
+/// Find a layer by exact name, falling back to a case-insensitive match
+pub fn find_layer<'a>(stack: &'a ProcessStack, layer_name: &str) -> Option<&'a Layer> {
+    stack.get_layer(layer_name).or_else(|| {
+        stack
+            .layers
+            .iter()
+            .find(|layer| layer.name().eq_ignore_ascii_case(layer_name))
+    })
+}
+
+/// Look up a single field of a layer
+pub fn query_layer_field(
+    stack: &ProcessStack,
+    layer_name: &str,
+    field: &str,
+) -> Result<QueryValue, QueryError> {
+    let field_lower = field.to_lowercase();
+    if !QUERY_FIELDS.contains(&field_lower.as_str()) {
+        return Err(QueryError::UnknownField(field.to_string()));
+    }
+
+    let layer =
+        find_layer(stack, layer_name).ok_or_else(|| QueryError::UnknownLayer(layer_name.into()))?;
+
+    let missing = || QueryError::MissingValue {
+        layer: layer.name().to_string(),
+        field: field_lower.clone(),
+    };
+
+    let value = match field_lower.as_str() {
+        "name" => return Ok(QueryValue::Text(layer.name().to_string())),
+        "type" => return Ok(QueryValue::Text(format!("{:?}", layer.layer_type()))),
+        "thickness" => Some(layer.thickness()),
+        "z_bottom" => Some(layer.get_bottom_z()),
+        "z_top" => Some(layer.get_top_z()),
+        "er" => match layer {
+            Layer::Dielectric(d) => Some(d.dielectric_constant),
+            Layer::Conductor(c) => c.physical_props.dielectric_constant,
+        },
+        "crt1" => conductor_value(layer, |c| c.electrical_props.crt1),
+        "crt2" => conductor_value(layer, |c| c.electrical_props.crt2),
+        "rpsq" => conductor_value(layer, |c| c.electrical_props.rpsq),
+        "resistivity" => conductor_value(layer, |c| {
+            c.get_effective_resistivity()
+                .map(|rho| rho.ohm_micrometers())
+        }),
+        "wmin" => conductor_value(layer, |c| c.physical_props.width_min),
+        "smin" => conductor_value(layer, |c| c.physical_props.spacing_min),
+        "side_tangent" => conductor_value(layer, |c| c.physical_props.side_tangent),
+        _ => None,
+    };
+
+    value.map(QueryValue::Number).ok_or_else(missing)
+}
+
+fn conductor_value(
+    layer: &Layer,
+    getter: impl Fn(&crate::data::ConductorLayer) -> Option<f64>,
+) -> Option<f64> {
+    match layer {
+        Layer::Conductor(conductor) => getter(conductor),
+        Layer::Dielectric(_) => None,
+    }
+}
+
+/// Evaluate an arithmetic expression over layer fields
+///
+/// Supports numbers, `+ - * /`, parentheses, unary minus, field functions such as
+/// `thickness('metal3')` and the stack-level function `total_height()`.
+pub fn evaluate_query_expression(stack: &ProcessStack, expr: &str) -> Result<f64, QueryError> {
+    ExpressionEvaluator::new(stack, expr, None, &HashMap::new()).evaluate()
+}
+
+/// Evaluate an expression in the context of one layer
+///
+/// Bare identifiers resolve to `variables` first and then to the fields of `layer_name`,
+/// so `rpsq*length/wmin` works per conductor with `length` supplied by the caller.
+pub fn evaluate_layer_expression(
+    stack: &ProcessStack,
+    layer_name: &str,
+    expr: &str,
+    variables: &HashMap<String, f64>,
+) -> Result<f64, QueryError> {
+    ExpressionEvaluator::new(stack, expr, Some(layer_name), variables).evaluate()
+}
+
+struct ExpressionEvaluator<'a> {
+    stack: &'a ProcessStack,
+    layer_name: Option<&'a str>,
+    variables: &'a HashMap<String, f64>,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl<'a> ExpressionEvaluator<'a> {
+    fn new(
+        stack: &'a ProcessStack,
+        expr: &str,
+        layer_name: Option<&'a str>,
+        variables: &'a HashMap<String, f64>,
+    ) -> Self {
+        Self {
+            stack,
+            layer_name,
+            variables,
+            chars: expr.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn evaluate(mut self) -> Result<f64, QueryError> {
+        let value = self.parse_sum()?;
+        self.skip_whitespace();
+        if self.pos < self.chars.len() {
+            return Err(QueryError::ExpressionError(format!(
+                "Unexpected character '{}' at position {}",
+                self.chars[self.pos], self.pos
+            )));
+        }
+
+        Ok(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), QueryError> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(QueryError::ExpressionError(format!(
+                "Expected '{expected}' at position {}",
+                self.pos
+            )))
+        }
+    }
+
+    fn parse_sum(&mut self) -> Result<f64, QueryError> {
+        let mut value = self.parse_product()?;
+        while let Some(op) = self.peek() {
+            match op {
+                '+' => {
+                    self.pos += 1;
+                    value += self.parse_product()?;
+                }
+                '-' => {
+                    self.pos += 1;
+                    value -= self.parse_product()?;
+                }
+                _ => break,
+            }
+        }
+        Ok(value)
+    }
+
+    fn parse_product(&mut self) -> Result<f64, QueryError> {
+        let mut value = self.parse_unary()?;
+        while let Some(op) = self.peek() {
+            match op {
+                '*' => {
+                    self.pos += 1;
+                    value *= self.parse_unary()?;
+                }
+                '/' => {
+                    self.pos += 1;
+                    value /= self.parse_unary()?;
+                }
+                _ => break,
+            }
+        }
+        Ok(value)
+    }
+
+    fn parse_unary(&mut self) -> Result<f64, QueryError> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(-self.parse_unary()?)
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.parse_unary()
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<f64, QueryError> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.parse_sum()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.parse_number(),
+            Some(c) if c.is_alphabetic() || c == '_' => self.parse_identifier_term(),
+            Some(c) => Err(QueryError::ExpressionError(format!(
+                "Unexpected character '{c}' at position {}",
+                self.pos
+            ))),
+            None => Err(QueryError::ExpressionError(
+                "Unexpected end of expression".to_string(),
+            )),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<f64, QueryError> {
+        let start = self.pos;
+        while self.pos < self.chars.len() {
+            let c = self.chars[self.pos];
+            let is_exponent_sign = (c == '+' || c == '-')
+                && self.pos > start
+                && matches!(self.chars[self.pos - 1], 'e' | 'E');
+            if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || is_exponent_sign {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse::<f64>()
+            .map_err(|_| QueryError::ExpressionError(format!("Invalid number '{text}'")))
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let start = self.pos;
+        while self.pos < self.chars.len()
+            && (self.chars[self.pos].is_alphanumeric() || self.chars[self.pos] == '_')
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn parse_identifier_term(&mut self) -> Result<f64, QueryError> {
+        let identifier = self.parse_identifier();
+        if self.peek() == Some('(') {
+            self.parse_function_call(identifier)
+        } else {
+            self.resolve_variable(&identifier)
+        }
+    }
+
+    fn resolve_variable(&self, name: &str) -> Result<f64, QueryError> {
+        if let Some(value) = self
+            .variables
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+        {
+            return Ok(value);
+        }
+
+        let Some(layer_name) = self.layer_name else {
+            return Err(QueryError::ExpressionError(format!(
+                "Unknown variable '{name}'"
+            )));
+        };
+
+        let value = query_layer_field(self.stack, layer_name, name)?;
+        value
+            .as_number()
+            .ok_or(QueryError::NotNumeric(name.to_lowercase()))
+    }
+
+    fn parse_function_call(&mut self, function: String) -> Result<f64, QueryError> {
+        self.expect('(')?;
+
+        if function.eq_ignore_ascii_case("total_height") {
+            self.expect(')')?;
+            return Ok(self.stack.get_total_height());
+        }
+
+        let layer_name = self.parse_layer_argument()?;
+        self.expect(')')?;
+
+        let value = query_layer_field(self.stack, &layer_name, &function)?;
+        value
+            .as_number()
+            .ok_or(QueryError::NotNumeric(function.to_lowercase()))
+    }
+
+    fn parse_layer_argument(&mut self) -> Result<String, QueryError> {
+        match self.peek() {
+            Some(quote @ ('\'' | '"')) => {
+                self.pos += 1;
+                let start = self.pos;
+                while self.pos < self.chars.len() && self.chars[self.pos] != quote {
+                    self.pos += 1;
+                }
+                if self.pos >= self.chars.len() {
+                    return Err(QueryError::ExpressionError(
+                        "Unterminated layer name".to_string(),
+                    ));
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                self.pos += 1;
+                Ok(name)
+            }
+            Some(c) if c.is_alphanumeric() || c == '_' => Ok(self.parse_identifier()),
+            _ => Err(QueryError::ExpressionError(format!(
+                "Expected layer name at position {}",
+                self.pos
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo};
+    use approx::assert_relative_eq;
+
+    fn create_test_stack() -> ProcessStack {
+        let tech = TechnologyInfo::new("query_tech".to_string());
+        let mut stack = ProcessStack::new(tech);
+
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide1".to_string(),
+            1.0,
+            4.2,
+        )));
+        let mut metal = ConductorLayer::new("metal3".to_string(), 0.5);
+        metal.electrical_props.rpsq = Some(0.02);
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+
+        stack
+    }
+
+    #[test]
+    fn test_query_layer_field() {
+        let stack = create_test_stack();
+
+        assert_eq!(
+            query_layer_field(&stack, "metal3", "thickness").unwrap(),
+            QueryValue::Number(0.5)
+        );
+        assert_eq!(
+            query_layer_field(&stack, "METAL3", "RPSQ").unwrap(),
+            QueryValue::Number(0.02)
+        );
+        assert_eq!(
+            query_layer_field(&stack, "metal3", "resistivity").unwrap(),
+            QueryValue::Number(0.01)
+        );
+        assert_eq!(
+            query_layer_field(&stack, "oxide1", "type")
+                .unwrap()
+                .to_string(),
+            "Dielectric"
+        );
+        assert_eq!(
+            query_layer_field(&stack, "metal3", "z_bottom").unwrap(),
+            QueryValue::Number(1.0)
+        );
+
+        assert!(matches!(
+            query_layer_field(&stack, "metal9", "thickness"),
+            Err(QueryError::UnknownLayer(_))
+        ));
+        assert!(matches!(
+            query_layer_field(&stack, "metal3", "color"),
+            Err(QueryError::UnknownField(_))
+        ));
+        assert!(matches!(
+            query_layer_field(&stack, "metal3", "crt1"),
+            Err(QueryError::MissingValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_query_expression() {
+        let stack = create_test_stack();
+
+        assert_relative_eq!(
+            evaluate_query_expression(&stack, "thickness('METAL3')*2").unwrap(),
+            1.0
+        );
+        assert_relative_eq!(
+            evaluate_query_expression(&stack, "(z_top(metal3) - z_bottom(\"oxide1\")) / 3")
+                .unwrap(),
+            0.5
+        );
+        assert_relative_eq!(
+            evaluate_query_expression(&stack, "total_height() + -1.5e-1").unwrap(),
+            1.35
+        );
+
+        assert!(evaluate_query_expression(&stack, "thickness('metal3'").is_err());
+        assert!(evaluate_query_expression(&stack, "type('metal3')").is_err());
+        assert!(evaluate_query_expression(&stack, "1 +").is_err());
+    }
+
+    #[test]
+    fn test_evaluate_layer_expression() {
+        let stack = create_test_stack();
+        let mut variables = HashMap::new();
+        variables.insert("length".to_string(), 100.0);
+
+        assert_relative_eq!(
+            evaluate_layer_expression(&stack, "metal3", "rpsq*length/thickness", &variables)
+                .unwrap(),
+            4.0
+        );
+        // Function calls still reach other layers
+        assert_relative_eq!(
+            evaluate_layer_expression(
+                &stack,
+                "metal3",
+                "thickness / thickness('oxide1')",
+                &variables
+            )
+            .unwrap(),
+            0.5
+        );
+
+        assert!(matches!(
+            evaluate_layer_expression(&stack, "oxide1", "rpsq*length", &variables),
+            Err(QueryError::MissingValue { .. })
+        ));
+        assert!(evaluate_query_expression(&stack, "length * 2").is_err());
+    }
+}
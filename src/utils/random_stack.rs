@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{
+    ConductorLayer, DielectricLayer, Layer, ProcessStack, TechnologyInfo, ViaConnection,
+};
+
+/// SplitMix64, small and deterministic so random stacks need no extra dependency
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.next_f64()
+    }
+
+    /// Log-uniform, for values spanning orders of magnitude
+    fn log_range(&mut self, min: f64, max: f64) -> f64 {
+        self.range(min.ln(), max.ln()).exp()
+    }
+}
+
+/// Generate a valid process stack with plausible random parameters
+///
+/// The same seed always gives the same stack. Layers are ordered like a parsed ITF
+/// file: a passivation dielectric first, then conductors separated by dielectrics
+/// down to a bottom dielectric, with a via between each pair of neighboring conductors.
+/// Conductors get thicker towards the top of the stack.
+pub fn generate_random_stack(seed: u64, n_layers: usize) -> ProcessStack {
+    let mut rng = SplitMix64(seed);
+    let n_layers = n_layers.max(1);
+
+    let mut stack = ProcessStack::new(
+        TechnologyInfo::new(format!("random_{seed}")).with_temperature(rng.range(25.0, 125.0)),
+    );
+
+    // Conductors sit at odd positions, never first or last
+    let conductor_count = (1..n_layers.saturating_sub(1)).step_by(2).count();
+    let mut conductor_number = conductor_count;
+    let mut conductor_names: Vec<String> = Vec::new();
+
+    for index in 0..n_layers {
+        let is_conductor = index % 2 == 1 && index < n_layers - 1;
+        // 1.0 at the top of the stack, 0.0 at the bottom
+        let height = 1.0 - index as f64 / n_layers as f64;
+
+        if is_conductor {
+            let name = format!("metal{conductor_number}");
+            conductor_number -= 1;
+
+            let thickness = rng.log_range(0.05, 0.3) * (1.0 + 8.0 * height.powi(3));
+            let width_min = thickness * rng.range(0.4, 1.0);
+
+            let mut layer = ConductorLayer::new(name.clone(), round(thickness))
+                .with_side_tangent(round(rng.range(-0.05, 0.1)))
+                .with_width_spacing_limits(
+                    round(width_min),
+                    round(width_min * rng.range(0.9, 1.3)),
+                );
+            layer.electrical_props.rpsq = Some(round(rng.range(0.018, 0.03) / thickness));
+            layer.electrical_props.crt1 = Some(round(rng.range(2.0e-3, 4.5e-3)));
+            layer.electrical_props.crt2 = Some(round(rng.range(-1.0e-6, 0.0)));
+
+            stack.add_layer(Layer::Conductor(Box::new(layer)));
+            conductor_names.push(name);
+        } else {
+            // Occasional low-k layers between higher-k etch stops and passivation
+            let dielectric_constant = if rng.next_f64() < 0.3 {
+                rng.range(2.5, 3.2)
+            } else {
+                rng.range(3.5, 7.5)
+            };
+            let thickness = rng.log_range(0.02, 1.5) * (1.0 + 2.0 * height);
+
+            stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+                format!("diel{index}"),
+                round(thickness),
+                round(dielectric_constant),
+            )));
+        }
+    }
+
+    // Names were collected top down, vias go from each conductor to the one above
+    for pair in conductor_names.windows(2) {
+        let (upper, lower) = (&pair[0], &pair[1]);
+        let width = stack
+            .get_layer(lower)
+            .and_then(|layer| match layer {
+                Layer::Conductor(conductor) => conductor.physical_props.width_min,
+                Layer::Dielectric(_) => None,
+            })
+            .unwrap_or(0.1);
+        let area = width * width * rng.range(0.8, 1.2);
+
+        stack.add_via(ViaConnection::new(
+            format!("via_{lower}"),
+            lower.clone(),
+            upper.clone(),
+            round(area),
+            round(rng.log_range(0.5, 50.0)),
+        ));
+    }
+
+    stack
+}
+
+/// Round to six significant digits so generated values read like hand-written ones
+fn round(value: f64) -> f64 {
+    if value == 0.0 {
+        return value;
+    }
+    let magnitude = 10f64.powi(6 - value.abs().log10().ceil() as i32);
+    (value * magnitude).round() / magnitude
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_stack_is_deterministic() {
+        let first = generate_random_stack(42, 15);
+        let second = generate_random_stack(42, 15);
+        let other = generate_random_stack(43, 15);
+
+        let thicknesses = |stack: &ProcessStack| {
+            stack
+                .layers
+                .iter()
+                .map(|l| l.thickness())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(thicknesses(&first), thicknesses(&second));
+        assert_ne!(thicknesses(&first), thicknesses(&other));
+    }
+
+    #[test]
+    fn test_random_stack_is_valid() {
+        for seed in 0..20 {
+            for n_layers in [1, 2, 3, 10, 41] {
+                let stack = generate_random_stack(seed, n_layers);
+
+                assert_eq!(stack.get_layer_count(), n_layers);
+                assert!(stack.validate_stack().is_ok());
+                assert!(stack.get_via_span_warnings().is_empty());
+                assert_eq!(
+                    stack.get_via_count(),
+                    stack.get_conductor_count().saturating_sub(1)
+                );
+
+                for layer in &stack.layers {
+                    assert!(layer.thickness() > 0.0);
+                    if let Layer::Conductor(conductor) = layer {
+                        assert!(conductor.electrical_props.rpsq.unwrap() > 0.0);
+                        let wmin = conductor.physical_props.width_min.unwrap();
+                        assert!(conductor.physical_props.spacing_min.unwrap() >= wmin * 0.9);
+                    }
+                }
+            }
+        }
+    }
+}
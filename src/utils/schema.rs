@@ -0,0 +1,851 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use crate::utils::json::Json;
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use thiserror::Error;
+
+/// Passes over the type before giving up on reaching every enum variant
+const MAX_TRACE_PASSES: usize = 64;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    #[error("Cannot describe type: {0}")]
+    Unsupported(String),
+
+    #[error("Enum '{0}' has variants that could not be reached")]
+    UnreachableVariants(String),
+}
+
+impl de::Error for SchemaError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SchemaError::Unsupported(msg.to_string())
+    }
+}
+
+/// Shape of a value in the serde data model
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaType {
+    Bool,
+    Integer {
+        unsigned: bool,
+    },
+    Number,
+    String,
+    Unit,
+    Option(Box<SchemaType>),
+    Array(Box<SchemaType>),
+    /// Object with arbitrary string keys
+    Map(Box<SchemaType>),
+    Tuple(Vec<SchemaType>),
+    /// Struct or enum described in the schema definitions
+    Named(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariantShape {
+    Unit,
+    Newtype(SchemaType),
+    Tuple(Vec<SchemaType>),
+    Struct(Vec<(&'static str, SchemaType)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeDefinition {
+    Struct(Vec<(&'static str, SchemaType)>),
+    NewtypeStruct(SchemaType),
+    /// Variants in declaration order, `None` for variants not traced yet
+    Enum(Vec<(&'static str, Option<VariantShape>)>),
+}
+
+/// Structure of a serializable type and every named type it contains
+///
+/// Built by driving the type's `Deserialize` implementation with a tracing
+/// deserializer, so the description always matches the derived serde format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeSchema {
+    pub root: &'static str,
+    pub definitions: BTreeMap<&'static str, TypeDefinition>,
+}
+
+impl TypeSchema {
+    /// Trace `T`, which must be a struct, enum or newtype struct
+    pub fn trace<T: for<'de> Deserialize<'de>>() -> Result<Self, SchemaError> {
+        let mut registry = Registry::default();
+        for _ in 0..MAX_TRACE_PASSES {
+            let mut root = SchemaType::Unit;
+            T::deserialize(Tracer {
+                registry: &mut registry,
+                out: &mut root,
+            })?;
+
+            let SchemaType::Named(root) = root else {
+                return Err(SchemaError::Unsupported(
+                    "the root must be a named type".to_string(),
+                ));
+            };
+            if registry.incomplete_enum().is_none() {
+                return Ok(Self {
+                    root,
+                    definitions: registry.definitions,
+                });
+            }
+        }
+
+        let name = registry.incomplete_enum().unwrap_or_default();
+        Err(SchemaError::UnreachableVariants(name.to_string()))
+    }
+
+    /// JSON Schema (draft 2020-12) of the serde JSON representation
+    pub fn to_json_schema(&self) -> String {
+        let definitions = self
+            .definitions
+            .iter()
+            .map(|(name, definition)| (name.to_string(), definition_json(definition)))
+            .collect();
+        let schema = Json::Object(vec![
+            (
+                "$schema".to_string(),
+                Json::string("https://json-schema.org/draft/2020-12/schema"),
+            ),
+            ("title".to_string(), Json::string(self.root)),
+            (
+                "description".to_string(),
+                Json::string(format!(
+                    "{} as serialized by {} {}",
+                    self.root,
+                    crate::NAME,
+                    crate::VERSION
+                )),
+            ),
+            ("$ref".to_string(), reference(self.root)),
+            ("$defs".to_string(), Json::Object(definitions)),
+        ]);
+
+        schema.to_json()
+    }
+
+    /// Markdown reference with one section per named type
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# {} schema", self.root);
+        let _ = writeln!(md);
+        let _ = writeln!(
+            md,
+            "Generated by {} {}. Enums use serde's externally tagged form: unit \
+             variants are strings, other variants are objects with the variant name \
+             as the only key. Optional fields may be null or missing.",
+            crate::NAME,
+            crate::VERSION
+        );
+
+        // Root type first, the rest alphabetically
+        let root = self.definitions.get_key_value(self.root);
+        let others = self
+            .definitions
+            .iter()
+            .filter(|(name, _)| **name != self.root);
+        for (name, definition) in root.into_iter().chain(others) {
+            let _ = writeln!(md);
+            let _ = writeln!(md, "## {name}");
+            let _ = writeln!(md);
+            match definition {
+                TypeDefinition::Struct(fields) => {
+                    let _ = writeln!(md, "| Field | Type | Required |");
+                    let _ = writeln!(md, "|-------|------|----------|");
+                    for (field, ty) in fields {
+                        let required = if is_optional(ty) { "no" } else { "yes" };
+                        let _ = writeln!(md, "| `{field}` | {} | {required} |", markdown_type(ty));
+                    }
+                }
+                TypeDefinition::NewtypeStruct(ty) => {
+                    let _ = writeln!(md, "Same as {}.", markdown_type(ty));
+                }
+                TypeDefinition::Enum(variants) => {
+                    let _ = writeln!(md, "| Variant | Content |");
+                    let _ = writeln!(md, "|---------|---------|");
+                    for (variant, shape) in variants {
+                        let content = match shape {
+                            Some(VariantShape::Unit) | None => "-".to_string(),
+                            Some(VariantShape::Newtype(ty)) => markdown_type(ty),
+                            Some(VariantShape::Tuple(items)) => {
+                                markdown_type(&SchemaType::Tuple(items.clone()))
+                            }
+                            Some(VariantShape::Struct(fields)) => fields
+                                .iter()
+                                .map(|(field, ty)| format!("`{field}`: {}", markdown_type(ty)))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                        };
+                        let _ = writeln!(md, "| `{variant}` | {content} |");
+                    }
+                }
+            }
+        }
+        md
+    }
+}
+
+/// Schema of the stack model, the root of every serialized stack
+pub fn process_stack_schema() -> Result<TypeSchema, SchemaError> {
+    TypeSchema::trace::<ProcessStack>()
+}
+
+fn is_optional(ty: &SchemaType) -> bool {
+    matches!(ty, SchemaType::Option(_))
+}
+
+fn markdown_type(ty: &SchemaType) -> String {
+    match ty {
+        SchemaType::Bool => "boolean".to_string(),
+        SchemaType::Integer { unsigned: true } => "unsigned integer".to_string(),
+        SchemaType::Integer { unsigned: false } => "integer".to_string(),
+        SchemaType::Number => "number".to_string(),
+        SchemaType::String => "string".to_string(),
+        SchemaType::Unit => "null".to_string(),
+        SchemaType::Option(inner) => format!("{} or null", markdown_type(inner)),
+        SchemaType::Array(item) => format!("array of {}", markdown_type(item)),
+        SchemaType::Map(value) => format!("map of string to {}", markdown_type(value)),
+        SchemaType::Tuple(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(markdown_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        SchemaType::Named(name) => format!("[{name}](#{})", name.to_lowercase()),
+    }
+}
+
+fn reference(name: &str) -> Json {
+    Json::Object(vec![(
+        "$ref".to_string(),
+        Json::string(format!("#/$defs/{name}")),
+    )])
+}
+
+fn type_json(ty: &SchemaType) -> Json {
+    let simple = |name: &str| Json::Object(vec![("type".to_string(), Json::string(name))]);
+    match ty {
+        SchemaType::Bool => simple("boolean"),
+        SchemaType::Integer { unsigned } => {
+            let mut object = vec![("type".to_string(), Json::string("integer"))];
+            if *unsigned {
+                object.push(("minimum".to_string(), Json::Integer(0)));
+            }
+            Json::Object(object)
+        }
+        SchemaType::Number => simple("number"),
+        SchemaType::String => simple("string"),
+        SchemaType::Unit => simple("null"),
+        SchemaType::Option(inner) => Json::Object(vec![(
+            "anyOf".to_string(),
+            Json::Array(vec![type_json(inner), simple("null")]),
+        )]),
+        SchemaType::Array(item) => Json::Object(vec![
+            ("type".to_string(), Json::string("array")),
+            ("items".to_string(), type_json(item)),
+        ]),
+        SchemaType::Map(value) => Json::Object(vec![
+            ("type".to_string(), Json::string("object")),
+            ("additionalProperties".to_string(), type_json(value)),
+        ]),
+        SchemaType::Tuple(items) => Json::Object(vec![
+            ("type".to_string(), Json::string("array")),
+            (
+                "prefixItems".to_string(),
+                Json::Array(items.iter().map(type_json).collect()),
+            ),
+            ("items".to_string(), Json::Bool(false)),
+        ]),
+        SchemaType::Named(name) => reference(name),
+    }
+}
+
+fn object_json(fields: &[(&'static str, SchemaType)]) -> Json {
+    let properties = fields
+        .iter()
+        .map(|(field, ty)| (field.to_string(), type_json(ty)))
+        .collect();
+    let required = fields
+        .iter()
+        .filter(|(_, ty)| !is_optional(ty))
+        .map(|(field, _)| Json::string(*field))
+        .collect();
+    Json::Object(vec![
+        ("type".to_string(), Json::string("object")),
+        ("properties".to_string(), Json::Object(properties)),
+        ("required".to_string(), Json::Array(required)),
+        ("additionalProperties".to_string(), Json::Bool(false)),
+    ])
+}
+
+/// Externally tagged variant: an object with the variant name as its only key
+fn tagged_json(name: &str, content: Json) -> Json {
+    Json::Object(vec![
+        ("type".to_string(), Json::string("object")),
+        (
+            "properties".to_string(),
+            Json::Object(vec![(name.to_string(), content)]),
+        ),
+        (
+            "required".to_string(),
+            Json::Array(vec![Json::string(name)]),
+        ),
+        ("additionalProperties".to_string(), Json::Bool(false)),
+    ])
+}
+
+fn definition_json(definition: &TypeDefinition) -> Json {
+    match definition {
+        TypeDefinition::Struct(fields) => object_json(fields),
+        TypeDefinition::NewtypeStruct(ty) => type_json(ty),
+        TypeDefinition::Enum(variants) => {
+            if variants
+                .iter()
+                .all(|(_, shape)| matches!(shape, Some(VariantShape::Unit)))
+            {
+                return Json::Object(vec![
+                    ("type".to_string(), Json::string("string")),
+                    (
+                        "enum".to_string(),
+                        Json::Array(
+                            variants
+                                .iter()
+                                .map(|(name, _)| Json::string(*name))
+                                .collect(),
+                        ),
+                    ),
+                ]);
+            }
+
+            let options = variants
+                .iter()
+                .map(|(name, shape)| {
+                    let content = match shape {
+                        Some(VariantShape::Unit) | None => {
+                            return Json::Object(vec![("const".to_string(), Json::string(*name))]);
+                        }
+                        Some(VariantShape::Newtype(ty)) => type_json(ty),
+                        Some(VariantShape::Tuple(items)) => {
+                            type_json(&SchemaType::Tuple(items.clone()))
+                        }
+                        Some(VariantShape::Struct(fields)) => object_json(fields),
+                    };
+                    tagged_json(name, content)
+                })
+                .collect();
+            Json::Object(vec![("oneOf".to_string(), Json::Array(options))])
+        }
+    }
+}
+
+/// Named types found so far and how often each enum has been visited
+#[derive(Default)]
+struct Registry {
+    definitions: BTreeMap<&'static str, TypeDefinition>,
+    enum_visits: BTreeMap<&'static str, usize>,
+}
+
+impl Registry {
+    fn incomplete_enum(&self) -> Option<&'static str> {
+        self.definitions
+            .iter()
+            .find(|(_, definition)| match definition {
+                TypeDefinition::Enum(variants) => variants.iter().any(|(_, shape)| shape.is_none()),
+                _ => false,
+            })
+            .map(|(name, _)| *name)
+    }
+
+    /// Variant to trace next: the first untraced one, otherwise round robin so
+    /// enums nested in later variants are reached on following passes
+    fn next_variant(&mut self, name: &'static str, variants: &'static [&'static str]) -> u32 {
+        let definition = self.definitions.entry(name).or_insert_with(|| {
+            TypeDefinition::Enum(variants.iter().map(|variant| (*variant, None)).collect())
+        });
+        let visits = self.enum_visits.entry(name).or_default();
+        *visits += 1;
+        let untraced = match definition {
+            TypeDefinition::Enum(variants) => {
+                variants.iter().position(|(_, shape)| shape.is_none())
+            }
+            _ => None,
+        };
+        untraced.unwrap_or(*visits % variants.len().max(1)) as u32
+    }
+
+    fn set_variant(&mut self, name: &'static str, index: u32, shape: VariantShape) {
+        if let Some(TypeDefinition::Enum(variants)) = self.definitions.get_mut(name) {
+            if let Some((_, slot)) = variants.get_mut(index as usize) {
+                *slot = Some(shape);
+            }
+        }
+    }
+}
+
+/// Deserializer that records the shape requested by the type and feeds back a
+/// placeholder value of that shape
+struct Tracer<'a> {
+    registry: &'a mut Registry,
+    out: &'a mut SchemaType,
+}
+
+impl<'de> Deserializer<'de> for Tracer<'_> {
+    type Error = SchemaError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, SchemaError> {
+        Err(SchemaError::Unsupported(
+            "self-describing values have no fixed shape".to_string(),
+        ))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        *self.out = SchemaType::Bool;
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        *self.out = SchemaType::Integer { unsigned: false };
+        visitor.visit_i64(0)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        *self.out = SchemaType::Integer { unsigned: true };
+        visitor.visit_u64(0)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        *self.out = SchemaType::Number;
+        visitor.visit_f64(0.0)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        *self.out = SchemaType::String;
+        visitor.visit_char(' ')
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        *self.out = SchemaType::String;
+        visitor.visit_str("")
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        *self.out = SchemaType::Array(Box::new(SchemaType::Integer { unsigned: true }));
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        let mut inner = SchemaType::Unit;
+        let value = visitor.visit_some(Tracer {
+            registry: self.registry,
+            out: &mut inner,
+        })?;
+        *self.out = SchemaType::Option(Box::new(inner));
+        Ok(value)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        *self.out = SchemaType::Unit;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        self.registry
+            .definitions
+            .insert(name, TypeDefinition::Struct(Vec::new()));
+        *self.out = SchemaType::Named(name);
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        let mut inner = SchemaType::Unit;
+        let value = visitor.visit_newtype_struct(Tracer {
+            registry: self.registry,
+            out: &mut inner,
+        })?;
+        self.registry
+            .definitions
+            .insert(name, TypeDefinition::NewtypeStruct(inner));
+        *self.out = SchemaType::Named(name);
+        Ok(value)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        // A single element is enough to learn the element type
+        let mut elements = SeqTracer::new(self.registry, 1);
+        let value = visitor.visit_seq(&mut elements)?;
+        let item = elements.slots.pop().unwrap_or(SchemaType::Unit);
+        *self.out = SchemaType::Array(Box::new(item));
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        let mut elements = SeqTracer::new(self.registry, len);
+        let value = visitor.visit_seq(&mut elements)?;
+        *self.out = SchemaType::Tuple(elements.slots);
+        Ok(value)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        let mut elements = SeqTracer::new(self.registry, len);
+        let value = visitor.visit_seq(&mut elements)?;
+        elements.registry.definitions.insert(
+            name,
+            TypeDefinition::NewtypeStruct(SchemaType::Tuple(elements.slots)),
+        );
+        *self.out = SchemaType::Named(name);
+        Ok(value)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        let mut entries = MapTracer {
+            registry: self.registry,
+            key: SchemaType::Unit,
+            value: SchemaType::Unit,
+            remaining: 1,
+        };
+        let value = visitor.visit_map(&mut entries)?;
+        *self.out = SchemaType::Map(Box::new(entries.value));
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        let mut tracer = StructTracer {
+            registry: self.registry,
+            fields,
+            slots: vec![SchemaType::Unit; fields.len()],
+            index: 0,
+        };
+        let value = visitor.visit_map(&mut tracer)?;
+        let definition = TypeDefinition::Struct(fields.iter().copied().zip(tracer.slots).collect());
+        tracer.registry.definitions.insert(name, definition);
+        *self.out = SchemaType::Named(name);
+        Ok(value)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        let index = self.registry.next_variant(name, variants);
+        *self.out = SchemaType::Named(name);
+        visitor.visit_enum(EnumTracer {
+            registry: self.registry,
+            name,
+            index,
+        })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        visitor.visit_unit()
+    }
+}
+
+struct SeqTracer<'a> {
+    registry: &'a mut Registry,
+    slots: Vec<SchemaType>,
+    len: usize,
+}
+
+impl<'a> SeqTracer<'a> {
+    fn new(registry: &'a mut Registry, len: usize) -> Self {
+        Self {
+            registry,
+            slots: Vec::with_capacity(len),
+            len,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqTracer<'_> {
+    type Error = SchemaError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SchemaError> {
+        if self.slots.len() == self.len {
+            return Ok(None);
+        }
+        let mut slot = SchemaType::Unit;
+        let value = seed.deserialize(Tracer {
+            registry: self.registry,
+            out: &mut slot,
+        })?;
+        self.slots.push(slot);
+        Ok(Some(value))
+    }
+}
+
+struct MapTracer<'a> {
+    registry: &'a mut Registry,
+    key: SchemaType,
+    value: SchemaType,
+    remaining: usize,
+}
+
+impl<'de> MapAccess<'de> for MapTracer<'_> {
+    type Error = SchemaError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SchemaError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(Tracer {
+            registry: self.registry,
+            out: &mut self.key,
+        })
+        .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SchemaError> {
+        seed.deserialize(Tracer {
+            registry: self.registry,
+            out: &mut self.value,
+        })
+    }
+}
+
+struct StructTracer<'a> {
+    registry: &'a mut Registry,
+    fields: &'static [&'static str],
+    slots: Vec<SchemaType>,
+    index: usize,
+}
+
+impl<'de> MapAccess<'de> for StructTracer<'_> {
+    type Error = SchemaError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SchemaError> {
+        match self.fields.get(self.index) {
+            Some(field) => seed.deserialize(field.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SchemaError> {
+        let value = seed.deserialize(Tracer {
+            registry: self.registry,
+            out: &mut self.slots[self.index],
+        })?;
+        self.index += 1;
+        Ok(value)
+    }
+}
+
+struct EnumTracer<'a> {
+    registry: &'a mut Registry,
+    name: &'static str,
+    index: u32,
+}
+
+impl<'de, 'a> EnumAccess<'de> for EnumTracer<'a> {
+    type Error = SchemaError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), SchemaError> {
+        let variant = seed.deserialize(self.index.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EnumTracer<'_> {
+    type Error = SchemaError;
+
+    fn unit_variant(self) -> Result<(), SchemaError> {
+        self.registry
+            .set_variant(self.name, self.index, VariantShape::Unit);
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, SchemaError> {
+        let mut inner = SchemaType::Unit;
+        let value = seed.deserialize(Tracer {
+            registry: self.registry,
+            out: &mut inner,
+        })?;
+        self.registry
+            .set_variant(self.name, self.index, VariantShape::Newtype(inner));
+        Ok(value)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        let mut elements = SeqTracer::new(self.registry, len);
+        let value = visitor.visit_seq(&mut elements)?;
+        let shape = VariantShape::Tuple(elements.slots);
+        self.registry.set_variant(self.name, self.index, shape);
+        Ok(value)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        let mut tracer = StructTracer {
+            registry: self.registry,
+            fields,
+            slots: vec![SchemaType::Unit; fields.len()],
+            index: 0,
+        };
+        let value = visitor.visit_map(&mut tracer)?;
+        let shape = VariantShape::Struct(fields.iter().copied().zip(tracer.slots).collect());
+        tracer.registry.set_variant(self.name, self.index, shape);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_stack_schema() {
+        let schema = process_stack_schema().unwrap();
+        assert_eq!(schema.root, "ProcessStack");
+
+        let Some(TypeDefinition::Struct(fields)) = schema.definitions.get("ProcessStack") else {
+            panic!("ProcessStack should be a struct");
+        };
+        let names: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+        assert!(names.starts_with(&["technology_info", "layers", "via_stack", "diagnostics"]));
+        assert_eq!(
+            fields[1].1,
+            SchemaType::Array(Box::new(SchemaType::Named("Layer")))
+        );
+
+        // Every variant is reached, including those behind other enums
+        let Some(TypeDefinition::Enum(variants)) = schema.definitions.get("Layer") else {
+            panic!("Layer should be an enum");
+        };
+        assert_eq!(
+            variants[1],
+            (
+                "Conductor",
+                Some(VariantShape::Newtype(SchemaType::Named("ConductorLayer")))
+            )
+        );
+        assert!(schema.definitions.contains_key("PropValue"));
+        assert!(matches!(
+            schema.definitions.get("Severity"),
+            Some(TypeDefinition::Enum(variants)) if variants.iter().all(|(_, shape)| *shape == Some(VariantShape::Unit))
+        ));
+    }
+
+    #[test]
+    fn test_schema_output() {
+        let schema = process_stack_schema().unwrap();
+
+        let json = schema.to_json_schema();
+        assert!(
+            json.starts_with("{\n  \"$schema\": \"https://json-schema.org/draft/2020-12/schema\"")
+        );
+        assert!(json.contains("\"$ref\": \"#/$defs/ProcessStack\""));
+        assert!(json.contains(
+            "\"enum\": [\n        \"Info\",\n        \"Warning\",\n        \"Error\"\n      ]"
+        ));
+        // Balanced output is a cheap well-formedness check without a JSON parser
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+
+        let markdown = schema.to_markdown();
+        assert!(markdown.starts_with("# ProcessStack schema"));
+        assert!(markdown.contains("| `layers` | array of [Layer](#layer) | yes |"));
+        assert!(markdown.contains("| `global_temperature` | number or null | no |"));
+    }
+}
@@ -0,0 +1,410 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{Layer, LookupTable2D, ProcessStack};
+use crate::utils::QueryValue;
+
+/// Where a property was found
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchTarget {
+    Technology,
+    Layer(String),
+    Via(String),
+}
+
+impl SearchTarget {
+    /// Layer or via name that can be selected in the viewer
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            SearchTarget::Technology => None,
+            SearchTarget::Layer(name) | SearchTarget::Via(name) => Some(name),
+        }
+    }
+}
+
+/// A single property matching a search query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub target: SearchTarget,
+    pub key: &'static str,
+    pub value: QueryValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+}
+
+impl CompareOp {
+    fn matches(self, lhs: f64, rhs: f64) -> bool {
+        let equal = (lhs - rhs).abs() <= 1e-9 * lhs.abs().max(rhs.abs()).max(1.0);
+        match self {
+            CompareOp::Less => lhs < rhs,
+            CompareOp::LessEqual => lhs <= rhs || equal,
+            CompareOp::Greater => lhs > rhs,
+            CompareOp::GreaterEqual => lhs >= rhs || equal,
+            CompareOp::Equal => equal,
+            CompareOp::NotEqual => !equal,
+        }
+    }
+}
+
+/// A parsed search query
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchQuery {
+    Empty,
+    /// Case-insensitive substring of a property key or value, including names
+    Text(String),
+    /// Numeric comparison against a property key, e.g. `ER<3.0`
+    Compare {
+        key: String,
+        op: CompareOp,
+        value: f64,
+    },
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Self {
+        use regex::Regex;
+
+        let query = query.trim();
+        if query.is_empty() {
+            return SearchQuery::Empty;
+        }
+
+        let compare_re = Regex::new(
+            r"^([A-Za-z_][A-Za-z0-9_]*)\s*(<=|>=|!=|==|=|<|>)\s*([-+]?(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?)$",
+        )
+        .unwrap();
+
+        if let Some(captures) = compare_re.captures(query) {
+            let op = match &captures[2] {
+                "<" => CompareOp::Less,
+                "<=" => CompareOp::LessEqual,
+                ">" => CompareOp::Greater,
+                ">=" => CompareOp::GreaterEqual,
+                "!=" => CompareOp::NotEqual,
+                _ => CompareOp::Equal,
+            };
+            if let Ok(value) = captures[3].parse::<f64>() {
+                return SearchQuery::Compare {
+                    key: captures[1].to_string(),
+                    op,
+                    value,
+                };
+            }
+        }
+
+        SearchQuery::Text(query.to_lowercase())
+    }
+
+    fn matches(&self, key: &str, value: &QueryValue) -> bool {
+        match self {
+            SearchQuery::Empty => false,
+            // Names are matched through the NAME entries of layers and vias
+            SearchQuery::Text(text) => {
+                key.to_lowercase().contains(text) || value.to_string().to_lowercase().contains(text)
+            }
+            SearchQuery::Compare {
+                key: wanted_key,
+                op,
+                value: wanted,
+            } => {
+                key.eq_ignore_ascii_case(wanted_key)
+                    && value.as_number().is_some_and(|v| op.matches(v, *wanted))
+            }
+        }
+    }
+}
+
+/// Search all parsed properties of a stack
+pub fn search_stack(stack: &ProcessStack, query: &str) -> Vec<SearchHit> {
+    let query = SearchQuery::parse(query);
+
+    stack_properties(stack)
+        .into_iter()
+        .filter(|hit| query.matches(hit.key, &hit.value))
+        .collect()
+}
+
+/// Flatten the technology, layers and vias of a stack into ITF keyword/value pairs
+pub fn stack_properties(stack: &ProcessStack) -> Vec<SearchHit> {
+    let mut properties = Vec::new();
+
+    let tech = &stack.technology_info;
+    let mut push = |target: &SearchTarget, key: &'static str, value: Option<QueryValue>| {
+        if let Some(value) = value {
+            properties.push(SearchHit {
+                target: target.clone(),
+                key,
+                value,
+            });
+        }
+    };
+    let number = |value: Option<f64>| value.map(QueryValue::Number);
+    let text = |value: Option<&String>| value.map(|v| QueryValue::Text(v.clone()));
+    let table = |table: &Option<LookupTable2D>| {
+        table
+            .as_ref()
+            .map(|t| QueryValue::Text(format!("{}x{} table", t.widths.len(), t.spacings.len())))
+    };
+
+    let target = SearchTarget::Technology;
+    push(
+        &target,
+        "TECHNOLOGY",
+        Some(QueryValue::Text(tech.name.clone())),
+    );
+    push(
+        &target,
+        "GLOBAL_TEMPERATURE",
+        number(tech.global_temperature),
+    );
+    push(
+        &target,
+        "REFERENCE_DIRECTION",
+        text(tech.reference_direction.as_ref()),
+    );
+    push(&target, "BACKGROUND_ER", number(tech.background_er));
+    push(
+        &target,
+        "HALF_NODE_SCALE_FACTOR",
+        number(tech.half_node_scale_factor),
+    );
+    push(
+        &target,
+        "USE_SI_DENSITY",
+        tech.use_si_density
+            .map(|v| QueryValue::Text(if v { "YES" } else { "NO" }.to_string())),
+    );
+    push(
+        &target,
+        "DROP_FACTOR_LATERAL_SPACING",
+        number(tech.drop_factor_lateral_spacing),
+    );
+
+    for layer in &stack.layers {
+        let target = SearchTarget::Layer(layer.name().to_string());
+        push(
+            &target,
+            "NAME",
+            Some(QueryValue::Text(layer.name().to_string())),
+        );
+        push(
+            &target,
+            "THICKNESS",
+            Some(QueryValue::Number(layer.thickness())),
+        );
+
+        match layer {
+            Layer::Dielectric(dielectric) => {
+                push(
+                    &target,
+                    "ER",
+                    Some(QueryValue::Number(dielectric.dielectric_constant)),
+                );
+                push(
+                    &target,
+                    "MEASURED_FROM",
+                    text(dielectric.measured_from.as_ref()),
+                );
+                push(&target, "SW_T", number(dielectric.sw_t));
+                push(&target, "TW_T", number(dielectric.tw_t));
+            }
+            Layer::Conductor(conductor) => {
+                let physical = &conductor.physical_props;
+                let electrical = &conductor.electrical_props;
+                push(&target, "WMIN", number(physical.width_min));
+                push(&target, "SMIN", number(physical.spacing_min));
+                push(&target, "SIDE_TANGENT", number(physical.side_tangent));
+                push(&target, "ER", number(physical.dielectric_constant));
+                push(&target, "CRT1", number(electrical.crt1));
+                push(&target, "CRT2", number(electrical.crt2));
+                push(&target, "RPSQ", number(electrical.rpsq));
+                push(&target, "RPV", number(electrical.rpv));
+                push(
+                    &target,
+                    "LAYER_TYPE",
+                    text(conductor.gate_props.layer_type.as_ref()),
+                );
+                push(
+                    &target,
+                    "GATE_FORMING_LAYER",
+                    conductor
+                        .gate_props
+                        .gate_forming_layer
+                        .then(|| QueryValue::Text("TRUE".to_string())),
+                );
+                push(
+                    &target,
+                    "GATE_TO_CONTACT_SMIN",
+                    number(conductor.gate_props.gate_to_contact_smin),
+                );
+                push(
+                    &target,
+                    "RESISTIVE_ONLY_ETCH",
+                    number(conductor.resistive_only_etch),
+                );
+                push(
+                    &target,
+                    "CAPACITIVE_ONLY_ETCH",
+                    number(conductor.capacitive_only_etch),
+                );
+                push(
+                    &target,
+                    "RHO_VS_WIDTH_AND_SPACING",
+                    table(&conductor.rho_vs_width_spacing),
+                );
+                push(
+                    &target,
+                    "RHO_VS_SI_WIDTH_AND_THICKNESS",
+                    table(&conductor.rho_vs_si_width_thickness),
+                );
+                push(
+                    &target,
+                    "ETCH_VS_WIDTH_AND_SPACING",
+                    table(&conductor.etch_vs_width_spacing),
+                );
+                push(&target, "ETCH_FROM_TOP", table(&conductor.etch_from_top));
+                push(
+                    &target,
+                    "THICKNESS_VS_WIDTH_AND_SPACING",
+                    table(&conductor.thickness_vs_width_spacing),
+                );
+                push(
+                    &target,
+                    "CRT_VS_SI_WIDTH",
+                    conductor
+                        .crt_vs_si_width
+                        .as_ref()
+                        .map(|t| QueryValue::Text(format!("{} entry table", t.widths.len()))),
+                );
+                push(
+                    &target,
+                    "POLYNOMIAL_BASED_THICKNESS_VARIATION",
+                    conductor
+                        .process_variation
+                        .as_ref()
+                        .map(|_| QueryValue::Text("defined".to_string())),
+                );
+            }
+        }
+    }
+
+    for via in stack.via_stack.iter() {
+        let target = SearchTarget::Via(via.name.clone());
+        push(&target, "NAME", Some(QueryValue::Text(via.name.clone())));
+        push(
+            &target,
+            "FROM",
+            Some(QueryValue::Text(via.from_layer.clone())),
+        );
+        push(&target, "TO", Some(QueryValue::Text(via.to_layer.clone())));
+        push(&target, "AREA", Some(QueryValue::Number(via.area)));
+        push(
+            &target,
+            "RPV",
+            Some(QueryValue::Number(via.resistance_per_via)),
+        );
+    }
+
+    properties
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, TechnologyInfo, ViaConnection};
+
+    fn create_test_stack() -> ProcessStack {
+        let tech = TechnologyInfo::new("search_tech".to_string());
+        let mut stack = ProcessStack::new(tech);
+
+        let mut oxide = DielectricLayer::new("oxide1".to_string(), 1.0, 4.2);
+        oxide.sw_t = Some(0.01);
+        stack.add_layer(Layer::Dielectric(oxide));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "low_k".to_string(),
+            0.5,
+            2.7,
+        )));
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.3);
+        metal.electrical_props.rpsq = Some(0.05);
+        metal.physical_props.width_min = Some(0.1);
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+        stack.via_stack.add_via(ViaConnection::new(
+            "via1".to_string(),
+            "metal1".to_string(),
+            "metal2".to_string(),
+            0.04,
+            5.0,
+        ));
+
+        stack
+    }
+
+    #[test]
+    fn test_parse_search_query() {
+        assert_eq!(SearchQuery::parse("  "), SearchQuery::Empty);
+        assert_eq!(
+            SearchQuery::parse("SW_T"),
+            SearchQuery::Text("sw_t".to_string())
+        );
+        assert_eq!(
+            SearchQuery::parse("ER<3.0"),
+            SearchQuery::Compare {
+                key: "ER".to_string(),
+                op: CompareOp::Less,
+                value: 3.0,
+            }
+        );
+        assert_eq!(
+            SearchQuery::parse("thickness >= 1e-1"),
+            SearchQuery::Compare {
+                key: "thickness".to_string(),
+                op: CompareOp::GreaterEqual,
+                value: 0.1,
+            }
+        );
+        assert!(matches!(
+            SearchQuery::parse("ER < abc"),
+            SearchQuery::Text(_)
+        ));
+    }
+
+    #[test]
+    fn test_search_stack() {
+        let stack = create_test_stack();
+
+        let hits = search_stack(&stack, "ER<3.0");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].target, SearchTarget::Layer("low_k".to_string()));
+
+        let hits = search_stack(&stack, "sw_t");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].target.name(), Some("oxide1"));
+        assert_eq!(hits[0].value, QueryValue::Number(0.01));
+
+        // Layer names match their own properties and the vias that reference them
+        let hits = search_stack(&stack, "metal1");
+        assert!(hits
+            .iter()
+            .any(|hit| hit.target == SearchTarget::Via("via1".to_string()) && hit.key == "FROM"));
+        assert!(hits.iter().any(
+            |hit| hit.target == SearchTarget::Layer("metal1".to_string()) && hit.key == "NAME"
+        ));
+        assert_eq!(hits.len(), 2);
+
+        let hits = search_stack(&stack, "rpv=5");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].target.name(), Some("via1"));
+
+        assert!(search_stack(&stack, "").is_empty());
+        assert!(search_stack(&stack, "wmin>1").is_empty());
+    }
+}
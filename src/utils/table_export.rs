@@ -0,0 +1,437 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{Diagnostic, Layer, MaterialDatabase, MetalDensities, ProcessStack};
+use crate::utils::{evaluate_layer_expression, query_layer_field, QueryError, QUERY_FIELDS};
+use std::collections::HashMap;
+
+/// A user-defined column computed per conductor from an expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedColumn {
+    pub name: String,
+    pub expression: String,
+}
+
+impl DerivedColumn {
+    pub fn new(name: impl Into<String>, expression: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            expression: expression.into(),
+        }
+    }
+
+    /// Parse a `name=expression` definition, e.g. `fom=rpsq*length/wmin`
+    pub fn parse(definition: &str) -> Result<Self, QueryError> {
+        let (name, expression) = split_definition(definition)?;
+        Ok(Self::new(name, expression))
+    }
+
+    /// Check the expression against the first conductor of the stack
+    ///
+    /// Values missing on that conductor are accepted; syntax errors and unknown
+    /// fields or variables are reported.
+    pub fn validate(
+        &self,
+        stack: &ProcessStack,
+        variables: &HashMap<String, f64>,
+    ) -> Result<(), QueryError> {
+        let Some(conductor) = stack.get_conductor_layers().into_iter().next() else {
+            return Ok(());
+        };
+
+        // Stand in for missing fields so evaluation reaches the whole expression
+        let mut sample_variables = variables.clone();
+        for field in QUERY_FIELDS {
+            if let Err(QueryError::MissingValue { .. }) =
+                query_layer_field(stack, conductor.name(), field)
+            {
+                sample_variables.entry(field.to_string()).or_insert(1.0);
+            }
+        }
+
+        match evaluate_layer_expression(
+            stack,
+            conductor.name(),
+            &self.expression,
+            &sample_variables,
+        ) {
+            Ok(_) | Err(QueryError::MissingValue { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Parse a `name=value` variable definition, e.g. `length=100`
+pub fn parse_variable(definition: &str) -> Result<(String, f64), QueryError> {
+    let (name, value) = split_definition(definition)?;
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| QueryError::ExpressionError(format!("Invalid value for '{name}': {value}")))?;
+    Ok((name.to_string(), value))
+}
+
+fn split_definition(definition: &str) -> Result<(&str, &str), QueryError> {
+    let (name, rest) = definition
+        .split_once('=')
+        .map(|(name, rest)| (name.trim(), rest.trim()))
+        .ok_or_else(|| {
+            QueryError::ExpressionError(format!("Expected name=expression, got '{definition}'"))
+        })?;
+
+    if name.is_empty() || rest.is_empty() {
+        return Err(QueryError::ExpressionError(format!(
+            "Expected name=expression, got '{definition}'"
+        )));
+    }
+
+    Ok((name, rest))
+}
+
+/// Build a CSV table with one row per layer
+///
+/// The standard columns are the fields in `QUERY_FIELDS`; derived columns follow and are
+/// only filled for conductors. Cells whose value is undefined are left empty.
+pub fn export_layer_table_csv(
+    stack: &ProcessStack,
+    derived_columns: &[DerivedColumn],
+    variables: &HashMap<String, f64>,
+) -> String {
+    let header: Vec<String> = QUERY_FIELDS
+        .iter()
+        .map(|field| field.to_string())
+        .chain(derived_columns.iter().map(|column| csv_field(&column.name)))
+        .collect();
+
+    let mut csv = header.join(",");
+    csv.push('\n');
+
+    for layer in &stack.layers {
+        let name = layer.name();
+        let mut row: Vec<String> = QUERY_FIELDS
+            .iter()
+            .map(|field| {
+                query_layer_field(stack, name, field)
+                    .map(|value| csv_field(&value.to_string()))
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        for column in derived_columns {
+            let value = match layer {
+                Layer::Conductor(_) => {
+                    evaluate_layer_expression(stack, name, &column.expression, variables)
+                        .ok()
+                        .filter(|value| value.is_finite())
+                }
+                Layer::Dielectric(_) => None,
+            };
+            row.push(value.map(|v| v.to_string()).unwrap_or_default());
+        }
+
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Build a CSV table of parse diagnostics, one row per diagnostic
+///
+/// Appended to a layer table export so QA records show how the file deviated from
+/// what the parser expected. Diagnostics without a line number leave that cell empty.
+pub fn export_diagnostics_csv(diagnostics: &[Diagnostic]) -> String {
+    let mut csv = String::from("severity,line,message\n");
+    for diagnostic in diagnostics {
+        let line = diagnostic
+            .line
+            .map(|line| line.to_string())
+            .unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            diagnostic.severity.label(),
+            line,
+            csv_field(&diagnostic.message)
+        ));
+    }
+    csv
+}
+
+/// Build a CSV table of the dielectric gaps between adjacent conductors, bottom up
+///
+/// Thicknesses are in um; the dielectrics between the pair are listed space-separated.
+pub fn export_dielectric_gaps_csv(stack: &ProcessStack) -> String {
+    let mut csv = String::from("lower,upper,dielectric_thickness,vertical_gap,dielectrics\n");
+    for gap in stack.get_dielectric_gaps() {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&gap.lower_conductor),
+            csv_field(&gap.upper_conductor),
+            gap.dielectric_thickness,
+            gap.vertical_gap,
+            csv_field(&gap.dielectric_layers.join(" "))
+        ));
+    }
+    csv
+}
+
+/// Build a CSV table of the assumed metal densities and resulting thicknesses, bottom up
+///
+/// `assumed` is `default` for metals without a user value. Thicknesses are in um.
+pub fn export_metal_density_csv(stack: &ProcessStack, densities: &MetalDensities) -> String {
+    let mut csv = String::from("metal,density,assumed,nominal_thickness,thickness\n");
+    for metal in stack.get_density_context(densities) {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&metal.layer_name),
+            metal.density,
+            if metal.user_set { "user" } else { "default" },
+            metal.nominal_thickness,
+            metal.thickness
+        ));
+    }
+    csv
+}
+
+/// Build a CSV table of the resistance drift of each conductor from -40 °C to 125 °C
+///
+/// Drifts are in percent relative to the CRT reference temperature; `crt_width` is the
+/// CRT_VS_SI_WIDTH width drifting the most, empty for fixed CRT1/CRT2.
+pub fn export_temperature_drift_csv(stack: &ProcessStack) -> String {
+    let summary = stack.get_temperature_drift();
+    let mut csv = String::from(
+        "conductor,reference_temperature,crt_width,cold_drift,hot_drift,worst_drift,worst_temperature\n",
+    );
+    for drift in &summary.conductors {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_field(&drift.layer_name),
+            summary.reference.celsius(),
+            drift
+                .crt_width
+                .map(|width| width.to_string())
+                .unwrap_or_default(),
+            drift.drift_percent.first().copied().unwrap_or_default(),
+            drift.drift_percent.last().copied().unwrap_or_default(),
+            drift.worst_drift_percent,
+            drift.worst_temperature.celsius()
+        ));
+    }
+    csv
+}
+
+/// Build a CSV table of the material tagged on each layer
+///
+/// `value` is the resistivity in ohm·um of conductors or the ER of dielectrics, `source`
+/// is `user` for materials from `overrides` and `auto` for inferred ones.
+pub fn export_layer_materials_csv(
+    stack: &ProcessStack,
+    database: &MaterialDatabase,
+    overrides: &HashMap<String, String>,
+) -> String {
+    let mut csv = String::from("layer,material,source,value,color\n");
+    for layer in stack.get_layer_materials(database, overrides) {
+        let (name, color) = match layer.material {
+            Some(material) => {
+                let [r, g, b] = material.color;
+                (material.name, format!("#{r:02x}{g:02x}{b:02x}"))
+            }
+            None => (String::new(), String::new()),
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&layer.layer_name),
+            csv_field(&name),
+            if layer.user_set { "user" } else { "auto" },
+            layer
+                .value
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+            color
+        ));
+    }
+    csv
+}
+
+/// Quote a CSV field when it contains separators, quotes or newlines
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, DielectricLayer, Severity, TechnologyInfo};
+
+    fn create_test_stack() -> ProcessStack {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("table_tech".to_string()));
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "oxide1".to_string(),
+            1.0,
+            4.2,
+        )));
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.2);
+        metal.electrical_props.rpsq = Some(0.05);
+        metal.physical_props.width_min = Some(0.1);
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+        stack
+    }
+
+    #[test]
+    fn test_parse_definitions() {
+        assert_eq!(
+            DerivedColumn::parse("fom = rpsq*length/wmin").unwrap(),
+            DerivedColumn::new("fom", "rpsq*length/wmin")
+        );
+        assert!(DerivedColumn::parse("rpsq*length").is_err());
+        assert!(DerivedColumn::parse("fom=").is_err());
+
+        assert_eq!(
+            parse_variable("length=100").unwrap(),
+            ("length".to_string(), 100.0)
+        );
+        assert!(parse_variable("length=abc").is_err());
+    }
+
+    #[test]
+    fn test_validate_derived_column() {
+        let stack = create_test_stack();
+        let variables = HashMap::from([("length".to_string(), 100.0)]);
+
+        assert!(DerivedColumn::new("fom", "rpsq*length/wmin")
+            .validate(&stack, &variables)
+            .is_ok());
+        // Missing values on the sample conductor are not an error
+        assert!(DerivedColumn::new("c", "crt1*2")
+            .validate(&stack, &variables)
+            .is_ok());
+        assert!(DerivedColumn::new("bad", "rpsq*")
+            .validate(&stack, &variables)
+            .is_err());
+        assert!(DerivedColumn::new("bad", "rpsq*width")
+            .validate(&stack, &variables)
+            .is_err());
+        assert!(DerivedColumn::new("bad", "crt1*")
+            .validate(&stack, &variables)
+            .is_err());
+    }
+
+    #[test]
+    fn test_export_layer_table_csv() {
+        let stack = create_test_stack();
+        let variables = HashMap::from([("length".to_string(), 100.0)]);
+        let columns = vec![DerivedColumn::new("R, min width", "rpsq*length/wmin")];
+
+        let csv = export_layer_table_csv(&stack, &columns, &variables);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("name,type,thickness,"));
+        assert!(lines[0].ends_with(",\"R, min width\""));
+        assert!(lines[1].starts_with("oxide1,Dielectric,1,"));
+        assert!(lines[1].ends_with(','));
+        assert!(lines[2].starts_with("metal1,Conductor,0.2,"));
+        assert!(lines[2].ends_with(",50"));
+        assert_eq!(
+            lines[2].split(',').count(),
+            QUERY_FIELDS.len() + columns.len()
+        );
+    }
+
+    #[test]
+    fn test_export_metal_density_csv() {
+        let stack = create_test_stack();
+        let mut densities = MetalDensities::new();
+        assert_eq!(
+            export_metal_density_csv(&stack, &densities),
+            "metal,density,assumed,nominal_thickness,thickness\nmetal1,0.5,default,0.2,0.2\n"
+        );
+
+        densities.set("metal1", Some(0.3));
+        assert!(
+            export_metal_density_csv(&stack, &densities).ends_with("\nmetal1,0.3,user,0.2,0.2\n")
+        );
+    }
+
+    #[test]
+    fn test_export_temperature_drift_csv() {
+        let mut stack = create_test_stack();
+        assert_eq!(
+            export_temperature_drift_csv(&stack).lines().count(),
+            1,
+            "no conductor has CRT values"
+        );
+
+        if let Some(Layer::Conductor(metal)) = stack.get_layer_mut("metal1") {
+            metal.electrical_props.crt1 = Some(2.0e-3);
+        }
+        let csv = export_temperature_drift_csv(&stack);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "conductor,reference_temperature,crt_width,cold_drift,hot_drift,worst_drift,worst_temperature"
+        );
+        assert_eq!(lines[1], "metal1,25,,-13,20,20,125");
+    }
+
+    #[test]
+    fn test_export_layer_materials_csv() {
+        let stack = create_test_stack();
+        let mut overrides = HashMap::new();
+        let csv = export_layer_materials_csv(&stack, &MaterialDatabase::builtin(), &overrides);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "layer,material,source,value,color");
+        assert_eq!(lines[1], "oxide1,SiO2,auto,4.2,#c8dcf0");
+        assert_eq!(lines[2], "metal1,,auto,0.010000000000000002,");
+
+        overrides.insert("metal1".to_string(), "Cu".to_string());
+        let csv = export_layer_materials_csv(&stack, &MaterialDatabase::builtin(), &overrides);
+        assert!(csv.ends_with("\nmetal1,Cu,user,0.010000000000000002,#b87333\n"));
+    }
+
+    #[test]
+    fn test_export_diagnostics_csv() {
+        let diagnostics = vec![
+            Diagnostic::new(Severity::Warning, "Skipped unrecognized line: FOO, BAR").with_line(12),
+            Diagnostic::new(Severity::Info, "Auto-created layer 'substrate'"),
+        ];
+
+        let csv = export_diagnostics_csv(&diagnostics);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "severity,line,message");
+        assert_eq!(
+            lines[1],
+            "warning,12,\"Skipped unrecognized line: FOO, BAR\""
+        );
+        assert_eq!(lines[2], "info,,Auto-created layer 'substrate'");
+    }
+
+    #[test]
+    fn test_export_dielectric_gaps_csv() {
+        let mut stack = create_test_stack();
+        stack.add_layer(Layer::Dielectric(DielectricLayer::new(
+            "ild".to_string(),
+            0.5,
+            4.0,
+        )));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "poly".to_string(),
+            0.1,
+        ))));
+
+        let csv = export_dielectric_gaps_csv(&stack);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "lower,upper,dielectric_thickness,vertical_gap,dielectrics",
+                "poly,metal1,0.5,0.4,ild",
+            ]
+        );
+    }
+}
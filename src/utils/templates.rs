@@ -0,0 +1,386 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::ProcessStack;
+use crate::parser::{parse_itf_file, ParseError};
+use std::fmt::Write;
+
+/// Built-in example stacks with made-up but plausible values
+///
+/// Templates are generated as ITF text and run through the regular parser, so they
+/// exercise the same code paths as real foundry files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackTemplate {
+    /// Generic logic process with thin lower metals and a few thicker global layers
+    GenericLogic,
+    /// Logic stack topped by thick low-resistance metals for inductors
+    RfThickTopMetal,
+    /// Silicon interposer with a few thick redistribution layers
+    Interposer,
+}
+
+impl StackTemplate {
+    pub const ALL: [StackTemplate; 3] = [
+        StackTemplate::GenericLogic,
+        StackTemplate::RfThickTopMetal,
+        StackTemplate::Interposer,
+    ];
+
+    /// Short identifier used on the command line
+    pub fn id(&self) -> &'static str {
+        match self {
+            StackTemplate::GenericLogic => "logic",
+            StackTemplate::RfThickTopMetal => "rf",
+            StackTemplate::Interposer => "interposer",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|template| template.id().eq_ignore_ascii_case(id))
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StackTemplate::GenericLogic => "Generic logic",
+            StackTemplate::RfThickTopMetal => "RF thick top metal",
+            StackTemplate::Interposer => "Interposer",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            StackTemplate::GenericLogic => "Poly plus copper metals, thicker towards the top",
+            StackTemplate::RfThickTopMetal => {
+                "Logic metals under a thick copper and an aluminum top layer"
+            }
+            StackTemplate::Interposer => "Thick copper redistribution layers in polymer",
+        }
+    }
+
+    /// Allowed number of metal layers
+    pub fn metal_range(&self) -> std::ops::RangeInclusive<usize> {
+        match self {
+            StackTemplate::GenericLogic => 2..=12,
+            StackTemplate::RfThickTopMetal => 3..=12,
+            StackTemplate::Interposer => 1..=4,
+        }
+    }
+
+    pub fn default_params(&self) -> TemplateParams {
+        let metal_count = match self {
+            StackTemplate::GenericLogic => 5,
+            StackTemplate::RfThickTopMetal => 6,
+            StackTemplate::Interposer => 3,
+        };
+        TemplateParams {
+            metal_count,
+            thickness_scale: 1.0,
+            temperature: 25.0,
+        }
+    }
+
+    /// Generate the template as ITF text
+    pub fn to_itf(&self, params: &TemplateParams) -> String {
+        let metal_range = self.metal_range();
+        let metal_count = params
+            .metal_count
+            .clamp(*metal_range.start(), *metal_range.end());
+        let scale = if params.thickness_scale > 0.0 {
+            params.thickness_scale
+        } else {
+            1.0
+        };
+
+        let mut itf = String::new();
+        let _ = writeln!(
+            itf,
+            "$ {} template ({metal_count} metals), generated by itf-viewer",
+            self.label()
+        );
+        let _ = writeln!(itf, "TECHNOLOGY = template_{}_{metal_count}m", self.id());
+        let _ = writeln!(itf, "GLOBAL_TEMPERATURE = {}", params.temperature);
+        let _ = writeln!(itf, "REFERENCE_DIRECTION = VERTICAL");
+        let _ = writeln!(itf, "BACKGROUND_ER = 4.0");
+        itf.push('\n');
+
+        // Layers are written from the top of the chip down, like foundry files
+        let metals = match self {
+            StackTemplate::GenericLogic => logic_metals(metal_count),
+            StackTemplate::RfThickTopMetal => rf_metals(metal_count),
+            StackTemplate::Interposer => interposer_metals(metal_count),
+        };
+        let (passivation, bottom) = match self {
+            StackTemplate::Interposer => (
+                vec![Dielectric::new("PASSIVATION", 5.0, 3.0)],
+                vec![Dielectric::new("LINER", 1.0, 3.9)],
+            ),
+            _ => (
+                vec![
+                    Dielectric::new("PASS2", 0.6, 7.0),
+                    Dielectric::new("PASS1", 0.4, 4.2),
+                ],
+                vec![Dielectric::new("ILD0", 0.35, 4.2)],
+            ),
+        };
+
+        for dielectric in &passivation {
+            dielectric.write(&mut itf, scale);
+        }
+        for metal in metals.iter().rev() {
+            metal.write(&mut itf, scale);
+            Dielectric::new(
+                &format!("IMD_{}", metal.name),
+                metal.imd_thickness,
+                metal.imd_er,
+            )
+            .write(&mut itf, scale);
+        }
+        if *self != StackTemplate::Interposer {
+            poly().write(&mut itf, scale);
+        }
+        for dielectric in &bottom {
+            dielectric.write(&mut itf, scale);
+        }
+        itf.push('\n');
+
+        // Vias between neighboring metals, plus the poly contact
+        for pair in metals.windows(2) {
+            let (lower, upper) = (&pair[0], &pair[1]);
+            let _ = writeln!(
+                itf,
+                "VIA VIA_{} {{ FROM={} TO={} AREA={} RPV={} }}",
+                lower.name, lower.name, upper.name, upper.via_area, upper.via_rpv
+            );
+        }
+        if *self != StackTemplate::Interposer {
+            let _ = writeln!(
+                itf,
+                "VIA CONT {{ FROM=poly TO={} AREA=0.0036 RPV=25 }}",
+                metals[0].name
+            );
+        }
+
+        itf
+    }
+
+    /// Generate and parse the template
+    pub fn build(&self, params: &TemplateParams) -> Result<ProcessStack, ParseError> {
+        parse_itf_file(&self.to_itf(params))
+    }
+}
+
+/// Parameters shared by all templates
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateParams {
+    /// Number of metal layers, clamped to the template's `metal_range`
+    pub metal_count: usize,
+    /// Factor applied to every layer thickness
+    pub thickness_scale: f64,
+    pub temperature: f64,
+}
+
+impl TemplateParams {
+    pub fn with_metal_count(mut self, metal_count: usize) -> Self {
+        self.metal_count = metal_count;
+        self
+    }
+
+    pub fn with_thickness_scale(mut self, thickness_scale: f64) -> Self {
+        self.thickness_scale = thickness_scale;
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+}
+
+struct Dielectric {
+    name: String,
+    thickness: f64,
+    er: f64,
+}
+
+impl Dielectric {
+    fn new(name: &str, thickness: f64, er: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            thickness,
+            er,
+        }
+    }
+
+    fn write(&self, itf: &mut String, scale: f64) {
+        let _ = writeln!(
+            itf,
+            "DIELECTRIC {} {{THICKNESS={} ER={}}}",
+            self.name,
+            round(self.thickness * scale),
+            self.er
+        );
+    }
+}
+
+/// A conductor with the dielectric below it and the via landing on it from below
+struct Metal {
+    name: String,
+    thickness: f64,
+    /// Bulk resistivity in ohm*um, RPSQ is derived from it and the thickness
+    resistivity: f64,
+    crt1: f64,
+    wmin: f64,
+    side_tangent: f64,
+    imd_thickness: f64,
+    imd_er: f64,
+    via_area: f64,
+    via_rpv: f64,
+}
+
+impl Metal {
+    fn copper(name: String, thickness: f64, wmin: f64) -> Self {
+        Self {
+            name,
+            thickness,
+            resistivity: 0.022,
+            crt1: 3.8e-3,
+            wmin,
+            side_tangent: 0.05,
+            imd_thickness: thickness * 1.6,
+            imd_er: 2.9,
+            via_area: round(wmin * wmin),
+            via_rpv: round(0.3 / wmin),
+        }
+    }
+
+    fn write(&self, itf: &mut String, scale: f64) {
+        let thickness = round(self.thickness * scale);
+        let _ = writeln!(itf, "CONDUCTOR {} {{", self.name);
+        let _ = writeln!(itf, "    THICKNESS={thickness}");
+        let _ = writeln!(itf, "    CRT1={:e} CRT2=-1.0e-7", self.crt1);
+        let _ = writeln!(itf, "    RPSQ={}", round(self.resistivity / thickness));
+        let _ = writeln!(itf, "    WMIN={} SMIN={}", self.wmin, self.wmin);
+        let _ = writeln!(itf, "    SIDE_TANGENT={}", self.side_tangent);
+        let _ = writeln!(itf, "}}");
+    }
+}
+
+fn logic_metals(metal_count: usize) -> Vec<Metal> {
+    (1..=metal_count)
+        .map(|index| {
+            // Lower half local routing, then intermediate, the top two are global
+            let (thickness, wmin) = if index > metal_count.saturating_sub(2) && metal_count > 3 {
+                (0.9, 0.4)
+            } else if index > metal_count / 2 {
+                (0.28, 0.1)
+            } else {
+                (0.14, 0.05)
+            };
+            Metal::copper(format!("metal{index}"), thickness, wmin)
+        })
+        .collect()
+}
+
+fn rf_metals(metal_count: usize) -> Vec<Metal> {
+    let mut metals = logic_metals(metal_count - 2);
+
+    let mut thick = Metal::copper(format!("metal{}", metal_count - 1), 3.3, 2.0);
+    thick.imd_thickness = 2.5;
+    thick.imd_er = 4.0;
+    metals.push(thick);
+
+    let mut aluminum = Metal::copper("AP".to_string(), 2.8, 3.0);
+    aluminum.resistivity = 0.028;
+    aluminum.crt1 = 4.0e-3;
+    aluminum.side_tangent = -0.03;
+    aluminum.imd_thickness = 1.5;
+    aluminum.imd_er = 4.1;
+    aluminum.via_area = 9.0;
+    aluminum.via_rpv = 0.02;
+    metals.push(aluminum);
+
+    metals
+}
+
+fn interposer_metals(metal_count: usize) -> Vec<Metal> {
+    (1..=metal_count)
+        .map(|index| {
+            let mut rdl = Metal::copper(format!("RDL{index}"), 2.0, 2.0);
+            rdl.side_tangent = 0.0;
+            rdl.imd_thickness = 5.0;
+            rdl.imd_er = 3.0;
+            rdl.via_area = 25.0;
+            rdl.via_rpv = 0.005;
+            rdl
+        })
+        .collect()
+}
+
+fn poly() -> Metal {
+    let mut poly = Metal::copper("poly".to_string(), 0.1, 0.04);
+    poly.resistivity = 0.8;
+    poly.crt1 = 2.0e-3;
+    poly.side_tangent = 0.0;
+    poly
+}
+
+/// Keep generated values short and free of float noise
+fn round(value: f64) -> f64 {
+    (value * 1e6).round() / 1e6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_build() {
+        for template in StackTemplate::ALL {
+            let params = template.default_params();
+            let stack = template.build(&params).unwrap();
+
+            assert!(stack.get_conductor_count() >= params.metal_count);
+            assert!(stack.get_via_count() >= params.metal_count - 1);
+            assert!(stack.get_invalid_vias().is_empty());
+            assert!(stack.get_via_span_warnings().is_empty());
+            assert!(stack.validate_stack().is_ok());
+            assert_eq!(StackTemplate::from_id(template.id()), Some(template));
+        }
+    }
+
+    #[test]
+    fn test_template_params() {
+        let template = StackTemplate::GenericLogic;
+        let params = template
+            .default_params()
+            .with_metal_count(8)
+            .with_thickness_scale(2.0)
+            .with_temperature(85.0);
+        let stack = template.build(&params).unwrap();
+
+        assert!(stack.get_layer("metal8").is_some());
+        assert!(stack.get_layer("metal9").is_none());
+        assert_eq!(stack.technology_info.global_temperature, Some(85.0));
+        let scaled = template
+            .build(&params.clone().with_thickness_scale(1.0))
+            .unwrap();
+        approx::assert_relative_eq!(
+            stack.get_total_height(),
+            scaled.get_total_height() * 2.0,
+            epsilon = 1e-4
+        );
+
+        // Out of range metal counts are clamped
+        let stack = StackTemplate::Interposer
+            .build(
+                &StackTemplate::Interposer
+                    .default_params()
+                    .with_metal_count(10),
+            )
+            .unwrap();
+        assert!(stack.get_layer("RDL4").is_some());
+        assert!(stack.get_layer("RDL5").is_none());
+    }
+}
@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+use crate::data::{LintConfig, ProcessStack, Severity};
+use crate::utils::json::Json;
+use std::fmt;
+
+/// Overall outcome of validating a techfile, from the most severe issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ValidationStatus {
+    Ok,
+    Warnings,
+    Errors,
+}
+
+impl ValidationStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            ValidationStatus::Ok => "ok",
+            ValidationStatus::Warnings => "warnings",
+            ValidationStatus::Errors => "errors",
+        }
+    }
+
+    /// Process exit code: 0 ok, 1 warnings, 2 errors
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ValidationStatus::Ok => 0,
+            ValidationStatus::Warnings => 1,
+            ValidationStatus::Errors => 2,
+        }
+    }
+}
+
+/// Parse diagnostic, lint finding or load failure found while validating
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Lint rule id, none for parse diagnostics and load failures
+    pub rule: Option<&'static str>,
+    /// 1-based line in the source file
+    pub line: Option<usize>,
+    /// Layer or via the issue is about
+    pub layer: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.severity.label())?;
+        if let Some(rule) = self.rule {
+            write!(f, "[{rule}]")?;
+        }
+        if let Some(line) = self.line {
+            write!(f, ": line {line}")?;
+        }
+        if let Some(layer) = &self.layer {
+            write!(f, ": {layer}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Issues of one techfile, parse diagnostics first and then lint findings in stack order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationReport {
+    pub file: String,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Report on a parsed stack, linted with `config`
+    pub fn from_stack(file: &str, stack: &ProcessStack, config: &LintConfig) -> Self {
+        let diagnostics = stack.diagnostics.iter().map(|diagnostic| ValidationIssue {
+            severity: diagnostic.severity,
+            rule: None,
+            line: diagnostic.target(stack).line,
+            layer: diagnostic.layer.clone(),
+            message: diagnostic.message.clone(),
+        });
+        let findings = stack
+            .lint(config)
+            .into_iter()
+            .map(|finding| ValidationIssue {
+                severity: finding.severity(),
+                rule: Some(finding.rule.id()),
+                line: stack.get_source_line(&finding.layer),
+                layer: Some(finding.layer),
+                message: finding.message,
+            });
+        Self {
+            file: file.to_string(),
+            issues: diagnostics.chain(findings).collect(),
+        }
+    }
+
+    /// Report on a file that could not be read or parsed
+    pub fn load_failure(file: &str, message: impl Into<String>) -> Self {
+        Self {
+            file: file.to_string(),
+            issues: vec![ValidationIssue {
+                severity: Severity::Error,
+                rule: None,
+                line: None,
+                layer: None,
+                message: message.into(),
+            }],
+        }
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+
+    pub fn status(&self) -> ValidationStatus {
+        match self.issues.iter().map(|issue| issue.severity).max() {
+            Some(Severity::Error) => ValidationStatus::Errors,
+            Some(Severity::Warning) => ValidationStatus::Warnings,
+            Some(Severity::Info) | None => ValidationStatus::Ok,
+        }
+    }
+
+    /// The report as a JSON object for CI tools
+    pub fn to_json(&self) -> String {
+        let count = |severity| Json::Integer(self.count(severity) as i64);
+        let issues = self
+            .issues
+            .iter()
+            .map(|issue| {
+                Json::Object(vec![
+                    ("severity".to_string(), Json::string(issue.severity.label())),
+                    (
+                        "rule".to_string(),
+                        issue.rule.map_or(Json::Null, Json::string),
+                    ),
+                    (
+                        "line".to_string(),
+                        issue
+                            .line
+                            .map_or(Json::Null, |line| Json::Integer(line as i64)),
+                    ),
+                    (
+                        "layer".to_string(),
+                        issue.layer.as_deref().map_or(Json::Null, Json::string),
+                    ),
+                    ("message".to_string(), Json::string(&issue.message)),
+                ])
+            })
+            .collect();
+        Json::Object(vec![
+            ("file".to_string(), Json::string(&self.file)),
+            ("status".to_string(), Json::string(self.status().label())),
+            (
+                "exit_code".to_string(),
+                Json::Integer(self.status().exit_code().into()),
+            ),
+            ("errors".to_string(), count(Severity::Error)),
+            ("warnings".to_string(), count(Severity::Warning)),
+            ("infos".to_string(), count(Severity::Info)),
+            ("diagnostics".to_string(), Json::Array(issues)),
+        ])
+        .to_json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ConductorLayer, Diagnostic, Layer, TechnologyInfo};
+
+    #[test]
+    fn test_validation_status() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("validate".to_string()));
+        stack.add_layer(Layer::Conductor(Box::new(ConductorLayer::new(
+            "metal1".to_string(),
+            0.2,
+        ))));
+        let report = ValidationReport::from_stack("ok.itf", &stack, &LintConfig::new());
+        assert_eq!(report.status(), ValidationStatus::Ok);
+        assert_eq!(report.status().exit_code(), 0);
+
+        stack
+            .diagnostics
+            .push(Diagnostic::new(Severity::Warning, "Skipped unknown keyword FOO").with_line(3));
+        let report = ValidationReport::from_stack("warn.itf", &stack, &LintConfig::new());
+        assert_eq!(report.status().exit_code(), 1);
+        assert_eq!(
+            report.issues[0].to_string(),
+            "warning: line 3: Skipped unknown keyword FOO"
+        );
+
+        let report = ValidationReport::load_failure("bad.itf", "Parse error: unexpected }");
+        assert_eq!(report.status(), ValidationStatus::Errors);
+        assert_eq!(report.status().exit_code(), 2);
+    }
+
+    #[test]
+    fn test_validation_json() {
+        let mut stack = ProcessStack::new(TechnologyInfo::new("validate".to_string()));
+        let mut metal = ConductorLayer::new("metal1".to_string(), 0.2);
+        metal.electrical_props.rpsq = Some(0.0);
+        stack.add_layer(Layer::Conductor(Box::new(metal)));
+        stack.set_source_line("metal1", 7);
+
+        let json = ValidationReport::from_stack("a\"b.itf", &stack, &LintConfig::new()).to_json();
+        assert!(json.contains("\"file\": \"a\\\"b.itf\""));
+        assert!(json.contains("\"status\": \"warnings\""));
+        assert!(json.contains("\"exit_code\": 1"));
+        assert!(json.contains("\"rule\": \"zero-rpsq\""));
+        assert!(json.contains("\"line\": 7"));
+        assert!(json.contains("\"layer\": \"metal1\""));
+    }
+}
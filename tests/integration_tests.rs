@@ -307,10 +307,13 @@ fn test_library_functions() {
     assert!(!validate_itf_content(invalid_content));
 
     // Test configuration
-    let config = get_default_config();
-    assert!(config.window_width > 0.0);
-    assert!(config.window_height > 0.0);
-    assert!(config.show_dimensions);
+    #[cfg(feature = "gui")]
+    {
+        let config = get_default_config();
+        assert!(config.window_width > 0.0);
+        assert!(config.window_height > 0.0);
+        assert!(config.show_dimensions);
+    }
 }
 
 #[test]
@@ -327,6 +330,22 @@ fn test_file_parsing_from_path() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_windows_latin1_file_parsing() {
+    // A BOM, CRLF line endings and a Latin-1 comment, as saved by some foundry tools
+    let content = fs::read_to_string("tests/data/simple_1p3m.itf").unwrap();
+    let mut bytes = b"\xEF\xBB\xBF$ Thickness in \xB5m\r\n".to_vec();
+    bytes.extend(content.replace('\n', "\r\n").into_bytes());
+    let file = tempfile::NamedTempFile::new().unwrap();
+    fs::write(file.path(), bytes).unwrap();
+
+    let stack = parse_itf_from_file(file.path());
+    let stack = stack.expect("Failed to parse Windows Latin-1 file");
+    let expected = parse_itf_file(&content).unwrap();
+    assert_eq!(stack.get_layer_count(), expected.get_layer_count());
+    assert!(stack.diagnostics[0].message.contains("Latin-1"));
+}
+
 #[test]
 fn test_process_summary_consistency() {
     let content =
@@ -347,7 +366,7 @@ fn test_process_summary_consistency() {
     );
 }
 
-#[cfg(test)]
+#[cfg(feature = "gui")]
 mod renderer_tests {
     use super::*;
     use egui::Vec2;
@@ -486,3 +505,93 @@ fn test_all_test_files_parse() {
         );
     }
 }
+
+#[test]
+fn test_fingerprint_ignores_formatting() {
+    let content =
+        fs::read_to_string("tests/data/simple_1p3m.itf").expect("Failed to read test file");
+    let fingerprint = parse_itf_file(&content).unwrap().fingerprint();
+
+    // Dropping comments and reordering properties describe the same process
+    let reformatted: String = content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('$'))
+        .map(|line| {
+            line.replace(
+                "DIELECTRIC ild1 {THICKNESS=0.8 ER=4.2}",
+                "DIELECTRIC ild1 { ER = 4.2  THICKNESS = 0.8 }",
+            ) + "\n"
+        })
+        .collect();
+    assert_ne!(reformatted, content);
+    assert_eq!(
+        parse_itf_file(&reformatted).unwrap().fingerprint(),
+        fingerprint
+    );
+
+    let changed = content.replace(
+        "DIELECTRIC ild1 {THICKNESS=0.8 ER=4.2}",
+        "DIELECTRIC ild1 {THICKNESS=0.8 ER=4.3}",
+    );
+    assert_ne!(parse_itf_file(&changed).unwrap().fingerprint(), fingerprint);
+}
+
+#[test]
+fn test_bundled_examples_match_test_data() {
+    for example in itf_viewer::utils::ExampleStack::ALL {
+        let path = format!("tests/data/{}", example.file_name());
+        let content = fs::read_to_string(&path).expect("Failed to read test file");
+        assert_eq!(example.source(), content);
+
+        let stack = example.load().expect("Failed to parse example");
+        assert_eq!(
+            stack.fingerprint(),
+            parse_itf_file(&content).unwrap().fingerprint()
+        );
+    }
+}
+
+#[test]
+fn test_file_history_from_git() {
+    use itf_viewer::utils::{file_history, read_revision};
+    use std::process::Command;
+
+    if Command::new("git").arg("--version").output().is_err() {
+        return;
+    }
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?}");
+    };
+    let file = repo.join("stack.itf");
+    let version = |thickness: f64| {
+        format!("TECHNOLOGY = history\nDIELECTRIC imd {{THICKNESS={thickness} ER=4.2}}\n")
+    };
+    git(&["init", "-q"]);
+    fs::write(&file, version(0.5)).unwrap();
+    git(&["add", "stack.itf"]);
+    git(&["commit", "-q", "-m", "First stack"]);
+    fs::write(&file, version(0.7)).unwrap();
+    git(&["commit", "-q", "-am", "Thicker imd"]);
+
+    let revisions = file_history(&file).unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0].summary, "Thicker imd");
+    assert_eq!(revisions[1].path, "stack.itf");
+
+    let old = parse_itf_file(&read_revision(&file, &revisions[1]).unwrap()).unwrap();
+    let new = parse_itf_file(&fs::read_to_string(&file).unwrap()).unwrap();
+    let diff = old.diff(&new);
+    assert_eq!(diff.layer_changes.len(), 1);
+    assert_eq!(diff.layer_changes[0].property, "THICKNESS");
+}
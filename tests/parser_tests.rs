@@ -32,6 +32,10 @@ fn test_parse_simple_1p3m() {
     assert_eq!(stack.get_conductor_count(), 4);
     assert_eq!(stack.get_dielectric_count(), 5);
 
+    // Every layer and via remembers the line of its definition
+    assert_eq!(stack.get_source_line("metal1"), Some(27));
+    assert_eq!(stack.get_source_line("via1"), Some(90));
+
     // Test specific layers
     let poly = stack.get_layer("poly");
     assert!(poly.is_some());
@@ -442,6 +446,82 @@ fn test_parse_complex_itf_file() {
     assert!(summary.total_height > 10.0); // Should be thick stack
 }
 
+#[test]
+fn test_parse_gate_layer_attributes() {
+    let content = fs::read_to_string("tests/data/complex_test.itf")
+        .expect("Failed to read real world test file");
+
+    let stack = parse_itf_file(&content).unwrap();
+
+    if let Some(Layer::Conductor(gate)) = stack.get_layer("n_gpoly") {
+        assert_eq!(gate.gate_props.layer_type, Some("GATE".to_string()));
+        assert_eq!(gate.gate_props.gate_to_contact_smin, Some(0.028197));
+        // Attributes following LAYER_TYPE on the same line must not be lost
+        assert_eq!(gate.thickness, 0.039688);
+        assert_eq!(gate.electrical_props.crt1, Some(2.843971e-04));
+        assert!(gate.rho_vs_si_width_thickness.is_some());
+        assert_eq!(gate.get_layer_type(), LayerType::Gate);
+    } else {
+        panic!("n_gpoly should be a conductor layer");
+    }
+
+    let field_poly = stack.get_layer("n_fpoly").unwrap();
+    assert_eq!(field_poly.layer_type(), LayerType::Gate);
+
+    if let Some(Layer::Conductor(diffusion)) = stack.get_layer("NOD") {
+        assert_eq!(
+            diffusion.gate_props.layer_type,
+            Some("DIFFUSION".to_string())
+        );
+        assert_eq!(diffusion.get_layer_type(), LayerType::Conductor);
+    } else {
+        panic!("NOD should be a conductor layer");
+    }
+
+    assert_eq!(
+        stack.get_layer("M1").unwrap().layer_type(),
+        LayerType::Conductor
+    );
+}
+
+#[test]
+fn test_parse_gate_forming_layer() {
+    let test_content = r#"
+TECHNOLOGY = test_gate
+
+DIELECTRIC FOX { THICKNESS = 0.300 ER = 3.9 }
+CONDUCTOR poly { GATE_FORMING_LAYER = TRUE THICKNESS = 0.080 RPSQ = 12.0
+    GATE_TO_DIFFUSION_CAP {
+        NUMBER_OF_TABLES = 1
+        NMOS {
+            CONTACT_TO_CONTACT_SPACINGS { 0.065 }
+            GATE_TO_CONTACT_SPACINGS { 0.065 }
+            CAPS_PER_MICRON { 0.0 }
+        }
+    }
+    WMIN = 0.040
+}
+DIELECTRIC PMD { THICKNESS = 0.200 ER = 4.1 }
+CONDUCTOR metal1 { THICKNESS = 0.150 RPSQ = 0.1 }
+"#;
+
+    let stack = parse_itf_file(test_content).unwrap();
+    assert_eq!(stack.get_conductor_count(), 2);
+
+    if let Some(Layer::Conductor(poly)) = stack.get_layer("poly") {
+        assert!(poly.gate_props.gate_forming_layer);
+        assert!(poly.gate_props.has_gate_to_diffusion_cap);
+        assert_eq!(poly.thickness, 0.080);
+        assert_eq!(poly.physical_props.width_min, Some(0.040));
+        assert_eq!(poly.get_layer_type(), LayerType::Gate);
+    } else {
+        panic!("poly should be a conductor layer");
+    }
+
+    let summary = stack.get_process_summary();
+    assert_eq!(summary.poly_layers, 1);
+}
+
 #[test]
 fn test_complex_via_parsing() {
     // Test parsing of complex VIA definitions with advanced properties
@@ -558,3 +638,315 @@ fn test_parse_complex_1p7m() {
     let viapa = stack.via_stack.vias.iter().find(|v| v.name == "viapa");
     assert!(viapa.is_some(), "viapa should exist");
 }
+
+#[test]
+fn test_parse_extra_properties() {
+    let test_content = r#"
+TECHNOLOGY = test_extra
+
+DIELECTRIC cap { THICKNESS = 0.1 ER = 7.0 MEASURED_FROM = TOP_OF_CHIP STRESS = -1.2e9 }
+CONDUCTOR metal1 { THICKNESS = 0.150 RPSQ = 0.1
+    THERMAL_COEFF = 2.5e-5 STRESS_NOTE = compressive
+}
+DIELECTRIC ild { THICKNESS = 0.3 ER = 4.2 }
+VIA via1 { FROM = metal1 TO = substrate AREA = 0.01 RPV = 2.0 EM_LIMIT = 0.4 }
+"#;
+
+    let stack = parse_itf_file(test_content).unwrap();
+
+    if let Some(Layer::Dielectric(cap)) = stack.get_layer("cap") {
+        assert_eq!(cap.dielectric_constant, 7.0);
+        assert_eq!(cap.measured_from, Some("TOP_OF_CHIP".to_string()));
+        assert_eq!(cap.extra_properties.len(), 1);
+        assert_eq!(
+            cap.extra_properties.get("STRESS"),
+            Some(&PropValue::Number(-1.2e9))
+        );
+    } else {
+        panic!("cap should be a dielectric layer");
+    }
+
+    let metal1 = stack.get_layer("metal1").unwrap();
+    assert_eq!(metal1.thickness(), 0.150);
+    let extra = metal1.extra_properties();
+    assert_eq!(
+        extra.get("THERMAL_COEFF").and_then(PropValue::as_number),
+        Some(2.5e-5)
+    );
+    assert_eq!(
+        extra.get("STRESS_NOTE").and_then(PropValue::as_text),
+        Some("compressive")
+    );
+
+    assert!(stack
+        .get_layer("ild")
+        .unwrap()
+        .extra_properties()
+        .is_empty());
+
+    let via = &stack.via_stack.vias[0];
+    assert_eq!(via.resistance_per_via, 2.0);
+    assert_eq!(
+        via.extra_properties.get("EM_LIMIT"),
+        Some(&PropValue::Number(0.4))
+    );
+}
+
+#[test]
+fn test_parse_only_etch_fields() {
+    let content =
+        fs::read_to_string("tests/data/complex_1p7m.itf").expect("Failed to read test file");
+    let stack = parse_itf_file(&content).unwrap();
+
+    if let Some(Layer::Conductor(metal7)) = stack.get_layer("metal7") {
+        assert_eq!(metal7.resistive_only_etch, Some(0.0));
+        assert_eq!(metal7.capacitive_only_etch, Some(-0.068203));
+        assert!(metal7.extra_properties.is_empty());
+    } else {
+        panic!("metal7 should be a conductor layer");
+    }
+}
+
+#[test]
+fn test_parse_diagnostics() {
+    let content = r#"TECHNOLOGY = diag_test
+GLOBAL_TEMPERATURE = 25.0
+
+DIELECTRIC ild {THICKNESS=0.5 ER=4.2}
+UNKNOWN_TABLE {
+    (1.0, 2.0)
+    (3.0, 4.0)
+}
+CONDUCTOR metal1 {THICKNESS=0.2 WMIN=0.1 SMIN=0.1 RPSQ=0.05}
+VIA via1 {FROM=metal1 TO=poly AREA=0.01 RPV=2.0}
+"#;
+    let stack = parse_itf_file(content).unwrap();
+
+    // The unknown block is reported once, not per line
+    let skipped: Vec<&Diagnostic> = stack
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .collect();
+    assert_eq!(skipped.len(), 1, "{:?}", stack.diagnostics);
+    assert_eq!(skipped[0].line, Some(5));
+    assert!(skipped[0].message.contains("4 unrecognized lines (5-8)"));
+    assert!(skipped[0].message.contains("UNKNOWN_TABLE"));
+
+    let created = stack
+        .diagnostics
+        .iter()
+        .find(|d| d.severity == Severity::Info && d.message.contains("'poly'"))
+        .expect("auto-created layer should be reported");
+    assert_eq!(created.line, None);
+
+    // A clean file has no warnings
+    let content =
+        fs::read_to_string("tests/data/simple_1p3m.itf").expect("Failed to read test file");
+    let stack = parse_itf_file(&content).unwrap();
+    assert!(stack
+        .diagnostics
+        .iter()
+        .all(|d| d.severity < Severity::Warning));
+}
+
+#[test]
+fn test_parse_locale_numbers() {
+    let content = r#"TECHNOLOGY = locale_test
+GLOBAL_TEMPERATURE = 25,0
+
+DIELECTRIC ild {THICKNESS=0,5 ER=4.2}
+CONDUCTOR metal1 {THICKNESS=0,2 WMIN=0.1 SMIN=0.1 RPSQ=0,05}
+DIELECTRIC imd {THICKNESS=0.3 ER=3.9}
+"#;
+    let stack = parse_itf_file(content).unwrap();
+    assert_eq!(stack.get_layer("ild").unwrap().thickness(), 0.5);
+    assert_eq!(stack.get_layer("metal1").unwrap().thickness(), 0.2);
+
+    let warnings: Vec<&Diagnostic> = stack
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .collect();
+    assert_eq!(warnings.len(), 4, "{:?}", stack.diagnostics);
+    assert_eq!(warnings[0].line, Some(2));
+    assert_eq!(
+        warnings[3].message,
+        "Read locale-formatted number '0,05' as 0.05"
+    );
+    assert_eq!(warnings[3].line, Some(5));
+
+    // Strict parsing reports the values without reading them
+    let stack = ItfParser::new()
+        .with_number_format(NumberFormat::Strict)
+        .parse_itf_file(content)
+        .unwrap();
+    assert!(stack
+        .diagnostics
+        .iter()
+        .any(|d| d.line == Some(4) && d.message.contains("'0,5' was not read")));
+    assert!(stack.get_layer("ild").is_none());
+    assert!(stack.get_layer("imd").is_some());
+}
+
+#[test]
+fn test_parse_with_progress() {
+    let content =
+        fs::read_to_string("tests/data/complex_1p7m.itf").expect("Failed to read test file");
+
+    let mut reports = Vec::new();
+    let stack = ItfParser::new()
+        .parse_with_progress(
+            &content,
+            |fraction| reports.push(fraction),
+            &CancelToken::new(),
+        )
+        .unwrap();
+    assert_eq!(
+        stack.layers.len(),
+        parse_itf_file(&content).unwrap().layers.len()
+    );
+
+    // Progress starts at zero, never goes back and ends at one
+    assert!(reports.len() > 2);
+    assert_eq!(reports.first(), Some(&0.0));
+    assert_eq!(reports.last(), Some(&1.0));
+    assert!(reports.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Cancelling from the callback stops the parse
+    let cancel = CancelToken::new();
+    let result = ItfParser::new().parse_with_progress(
+        &content,
+        |fraction| {
+            if fraction > 0.5 {
+                cancel.cancel();
+            }
+        },
+        &cancel,
+    );
+    assert!(matches!(result, Err(ParseError::Cancelled)));
+}
+
+#[test]
+fn test_parse_duplicate_layer_names() {
+    let content = r#"TECHNOLOGY = duplicate_test
+
+DIELECTRIC imd {THICKNESS=0.3 ER=3.9}
+CONDUCTOR metal1 {THICKNESS=0.2 RPSQ=0.05}
+DIELECTRIC imd {THICKNESS=0.5 ER=4.2}
+DIELECTRIC imd {THICKNESS=0.7 ER=4.1}
+"#;
+    // Renamed by default, with a warning on the line of each duplicate
+    let stack = parse_itf_file(content).unwrap();
+    assert_eq!(stack.get_layer_count(), 4);
+    assert_eq!(stack.get_layer("imd").unwrap().thickness(), 0.3);
+    assert_eq!(stack.get_layer("imd_1").unwrap().thickness(), 0.5);
+    assert_eq!(stack.get_layer("imd_2").unwrap().thickness(), 0.7);
+    let warnings: Vec<&Diagnostic> = stack
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .collect();
+    assert_eq!(warnings.len(), 2, "{:?}", stack.diagnostics);
+    assert_eq!(warnings[0].line, Some(5));
+    assert_eq!(
+        warnings[0].message,
+        "Renamed duplicate layer 'imd' to 'imd_1'"
+    );
+    assert_eq!(warnings[0].layer.as_deref(), Some("imd_1"));
+    assert_eq!(stack.get_source_line("imd_2"), Some(6));
+
+    let result = ItfParser::new()
+        .with_duplicate_names(DuplicateNamePolicy::Error)
+        .parse_itf_file(content);
+    assert!(matches!(
+        result,
+        Err(ParseError::DuplicateLayer { ref name, line: 5 }) if name == "imd"
+    ));
+
+    // Merged layers keep the first position and take the last values
+    let stack = ItfParser::new()
+        .with_duplicate_names(DuplicateNamePolicy::Merge)
+        .parse_itf_file(content)
+        .unwrap();
+    assert_eq!(stack.get_layer_count(), 2);
+    assert_eq!(stack.layers[0].name(), "imd");
+    assert_eq!(stack.get_layer("imd").unwrap().thickness(), 0.7);
+    assert_eq!(stack.get_layer("metal1").unwrap().get_bottom_z(), 0.7);
+}
+
+#[test]
+fn test_parse_custom_keywords() {
+    let content = r#"TECHNOLOGY = vendor_test
+
+DIELECTRIC imd {THICKNESS=0.3 ER=3.9 VENDOR_NOTE = low-k, rev B}
+CONDUCTOR metal1 {
+    THICKNESS=0.2 RPSQ=0.05
+    VENDOR_CMP { PAD = soft { DENSITY 0.4 } }
+}
+VIA via1 { FROM=metal1 TO=imd AREA=0.01 RPV=2 }
+"#;
+    // Without handlers the vendor lines are skipped with a warning
+    let stack = parse_itf_file(content).unwrap();
+    assert!(stack
+        .diagnostics
+        .iter()
+        .any(|d| d.severity == Severity::Warning));
+
+    let stack = ItfParser::new()
+        .with_keyword_handler(KeywordScope::Conductor, "vendor_cmp", parse_braced_text)
+        .with_keyword_handler(KeywordScope::Dielectric, "VENDOR_NOTE", parse_assigned_text)
+        .parse_itf_file(content)
+        .unwrap();
+    let Some(Layer::Conductor(metal)) = stack.get_layer("metal1") else {
+        panic!("metal1 is a conductor");
+    };
+    assert_eq!(
+        metal.extra_properties.get("VENDOR_CMP"),
+        Some(&PropValue::Text("PAD = soft { DENSITY 0.4 }".to_string()))
+    );
+    assert_eq!(metal.thickness, 0.2);
+    let imd = stack.get_layer("imd").unwrap();
+    assert_eq!(
+        imd.extra_properties().get("VENDOR_NOTE"),
+        Some(&PropValue::Text("low-k, rev B".to_string()))
+    );
+    assert_eq!(
+        stack.via_stack.iter().next().unwrap().resistance_per_via,
+        2.0
+    );
+}
+
+#[test]
+fn test_parse_er_regions() {
+    let content = r#"
+TECHNOLOGY = regions
+SUBSTRATE psub { ER = 11.9 THICKNESS = 5.0 }
+FIELD sti { ER = 3.9 THICKNESS = 0.3 }
+FIELD { ER = 4.1 }
+DIELECTRIC ild { THICKNESS = 0.5 ER = 4.2 }
+CONDUCTOR metal1 { THICKNESS = 0.2 }
+FIELD_OXIDE_NOTE = 1
+"#;
+    let stack = parse_itf_file(content).unwrap();
+
+    // Regions are not layers
+    assert_eq!(stack.get_layer_count(), 2);
+    assert!(stack.get_layer("psub").is_none());
+
+    let regions = stack.get_er_regions_from_top();
+    let names: Vec<&str> = regions.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["sti", "field", "psub"]);
+    assert_eq!(regions[0].kind, ErRegionKind::Field);
+    assert_eq!(regions[0].thickness, Some(0.3));
+    assert_eq!(regions[1].dielectric_constant, 4.1);
+    assert_eq!(regions[1].thickness, None);
+    assert_eq!(regions[2].kind, ErRegionKind::Substrate);
+    assert_eq!(regions[2].dielectric_constant, 11.9);
+
+    // A keyword merely starting with FIELD is not a region
+    assert!(stack
+        .diagnostics
+        .iter()
+        .any(|d| d.message.contains("FIELD_OXIDE_NOTE")));
+}
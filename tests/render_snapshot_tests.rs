@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Golden-file tests of the renderer layout
+//!
+//! Each case renders a test stack with `StackRenderer::dump_primitives` and compares the
+//! text dump with `tests/data/snapshots/<case>.txt`. After an intended layout change,
+//! regenerate the files with `UPDATE_SNAPSHOTS=1 cargo test --test render_snapshot_tests`
+//! and review the diff.
+
+use itf_viewer::*;
+use std::fs;
+
+const VIEWPORT_WIDTH: f32 = 1200.0;
+
+fn load_stack(file: &str) -> ProcessStack {
+    let content =
+        fs::read_to_string(format!("tests/data/{file}")).expect("Failed to read test file");
+    parse_itf_file(&content).expect("Failed to parse ITF file")
+}
+
+fn check_snapshot(case: &str, renderer: &StackRenderer, stack: &ProcessStack) {
+    let dump = format_primitives(&renderer.dump_primitives(stack, VIEWPORT_WIDTH));
+    let path = format!("tests/data/snapshots/{case}.txt");
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &dump).expect("Failed to write snapshot");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing snapshot {path}, run with UPDATE_SNAPSHOTS=1"));
+    for (line, (actual, expected)) in dump.lines().zip(expected.lines()).enumerate() {
+        assert_eq!(actual, expected, "{path}:{} differs", line + 1);
+    }
+    assert_eq!(
+        dump.lines().count(),
+        expected.lines().count(),
+        "{path} has a different number of primitives"
+    );
+}
+
+#[test]
+fn test_snapshot_simple_stack() {
+    let stack = load_stack("simple_1p3m.itf");
+    let mut renderer = StackRenderer::new();
+    check_snapshot("simple_1p3m", &renderer, &stack);
+
+    renderer.set_show_schematic_mode(true);
+    check_snapshot("simple_1p3m_schematic", &renderer, &stack);
+}
+
+#[test]
+fn test_snapshot_via_connections() {
+    let stack = load_stack("via_connections.itf");
+    let mut renderer = StackRenderer::new();
+    check_snapshot("via_connections", &renderer, &stack);
+
+    renderer.set_show_merged_vias(false);
+    check_snapshot("via_connections_discrete", &renderer, &stack);
+}
+
+#[test]
+fn test_snapshot_complex_stack() {
+    let stack = load_stack("complex_1p7m.itf");
+    let mut renderer = StackRenderer::new();
+    renderer.set_show_schematic_mode(true);
+    check_snapshot("complex_1p7m_schematic", &renderer, &stack);
+}
+
+#[test]
+fn test_vias_align_with_conductor_columns() {
+    for file in ["simple_1p3m.itf", "via_connections.itf", "complex_1p7m.itf"] {
+        let stack = load_stack(file);
+        for schematic in [false, true] {
+            let mut renderer = StackRenderer::new();
+            renderer.set_show_schematic_mode(schematic);
+            let primitives = renderer.dump_primitives(&stack, VIEWPORT_WIDTH);
+
+            let column_centers: Vec<f32> = primitives
+                .iter()
+                .filter(|p| p.kind == PrimitiveKind::Conductor)
+                .take(3)
+                .map(RenderPrimitive::center_x)
+                .collect();
+            assert_eq!(column_centers.len(), 3, "{file}");
+
+            for via in primitives.iter().filter(|p| p.kind == PrimitiveKind::Via) {
+                let column: usize = via.name.rsplit('_').next().unwrap().parse().unwrap();
+                assert!(
+                    (via.center_x() - column_centers[column]).abs() < 1e-3,
+                    "{file}: {} is off its conductor column",
+                    via.name
+                );
+            }
+        }
+    }
+}